@@ -0,0 +1,259 @@
+//! Alert hooks for budget and burn-rate thresholds
+//!
+//! When a threshold is crossed in `realtime` or `live` mode, the configured hooks
+//! from the `alerts` config section are triggered: the alert is POSTed as JSON to
+//! a webhook URL and/or passed to a shell command through `CLAUDELYTICS_ALERT_*`
+//! environment variables. Repeated alerts of the same kind are suppressed for
+//! `cooldown_minutes`.
+//!
+//! Hook commands run without a terminal: stdin and stdout are discarded and
+//! stderr is kept for the failure message, so a hook can't draw over a TUI.
+
+use crate::config_v2::AlertsConfig;
+use crate::display::print_warning;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+/// Timeout for webhook requests
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// A threshold crossing to report through the alert hooks
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertEvent {
+    /// Alert kind, e.g. `budget_threshold` or `high_burn_rate`
+    pub kind: String,
+    /// Severity label (`info`, `warning`, `critical`)
+    pub severity: String,
+    pub message: String,
+    /// Cost relevant to the alert (today's cost or cost per hour), in `currency`
+    pub cost: f64,
+    /// ISO 4217 code of `cost` and a cost `limit`, e.g. `USD` or the `--currency` code
+    pub currency: String,
+    /// Token count relevant to the alert (today's tokens or tokens per hour)
+    pub tokens: u64,
+    /// Limit that was crossed, if any
+    pub limit: Option<f64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl AlertEvent {
+    /// Environment variables passed to the alert command
+    fn env_vars(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("CLAUDELYTICS_ALERT_KIND", self.kind.clone()),
+            ("CLAUDELYTICS_ALERT_SEVERITY", self.severity.clone()),
+            ("CLAUDELYTICS_ALERT_MESSAGE", self.message.clone()),
+            ("CLAUDELYTICS_ALERT_COST", format!("{:.4}", self.cost)),
+            ("CLAUDELYTICS_ALERT_CURRENCY", self.currency.clone()),
+            ("CLAUDELYTICS_ALERT_TOKENS", self.tokens.to_string()),
+            (
+                "CLAUDELYTICS_ALERT_LIMIT",
+                self.limit.map(|l| format!("{:.2}", l)).unwrap_or_default(),
+            ),
+            ("CLAUDELYTICS_ALERT_TIMESTAMP", self.timestamp.to_rfc3339()),
+        ]
+    }
+}
+
+/// Dispatches alert events to the configured webhook and command
+pub struct AlertHooks {
+    config: AlertsConfig,
+    last_fired: HashMap<String, Instant>,
+    /// Failures of hooks run by `fire_in_background`
+    failures_tx: Sender<String>,
+    failures_rx: Receiver<String>,
+}
+
+impl AlertHooks {
+    pub fn new(config: AlertsConfig) -> Self {
+        let (failures_tx, failures_rx) = mpsc::channel();
+        Self {
+            config,
+            last_fired: HashMap::new(),
+            failures_tx,
+            failures_rx,
+        }
+    }
+
+    /// Trigger the hooks for `event` unless the same kind fired within the cooldown.
+    /// Hook failures are reported as warnings and never abort the caller.
+    pub fn fire(&mut self, event: &AlertEvent) {
+        if self.should_fire(event) {
+            for failure in dispatch(&self.config, event) {
+                print_warning(&failure);
+            }
+        }
+    }
+
+    /// Like `fire`, but the hooks run on another thread so a slow webhook or
+    /// command doesn't block the caller (the live dashboard's refresh loop).
+    /// Failures are collected for `take_failures` instead of printed.
+    pub fn fire_in_background(&mut self, event: &AlertEvent) {
+        if !self.should_fire(event) {
+            return;
+        }
+        let config = self.config.clone();
+        let event = event.clone();
+        let failures = self.failures_tx.clone();
+        std::thread::spawn(move || {
+            for failure in dispatch(&config, &event) {
+                let _ = failures.send(failure);
+            }
+        });
+    }
+
+    /// Failures of background hooks that finished since the last call
+    pub fn take_failures(&self) -> Vec<String> {
+        self.failures_rx.try_iter().collect()
+    }
+
+    /// Whether hooks are configured and `event`'s kind is out of its cooldown;
+    /// records the firing when it is
+    fn should_fire(&mut self, event: &AlertEvent) -> bool {
+        if !self.config.has_hooks() {
+            return false;
+        }
+
+        let cooldown = Duration::from_secs(self.config.cooldown_minutes * 60);
+        if let Some(last) = self.last_fired.get(&event.kind)
+            && last.elapsed() < cooldown
+        {
+            return false;
+        }
+        self.last_fired.insert(event.kind.clone(), Instant::now());
+        true
+    }
+}
+
+/// Run the configured hooks for `event` and describe the ones that failed
+fn dispatch(config: &AlertsConfig, event: &AlertEvent) -> Vec<String> {
+    let mut failures = Vec::new();
+    if let Some(url) = &config.webhook_url
+        && let Err(e) = post_webhook(url, event)
+    {
+        failures.push(format!("Alert webhook failed: {:#}", e));
+    }
+    if let Some(command) = &config.command
+        && let Err(e) = run_command(command, event)
+    {
+        failures.push(format!("Alert command failed: {:#}", e));
+    }
+    failures
+}
+
+fn post_webhook(url: &str, event: &AlertEvent) -> Result<()> {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+        .build()
+        .post(url)
+        .send_json(event)
+        .context("Failed to POST alert")?;
+    Ok(())
+}
+
+fn run_command(command: &str, event: &AlertEvent) -> Result<()> {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+
+    let output = cmd
+        .envs(event.env_vars())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .with_context(|| format!("Failed to run `{}`", command))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+            Some(line) => anyhow::bail!(
+                "`{}` exited with {}: {}",
+                command,
+                output.status,
+                line.trim()
+            ),
+            None => anyhow::bail!("`{}` exited with {}", command, output.status),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn sample_event() -> AlertEvent {
+        AlertEvent {
+            kind: "budget_threshold".to_string(),
+            severity: "warning".to_string(),
+            message: "Daily budget at 85%".to_string(),
+            cost: 8.5,
+            currency: "USD".to_string(),
+            tokens: 12345,
+            limit: Some(10.0),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_receives_env_and_respects_cooldown() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let out = dir.path().join("alerts.log");
+        let mut hooks = AlertHooks::new(AlertsConfig {
+            command: Some(format!(
+                "echo \"$CLAUDELYTICS_ALERT_KIND $CLAUDELYTICS_ALERT_COST $CLAUDELYTICS_ALERT_CURRENCY $CLAUDELYTICS_ALERT_LIMIT\" >> {}",
+                out.display()
+            )),
+            ..AlertsConfig::default()
+        });
+
+        hooks.fire(&sample_event());
+        hooks.fire(&sample_event());
+
+        let log = fs::read_to_string(&out).unwrap();
+        assert_eq!(log, "budget_threshold 8.5000 USD 10.00\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_background_failures_are_collected() {
+        let mut hooks = AlertHooks::new(AlertsConfig {
+            command: Some("echo output; echo 'hook broke' >&2; exit 3".to_string()),
+            ..AlertsConfig::default()
+        });
+        hooks.fire_in_background(&sample_event());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut failures = Vec::new();
+        while failures.is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+            failures = hooks.take_failures();
+        }
+        assert_eq!(failures.len(), 1);
+        assert!(
+            failures[0].ends_with("exit status: 3: hook broke"),
+            "{}",
+            failures[0]
+        );
+    }
+
+    #[test]
+    fn test_no_hooks_is_noop() {
+        let mut hooks = AlertHooks::new(AlertsConfig::default());
+        hooks.fire(&sample_event());
+        assert!(hooks.last_fired.is_empty());
+    }
+}
@@ -0,0 +1,919 @@
+//! Analytics Studio (`analytics-tui` command)
+//!
+//! A focused TUI on top of the existing analysis modules:
+//! - Patterns: cost by hour of day and day of week, session frequency
+//! - Forecast: recent daily cost and the projection from `ProjectionCalculator`
+//! - Budget & Risk: today's and this month's spend against the saved budget,
+//!   the projected month-end spend, and the most expensive sessions
+//! - Goals: compliance streaks and recent violations from `goals`
+
+use crate::budget_check::{self, BudgetCheckReport, BudgetStatus};
+use crate::config_v2::{BudgetConfig, GoalsConfig};
+use crate::currency::Money;
+use crate::goals::{self, GoalsReport};
+use crate::models::{DailyUsageMap, SessionUsageMap};
+use crate::projections::{ProjectionCalculator, TrendDirection, UsageProjection};
+use crate::session_analytics::{SessionAnalytics, SessionFrequencyAnalysis, SessionInfo};
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{
+    Frame, Terminal,
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Gauge, Paragraph, Sparkline, Tabs, Wrap},
+};
+use std::io;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Days of history shown in the forecast sparkline
+const HISTORY_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StudioTab {
+    Patterns,
+    Forecast,
+    Risk,
+    Goals,
+}
+
+impl StudioTab {
+    const ALL: [StudioTab; 4] = [
+        StudioTab::Patterns,
+        StudioTab::Forecast,
+        StudioTab::Risk,
+        StudioTab::Goals,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            StudioTab::Patterns => "1 Patterns",
+            StudioTab::Forecast => "2 Forecast",
+            StudioTab::Risk => "3 Budget & Risk",
+            StudioTab::Goals => "4 Goals",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+}
+
+/// Everything the studio shows, computed once at startup
+pub struct StudioData {
+    today: NaiveDate,
+    /// Cost per hour of day (UTC, as in `claudelytics analytics`)
+    hourly_cost: [f64; 24],
+    /// Sessions started per hour of day
+    hourly_sessions: [usize; 24],
+    /// Cost per weekday, Monday first
+    weekday_cost: [f64; 7],
+    frequency: SessionFrequencyAnalysis,
+    /// Daily cost for the last `HISTORY_DAYS` days, oldest first, gaps filled with 0
+    history: Vec<(NaiveDate, f64)>,
+    projection: UsageProjection,
+    budget: BudgetConfig,
+    budget_check: BudgetCheckReport,
+    month_to_date: f64,
+    /// Month-to-date spend plus the daily average for the remaining days
+    projected_month_end: f64,
+    month_end_risk: Option<BudgetStatus>,
+    expensive_sessions: Vec<SessionInfo>,
+    cost_threshold: f64,
+    /// `None` when no goals are saved
+    goals: Option<GoalsReport>,
+}
+
+impl StudioData {
+    pub fn build(
+        daily_map: &DailyUsageMap,
+        session_map: &SessionUsageMap,
+        budget: &BudgetConfig,
+        goals: &GoalsConfig,
+        cost_threshold: f64,
+        today: NaiveDate,
+    ) -> Self {
+        let analytics = SessionAnalytics::new(session_map);
+
+        let mut hourly_cost = [0.0; 24];
+        let mut hourly_sessions = [0; 24];
+        for (hour, metrics) in analytics.analyze_time_of_day().hourly_usage {
+            hourly_cost[hour as usize % 24] = metrics.usage.total_cost;
+            hourly_sessions[hour as usize % 24] = metrics.session_count;
+        }
+
+        let mut weekday_cost = [0.0; 7];
+        for (weekday, usage) in analytics.analyze_day_of_week().daily_usage {
+            weekday_cost[weekday.num_days_from_monday() as usize] = usage.total_cost;
+        }
+
+        let history = (0..HISTORY_DAYS)
+            .rev()
+            .map(|days_ago| {
+                let date = today - Duration::days(days_ago);
+                let cost = daily_map.get(&date).map_or(0.0, |usage| usage.total_cost);
+                (date, cost)
+            })
+            .collect();
+
+        let projection = ProjectionCalculator::new()
+            .with_limits(None, budget.monthly_limit)
+            .with_projection_days(14)
+            .with_billing_cycle_start_day(budget.billing_cycle_start_day)
+            .calculate_projections(daily_map);
+
+        let budget_check = budget_check::check_budget(
+            daily_map,
+            today,
+            budget.daily_limit,
+            budget.monthly_limit,
+            budget.alert_threshold,
+        );
+
+        let month_to_date = month_to_date_cost(daily_map, today);
+        let projected_month_end =
+            month_to_date + projection.daily_average * remaining_days_in_month(today) as f64;
+        let month_end_risk = budget
+            .monthly_limit
+            .map(|limit| risk_status(projected_month_end, limit, budget.alert_threshold));
+
+        let mut expensive_sessions = analytics
+            .analyze_cost_efficiency(cost_threshold)
+            .sessions_above_threshold;
+        expensive_sessions.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+        expensive_sessions.truncate(10);
+
+        Self {
+            today,
+            hourly_cost,
+            hourly_sessions,
+            weekday_cost,
+            frequency: analytics.analyze_session_frequency(),
+            history,
+            projection,
+            budget: budget.clone(),
+            budget_check,
+            month_to_date,
+            projected_month_end,
+            month_end_risk,
+            expensive_sessions,
+            cost_threshold,
+            goals: goals
+                .has_goals()
+                .then(|| GoalsReport::new(daily_map, goals, today)),
+        }
+    }
+}
+
+/// Spend from the first of `today`'s month up to and including `today`
+fn month_to_date_cost(daily_map: &DailyUsageMap, today: NaiveDate) -> f64 {
+    daily_map
+        .iter()
+        .filter(|(date, _)| {
+            date.year() == today.year() && date.month() == today.month() && **date <= today
+        })
+        .map(|(_, usage)| usage.total_cost)
+        .sum()
+}
+
+/// Days left in the month after `today`
+fn remaining_days_in_month(today: NaiveDate) -> i64 {
+    let (year, month) = if today.month() == 12 {
+        (today.year() + 1, 1)
+    } else {
+        (today.year(), today.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .map_or(0, |next_month| (next_month - today).num_days() - 1)
+}
+
+fn risk_status(projected: f64, limit: f64, alert_threshold: f64) -> BudgetStatus {
+    if projected >= limit {
+        BudgetStatus::Exceeded
+    } else if projected >= limit * alert_threshold {
+        BudgetStatus::Warning
+    } else {
+        BudgetStatus::Ok
+    }
+}
+
+fn status_color(status: BudgetStatus) -> Color {
+    match status {
+        BudgetStatus::Ok => Color::Green,
+        BudgetStatus::Warning => Color::Yellow,
+        BudgetStatus::Exceeded => Color::Red,
+    }
+}
+
+fn status_label(status: BudgetStatus) -> &'static str {
+    match status {
+        BudgetStatus::Ok => "On track",
+        BudgetStatus::Warning => "At risk",
+        BudgetStatus::Exceeded => "Over budget",
+    }
+}
+
+/// Bar values are integers; cents keep small costs visible
+fn cents(cost: f64) -> u64 {
+    (cost * 100.0).round().max(0.0) as u64
+}
+
+pub struct AnalyticsStudio {
+    data: StudioData,
+    tab: StudioTab,
+    should_quit: bool,
+}
+
+impl AnalyticsStudio {
+    pub fn new(data: StudioData) -> Self {
+        Self {
+            data,
+            tab: StudioTab::Patterns,
+            should_quit: false,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend)?;
+
+        let result = self.run_app(&mut terminal);
+
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+
+        result
+    }
+
+    fn run_app<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        while !self.should_quit {
+            terminal.draw(|f| self.ui(f))?;
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key.code, key.modifiers);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let index = self.tab.index();
+        let count = StudioTab::ALL.len();
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true
+            }
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
+                self.tab = StudioTab::ALL[(index + 1) % count];
+            }
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.tab = StudioTab::ALL[(index + count - 1) % count];
+            }
+            KeyCode::Char(c @ '1'..='4') => {
+                self.tab = StudioTab::ALL[c as usize - '1' as usize];
+            }
+            _ => {}
+        }
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .split(f.area());
+
+        let titles: Vec<Line> = StudioTab::ALL
+            .iter()
+            .map(|tab| Line::from(tab.title()))
+            .collect();
+        let tabs = Tabs::new(titles)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("\u{1f52c} Claudelytics Analytics Studio"),
+            )
+            .select(self.tab.index())
+            .style(Style::default().fg(Color::Gray))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            );
+        f.render_widget(tabs, chunks[0]);
+
+        match self.tab {
+            StudioTab::Patterns => self.render_patterns(f, chunks[1]),
+            StudioTab::Forecast => self.render_forecast(f, chunks[1]),
+            StudioTab::Risk => self.render_risk(f, chunks[1]),
+            StudioTab::Goals => self.render_goals(f, chunks[1]),
+        }
+
+        let footer = Paragraph::new("1-4/Tab: switch view  q/Esc: quit")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(footer, chunks[2]);
+    }
+
+    fn render_patterns(&self, f: &mut Frame, area: Rect) {
+        let data = &self.data;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunks[1]);
+
+        let peak_hour = (0..24)
+            .max_by(|a, b| data.hourly_cost[*a].total_cmp(&data.hourly_cost[*b]))
+            .unwrap_or(0);
+        let hour_bars: Vec<Bar> = (0..24)
+            .map(|hour| {
+                let color = if hour == peak_hour && data.hourly_cost[hour] > 0.0 {
+                    Color::Yellow
+                } else {
+                    Color::Cyan
+                };
+                Bar::default()
+                    .value(cents(data.hourly_cost[hour]))
+                    .label(Line::from(format!("{:02}", hour)))
+                    .text_value(String::new())
+                    .style(Style::default().fg(color))
+            })
+            .collect();
+        let hourly = BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("\u{23f0} Cost by hour of day (UTC)"),
+            )
+            .data(BarGroup::default().bars(&hour_bars))
+            .bar_width(2)
+            .bar_gap(1);
+        f.render_widget(hourly, chunks[0]);
+
+        let weekday_bars: Vec<Bar> = WEEKDAYS
+            .iter()
+            .enumerate()
+            .map(|(i, weekday)| {
+                let color = if i >= 5 { Color::Magenta } else { Color::Blue };
+                Bar::default()
+                    .value(cents(data.weekday_cost[i]))
+                    .label(Line::from(weekday.to_string()))
+                    .text_value(format!("{:.0}", Money(data.weekday_cost[i])))
+                    .style(Style::default().fg(color))
+            })
+            .collect();
+        let weekly = BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("\u{1f4c5} Cost by day of week"),
+            )
+            .data(BarGroup::default().bars(&weekday_bars))
+            .bar_width(5)
+            .bar_gap(1);
+        f.render_widget(weekly, bottom[0]);
+
+        let weekend: f64 = data.weekday_cost[5..].iter().sum();
+        let total: f64 = data.weekday_cost.iter().sum();
+        let frequency = &data.frequency;
+        let lines = vec![
+            stat_line(
+                "Peak hour",
+                format!(
+                    "{:02}:00 ({} sessions)",
+                    peak_hour, data.hourly_sessions[peak_hour]
+                ),
+            ),
+            stat_line(
+                "Weekend share",
+                if total > 0.0 {
+                    format!("{:.0}%", weekend / total * 100.0)
+                } else {
+                    "-".to_string()
+                },
+            ),
+            stat_line("Active days", frequency.days_with_usage.to_string()),
+            stat_line(
+                "Sessions / active day",
+                format!("{:.1}", frequency.avg_sessions_per_active_day),
+            ),
+            stat_line(
+                "Sessions / week",
+                format!("{:.1}", frequency.sessions_per_week),
+            ),
+            stat_line(
+                "Current streak",
+                format!("{} days", frequency.current_streak),
+            ),
+            stat_line(
+                "Longest streak",
+                format!("{} days", frequency.longest_streak),
+            ),
+        ];
+        let summary = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Summary"))
+            .wrap(Wrap { trim: false });
+        f.render_widget(summary, bottom[1]);
+    }
+
+    fn render_forecast(&self, f: &mut Frame, area: Rect) {
+        let data = &self.data;
+        let projection = &data.projection;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(40),
+                Constraint::Percentage(30),
+                Constraint::Min(0),
+            ])
+            .split(area);
+
+        let history: Vec<u64> = data.history.iter().map(|(_, cost)| cents(*cost)).collect();
+        let first = data.history.first().map(|(date, _)| *date);
+        let peak = data
+            .history
+            .iter()
+            .map(|(_, cost)| *cost)
+            .fold(0.0, f64::max);
+        let sparkline = Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "\u{1f4c8} Daily cost, last {} days{} (peak ${:.2})",
+                HISTORY_DAYS,
+                first.map_or(String::new(), |date| format!(" since {}", date)),
+                peak
+            )))
+            .data(&history)
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(sparkline, chunks[0]);
+
+        let forecast: Vec<u64> = projection
+            .projections
+            .iter()
+            .map(|p| cents(p.value))
+            .collect();
+        let forecast_chart = Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "\u{1f52e} Projected daily cost, next {} days",
+                projection.projections.len()
+            )))
+            .data(&forecast)
+            .style(Style::default().fg(Color::Magenta));
+        f.render_widget(forecast_chart, chunks[1]);
+
+        let (trend, trend_color) = match projection.trend {
+            TrendDirection::Increasing => ("\u{2197} Increasing", Color::Red),
+            TrendDirection::Decreasing => ("\u{2198} Decreasing", Color::Green),
+            TrendDirection::Stable => ("\u{2192} Stable", Color::Yellow),
+        };
+        let mut lines = vec![
+            stat_line(
+                "Daily average",
+                format!("{:.2}", Money(projection.daily_average)),
+            ),
+            stat_line(
+                "Weekly average",
+                format!("{:.2}", Money(projection.weekly_average)),
+            ),
+            Line::from(vec![
+                Span::styled(format!("{:<24}", "Trend"), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("{} ({:+.1}%/day)", trend, projection.growth_rate),
+                    Style::default().fg(trend_color),
+                ),
+            ]),
+            stat_line(
+                "Estimated monthly cost",
+                format!("{:.2}", Money(projection.estimated_monthly_cost)),
+            ),
+        ];
+        if let Some(last) = projection.projections.last() {
+            lines.push(stat_line(
+                &format!("Projection for {}", last.date),
+                format!(
+                    "{:.2} ({:.2} - {:.2})",
+                    Money(last.value),
+                    Money(last.lower_bound),
+                    Money(last.upper_bound)
+                ),
+            ));
+        }
+        if let (Some(days), Some(date)) = (projection.days_until_limit, projection.limit_date) {
+            lines.push(stat_line(
+                "Monthly limit reached",
+                format!("in {} days ({})", days, date),
+            ));
+        }
+        let stats = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Forecast"))
+            .wrap(Wrap { trim: false });
+        f.render_widget(stats, chunks[2]);
+    }
+
+    fn render_risk(&self, f: &mut Frame, area: Rect) {
+        let data = &self.data;
+        if data.budget.daily_limit.is_none() && data.budget.monthly_limit.is_none() {
+            let text = vec![
+                Line::from(""),
+                Line::from("No budget limits saved"),
+                Line::from(""),
+                Line::from("Save limits to track spend and month-end risk here:"),
+                Line::from("  claudelytics budget set --daily 20 --monthly 400"),
+                Line::from(""),
+                stat_line("Spent today", format!("{:.2}", Money(today_cost(data)))),
+                stat_line(
+                    "Spent this month",
+                    format!("{:.2}", Money(data.month_to_date)),
+                ),
+                stat_line(
+                    "Projected month end",
+                    format!("{:.2}", Money(data.projected_month_end)),
+                ),
+            ];
+            let paragraph = Paragraph::new(text)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("\u{1f4b0} Budget & Risk"),
+                )
+                .style(Style::default().fg(Color::Gray));
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(6),
+                Constraint::Min(0),
+            ])
+            .split(area);
+
+        for (i, period) in ["daily", "monthly"].iter().enumerate() {
+            let Some(check) = data
+                .budget_check
+                .checks
+                .iter()
+                .find(|c| c.period == *period)
+            else {
+                let paragraph = Paragraph::new(format!("No {} limit saved", period))
+                    .block(Block::default().borders(Borders::ALL))
+                    .style(Style::default().fg(Color::DarkGray));
+                f.render_widget(paragraph, chunks[i]);
+                continue;
+            };
+            let title = if *period == "daily" {
+                format!("Today ({})", data.today)
+            } else {
+                format!("This month ({})", data.today.format("%Y-%m"))
+            };
+            let gauge = Gauge::default()
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .gauge_style(Style::default().fg(status_color(check.status)))
+                .ratio((check.percent / 100.0).clamp(0.0, 1.0))
+                .label(format!(
+                    "{:.2} / {:.2} ({:.0}%)",
+                    Money(check.spent),
+                    Money(check.limit),
+                    check.percent
+                ));
+            f.render_widget(gauge, chunks[i]);
+        }
+
+        let mut lines = vec![stat_line(
+            "Projected month end",
+            format!(
+                "{:.2} ({:.2}/day for {} more days)",
+                Money(data.projected_month_end),
+                Money(data.projection.daily_average),
+                remaining_days_in_month(data.today)
+            ),
+        )];
+        match (data.month_end_risk, data.budget.monthly_limit) {
+            (Some(status), Some(limit)) => {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{:<24}", "Month-end risk"),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::styled(
+                        format!(
+                            "{} ({:.0}% of {:.2})",
+                            status_label(status),
+                            data.projected_month_end / limit * 100.0,
+                            Money(limit)
+                        ),
+                        Style::default()
+                            .fg(status_color(status))
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ]));
+                if let Some(days) = data.projection.days_until_limit {
+                    lines.push(stat_line(
+                        "Limit reached in",
+                        format!("{} days at the current trend", days),
+                    ));
+                }
+            }
+            _ => lines.push(stat_line(
+                "Month-end risk",
+                "no monthly limit saved".to_string(),
+            )),
+        }
+        lines.push(stat_line(
+            "Alert threshold",
+            format!("{:.0}%", data.budget.alert_threshold * 100.0),
+        ));
+        let risk = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Risk"))
+            .wrap(Wrap { trim: false });
+        f.render_widget(risk, chunks[2]);
+
+        let session_lines: Vec<Line> = if data.expensive_sessions.is_empty() {
+            vec![Line::from(Span::styled(
+                format!("No sessions above {:.2}", Money(data.cost_threshold)),
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            data.expensive_sessions
+                .iter()
+                .map(|session| {
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{:>9.2}  ", Money(session.cost)),
+                            Style::default().fg(Color::Yellow),
+                        ),
+                        Span::raw(session.path.clone()),
+                    ])
+                })
+                .collect()
+        };
+        let sessions = Paragraph::new(session_lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Sessions above {:.2}", Money(data.cost_threshold))),
+        );
+        f.render_widget(sessions, chunks[3]);
+    }
+
+    fn render_goals(&self, f: &mut Frame, area: Rect) {
+        let Some(report) = &self.data.goals else {
+            let text = vec![
+                Line::from(""),
+                Line::from("No goals saved"),
+                Line::from(""),
+                Line::from("Save goals to track streaks of compliant days here:"),
+                Line::from("  claudelytics goals set --daily-cost 10 --weekly-tokens 2000000"),
+            ];
+            let paragraph = Paragraph::new(text)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("\u{1f3af} Goals"),
+                )
+                .style(Style::default().fg(Color::Gray));
+            f.render_widget(paragraph, area);
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(5),
+                Constraint::Length(3 * report.progress.len() as u16),
+                Constraint::Length(3),
+                Constraint::Min(0),
+            ])
+            .split(area);
+
+        let streak_color = if report.current_streak > 0 {
+            Color::Green
+        } else {
+            Color::Red
+        };
+        let stats = Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<24}", "Current streak"),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(
+                    format!("{} days", report.current_streak),
+                    Style::default()
+                        .fg(streak_color)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            stat_line("Longest streak", format!("{} days", report.longest_streak)),
+            stat_line(
+                "Days over a goal",
+                format!(
+                    "{} of the last {}",
+                    report.violations.len(),
+                    goals::HISTORY_DAYS
+                ),
+            ),
+        ])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("\u{1f3af} Goals"),
+        );
+        f.render_widget(stats, chunks[0]);
+
+        let gauge_areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(3); report.progress.len()])
+            .split(chunks[1]);
+        for (progress, gauge_area) in report.progress.iter().zip(gauge_areas.iter()) {
+            let color = if progress.met {
+                Color::Green
+            } else {
+                Color::Red
+            };
+            let title = if progress.goal.starts_with("weekly") {
+                format!("{} (last 7 days)", progress.goal)
+            } else {
+                format!("{} (today)", progress.goal)
+            };
+            let gauge = Gauge::default()
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .gauge_style(Style::default().fg(color))
+                .ratio((progress.actual / progress.limit).clamp(0.0, 1.0))
+                .label(format!(
+                    "{} / {}",
+                    progress.unit.format(progress.actual),
+                    progress.unit.format(progress.limit)
+                ));
+            f.render_widget(gauge, *gauge_area);
+        }
+
+        // One cell per day, oldest first
+        let cells: Vec<Span> = (0..goals::HISTORY_DAYS)
+            .rev()
+            .map(|days_ago| {
+                let date = report.today - Duration::days(days_ago);
+                let violated = report.violations.iter().any(|v| v.date == date);
+                let color = if violated { Color::Red } else { Color::Green };
+                Span::styled("\u{25a0} ", Style::default().fg(color))
+            })
+            .collect();
+        let strip = Paragraph::new(Line::from(cells)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Last {} days", goals::HISTORY_DAYS)),
+        );
+        f.render_widget(strip, chunks[2]);
+
+        let violation_lines: Vec<Line> = if report.violations.is_empty() {
+            vec![Line::from(Span::styled(
+                format!("No goals missed in the last {} days", goals::HISTORY_DAYS),
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            report
+                .violations
+                .iter()
+                .map(|violation| {
+                    Line::from(vec![
+                        Span::raw(format!("{}  ", violation.date)),
+                        Span::styled(
+                            format!("{:>9.2}  ", Money(violation.cost)),
+                            Style::default().fg(Color::Yellow),
+                        ),
+                        Span::styled(violation.missed.join(", "), Style::default().fg(Color::Red)),
+                    ])
+                })
+                .collect()
+        };
+        let violations = Paragraph::new(violation_lines)
+            .block(Block::default().borders(Borders::ALL).title("Violations"));
+        f.render_widget(violations, chunks[3]);
+    }
+}
+
+fn today_cost(data: &StudioData) -> f64 {
+    data.history.last().map_or(0.0, |(_, cost)| *cost)
+}
+
+fn stat_line(label: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{:<24}", label), Style::default().fg(Color::Gray)),
+        Span::styled(value, Style::default().fg(Color::White)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+    use chrono::{TimeZone, Utc};
+
+    fn usage(cost: f64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: 1000,
+            total_cost: cost,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_remaining_days_and_risk() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(remaining_days_in_month(date(2024, 2, 10)), 19);
+        assert_eq!(remaining_days_in_month(date(2024, 12, 31)), 0);
+        assert_eq!(remaining_days_in_month(date(2025, 4, 1)), 29);
+
+        assert_eq!(risk_status(50.0, 100.0, 0.8), BudgetStatus::Ok);
+        assert_eq!(risk_status(85.0, 100.0, 0.8), BudgetStatus::Warning);
+        assert_eq!(risk_status(100.0, 100.0, 0.8), BudgetStatus::Exceeded);
+    }
+
+    #[test]
+    fn test_studio_data_build() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let mut daily_map = DailyUsageMap::new();
+        daily_map.insert(today, usage(4.0));
+        daily_map.insert(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), usage(6.0));
+        daily_map.insert(NaiveDate::from_ymd_opt(2024, 2, 28).unwrap(), usage(50.0));
+
+        let mut session_map = SessionUsageMap::new();
+        // 2024-03-20 is a Wednesday
+        session_map.insert(
+            "p1/a".to_string(),
+            (
+                usage(4.0),
+                Utc.with_ymd_and_hms(2024, 3, 20, 14, 0, 0).unwrap(),
+            ),
+        );
+        session_map.insert(
+            "p1/b".to_string(),
+            (
+                usage(6.0),
+                Utc.with_ymd_and_hms(2024, 3, 2, 9, 30, 0).unwrap(),
+            ),
+        );
+
+        let budget = BudgetConfig {
+            daily_limit: Some(5.0),
+            monthly_limit: Some(20.0),
+            ..Default::default()
+        };
+        let goals = GoalsConfig {
+            max_daily_cost: Some(5.0),
+            ..Default::default()
+        };
+        let data = StudioData::build(&daily_map, &session_map, &budget, &goals, 5.0, today);
+
+        assert_eq!(data.hourly_cost[14], 4.0);
+        assert_eq!(data.hourly_sessions[9], 1);
+        assert_eq!(data.weekday_cost[2], 4.0);
+        assert_eq!(data.weekday_cost[5], 6.0);
+        assert_eq!(data.month_to_date, 10.0);
+        assert_eq!(data.history.len(), HISTORY_DAYS as usize);
+        assert_eq!(today_cost(&data), 4.0);
+        assert!(data.month_end_risk.is_some());
+        assert_eq!(data.expensive_sessions.len(), 1);
+        assert_eq!(data.expensive_sessions[0].path, "p1/b");
+        let goals = data.goals.as_ref().expect("goals are saved");
+        assert_eq!(goals.violations.len(), 2);
+        assert_eq!(goals.current_streak, 19);
+
+        let mut studio = AnalyticsStudio::new(data);
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(100, 40)).unwrap();
+        for key in ['1', '2', '3'] {
+            studio.handle_key(KeyCode::Char(key), KeyModifiers::NONE);
+            terminal.draw(|f| studio.ui(f)).unwrap();
+        }
+        assert_eq!(studio.tab, StudioTab::Risk);
+    }
+}
@@ -0,0 +1,433 @@
+//! Snapshot archive of aggregated usage (`snapshot` command)
+//!
+//! Claude Code prunes old JSONL files, and their history goes with them. A
+//! snapshot appends the usage of every day, session, and model to an
+//! append-only JSONL archive. Each line holds the totals as of the snapshot, so
+//! the latest line for a day, session, and model wins and rows that did not
+//! change since the previous snapshot are not written again.
+//!
+//! With `--with-archive` (or `archive.merge`) the parser adds archived usage for
+//! the days and sessions that no longer have live data. Archived costs are the
+//! ones calculated when the snapshot was taken.
+
+use crate::currency::Money;
+use crate::import::IMPORTED_PROJECT;
+use crate::models::{DailyUsageMap, SessionUsageMap, TokenUsage};
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+/// Usage of one session and model on one day, as of a snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedUsage {
+    pub taken_at: DateTime<Utc>,
+    pub date: NaiveDate,
+    /// Session key (`project-dir/session-id`)
+    pub session: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost: f64,
+    #[serde(default)]
+    pub fast_mode_cost: f64,
+    #[serde(default)]
+    pub message_count: u64,
+    #[serde(default)]
+    pub thinking_tokens: u64,
+    /// Last activity of the session when the snapshot was taken
+    pub last_activity: DateTime<Utc>,
+}
+
+impl ArchivedUsage {
+    fn key(&self) -> (NaiveDate, String, String) {
+        (self.date, self.session.clone(), self.model.clone())
+    }
+
+    /// Same totals, ignoring when the rows were taken
+    fn same_usage(&self, other: &ArchivedUsage) -> bool {
+        self.input_tokens == other.input_tokens
+            && self.output_tokens == other.output_tokens
+            && self.cache_creation_tokens == other.cache_creation_tokens
+            && self.cache_read_tokens == other.cache_read_tokens
+            && self.message_count == other.message_count
+            && (self.cost - other.cost).abs() < 1e-9
+    }
+
+    /// Usage tagged by model and session, like a parsed record
+    pub fn to_usage(&self) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            cache_creation_tokens: self.cache_creation_tokens,
+            cache_read_tokens: self.cache_read_tokens,
+            total_cost: self.cost,
+            fast_mode_cost: self.fast_mode_cost,
+            message_count: self.message_count,
+            thinking_tokens: self.thinking_tokens,
+            ..Default::default()
+        }
+        .tagged(&self.model, &self.session)
+    }
+}
+
+/// One row per day, session, and model of the parsed usage. Imported usage is
+/// left out, since the import store already keeps it.
+pub fn snapshot_rows(
+    daily_map: &DailyUsageMap,
+    session_map: &SessionUsageMap,
+    taken_at: DateTime<Utc>,
+) -> Vec<ArchivedUsage> {
+    let imported_prefix = format!("{}/", IMPORTED_PROJECT);
+    let mut rows = Vec::new();
+    for (date, usage) in daily_map {
+        for (session, session_usage) in &usage.by_session {
+            if session.starts_with(&imported_prefix) {
+                continue;
+            }
+            let last_activity = session_map
+                .get(session)
+                .map(|(_, last_activity)| *last_activity)
+                .unwrap_or(taken_at);
+            let row = |model: &str, usage: &TokenUsage| ArchivedUsage {
+                taken_at,
+                date: *date,
+                session: session.clone(),
+                model: model.to_string(),
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cache_creation_tokens: usage.cache_creation_tokens,
+                cache_read_tokens: usage.cache_read_tokens,
+                cost: usage.total_cost,
+                fast_mode_cost: usage.fast_mode_cost,
+                message_count: usage.message_count,
+                thinking_tokens: usage.thinking_tokens,
+                last_activity,
+            };
+            if session_usage.by_model.is_empty() {
+                rows.push(row("unknown", session_usage));
+            } else {
+                rows.extend(
+                    session_usage
+                        .by_model
+                        .iter()
+                        .map(|(model, usage)| row(model, usage)),
+                );
+            }
+        }
+    }
+    rows.sort_by_key(|row| row.key());
+    rows
+}
+
+/// Result of taking a snapshot
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotSummary {
+    pub archive_path: PathBuf,
+    pub taken_at: DateTime<Utc>,
+    /// Rows written because they were new or changed
+    pub appended: usize,
+    /// Rows already archived with the same totals
+    pub unchanged: usize,
+    pub days: usize,
+    pub sessions: usize,
+}
+
+/// What the archive holds (latest row per day, session, and model)
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveStats {
+    pub archive_path: PathBuf,
+    pub snapshots: usize,
+    pub last_snapshot: Option<DateTime<Utc>>,
+    /// Lines in the file, including superseded rows
+    pub lines: usize,
+    pub rows: usize,
+    pub first_date: Option<NaiveDate>,
+    pub last_date: Option<NaiveDate>,
+    pub days: usize,
+    pub sessions: usize,
+    pub total_cost: f64,
+}
+
+/// Append-only JSONL archive file
+pub struct UsageArchive {
+    path: PathBuf,
+}
+
+impl UsageArchive {
+    /// Get the archive file path
+    pub fn get_archive_path() -> Result<PathBuf> {
+        let data_dir = dirs::data_dir()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("share")))
+            .ok_or_else(|| anyhow::anyhow!("Could not determine data directory"))?;
+
+        Ok(data_dir.join("claudelytics").join("usage_archive.jsonl"))
+    }
+
+    pub fn open() -> Result<Self> {
+        Ok(Self::at(Self::get_archive_path()?))
+    }
+
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Every row in the file, oldest snapshot first. Unreadable lines (e.g.
+    /// from an interrupted write) are skipped.
+    fn read_rows(&self) -> Result<Vec<ArchivedUsage>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let file = File::open(&self.path)
+            .with_context(|| format!("Failed to open archive: {}", self.path.display()))?;
+        let mut rows = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line =
+                line.with_context(|| format!("Failed to read archive: {}", self.path.display()))?;
+            if let Ok(row) = serde_json::from_str::<ArchivedUsage>(&line) {
+                rows.push(row);
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Latest row for each day, session, and model
+    pub fn load_latest(&self) -> Result<Vec<ArchivedUsage>> {
+        Ok(Self::latest(self.read_rows()?).into_values().collect())
+    }
+
+    fn latest(rows: Vec<ArchivedUsage>) -> BTreeMap<(NaiveDate, String, String), ArchivedUsage> {
+        let mut latest: BTreeMap<_, ArchivedUsage> = BTreeMap::new();
+        for row in rows {
+            match latest.get(&row.key()) {
+                Some(existing) if existing.taken_at > row.taken_at => {}
+                _ => {
+                    latest.insert(row.key(), row);
+                }
+            }
+        }
+        latest
+    }
+
+    /// Append the rows of the parsed usage that are new or changed
+    pub fn snapshot(
+        &self,
+        daily_map: &DailyUsageMap,
+        session_map: &SessionUsageMap,
+        taken_at: DateTime<Utc>,
+    ) -> Result<SnapshotSummary> {
+        let latest = Self::latest(self.read_rows()?);
+        let rows = snapshot_rows(daily_map, session_map, taken_at);
+        let total = rows.len();
+        let days: HashSet<NaiveDate> = rows.iter().map(|row| row.date).collect();
+        let sessions: HashSet<&str> = rows.iter().map(|row| row.session.as_str()).collect();
+        let (days, sessions) = (days.len(), sessions.len());
+
+        let changed: Vec<&ArchivedUsage> = rows
+            .iter()
+            .filter(|row| {
+                latest
+                    .get(&row.key())
+                    .is_none_or(|archived| !archived.same_usage(row))
+            })
+            .collect();
+        if !changed.is_empty() {
+            self.append(&changed)?;
+        }
+
+        Ok(SnapshotSummary {
+            archive_path: self.path.clone(),
+            taken_at,
+            appended: changed.len(),
+            unchanged: total - changed.len(),
+            days,
+            sessions,
+        })
+    }
+
+    fn append(&self, rows: &[&ArchivedUsage]) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open archive: {}", self.path.display()))?;
+        let mut writer = BufWriter::new(file);
+        for row in rows {
+            serde_json::to_writer(&mut writer, row).context("Failed to serialize archive row")?;
+            writer.write_all(b"\n")?;
+        }
+        writer
+            .flush()
+            .with_context(|| format!("Failed to write archive: {}", self.path.display()))
+    }
+
+    pub fn stats(&self) -> Result<ArchiveStats> {
+        let rows = self.read_rows()?;
+        let lines = rows.len();
+        let snapshots: BTreeSet<DateTime<Utc>> = rows.iter().map(|row| row.taken_at).collect();
+        let latest: Vec<ArchivedUsage> = Self::latest(rows).into_values().collect();
+        let days: BTreeSet<NaiveDate> = latest.iter().map(|row| row.date).collect();
+        let sessions: HashSet<&str> = latest.iter().map(|row| row.session.as_str()).collect();
+
+        Ok(ArchiveStats {
+            archive_path: self.path.clone(),
+            snapshots: snapshots.len(),
+            last_snapshot: snapshots.last().copied(),
+            lines,
+            rows: latest.len(),
+            first_date: days.first().copied(),
+            last_date: days.last().copied(),
+            days: days.len(),
+            sessions: sessions.len(),
+            total_cost: latest.iter().map(|row| row.cost).sum::<f64>().max(0.0),
+        })
+    }
+}
+
+pub fn display_snapshot(summary: &SnapshotSummary, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(summary) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!(
+        "📦 Snapshot saved: {} new or changed rows, {} unchanged ({} days, {} sessions)",
+        summary.appended.to_string().green(),
+        summary.unchanged,
+        summary.days,
+        summary.sessions
+    );
+    println!("   Archive: {}", summary.archive_path.display());
+}
+
+pub fn display_archive_stats(stats: &ArchiveStats, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(stats) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "USAGE ARCHIVE".bold());
+    println!("{}", "═".repeat(60));
+    println!("  {:<18} {}", "File:".cyan(), stats.archive_path.display());
+    if stats.snapshots == 0 {
+        println!("  No snapshots yet (run `claudelytics snapshot`)");
+        return;
+    }
+    println!("  {:<18} {}", "Snapshots:".cyan(), stats.snapshots);
+    if let Some(last) = stats.last_snapshot {
+        println!(
+            "  {:<18} {}",
+            "Last snapshot:".cyan(),
+            last.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+        );
+    }
+    if let (Some(first), Some(last)) = (stats.first_date, stats.last_date) {
+        println!("  {:<18} {} to {}", "Covers:".cyan(), first, last);
+    }
+    println!(
+        "  {:<18} {} days, {} sessions",
+        "Archived:".cyan(),
+        stats.days,
+        stats.sessions
+    );
+    println!(
+        "  {:<18} {} ({} lines in file)",
+        "Rows:".cyan(),
+        stats.rows,
+        stats.lines
+    );
+    println!(
+        "  {:<18} {:.2}",
+        "Total cost:".bold(),
+        Money(stats.total_cost)
+    );
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    fn parsed(date: NaiveDate, session: &str, model: &str, tokens: u64) -> TokenUsage {
+        ArchivedUsage {
+            taken_at: Utc::now(),
+            date,
+            session: session.to_string(),
+            model: model.to_string(),
+            input_tokens: tokens,
+            output_tokens: tokens,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            cost: tokens as f64 / 1000.0,
+            fast_mode_cost: 0.0,
+            message_count: 1,
+            thinking_tokens: 0,
+            last_activity: Utc::now(),
+        }
+        .to_usage()
+    }
+
+    #[test]
+    fn test_snapshot_appends_only_changes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let archive = UsageArchive::at(dir.path().join("archive.jsonl"));
+        let day = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let last = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+
+        let mut daily_map: DailyUsageMap = HashMap::new();
+        let entry = daily_map.entry(day).or_default();
+        entry.add(&parsed(day, "proj/s1", "claude-opus-4", 100));
+        entry.add(&parsed(day, "proj/s1", "claude-sonnet-4", 200));
+        entry.add(&parsed(day, "imported/console", "claude-opus-4", 300));
+        let session_map: SessionUsageMap =
+            HashMap::from([("proj/s1".to_string(), (TokenUsage::default(), last))]);
+
+        let first = archive
+            .snapshot(&daily_map, &session_map, Utc::now())
+            .unwrap();
+        assert_eq!((first.appended, first.unchanged), (2, 0));
+        assert_eq!((first.days, first.sessions), (1, 1));
+
+        let second = archive
+            .snapshot(&daily_map, &session_map, Utc::now())
+            .unwrap();
+        assert_eq!((second.appended, second.unchanged), (0, 2));
+
+        // The session grew: only its changed model row is appended, and it wins
+        daily_map
+            .get_mut(&day)
+            .unwrap()
+            .add(&parsed(day, "proj/s1", "claude-opus-4", 50));
+        let third = archive
+            .snapshot(&daily_map, &session_map, Utc::now())
+            .unwrap();
+        assert_eq!((third.appended, third.unchanged), (1, 1));
+
+        let latest = archive.load_latest().unwrap();
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].model, "claude-opus-4");
+        assert_eq!(latest[0].input_tokens, 150);
+        assert_eq!(latest[0].last_activity, last);
+
+        let stats = archive.stats().unwrap();
+        assert_eq!((stats.snapshots, stats.lines, stats.rows), (2, 3, 2));
+        assert_eq!(stats.first_date, Some(day));
+    }
+}
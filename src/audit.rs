@@ -0,0 +1,349 @@
+//! Cost audit (`audit` command)
+//!
+//! Lists every model found in the usage records with its record count and
+//! whether it has a price; records of unpriced models count as $0 unless they
+//! carry a stored `costUSD`. Days whose stored `costUSD` totals differ from
+//! the cost calculated from tokens by more than a tolerance are flagged too,
+//! so under- or over-reported totals can be traced to a model or a day.
+
+use crate::display::format_number;
+use chrono::NaiveDate;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Stored and calculated differences below this many USD are never flagged
+const MIN_DIFFERENCE_USD: f64 = 0.01;
+
+/// Cost figures of one usage record
+#[derive(Debug, Clone)]
+pub struct AuditEntry<'a> {
+    pub date: NaiveDate,
+    pub model: &'a str,
+    pub tokens: u64,
+    /// Whether the model has a price
+    pub priced: bool,
+    /// `costUSD` written by Claude Code
+    pub stored_cost: Option<f64>,
+    /// Cost from token counts and the pricing table
+    pub calculated_cost: f64,
+    /// Cost counted in reports under the current `--cost-mode`
+    pub reported_cost: f64,
+}
+
+/// Cost figures collected while parsing (see `UsageParser::with_cost_audit`)
+#[derive(Debug, Clone, Default)]
+pub struct CostAudit {
+    models: BTreeMap<String, ModelAudit>,
+    days: BTreeMap<NaiveDate, DayTotals>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModelAudit {
+    pub model: String,
+    pub records: u64,
+    pub tokens: u64,
+    pub priced: bool,
+    /// Reported cost in USD
+    pub cost: f64,
+    /// Records with tokens that were reported at $0
+    pub zero_cost_records: u64,
+    pub zero_cost_tokens: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct DayTotals {
+    stored_records: u64,
+    stored_cost: f64,
+    calculated_cost: f64,
+}
+
+/// A day whose stored `costUSD` total disagrees with the calculated cost
+#[derive(Debug, Clone, Serialize)]
+pub struct CostMismatch {
+    pub date: NaiveDate,
+    /// Records of the day with a stored `costUSD`, the only ones compared
+    pub records: u64,
+    pub stored_cost: f64,
+    pub calculated_cost: f64,
+    pub difference: f64,
+    pub difference_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditReport {
+    pub tolerance_pct: f64,
+    pub total_records: u64,
+    /// Reported cost in USD
+    pub total_cost: f64,
+    /// Models by number of records
+    pub models: Vec<ModelAudit>,
+    pub unpriced_models: Vec<String>,
+    pub zero_cost_records: u64,
+    pub zero_cost_tokens: u64,
+    pub mismatched_days: Vec<CostMismatch>,
+}
+
+impl CostAudit {
+    pub fn add(&mut self, entry: AuditEntry) {
+        let model = self
+            .models
+            .entry(entry.model.to_string())
+            .or_insert_with(|| ModelAudit {
+                model: entry.model.to_string(),
+                priced: entry.priced,
+                ..Default::default()
+            });
+        model.records += 1;
+        model.tokens += entry.tokens;
+        model.cost += entry.reported_cost;
+        if entry.reported_cost == 0.0 && entry.tokens > 0 {
+            model.zero_cost_records += 1;
+            model.zero_cost_tokens += entry.tokens;
+        }
+
+        if let Some(stored) = entry.stored_cost {
+            let day = self.days.entry(entry.date).or_default();
+            day.stored_records += 1;
+            day.stored_cost += stored;
+            day.calculated_cost += entry.calculated_cost;
+        }
+    }
+
+    pub fn merge(&mut self, other: CostAudit) {
+        for (name, model) in other.models {
+            let entry = self.models.entry(name).or_insert_with(|| ModelAudit {
+                model: model.model.clone(),
+                priced: model.priced,
+                ..Default::default()
+            });
+            entry.records += model.records;
+            entry.tokens += model.tokens;
+            entry.cost += model.cost;
+            entry.zero_cost_records += model.zero_cost_records;
+            entry.zero_cost_tokens += model.zero_cost_tokens;
+        }
+        for (date, day) in other.days {
+            let entry = self.days.entry(date).or_default();
+            entry.stored_records += day.stored_records;
+            entry.stored_cost += day.stored_cost;
+            entry.calculated_cost += day.calculated_cost;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+
+    /// Summarize, flagging days whose stored and calculated costs differ by more
+    /// than `tolerance_pct` percent of the calculated cost
+    pub fn report(&self, tolerance_pct: f64) -> AuditReport {
+        let mut models: Vec<ModelAudit> = self.models.values().cloned().collect();
+        models.sort_by(|a, b| b.records.cmp(&a.records).then(a.model.cmp(&b.model)));
+
+        let mismatched_days = self
+            .days
+            .iter()
+            .filter_map(|(date, day)| {
+                let difference = day.stored_cost - day.calculated_cost;
+                let difference_pct = if day.calculated_cost > 0.0 {
+                    difference / day.calculated_cost * 100.0
+                } else if difference == 0.0 {
+                    0.0
+                } else {
+                    100.0
+                };
+                (difference.abs() >= MIN_DIFFERENCE_USD && difference_pct.abs() > tolerance_pct)
+                    .then_some(CostMismatch {
+                        date: *date,
+                        records: day.stored_records,
+                        stored_cost: day.stored_cost,
+                        calculated_cost: day.calculated_cost,
+                        difference,
+                        difference_pct,
+                    })
+            })
+            .collect();
+
+        AuditReport {
+            tolerance_pct,
+            total_records: models.iter().map(|m| m.records).sum(),
+            total_cost: models.iter().map(|m| m.cost).sum(),
+            unpriced_models: models
+                .iter()
+                .filter(|m| !m.priced)
+                .map(|m| m.model.clone())
+                .collect(),
+            zero_cost_records: models.iter().map(|m| m.zero_cost_records).sum(),
+            zero_cost_tokens: models.iter().map(|m| m.zero_cost_tokens).sum(),
+            models,
+            mismatched_days,
+        }
+    }
+}
+
+pub fn display_audit_report(report: &AuditReport, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "COST AUDIT".bold());
+    println!("{}", "═".repeat(78));
+    if report.models.is_empty() {
+        println!("  No usage records found");
+        return;
+    }
+    println!(
+        "  {:<22} {:>12}",
+        "Usage records:".cyan(),
+        report.total_records
+    );
+    println!(
+        "  {:<22} {:>12}",
+        "Reported cost:".cyan(),
+        format!("${:.2}", report.total_cost)
+    );
+
+    println!("\n{}", "Models".bold());
+    println!("{}", "─".repeat(78));
+    println!(
+        "  {:<36} {:>8} {:>12} {:>10}  Priced",
+        "", "Records", "Tokens", "Cost"
+    );
+    for model in &report.models {
+        println!(
+            "  {:<36} {:>8} {:>12} {:>10}  {}",
+            model.model,
+            model.records,
+            format_number(model.tokens),
+            format!("${:.2}", model.cost),
+            if model.priced {
+                "yes".green()
+            } else {
+                "no".red()
+            }
+        );
+    }
+
+    println!("\n{}", "Zero-Cost Records".bold());
+    println!("{}", "─".repeat(78));
+    if report.zero_cost_records == 0 {
+        println!("  {} Every record with tokens has a cost", "✓".green());
+    } else {
+        println!(
+            "  {} {} records with {} tokens were counted as $0",
+            "⚠".yellow(),
+            report.zero_cost_records,
+            format_number(report.zero_cost_tokens)
+        );
+        for model in report.models.iter().filter(|m| m.zero_cost_records > 0) {
+            println!(
+                "    {:<34} {:>8} records {:>12} tokens",
+                model.model,
+                model.zero_cost_records,
+                format_number(model.zero_cost_tokens)
+            );
+        }
+        if !report.unpriced_models.is_empty() {
+            println!(
+                "  {}",
+                "Run `claudelytics pricing-cache --update`, or price other providers' models in models.yaml"
+                    .dimmed()
+            );
+        }
+    }
+
+    println!(
+        "\n{}",
+        format!(
+            "Stored vs Calculated Cost (tolerance {}%)",
+            report.tolerance_pct
+        )
+        .bold()
+    );
+    println!("{}", "─".repeat(78));
+    if report.mismatched_days.is_empty() {
+        println!(
+            "  {} Stored costUSD matches the calculated cost on every day",
+            "✓".green()
+        );
+    } else {
+        println!(
+            "  {:<12} {:>8} {:>12} {:>12} {:>12} {:>9}",
+            "Date", "Records", "Stored", "Calculated", "Difference", "%"
+        );
+        for day in &report.mismatched_days {
+            let difference = format!("{:+.2}", day.difference);
+            println!(
+                "  {:<12} {:>8} {:>12} {:>12} {:>12} {:>8.1}%",
+                day.date.format("%Y-%m-%d"),
+                day.records,
+                format!("${:.2}", day.stored_cost),
+                format!("${:.2}", day.calculated_cost),
+                if day.difference < 0.0 {
+                    difference.red().to_string()
+                } else {
+                    difference.yellow().to_string()
+                },
+                day.difference_pct
+            );
+        }
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        day: u32,
+        model: &str,
+        priced: bool,
+        stored_cost: Option<f64>,
+        calculated_cost: f64,
+    ) -> AuditEntry<'_> {
+        AuditEntry {
+            date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+            model,
+            tokens: 1000,
+            priced,
+            stored_cost,
+            calculated_cost,
+            reported_cost: stored_cost.filter(|c| *c > 0.0).unwrap_or(calculated_cost),
+        }
+    }
+
+    #[test]
+    fn test_audit_flags_unpriced_models_and_mismatched_days() {
+        let mut first = CostAudit::default();
+        first.add(entry(1, "claude-sonnet-4", true, Some(1.0), 1.02));
+        first.add(entry(2, "claude-sonnet-4", true, Some(0.5), 1.0));
+        let mut second = CostAudit::default();
+        second.add(entry(2, "gpt-4o", false, None, 0.0));
+        second.add(entry(2, "gpt-4o", false, None, 0.0));
+        first.merge(second);
+
+        let report = first.report(5.0);
+        assert_eq!(report.total_records, 4);
+        // Ties in record count are ordered by name
+        assert_eq!(report.models[0].model, "claude-sonnet-4");
+        assert_eq!(report.unpriced_models, vec!["gpt-4o"]);
+        assert_eq!(report.zero_cost_records, 2);
+        assert_eq!(report.zero_cost_tokens, 2000);
+
+        // Day 1 is within 5%; day 2 stored half of the calculated cost
+        assert_eq!(report.mismatched_days.len(), 1);
+        let day = &report.mismatched_days[0];
+        assert_eq!(day.date, NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+        assert_eq!(day.records, 1);
+        assert!((day.difference + 0.5).abs() < 1e-9);
+        assert!((day.difference_pct + 50.0).abs() < 1e-9);
+
+        assert!(first.report(60.0).mismatched_days.is_empty());
+    }
+}
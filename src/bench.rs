@@ -0,0 +1,388 @@
+//! Parser benchmark (`bench` command)
+//!
+//! Times full parses of the local Claude Code data, then parses every project
+//! directory on its own to show where the time goes. Each run is saved in the
+//! cache directory and compared with the previous one, so a slowdown after an
+//! upgrade or a change to the parser shows up as a percentage.
+
+use crate::helpers::format_size;
+use crate::parser::UsageParser;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Timing of the files in one project directory, parsed on their own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryTiming {
+    pub directory: String,
+    pub files: usize,
+    pub bytes: u64,
+    pub records: u64,
+    pub elapsed_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchRun {
+    pub version: String,
+    pub taken_at: DateTime<Utc>,
+    pub runs: usize,
+    /// Whether unchanged files were served from the parse cache
+    pub parse_cache: bool,
+    pub files: usize,
+    pub bytes: u64,
+    /// Usage records counted in the reports (after deduplication)
+    pub records: u64,
+    /// Median wall time of the full parses
+    pub median_ms: f64,
+    pub min_ms: f64,
+    pub records_per_sec: f64,
+    /// Peak resident memory of the process, where the platform reports it
+    pub peak_memory_kb: Option<u64>,
+    /// Slowest directory first
+    pub directories: Vec<DirectoryTiming>,
+}
+
+/// Relative change of the current run against the previous one, in percent
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchComparison {
+    pub previous_version: String,
+    pub previous_taken_at: DateTime<Utc>,
+    pub median_ms_change: f64,
+    pub records_per_sec_change: f64,
+    pub peak_memory_change: Option<f64>,
+    /// Same files and parse cache setting as the previous run
+    pub like_for_like: bool,
+}
+
+impl BenchComparison {
+    pub fn new(previous: &BenchRun, current: &BenchRun) -> Self {
+        let peak_memory_change = match (previous.peak_memory_kb, current.peak_memory_kb) {
+            (Some(before), Some(after)) => Some(pct_change(before as f64, after as f64)),
+            _ => None,
+        };
+        Self {
+            previous_version: previous.version.clone(),
+            previous_taken_at: previous.taken_at,
+            median_ms_change: pct_change(previous.median_ms, current.median_ms),
+            records_per_sec_change: pct_change(previous.records_per_sec, current.records_per_sec),
+            peak_memory_change,
+            like_for_like: previous.files == current.files
+                && previous.bytes == current.bytes
+                && previous.parse_cache == current.parse_cache,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub current: BenchRun,
+    pub previous: Option<BenchComparison>,
+}
+
+fn pct_change(before: f64, after: f64) -> f64 {
+    if before > 0.0 {
+        (after - before) / before * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Run `runs` full parses and one parse per project directory. The parser's
+/// date and model filters apply, so keep them unset for comparable runs.
+pub fn run_bench(parser: &UsageParser, runs: usize, parse_cache: bool) -> Result<BenchRun> {
+    let files = parser.find_jsonl_files()?;
+    if files.is_empty() {
+        anyhow::bail!("No JSONL files found to benchmark");
+    }
+    let bytes = files.iter().map(|path| file_size(path)).sum();
+
+    let mut timings = Vec::new();
+    let mut records = 0;
+    for _ in 0..runs.max(1) {
+        let started = Instant::now();
+        let (daily_map, _, _) = parser.parse_files(&files)?;
+        timings.push(started.elapsed().as_secs_f64() * 1000.0);
+        records = count_records(&daily_map);
+    }
+    // Read before the per-directory parses, which use less memory
+    let peak_memory_kb = peak_memory_kb();
+    timings.sort_by(f64::total_cmp);
+    let median_ms = timings[timings.len() / 2];
+
+    let mut groups: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for path in files.iter() {
+        groups
+            .entry(project_directory(path))
+            .or_default()
+            .push(path.clone());
+    }
+    let mut directories = groups
+        .into_iter()
+        .map(|(directory, files)| {
+            let started = Instant::now();
+            let (daily_map, _, _) = parser.parse_files(&files)?;
+            Ok(DirectoryTiming {
+                directory,
+                bytes: files.iter().map(|path| file_size(path)).sum(),
+                files: files.len(),
+                records: count_records(&daily_map),
+                elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    directories.sort_by(|a, b| b.elapsed_ms.total_cmp(&a.elapsed_ms));
+
+    Ok(BenchRun {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        taken_at: Utc::now(),
+        runs: timings.len(),
+        parse_cache,
+        files: files.len(),
+        bytes,
+        records,
+        median_ms,
+        min_ms: timings[0],
+        records_per_sec: if median_ms > 0.0 {
+            records as f64 / (median_ms / 1000.0)
+        } else {
+            0.0
+        },
+        peak_memory_kb,
+        directories,
+    })
+}
+
+fn count_records(daily_map: &crate::models::DailyUsageMap) -> u64 {
+    daily_map.values().map(|usage| usage.message_count).sum()
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |metadata| metadata.len())
+}
+
+/// The directory under `projects/` a session file belongs to
+fn project_directory(path: &Path) -> String {
+    let components: Vec<_> = path.components().collect();
+    components
+        .iter()
+        .rposition(|component| component.as_os_str() == "projects")
+        .and_then(|index| components.get(index + 1))
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .unwrap_or_else(|| "(unknown)".to_string())
+}
+
+/// Peak resident set size (`VmHWM`) on Linux; other platforms report none
+fn peak_memory_kb() -> Option<u64> {
+    fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| parse_vm_hwm(&status))
+}
+
+fn parse_vm_hwm(status: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+}
+
+fn last_run_path() -> Result<PathBuf> {
+    let cache_dir = dirs::cache_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".cache")))
+        .ok_or_else(|| anyhow::anyhow!("Could not determine cache directory"))?;
+    Ok(cache_dir.join("claudelytics").join("bench_last.json"))
+}
+
+/// The run saved by the previous `bench`, if any
+pub fn load_last_run() -> Option<BenchRun> {
+    let data = fs::read(last_run_path().ok()?).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+pub fn save_run(run: &BenchRun) -> Result<()> {
+    let path = last_run_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let data = serde_json::to_vec_pretty(run).context("Failed to serialize benchmark")?;
+    fs::write(&path, data)
+        .with_context(|| format!("Failed to write benchmark file: {}", path.display()))
+}
+
+pub fn display_bench_report(report: &BenchReport, top: usize, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    let run = &report.current;
+    println!("\n{}", "PARSER BENCHMARK".bold());
+    println!("{}", "═".repeat(72));
+    println!(
+        "  {:<18} {} files, {}, {} records",
+        "Data:".bold(),
+        run.files,
+        format_size(run.bytes),
+        run.records
+    );
+    println!(
+        "  {:<18} {} ({})",
+        "Runs:".bold(),
+        run.runs,
+        if run.parse_cache {
+            "parse cache on"
+        } else {
+            "parse cache off"
+        }
+    );
+
+    let comparison = report.previous.as_ref();
+    println!(
+        "  {:<18} {:.1} ms (min {:.1} ms){}",
+        "Parse time:".bold(),
+        run.median_ms,
+        run.min_ms,
+        change_suffix(comparison.map(|c| c.median_ms_change), false)
+    );
+    println!(
+        "  {:<18} {:.0}{}",
+        "Records/sec:".bold(),
+        run.records_per_sec,
+        change_suffix(comparison.map(|c| c.records_per_sec_change), true)
+    );
+    match run.peak_memory_kb {
+        Some(kb) => println!(
+            "  {:<18} {:.1} MB{}",
+            "Peak memory:".bold(),
+            kb as f64 / 1024.0,
+            change_suffix(comparison.and_then(|c| c.peak_memory_change), false)
+        ),
+        None => println!("  {:<18} n/a on this platform", "Peak memory:".bold()),
+    }
+
+    match comparison {
+        Some(c) => {
+            println!(
+                "  {}",
+                format!(
+                    "Compared with the run of {} (v{})",
+                    c.previous_taken_at.format("%Y-%m-%d %H:%M UTC"),
+                    c.previous_version
+                )
+                .dimmed()
+            );
+            if !c.like_for_like {
+                println!(
+                    "  {}",
+                    "The data or the parse cache setting changed since that run".yellow()
+                );
+            }
+        }
+        None => println!("  {}", "No previous run to compare with".dimmed()),
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec![
+            Cell::new("Directory").fg(Color::Cyan),
+            Cell::new("Files").fg(Color::White),
+            Cell::new("Size").fg(Color::White),
+            Cell::new("Records").fg(Color::White),
+            Cell::new("Time").fg(Color::Yellow),
+            Cell::new("Records/sec").fg(Color::Green),
+        ]);
+    for directory in run.directories.iter().take(top) {
+        let per_sec = if directory.elapsed_ms > 0.0 {
+            directory.records as f64 / (directory.elapsed_ms / 1000.0)
+        } else {
+            0.0
+        };
+        table.add_row(vec![
+            Cell::new(&directory.directory),
+            Cell::new(directory.files),
+            Cell::new(format_size(directory.bytes)),
+            Cell::new(directory.records),
+            Cell::new(format!("{:.1} ms", directory.elapsed_ms)).fg(Color::Yellow),
+            Cell::new(format!("{:.0}", per_sec)).fg(Color::Green),
+        ]);
+    }
+    println!("\n{}", "Slowest Directories (parsed on their own)".bold());
+    println!("{table}");
+    if run.directories.len() > top {
+        println!(
+            "  {}",
+            format!("{} more not shown", run.directories.len() - top).dimmed()
+        );
+    }
+}
+
+/// `(+12.3%)` colored by whether the change is an improvement
+fn change_suffix(change: Option<f64>, higher_is_better: bool) -> String {
+    let Some(change) = change else {
+        return String::new();
+    };
+    let text = format!(" ({:+.1}%)", change);
+    if change.abs() < 1.0 {
+        text.dimmed().to_string()
+    } else if (change > 0.0) == higher_is_better {
+        text.green().to_string()
+    } else {
+        text.red().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(median_ms: f64, records_per_sec: f64, peak_memory_kb: Option<u64>) -> BenchRun {
+        BenchRun {
+            version: "0.6.0".to_string(),
+            taken_at: Utc::now(),
+            runs: 3,
+            parse_cache: false,
+            files: 10,
+            bytes: 4096,
+            records: 500,
+            median_ms,
+            min_ms: median_ms,
+            records_per_sec,
+            peak_memory_kb,
+            directories: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_comparison_and_helpers() {
+        let comparison = BenchComparison::new(
+            &run(200.0, 2500.0, Some(40_000)),
+            &run(150.0, 3333.0, Some(50_000)),
+        );
+        assert!((comparison.median_ms_change + 25.0).abs() < 1e-9);
+        assert!((comparison.records_per_sec_change - 33.32).abs() < 1e-9);
+        assert_eq!(comparison.peak_memory_change, Some(25.0));
+        assert!(comparison.like_for_like);
+
+        let comparison = BenchComparison::new(&run(200.0, 2500.0, None), &run(0.0, 0.0, None));
+        assert_eq!(comparison.peak_memory_change, None);
+
+        assert_eq!(
+            parse_vm_hwm("Name:\tclaudelytics\nVmPeak:\t  90000 kB\nVmHWM:\t   41234 kB\n"),
+            Some(41234)
+        );
+        assert_eq!(
+            project_directory(Path::new("/home/me/.claude/projects/-home-me-api/s1.jsonl")),
+            "-home-me-api"
+        );
+    }
+}
@@ -1,12 +1,45 @@
-use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
-use serde::Serialize;
+//! 5-hour billing blocks, either fixed (00:00, 05:00, ... UTC) or rolling from
+//! the first activity like Claude's own limits
+
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::models::TokenUsage;
 
 /// Claude uses 5-hour billing blocks for usage tracking
 /// This aligns with how Claude actually bills users
-const BILLING_BLOCK_HOURS: i64 = 5;
+pub const BILLING_BLOCK_HOURS: i64 = 5;
+
+/// How billing blocks are positioned in time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockAnchor {
+    /// Fixed windows from midnight UTC (00:00, 05:00, 10:00, ... for 5-hour blocks)
+    #[default]
+    Fixed,
+    /// Blocks start at the first activity (floored to the hour) and run for the
+    /// block duration, matching how Claude's usage limits actually reset
+    Rolling,
+}
+
+/// Billing block layout used by `BillingBlockManager`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BillingBlockConfig {
+    /// How blocks are positioned in time
+    pub anchor: BlockAnchor,
+    /// Length of each block in hours (clamped to 1-24)
+    pub duration_hours: i64,
+}
+
+impl Default for BillingBlockConfig {
+    fn default() -> Self {
+        Self {
+            anchor: BlockAnchor::Fixed,
+            duration_hours: BILLING_BLOCK_HOURS,
+        }
+    }
+}
 
 /// Represents a 5-hour billing block with usage data
 #[derive(Debug, Clone, Serialize)]
@@ -19,22 +52,34 @@ pub struct BillingBlock {
     pub usage: TokenUsage,
     /// Number of sessions active in this block
     pub session_count: usize,
+    /// Number of usage records (API responses) in this block
+    pub message_count: usize,
 }
 
 impl BillingBlock {
     /// Create a new billing block starting at the given time
+    #[allow(dead_code)]
     pub fn new(start_time: DateTime<Utc>) -> Self {
-        let block_start = Self::normalize_to_block_start(start_time);
+        Self::with_duration(
+            Self::normalize_to_block_start(start_time),
+            BILLING_BLOCK_HOURS,
+        )
+    }
+
+    /// Create a block of `hours` starting exactly at `start_time`
+    pub fn with_duration(start_time: DateTime<Utc>, hours: i64) -> Self {
         Self {
-            start_time: block_start,
-            end_time: block_start + Duration::hours(BILLING_BLOCK_HOURS),
+            start_time,
+            end_time: start_time + Duration::hours(hours),
             usage: TokenUsage::default(),
             session_count: 0,
+            message_count: 0,
         }
     }
 
     /// Normalize a timestamp to the start of its billing block
     /// Billing blocks start at 00:00, 05:00, 10:00, 15:00, 20:00 UTC
+    #[allow(dead_code)]
     pub fn normalize_to_block_start(time: DateTime<Utc>) -> DateTime<Utc> {
         let hour = time.hour() as i64;
         let block_hour = (hour / BILLING_BLOCK_HOURS) * BILLING_BLOCK_HOURS;
@@ -60,10 +105,11 @@ impl BillingBlock {
         )
     }
 
-    /// Get the block index for the day (0-4)
+    /// Get the block index for the day (0-4 for 5-hour blocks)
     #[allow(dead_code)]
     pub fn block_index(&self) -> usize {
-        (self.start_time.hour() as usize) / (BILLING_BLOCK_HOURS as usize)
+        let hours = (self.end_time - self.start_time).num_hours().max(1);
+        (self.start_time.hour() as usize) / (hours as usize)
     }
 }
 
@@ -72,44 +118,79 @@ impl BillingBlock {
 pub struct BillingBlockManager {
     /// Map of date to billing blocks for that day
     blocks: HashMap<NaiveDate, Vec<BillingBlock>>,
+    config: BillingBlockConfig,
+    /// Usage waiting to be grouped into rolling blocks by `finalize`
+    pending: Vec<(DateTime<Utc>, TokenUsage, bool)>,
 }
 
 impl BillingBlockManager {
     /// Create a new billing block manager
+    #[allow(dead_code)]
     pub fn new() -> Self {
+        Self::with_config(BillingBlockConfig::default())
+    }
+
+    /// Create a manager with a custom block anchor and duration
+    pub fn with_config(config: BillingBlockConfig) -> Self {
         Self {
             blocks: HashMap::new(),
+            config: BillingBlockConfig {
+                duration_hours: config.duration_hours.clamp(1, 24),
+                ..config
+            },
+            pending: Vec::new(),
+        }
+    }
+
+    /// Multiply every block's costs by `factor` (currency conversion)
+    pub fn scale_costs(&mut self, factor: f64) {
+        for block in self.blocks.values_mut().flatten() {
+            block.usage.scale_costs(factor);
+        }
+        for (_, usage, _) in &mut self.pending {
+            usage.scale_costs(factor);
         }
     }
 
     /// Add usage data to the appropriate billing block
+    ///
+    /// In rolling mode usage is buffered, since block boundaries depend on the
+    /// chronological order of all records; call `finalize` once everything is added.
     pub fn add_usage(
         &mut self,
         timestamp: DateTime<Utc>,
         usage: &TokenUsage,
         session_id: Option<&str>,
     ) {
+        if self.config.anchor == BlockAnchor::Rolling {
+            self.pending
+                .push((timestamp, usage.clone(), session_id.is_some()));
+            return;
+        }
+
         let date = timestamp.date_naive();
-        let _block_start = BillingBlock::normalize_to_block_start(timestamp);
+        let hours = self.config.duration_hours;
 
         // Ensure we have blocks for this date
         let day_blocks = self.blocks.entry(date).or_insert_with(|| {
-            let mut blocks = Vec::new();
-            for i in 0..5 {
-                let start = date
-                    .and_hms_opt(i * BILLING_BLOCK_HOURS as u32, 0, 0)
-                    .unwrap()
-                    .and_local_timezone(Utc)
-                    .unwrap();
-                blocks.push(BillingBlock::new(start));
-            }
-            blocks
+            (0..24)
+                .step_by(hours as usize)
+                .map(|hour| {
+                    let start = date
+                        .and_hms_opt(hour, 0, 0)
+                        .unwrap()
+                        .and_local_timezone(Utc)
+                        .unwrap();
+                    BillingBlock::with_duration(start, hours)
+                })
+                .collect()
         });
 
         // Find the correct block and add usage
         for block in day_blocks.iter_mut() {
             if block.contains(timestamp) {
                 block.usage.add(usage);
+                block.message_count += 1;
                 if session_id.is_some() {
                     block.session_count += 1;
                 }
@@ -118,6 +199,53 @@ impl BillingBlockManager {
         }
     }
 
+    /// Group buffered usage into rolling blocks. A block starts at the hour of the
+    /// first activity and ends after the block duration; the next activity after
+    /// that starts a new block. No-op in fixed mode.
+    pub fn finalize(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.sort_by_key(|(timestamp, _, _)| *timestamp);
+
+        let hours = self.config.duration_hours;
+        let mut current: Option<BillingBlock> = None;
+        for (timestamp, usage, has_session) in pending {
+            if current
+                .as_ref()
+                .is_none_or(|block| !block.contains(timestamp))
+            {
+                if let Some(block) = current.take() {
+                    self.push_block(block);
+                }
+                let start = timestamp
+                    .duration_trunc(Duration::hours(1))
+                    .unwrap_or(timestamp);
+                current = Some(BillingBlock::with_duration(start, hours));
+            }
+
+            if let Some(block) = current.as_mut() {
+                block.usage.add(&usage);
+                block.message_count += 1;
+                if has_session {
+                    block.session_count += 1;
+                }
+            }
+        }
+        if let Some(block) = current {
+            self.push_block(block);
+        }
+    }
+
+    fn push_block(&mut self, block: BillingBlock) {
+        self.blocks
+            .entry(block.start_time.date_naive())
+            .or_default()
+            .push(block);
+    }
+
     /// Get billing blocks for a specific date
     #[allow(dead_code)]
     pub fn get_blocks_for_date(&self, date: NaiveDate) -> Option<&Vec<BillingBlock>> {
@@ -217,12 +345,18 @@ impl BillingBlockManager {
             cache_read_tokens: (total.cache_read_tokens as f64 / count) as u64,
             total_cost: total.total_cost / count,
             fast_mode_cost: total.fast_mode_cost / count,
+            ..Default::default()
         }
     }
 }
 
+/// Billing blocks with usage and their totals (`billing-blocks --json`)
 #[derive(Debug, Serialize)]
 pub struct BillingBlockReport {
+    /// How blocks were anchored (fixed or rolling)
+    pub anchor: BlockAnchor,
+    /// Length of each block in hours
+    pub duration_hours: i64,
     /// Summary of all billing blocks
     pub blocks: Vec<BillingBlockSummary>,
     /// Total usage across all blocks
@@ -235,15 +369,24 @@ pub struct BillingBlockReport {
     pub usage_by_time: HashMap<String, TokenUsage>,
 }
 
+/// One billing block in a report
 #[derive(Debug, Serialize, Clone)]
 pub struct BillingBlockSummary {
+    /// UTC date of the block start, `YYYY-MM-DD`
     pub date: String,
+    /// Hours the block covers, e.g. `05:00-10:00`
     pub time_range: String,
+    /// Block start, `YYYY-MM-DD HH:MM:SS UTC`
     pub start_time: String,
+    /// Block end, `YYYY-MM-DD HH:MM:SS UTC`
     pub end_time: String,
+    /// Token usage within the block
     #[serde(flatten)]
     pub usage: TokenUsage,
+    /// Sessions active in the block
     pub session_count: usize,
+    /// Usage records (API responses) in the block
+    pub message_count: usize,
 }
 
 impl From<(NaiveDate, &BillingBlock)> for BillingBlockSummary {
@@ -255,6 +398,7 @@ impl From<(NaiveDate, &BillingBlock)> for BillingBlockSummary {
             end_time: block.end_time.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
             usage: block.usage.clone(),
             session_count: block.session_count,
+            message_count: block.message_count,
         }
     }
 }
@@ -276,6 +420,8 @@ impl BillingBlockManager {
             .collect();
 
         BillingBlockReport {
+            anchor: self.config.anchor,
+            duration_hours: self.config.duration_hours,
             blocks,
             total_usage,
             peak_block,
@@ -333,6 +479,55 @@ mod tests {
         assert!(!block.contains(Utc.with_ymd_and_hms(2024, 1, 1, 15, 0, 0).unwrap()));
     }
 
+    #[test]
+    fn test_rolling_blocks_anchor_to_first_activity() {
+        let mut manager = BillingBlockManager::with_config(BillingBlockConfig {
+            anchor: BlockAnchor::Rolling,
+            duration_hours: 5,
+        });
+        let usage = TokenUsage {
+            input_tokens: 10,
+            ..Default::default()
+        };
+
+        // Added out of order; the 03:20 activity anchors the first block at 03:00
+        for (h, m) in [(9, 15), (3, 20), (7, 59), (8, 0), (22, 30)] {
+            let time = Utc.with_ymd_and_hms(2024, 1, 1, h, m, 0).unwrap();
+            manager.add_usage(time, &usage, Some("session"));
+        }
+        assert!(manager.get_all_blocks().is_empty());
+        manager.finalize();
+
+        let blocks = manager.get_all_blocks();
+        let ranges: Vec<_> = blocks
+            .iter()
+            .map(|(_, b)| (b.label(), b.usage.input_tokens))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                ("03:00-08:00".to_string(), 20),
+                ("08:00-13:00".to_string(), 20),
+                ("22:00-03:00".to_string(), 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fixed_blocks_with_custom_duration() {
+        let mut manager = BillingBlockManager::with_config(BillingBlockConfig {
+            anchor: BlockAnchor::Fixed,
+            duration_hours: 8,
+        });
+        let time = Utc.with_ymd_and_hms(2024, 1, 1, 17, 30, 0).unwrap();
+        manager.add_usage(time, &TokenUsage::default(), None);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let blocks = manager.get_blocks_for_date(date).unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[2].label(), "16:00-00:00");
+    }
+
     #[test]
     fn test_billing_block_manager() {
         let mut manager = BillingBlockManager::new();
@@ -349,6 +544,7 @@ mod tests {
             cache_read_tokens: 0,
             total_cost: 0.001,
             fast_mode_cost: 0.0,
+            ..Default::default()
         };
 
         let usage2 = TokenUsage {
@@ -358,6 +554,7 @@ mod tests {
             cache_read_tokens: 0,
             total_cost: 0.002,
             fast_mode_cost: 0.0,
+            ..Default::default()
         };
 
         manager.add_usage(time1, &usage1, Some("session1"));
@@ -378,6 +575,7 @@ mod tests {
         assert_eq!(blocks[1].usage.input_tokens, 300);
         assert_eq!(blocks[1].usage.output_tokens, 500);
         assert_eq!(blocks[1].session_count, 2);
+        assert_eq!(blocks[1].message_count, 2);
 
         // Check total usage
         let total = manager.total_usage();
@@ -0,0 +1,226 @@
+//! Session bookmarks with notes and tags
+//!
+//! Bookmarks are kept in `bookmarks.yaml` next to the configuration file, so
+//! they survive TUI restarts and can be managed with `claudelytics bookmarks`.
+//! Sessions are identified as `project-dir/session-id`, the same key session
+//! reports use.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Bookmark {
+    /// `project-dir/session-id`
+    pub session: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Bookmark {
+    /// Session ID without the project directory
+    pub fn session_id(&self) -> &str {
+        self.session.rsplit('/').next().unwrap_or(&self.session)
+    }
+
+    /// Project directory without the session ID
+    pub fn project(&self) -> &str {
+        self.session
+            .rsplit_once('/')
+            .map_or("", |(project, _)| project)
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BookmarkStore {
+    #[serde(default)]
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl BookmarkStore {
+    /// `bookmarks.yaml` in the claudelytics configuration directory
+    pub fn store_path() -> Result<PathBuf> {
+        let config_path = crate::config_v2::AppConfig::config_path()?;
+        let dir = config_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Invalid config path: {}", config_path.display()))?;
+        Ok(dir.join("bookmarks.yaml"))
+    }
+
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::store_path()?)
+    }
+
+    fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read bookmarks: {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse bookmarks: {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::store_path()?)
+    }
+
+    fn save_to(&self, path: &Path) -> Result<()> {
+        let dir = path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Invalid bookmarks path: {}", path.display()))?;
+        fs::create_dir_all(dir)?;
+
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        serde_yaml::to_writer(&mut tmp, self).context("Failed to serialize bookmarks")?;
+        tmp.persist(path)
+            .with_context(|| format!("Failed to write bookmarks: {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn get(&self, session: &str) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|b| b.session == session)
+    }
+
+    pub fn contains(&self, session: &str) -> bool {
+        self.get(session).is_some()
+    }
+
+    /// Bookmark for `session`, created if it does not exist yet
+    pub fn entry(&mut self, session: &str) -> &mut Bookmark {
+        let index = match self.bookmarks.iter().position(|b| b.session == session) {
+            Some(index) => index,
+            None => {
+                self.bookmarks.push(Bookmark {
+                    session: session.to_string(),
+                    note: None,
+                    tags: Vec::new(),
+                    created_at: Utc::now(),
+                });
+                self.bookmarks.len() - 1
+            }
+        };
+        &mut self.bookmarks[index]
+    }
+
+    /// Add tags to a bookmark, skipping ones it already has
+    pub fn add_tags(&mut self, session: &str, tags: &[String]) {
+        let bookmark = self.entry(session);
+        for tag in tags {
+            if !bookmark.tags.contains(tag) {
+                bookmark.tags.push(tag.clone());
+            }
+        }
+    }
+
+    /// Remove a bookmark. Returns false when it did not exist.
+    pub fn remove(&mut self, session: &str) -> bool {
+        let before = self.bookmarks.len();
+        self.bookmarks.retain(|b| b.session != session);
+        self.bookmarks.len() != before
+    }
+
+    /// Stored session matching `query` by full key, session ID, or unique ID prefix
+    pub fn find(&self, query: &str) -> Result<String> {
+        match_session(self.bookmarks.iter().map(|b| b.session.clone()), query)
+    }
+}
+
+/// Resolve a session ID (or unique prefix, or `project/session-id`) to its
+/// `project-dir/session-id` key by looking at the session files on disk
+pub fn resolve_session(claude_dirs: &[PathBuf], query: &str) -> Result<String> {
+    let sessions = claude_dirs.iter().flat_map(|dir| {
+        let projects_dir = dir.join("projects");
+        WalkDir::new(&projects_dir)
+            .min_depth(2)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
+            .filter_map(move |entry| {
+                let relative = entry.path().strip_prefix(&projects_dir).ok()?;
+                let key = relative.with_extension("");
+                Some(key.to_string_lossy().replace('\\', "/"))
+            })
+    });
+    match_session(sessions, query)
+}
+
+/// Session among `sessions` matching `query` by full key, session ID, or unique ID prefix
+pub fn match_session(sessions: impl Iterator<Item = String>, query: &str) -> Result<String> {
+    let mut matches = Vec::new();
+    for session in sessions {
+        let id = session.rsplit('/').next().unwrap_or(&session);
+        if session == query || id == query {
+            return Ok(session);
+        }
+        if id.starts_with(query) && !matches.contains(&session) {
+            matches.push(session);
+        }
+    }
+
+    match matches.len() {
+        0 => anyhow::bail!("No session matches '{}'", query),
+        1 => Ok(matches.remove(0)),
+        n => anyhow::bail!(
+            "'{}' matches {} sessions, use a longer prefix:\n  {}",
+            query,
+            n,
+            matches.join("\n  ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_store_roundtrip_and_updates() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bookmarks.yaml");
+
+        let mut store = BookmarkStore::load_from(&path).unwrap();
+        assert!(store.bookmarks.is_empty());
+
+        store.entry("-home-me-app/abc").note = Some("slow build".to_string());
+        store.add_tags("-home-me-app/abc", &["perf".to_string()]);
+        store.add_tags("-home-me-app/abc", &["perf".to_string(), "ci".to_string()]);
+        store.entry("-home-me-lib/def");
+        store.save_to(&path).unwrap();
+
+        let mut store = BookmarkStore::load_from(&path).unwrap();
+        let bookmark = store.get("-home-me-app/abc").unwrap();
+        assert_eq!(bookmark.note.as_deref(), Some("slow build"));
+        assert_eq!(bookmark.tags, vec!["perf", "ci"]);
+        assert_eq!(bookmark.project(), "-home-me-app");
+        assert_eq!(bookmark.session_id(), "abc");
+
+        assert_eq!(store.find("de").unwrap(), "-home-me-lib/def");
+        assert!(store.remove("-home-me-lib/def"));
+        assert!(!store.remove("-home-me-lib/def"));
+        assert_eq!(store.bookmarks.len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_session() {
+        let dir = TempDir::new().unwrap();
+        for (project, session) in [("p1", "abc123"), ("p1", "abd456"), ("p2", "xyz")] {
+            let project_dir = dir.path().join("projects").join(project);
+            fs::create_dir_all(&project_dir).unwrap();
+            fs::write(project_dir.join(format!("{}.jsonl", session)), "").unwrap();
+        }
+        let dirs = vec![dir.path().to_path_buf()];
+
+        assert_eq!(resolve_session(&dirs, "abc").unwrap(), "p1/abc123");
+        assert_eq!(resolve_session(&dirs, "p2/xyz").unwrap(), "p2/xyz");
+        assert!(resolve_session(&dirs, "ab").is_err());
+        assert!(resolve_session(&dirs, "nope").is_err());
+    }
+}
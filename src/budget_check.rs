@@ -0,0 +1,318 @@
+//! Budget threshold check for scripts (`check` command)
+//!
+//! Compares today's and this month's spend against limits and maps the result
+//! to an exit code, so it can drive cron jobs and shell prompts. `PeriodSpend`
+//! backs the one-line budget summary of `cost --week` / `cost --month`.
+
+use crate::currency::Money;
+use crate::models::DailyUsageMap;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use colored::Colorize;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetStatus {
+    /// Below the warning threshold
+    Ok,
+    /// At or above the warning threshold, below the limit
+    Warning,
+    /// Limit reached or exceeded
+    Exceeded,
+}
+
+impl BudgetStatus {
+    /// Process exit code: 0 ok, 1 warning, 2 exceeded
+    pub fn exit_code(self) -> i32 {
+        match self {
+            BudgetStatus::Ok => 0,
+            BudgetStatus::Warning => 1,
+            BudgetStatus::Exceeded => 2,
+        }
+    }
+}
+
+/// Spend of one period against its limit
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodCheck {
+    pub period: String,
+    pub spent: f64,
+    pub limit: f64,
+    pub percent: f64,
+    pub status: BudgetStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetCheckReport {
+    pub date: NaiveDate,
+    pub warn_threshold: f64,
+    pub checks: Vec<PeriodCheck>,
+    /// Worst status over all checks
+    pub status: BudgetStatus,
+    pub exit_code: i32,
+}
+
+/// Check today's and this month's spend. Periods without a limit are skipped.
+pub fn check_budget(
+    daily_map: &DailyUsageMap,
+    today: NaiveDate,
+    max_daily_cost: Option<f64>,
+    max_monthly_cost: Option<f64>,
+    warn_threshold: f64,
+) -> BudgetCheckReport {
+    let daily_spent = daily_map.get(&today).map_or(0.0, |usage| usage.total_cost);
+    let monthly_spent: f64 = daily_map
+        .iter()
+        .filter(|(date, _)| {
+            date.year() == today.year() && date.month() == today.month() && **date <= today
+        })
+        .map(|(_, usage)| usage.total_cost)
+        .sum();
+
+    let checks: Vec<PeriodCheck> = [
+        ("daily", daily_spent, max_daily_cost),
+        ("monthly", monthly_spent, max_monthly_cost),
+    ]
+    .into_iter()
+    .filter_map(|(period, spent, limit)| {
+        let limit = limit?;
+        let percent = if limit > 0.0 {
+            spent / limit * 100.0
+        } else {
+            100.0
+        };
+        let status = if spent >= limit {
+            BudgetStatus::Exceeded
+        } else if percent >= warn_threshold * 100.0 {
+            BudgetStatus::Warning
+        } else {
+            BudgetStatus::Ok
+        };
+        Some(PeriodCheck {
+            period: period.to_string(),
+            spent,
+            limit,
+            percent,
+            status,
+        })
+    })
+    .collect();
+
+    let status = checks
+        .iter()
+        .map(|check| check.status)
+        .max()
+        .unwrap_or(BudgetStatus::Ok);
+
+    BudgetCheckReport {
+        date: today,
+        warn_threshold,
+        checks,
+        status,
+        exit_code: status.exit_code(),
+    }
+}
+
+/// Calendar period summarized by the `cost` command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostPeriod {
+    Today,
+    /// Monday to Sunday
+    Week,
+    Month,
+}
+
+impl CostPeriod {
+    fn adjective(self) -> &'static str {
+        match self {
+            CostPeriod::Today => "daily",
+            CostPeriod::Week => "weekly",
+            CostPeriod::Month => "monthly",
+        }
+    }
+
+    /// First and last day of the period containing `date`
+    fn bounds(self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            CostPeriod::Today => (date, date),
+            CostPeriod::Week => {
+                let start = date - Days::new(date.weekday().num_days_from_monday() as u64);
+                (start, start + Days::new(6))
+            }
+            CostPeriod::Month => {
+                let start = date.with_day(1).unwrap_or(date);
+                (start, start + Months::new(1) - Days::new(1))
+            }
+        }
+    }
+}
+
+/// Spend so far in the current period, its budget, and the pace for the whole period
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodSpend {
+    pub period: CostPeriod,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub spent: f64,
+    pub budget: Option<f64>,
+    /// Spent as a percentage of the budget
+    pub percent: Option<f64>,
+    /// Spend at the end of the period if the average daily spend so far continues
+    pub on_pace_for: f64,
+}
+
+/// Spend of the `period` containing `today`, counting days up to `today`
+pub fn period_spend(
+    daily_map: &DailyUsageMap,
+    today: NaiveDate,
+    period: CostPeriod,
+    budget: Option<f64>,
+) -> PeriodSpend {
+    let (start, end) = period.bounds(today);
+    let spent: f64 = daily_map
+        .iter()
+        .filter(|(date, _)| (start..=today).contains(*date))
+        .map(|(_, usage)| usage.total_cost)
+        .sum();
+    let elapsed_days = (today - start).num_days() + 1;
+    let total_days = (end - start).num_days() + 1;
+    PeriodSpend {
+        period,
+        start,
+        end,
+        spent,
+        budget,
+        percent: budget
+            .filter(|limit| *limit > 0.0)
+            .map(|limit| spent / limit * 100.0),
+        on_pace_for: spent / elapsed_days as f64 * total_days as f64,
+    }
+}
+
+impl PeriodSpend {
+    /// One line for shell prompts, e.g.
+    /// `$37.20 of $100.00 monthly budget (37%), on pace for $82.00`
+    pub fn summary_line(&self) -> String {
+        let mut line = match (self.budget, self.percent) {
+            (Some(budget), Some(percent)) => format!(
+                "{:.2} of {:.2} {} budget ({:.0}%)",
+                Money(self.spent),
+                Money(budget),
+                self.period.adjective(),
+                percent
+            ),
+            (Some(budget), None) => format!(
+                "{:.2} of {:.2} {} budget",
+                Money(self.spent),
+                Money(budget),
+                self.period.adjective()
+            ),
+            (None, _) => format!(
+                "{:.2} {}",
+                Money(self.spent),
+                match self.period {
+                    CostPeriod::Today => "today",
+                    CostPeriod::Week => "this week",
+                    CostPeriod::Month => "this month",
+                }
+            ),
+        };
+        if self.period != CostPeriod::Today {
+            line.push_str(&format!(", on pace for {:.2}", Money(self.on_pace_for)));
+        }
+        line
+    }
+}
+
+pub fn display_budget_check(report: &BudgetCheckReport, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    for check in &report.checks {
+        let label = match check.status {
+            BudgetStatus::Ok => "OK  ".green(),
+            BudgetStatus::Warning => "WARN".yellow(),
+            BudgetStatus::Exceeded => "OVER".red().bold(),
+        };
+        println!(
+            "{} {:<8} {:.2} / {:.2} ({:.0}%)",
+            label,
+            check.period,
+            Money(check.spent),
+            Money(check.limit),
+            check.percent
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+
+    fn usage(cost: f64) -> TokenUsage {
+        TokenUsage {
+            total_cost: cost,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_budget_statuses() {
+        let date = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut daily_map = DailyUsageMap::new();
+        daily_map.insert(date(1), usage(300.0));
+        daily_map.insert(date(10), usage(21.0));
+        daily_map.insert(NaiveDate::from_ymd_opt(2024, 2, 28).unwrap(), usage(500.0));
+
+        let report = check_budget(&daily_map, date(10), Some(25.0), Some(400.0), 0.8);
+        assert_eq!(report.checks[0].status, BudgetStatus::Warning);
+        assert_eq!(report.checks[1].spent, 321.0);
+        assert_eq!(report.checks[1].status, BudgetStatus::Warning);
+        assert_eq!(report.exit_code, 1);
+
+        let report = check_budget(&daily_map, date(10), Some(20.0), None, 0.8);
+        assert_eq!(report.checks.len(), 1);
+        assert_eq!(report.status, BudgetStatus::Exceeded);
+        assert_eq!(report.exit_code, 2);
+
+        let report = check_budget(&daily_map, date(11), Some(25.0), Some(1000.0), 0.8);
+        assert_eq!(report.status, BudgetStatus::Ok);
+        assert_eq!(report.checks[0].spent, 0.0);
+    }
+
+    #[test]
+    fn test_period_spend() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let mut daily_map = DailyUsageMap::new();
+        daily_map.insert(date(4, 30), usage(50.0));
+        daily_map.insert(date(6, 3), usage(10.0));
+        daily_map.insert(date(6, 5), usage(20.0));
+        daily_map.insert(date(6, 6), usage(99.0));
+
+        // Wednesday June 5th: 5 of 30 days of the month have passed
+        let month = period_spend(&daily_map, date(6, 5), CostPeriod::Month, Some(100.0));
+        assert_eq!(month.start, date(6, 1));
+        assert_eq!(month.end, date(6, 30));
+        assert_eq!(month.spent, 30.0);
+        assert_eq!(month.on_pace_for, 180.0);
+        assert_eq!(
+            month.summary_line(),
+            "$30.00 of $100.00 monthly budget (30%), on pace for $180.00"
+        );
+
+        let week = period_spend(&daily_map, date(6, 5), CostPeriod::Week, None);
+        assert_eq!(week.start, date(6, 3));
+        assert_eq!(week.spent, 30.0);
+        assert_eq!(week.summary_line(), "$30.00 this week, on pace for $70.00");
+
+        let today = period_spend(&daily_map, date(6, 5), CostPeriod::Today, Some(40.0));
+        assert_eq!(today.summary_line(), "$20.00 of $40.00 daily budget (50%)");
+    }
+}
@@ -1,6 +1,10 @@
-use crate::models::DailyUsageMap;
+//! Token and cost burn rates over recent usage, overall and per model or project
+
+use crate::models::{DailyUsageMap, TokenUsage};
+use crate::models_registry::ModelsRegistry;
+use anyhow::{Result, bail};
 use chrono::{DateTime, Duration, Local, Utc};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Token burn rate calculator inspired by ccusage
 pub struct BurnRateCalculator {
@@ -31,13 +35,14 @@ pub struct BurnRateMetrics {
 
 /// Hourly usage data for burn rate calculation
 #[derive(Debug, Clone, Default)]
-pub struct HourlyUsage {
+struct HourlyUsage {
     pub tokens: u64,
     pub cost: f64,
     pub timestamp: DateTime<Utc>,
 }
 
 impl BurnRateCalculator {
+    /// Calculator over the usage in `daily_usage`
     pub fn new(daily_usage: DailyUsageMap) -> Self {
         Self { daily_usage }
     }
@@ -192,44 +197,290 @@ impl BurnRateCalculator {
     }
 }
 
-/// Format burn rate metrics for display
-#[allow(dead_code)]
-pub fn format_burn_rate(metrics: &BurnRateMetrics) -> String {
-    let trend_arrow = if metrics.trend_percentage > 0.0 {
-        "↑"
-    } else if metrics.trend_percentage < 0.0 {
-        "↓"
-    } else {
-        "→"
+/// A single usage record with its time and model, collected by the parser for
+/// windowed burn rates
+#[derive(Debug, Clone)]
+pub struct UsageEvent {
+    /// When the response was recorded
+    pub timestamp: DateTime<Utc>,
+    /// `project-dir/session-id` key
+    pub session: String,
+    /// Model that answered
+    pub model: String,
+    /// Tokens and cost of the record
+    pub usage: TokenUsage,
+}
+
+/// Burn rate of one model family within a window
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ModelBurnRate {
+    /// Model family, e.g. `opus`
+    pub family: String,
+    /// Tokens per hour over the window
+    pub tokens_per_hour: f64,
+    /// Cost per hour over the window
+    pub cost_per_hour: f64,
+}
+
+/// Burn rate over a lookback window ending now, overall and per model family
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WindowBurnRate {
+    /// Window as written in the config, e.g. `15m`
+    pub label: String,
+    /// Length of the window in minutes
+    pub minutes: i64,
+    /// Tokens per hour over the window
+    pub tokens_per_hour: f64,
+    /// Cost per hour over the window
+    pub cost_per_hour: f64,
+    /// Most expensive family first
+    pub by_model: Vec<ModelBurnRate>,
+}
+
+/// Parse a lookback window such as `15m`, `1h`, `6h` or `1d`
+pub fn parse_window(window: &str) -> Result<Duration> {
+    let window = window.trim();
+    let split = window.char_indices().last().map_or(0, |(i, _)| i);
+    let (amount, unit) = window.split_at(split);
+    let Ok(amount) = amount.parse::<i64>() else {
+        bail!(
+            "Invalid window '{}': expected e.g. 15m, 1h, 6h or 1d",
+            window
+        );
+    };
+    let duration = match unit {
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        _ => bail!(
+            "Invalid window '{}': expected e.g. 15m, 1h, 6h or 1d",
+            window
+        ),
     };
+    if amount <= 0 {
+        bail!("Invalid window '{}': must be longer than zero", window);
+    }
+    Ok(duration)
+}
+
+/// Parse configured windows, keeping each one's label for display
+pub fn parse_windows(windows: &[String]) -> Result<Vec<(String, Duration)>> {
+    windows
+        .iter()
+        .map(|window| Ok((window.trim().to_string(), parse_window(window)?)))
+        .collect()
+}
 
-    format!(
-        "Burn Rate: {} tok/hr (${:.4}/hr) {} {:.1}%\n\
-         Projected: {} tokens/day (${:.2}/day)\n\
-         Monthly Projection: ${:.2}",
-        format_number(metrics.tokens_per_hour as u64),
-        metrics.cost_per_hour,
-        trend_arrow,
-        metrics.trend_percentage.abs(),
-        format_number(metrics.projected_daily_tokens),
-        metrics.projected_daily_cost,
-        metrics.projected_monthly_cost
-    )
+/// Burn rates over each window, ending at `now`. Rates are per hour of the
+/// window, so a 15 minute window with $1 of usage burns $4/hour.
+pub fn windowed_burn_rates(
+    events: &[UsageEvent],
+    windows: &[(String, Duration)],
+    now: DateTime<Utc>,
+) -> Vec<WindowBurnRate> {
+    let registry = ModelsRegistry::new();
+
+    windows
+        .iter()
+        .map(|(label, window)| {
+            let start = now - *window;
+            let hours = window.num_seconds() as f64 / 3600.0;
+
+            let mut total = TokenUsage::default();
+            let mut families: BTreeMap<String, TokenUsage> = BTreeMap::new();
+            for event in events
+                .iter()
+                .filter(|e| e.timestamp > start && e.timestamp <= now)
+            {
+                total.add(&event.usage);
+                let family = registry
+                    .get_model_family(&event.model)
+                    .unwrap_or_else(|| "unknown".to_string());
+                families.entry(family).or_default().add(&event.usage);
+            }
+
+            let mut by_model: Vec<ModelBurnRate> = families
+                .into_iter()
+                .map(|(family, usage)| ModelBurnRate {
+                    family,
+                    tokens_per_hour: usage.total_tokens() as f64 / hours,
+                    cost_per_hour: usage.total_cost / hours,
+                })
+                .collect();
+            by_model.sort_by(|a, b| b.cost_per_hour.total_cmp(&a.cost_per_hour));
+
+            WindowBurnRate {
+                label: label.clone(),
+                minutes: window.num_minutes(),
+                tokens_per_hour: total.total_tokens() as f64 / hours,
+                cost_per_hour: total.total_cost / hours,
+                by_model,
+            }
+        })
+        .collect()
 }
 
-/// Format number with commas
-#[allow(dead_code)]
-fn format_number(num: u64) -> String {
-    let num_str = num.to_string();
-    let chars: Vec<char> = num_str.chars().collect();
-    let mut result = String::new();
+/// Burn rate of one project over the last hour and the last day
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProjectBurnRate {
+    /// Project directory, the part of the session key before the session id
+    pub project: String,
+    /// Tokens per hour over the last hour
+    pub last_hour_tokens_per_hour: f64,
+    /// Cost per hour over the last hour
+    pub last_hour_cost_per_hour: f64,
+    /// Tokens per hour over the last 24 hours
+    pub last_day_tokens_per_hour: f64,
+    /// Cost per hour over the last 24 hours
+    pub last_day_cost_per_hour: f64,
+    /// Sessions of the project with usage in the last hour
+    pub active_sessions: usize,
+    /// Time of the project's latest record
+    pub last_activity: DateTime<Utc>,
+}
 
-    for (i, c) in chars.iter().enumerate() {
-        if i > 0 && (chars.len() - i).is_multiple_of(3) {
-            result.push(',');
+/// Burn rates of every project with usage in the 24 hours before `now`, the
+/// most active in the last hour first (then by the last day)
+pub fn project_burn_rates(events: &[UsageEvent], now: DateTime<Utc>) -> Vec<ProjectBurnRate> {
+    let hour_start = now - Duration::hours(1);
+    let day_start = now - Duration::hours(24);
+
+    let mut projects: BTreeMap<&str, ProjectBurnRate> = BTreeMap::new();
+    let mut active: BTreeMap<&str, HashSet<&str>> = BTreeMap::new();
+    for event in events
+        .iter()
+        .filter(|e| e.timestamp > day_start && e.timestamp <= now)
+    {
+        let project = event
+            .session
+            .rsplit_once('/')
+            .map_or(event.session.as_str(), |(project, _)| project);
+        let rate = projects.entry(project).or_insert_with(|| ProjectBurnRate {
+            project: project.to_string(),
+            last_hour_tokens_per_hour: 0.0,
+            last_hour_cost_per_hour: 0.0,
+            last_day_tokens_per_hour: 0.0,
+            last_day_cost_per_hour: 0.0,
+            active_sessions: 0,
+            last_activity: event.timestamp,
+        });
+        // Per hour of a 24 hour window
+        rate.last_day_tokens_per_hour += event.usage.total_tokens() as f64 / 24.0;
+        rate.last_day_cost_per_hour += event.usage.total_cost / 24.0;
+        rate.last_activity = rate.last_activity.max(event.timestamp);
+        if event.timestamp > hour_start {
+            rate.last_hour_tokens_per_hour += event.usage.total_tokens() as f64;
+            rate.last_hour_cost_per_hour += event.usage.total_cost;
+            active
+                .entry(project)
+                .or_default()
+                .insert(event.session.as_str());
         }
-        result.push(*c);
     }
 
-    result
+    let mut rates: Vec<ProjectBurnRate> = projects
+        .into_iter()
+        .map(|(project, mut rate)| {
+            rate.active_sessions = active.get(project).map_or(0, HashSet::len);
+            rate
+        })
+        .collect();
+    rates.sort_by(|a, b| {
+        b.last_hour_cost_per_hour
+            .total_cmp(&a.last_hour_cost_per_hour)
+            .then(
+                b.last_day_cost_per_hour
+                    .total_cmp(&a.last_day_cost_per_hour),
+            )
+    });
+    rates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(
+        minutes_ago: i64,
+        model: &str,
+        tokens: u64,
+        cost: f64,
+        now: DateTime<Utc>,
+    ) -> UsageEvent {
+        UsageEvent {
+            timestamp: now - Duration::minutes(minutes_ago),
+            session: "-p/s".to_string(),
+            model: model.to_string(),
+            usage: TokenUsage {
+                input_tokens: tokens,
+                total_cost: cost,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_parse_window() {
+        assert_eq!(parse_window("15m").unwrap(), Duration::minutes(15));
+        assert_eq!(parse_window("6h").unwrap(), Duration::hours(6));
+        assert_eq!(parse_window("1d").unwrap(), Duration::days(1));
+        assert!(parse_window("0h").is_err());
+        assert!(parse_window("h").is_err());
+        assert!(parse_window("10s").is_err());
+    }
+
+    #[test]
+    fn test_windowed_burn_rates_per_model() {
+        let now = Utc::now();
+        let events = vec![
+            event(5, "claude-opus-4-20250514", 1000, 1.0, now),
+            event(10, "claude-sonnet-4-20250514", 4000, 0.25, now),
+            event(90, "claude-opus-4-20250514", 2000, 2.0, now),
+        ];
+        let windows = vec![
+            ("15m".to_string(), Duration::minutes(15)),
+            ("2h".to_string(), Duration::hours(2)),
+        ];
+
+        let rates = windowed_burn_rates(&events, &windows, now);
+        assert_eq!(rates.len(), 2);
+
+        let quarter = &rates[0];
+        assert_eq!(quarter.minutes, 15);
+        assert!((quarter.cost_per_hour - 5.0).abs() < 1e-9);
+        assert_eq!(quarter.tokens_per_hour, 20000.0);
+        assert_eq!(quarter.by_model[0].family, "opus");
+        assert!((quarter.by_model[0].cost_per_hour - 4.0).abs() < 1e-9);
+        assert!((quarter.by_model[1].cost_per_hour - 1.0).abs() < 1e-9);
+
+        let two_hours = &rates[1];
+        assert!((two_hours.cost_per_hour - 1.625).abs() < 1e-9);
+        assert!((two_hours.by_model[0].cost_per_hour - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_project_burn_rates_ranking() {
+        let now = Utc::now();
+        let mut busy = event(10, "claude-opus-4-20250514", 1000, 3.0, now);
+        busy.session = "-home-me-agent/s1".to_string();
+        let mut busy_other_session = event(20, "claude-opus-4-20250514", 1000, 1.0, now);
+        busy_other_session.session = "-home-me-agent/s2".to_string();
+        let mut quiet = event(120, "claude-sonnet-4-20250514", 2000, 48.0, now);
+        quiet.session = "-home-me-docs/s3".to_string();
+        let mut stale = event(25 * 60, "claude-sonnet-4-20250514", 2000, 5.0, now);
+        stale.session = "-home-me-old/s4".to_string();
+
+        let rates = project_burn_rates(&[quiet, busy, busy_other_session, stale], now);
+        assert_eq!(rates.len(), 2);
+        // Active in the last hour ranks first even though the other project spent more today
+        assert_eq!(rates[0].project, "-home-me-agent");
+        assert!((rates[0].last_hour_cost_per_hour - 4.0).abs() < 1e-9);
+        assert_eq!(rates[0].last_hour_tokens_per_hour, 2000.0);
+        assert_eq!(rates[0].active_sessions, 2);
+        assert_eq!(rates[0].last_activity, now - Duration::minutes(10));
+        assert_eq!(rates[1].project, "-home-me-docs");
+        assert_eq!(rates[1].last_hour_cost_per_hour, 0.0);
+        assert!((rates[1].last_day_cost_per_hour - 2.0).abs() < 1e-9);
+        assert_eq!(rates[1].active_sessions, 0);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::currency::Money;
 use anyhow::Result;
 use chrono::{DateTime, Local, NaiveDate, Utc};
 use colored::Colorize;
@@ -535,45 +536,45 @@ pub fn display_cache_analysis(
     println!("{}", "─".repeat(56));
     let tw = analysis.total_cache_writes.max(1) as f64;
     println!(
-        "  {:<16} {:>8} {:>5.1}%  (${:.2})",
+        "  {:<16} {:>8} {:>5.1}%  ({:.2})",
         "Cold start:".cyan(),
         format_tokens(analysis.total_cold_start),
         analysis.total_cold_start as f64 / tw * 100.0,
-        write_cost(analysis.total_cold_start)
+        Money(write_cost(analysis.total_cold_start))
     );
     println!(
-        "  {:<16} {:>8} {:>5.1}%  (${:.2})",
+        "  {:<16} {:>8} {:>5.1}%  ({:.2})",
         "5m TTL miss:".yellow(),
         format_tokens(analysis.total_5m_miss),
         analysis.total_5m_miss as f64 / tw * 100.0,
-        write_cost(analysis.total_5m_miss)
+        Money(write_cost(analysis.total_5m_miss))
     );
     println!(
-        "  {:<16} {:>8} {:>5.1}%  (${:.2})",
+        "  {:<16} {:>8} {:>5.1}%  ({:.2})",
         "60m TTL miss:".yellow(),
         format_tokens(analysis.total_60m_miss),
         analysis.total_60m_miss as f64 / tw * 100.0,
-        write_cost(analysis.total_60m_miss)
+        Money(write_cost(analysis.total_60m_miss))
     );
     println!(
-        "  {:<16} {:>8} {:>5.1}%  (${:.2})",
+        "  {:<16} {:>8} {:>5.1}%  ({:.2})",
         "Normal churn:".green(),
         format_tokens(analysis.total_normal_churn),
         analysis.total_normal_churn as f64 / tw * 100.0,
-        write_cost(analysis.total_normal_churn)
+        Money(write_cost(analysis.total_normal_churn))
     );
     println!("  {}", "─".repeat(40));
     println!(
-        "  {:<16} {:>8}         (${:.2})",
+        "  {:<16} {:>8}         ({:.2})",
         "Total writes:".bold(),
         format_tokens(analysis.total_cache_writes),
-        write_cost(analysis.total_cache_writes)
+        Money(write_cost(analysis.total_cache_writes))
     );
     println!(
-        "  {:<16} {:>8}         (${:.2})",
+        "  {:<16} {:>8}         ({:.2})",
         "Total reads:".bold(),
         format_tokens(analysis.total_cache_reads),
-        read_cost(analysis.total_cache_reads)
+        Money(read_cost(analysis.total_cache_reads))
     );
     let overall_total = analysis.total_cache_writes + analysis.total_cache_reads;
     let overall_hit_rate = if overall_total > 0 {
@@ -0,0 +1,337 @@
+//! Cache efficiency statistics for the `cache-stats` command
+//!
+//! Compares cache creation and cache read tokens per day, session and model, and
+//! estimates how much prompt caching saved compared to sending the same context as
+//! plain input tokens. Sessions whose cache writes never paid for themselves are
+//! flagged as wasteful.
+
+use crate::currency::Money;
+use crate::models::{DailyUsageMap, SessionUsageMap, TokenUsage};
+use crate::pricing::{ModelPricing, PricingFetcher};
+use crate::pricing_cache::PricingCache;
+use crate::reports::parse_session_path;
+use colored::Colorize;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Cache usage for one day, session or model
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStatsRow {
+    pub label: String,
+    pub input_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// Cache read tokens per cache creation token (`None` when nothing was written)
+    pub read_write_ratio: Option<f64>,
+    /// Share of input context served from the cache
+    pub hit_rate_pct: f64,
+    /// Estimated USD saved versus paying the plain input price for all cached context.
+    /// Negative when cache writes cost more than the reads saved.
+    pub estimated_savings: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStatsReport {
+    pub totals: CacheStatsRow,
+    pub daily: Vec<CacheStatsRow>,
+    pub sessions: Vec<CacheStatsRow>,
+    pub models: Vec<CacheStatsRow>,
+    /// Sessions that wrote at least `min_creation` cache tokens without recouping the cost
+    pub wasteful_sessions: Vec<CacheStatsRow>,
+}
+
+/// Estimates cache savings per model from the effective pricing table
+struct SavingsEstimator {
+    pricing_data: HashMap<String, ModelPricing>,
+    fetcher: PricingFetcher,
+}
+
+impl SavingsEstimator {
+    fn new(pricing_data: HashMap<String, ModelPricing>) -> Self {
+        Self {
+            pricing_data,
+            fetcher: PricingFetcher::new(),
+        }
+    }
+
+    /// Savings of cache reads (read instead of input price) minus the cache write premium
+    fn model_savings(&self, model: &str, usage: &TokenUsage) -> f64 {
+        let Some(pricing) = self.fetcher.get_model_pricing(&self.pricing_data, model) else {
+            return 0.0;
+        };
+        let input = pricing.input_cost_per_token.unwrap_or(0.0);
+        let read = pricing.cache_read_input_token_cost.unwrap_or(input);
+        let write = pricing.cache_creation_input_token_cost.unwrap_or(input);
+
+        usage.cache_read_tokens as f64 * (input - read)
+            - usage.cache_creation_tokens as f64 * (write - input)
+    }
+
+    fn savings(&self, usage: &TokenUsage) -> f64 {
+        usage
+            .by_model
+            .iter()
+            .map(|(model, model_usage)| self.model_savings(model, model_usage))
+            .sum()
+    }
+
+    fn row(&self, label: String, usage: &TokenUsage) -> CacheStatsRow {
+        let context = usage.input_tokens + usage.cache_creation_tokens + usage.cache_read_tokens;
+        CacheStatsRow {
+            label,
+            input_tokens: usage.input_tokens,
+            cache_creation_tokens: usage.cache_creation_tokens,
+            cache_read_tokens: usage.cache_read_tokens,
+            read_write_ratio: (usage.cache_creation_tokens > 0)
+                .then(|| usage.cache_read_tokens as f64 / usage.cache_creation_tokens as f64),
+            hit_rate_pct: if context > 0 {
+                usage.cache_read_tokens as f64 / context as f64 * 100.0
+            } else {
+                0.0
+            },
+            estimated_savings: self.savings(usage),
+        }
+    }
+}
+
+fn by_savings_desc(a: &CacheStatsRow, b: &CacheStatsRow) -> Ordering {
+    b.estimated_savings
+        .partial_cmp(&a.estimated_savings)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Build cache statistics using the cached (or fallback) pricing table
+pub fn build_cache_stats(
+    daily_map: &DailyUsageMap,
+    session_map: &SessionUsageMap,
+    min_creation: u64,
+) -> CacheStatsReport {
+    build_cache_stats_with_pricing(
+        daily_map,
+        session_map,
+        min_creation,
+        PricingCache::effective_pricing(),
+    )
+}
+
+fn build_cache_stats_with_pricing(
+    daily_map: &DailyUsageMap,
+    session_map: &SessionUsageMap,
+    min_creation: u64,
+    pricing_data: HashMap<String, ModelPricing>,
+) -> CacheStatsReport {
+    let estimator = SavingsEstimator::new(pricing_data);
+
+    let mut total = TokenUsage::default();
+    let mut dates: Vec<_> = daily_map.keys().collect();
+    dates.sort_by_key(|date| std::cmp::Reverse(*date));
+    let daily = dates
+        .into_iter()
+        .map(|date| {
+            let usage = &daily_map[date];
+            total.add(usage);
+            estimator.row(date.format("%Y-%m-%d").to_string(), usage)
+        })
+        .collect();
+
+    let mut sessions: Vec<CacheStatsRow> = session_map
+        .iter()
+        .map(|(path, (usage, _))| {
+            let (project, session_id) = parse_session_path(path);
+            estimator.row(format!("{}/{}", project, session_id), usage)
+        })
+        .collect();
+    sessions.sort_by(by_savings_desc);
+
+    let mut wasteful_sessions: Vec<CacheStatsRow> = sessions
+        .iter()
+        .filter(|row| row.cache_creation_tokens >= min_creation && row.estimated_savings < 0.0)
+        .cloned()
+        .collect();
+    wasteful_sessions.sort_by_key(|row| std::cmp::Reverse(row.cache_creation_tokens));
+
+    let mut models: Vec<CacheStatsRow> = total
+        .by_model
+        .iter()
+        .map(|(model, usage)| {
+            let mut single = usage.clone();
+            single.by_model.insert(model.clone(), usage.clone());
+            estimator.row(model.clone(), &single)
+        })
+        .collect();
+    models.sort_by(by_savings_desc);
+
+    CacheStatsReport {
+        totals: estimator.row("Total".to_string(), &total),
+        daily,
+        sessions,
+        models,
+        wasteful_sessions,
+    }
+}
+
+pub fn display_cache_stats(report: &CacheStatsReport, json: bool, top: usize) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "CACHE EFFICIENCY".bold());
+    println!("{}", "═".repeat(72));
+
+    let totals = &report.totals;
+    println!(
+        "  {:<20} {:>10}",
+        "Cache writes:".cyan(),
+        format_tokens(totals.cache_creation_tokens)
+    );
+    println!(
+        "  {:<20} {:>10}",
+        "Cache reads:".cyan(),
+        format_tokens(totals.cache_read_tokens)
+    );
+    println!(
+        "  {:<20} {:>10}",
+        "Read/write ratio:".cyan(),
+        format_ratio(totals.read_write_ratio)
+    );
+    println!(
+        "  {:<20} {:>9.1}%",
+        "Cache hit rate:".cyan(),
+        totals.hit_rate_pct
+    );
+    println!(
+        "  {:<20} {:>10}",
+        "Estimated savings:".bold(),
+        format_savings(totals.estimated_savings)
+    );
+
+    print_section("By Model", &report.models, report.models.len());
+    print_section("By Day (most recent)", &report.daily, top);
+    print_section("Top Sessions by Savings", &report.sessions, top);
+
+    if report.wasteful_sessions.is_empty() {
+        println!("\n{}", "No sessions wasting cache writes".green());
+    } else {
+        print_section(
+            "⚠ Sessions Wasting Cache Writes",
+            &report.wasteful_sessions,
+            top,
+        );
+    }
+    println!();
+}
+
+fn print_section(title: &str, rows: &[CacheStatsRow], limit: usize) {
+    if rows.is_empty() {
+        return;
+    }
+
+    println!("\n{}", title.bold());
+    println!("{}", "─".repeat(72));
+    println!(
+        "  {:<32} {:>8} {:>8} {:>7} {:>6} {:>8}",
+        "", "Writes", "Reads", "R/W", "Hit%", "Saved"
+    );
+    for row in rows.iter().take(limit) {
+        println!(
+            "  {:<32} {:>8} {:>8} {:>7} {:>5.1}% {:>8}",
+            truncate_label(&row.label, 32),
+            format_tokens(row.cache_creation_tokens),
+            format_tokens(row.cache_read_tokens),
+            format_ratio(row.read_write_ratio),
+            row.hit_rate_pct,
+            format_savings(row.estimated_savings)
+        );
+    }
+    if rows.len() > limit {
+        println!("  {}", format!("... {} more", rows.len() - limit).dimmed());
+    }
+}
+
+fn truncate_label(label: &str, max: usize) -> String {
+    let count = label.chars().count();
+    if count <= max {
+        label.to_string()
+    } else {
+        let tail: String = label.chars().skip(count - (max - 3)).collect();
+        format!("...{}", tail)
+    }
+}
+
+fn format_ratio(ratio: Option<f64>) -> String {
+    ratio.map_or_else(|| "-".to_string(), |r| format!("{:.1}x", r))
+}
+
+fn format_savings(amount: f64) -> String {
+    let text = format!("{:.2}", Money(amount.abs()));
+    if amount < 0.0 {
+        format!("-{}", text).red().to_string()
+    } else {
+        text.green().to_string()
+    }
+}
+
+fn format_tokens(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.0}K", n as f64 / 1_000.0)
+    } else {
+        format!("{}", n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::get_fallback_pricing;
+    use chrono::{NaiveDate, Utc};
+
+    fn tagged(model: &str, creation: u64, read: u64) -> TokenUsage {
+        let mut usage = TokenUsage {
+            input_tokens: 100,
+            cache_creation_tokens: creation,
+            cache_read_tokens: read,
+            ..Default::default()
+        };
+        usage.by_model.insert(model.to_string(), usage.clone());
+        usage
+    }
+
+    #[test]
+    fn test_savings_and_wasteful_sessions() {
+        let model = "claude-sonnet-4-20250514";
+        let good = tagged(model, 10_000, 500_000);
+        let wasteful = tagged(model, 50_000, 0);
+
+        let mut day = good.clone();
+        day.add(&wasteful);
+        let daily_map = HashMap::from([(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), day)]);
+        let session_map = HashMap::from([
+            ("proj/good".to_string(), (good, Utc::now())),
+            ("proj/wasteful".to_string(), (wasteful, Utc::now())),
+        ]);
+
+        let report = build_cache_stats_with_pricing(
+            &daily_map,
+            &session_map,
+            10_000,
+            get_fallback_pricing(),
+        );
+
+        // Sonnet 4: input $3/M, cache write $3.75/M, cache read $0.30/M
+        let expected = 500_000.0 * 2.7e-6 - 60_000.0 * 0.75e-6;
+        assert!((report.totals.estimated_savings - expected).abs() < 1e-9);
+        assert_eq!(report.totals.read_write_ratio, Some(500_000.0 / 60_000.0));
+        assert_eq!(report.models.len(), 1);
+        assert_eq!(report.sessions[0].label, "proj/good");
+        assert_eq!(report.wasteful_sessions.len(), 1);
+        assert_eq!(report.wasteful_sessions[0].label, "proj/wasteful");
+        assert_eq!(report.wasteful_sessions[0].read_write_ratio, Some(0.0));
+    }
+}
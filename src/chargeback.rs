@@ -0,0 +1,377 @@
+//! Cost allocation statements for invoicing (`chargeback` command)
+//!
+//! Rolls one calendar month of usage up by project or tag (see
+//! [`crate::reports::generate_group_report`]) and applies an optional markup,
+//! producing a statement that can be printed, or written as CSV, JSON or
+//! Markdown that converts cleanly to PDF.
+
+use crate::currency::{self, Money};
+use crate::models::{DailyUsageMap, SessionUsageMap};
+use crate::reports::{self, GroupBy, GroupPeriod, SortField, SortOrder};
+use crate::tags::Tagger;
+use anyhow::{Context, Result};
+use chrono::{Datelike, Local, NaiveDate};
+use colored::Colorize;
+use csv::Writer;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatementFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+/// One billable project or tag
+#[derive(Debug, Clone, Serialize)]
+pub struct ChargebackLine {
+    pub group: String,
+    pub sessions: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost: f64,
+    pub markup: f64,
+    /// Cost plus markup
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChargebackStatement {
+    /// Billed month, `YYYY-MM`
+    pub period: String,
+    pub group_by: String,
+    pub markup_percent: f64,
+    pub generated_at: String,
+    pub lines: Vec<ChargebackLine>,
+    pub total_tokens: u64,
+    pub total_cost: f64,
+    pub total_markup: f64,
+    pub total_amount: f64,
+    /// Usage counted under more than one tag is billed to each of them
+    pub overlapping: bool,
+}
+
+/// First day of a `YYYY-MM` period
+pub fn parse_period(period: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", period.trim()), "%Y-%m-%d")
+        .with_context(|| format!("Invalid period '{}': expected YYYY-MM", period))
+}
+
+/// First day of the month before `today`, the default billing period
+pub fn previous_month(today: NaiveDate) -> NaiveDate {
+    let (year, month) = if today.month() == 1 {
+        (today.year() - 1, 12)
+    } else {
+        (today.year(), today.month() - 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(today)
+}
+
+pub fn build_statement(
+    daily_map: &DailyUsageMap,
+    session_map: &SessionUsageMap,
+    month: NaiveDate,
+    group_by: GroupBy,
+    tagger: &Tagger,
+    markup_percent: f64,
+) -> ChargebackStatement {
+    let in_month: DailyUsageMap = daily_map
+        .iter()
+        .filter(|(date, _)| date.year() == month.year() && date.month() == month.month())
+        .map(|(date, usage)| (*date, usage.clone()))
+        .collect();
+
+    let report = reports::generate_group_report(
+        &in_month,
+        session_map,
+        group_by,
+        GroupPeriod::Month,
+        tagger,
+        Some(SortField::Cost),
+        Some(SortOrder::Desc),
+    );
+
+    let rate = markup_percent / 100.0;
+    let lines: Vec<ChargebackLine> = report
+        .groups
+        .into_iter()
+        .map(|group| ChargebackLine {
+            group: group.group,
+            sessions: group.sessions,
+            input_tokens: group.input_tokens,
+            output_tokens: group.output_tokens,
+            cache_creation_tokens: group.cache_creation_tokens,
+            cache_read_tokens: group.cache_read_tokens,
+            total_tokens: group.total_tokens,
+            cost: group.total_cost,
+            markup: group.total_cost * rate,
+            amount: group.total_cost * (1.0 + rate),
+        })
+        .collect();
+
+    let total_tokens = lines.iter().map(|l| l.total_tokens).sum();
+    let total_cost: f64 = lines.iter().map(|l| l.cost).sum();
+    let total_markup = lines.iter().map(|l| l.markup).sum();
+    let total_amount = lines.iter().map(|l| l.amount).sum();
+
+    ChargebackStatement {
+        period: month.format("%Y-%m").to_string(),
+        group_by: group_by.as_str().to_string(),
+        markup_percent,
+        generated_at: Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        overlapping: total_cost > report.totals.total_cost + 1e-9,
+        lines,
+        total_tokens,
+        total_cost,
+        total_markup,
+        total_amount,
+    }
+}
+
+pub fn statement_to_csv(statement: &ChargebackStatement) -> Result<String> {
+    let code = &currency::current().code;
+    let mut wtr = Writer::from_writer(Vec::new());
+    wtr.write_record([
+        "Period",
+        if statement.group_by == "tag" {
+            "Tag"
+        } else {
+            "Project"
+        },
+        "Sessions",
+        "Input Tokens",
+        "Output Tokens",
+        "Cache Creation Tokens",
+        "Cache Read Tokens",
+        "Total Tokens",
+        &format!("Cost {}", code),
+        &format!("Markup {}", code),
+        &format!("Amount {}", code),
+    ])?;
+    for line in &statement.lines {
+        wtr.write_record(&[
+            statement.period.clone(),
+            line.group.clone(),
+            line.sessions.to_string(),
+            line.input_tokens.to_string(),
+            line.output_tokens.to_string(),
+            line.cache_creation_tokens.to_string(),
+            line.cache_read_tokens.to_string(),
+            line.total_tokens.to_string(),
+            format!("{:.2}", line.cost),
+            format!("{:.2}", line.markup),
+            format!("{:.2}", line.amount),
+        ])?;
+    }
+    Ok(String::from_utf8(wtr.into_inner()?)?)
+}
+
+/// Statement as Markdown: a title block, one table, and the totals, which
+/// renders well through pandoc or a Markdown-to-PDF printer
+pub fn statement_to_markdown(statement: &ChargebackStatement) -> String {
+    let label = if statement.group_by == "tag" {
+        "Tag"
+    } else {
+        "Project"
+    };
+    let mut md = format!("# Claude Usage Statement — {}\n\n", statement.period);
+    let _ = writeln!(md, "- **Period:** {}", statement.period);
+    let _ = writeln!(md, "- **Allocated by:** {}", statement.group_by);
+    if statement.markup_percent != 0.0 {
+        let _ = writeln!(md, "- **Markup:** {}%", statement.markup_percent);
+    }
+    let _ = writeln!(md, "- **Generated:** {}\n", statement.generated_at);
+
+    let _ = writeln!(
+        md,
+        "| {} | Sessions | Tokens | Cost ({code}) | Markup ({code}) | Amount ({code}) |",
+        label,
+        code = currency::current().code
+    );
+    md.push_str("|---|---:|---:|---:|---:|---:|\n");
+    for line in &statement.lines {
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} | {:.2} | {:.2} | {:.2} |",
+            line.group.replace('|', "\\|"),
+            line.sessions,
+            line.total_tokens,
+            line.cost,
+            line.markup,
+            line.amount
+        );
+    }
+    let _ = writeln!(
+        md,
+        "| **Total** | | **{}** | **{:.2}** | **{:.2}** | **{:.2}** |",
+        statement.total_tokens,
+        statement.total_cost,
+        statement.total_markup,
+        statement.total_amount
+    );
+
+    if statement.overlapping {
+        md.push_str(
+            "\n_Sessions with several tags are billed under each of them, so lines overlap._\n",
+        );
+    }
+    md
+}
+
+/// Write the statement to `writer` in `format`
+pub fn write_statement(
+    statement: &ChargebackStatement,
+    format: StatementFormat,
+    writer: &mut impl Write,
+) -> Result<()> {
+    let content = match format {
+        StatementFormat::Csv => statement_to_csv(statement)?,
+        StatementFormat::Json => serde_json::to_string_pretty(statement)? + "\n",
+        StatementFormat::Markdown => statement_to_markdown(statement),
+    };
+    writer.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+pub fn display_statement(statement: &ChargebackStatement) {
+    println!(
+        "\n{}",
+        format!("CHARGEBACK STATEMENT {}", statement.period).bold()
+    );
+    println!("{}", "═".repeat(72));
+    println!(
+        "  {:<16} {}",
+        "Allocated by:".cyan(),
+        statement.group_by.as_str()
+    );
+    if statement.markup_percent != 0.0 {
+        println!("  {:<16} {}%", "Markup:".cyan(), statement.markup_percent);
+    }
+
+    if statement.lines.is_empty() {
+        println!("\n  No usage in {}", statement.period);
+        return;
+    }
+
+    use comfy_table::{
+        Cell, CellAlignment, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL,
+    };
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec![
+            Cell::new(if statement.group_by == "tag" {
+                "Tag"
+            } else {
+                "Project"
+            })
+            .fg(Color::Cyan),
+            Cell::new("Sessions").fg(Color::Cyan),
+            Cell::new("Tokens").fg(Color::Cyan),
+            Cell::new("Cost").fg(Color::Cyan),
+            Cell::new("Markup").fg(Color::Cyan),
+            Cell::new("Amount").fg(Color::Cyan),
+        ]);
+    let money =
+        |value: f64| Cell::new(format!("{:.2}", Money(value))).set_alignment(CellAlignment::Right);
+    for line in &statement.lines {
+        table.add_row(vec![
+            Cell::new(&line.group),
+            Cell::new(line.sessions).set_alignment(CellAlignment::Right),
+            Cell::new(line.total_tokens).set_alignment(CellAlignment::Right),
+            money(line.cost),
+            money(line.markup),
+            money(line.amount).fg(Color::Green),
+        ]);
+    }
+    table.add_row(vec![
+        Cell::new("Total").fg(Color::Yellow),
+        Cell::new(""),
+        Cell::new(statement.total_tokens).set_alignment(CellAlignment::Right),
+        money(statement.total_cost).fg(Color::Yellow),
+        money(statement.total_markup).fg(Color::Yellow),
+        money(statement.total_amount).fg(Color::Yellow),
+    ]);
+    println!("{}", table);
+
+    if statement.overlapping {
+        println!(
+            "{}",
+            "Sessions with several tags are billed under each of them, so lines overlap.".yellow()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_v2::TagsConfig;
+    use crate::models::TokenUsage;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_period_parsing() {
+        assert_eq!(
+            parse_period("2025-06").unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+        );
+        assert!(parse_period("2025-13").is_err());
+        assert!(parse_period("june").is_err());
+        assert_eq!(
+            previous_month(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap()),
+            NaiveDate::from_ymd_opt(2024, 12, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_statement_for_month_with_markup() {
+        let mut daily_map: DailyUsageMap = HashMap::new();
+        for (month, day, session, cost) in [
+            (6, 3, "client-a/s1", 10.0),
+            (6, 4, "client-a/s2", 5.0),
+            (6, 4, "client-b/s3", 4.0),
+            (7, 1, "client-a/s1", 100.0),
+        ] {
+            let usage = TokenUsage {
+                input_tokens: 1000,
+                total_cost: cost,
+                ..Default::default()
+            };
+            let mut day_usage = usage.clone();
+            day_usage.by_session.insert(session.to_string(), usage);
+            daily_map
+                .entry(NaiveDate::from_ymd_opt(2025, month, day).unwrap())
+                .or_default()
+                .add(&day_usage);
+        }
+
+        let statement = build_statement(
+            &daily_map,
+            &HashMap::new(),
+            parse_period("2025-06").unwrap(),
+            GroupBy::Project,
+            &Tagger::new(&TagsConfig::default()),
+            10.0,
+        );
+
+        assert_eq!(statement.period, "2025-06");
+        assert_eq!(statement.lines.len(), 2);
+        assert_eq!(statement.lines[0].group, "client-a");
+        assert_eq!(statement.lines[0].sessions, 2);
+        assert!((statement.lines[0].amount - 16.5).abs() < 1e-9);
+        assert!((statement.total_cost - 19.0).abs() < 1e-9);
+        assert!((statement.total_amount - 20.9).abs() < 1e-9);
+        assert!(!statement.overlapping);
+
+        let csv = statement_to_csv(&statement).unwrap();
+        assert!(csv.contains("2025-06,client-a,2,2000,0,0,0,2000,15.00,1.50,16.50"));
+        let md = statement_to_markdown(&statement);
+        assert!(md.contains("| **Total** | | **3000** | **19.00** | **1.90** | **20.90** |"));
+    }
+}
@@ -0,0 +1,278 @@
+//! Usage summaries posted to Slack or Discord webhooks (`notify` command)
+//!
+//! Takes the figures of a [`Digest`] and formats them as Slack Block Kit blocks
+//! or a Discord embed: the headline sentence, the top sessions and projects,
+//! the model mix, anomalies, and the billing cycle projection.
+
+use crate::currency::Money;
+use crate::digest::{self, Digest};
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Timeout for webhook requests
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+/// Sessions, projects and models listed in a post
+const LIST_LENGTH: usize = 5;
+/// Embed colors: spend went up, down or can't be compared
+const DISCORD_RED: u32 = 0xE0_4F_4F;
+const DISCORD_GREEN: u32 = 0x3B_A5_5C;
+const DISCORD_BLUE: u32 = 0x58_65_F2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotifyTarget {
+    Slack,
+    Discord,
+}
+
+impl NotifyTarget {
+    pub fn name(self) -> &'static str {
+        match self {
+            NotifyTarget::Slack => "Slack",
+            NotifyTarget::Discord => "Discord",
+        }
+    }
+
+    pub fn payload(self, digest: &Digest) -> Value {
+        match self {
+            NotifyTarget::Slack => slack_payload(digest),
+            NotifyTarget::Discord => discord_payload(digest),
+        }
+    }
+}
+
+fn title(digest: &Digest) -> String {
+    format!(
+        "Claude Code {} spend: {:.2}",
+        digest.period,
+        Money(digest.total_cost)
+    )
+}
+
+fn session_lines(digest: &Digest) -> String {
+    digest
+        .top_sessions
+        .iter()
+        .take(LIST_LENGTH)
+        .enumerate()
+        .map(|(i, session)| {
+            let name = session.summary.as_deref().unwrap_or(&session.session_id);
+            format!(
+                "{}. {} ({}): {:.2}",
+                i + 1,
+                crate::display::truncate_text(name, 50),
+                session.project,
+                Money(session.cost)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn project_lines(digest: &Digest) -> String {
+    digest
+        .top_projects
+        .iter()
+        .take(LIST_LENGTH)
+        .map(|project| {
+            format!(
+                "{}: {:.2} ({:.0}%)",
+                project.project,
+                Money(project.cost),
+                project.share
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn model_lines(digest: &Digest) -> String {
+    digest
+        .models
+        .iter()
+        .take(LIST_LENGTH)
+        .map(|model| format!("{}: {:.0}%", model.family, model.share))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn anomaly_lines(digest: &Digest) -> String {
+    digest
+        .anomalies
+        .iter()
+        .map(|anomaly| format!("• {}", anomaly))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Slack mrkdwn bolds with single asterisks
+fn slack_bold(markdown: &str) -> String {
+    markdown.replace("**", "*")
+}
+
+/// Block Kit message for a Slack incoming webhook
+pub fn slack_payload(digest: &Digest) -> Value {
+    let mut blocks = vec![
+        json!({
+            "type": "header",
+            "text": { "type": "plain_text", "text": title(digest) },
+        }),
+        json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": slack_bold(&digest::headline(digest)) },
+        }),
+    ];
+
+    let fields: Vec<Value> = [
+        ("Top sessions", session_lines(digest)),
+        ("Top projects", project_lines(digest)),
+        ("Model mix", model_lines(digest)),
+    ]
+    .into_iter()
+    .filter(|(_, text)| !text.is_empty())
+    .map(|(name, text)| json!({ "type": "mrkdwn", "text": format!("*{}*\n{}", name, text) }))
+    .collect();
+    if !fields.is_empty() {
+        blocks.push(json!({ "type": "section", "fields": fields }));
+    }
+
+    if !digest.anomalies.is_empty() {
+        blocks.push(json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": format!(":warning: *Anomalies*\n{}", anomaly_lines(digest)),
+            },
+        }));
+    }
+    blocks.push(json!({
+        "type": "context",
+        "elements": [
+            { "type": "mrkdwn", "text": slack_bold(&digest::projection_line(digest)) },
+        ],
+    }));
+
+    json!({
+        // Shown in notifications, where blocks are not rendered
+        "text": title(digest),
+        "blocks": blocks,
+    })
+}
+
+/// Embed message for a Discord webhook
+pub fn discord_payload(digest: &Digest) -> Value {
+    let color = match digest.cost_change {
+        Some(change) if change > 0.0 => DISCORD_RED,
+        Some(_) => DISCORD_GREEN,
+        None => DISCORD_BLUE,
+    };
+    let mut fields: Vec<Value> = [
+        ("Top sessions", session_lines(digest), false),
+        ("Top projects", project_lines(digest), true),
+        ("Model mix", model_lines(digest), true),
+        ("⚠️ Anomalies", anomaly_lines(digest), false),
+    ]
+    .into_iter()
+    .filter(|(_, value, _)| !value.is_empty())
+    .map(|(name, value, inline)| json!({ "name": name, "value": value, "inline": inline }))
+    .collect();
+    fields.push(json!({
+        "name": "Projection",
+        "value": digest::projection_line(digest),
+        "inline": false,
+    }));
+
+    json!({
+        "embeds": [{
+            "title": title(digest),
+            "description": digest::headline(digest),
+            "color": color,
+            "fields": fields,
+            "footer": { "text": format!("claudelytics · {} to {}", digest.start, digest.end) },
+        }],
+    })
+}
+
+/// POST `payload` to a webhook URL
+pub fn post(url: &str, payload: &Value) -> Result<()> {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+        .build()
+        .post(url)
+        .send_json(payload)
+        .context("Failed to POST the summary")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::{DigestPeriod, build_digest};
+    use crate::models::{DailyUsageMap, TokenUsage};
+    use crate::projections::AnomalyReport;
+    use chrono::NaiveDate;
+    use std::collections::HashMap;
+
+    fn sample_digest() -> Digest {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+        let mut daily_map = DailyUsageMap::new();
+        daily_map.insert(
+            today,
+            TokenUsage {
+                input_tokens: 500,
+                total_cost: 2.5,
+                ..Default::default()
+            }
+            .tagged("claude-sonnet-4-20250514", "-p-api/s1"),
+        );
+        let anomalies = AnomalyReport {
+            threshold: 3.0,
+            window_days: 30,
+            cost_anomalies: Vec::new(),
+            session_anomalies: Vec::new(),
+        };
+        build_digest(
+            &daily_map,
+            &HashMap::new(),
+            &anomalies,
+            DigestPeriod::Week,
+            today,
+            1,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_slack_payload() {
+        let payload = slack_payload(&sample_digest());
+        assert_eq!(payload["text"], "Claude Code weekly spend: $2.50");
+        let blocks = payload["blocks"].as_array().unwrap();
+        assert_eq!(blocks[0]["type"], "header");
+        let headline = blocks[1]["text"]["text"].as_str().unwrap();
+        assert!(headline.starts_with("You spent *$2.50*"));
+        assert!(!headline.contains("**"));
+        assert_eq!(
+            blocks[2]["fields"][0]["text"],
+            "*Top sessions*\n1. s1 (-p-api): $2.50"
+        );
+        // No anomalies section, and the projection last
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[3]["type"], "context");
+    }
+
+    #[test]
+    fn test_discord_payload() {
+        let payload = discord_payload(&sample_digest());
+        let embed = &payload["embeds"][0];
+        assert_eq!(embed["title"], "Claude Code weekly spend: $2.50");
+        assert_eq!(embed["color"], DISCORD_BLUE);
+        let fields = embed["fields"].as_array().unwrap();
+        let names: Vec<&str> = fields.iter().map(|f| f["name"].as_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            ["Top sessions", "Top projects", "Model mix", "Projection"]
+        );
+        assert_eq!(fields[2]["value"], "sonnet: 100%");
+    }
+}
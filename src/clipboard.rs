@@ -0,0 +1,202 @@
+//! Clipboard access for the TUI with fallbacks for SSH and headless sessions
+//!
+//! The system clipboard (`copypasta`) needs a local display server, which SSH,
+//! tmux on a remote host, and headless machines don't have. The OSC 52 escape
+//! sequence asks the terminal emulator itself to set the clipboard, so a copy
+//! made on a remote host lands on the local machine. When neither works, the
+//! content is written to a file and its path is shown instead.
+//!
+//! `clipboard.method` in the config picks one method; `auto` (the default)
+//! tries the system clipboard (skipped over SSH, where it would be the remote
+//! host's), then OSC 52, then a file.
+
+use crate::config_v2::ClipboardConfig;
+use anyhow::{Context, Result, bail};
+use copypasta::{ClipboardContext, ClipboardProvider};
+use serde::{Deserialize, Serialize};
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+
+/// Terminals ignore longer OSC 52 sequences (xterm and tmux cap them around 100 KB)
+const OSC52_MAX_ENCODED: usize = 100_000;
+
+/// How copied content reaches the user
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardMethod {
+    /// System clipboard, then OSC 52, then a file
+    #[default]
+    Auto,
+    /// System clipboard only
+    System,
+    /// OSC 52 escape sequence only
+    Osc52,
+    /// Always write a file
+    File,
+}
+
+/// Where a copy ended up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Copied {
+    System,
+    Osc52,
+    File(PathBuf),
+}
+
+impl Copied {
+    /// "clipboard", "terminal clipboard (OSC 52)" or "file /tmp/…"
+    pub fn describe(&self) -> String {
+        match self {
+            Copied::System => "clipboard".to_string(),
+            Copied::Osc52 => "terminal clipboard (OSC 52)".to_string(),
+            Copied::File(path) => format!("file {}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Clipboard {
+    method: ClipboardMethod,
+    file_dir: PathBuf,
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Self::new(&ClipboardConfig::default())
+    }
+}
+
+impl Clipboard {
+    pub fn new(config: &ClipboardConfig) -> Self {
+        Self {
+            method: config.method,
+            file_dir: config.file_dir.clone().unwrap_or_else(std::env::temp_dir),
+        }
+    }
+
+    /// Copy `content`; `file_name` (e.g. `daily.csv`) names the fallback file
+    pub fn copy(&self, content: &str, file_name: &str) -> Result<Copied> {
+        match self.method {
+            ClipboardMethod::System => copy_system(content).map(|()| Copied::System),
+            ClipboardMethod::Osc52 => copy_osc52(content).map(|()| Copied::Osc52),
+            ClipboardMethod::File => self.write_file(content, file_name),
+            ClipboardMethod::Auto => {
+                if !is_remote() && copy_system(content).is_ok() {
+                    return Ok(Copied::System);
+                }
+                if copy_osc52(content).is_ok() {
+                    return Ok(Copied::Osc52);
+                }
+                self.write_file(content, file_name)
+            }
+        }
+    }
+
+    fn write_file(&self, content: &str, file_name: &str) -> Result<Copied> {
+        std::fs::create_dir_all(&self.file_dir)
+            .with_context(|| format!("Failed to create {}", self.file_dir.display()))?;
+        let path = self.file_dir.join(format!(
+            "claudelytics-{}-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            file_name
+        ));
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Copied::File(path))
+    }
+}
+
+/// Over SSH the system clipboard belongs to the remote host
+fn is_remote() -> bool {
+    ["SSH_TTY", "SSH_CONNECTION", "SSH_CLIENT"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some())
+}
+
+fn copy_system(content: &str) -> Result<()> {
+    let mut ctx = ClipboardContext::new()
+        .map_err(|e| anyhow::anyhow!("Failed to access clipboard: {}", e))?;
+    ctx.set_contents(content.to_string())
+        .map_err(|e| anyhow::anyhow!("Failed to copy to clipboard: {}", e))
+}
+
+fn copy_osc52(content: &str) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    if !stdout.is_terminal() {
+        bail!("OSC 52 needs a terminal");
+    }
+    let encoded = base64(content.as_bytes());
+    if encoded.len() > OSC52_MAX_ENCODED {
+        bail!(
+            "Too large for the terminal clipboard ({} KB)",
+            content.len() / 1024
+        );
+    }
+    let in_tmux = std::env::var_os("TMUX").is_some();
+    stdout.write_all(osc52_sequence(&encoded, in_tmux).as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// OSC 52 sequence setting the clipboard to base64 `encoded`
+///
+/// Inside tmux the sequence is sent twice: as is, for `set-clipboard on`, and
+/// wrapped in a passthrough, for `allow-passthrough on` with the outer terminal.
+fn osc52_sequence(encoded: &str, in_tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", encoded);
+    if in_tmux {
+        format!("{}\x1bPtmux;\x1b{}\x1b\\", sequence, sequence)
+    } else {
+        sequence
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_and_osc52() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64("¥1,000\n".as_bytes()), "wqUxLDAwMAo=");
+        assert_eq!(osc52_sequence("Zm9v", false), "\x1b]52;c;Zm9v\x07");
+        assert!(osc52_sequence("Zm9v", true).ends_with("\x1bPtmux;\x1b\x1b]52;c;Zm9v\x07\x1b\\"));
+    }
+
+    #[test]
+    fn test_file_method() {
+        let dir = tempfile::tempdir().unwrap();
+        let clipboard = Clipboard::new(&ClipboardConfig {
+            method: ClipboardMethod::File,
+            file_dir: Some(dir.path().to_path_buf()),
+        });
+        let Copied::File(path) = clipboard.copy("a,b\n", "daily.csv").unwrap() else {
+            panic!("expected a file");
+        };
+        assert!(path.starts_with(dir.path()));
+        assert!(path.to_string_lossy().ends_with("-daily.csv"));
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "a,b\n");
+    }
+}
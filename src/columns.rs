@@ -0,0 +1,552 @@
+//! Column registry for `--columns`
+//!
+//! Every column a daily or session row can show is listed once here with its
+//! header and how to read it from a row (and from the totals), so the terminal
+//! table, JSON, and CSV export of `daily --columns`, `session --columns`, and
+//! `export --columns` all pick from the same names.
+
+use crate::currency::{self, Money};
+use crate::display::format_number;
+use crate::models::{DailyUsage, SessionUsage, TokenUsageTotals};
+use anyhow::Result;
+use comfy_table::{
+    Cell, CellAlignment, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL,
+};
+use serde_json::{Map, json};
+
+/// A cell of a selected column
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    Tokens(u64),
+    Cost(f64),
+    /// Plain number, shown with one decimal
+    Number(f64),
+    Empty,
+}
+
+impl Value {
+    /// Text for the terminal table
+    pub fn display(&self) -> String {
+        match self {
+            Value::Text(text) => text.clone(),
+            Value::Tokens(tokens) => format_number(*tokens),
+            Value::Cost(cost) if *cost >= 100.0 => format!("{:.2}", Money(*cost)),
+            Value::Cost(cost) => format!("{:.4}", Money(*cost)),
+            Value::Number(number) => format!("{:.1}", number),
+            Value::Empty => "-".to_string(),
+        }
+    }
+
+    /// Text for a CSV field
+    pub fn csv(&self) -> String {
+        match self {
+            Value::Text(text) => text.clone(),
+            Value::Tokens(tokens) => tokens.to_string(),
+            Value::Cost(cost) => format!("{:.6}", cost),
+            Value::Number(number) => format!("{:.2}", number),
+            Value::Empty => String::new(),
+        }
+    }
+
+    pub fn json(&self) -> serde_json::Value {
+        match self {
+            Value::Text(text) => json!(text),
+            Value::Tokens(tokens) => json!(tokens),
+            Value::Cost(cost) | Value::Number(cost) => json!(cost),
+            Value::Empty => serde_json::Value::Null,
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self, Value::Tokens(_) | Value::Cost(_) | Value::Number(_))
+    }
+}
+
+/// One selectable column of a report row type `R`
+pub struct Column<R> {
+    /// Name used in `--columns` and as the JSON key
+    pub id: &'static str,
+    /// Other names accepted in `--columns`
+    pub aliases: &'static [&'static str],
+    pub header: &'static str,
+    pub value: fn(&R) -> Value,
+    /// Value in the totals row, if the column has one
+    pub total: Option<fn(&TokenUsageTotals) -> Value>,
+}
+
+impl<R> Column<R> {
+    /// Header for tables and CSV; the cost column names the display currency
+    pub fn header(&self) -> String {
+        if self.id == "cost" {
+            currency::cost_header()
+        } else {
+            self.header.to_string()
+        }
+    }
+
+    /// CSV header, matching the other CSV exports (`Cost USD`)
+    fn csv_header(&self) -> String {
+        if self.id == "cost" {
+            format!("Cost {}", currency::current().code)
+        } else {
+            self.header.to_string()
+        }
+    }
+}
+
+fn tokens_per_dollar(tokens: u64, cost: f64) -> Value {
+    if cost > 0.0 {
+        Value::Number(tokens as f64 / cost)
+    } else {
+        Value::Empty
+    }
+}
+
+fn output_input_ratio(input: u64, output: u64) -> Value {
+    if input > 0 {
+        Value::Number(output as f64 / input as f64)
+    } else {
+        Value::Empty
+    }
+}
+
+/// Columns of `daily`
+pub fn daily_columns() -> Vec<Column<DailyUsage>> {
+    vec![
+        Column {
+            id: "date",
+            aliases: &[],
+            header: "Date",
+            value: |d| Value::Text(d.date.clone()),
+            total: Some(|_| Value::Text("Total".to_string())),
+        },
+        Column {
+            id: "input_tokens",
+            aliases: &["input"],
+            header: "Input Tokens",
+            value: |d| Value::Tokens(d.input_tokens),
+            total: Some(|t| Value::Tokens(t.input_tokens)),
+        },
+        Column {
+            id: "output_tokens",
+            aliases: &["output"],
+            header: "Output Tokens",
+            value: |d| Value::Tokens(d.output_tokens),
+            total: Some(|t| Value::Tokens(t.output_tokens)),
+        },
+        Column {
+            id: "cache_creation_tokens",
+            aliases: &["cache_creation"],
+            header: "Cache Creation",
+            value: |d| Value::Tokens(d.cache_creation_tokens),
+            total: Some(|t| Value::Tokens(t.cache_creation_tokens)),
+        },
+        Column {
+            id: "cache_read_tokens",
+            aliases: &["cache_read"],
+            header: "Cache Read",
+            value: |d| Value::Tokens(d.cache_read_tokens),
+            total: Some(|t| Value::Tokens(t.cache_read_tokens)),
+        },
+        Column {
+            id: "cache_tokens",
+            aliases: &["cache"],
+            header: "Cache Tokens",
+            value: |d| Value::Tokens(d.cache_creation_tokens + d.cache_read_tokens),
+            total: Some(|t| Value::Tokens(t.cache_creation_tokens + t.cache_read_tokens)),
+        },
+        Column {
+            id: "total_tokens",
+            aliases: &["tokens"],
+            header: "Total Tokens",
+            value: |d| Value::Tokens(d.total_tokens),
+            total: Some(|t| Value::Tokens(t.total_tokens)),
+        },
+        Column {
+            id: "thinking_tokens",
+            aliases: &["thinking"],
+            header: "Thinking Tokens",
+            value: |d| Value::Tokens(d.thinking_tokens),
+            total: Some(|t| Value::Tokens(t.thinking_tokens)),
+        },
+        Column {
+            id: "cost",
+            aliases: &["total_cost"],
+            header: "Cost",
+            value: |d| Value::Cost(d.total_cost),
+            total: Some(|t| Value::Cost(t.total_cost)),
+        },
+        Column {
+            id: "efficiency",
+            aliases: &[],
+            header: "Tokens/$",
+            value: |d| tokens_per_dollar(d.total_tokens, d.total_cost),
+            total: Some(|t| tokens_per_dollar(t.total_tokens, t.total_cost)),
+        },
+        Column {
+            id: "ratio",
+            aliases: &[],
+            header: "O/I Ratio",
+            value: |d| output_input_ratio(d.input_tokens, d.output_tokens),
+            total: Some(|t| output_input_ratio(t.input_tokens, t.output_tokens)),
+        },
+        Column {
+            id: "models",
+            aliases: &["model_breakdown"],
+            header: "Models",
+            value: |d| {
+                Value::Text(
+                    d.models
+                        .iter()
+                        .map(|m| format!("{} {:.2}", m.family, Money(m.total_cost)))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+            },
+            total: None,
+        },
+        Column {
+            id: "sources",
+            aliases: &[],
+            header: "Sources",
+            value: |d| {
+                Value::Text(
+                    d.sources
+                        .iter()
+                        .map(|s| format!("{} {:.2}", s.source, Money(s.total_cost)))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+            },
+            total: None,
+        },
+    ]
+}
+
+/// Columns of `session`
+pub fn session_columns() -> Vec<Column<SessionUsage>> {
+    vec![
+        Column {
+            id: "session",
+            aliases: &["session_path"],
+            header: "Session Path",
+            value: |s| Value::Text(format!("{}/{}", s.project_path, s.session_id)),
+            total: Some(|_| Value::Text("Total".to_string())),
+        },
+        Column {
+            id: "project",
+            aliases: &["project_path"],
+            header: "Project",
+            value: |s| Value::Text(s.project_path.clone()),
+            total: None,
+        },
+        Column {
+            id: "session_id",
+            aliases: &[],
+            header: "Session ID",
+            value: |s| Value::Text(s.session_id.clone()),
+            total: None,
+        },
+        Column {
+            id: "last_activity",
+            aliases: &["date"],
+            header: "Last Activity",
+            value: |s| Value::Text(s.last_activity.clone()),
+            total: None,
+        },
+        Column {
+            id: "summary",
+            aliases: &[],
+            header: "Summary",
+            value: |s| s.summary.clone().map_or(Value::Empty, Value::Text),
+            total: None,
+        },
+        Column {
+            id: "input_tokens",
+            aliases: &["input"],
+            header: "Input Tokens",
+            value: |s| Value::Tokens(s.input_tokens),
+            total: Some(|t| Value::Tokens(t.input_tokens)),
+        },
+        Column {
+            id: "output_tokens",
+            aliases: &["output"],
+            header: "Output Tokens",
+            value: |s| Value::Tokens(s.output_tokens),
+            total: Some(|t| Value::Tokens(t.output_tokens)),
+        },
+        Column {
+            id: "cache_creation_tokens",
+            aliases: &["cache_creation"],
+            header: "Cache Creation",
+            value: |s| Value::Tokens(s.cache_creation_tokens),
+            total: Some(|t| Value::Tokens(t.cache_creation_tokens)),
+        },
+        Column {
+            id: "cache_read_tokens",
+            aliases: &["cache_read"],
+            header: "Cache Read",
+            value: |s| Value::Tokens(s.cache_read_tokens),
+            total: Some(|t| Value::Tokens(t.cache_read_tokens)),
+        },
+        Column {
+            id: "cache_tokens",
+            aliases: &["cache"],
+            header: "Cache Tokens",
+            value: |s| Value::Tokens(s.cache_creation_tokens + s.cache_read_tokens),
+            total: Some(|t| Value::Tokens(t.cache_creation_tokens + t.cache_read_tokens)),
+        },
+        Column {
+            id: "total_tokens",
+            aliases: &["tokens"],
+            header: "Total Tokens",
+            value: |s| Value::Tokens(s.total_tokens),
+            total: Some(|t| Value::Tokens(t.total_tokens)),
+        },
+        Column {
+            id: "thinking_tokens",
+            aliases: &["thinking"],
+            header: "Thinking Tokens",
+            value: |s| Value::Tokens(s.thinking_tokens),
+            total: Some(|t| Value::Tokens(t.thinking_tokens)),
+        },
+        Column {
+            id: "cost",
+            aliases: &["total_cost"],
+            header: "Cost",
+            value: |s| Value::Cost(s.total_cost),
+            total: Some(|t| Value::Cost(t.total_cost)),
+        },
+        Column {
+            id: "efficiency",
+            aliases: &[],
+            header: "Tokens/$",
+            value: |s| tokens_per_dollar(s.total_tokens, s.total_cost),
+            total: Some(|t| tokens_per_dollar(t.total_tokens, t.total_cost)),
+        },
+        Column {
+            id: "score",
+            aliases: &["efficiency_score"],
+            header: "Score",
+            value: |s| s.efficiency_score.map_or(Value::Empty, Value::Number),
+            total: None,
+        },
+        Column {
+            id: "context_peak",
+            aliases: &["context_peak_percent"],
+            header: "Context Peak %",
+            value: |s| s.context_peak_percent.map_or(Value::Empty, Value::Number),
+            total: None,
+        },
+        Column {
+            id: "resumes",
+            aliases: &["resumed_sessions"],
+            header: "Resumes",
+            value: |s| Value::Tokens(s.resumed_sessions.len() as u64),
+            total: None,
+        },
+    ]
+}
+
+/// Pick the columns named in a comma-separated `--columns` list, in that order
+pub fn select<R>(spec: &str, available: Vec<Column<R>>, report: &str) -> Result<Vec<Column<R>>> {
+    let ids: Vec<&str> = available.iter().map(|column| column.id).collect();
+    let mut available: Vec<Option<Column<R>>> = available.into_iter().map(Some).collect();
+    let mut selected = Vec::new();
+    for name in spec.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let name = name.to_ascii_lowercase().replace('-', "_");
+        let position = available.iter().position(|column| {
+            column
+                .as_ref()
+                .is_some_and(|c| c.id == name || c.aliases.contains(&name.as_str()))
+        });
+        match position {
+            Some(position) => selected.extend(available[position].take()),
+            None if selected
+                .iter()
+                .any(|c: &Column<R>| c.id == name || c.aliases.contains(&name.as_str())) =>
+            {
+                anyhow::bail!("Column '{}' is listed twice in --columns", name)
+            }
+            None => anyhow::bail!(
+                "Unknown {} column '{}' (available: {})",
+                report,
+                name,
+                ids.join(", ")
+            ),
+        }
+    }
+    if selected.is_empty() {
+        anyhow::bail!("--columns needs at least one column name");
+    }
+    Ok(selected)
+}
+
+/// Whether the column `id` is among the selected ones
+pub fn contains<R>(columns: &[Column<R>], id: &str) -> bool {
+    columns.iter().any(|column| column.id == id)
+}
+
+/// Table with the selected columns and a totals row
+pub fn display_table<R>(columns: &[Column<R>], rows: &[R], totals: &TokenUsageTotals) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(
+            columns
+                .iter()
+                .map(|column| Cell::new(column.header()).fg(Color::Cyan)),
+        );
+    for row in rows {
+        table.add_row(columns.iter().map(|column| {
+            let value = (column.value)(row);
+            let cell = Cell::new(value.display());
+            if value.is_numeric() {
+                cell.set_alignment(CellAlignment::Right)
+            } else {
+                cell
+            }
+        }));
+    }
+    if !rows.is_empty() && columns.iter().any(|column| column.total.is_some()) {
+        table.add_row(columns.iter().map(|column| {
+            let value = column.total.map_or(Value::Empty, |total| total(totals));
+            let text = if value == Value::Empty {
+                String::new()
+            } else {
+                value.display()
+            };
+            Cell::new(text)
+                .fg(Color::Yellow)
+                .set_alignment(if value.is_numeric() {
+                    CellAlignment::Right
+                } else {
+                    CellAlignment::Left
+                })
+        }));
+    }
+    println!("{}", table);
+}
+
+/// `{ <rows_key>: [{column: value}], totals: {column: value} }`
+pub fn to_json<R>(
+    columns: &[Column<R>],
+    rows: &[R],
+    totals: &TokenUsageTotals,
+    rows_key: &str,
+) -> serde_json::Value {
+    let rows: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| (column.id.to_string(), (column.value)(row).json()))
+                .collect::<Map<_, _>>()
+                .into()
+        })
+        .collect();
+    let totals: Map<_, _> = columns
+        .iter()
+        .filter_map(|column| {
+            let total = column.total?(totals);
+            matches!(total, Value::Tokens(_) | Value::Cost(_) | Value::Number(_))
+                .then(|| (column.id.to_string(), total.json()))
+        })
+        .collect();
+    json!({ rows_key: rows, "totals": totals })
+}
+
+/// CSV with a header row and one line per row
+pub fn write_csv<R, W: std::io::Write>(columns: &[Column<R>], rows: &[R], writer: W) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(columns.iter().map(|column| column.csv_header()))?;
+    for row in rows {
+        wtr.write_record(columns.iter().map(|column| (column.value)(row).csv()))?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, input: u64, output: u64, cost: f64) -> DailyUsage {
+        DailyUsage {
+            date: date.to_string(),
+            input_tokens: input,
+            output_tokens: output,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            total_tokens: input + output,
+            total_cost: cost,
+            thinking_tokens: 0,
+            models: Vec::new(),
+            sources: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_select_columns() {
+        let columns = select("Date, cost,tokens", daily_columns(), "daily").unwrap();
+        let ids: Vec<_> = columns.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec!["date", "cost", "total_tokens"]);
+
+        // Sessions take `date` as their last activity
+        let columns = select("date,cost", session_columns(), "session").unwrap();
+        assert_eq!(columns[0].id, "last_activity");
+
+        let err = select("date,price", daily_columns(), "daily")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Unknown daily column 'price'"));
+        assert!(err.to_string().contains("available: "));
+        assert!(select("cost,total_cost", daily_columns(), "daily").is_err());
+        assert!(select(" , ", daily_columns(), "daily").is_err());
+    }
+
+    #[test]
+    fn test_columns_to_json_and_csv() {
+        let rows = vec![
+            day("2024-01-01", 1000, 500, 1.5),
+            day("2024-01-02", 0, 0, 0.0),
+        ];
+        let totals = TokenUsageTotals {
+            input_tokens: 1000,
+            output_tokens: 500,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            total_tokens: 1500,
+            total_cost: 1.5,
+            thinking_tokens: 0,
+        };
+        let columns = select(
+            "date,total_tokens,cost,efficiency",
+            daily_columns(),
+            "daily",
+        )
+        .unwrap();
+
+        let value = to_json(&columns, &rows, &totals, "daily");
+        assert_eq!(
+            value["daily"][0],
+            json!({"date": "2024-01-01", "total_tokens": 1500, "cost": 1.5, "efficiency": 1000.0})
+        );
+        assert_eq!(value["daily"][1]["efficiency"], serde_json::Value::Null);
+        assert_eq!(
+            value["totals"],
+            json!({"total_tokens": 1500, "cost": 1.5, "efficiency": 1000.0})
+        );
+
+        let mut csv = Vec::new();
+        write_csv(&columns, &rows, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "Date,Total Tokens,Cost USD,Tokens/$\n\
+             2024-01-01,1500,1.500000,1000.00\n\
+             2024-01-02,0,0.000000,\n"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Shell completion scripts (`completions` command) and the dynamic
+//! candidates behind them (`__complete` command)
+//!
+//! clap_complete only knows the static CLI definition, so the generated zsh and
+//! fish scripts are extended to ask `claudelytics __complete sessions|projects`
+//! for session IDs and project names when completing `--session`/`--project`.
+
+use clap::Command;
+use clap_complete::Shell;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Sessions,
+    Projects,
+}
+
+/// Project directory names or session IDs, most recently active first
+pub fn completion_candidates(claude_dirs: &[PathBuf], kind: CompletionKind) -> Vec<String> {
+    let mut entries: Vec<(SystemTime, String)> = claude_dirs
+        .iter()
+        .flat_map(|dir| {
+            WalkDir::new(dir.join("projects"))
+                .min_depth(1)
+                .max_depth(2)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+        })
+        .filter_map(|entry| {
+            let name = match kind {
+                CompletionKind::Projects if entry.depth() == 1 && entry.file_type().is_dir() => {
+                    entry.file_name().to_string_lossy().to_string()
+                }
+                CompletionKind::Sessions
+                    if entry.depth() == 2
+                        && entry.path().extension().is_some_and(|ext| ext == "jsonl") =>
+                {
+                    entry.path().file_stem()?.to_string_lossy().to_string()
+                }
+                _ => return None,
+            };
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, name))
+        })
+        .collect();
+
+    entries.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    let mut seen = HashSet::new();
+    entries
+        .into_iter()
+        .map(|(_, name)| name)
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
+}
+
+/// Completion script for `shell`, with dynamic session/project completion for zsh and fish
+pub fn completion_script(shell: Shell, cmd: &mut Command) -> String {
+    let bin_name = cmd.get_name().to_string();
+    let mut buffer = Vec::new();
+    clap_complete::generate(shell, cmd, &bin_name, &mut buffer);
+    let script = String::from_utf8_lossy(&buffer).into_owned();
+
+    match shell {
+        Shell::Zsh => zsh_dynamic(&script, &bin_name),
+        Shell::Fish => fish_dynamic(script, &bin_name),
+        _ => script,
+    }
+}
+
+/// Point the `SESSION`/`PROJECT` value actions at helper functions that call
+/// `__complete`, defined ahead of the script's `compdef` footer
+fn zsh_dynamic(script: &str, bin_name: &str) -> String {
+    let fn_prefix = format!("_{}", bin_name.replace('-', "_"));
+    let mut helpers = String::new();
+    for kind in ["sessions", "projects"] {
+        helpers.push_str(&format!(
+            "{fn_prefix}_{kind}() {{\n    local -a candidates\n    candidates=(${{(f)\"$({bin_name} __complete {kind} 2>/dev/null)\"}})\n    compadd -a candidates\n}}\n\n"
+        ));
+    }
+
+    let mut output = String::with_capacity(script.len() + helpers.len());
+    let mut helpers_written = false;
+    for line in script.lines() {
+        if !helpers_written && line.starts_with("if [ \"$funcstack[1]\"") {
+            output.push_str(&helpers);
+            helpers_written = true;
+        }
+        let line = line
+            .replace(
+                ":SESSION:_default' \\",
+                &format!(":SESSION:{fn_prefix}_sessions' \\"),
+            )
+            .replace(
+                ":PROJECT:_default' \\",
+                &format!(":PROJECT:{fn_prefix}_projects' \\"),
+            );
+        output.push_str(&line);
+        output.push('\n');
+    }
+    if !helpers_written {
+        output.push('\n');
+        output.push_str(&helpers);
+    }
+    output
+}
+
+/// Add a candidate command to the `--session`/`--project` value completions
+fn fish_dynamic(script: String, bin_name: &str) -> String {
+    script
+        .lines()
+        .map(|line| {
+            let kind = if line.contains(" -l session ") {
+                "sessions"
+            } else if line.contains(" -l project ") {
+                "projects"
+            } else {
+                return format!("{line}\n");
+            };
+            if line.ends_with(" -r") {
+                format!("{line} -f -a '({bin_name} __complete {kind} 2>/dev/null)'\n")
+            } else {
+                format!("{line}\n")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_completion_candidates() {
+        let temp_dir = TempDir::new().unwrap();
+        for (project, session) in [("-home-a", "s1"), ("-home-b", "s2"), ("-home-b", "s1")] {
+            let dir = temp_dir.path().join("projects").join(project);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(format!("{session}.jsonl")), "").unwrap();
+        }
+        let dirs = vec![temp_dir.path().to_path_buf()];
+
+        let mut sessions = completion_candidates(&dirs, CompletionKind::Sessions);
+        sessions.sort();
+        assert_eq!(sessions, vec!["s1", "s2"]);
+
+        let mut projects = completion_candidates(&dirs, CompletionKind::Projects);
+        projects.sort();
+        assert_eq!(projects, vec!["-home-a", "-home-b"]);
+    }
+}
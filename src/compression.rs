@@ -0,0 +1,217 @@
+//! Transparent reading of compressed session logs
+//!
+//! Session files may be plain `.jsonl` or compressed to `.jsonl.gz` / `.jsonl.zst`
+//! to save disk. Readers open them through `open_session_file`, which picks the
+//! decoder from the extension. When both a plain and a compressed copy of a
+//! session exist, the plain file is the one still being written and wins.
+
+use anyhow::{Context, Result};
+use flate2::read::MultiGzDecoder;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Compression of a session file, from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Suffix after `.jsonl`
+    fn suffix(self) -> &'static str {
+        match self {
+            Compression::None => "",
+            Compression::Gzip => ".gz",
+            Compression::Zstd => ".zst",
+        }
+    }
+
+    /// Compression of `path`, or `None` if it is not a session file
+    pub fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        [Compression::None, Compression::Gzip, Compression::Zstd]
+            .into_iter()
+            .find(|c| name.ends_with(&format!(".jsonl{}", c.suffix())))
+    }
+}
+
+/// Whether `path` is a plain or compressed JSONL session file
+pub fn is_session_file(path: &Path) -> bool {
+    Compression::of(path).is_some()
+}
+
+/// File name without `.jsonl` and any compression suffix (the session ID)
+pub fn session_stem(file_name: &str) -> Option<&str> {
+    let compression = Compression::of(Path::new(file_name))?;
+    file_name
+        .strip_suffix(compression.suffix())?
+        .strip_suffix(".jsonl")
+}
+
+/// The `.jsonl` path a compressed file was made from
+pub fn uncompressed_path(path: &Path) -> Option<PathBuf> {
+    let compression = Compression::of(path)?;
+    if compression == Compression::None {
+        return None;
+    }
+    let name = path.file_name()?.to_str()?;
+    Some(path.with_file_name(name.strip_suffix(compression.suffix())?))
+}
+
+/// Drop compressed files whose plain `.jsonl` is also in `files`
+pub fn dedup_compressed(files: &mut Vec<PathBuf>) {
+    let plain: HashSet<PathBuf> = files
+        .iter()
+        .filter(|path| Compression::of(path) == Some(Compression::None))
+        .cloned()
+        .collect();
+    files.retain(|path| uncompressed_path(path).is_none_or(|original| !plain.contains(&original)));
+}
+
+/// Open a session file for reading, decompressing it if needed
+pub fn open_session_file(path: &Path) -> Result<Box<dyn Read + Send>> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open file: {}", path.display()))?;
+    Ok(match Compression::of(path).unwrap_or(Compression::None) {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(MultiGzDecoder::new(BufReader::new(file))),
+        Compression::Zstd => Box::new(
+            zstd::stream::read::Decoder::new(file)
+                .with_context(|| format!("Failed to read zstd file: {}", path.display()))?,
+        ),
+    })
+}
+
+/// Open a session file positioned at byte `offset` of its uncompressed content
+///
+/// Plain files seek there; compressed files are decompressed up to it.
+pub fn open_session_file_at(path: &Path, offset: u64) -> Result<Box<dyn Read + Send>> {
+    if matches!(
+        Compression::of(path),
+        Some(Compression::Gzip | Compression::Zstd)
+    ) {
+        let mut reader = open_session_file(path)?;
+        skip(&mut reader, offset)?;
+        return Ok(reader);
+    }
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open file: {}", path.display()))?;
+    file.seek(SeekFrom::Start(offset))?;
+    Ok(Box::new(file))
+}
+
+/// Read and discard `len` bytes
+pub fn skip(reader: &mut impl Read, len: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(len), &mut io::sink())?;
+    if skipped < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+/// Uncompressed size recorded in a compressed file's header or trailer, if any
+///
+/// gzip stores it modulo 2^32 in the last four bytes; zstd stores it in the
+/// frame header when the compressor knew it.
+pub fn uncompressed_size(path: &Path) -> Option<u64> {
+    let mut file = File::open(path).ok()?;
+    match Compression::of(path)? {
+        Compression::None => None,
+        Compression::Gzip => {
+            file.seek(SeekFrom::End(-4)).ok()?;
+            let mut trailer = [0u8; 4];
+            file.read_exact(&mut trailer).ok()?;
+            Some(u32::from_le_bytes(trailer) as u64)
+        }
+        Compression::Zstd => {
+            let mut header = [0u8; 18];
+            let read = file.read(&mut header).ok()?;
+            zstd::zstd_safe::get_frame_content_size(&header[..read])
+                .ok()
+                .flatten()
+        }
+    }
+}
+
+/// Whether a cached entry for the original `.jsonl` of size `size` still
+/// describes the compressed file `path` (the original is gone and the sizes match)
+pub fn replaces_original(path: &Path, size: u64) -> bool {
+    let Some(original) = uncompressed_path(path) else {
+        return false;
+    };
+    let expected = match Compression::of(path) {
+        Some(Compression::Gzip) => size & u32::MAX as u64,
+        _ => size,
+    };
+    !original.exists() && uncompressed_size(path) == Some(expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression as GzLevel;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    const CONTENT: &[u8] = b"{\"a\":1}\n{\"b\":2}\n";
+
+    #[test]
+    fn test_session_names() {
+        assert_eq!(session_stem("abc.jsonl"), Some("abc"));
+        assert_eq!(session_stem("abc.jsonl.gz"), Some("abc"));
+        assert_eq!(session_stem("abc.jsonl.zst"), Some("abc"));
+        assert_eq!(session_stem("abc.json"), None);
+        assert_eq!(
+            uncompressed_path(Path::new("/p/abc.jsonl.zst")),
+            Some(PathBuf::from("/p/abc.jsonl"))
+        );
+        assert_eq!(uncompressed_path(Path::new("/p/abc.jsonl")), None);
+
+        let mut files = vec![
+            PathBuf::from("/p/a.jsonl"),
+            PathBuf::from("/p/a.jsonl.gz"),
+            PathBuf::from("/p/b.jsonl.zst"),
+        ];
+        dedup_compressed(&mut files);
+        assert_eq!(
+            files,
+            vec![PathBuf::from("/p/a.jsonl"), PathBuf::from("/p/b.jsonl.zst")]
+        );
+    }
+
+    #[test]
+    fn test_reads_compressed_files() {
+        let dir = tempdir().unwrap();
+        let gz_path = dir.path().join("s.jsonl.gz");
+        let mut encoder = GzEncoder::new(File::create(&gz_path).unwrap(), GzLevel::default());
+        encoder.write_all(CONTENT).unwrap();
+        encoder.finish().unwrap();
+        let zst_path = dir.path().join("s.jsonl.zst");
+        std::fs::write(&zst_path, zstd::bulk::compress(CONTENT, 3).unwrap()).unwrap();
+
+        for path in [&gz_path, &zst_path] {
+            let mut text = String::new();
+            open_session_file(path)
+                .unwrap()
+                .read_to_string(&mut text)
+                .unwrap();
+            assert_eq!(text.as_bytes(), CONTENT);
+
+            let mut tail = String::new();
+            open_session_file_at(path, 8)
+                .unwrap()
+                .read_to_string(&mut tail)
+                .unwrap();
+            assert_eq!(tail, "{\"b\":2}\n");
+
+            assert_eq!(uncompressed_size(path), Some(CONTENT.len() as u64));
+            assert!(replaces_original(path, CONTENT.len() as u64));
+            assert!(!replaces_original(path, 1));
+        }
+    }
+}
@@ -1,20 +1,37 @@
-use anyhow::Result;
+//! Legacy (v1) configuration format and its migration to v2
+//!
+//! Version 1 stored a handful of flat keys (`claude_path`, `default_command`, ...)
+//! at the top of `config.yaml`; `config_v2::AppConfig` keeps the same settings in
+//! sections. v1 keys are still honored when the file is loaded, and the first
+//! run that finds them rewrites the file in the v2 layout after backing it up
+//! (or `claudelytics config --migrate` does so explicitly).
+
+use crate::config_v2::{self, AppConfig};
+use anyhow::{Context, Result};
+use chrono::Local;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-
-/// Configuration settings for Claudelytics
-///
-/// Stores user preferences that persist between runs, including:
-/// - Claude directory location
-/// - Default output format (enhanced/classic/json)
-/// - Default command to run
-/// - Export directory for CSV files
-/// - Date format preferences
+use std::path::{Path, PathBuf};
+
+/// Top-level keys of the v1 format
+pub const LEGACY_KEYS: [&str; 7] = [
+    "claude_path",
+    "claude_paths",
+    "default_output_format",
+    "default_command",
+    "export_directory",
+    "date_format",
+    "watch_interval_seconds",
+];
+
+/// Settings of a v1 config file
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Config {
+#[serde(default)]
+pub struct LegacyConfig {
     /// Custom path to Claude directory (default: ~/.claude)
     pub claude_path: Option<PathBuf>,
+    /// Several Claude directories to merge (e.g. synced from other machines)
+    pub claude_paths: Vec<PathBuf>,
     /// Default output format for reports
     pub default_output_format: OutputFormat,
     /// Default command to execute when none specified
@@ -28,33 +45,26 @@ pub struct Config {
 /// Output format options for reports
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum OutputFormat {
-    /// Enhanced format with visual cards and summaries (default)
     Enhanced,
-    /// Classic ASCII table format
     Table,
-    /// JSON format for scripting and automation
     Json,
 }
 
 /// Default command to execute when none specified
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum DefaultCommand {
-    /// Show daily usage report (default)
     Daily,
-    /// Show session-based usage report
     Session,
-    /// Launch interactive session selector
     Interactive,
-    /// Launch terminal user interface
     Tui,
-    /// Launch advanced analytics TUI
     AdvancedTui,
 }
 
-impl Default for Config {
+impl Default for LegacyConfig {
     fn default() -> Self {
         Self {
             claude_path: None,
+            claude_paths: Vec::new(),
             default_output_format: OutputFormat::Enhanced,
             default_command: DefaultCommand::Daily,
             export_directory: None,
@@ -63,57 +73,123 @@ impl Default for Config {
     }
 }
 
-impl Config {
-    pub fn load() -> Result<Self> {
-        let config_path = Self::config_path()?;
-
-        if config_path.exists() {
-            let content = fs::read_to_string(&config_path)?;
-            let config: Config = serde_yaml::from_str(&content)?;
-            Ok(config)
-        } else {
-            Ok(Config::default())
+impl LegacyConfig {
+    /// Copy the v1 settings into `config` where it still has the default value,
+    /// so settings made in the v2 sections win
+    pub fn apply_to(&self, config: &mut AppConfig) {
+        let defaults = AppConfig::default();
+        if config.core.claude_path.is_none() {
+            config.core.claude_path = self.claude_path.clone();
+        }
+        if config.core.claude_paths.is_empty() {
+            config.core.claude_paths = self.claude_paths.clone();
+        }
+        if config.display.output_format.to_string() == defaults.display.output_format.to_string() {
+            config.display.output_format = match self.default_output_format {
+                OutputFormat::Enhanced => config_v2::OutputFormat::Enhanced,
+                OutputFormat::Table => config_v2::OutputFormat::Table,
+                OutputFormat::Json => config_v2::OutputFormat::Json,
+            };
+        }
+        if config.core.default_command.to_string() == defaults.core.default_command.to_string() {
+            config.core.default_command = match self.default_command {
+                DefaultCommand::Daily => config_v2::DefaultCommand::Daily,
+                DefaultCommand::Session => config_v2::DefaultCommand::Session,
+                DefaultCommand::Interactive => config_v2::DefaultCommand::Interactive,
+                DefaultCommand::Tui => config_v2::DefaultCommand::Tui,
+                DefaultCommand::AdvancedTui => config_v2::DefaultCommand::AdvancedTui,
+            };
+        }
+        if config.export.directory.is_none() {
+            config.export.directory = self.export_directory.clone();
+        }
+        if config.display.date_format == defaults.display.date_format {
+            config.display.date_format = self.date_format.clone();
         }
     }
+}
 
-    pub fn save(&self) -> Result<()> {
-        let config_path = Self::config_path()?;
-
-        if let Some(parent) = config_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
+/// Whether a parsed config document has any v1 keys
+pub fn has_legacy_keys(document: &serde_yaml::Value) -> bool {
+    LEGACY_KEYS.iter().any(|key| document.get(key).is_some())
+}
 
-        let content = serde_yaml::to_string(self)?;
-        fs::write(&config_path, content)?;
-        Ok(())
+/// Rewrite the config file without v1 keys, their settings moved to the v2
+/// sections. Returns the backup of the original file, or `None` when there
+/// was nothing to migrate.
+pub fn migrate() -> Result<Option<PathBuf>> {
+    let path = AppConfig::config_path()?;
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let mut document: serde_yaml::Value = serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+    if !has_legacy_keys(&document) {
+        return Ok(None);
     }
 
-    pub fn config_path() -> Result<PathBuf> {
-        let home = std::env::var("HOME")
-            .map_err(|_| anyhow::anyhow!("HOME environment variable not set"))?;
-        Ok(PathBuf::from(home)
-            .join(".config")
-            .join("claudelytics")
-            .join("config.yaml"))
-    }
+    // Parsing applies the v1 settings
+    let config = AppConfig::parse(&content).map_err(|message| {
+        anyhow::anyhow!("Invalid config file {}: {}", path.display(), message)
+    })?;
+    config.validate()?;
 
-    pub fn get_claude_path(&self) -> Result<PathBuf> {
-        if let Some(path) = &self.claude_path {
-            Ok(path.clone())
-        } else {
-            let home = std::env::var("HOME")
-                .map_err(|_| anyhow::anyhow!("HOME environment variable not set"))?;
-            Ok(PathBuf::from(home).join(".claude"))
+    let backup = backup_path(&path);
+    fs::copy(&path, &backup)
+        .with_context(|| format!("Failed to back up config file to {}", backup.display()))?;
+    if let Some(mapping) = document.as_mapping_mut() {
+        for key in LEGACY_KEYS {
+            mapping.remove(key);
         }
     }
+    fs::write(&path, serde_yaml::to_string(&document)?)
+        .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+    config.save()?;
+    Ok(Some(backup))
+}
+
+/// `config.yaml.v1.bak`, or a timestamped name when that already exists
+fn backup_path(path: &Path) -> PathBuf {
+    let backup = path.with_extension("yaml.v1.bak");
+    if !backup.exists() {
+        return backup;
+    }
+    path.with_extension(format!(
+        "yaml.v1.{}.bak",
+        Local::now().format("%Y%m%d%H%M%S")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_settings_are_applied() {
+        let config = AppConfig::parse(
+            "claude_path: /data/claude\ndefault_command: Session\ndefault_output_format: Json\ndate_format: \"%d/%m/%Y\"\nwatch_interval_seconds: 5\nbudget:\n  daily_limit: 20.0\n",
+        )
+        .unwrap();
+        assert_eq!(config.core.claude_path, Some(PathBuf::from("/data/claude")));
+        assert_eq!(config.core.default_command.to_string(), "session");
+        assert_eq!(config.display.output_format.to_string(), "json");
+        assert_eq!(config.display.date_format, "%d/%m/%Y");
+        assert_eq!(config.budget.daily_limit, Some(20.0));
 
-    pub fn set_claude_path(&mut self, path: PathBuf) {
-        self.claude_path = Some(path);
+        // Values set in the v2 sections win over v1 keys
+        let v2_wins = AppConfig::parse(
+            "claude_path: /old\ncore:\n  claude_path: /new\n  default_command: Daily\n  config_version: 0.3.0\n",
+        )
+        .unwrap();
+        assert_eq!(v2_wins.core.claude_path, Some(PathBuf::from("/new")));
     }
 
-    pub fn get_export_directory(&self) -> PathBuf {
-        self.export_directory
-            .clone()
-            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")))
+    #[test]
+    fn test_backup_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.yaml");
+        assert_eq!(backup_path(&path), dir.path().join("config.yaml.v1.bak"));
+        fs::write(dir.path().join("config.yaml.v1.bak"), "").unwrap();
+        assert_ne!(backup_path(&path), dir.path().join("config.yaml.v1.bak"));
     }
 }
@@ -1,6 +1,10 @@
+use crate::billing_blocks::{BILLING_BLOCK_HOURS, BillingBlockConfig, BlockAnchor};
+use crate::clipboard::ClipboardMethod;
+use crate::config::{LEGACY_KEYS, LegacyConfig};
 use crate::error::{ClaudelyticsError, Result};
+use crate::quota::{Plan, QuotaLimits};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fmt;
 use std::fs;
@@ -8,6 +12,7 @@ use std::path::PathBuf;
 
 /// 完全な設定構造体 - アプリケーション全体の設定を管理
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
 pub struct AppConfig {
     /// 基本設定
     pub core: CoreConfig,
@@ -19,6 +24,37 @@ pub struct AppConfig {
     pub pricing: PricingConfig,
     /// エクスポート設定
     pub export: ExportConfig,
+    /// 予算設定
+    pub budget: BudgetConfig,
+    /// MCP サーバー設定
+    pub mcp: McpConfig,
+    /// アラートフック設定
+    pub alerts: AlertsConfig,
+    /// 課金ブロック設定
+    pub billing_blocks: BillingBlocksConfig,
+    /// サブスクリプションのクォータ設定
+    pub quota: QuotaConfig,
+    /// セッションのタグ設定
+    pub tags: TagsConfig,
+    /// バーンレート設定
+    pub burn_rate: BurnRateConfig,
+    /// 使用量アーカイブ設定
+    pub archive: ArchiveConfig,
+    /// 使用量の目標設定
+    pub goals: GoalsConfig,
+    /// 会話のマスキング設定
+    pub redaction: RedactionConfig,
+    /// チケット ID によるコスト配分の設定
+    pub tickets: TicketsConfig,
+    /// 表示通貨の設定
+    pub currency: CurrencyConfig,
+    /// 時間帯によるコストセンターの設定
+    pub schedule: ScheduleConfig,
+    /// TUI のクリップボード設定
+    pub clipboard: ClipboardConfig,
+    /// コマンドラインフラグのデフォルト値
+    #[serde(alias = "default_flags")]
+    pub defaults: DefaultFlagsConfig,
     /// プロファイル設定
     pub profiles: HashMap<String, ProfileConfig>,
 }
@@ -28,6 +64,9 @@ pub struct AppConfig {
 pub struct CoreConfig {
     /// Claude ディレクトリのパス
     pub claude_path: Option<PathBuf>,
+    /// まとめて集計する複数の Claude ディレクトリ（他のマシンから同期したものなど）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claude_paths: Vec<PathBuf>,
     /// デフォルトのコマンド
     pub default_command: DefaultCommand,
     /// 設定ファイルのバージョン
@@ -88,6 +127,259 @@ pub struct ExportConfig {
     pub compression_enabled: bool,
 }
 
+/// 予算設定（realtime / live / blocks コマンドのデフォルト上限）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// 1日の予算上限（USD）
+    pub daily_limit: Option<f64>,
+    /// 1ヶ月の予算上限（USD）
+    pub monthly_limit: Option<f64>,
+    /// 1年の予算上限（USD）
+    pub yearly_limit: Option<f64>,
+    /// アラートを出す予算消化率（0.0-1.0）
+    pub alert_threshold: f64,
+    /// 請求サイクルの開始日（1-31、月末を超える場合はその月の末日）
+    pub billing_cycle_start_day: u32,
+}
+
+/// アラートフック設定（realtime / live コマンドで閾値を超えたときに実行）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct AlertsConfig {
+    /// アラートを JSON で POST する Webhook URL
+    pub webhook_url: Option<String>,
+    /// アラート時に実行するシェルコマンド（CLAUDELYTICS_ALERT_* 環境変数付き）
+    pub command: Option<String>,
+    /// 高バーンレートとみなすコスト（USD/時間）
+    pub burn_rate_threshold: f64,
+    /// 同じ種類のアラートを再送するまでの間隔（分）
+    pub cooldown_minutes: u64,
+    /// 異常検知の閾値（直近の平均から標準偏差の何倍を超えたら異常とみなすか）
+    pub anomaly_threshold: f64,
+    /// 同時にトークンを消費しているセッション数の上限（超えた期間を realtime で警告）
+    pub max_concurrent_sessions: usize,
+}
+
+/// 課金ブロック設定（billing-blocks コマンドのデフォルト）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct BillingBlocksConfig {
+    /// ブロックの起点（fixed: UTC 0時基準の固定枠 / rolling: 最初の利用時刻から）
+    pub anchor: BlockAnchor,
+    /// ブロックの長さ（時間）
+    pub duration_hours: i64,
+}
+
+/// サブスクリプションのクォータ設定（quota コマンド）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct QuotaConfig {
+    /// 契約プラン（pro / max5 / max20）
+    pub plan: Plan,
+    /// 5時間ブロックあたりのトークン上限（入力+出力、未指定ならプランの推定値）
+    pub token_limit: Option<u64>,
+    /// 5時間ブロックあたりのメッセージ上限
+    pub message_limit: Option<u64>,
+    /// 5時間ブロックあたりのコスト上限（USD）
+    pub cost_limit: Option<f64>,
+}
+
+/// バーンレート設定（live / blocks --live で使用）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct BurnRateConfig {
+    /// バーンレートを計算する直近の期間（15m / 1h / 6h / 1d 形式）
+    pub windows: Vec<String>,
+}
+
+/// 使用量アーカイブ設定（snapshot コマンド）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    /// 実行のたびにスナップショットをアーカイブへ追記する
+    pub auto_snapshot: bool,
+    /// レポートにアーカイブのみに残る使用量を含める（--with-archive と同じ）
+    pub merge: bool,
+}
+
+/// 使用量の目標設定（goals コマンドと analytics-tui の Goals タブ）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct GoalsConfig {
+    /// 1日のコストの目標上限（USD）
+    pub max_daily_cost: Option<f64>,
+    /// 1日のトークン数の目標上限（キャッシュを含む合計）
+    pub max_daily_tokens: Option<u64>,
+    /// 直近7日間のコストの目標上限（USD）
+    pub max_weekly_cost: Option<f64>,
+    /// 直近7日間のトークン数の目標上限（キャッシュを含む合計）
+    pub max_weekly_tokens: Option<u64>,
+}
+
+/// 会話の表示・エクスポートのマスキング設定（--redact で使用）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// 組み込みの検出器に加えてマスクする正規表現
+    pub patterns: Vec<RedactionPattern>,
+}
+
+/// マスクする正規表現（一致部分は [REDACTED:名前] に置き換える）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RedactionPattern {
+    /// マスク後の表示とカウントに使う名前
+    pub name: String,
+    /// 正規表現（regex クレートの構文）
+    pub pattern: String,
+}
+
+/// チケット ID の抽出設定（tickets コマンドで使用）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TicketsConfig {
+    /// チケット ID の正規表現（未設定なら JIRA 形式 `ABC-123`）。キャプチャグループがあれば最初のグループを ID とする
+    pub patterns: Vec<String>,
+    /// チケットとして扱わないプレフィックス（組み込みの UTF・SHA などに追加）
+    pub ignore: Vec<String>,
+}
+
+/// 表示通貨の設定（--currency で上書き）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct CurrencyConfig {
+    /// デフォルトの表示通貨（ISO 4217 コード、未設定なら USD）
+    pub default: Option<String>,
+    /// 固定の為替レート（通貨コード → 1 USD あたりの額）。設定された通貨は取得しない
+    pub rates: BTreeMap<String, f64>,
+    /// 為替レートの取得元 URL（未設定なら組み込みの取得元）
+    pub rate_source: Option<String>,
+}
+
+/// TUI のクリップボード設定（エクスポートとコピーで使用）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    /// コピー方法（auto・system・osc52・file）。auto はシステム、OSC 52、ファイルの順に試す
+    pub method: ClipboardMethod,
+    /// file 方式で書き出すディレクトリ（未設定なら一時ディレクトリ）
+    pub file_dir: Option<PathBuf>,
+}
+
+/// 時間帯によるコストセンターの設定（--split-by schedule で使用）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    /// 時間帯のルール（上から順に評価し、最初に一致したルールのバケットに配分）
+    pub rules: Vec<ScheduleRule>,
+    /// どのルールにも一致しない使用量のバケット
+    pub default_bucket: String,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            rules: vec![ScheduleRule {
+                bucket: "work".to_string(),
+                days: ["mon", "tue", "wed", "thu", "fri"]
+                    .map(String::from)
+                    .to_vec(),
+                start: "09:00".to_string(),
+                end: "18:00".to_string(),
+            }],
+            default_bucket: "personal".to_string(),
+        }
+    }
+}
+
+/// 曜日と時間帯（ローカル時刻）をバケットに対応付けるルール
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScheduleRule {
+    /// バケット名（例: work）
+    pub bucket: String,
+    /// 対象の曜日（mon〜sun、空なら毎日）
+    #[serde(default)]
+    pub days: Vec<String>,
+    /// 開始時刻（HH:MM）
+    pub start: String,
+    /// 終了時刻（HH:MM、開始時刻以前なら翌日まで）
+    pub end: String,
+}
+
+/// コマンドラインフラグのデフォルト値（コマンドラインやプロファイルで指定されていない場合に適用）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct DefaultFlagsConfig {
+    /// --json
+    pub json: Option<bool>,
+    /// --classic
+    pub classic: Option<bool>,
+    /// --compact
+    pub compact: Option<bool>,
+    /// --responsive
+    pub responsive: Option<bool>,
+    /// --model-filter
+    pub model_filter: Option<String>,
+    /// --currency
+    pub currency: Option<String>,
+    /// --cost-mode（auto・calculate・display・batch）
+    pub cost_mode: Option<String>,
+}
+
+impl DefaultFlagsConfig {
+    /// 設定されているフラグを `--flag` / `--flag value` の形式で列挙
+    pub fn describe(&self) -> Vec<String> {
+        let switches = [
+            ("json", self.json),
+            ("classic", self.classic),
+            ("compact", self.compact),
+            ("responsive", self.responsive),
+        ];
+        let values = [
+            ("model-filter", &self.model_filter),
+            ("currency", &self.currency),
+            ("cost-mode", &self.cost_mode),
+        ];
+        switches
+            .into_iter()
+            .filter(|(_, on)| *on == Some(true))
+            .map(|(flag, _)| format!("--{}", flag))
+            .chain(
+                values
+                    .into_iter()
+                    .filter_map(|(flag, value)| Some(format!("--{} {}", flag, value.as_ref()?))),
+            )
+            .collect()
+    }
+}
+
+/// セッションのタグ設定（--group-by tag で使用）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TagsConfig {
+    /// プロジェクトパスのグロブとタグの対応（上から順にすべて適用）
+    pub projects: Vec<ProjectTagRule>,
+    /// セッションごとの手動タグ（project-dir/session-id → タグ）
+    pub sessions: BTreeMap<String, Vec<String>>,
+}
+
+/// プロジェクトパスのグロブ（例: ~/work/*）に付けるタグ
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProjectTagRule {
+    /// プロジェクトパスのグロブ（* と ? が使える）
+    pub pattern: String,
+    /// 一致したセッションに付けるタグ
+    pub tags: Vec<String>,
+}
+
+/// MCP サーバー設定
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct McpConfig {
+    /// HTTP トランスポートのバインドアドレス
+    pub http_bind_address: String,
+}
+
 /// プロファイル設定（異なる環境や用途に応じた設定セット）
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProfileConfig {
@@ -99,13 +391,45 @@ pub struct ProfileConfig {
     pub overrides: ConfigOverrides,
 }
 
-/// 設定のオーバーライド
+/// `section.key` 形式のキーが YAML 内で定義されている行と列（1 始まり）
+fn key_location(content: &str, key: &str) -> Option<(usize, usize)> {
+    let mut lines = content.lines().enumerate();
+    let mut parent_indent: Option<usize> = None;
+    let mut found = None;
+    // シーケンスの添字はキーとして現れないので飛ばす
+    for part in key.split('.').filter(|part| part.parse::<usize>().is_err()) {
+        let (index, indent) = lines.by_ref().find_map(|(index, line)| {
+            let trimmed = line.trim_start_matches([' ', '-']);
+            let indent = line.len() - trimmed.len();
+            let matches = trimmed
+                .strip_prefix(part)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'));
+            (matches && parent_indent.is_none_or(|parent| indent > parent))
+                .then_some((index, indent))
+        })?;
+        parent_indent = Some(indent);
+        found = Some((index + 1, indent + 1));
+    }
+    found
+}
+
+/// 設定のオーバーライド（コマンドラインで指定した値が優先される）
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct ConfigOverrides {
     pub claude_path: Option<PathBuf>,
     pub output_format: Option<OutputFormat>,
     pub parallel_workers: Option<usize>,
     pub pricing_strategy: Option<PricingStrategy>,
+    /// 1日の予算上限（USD）
+    pub daily_limit: Option<f64>,
+    /// 1か月の予算上限（USD）
+    pub monthly_limit: Option<f64>,
+    /// タイムゾーン（TZ 環境変数の形式、例: Asia/Tokyo）
+    pub timezone: Option<String>,
+    /// デフォルトのモデルフィルター（--model-filter）
+    pub model_filter: Option<String>,
+    /// デフォルトの開始日（--since、YYYYMMDD）
+    pub since: Option<String>,
 }
 
 // 列挙型の定義
@@ -158,6 +482,7 @@ impl Default for CoreConfig {
     fn default() -> Self {
         Self {
             claude_path: None,
+            claude_paths: Vec::new(),
             default_command: DefaultCommand::Daily,
             config_version: "0.3.0".to_string(),
         }
@@ -209,6 +534,101 @@ impl Default for ExportConfig {
     }
 }
 
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            daily_limit: None,
+            monthly_limit: None,
+            yearly_limit: None,
+            alert_threshold: 0.8, // Alert at 80% of budget
+            billing_cycle_start_day: 1,
+        }
+    }
+}
+
+impl BudgetConfig {
+    /// いずれかの上限が設定されているか
+    pub fn has_limits(&self) -> bool {
+        self.daily_limit.is_some() || self.monthly_limit.is_some() || self.yearly_limit.is_some()
+    }
+}
+
+impl GoalsConfig {
+    /// いずれかの目標が設定されているか
+    pub fn has_goals(&self) -> bool {
+        self.max_daily_cost.is_some()
+            || self.max_daily_tokens.is_some()
+            || self.max_weekly_cost.is_some()
+            || self.max_weekly_tokens.is_some()
+    }
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            command: None,
+            burn_rate_threshold: 10.0,
+            cooldown_minutes: 30,
+            anomaly_threshold: 3.0,
+            max_concurrent_sessions: 3,
+        }
+    }
+}
+
+impl AlertsConfig {
+    /// Webhook またはコマンドが設定されているか
+    pub fn has_hooks(&self) -> bool {
+        self.webhook_url.is_some() || self.command.is_some()
+    }
+}
+
+impl Default for BurnRateConfig {
+    fn default() -> Self {
+        Self {
+            windows: vec!["15m".to_string(), "1h".to_string(), "6h".to_string()],
+        }
+    }
+}
+
+impl Default for BillingBlocksConfig {
+    fn default() -> Self {
+        Self {
+            anchor: BlockAnchor::Fixed,
+            duration_hours: BILLING_BLOCK_HOURS,
+        }
+    }
+}
+
+impl From<&BillingBlocksConfig> for BillingBlockConfig {
+    fn from(config: &BillingBlocksConfig) -> Self {
+        Self {
+            anchor: config.anchor,
+            duration_hours: config.duration_hours,
+        }
+    }
+}
+
+impl QuotaConfig {
+    /// プランの推定値に設定ファイルの上書きを適用した上限
+    pub fn limits_for(&self, plan: Plan) -> QuotaLimits {
+        let defaults = plan.limits();
+        QuotaLimits {
+            tokens: self.token_limit.unwrap_or(defaults.tokens),
+            messages: self.message_limit.unwrap_or(defaults.messages),
+            cost: self.cost_limit.unwrap_or(defaults.cost),
+        }
+    }
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self {
+            http_bind_address: "127.0.0.1".to_string(),
+        }
+    }
+}
+
 impl Default for NumberFormat {
     fn default() -> Self {
         Self {
@@ -252,7 +672,7 @@ impl fmt::Display for DefaultCommand {
 impl AppConfig {
     /// 設定をロード（環境変数、設定ファイル、デフォルト値の順で優先）
     pub fn load() -> Result<Self> {
-        let mut config = Self::load_from_file().unwrap_or_default();
+        let mut config = Self::load_from_file()?;
         config.apply_environment_variables()?;
         config.validate()?;
         Ok(config)
@@ -274,8 +694,12 @@ impl AppConfig {
                 ClaudelyticsError::config_error(&format!("Failed to read config file: {}", e))
             })?;
 
-            let config: AppConfig = serde_yaml::from_str(&content).map_err(|e| {
-                ClaudelyticsError::config_error(&format!("Failed to parse config file: {}", e))
+            let config = Self::parse(&content).map_err(|message| {
+                ClaudelyticsError::config_error(&format!(
+                    "Invalid config file {}: {}\nFix it with `claudelytics config --edit`",
+                    config_path.display(),
+                    message
+                ))
             })?;
 
             Ok(config)
@@ -284,6 +708,34 @@ impl AppConfig {
         }
     }
 
+    /// YAML を型に沿って解析する。型の誤りや未知のキーは行・列付きのメッセージで返す
+    pub fn parse(content: &str) -> std::result::Result<Self, String> {
+        let mut unknown = Vec::new();
+        let mut config: AppConfig =
+            serde_ignored::deserialize(serde_yaml::Deserializer::from_str(content), |path| {
+                unknown.push(path.to_string())
+            })
+            .map_err(|e| e.to_string())?;
+
+        // v1 形式のキーは未知扱いせず、移行されるまで v2 の設定として読み替える
+        let (legacy, unknown): (Vec<_>, Vec<_>) = unknown
+            .into_iter()
+            .partition(|key| LEGACY_KEYS.contains(&key.as_str()));
+        if let Some(key) = unknown.first() {
+            return Err(match key_location(content, key) {
+                Some((line, column)) => {
+                    format!("unknown key `{}` at line {} column {}", key, line, column)
+                }
+                None => format!("unknown key `{}`", key),
+            });
+        }
+        if !legacy.is_empty() {
+            let legacy: LegacyConfig = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+            legacy.apply_to(&mut config);
+        }
+        Ok(config)
+    }
+
     /// 環境変数を適用
     fn apply_environment_variables(&mut self) -> Result<()> {
         if let Ok(path) = env::var("CLAUDELYTICS_CLAUDE_PATH") {
@@ -339,6 +791,14 @@ impl AppConfig {
             if let Some(pricing_strategy) = &overrides.pricing_strategy {
                 self.pricing.calculation_strategy = pricing_strategy.clone();
             }
+
+            if overrides.daily_limit.is_some() {
+                self.budget.daily_limit = overrides.daily_limit;
+            }
+
+            if overrides.monthly_limit.is_some() {
+                self.budget.monthly_limit = overrides.monthly_limit;
+            }
         } else {
             return Err(ClaudelyticsError::config_error(&format!(
                 "Profile '{}' not found",
@@ -350,7 +810,7 @@ impl AppConfig {
     }
 
     /// 設定を検証
-    fn validate(&self) -> Result<()> {
+    pub fn validate(&self) -> Result<()> {
         if self.performance.parallel_workers > 1000 {
             return Err(ClaudelyticsError::validation_error(
                 "parallel_workers",
@@ -367,6 +827,95 @@ impl AppConfig {
             ));
         }
 
+        if !(0.0..=1.0).contains(&self.budget.alert_threshold) {
+            return Err(ClaudelyticsError::validation_error(
+                "budget.alert_threshold",
+                "Alert threshold must be between 0.0 and 1.0",
+            ));
+        }
+
+        if !(1..=31).contains(&self.budget.billing_cycle_start_day) {
+            return Err(ClaudelyticsError::validation_error(
+                "budget.billing_cycle_start_day",
+                "Billing cycle start day must be between 1 and 31",
+            ));
+        }
+
+        for (field, limit) in [
+            ("budget.daily_limit", self.budget.daily_limit),
+            ("budget.monthly_limit", self.budget.monthly_limit),
+            ("budget.yearly_limit", self.budget.yearly_limit),
+        ] {
+            if limit.is_some_and(|l| l <= 0.0) {
+                return Err(ClaudelyticsError::validation_error(
+                    field,
+                    "Budget limits must be positive",
+                ));
+            }
+        }
+
+        for (field, goal) in [
+            ("goals.max_daily_cost", self.goals.max_daily_cost),
+            ("goals.max_weekly_cost", self.goals.max_weekly_cost),
+        ] {
+            if goal.is_some_and(|g| g <= 0.0) {
+                return Err(ClaudelyticsError::validation_error(
+                    field,
+                    "Goals must be positive",
+                ));
+            }
+        }
+
+        if self.currency.rates.values().any(|rate| *rate <= 0.0) {
+            return Err(ClaudelyticsError::validation_error(
+                "currency.rates",
+                "Exchange rates must be positive",
+            ));
+        }
+
+        for (field, goal) in [
+            ("goals.max_daily_tokens", self.goals.max_daily_tokens),
+            ("goals.max_weekly_tokens", self.goals.max_weekly_tokens),
+        ] {
+            if goal == Some(0) {
+                return Err(ClaudelyticsError::validation_error(
+                    field,
+                    "Goals must be positive",
+                ));
+            }
+        }
+
+        if self.alerts.burn_rate_threshold <= 0.0 {
+            return Err(ClaudelyticsError::validation_error(
+                "alerts.burn_rate_threshold",
+                "Burn rate threshold must be positive",
+            ));
+        }
+
+        if self.alerts.max_concurrent_sessions == 0 {
+            return Err(ClaudelyticsError::validation_error(
+                "alerts.max_concurrent_sessions",
+                "Concurrent session limit must be at least 1",
+            ));
+        }
+
+        if !(1..=24).contains(&self.billing_blocks.duration_hours) {
+            return Err(ClaudelyticsError::validation_error(
+                "billing_blocks.duration_hours",
+                "Block duration must be between 1 and 24 hours",
+            ));
+        }
+
+        if let Some(url) = &self.alerts.webhook_url
+            && !url.starts_with("http://")
+            && !url.starts_with("https://")
+        {
+            return Err(ClaudelyticsError::validation_error(
+                "alerts.webhook_url",
+                "Webhook URL must start with http:// or https://",
+            ));
+        }
+
         Ok(())
     }
 
@@ -383,7 +932,22 @@ impl AppConfig {
             })?;
         }
 
-        let content = serde_yaml::to_string(self).map_err(|e| {
+        // Keep keys we don't own, such as v1 keys that are not migrated yet
+        let mut document = fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())
+            .filter(|value| value.is_mapping())
+            .unwrap_or_else(|| serde_yaml::Value::Mapping(Default::default()));
+        let value = serde_yaml::to_value(self).map_err(|e| {
+            ClaudelyticsError::config_error(&format!("Failed to serialize config: {}", e))
+        })?;
+        if let (Some(existing), serde_yaml::Value::Mapping(ours)) =
+            (document.as_mapping_mut(), value)
+        {
+            existing.extend(ours);
+        }
+
+        let content = serde_yaml::to_string(&document).map_err(|e| {
             ClaudelyticsError::config_error(&format!("Failed to serialize config: {}", e))
         })?;
 
@@ -446,6 +1010,62 @@ impl AppConfig {
     pub fn list_profiles(&self) -> Vec<&String> {
         self.profiles.keys().collect()
     }
+
+    /// `section.key` 形式のキーに値を設定する（値は YAML として解釈、`null` で未設定に戻す）
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let path: Vec<&str> = key.split('.').collect();
+        if path.iter().any(|part| part.is_empty()) {
+            return Err(ClaudelyticsError::config_error(&format!(
+                "Invalid config key '{}': expected e.g. defaults.responsive",
+                key
+            )));
+        }
+        let new_value: serde_yaml::Value = serde_yaml::from_str(value).map_err(|e| {
+            ClaudelyticsError::validation_error(key, &format!("Invalid value: {}", e))
+        })?;
+
+        let mut document = serde_yaml::to_value(&*self).map_err(|e| {
+            ClaudelyticsError::config_error(&format!("Failed to serialize config: {}", e))
+        })?;
+        let unknown_key =
+            || ClaudelyticsError::config_error(&format!("Unknown config key '{}'", key));
+        let (last, parents) = path.split_last().ok_or_else(unknown_key)?;
+        let mut node = &mut document;
+        for part in parents {
+            node = node.get_mut(*part).ok_or_else(unknown_key)?;
+        }
+        node.as_mapping_mut()
+            .ok_or_else(unknown_key)?
+            .insert(serde_yaml::Value::from(*last), new_value.clone());
+
+        let updated: AppConfig = serde_yaml::from_value(document).map_err(|e| {
+            ClaudelyticsError::validation_error(key, &format!("Invalid value: {}", e))
+        })?;
+        // Keys that are not part of the config are silently dropped on deserialization
+        let check = serde_yaml::to_value(&updated).map_err(|e| {
+            ClaudelyticsError::config_error(&format!("Failed to serialize config: {}", e))
+        })?;
+        let kept = path
+            .iter()
+            .try_fold(&check, |node, part| node.get(*part))
+            .is_some();
+        if !kept && !new_value.is_null() {
+            return Err(unknown_key());
+        }
+        updated.validate()?;
+        *self = updated;
+        Ok(())
+    }
+
+    /// 名前でプロファイルを取得
+    pub fn profile(&self, name: &str) -> Result<&ProfileConfig> {
+        self.profiles.get(name).ok_or_else(|| {
+            ClaudelyticsError::config_error(&format!(
+                "Profile '{}' not found (see `claudelytics config --list-profiles`)",
+                name
+            ))
+        })
+    }
 }
 
 #[cfg(test)]
@@ -490,4 +1110,125 @@ mod tests {
 
         matches!(config.display.output_format, OutputFormat::Json);
     }
+
+    #[test]
+    fn test_profile_budget_override() {
+        let mut config = AppConfig::default();
+        config.budget.daily_limit = Some(10.0);
+        config.budget.monthly_limit = Some(200.0);
+        config.add_profile(ProfileConfig {
+            name: "work".to_string(),
+            description: String::new(),
+            overrides: ConfigOverrides {
+                daily_limit: Some(50.0),
+                ..Default::default()
+            },
+        });
+
+        config.apply_profile("work").unwrap();
+        assert_eq!(config.budget.daily_limit, Some(50.0));
+        assert_eq!(config.budget.monthly_limit, Some(200.0));
+        assert!(config.profile("personal").is_err());
+        assert!(config.apply_profile("personal").is_err());
+    }
+
+    #[test]
+    fn test_budget_validation() {
+        let mut config = AppConfig::default();
+        assert!(!config.budget.has_limits());
+        assert!(config.validate().is_ok());
+
+        config.budget.daily_limit = Some(50.0);
+        assert!(config.budget.has_limits());
+        assert!(config.validate().is_ok());
+
+        config.budget.alert_threshold = 1.5;
+        assert!(config.validate().is_err());
+
+        config.budget.alert_threshold = 0.8;
+        config.budget.monthly_limit = Some(-1.0);
+        assert!(config.validate().is_err());
+
+        config.budget.monthly_limit = Some(100.0);
+        config.budget.billing_cycle_start_day = 12;
+        assert!(config.validate().is_ok());
+        config.budget.billing_cycle_start_day = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_set_by_key() {
+        let mut config = AppConfig::default();
+        config.set("defaults.responsive", "true").unwrap();
+        config
+            .set("default_flags.model_filter", "opus")
+            .unwrap_err();
+        config.set("defaults.model_filter", "opus").unwrap();
+        config.set("budget.daily_limit", "20").unwrap();
+        config.set("currency.rates.EUR", "0.9").unwrap();
+        assert_eq!(config.defaults.responsive, Some(true));
+        assert_eq!(config.defaults.model_filter.as_deref(), Some("opus"));
+        assert_eq!(config.budget.daily_limit, Some(20.0));
+        assert_eq!(config.currency.rates["EUR"], 0.9);
+        assert_eq!(
+            config.defaults.describe(),
+            ["--responsive", "--model-filter opus"]
+        );
+
+        config.set("budget.daily_limit", "null").unwrap();
+        assert_eq!(config.budget.daily_limit, None);
+
+        // Unknown keys, wrong types, and invalid values leave the config unchanged
+        assert!(config.set("defaults.verbose", "true").is_err());
+        assert!(config.set("nosuch.key", "1").is_err());
+        assert!(config.set("defaults.responsive", "sometimes").is_err());
+        assert!(config.set("budget.alert_threshold", "2").is_err());
+        assert_eq!(config.budget.alert_threshold, 0.8);
+        assert_eq!(config.defaults.responsive, Some(true));
+    }
+
+    #[test]
+    fn test_default_flags_section() {
+        let config: AppConfig = serde_yaml::from_str(
+            "default_flags:\n  responsive: true\n  classic: false\n  model_filter: opus\n",
+        )
+        .expect("valid yaml");
+        assert_eq!(config.defaults.responsive, Some(true));
+        assert_eq!(config.defaults.classic, Some(false));
+        assert_eq!(config.defaults.model_filter.as_deref(), Some("opus"));
+    }
+
+    #[test]
+    fn test_parse_reports_location() {
+        let config = AppConfig::parse(
+            "claude_path: /tmp/claude\nbudget:\n  daily_limit: 25.0\ndefaults:\n  responsive: true\n",
+        )
+        .unwrap();
+        assert_eq!(config.budget.daily_limit, Some(25.0));
+
+        let typo = AppConfig::parse("budget:\n  monthly_limit: 100\n  daly_limit: 25.0\n");
+        assert_eq!(
+            typo.unwrap_err(),
+            "unknown key `budget.daly_limit` at line 3 column 3"
+        );
+        let section = AppConfig::parse("budget: {}\nbudjet:\n  daily_limit: 25.0\n");
+        assert_eq!(
+            section.unwrap_err(),
+            "unknown key `budjet` at line 2 column 1"
+        );
+
+        let wrong_type = AppConfig::parse("budget:\n  daily_limit: lots\n").unwrap_err();
+        assert!(wrong_type.contains("budget.daily_limit"), "{}", wrong_type);
+        assert!(wrong_type.contains("line 2 column"), "{}", wrong_type);
+    }
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: AppConfig =
+            serde_yaml::from_str("budget:\n  daily_limit: 25.0\n").expect("valid yaml");
+        assert_eq!(config.budget.daily_limit, Some(25.0));
+        assert_eq!(config.budget.alert_threshold, 0.8);
+        assert_eq!(config.budget.billing_cycle_start_day, 1);
+        assert_eq!(config.core.default_command.to_string(), "daily");
+    }
 }
@@ -0,0 +1,267 @@
+//! How full the model's context window got in each session
+//!
+//! The prompt of every request (input, cache creation, and cache read tokens)
+//! is what occupies the context window. The parser keeps the largest prompt per
+//! model in `TokenUsage::peak_context_tokens`, so session reports can warn about
+//! conversations that came close to the limit, and `inspect --context-usage`
+//! plots the prompt size of every turn of a conversation.
+
+use crate::conversation_parser::Conversation;
+use crate::display::format_number;
+use crate::models::{SessionReport, TokenUsage};
+use crate::models_registry::{DEFAULT_CONTEXT_WINDOW, ModelsRegistry};
+use chrono::{DateTime, Local, Utc};
+use colored::Colorize;
+use serde::Serialize;
+
+/// Share of the context window (percent) above which sessions are flagged
+pub const WARN_PERCENT: f64 = 80.0;
+
+/// Rows of the `inspect --context-usage` chart; longer conversations are bucketed
+const MAX_CHART_ROWS: usize = 40;
+
+/// Width of the chart bars in characters
+const BAR_WIDTH: usize = 30;
+
+/// Largest share of its model's context window any single request used, in percent
+pub fn peak_percent(usage: &TokenUsage, registry: &ModelsRegistry) -> Option<f64> {
+    let percent = |tokens: u64, window: u64| tokens as f64 / window as f64 * 100.0;
+    let peak = if usage.by_model.is_empty() {
+        percent(usage.peak_context_tokens, DEFAULT_CONTEXT_WINDOW)
+    } else {
+        usage
+            .by_model
+            .iter()
+            .map(|(model, model_usage)| {
+                percent(
+                    model_usage.peak_context_tokens,
+                    registry.context_window(model),
+                )
+            })
+            .fold(0.0, f64::max)
+    };
+    (peak > 0.0).then_some(peak)
+}
+
+/// Prompt size of one assistant turn
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextPoint {
+    pub timestamp: DateTime<Utc>,
+    pub model: String,
+    pub context_tokens: u64,
+    pub context_window: u64,
+    pub percent: f64,
+}
+
+/// Prompt size of every assistant turn of a conversation, in order
+pub fn context_timeline(
+    conversation: &Conversation,
+    registry: &ModelsRegistry,
+) -> Vec<ContextPoint> {
+    let mut points: Vec<ContextPoint> = conversation
+        .messages
+        .iter()
+        .filter_map(|message| {
+            let usage = message.usage.as_ref()?;
+            let context_tokens =
+                usage.input_tokens + usage.cache_creation_tokens + usage.cache_read_tokens;
+            if context_tokens == 0 {
+                return None;
+            }
+            let model = message.model.clone().unwrap_or_default();
+            let context_window = registry.context_window(&model);
+            Some(ContextPoint {
+                timestamp: message.timestamp,
+                model,
+                context_tokens,
+                context_window,
+                percent: context_tokens as f64 / context_window as f64 * 100.0,
+            })
+        })
+        .collect();
+    points.sort_by_key(|point| point.timestamp);
+    points
+}
+
+/// Keep at most `max` points, taking the fullest turn of each equal-sized bucket
+fn downsample(points: &[ContextPoint], max: usize) -> Vec<&ContextPoint> {
+    if points.len() <= max {
+        return points.iter().collect();
+    }
+    let bucket = points.len().div_ceil(max);
+    points
+        .chunks(bucket)
+        .filter_map(|chunk| chunk.iter().max_by(|a, b| a.percent.total_cmp(&b.percent)))
+        .collect()
+}
+
+/// Print the context usage chart of one session
+pub fn display_context_timeline(session: &str, points: &[ContextPoint]) {
+    println!(
+        "\n{} {}",
+        "🧠 Context Usage:".bold().cyan(),
+        session.yellow()
+    );
+    if points.is_empty() {
+        println!("   No assistant turns with token usage");
+        return;
+    }
+
+    let shown = downsample(points, MAX_CHART_ROWS);
+    if shown.len() < points.len() {
+        println!(
+            "   {} turns, showing the fullest of every {}",
+            points.len(),
+            points.len().div_ceil(MAX_CHART_ROWS)
+        );
+    }
+    for point in shown {
+        let filled = ((point.percent / 100.0).min(1.0) * BAR_WIDTH as f64).round() as usize;
+        let bar = format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled));
+        let bar = if point.percent >= WARN_PERCENT {
+            bar.red()
+        } else if point.percent >= WARN_PERCENT / 2.0 {
+            bar.yellow()
+        } else {
+            bar.green()
+        };
+        println!(
+            "   {} {} {:>9} / {:<9} {:>5.1}%",
+            point
+                .timestamp
+                .with_timezone(&Local)
+                .format("%m-%d %H:%M")
+                .to_string()
+                .bright_black(),
+            bar,
+            format_number(point.context_tokens),
+            format_number(point.context_window),
+            point.percent
+        );
+    }
+
+    if let Some(peak) = points.iter().max_by(|a, b| a.percent.total_cmp(&b.percent)) {
+        println!(
+            "   Peak: {} tokens ({:.1}% of {}'s window)",
+            format_number(peak.context_tokens),
+            peak.percent,
+            if peak.model.is_empty() {
+                "the model"
+            } else {
+                &peak.model
+            }
+        );
+    }
+}
+
+/// Warn about sessions whose prompts came close to the context window
+pub fn display_session_context_warnings(report: &SessionReport) {
+    let mut near_limit: Vec<_> = report
+        .sessions
+        .iter()
+        .filter_map(|s| {
+            s.context_peak_percent
+                .filter(|percent| *percent >= WARN_PERCENT)
+                .map(|percent| (s, percent))
+        })
+        .collect();
+    if near_limit.is_empty() {
+        return;
+    }
+    near_limit.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    println!(
+        "\n{} {} {} used over {:.0}% of the context window:",
+        "⚠️".yellow(),
+        near_limit.len(),
+        if near_limit.len() == 1 {
+            "session"
+        } else {
+            "sessions"
+        },
+        WARN_PERCENT
+    );
+    for (session, percent) in near_limit.iter().take(5) {
+        println!(
+            "   {:>5.1}%  {}/{}",
+            percent, session.project_path, session.session_id
+        );
+    }
+    println!(
+        "   {}",
+        "See `claudelytics inspect <session> --context-usage` for the timeline".bright_black()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation_parser::ConversationMessage;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn usage(peak: u64) -> TokenUsage {
+        TokenUsage {
+            peak_context_tokens: peak,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_peak_percent_uses_largest_model_share() {
+        let registry = ModelsRegistry::new();
+        let mut session = usage(0);
+        assert_eq!(peak_percent(&session, &registry), None);
+
+        session.add(&usage(50_000).tagged("claude-3-5-haiku-20241022", "p/s"));
+        session.add(&usage(170_000).tagged("claude-sonnet-4-20250514", "p/s"));
+        assert_eq!(session.peak_context_tokens, 170_000);
+        assert_eq!(peak_percent(&session, &registry), Some(85.0));
+
+        // Usage without a model split falls back to the default window
+        assert_eq!(peak_percent(&usage(100_000), &registry), Some(50.0));
+    }
+
+    #[test]
+    fn test_context_timeline_and_downsample() {
+        let registry = ModelsRegistry::new();
+        let message = |minute: u32, input: u64, cache_read: u64| ConversationMessage {
+            uuid: minute.to_string(),
+            parent_uuid: None,
+            message_type: "assistant".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 15, 12, minute, 0).unwrap(),
+            role: "assistant".to_string(),
+            content: Vec::new(),
+            usage: Some(TokenUsage {
+                input_tokens: input,
+                cache_read_tokens: cache_read,
+                ..Default::default()
+            }),
+            model: Some("claude-sonnet-4-20250514".to_string()),
+            session_id: "s".to_string(),
+            cwd: None,
+            is_sidechain: false,
+            git_branch: None,
+        };
+        let conversation = Conversation {
+            file_path: PathBuf::from("s.jsonl"),
+            summary: None,
+            messages: vec![message(2, 10_000, 150_000), message(1, 20_000, 0)],
+            message_index: HashMap::new(),
+            total_usage: TokenUsage::default(),
+            started_at: None,
+            ended_at: None,
+        };
+
+        let points = context_timeline(&conversation, &registry);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].context_tokens, 20_000);
+        assert_eq!(points[1].context_tokens, 160_000);
+        assert_eq!(points[1].percent, 80.0);
+
+        let sampled = downsample(&points, 1);
+        assert_eq!(sampled.len(), 1);
+        assert_eq!(sampled[0].context_tokens, 160_000);
+    }
+}
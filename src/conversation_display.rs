@@ -7,6 +7,7 @@
 use crate::conversation_parser::{
     Conversation, ConversationMessage, MessageContentBlock, MessageThread,
 };
+use crate::currency::Money;
 use crate::models::{ClaudeMessage, ClaudeSession, ContentPart};
 use colored::*;
 use ratatui::{
@@ -107,10 +108,10 @@ impl ConversationDisplay {
         ));
         output.push_str(&format!("Summary: {}\n", session.summary));
         output.push_str(&format!(
-            "Messages: {} | Tokens: {} | Cost: ${:.4}\n",
+            "Messages: {} | Tokens: {} | Cost: {:.4}\n",
             session.message_count,
             session.usage.total_tokens(),
-            session.usage.total_cost
+            Money(session.usage.total_cost)
         ));
         output.push_str(&format!(
             "{}\n\n",
@@ -151,10 +152,12 @@ impl ConversationDisplay {
         }
 
         header.push_str(&format!(
-            "{} Total: {} tokens (${:.4})\n",
+            "{} Total: {} tokens ({:.4})\n",
             "💰".dimmed(),
             conversation.total_usage.total_tokens().to_string().yellow(),
-            conversation.total_usage.total_cost.to_string().green()
+            Money(conversation.total_usage.total_cost)
+                .to_string()
+                .green()
         ));
 
         header.push_str(&format!(
@@ -256,9 +259,9 @@ impl ConversationDisplay {
                 input,
             } => self.format_tool_use_block(id, name, input, &indent),
             MessageContentBlock::ToolResult {
-                content_type: _,
                 tool_use_id,
                 content,
+                ..
             } => self.format_tool_result_block(tool_use_id, content, &indent),
         }
     }
@@ -540,8 +543,8 @@ impl ConversationDisplay {
         }
 
         summary.push_str(&format!(
-            "  Cost: ${:.4}\n",
-            usage.total_cost.to_string().green()
+            "  Cost: {:.4}\n",
+            Money(usage.total_cost).to_string().green()
         ));
 
         summary
@@ -0,0 +1,218 @@
+//! `conversation --export FORMAT --split-files -o DIR`
+//!
+//! Writes every matching conversation to its own file in `DIR`, named
+//! `project__session__date.ext` after the project directory, the session file
+//! and the local date the conversation started, plus an `index.md` that links
+//! to each file with its summary, size, and cost.
+
+use crate::compression;
+use crate::conversation_parser::Conversation;
+use crate::currency::Money;
+use crate::pricing::ModelPricing;
+use crate::session_timeline;
+use anyhow::{Context, Result};
+use chrono::Local;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+pub const INDEX_FILE: &str = "index.md";
+
+/// One exported conversation, as listed in the index
+#[derive(Debug)]
+pub struct ExportedFile {
+    pub file_name: String,
+    pub project: String,
+    pub session: String,
+    pub date: Option<String>,
+    pub summary: Option<String>,
+    pub messages: usize,
+    /// In the display currency
+    pub cost: f64,
+}
+
+/// File extension of an export format, as accepted by `--export`
+pub fn extension(format: &str) -> &'static str {
+    match format {
+        "markdown" => "md",
+        "json" => "json",
+        "html" => "html",
+        _ => "txt",
+    }
+}
+
+/// Write each conversation rendered by `render` to `dir`, followed by the index;
+/// returns the exported files in index order
+pub fn export_split_files(
+    conversations: &[Conversation],
+    projects_dir: &Path,
+    dir: &Path,
+    format: &str,
+    pricing_data: &HashMap<String, ModelPricing>,
+    render: impl Fn(&Conversation) -> Result<String>,
+) -> Result<Vec<ExportedFile>> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let mut exported = Vec::new();
+    for conversation in conversations {
+        let (project, session) = project_and_session(projects_dir, &conversation.file_path);
+        let date = start_date(conversation);
+        let file_name = format!(
+            "{}.{}",
+            join_stem(&project, &session, date.as_deref()),
+            extension(format)
+        );
+        let path = dir.join(&file_name);
+        fs::write(&path, render(conversation)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        let cost = conversation_cost(conversation, pricing_data);
+        exported.push(ExportedFile {
+            file_name,
+            project,
+            session,
+            date,
+            summary: conversation.summary.as_ref().map(|s| s.summary.clone()),
+            messages: conversation.messages.len(),
+            cost,
+        });
+    }
+
+    exported
+        .sort_by(|a, b| (&a.date, &a.project, &a.session).cmp(&(&b.date, &b.project, &b.session)));
+    let index = dir.join(INDEX_FILE);
+    fs::write(&index, render_index(&exported))
+        .with_context(|| format!("Failed to write {}", index.display()))?;
+    Ok(exported)
+}
+
+/// Cost of a conversation in the display currency; conversations are parsed without prices
+pub fn conversation_cost(
+    conversation: &Conversation,
+    pricing_data: &HashMap<String, ModelPricing>,
+) -> f64 {
+    let messages: Vec<_> = conversation.messages.iter().collect();
+    session_timeline::bucket_messages(&messages, pricing_data)
+        .iter()
+        .map(|bucket| bucket.cost)
+        .sum()
+}
+
+/// File name of a conversation's export without the extension, `project__session__date`
+pub fn file_stem(projects_dir: &Path, conversation: &Conversation) -> String {
+    let (project, session) = project_and_session(projects_dir, &conversation.file_path);
+    join_stem(&project, &session, start_date(conversation).as_deref())
+}
+
+fn join_stem(project: &str, session: &str, date: Option<&str>) -> String {
+    format!("{}__{}__{}", project, session, date.unwrap_or("undated"))
+}
+
+/// Local date the conversation started
+fn start_date(conversation: &Conversation) -> Option<String> {
+    conversation
+        .started_at
+        .map(|start| start.with_timezone(&Local).format("%Y-%m-%d").to_string())
+}
+
+/// Project directory and session file stem of a conversation file
+fn project_and_session(projects_dir: &Path, file_path: &Path) -> (String, String) {
+    let file_name = file_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let session = compression::session_stem(file_name)
+        .unwrap_or(file_name)
+        .to_string();
+    // Subagent files sit below the project directory, so take the first component
+    let project = file_path
+        .strip_prefix(projects_dir)
+        .ok()
+        .and_then(|relative| relative.parent()?.components().next())
+        .and_then(|component| component.as_os_str().to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    (project, session)
+}
+
+fn render_index(exported: &[ExportedFile]) -> String {
+    let mut index = String::from("# Conversations\n\n");
+    let total: f64 = exported.iter().map(|file| file.cost).sum();
+    let _ = writeln!(
+        index,
+        "{} conversations, {:.4} in total, exported {}\n",
+        exported.len(),
+        Money(total),
+        Local::now().format("%Y-%m-%d %H:%M")
+    );
+    index.push_str("| Date | Project | Session | Summary | Messages | Cost |\n");
+    index.push_str("|------|---------|---------|---------|----------|------|\n");
+    for file in exported {
+        let _ = writeln!(
+            index,
+            "| {} | {} | [{}]({}) | {} | {} | {:.4} |",
+            file.date.as_deref().unwrap_or("-"),
+            file.project,
+            file.session,
+            file.file_name,
+            file.summary.as_deref().unwrap_or("").replace('|', "\\|"),
+            file.messages,
+            Money(file.cost)
+        );
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation_parser::ConversationParser;
+
+    #[test]
+    fn test_export_split_files_with_index() {
+        let claude_dir = tempfile::tempdir().unwrap();
+        let projects_dir = claude_dir.path().join("projects");
+        let project_dir = projects_dir.join("-home-me-app");
+        fs::create_dir_all(&project_dir).unwrap();
+        let lines = [
+            r#"{"type":"summary","summary":"Fix | login","leafUuid":"a1"}"#,
+            r#"{"type":"user","uuid":"u1","parentUuid":null,"sessionId":"abc","timestamp":"2024-01-01T12:00:00Z","message":{"role":"user","content":"Fix login"}}"#,
+            r#"{"type":"assistant","uuid":"a1","parentUuid":"u1","sessionId":"abc","timestamp":"2024-01-01T12:00:05Z","message":{"role":"assistant","model":"claude-sonnet-4","content":[{"type":"text","text":"Done"}],"usage":{"input_tokens":10,"output_tokens":5}}}"#,
+        ];
+        fs::write(project_dir.join("abc.jsonl"), lines.join("\n")).unwrap();
+
+        let parser = ConversationParser::new(claude_dir.path().to_path_buf());
+        let conversations: Vec<_> = parser
+            .find_conversation_files()
+            .unwrap()
+            .iter()
+            .map(|path| parser.parse_conversation(path).unwrap())
+            .collect();
+
+        let out = tempfile::tempdir().unwrap();
+        let dir = out.path().join("export");
+        let exported = export_split_files(
+            &conversations,
+            &projects_dir,
+            &dir,
+            "markdown",
+            &HashMap::new(),
+            |c| Ok(format!("{} messages", c.messages.len())),
+        )
+        .unwrap();
+
+        assert_eq!(exported.len(), 1);
+        let date = exported[0].date.clone().unwrap();
+        let file_name = format!("-home-me-app__abc__{}.md", date);
+        assert_eq!(exported[0].file_name, file_name);
+        assert_eq!(
+            fs::read_to_string(dir.join(&file_name)).unwrap(),
+            "2 messages"
+        );
+
+        let index = fs::read_to_string(dir.join(INDEX_FILE)).unwrap();
+        assert!(index.contains(&format!("[abc]({})", file_name)));
+        assert!(index.contains("Fix \\| login"));
+    }
+}
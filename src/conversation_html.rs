@@ -0,0 +1,392 @@
+//! Conversation export to a single self-contained HTML file
+//!
+//! Each logical thread of a session (see `Conversation::root_groups`) becomes a
+//! collapsible section. Fenced code blocks are highlighted with syntect using
+//! inline styles, thinking blocks sit in an accordion, and tool calls and
+//! results can be expanded to show their JSON input and output. Collapsing
+//! uses `<details>` elements, so the file needs no JavaScript or external assets.
+
+use crate::conversation_parser::{Conversation, ConversationMessage, MessageContentBlock};
+use crate::currency::Money;
+use crate::html_report::escape;
+use chrono::Local;
+use std::fmt::Write as _;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+const CODE_THEME: &str = "InspiredGitHub";
+
+/// Characters of the first user prompt shown in a collapsed thread
+const THREAD_TITLE_CHARS: usize = 100;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; margin: 0; background: #f6f7f9; color: #1f2328; line-height: 1.5; }
+main { max-width: 920px; margin: 0 auto; padding: 32px 24px; }
+h1 { margin: 0 0 4px; font-size: 24px; }
+.meta { color: #656d76; font-size: 14px; }
+article { margin-top: 28px; }
+details.thread { background: #fff; border: 1px solid #d0d7de; border-radius: 8px; margin-top: 12px; }
+details.thread > summary { padding: 10px 14px; cursor: pointer; font-weight: 600; }
+details.thread > summary .meta { font-weight: normal; margin-left: 8px; }
+.message { border-top: 1px solid #eaeef2; padding: 10px 14px; }
+.message.sidechain { margin-left: 24px; border-left: 3px solid #d0d7de; }
+.role { font-weight: 600; font-size: 13px; }
+.role.user { color: #1a7f37; }
+.role.assistant { color: #0969da; }
+.message .meta { font-size: 12px; margin-left: 6px; }
+p { margin: 6px 0; white-space: pre-wrap; word-wrap: break-word; }
+code { background: #eff1f3; border-radius: 4px; padding: 1px 4px; font-size: 90%; }
+pre { border: 1px solid #eaeef2; border-radius: 6px; padding: 10px 12px; overflow-x: auto; font-size: 13px; }
+details.block { margin: 6px 0; border: 1px solid #eaeef2; border-radius: 6px; padding: 4px 10px; }
+details.block > summary { cursor: pointer; font-size: 13px; color: #656d76; }
+details.thinking { background: #fbf8ff; border-color: #e3d8f5; }
+details.thinking > summary { color: #8250df; }
+details.thinking p { color: #57606a; font-style: italic; }
+details.tool > summary { color: #9a6700; }
+"#;
+
+/// Render `conversations` as one HTML document
+pub fn render_conversations_html(
+    conversations: &[Conversation],
+    include_thinking: bool,
+    include_tools: bool,
+) -> String {
+    let highlighter = CodeHighlighter::new();
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>{STYLE}</style>
+</head>
+<body>
+<main>
+"#,
+        title = escape(&document_title(conversations)),
+    );
+
+    for conversation in conversations {
+        render_conversation(
+            &mut html,
+            conversation,
+            &highlighter,
+            include_thinking,
+            include_tools,
+        );
+    }
+
+    let _ = write!(
+        html,
+        r#"<p class="meta">Exported by claudelytics on {}</p>
+</main>
+</body>
+</html>
+"#,
+        Local::now().format("%Y-%m-%d %H:%M")
+    );
+    html
+}
+
+fn document_title(conversations: &[Conversation]) -> String {
+    match conversations {
+        [conversation] => conversation
+            .summary
+            .as_ref()
+            .map(|s| s.summary.clone())
+            .unwrap_or_else(|| "Conversation".to_string()),
+        _ => format!("{} conversations", conversations.len()),
+    }
+}
+
+fn render_conversation(
+    html: &mut String,
+    conversation: &Conversation,
+    highlighter: &CodeHighlighter,
+    include_thinking: bool,
+    include_tools: bool,
+) {
+    let title = conversation
+        .summary
+        .as_ref()
+        .map_or("Conversation", |s| s.summary.as_str());
+    let session = conversation
+        .file_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut meta = vec![format!("Session {}", session)];
+    if let (Some(start), Some(end)) = (conversation.started_at, conversation.ended_at) {
+        meta.push(format!(
+            "{} – {}",
+            start.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            end.with_timezone(&Local).format("%H:%M")
+        ));
+    }
+    meta.push(format!("{} messages", conversation.messages.len()));
+    meta.push(format!(
+        "{} tokens",
+        conversation.total_usage.total_tokens()
+    ));
+    meta.push(format!("{:.4}", Money(conversation.total_usage.total_cost)));
+
+    let _ = write!(
+        html,
+        "<article>\n<h1>{}</h1>\n<div class=\"meta\">{}</div>\n",
+        escape(title),
+        escape(&meta.join(" · "))
+    );
+
+    for messages in conversation.root_groups() {
+        let cost: f64 = messages
+            .iter()
+            .filter_map(|m| m.usage.as_ref())
+            .map(|u| u.total_cost)
+            .sum();
+        let _ = write!(
+            html,
+            "<details class=\"thread\" open>\n<summary>{}<span class=\"meta\">{} messages · {:.4}</span></summary>\n",
+            escape(&thread_title(&messages)),
+            messages.len(),
+            Money(cost)
+        );
+        for message in messages {
+            render_message(html, message, highlighter, include_thinking, include_tools);
+        }
+        html.push_str("</details>\n");
+    }
+    html.push_str("</article>\n");
+}
+
+/// First line of the first user prompt in a thread
+fn thread_title(messages: &[&ConversationMessage]) -> String {
+    let prompt = messages
+        .iter()
+        .filter(|m| m.role == "user")
+        .flat_map(|m| &m.content)
+        .find_map(|block| match block {
+            MessageContentBlock::Text { content_type, text }
+                if content_type != "thinking" && !text.trim().is_empty() =>
+            {
+                Some(text.trim().lines().next().unwrap_or_default().to_string())
+            }
+            _ => None,
+        });
+    match prompt {
+        Some(prompt) if prompt.chars().count() > THREAD_TITLE_CHARS => {
+            let head: String = prompt.chars().take(THREAD_TITLE_CHARS - 1).collect();
+            format!("{}…", head)
+        }
+        Some(prompt) => prompt,
+        None => "Thread".to_string(),
+    }
+}
+
+fn render_message(
+    html: &mut String,
+    message: &ConversationMessage,
+    highlighter: &CodeHighlighter,
+    include_thinking: bool,
+    include_tools: bool,
+) {
+    let role_class = match message.role.as_str() {
+        "user" | "assistant" => message.role.as_str(),
+        _ => "other",
+    };
+    let mut meta = message
+        .timestamp
+        .with_timezone(&Local)
+        .format("%H:%M:%S")
+        .to_string();
+    if let Some(model) = &message.model {
+        let _ = write!(meta, " · {}", model);
+    }
+    if let Some(usage) = &message.usage {
+        let _ = write!(
+            meta,
+            " · {} tokens · {:.4}",
+            usage.total_tokens(),
+            Money(usage.total_cost)
+        );
+    }
+
+    let _ = write!(
+        html,
+        "<div class=\"message{}\">\n<span class=\"role {}\">{}</span><span class=\"meta\">{}</span>\n",
+        if message.is_sidechain {
+            " sidechain"
+        } else {
+            ""
+        },
+        role_class,
+        escape(&message.role),
+        escape(&meta)
+    );
+
+    for block in &message.content {
+        match block {
+            MessageContentBlock::Text { content_type, text } if content_type == "thinking" => {
+                if include_thinking {
+                    let _ = write!(
+                        html,
+                        "<details class=\"block thinking\">\n<summary>💭 Thinking ({} lines)</summary>\n",
+                        text.lines().count()
+                    );
+                    render_markdown(html, text, highlighter);
+                    html.push_str("</details>\n");
+                }
+            }
+            MessageContentBlock::Text { text, .. } => render_markdown(html, text, highlighter),
+            MessageContentBlock::ToolUse { name, input, .. } => {
+                if include_tools {
+                    let json = serde_json::to_string_pretty(input).unwrap_or_default();
+                    let _ = write!(
+                        html,
+                        "<details class=\"block tool\">\n<summary>🔧 {}</summary>\n{}</details>\n",
+                        escape(name),
+                        highlighter.highlight("json", &json)
+                    );
+                }
+            }
+            MessageContentBlock::ToolResult { content, .. } => {
+                if include_tools {
+                    // Tool results are often JSON; show them highlighted when they parse
+                    let (lang, body) = match serde_json::from_str::<serde_json::Value>(content) {
+                        Ok(value) if value.is_object() || value.is_array() => (
+                            "json",
+                            serde_json::to_string_pretty(&value).unwrap_or_default(),
+                        ),
+                        _ => ("txt", content.clone()),
+                    };
+                    let _ = write!(
+                        html,
+                        "<details class=\"block tool\">\n<summary>↳ Tool result ({} lines)</summary>\n{}</details>\n",
+                        content.lines().count(),
+                        highlighter.highlight(lang, &body)
+                    );
+                }
+            }
+        }
+    }
+    html.push_str("</div>\n");
+}
+
+/// Paragraphs with `inline code`, and highlighted fenced code blocks
+fn render_markdown(html: &mut String, text: &str, highlighter: &CodeHighlighter) {
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<(String, Vec<&str>)> = None;
+
+    for raw in text.lines() {
+        if let Some(fence) = raw.trim_start().strip_prefix("```") {
+            match code.take() {
+                Some((lang, body)) => {
+                    html.push_str(&highlighter.highlight(&lang, &body.join("\n")))
+                }
+                None => {
+                    flush_paragraph(html, &mut paragraph);
+                    code = Some((fence.trim().to_string(), Vec::new()));
+                }
+            }
+            continue;
+        }
+        match code.as_mut() {
+            Some((_, body)) => body.push(raw),
+            None if raw.trim().is_empty() => flush_paragraph(html, &mut paragraph),
+            None => paragraph.push(raw),
+        }
+    }
+    // Unterminated fence: still show what we have
+    if let Some((lang, body)) = code {
+        html.push_str(&highlighter.highlight(&lang, &body.join("\n")));
+    }
+    flush_paragraph(html, &mut paragraph);
+}
+
+fn flush_paragraph(html: &mut String, lines: &mut Vec<&str>) {
+    if lines.is_empty() {
+        return;
+    }
+    let mut in_code = false;
+    html.push_str("<p>");
+    for (i, part) in escape(&lines.join("\n")).split('`').enumerate() {
+        if i > 0 {
+            html.push_str(if in_code { "</code>" } else { "<code>" });
+            in_code = !in_code;
+        }
+        html.push_str(part);
+    }
+    if in_code {
+        html.push_str("</code>");
+    }
+    html.push_str("</p>\n");
+    lines.clear();
+}
+
+struct CodeHighlighter {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+}
+
+impl CodeHighlighter {
+    fn new() -> Self {
+        let mut themes = ThemeSet::load_defaults().themes;
+        Self {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            theme: themes.remove(CODE_THEME).unwrap_or_default(),
+        }
+    }
+
+    /// `<pre>` block with inline styles, plain escaped text if highlighting fails
+    fn highlight(&self, lang: &str, code: &str) -> String {
+        let syntax = self
+            .syntaxes
+            .find_syntax_by_token(lang)
+            .unwrap_or_else(|| self.syntaxes.find_syntax_plain_text());
+        let code = format!("{}\n", code);
+        highlighted_html_for_string(&code, &self.syntaxes, syntax, &self.theme)
+            .unwrap_or_else(|_| format!("<pre>{}</pre>\n", escape(&code)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation_parser::ConversationParser;
+    use std::io::Write;
+
+    #[test]
+    fn test_render_conversation_html() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc.jsonl");
+        let mut file = std::fs::File::create(&path).unwrap();
+        let lines = [
+            r#"{"type":"user","uuid":"u1","parentUuid":null,"sessionId":"abc","timestamp":"2024-01-01T10:00:00Z","message":{"role":"user","content":"Fix <main> please"}}"#,
+            r#"{"type":"assistant","uuid":"a1","parentUuid":"u1","sessionId":"abc","timestamp":"2024-01-01T10:00:05Z","message":{"role":"assistant","model":"claude-sonnet-4","content":[{"type":"thinking","thinking":"Look at `main`"},{"type":"text","text":"Done:\n```rust\nfn main() {}\n```"},{"type":"tool_use","id":"t1","name":"Edit","input":{"file":"src/main.rs"}}],"usage":{"input_tokens":10,"output_tokens":5}}}"#,
+        ];
+        for line in lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+
+        let conversation = ConversationParser::new(dir.path().to_path_buf())
+            .parse_conversation(&path)
+            .unwrap();
+        let html = render_conversations_html(std::slice::from_ref(&conversation), true, true);
+        assert!(html.contains("<details class=\"thread\" open>"));
+        assert!(html.contains("Fix &lt;main&gt; please"));
+        assert!(html.contains("💭 Thinking (1 lines)"));
+        assert!(html.contains("<code>main</code>"));
+        assert!(html.contains("🔧 Edit"));
+        // Highlighted code uses inline styles instead of the raw fence
+        assert!(!html.contains("```"));
+        assert!(html.contains("<pre style="));
+
+        let html = render_conversations_html(&[conversation], false, false);
+        assert!(!html.contains("Thinking"));
+        assert!(!html.contains("🔧"));
+    }
+}
@@ -1,10 +1,11 @@
-use crate::models::TokenUsage;
+use crate::compression;
+use crate::models::{TokenUsage, estimate_thinking_tokens};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
 /// Represents a complete conversation from a JSONL file
@@ -63,6 +64,8 @@ pub struct ConversationMessage {
     pub cwd: Option<String>,
     /// Whether this is a sidechain message
     pub is_sidechain: bool,
+    /// Git branch checked out in the working directory when the message was sent
+    pub git_branch: Option<String>,
 }
 
 /// Content block within a message
@@ -73,6 +76,7 @@ pub enum MessageContentBlock {
     Text {
         #[serde(rename = "type")]
         content_type: String,
+        #[serde(alias = "thinking")]
         text: String,
     },
     /// Tool use content
@@ -88,10 +92,186 @@ pub enum MessageContentBlock {
         #[serde(rename = "type")]
         content_type: String,
         tool_use_id: String,
+        #[serde(default, deserialize_with = "deserialize_tool_result_content")]
         content: String,
+        /// Set when the tool call failed
+        #[serde(default)]
+        is_error: bool,
     },
 }
 
+/// Tool result content is either a string or a list of content blocks; the
+/// text of the blocks is joined
+fn deserialize_tool_result_content<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(text) => text,
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    })
+}
+
+/// The fields of a JSONL line the conversation parser reads. Everything else
+/// (such as `toolUseResult`, which repeats tool output) is skipped while
+/// deserializing instead of being built into a JSON tree.
+#[derive(Debug, Deserialize)]
+struct RawLine {
+    #[serde(rename = "type", default)]
+    record_type: String,
+    #[serde(default)]
+    uuid: Option<String>,
+    #[serde(rename = "parentUuid", default)]
+    parent_uuid: Option<String>,
+    #[serde(default)]
+    timestamp: Option<String>,
+    #[serde(rename = "sessionId", default)]
+    session_id: Option<String>,
+    #[serde(default)]
+    cwd: Option<String>,
+    #[serde(rename = "isSidechain", default)]
+    is_sidechain: Option<bool>,
+    #[serde(rename = "gitBranch", default)]
+    git_branch: Option<String>,
+    #[serde(default)]
+    message: Option<serde_json::Value>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(rename = "leafUuid", default)]
+    leaf_uuid: Option<String>,
+}
+
+impl RawLine {
+    fn into_summary(self) -> Option<ConversationSummary> {
+        Some(ConversationSummary {
+            record_type: self.record_type,
+            summary: self.summary?,
+            leaf_uuid: self.leaf_uuid?,
+        })
+    }
+}
+
+/// The fields `index_conversation` needs; message content is skipped
+#[derive(Debug, Deserialize)]
+struct IndexLine<'a> {
+    #[serde(rename = "type", default)]
+    record_type: String,
+    #[serde(default)]
+    uuid: Option<serde::de::IgnoredAny>,
+    #[serde(default, borrow)]
+    timestamp: Option<std::borrow::Cow<'a, str>>,
+    #[serde(default, borrow)]
+    message: Option<IndexMessage<'a>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IndexMessage<'a> {
+    #[serde(default, borrow)]
+    id: Option<std::borrow::Cow<'a, str>>,
+}
+
+fn parse_timestamp(text: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(text)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Call `f` with the number, byte offset, and text of every non-empty line,
+/// reusing one buffer so only the current line is held in memory
+fn for_each_line(
+    file_path: &Path,
+    mut f: impl FnMut(usize, u64, &str) -> Result<()>,
+) -> Result<()> {
+    let file = compression::open_session_file(file_path)
+        .with_context(|| format!("Failed to open conversation file: {}", file_path.display()))?;
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    let mut offset = 0u64;
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            break;
+        }
+        let text = line.trim_end_matches(['\n', '\r']);
+        if !text.trim().is_empty() {
+            f(line_number, offset, text)?;
+        }
+        offset += read as u64;
+        line_number += 1;
+    }
+    Ok(())
+}
+
+/// Byte offsets of the messages in a conversation file (see
+/// `ConversationParser::index_conversation`)
+#[derive(Debug, Clone)]
+pub struct ConversationIndex {
+    pub file_path: PathBuf,
+    pub summary: Option<ConversationSummary>,
+    entries: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IndexEntry {
+    offset: u64,
+    len: usize,
+    /// First line of its API message, so its usage is counted
+    counts_usage: bool,
+}
+
+impl ConversationIndex {
+    /// Number of messages in the file
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Read the messages in `range` (message positions, not lines)
+    pub fn load_messages(&self, range: Range<usize>) -> Result<Vec<ConversationMessage>> {
+        let entries = &self.entries[range.start.min(self.len())..range.end.min(self.len())];
+        let Some(first) = entries.first() else {
+            return Ok(Vec::new());
+        };
+        // Entries are in file order, so the range is read front to back
+        let mut file = compression::open_session_file_at(&self.file_path, first.offset)
+            .with_context(|| {
+                format!(
+                    "Failed to open conversation file: {}",
+                    self.file_path.display()
+                )
+            })?;
+        let mut position = first.offset;
+        let parser = ConversationParser::new(PathBuf::new());
+        let mut buffer = Vec::new();
+        let mut messages = Vec::with_capacity(entries.len());
+        for entry in entries {
+            compression::skip(&mut file, entry.offset - position)
+                .and_then(|()| {
+                    buffer.resize(entry.len, 0);
+                    file.read_exact(&mut buffer)
+                })
+                .context("Conversation file changed while reading")?;
+            position = entry.offset + entry.len as u64;
+            let record: RawLine =
+                serde_json::from_slice(&buffer).with_context(|| "Failed to parse JSON line")?;
+            if let Some(message) = parser.parse_message_line(record, |_| entry.counts_usage)? {
+                messages.push(message);
+            }
+        }
+        Ok(messages)
+    }
+}
+
 /// Parser for conversation JSONL files
 #[allow(dead_code)]
 pub struct ConversationParser {
@@ -107,12 +287,7 @@ impl ConversationParser {
 
     /// Parse a single conversation file
     pub fn parse_conversation(&self, file_path: &Path) -> Result<Conversation> {
-        let file = File::open(file_path).with_context(|| {
-            format!("Failed to open conversation file: {}", file_path.display())
-        })?;
-        let reader = BufReader::new(file);
-        let mut lines = reader.lines();
-
+        let mut seen_message_ids = HashSet::new();
         let mut conversation = Conversation {
             file_path: file_path.to_path_buf(),
             summary: None,
@@ -123,30 +298,32 @@ impl ConversationParser {
             ended_at: None,
         };
 
-        // Check if first line is a summary
-        if let Some(first_line) = lines.next() {
-            let first_line = first_line?;
-            if let Ok(record) = serde_json::from_str::<serde_json::Value>(&first_line) {
-                if record.get("type").and_then(|t| t.as_str()) == Some("summary") {
-                    // Parse as summary
-                    if let Ok(summary) = serde_json::from_value::<ConversationSummary>(record) {
-                        conversation.summary = Some(summary);
-                    }
-                } else {
-                    // It's a regular message, parse it
-                    self.parse_message_line(&first_line, &mut conversation)?;
-                }
+        for_each_line(file_path, |line_number, _, line| {
+            let record: RawLine = match serde_json::from_str(line) {
+                Ok(record) => record,
+                // An unreadable first line is skipped like a non-summary header
+                Err(_) if line_number == 0 => return Ok(()),
+                Err(e) => return Err(e).with_context(|| "Failed to parse JSON line"),
+            };
+            // Only a summary on the first line describes the session
+            if line_number == 0 && record.record_type == "summary" {
+                conversation.summary = record.into_summary();
+                return Ok(());
             }
-        }
-
-        // Parse remaining lines as messages
-        for line in lines {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
+            let message = self.parse_message_line(record, |id| {
+                id.is_none_or(|id| seen_message_ids.insert(id.to_string()))
+            })?;
+            if let Some(message) = message {
+                if let Some(usage) = &message.usage {
+                    conversation.total_usage.add(usage);
+                }
+                conversation
+                    .message_index
+                    .insert(message.uuid.clone(), conversation.messages.len());
+                conversation.messages.push(message);
             }
-            self.parse_message_line(&line, &mut conversation)?;
-        }
+            Ok(())
+        })?;
 
         // Update start and end times
         if let Some(first_msg) = conversation.messages.first() {
@@ -159,127 +336,151 @@ impl ConversationParser {
         Ok(conversation)
     }
 
-    /// Parse a single message line and add it to the conversation
-    fn parse_message_line(&self, line: &str, conversation: &mut Conversation) -> Result<()> {
-        let record: serde_json::Value =
-            serde_json::from_str(line).with_context(|| "Failed to parse JSON line")?;
+    /// Record where each message of a conversation file starts without keeping
+    /// any message content, so that large files can be read a range at a time
+    pub fn index_conversation(&self, file_path: &Path) -> Result<ConversationIndex> {
+        let mut seen_message_ids = HashSet::new();
+        let mut index = ConversationIndex {
+            file_path: file_path.to_path_buf(),
+            summary: None,
+            entries: Vec::new(),
+        };
 
-        // Skip non-message records (like summaries we've already handled)
-        let record_type = record.get("type").and_then(|t| t.as_str()).unwrap_or("");
-        if record_type == "summary" {
-            return Ok(());
-        }
+        for_each_line(file_path, |line_number, offset, line| {
+            let record: IndexLine = match serde_json::from_str(line) {
+                Ok(record) => record,
+                Err(_) if line_number == 0 => return Ok(()),
+                Err(e) => return Err(e).with_context(|| "Failed to parse JSON line"),
+            };
+            if line_number == 0 && record.record_type == "summary" {
+                let full: RawLine = serde_json::from_str(line)?;
+                index.summary = full.into_summary();
+                return Ok(());
+            }
+            let Some(message) = record.message.filter(|_| record.record_type != "summary") else {
+                return Ok(());
+            };
+            // Fail on the same lines as parse_conversation
+            if record.uuid.is_none() {
+                anyhow::bail!("Missing uuid in message");
+            }
+            if record
+                .timestamp
+                .as_deref()
+                .and_then(parse_timestamp)
+                .is_none()
+            {
+                anyhow::bail!("Missing or invalid timestamp");
+            }
+            let counts_usage = message
+                .id
+                .is_none_or(|id| seen_message_ids.insert(id.to_string()));
+            index.entries.push(IndexEntry {
+                offset,
+                len: line.len(),
+                counts_usage,
+            });
+            Ok(())
+        })?;
 
-        // Extract message fields
-        let uuid = record
-            .get("uuid")
-            .and_then(|u| u.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing uuid in message"))?
-            .to_string();
+        Ok(index)
+    }
 
-        let parent_uuid = record
-            .get("parentUuid")
-            .and_then(|p| p.as_str())
-            .map(|s| s.to_string());
+    /// Build a message from one parsed line, or `None` for records that are not
+    /// messages (summaries, file snapshots, ...)
+    ///
+    /// Streaming writes the same API message (`message.id`) on several lines; its usage
+    /// is only counted where `counts_usage` returns true for the message ID.
+    fn parse_message_line(
+        &self,
+        record: RawLine,
+        counts_usage: impl FnOnce(Option<&str>) -> bool,
+    ) -> Result<Option<ConversationMessage>> {
+        let Some(mut message) = record.message.filter(|_| record.record_type != "summary") else {
+            return Ok(None);
+        };
 
+        let uuid = record
+            .uuid
+            .ok_or_else(|| anyhow::anyhow!("Missing uuid in message"))?;
         let timestamp = record
-            .get("timestamp")
-            .and_then(|t| t.as_str())
-            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
-            .map(|t| t.with_timezone(&Utc))
+            .timestamp
+            .as_deref()
+            .and_then(parse_timestamp)
             .ok_or_else(|| anyhow::anyhow!("Missing or invalid timestamp"))?;
 
-        let session_id = record
-            .get("sessionId")
-            .and_then(|s| s.as_str())
-            .unwrap_or("")
+        let role = message
+            .get("role")
+            .and_then(|r| r.as_str())
+            .unwrap_or("unknown")
             .to_string();
 
-        let cwd = record
-            .get("cwd")
-            .and_then(|c| c.as_str())
+        let model = message
+            .get("model")
+            .and_then(|m| m.as_str())
             .map(|s| s.to_string());
 
-        let is_sidechain = record
-            .get("isSidechain")
-            .and_then(|s| s.as_bool())
-            .unwrap_or(false);
-
-        // Parse message content
-        if let Some(message) = record.get("message") {
-            let role = message
-                .get("role")
-                .and_then(|r| r.as_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            let model = message
-                .get("model")
-                .and_then(|m| m.as_str())
-                .map(|s| s.to_string());
-
-            // Parse content blocks
-            let content = self.parse_content_blocks(message)?;
-
-            // Parse usage if available
-            let usage = if let Some(usage_data) = message.get("usage") {
+        // Parse usage if available (once per API message)
+        let mut usage = match message.get("usage") {
+            Some(usage_data) if counts_usage(message.get("id").and_then(|id| id.as_str())) => {
                 self.parse_usage(usage_data)?
-            } else {
-                None
-            };
-
-            // Add to total usage
-            if let Some(ref msg_usage) = usage {
-                conversation.total_usage.add(msg_usage);
             }
+            _ => None,
+        };
 
-            let msg_index = conversation.messages.len();
-            conversation.message_index.insert(uuid.clone(), msg_index);
-
-            conversation.messages.push(ConversationMessage {
-                uuid,
-                parent_uuid,
-                message_type: record_type.to_string(),
-                timestamp,
-                role,
-                content,
-                usage,
-                model,
-                session_id,
-                cwd,
-                is_sidechain,
-            });
+        // Parse content blocks
+        let content = self.parse_content_blocks(&mut message)?;
+
+        // Thinking is billed as output; estimate its share from the block text
+        if let Some(usage) = usage.as_mut() {
+            let thinking_chars: usize = content
+                .iter()
+                .filter_map(|block| match block {
+                    MessageContentBlock::Text { content_type, text }
+                        if content_type == "thinking" =>
+                    {
+                        Some(text.len())
+                    }
+                    _ => None,
+                })
+                .sum();
+            usage.thinking_tokens = estimate_thinking_tokens(thinking_chars as u64);
         }
 
-        Ok(())
+        Ok(Some(ConversationMessage {
+            uuid,
+            parent_uuid: record.parent_uuid,
+            message_type: record.record_type,
+            timestamp,
+            role,
+            content,
+            usage,
+            model,
+            session_id: record.session_id.unwrap_or_default(),
+            cwd: record.cwd,
+            is_sidechain: record.is_sidechain.unwrap_or(false),
+            git_branch: record.git_branch.filter(|branch| !branch.is_empty()),
+        }))
     }
 
-    /// Parse content blocks from message
+    /// Parse content blocks from message, taking them out of the JSON value
     fn parse_content_blocks(
         &self,
-        message: &serde_json::Value,
+        message: &mut serde_json::Value,
     ) -> Result<Vec<MessageContentBlock>> {
-        let mut blocks = Vec::new();
-
-        // Handle string content (old format)
-        if let Some(content_str) = message.get("content").and_then(|c| c.as_str()) {
-            blocks.push(MessageContentBlock::Text {
+        let blocks = match message.get_mut("content").map(serde_json::Value::take) {
+            // String content (old format)
+            Some(serde_json::Value::String(text)) => vec![MessageContentBlock::Text {
                 content_type: "text".to_string(),
-                text: content_str.to_string(),
-            });
-            return Ok(blocks);
-        }
-
-        // Handle array content (new format)
-        if let Some(content_array) = message.get("content").and_then(|c| c.as_array()) {
-            for content_item in content_array {
-                if let Ok(block) =
-                    serde_json::from_value::<MessageContentBlock>(content_item.clone())
-                {
-                    blocks.push(block);
-                }
-            }
-        }
+                text,
+            }],
+            // Array content (new format)
+            Some(serde_json::Value::Array(items)) => items
+                .into_iter()
+                .filter_map(|item| serde_json::from_value::<MessageContentBlock>(item).ok())
+                .collect(),
+            _ => Vec::new(),
+        };
 
         Ok(blocks)
     }
@@ -314,6 +515,8 @@ impl ConversationParser {
             cache_read_tokens,
             total_cost: 0.0,
             fast_mode_cost: 0.0,
+            peak_context_tokens: input_tokens + cache_creation_tokens + cache_read_tokens,
+            ..Default::default()
         };
 
         Ok(Some(usage))
@@ -331,20 +534,14 @@ impl ConversationParser {
             );
         }
 
-        let files: Vec<PathBuf> = WalkDir::new(projects_dir)
+        let mut files: Vec<PathBuf> = WalkDir::new(projects_dir)
             .into_iter()
             .filter_map(|entry| entry.ok())
             .filter(|entry| entry.file_type().is_file())
-            .filter(|entry| {
-                entry
-                    .path()
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| ext == "jsonl")
-                    .unwrap_or(false)
-            })
+            .filter(|entry| compression::is_session_file(entry.path()))
             .map(|entry| entry.path().to_path_buf())
             .collect();
+        compression::dedup_compressed(&mut files);
 
         Ok(files)
     }
@@ -368,6 +565,32 @@ impl Conversation {
         threads
     }
 
+    /// Group messages by the root of their parent chain, in order of first appearance.
+    /// Each group is one logical conversation within the session file; messages whose
+    /// parent is missing from the file start a new group.
+    pub fn root_groups(&self) -> Vec<Vec<&ConversationMessage>> {
+        let mut root_of: HashMap<&str, usize> = HashMap::new();
+        let mut groups: Vec<Vec<&ConversationMessage>> = Vec::new();
+
+        for message in &self.messages {
+            let group = match message
+                .parent_uuid
+                .as_deref()
+                .and_then(|parent| root_of.get(parent))
+            {
+                Some(&group) => group,
+                None => {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                }
+            };
+            root_of.insert(&message.uuid, group);
+            groups[group].push(message);
+        }
+
+        groups
+    }
+
     /// Build a message thread starting from a root message
     fn build_thread(
         &self,
@@ -478,6 +701,7 @@ pub struct ToolUsageRecord {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs::File;
     use std::io::Write;
     use tempfile::tempdir;
 
@@ -518,13 +742,16 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
 
         writeln!(file, r#"{{"uuid":"msg1","parentUuid":null,"type":"assistant","timestamp":"2024-01-01T12:00:00Z","sessionId":"session1","message":{{"role":"assistant","content":[{{"type":"thinking","text":"Let me think about this..."}},{{"type":"text","text":"Here's my response"}}]}}}}"#).unwrap();
+        // Claude Code stores the thinking text under "thinking"
+        writeln!(file, r#"{{"uuid":"msg2","parentUuid":"msg1","type":"assistant","timestamp":"2024-01-01T12:00:01Z","sessionId":"session1","message":{{"role":"assistant","content":[{{"type":"thinking","thinking":"Second thought","signature":"abc"}}]}}}}"#).unwrap();
 
         let parser = ConversationParser::new(dir.path().to_path_buf());
         let conversation = parser.parse_conversation(&file_path).unwrap();
         let thinking_blocks = conversation.extract_thinking_blocks();
 
-        assert_eq!(thinking_blocks.len(), 1);
+        assert_eq!(thinking_blocks.len(), 2);
         assert_eq!(thinking_blocks[0].content, "Let me think about this...");
+        assert_eq!(thinking_blocks[1].content, "Second thought");
     }
 
     #[test]
@@ -543,6 +770,84 @@ mod tests {
         assert_eq!(tool_usage[0].tool_name, "Read");
     }
 
+    #[test]
+    fn test_root_groups_and_duplicate_usage() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.jsonl");
+        let mut file = File::create(&file_path).unwrap();
+
+        writeln!(file, r#"{{"uuid":"msg1","parentUuid":null,"type":"user","timestamp":"2024-01-01T12:00:00Z","sessionId":"session1","message":{{"role":"user","content":"Task A"}}}}"#).unwrap();
+        writeln!(file, r#"{{"uuid":"msg2","parentUuid":"msg1","type":"assistant","timestamp":"2024-01-01T12:00:01Z","sessionId":"session1","message":{{"id":"api1","role":"assistant","content":[],"usage":{{"input_tokens":10,"output_tokens":5}}}}}}"#).unwrap();
+        writeln!(file, r#"{{"uuid":"msg3","parentUuid":"msg2","type":"assistant","timestamp":"2024-01-01T12:00:02Z","sessionId":"session1","message":{{"id":"api1","role":"assistant","content":[],"usage":{{"input_tokens":10,"output_tokens":5}}}}}}"#).unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"file-history-snapshot","messageId":"snap1"}}"#
+        )
+        .unwrap();
+        writeln!(file, r#"{{"uuid":"msg4","parentUuid":null,"type":"user","timestamp":"2024-01-01T13:00:00Z","sessionId":"session1","message":{{"role":"user","content":"Task B"}}}}"#).unwrap();
+
+        let parser = ConversationParser::new(dir.path().to_path_buf());
+        let conversation = parser.parse_conversation(&file_path).unwrap();
+
+        assert_eq!(conversation.total_usage.input_tokens, 10);
+        assert!(conversation.messages[2].usage.is_none());
+
+        let groups = conversation.root_groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].len(), 3);
+        assert_eq!(groups[1][0].uuid, "msg4");
+    }
+
+    #[test]
+    fn test_index_conversation_loads_ranges() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.jsonl");
+        let mut file = File::create(&file_path).unwrap();
+
+        writeln!(
+            file,
+            r#"{{"type":"summary","summary":"Indexed","leafUuid":"msg3"}}"#
+        )
+        .unwrap();
+        writeln!(file, r#"{{"uuid":"msg1","parentUuid":null,"type":"user","timestamp":"2024-01-01T12:00:00Z","sessionId":"session1","message":{{"role":"user","content":"First"}}}}"#).unwrap();
+        writeln!(file, r#"{{"uuid":"msg2","parentUuid":"msg1","type":"assistant","timestamp":"2024-01-01T12:00:01Z","sessionId":"session1","message":{{"id":"api1","role":"assistant","content":[],"usage":{{"input_tokens":10,"output_tokens":5}}}}}}"#).unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"file-history-snapshot","messageId":"snap1"}}"#
+        )
+        .unwrap();
+        writeln!(file).unwrap();
+        writeln!(file, r#"{{"uuid":"msg3","parentUuid":"msg2","type":"assistant","timestamp":"2024-01-01T12:00:02Z","sessionId":"session1","message":{{"id":"api1","role":"assistant","content":[{{"type":"text","text":"Done"}}],"usage":{{"input_tokens":10,"output_tokens":5}}}}}}"#).unwrap();
+
+        let parser = ConversationParser::new(dir.path().to_path_buf());
+        let index = parser.index_conversation(&file_path).unwrap();
+
+        assert_eq!(index.summary.as_ref().unwrap().summary, "Indexed");
+        assert_eq!(index.len(), 3);
+
+        let tail = index.load_messages(1..10).unwrap();
+        assert_eq!(tail.len(), 2);
+        assert_eq!(tail[0].uuid, "msg2");
+        assert!(tail[0].usage.is_some());
+        // Usage of a repeated API message is only counted on its first line
+        assert!(tail[1].usage.is_none());
+        assert!(matches!(
+            &tail[1].content[0],
+            MessageContentBlock::Text { text, .. } if text == "Done"
+        ));
+
+        // The whole file gives the same messages as parse_conversation
+        let conversation = parser.parse_conversation(&file_path).unwrap();
+        let all = index.load_messages(0..index.len()).unwrap();
+        let uuids: Vec<_> = all.iter().map(|m| m.uuid.as_str()).collect();
+        let expected: Vec<_> = conversation
+            .messages
+            .iter()
+            .map(|m| m.uuid.as_str())
+            .collect();
+        assert_eq!(uuids, expected);
+    }
+
     #[test]
     fn test_thread_structure() {
         let dir = tempdir().unwrap();
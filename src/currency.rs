@@ -0,0 +1,370 @@
+//! Display currency for costs
+//!
+//! Costs are calculated in USD. With `--currency` (or `currency.default` in the
+//! config) they are converted once after parsing, so every report, export, and
+//! budget comparison works in the selected currency. Formatted amounts use its
+//! symbol and JSON output names it in a `currency` field.
+//!
+//! Rates come from `currency.rates` in the config when set there, otherwise they
+//! are fetched from an exchange rate API at most once a day and cached.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::billing_blocks::BillingBlockManager;
+use crate::burn_rate::UsageEvent;
+use crate::config_v2::CurrencyConfig;
+use crate::models::{DailyUsageMap, SessionUsageMap};
+
+/// Default exchange rate source; any URL returning USD-based `{"rates": {...}}` works
+pub const DEFAULT_RATE_SOURCE: &str = "https://open.er-api.com/v6/latest/USD";
+
+/// Fetched rates are reused for a day
+const CACHE_DURATION_HOURS: i64 = 24;
+
+/// Timeout for the exchange rate HTTP request
+const FETCH_TIMEOUT_SECS: u64 = 15;
+
+/// Currency costs are shown in
+static CURRENT: OnceLock<Currency> = OnceLock::new();
+
+/// A display currency and its rate against USD
+#[derive(Debug, Clone, PartialEq)]
+pub struct Currency {
+    /// ISO 4217 code, e.g. `EUR`
+    pub code: String,
+    /// Units of this currency per USD
+    pub rate: f64,
+}
+
+impl Currency {
+    pub fn usd() -> Self {
+        Self {
+            code: "USD".to_string(),
+            rate: 1.0,
+        }
+    }
+
+    pub fn is_usd(&self) -> bool {
+        self.code == "USD"
+    }
+
+    /// Symbol placed before amounts; the code itself for currencies without a well-known one
+    pub fn symbol(&self) -> String {
+        match self.code.as_str() {
+            "USD" => "$".to_string(),
+            "EUR" => "€".to_string(),
+            "GBP" => "£".to_string(),
+            "JPY" | "CNY" => "¥".to_string(),
+            "INR" => "₹".to_string(),
+            "KRW" => "₩".to_string(),
+            code => format!("{} ", code),
+        }
+    }
+
+    /// Convert every cost in the parsed usage from USD to this currency
+    pub fn convert(
+        &self,
+        daily_map: &mut DailyUsageMap,
+        session_map: &mut SessionUsageMap,
+        billing_manager: &mut BillingBlockManager,
+        events: &mut [UsageEvent],
+    ) {
+        if self.rate == 1.0 {
+            return;
+        }
+        for usage in daily_map.values_mut() {
+            usage.scale_costs(self.rate);
+        }
+        for (usage, _) in session_map.values_mut() {
+            usage.scale_costs(self.rate);
+        }
+        billing_manager.scale_costs(self.rate);
+        for event in events {
+            event.usage.scale_costs(self.rate);
+        }
+    }
+}
+
+/// Set the display currency (only the first call has an effect)
+pub fn set_current(currency: Currency) {
+    let _ = CURRENT.set(currency);
+}
+
+/// The display currency, USD unless `set_current` was called
+pub fn current() -> &'static Currency {
+    CURRENT.get_or_init(Currency::usd)
+}
+
+/// A cost formatted with the display currency's symbol, e.g. `format!("{:.2}", Money(cost))`
+///
+/// Precision applies to the amount; width pads the amount and symbol together.
+pub struct Money(pub f64);
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let amount = match f.precision() {
+            Some(precision) => format!("{:.*}", precision, self.0),
+            None => self.0.to_string(),
+        };
+        let text = format!("{}{}", current().symbol(), amount);
+        let padding = f.width().unwrap_or(0).saturating_sub(text.chars().count());
+        match f.align() {
+            Some(fmt::Alignment::Left) => write!(f, "{}{}", text, " ".repeat(padding)),
+            Some(fmt::Alignment::Center) => write!(
+                f,
+                "{}{}{}",
+                " ".repeat(padding / 2),
+                text,
+                " ".repeat(padding - padding / 2)
+            ),
+            _ => write!(f, "{}{}", " ".repeat(padding), text),
+        }
+    }
+}
+
+/// Cost column header, e.g. `Cost (EUR)`
+pub fn cost_header() -> String {
+    format!("Cost ({})", current().code)
+}
+
+/// A JSON document with the display currency added as its first field
+#[derive(Serialize)]
+pub struct WithCurrency<'a, T> {
+    currency: &'a str,
+    #[serde(flatten)]
+    report: &'a T,
+}
+
+pub fn with_currency<T: Serialize>(report: &T) -> WithCurrency<'_, T> {
+    WithCurrency {
+        currency: &current().code,
+        report,
+    }
+}
+
+/// Look up the rate for `code`: static config rates first, then the daily cache,
+/// then the rate source
+pub fn resolve(code: &str, config: &CurrencyConfig) -> Result<Currency> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        anyhow::bail!("Invalid currency code '{}': expected e.g. EUR or JPY", code);
+    }
+    if code == "USD" {
+        return Ok(Currency::usd());
+    }
+    if let Some(rate) = config.rates.get(&code) {
+        return Ok(Currency { code, rate: *rate });
+    }
+
+    let source = config.rate_source.as_deref().unwrap_or(DEFAULT_RATE_SOURCE);
+    let cached = RateCache::load().ok().flatten();
+    let fresh = cached
+        .as_ref()
+        .filter(|cache| cache.source == source && cache.is_valid());
+    if let Some(rate) = fresh.and_then(|cache| cache.rates.get(&code)) {
+        return Ok(Currency { code, rate: *rate });
+    }
+
+    let rate = match RateCache::fetch(source) {
+        Ok(cache) => {
+            if let Err(e) = cache.save() {
+                tracing::warn!("failed to cache exchange rates: {:#}", e);
+            }
+            cache.rates.get(&code).copied()
+        }
+        Err(e) => {
+            // An outdated rate beats no conversion at all
+            let stale = cached.and_then(|cache| cache.rates.get(&code).copied());
+            if stale.is_none() {
+                return Err(e);
+            }
+            tracing::warn!("using cached exchange rates: {:#}", e);
+            stale
+        }
+    };
+    let rate = rate.with_context(|| {
+        format!(
+            "No exchange rate for {} from {}; set currency.rates.{} in the config",
+            code, source, code
+        )
+    })?;
+    Ok(Currency { code, rate })
+}
+
+/// Exchange rates fetched from a rate source
+#[derive(Debug, Serialize, Deserialize)]
+struct RateCache {
+    source: String,
+    fetched_at: DateTime<Utc>,
+    /// Units of each currency per USD
+    rates: BTreeMap<String, f64>,
+}
+
+impl RateCache {
+    fn is_valid(&self) -> bool {
+        Utc::now() - self.fetched_at < Duration::hours(CACHE_DURATION_HOURS)
+    }
+
+    fn fetch(source: &str) -> Result<Self> {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(std::time::Duration::from_secs(FETCH_TIMEOUT_SECS))
+            .build();
+        // ureq errors already include the URL
+        let json: serde_json::Value = agent
+            .get(source)
+            .call()
+            .context("Failed to fetch exchange rates")?
+            .into_json()
+            .context("Failed to parse exchange rates")?;
+        let rates = parse_rates(&json);
+        if rates.is_empty() {
+            anyhow::bail!("No exchange rates found in {}", source);
+        }
+        Ok(Self {
+            source: source.to_string(),
+            fetched_at: Utc::now(),
+            rates,
+        })
+    }
+
+    fn path() -> Result<PathBuf> {
+        let cache_dir = dirs::cache_dir()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".cache")))
+            .ok_or_else(|| anyhow::anyhow!("Could not determine cache directory"))?;
+        let claudelytics_cache = cache_dir.join("claudelytics");
+        fs::create_dir_all(&claudelytics_cache)?;
+        Ok(claudelytics_cache.join("exchange_rates.json"))
+    }
+
+    fn load() -> Result<Option<Self>> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read cache file: {}", path.display()))?;
+        Ok(Some(
+            serde_json::from_str(&data).context("Failed to parse exchange rate cache")?,
+        ))
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        let data = serde_json::to_string_pretty(self)?;
+        fs::write(&path, data)
+            .with_context(|| format!("Failed to write cache file: {}", path.display()))
+    }
+}
+
+/// Positive numeric entries of the `rates` object
+fn parse_rates(json: &serde_json::Value) -> BTreeMap<String, f64> {
+    json.get("rates")
+        .and_then(|rates| rates.as_object())
+        .map(|rates| {
+            rates
+                .iter()
+                .filter_map(|(code, rate)| {
+                    let rate = rate.as_f64()?;
+                    (rate > 0.0).then(|| (code.to_ascii_uppercase(), rate))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+    use chrono::NaiveDate;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_resolve_static_rates() {
+        let config = CurrencyConfig {
+            rates: BTreeMap::from([("EUR".to_string(), 0.9)]),
+            ..Default::default()
+        };
+        let eur = resolve("eur", &config).unwrap();
+        assert_eq!(eur.code, "EUR");
+        assert_eq!(eur.rate, 0.9);
+        assert_eq!(eur.symbol(), "€");
+        assert_eq!(resolve("USD", &config).unwrap(), Currency::usd());
+        assert!(resolve("euro", &config).is_err());
+
+        let chf = Currency {
+            code: "CHF".to_string(),
+            rate: 0.8,
+        };
+        assert_eq!(chf.symbol(), "CHF ");
+    }
+
+    #[test]
+    fn test_parse_rates() {
+        let json = serde_json::json!({
+            "result": "success",
+            "rates": {"USD": 1, "EUR": 0.92, "JPY": 150.5, "BAD": "x", "ZERO": 0}
+        });
+        let rates = parse_rates(&json);
+        assert_eq!(rates.len(), 3);
+        assert_eq!(rates["JPY"], 150.5);
+        assert!(parse_rates(&serde_json::json!({"error": "quota"})).is_empty());
+    }
+
+    #[test]
+    fn test_convert_scales_costs() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let usage = TokenUsage {
+            input_tokens: 100,
+            total_cost: 2.0,
+            fast_mode_cost: 1.0,
+            ..Default::default()
+        }
+        .tagged("claude-3-opus-20240229", "p/s1");
+        let mut daily_map = HashMap::from([(date, usage.clone())]);
+        let mut session_map = HashMap::from([("p/s1".to_string(), (usage, Utc::now()))]);
+        let mut billing_manager = BillingBlockManager::new();
+
+        let eur = Currency {
+            code: "EUR".to_string(),
+            rate: 0.5,
+        };
+        eur.convert(
+            &mut daily_map,
+            &mut session_map,
+            &mut billing_manager,
+            &mut [],
+        );
+        let day = &daily_map[&date];
+        assert_eq!(day.total_cost, 1.0);
+        assert_eq!(day.fast_mode_cost, 0.5);
+        assert_eq!(day.input_tokens, 100);
+        assert_eq!(day.by_model["claude-3-opus-20240229"].total_cost, 1.0);
+        assert_eq!(day.by_session["p/s1"].total_cost, 1.0);
+        assert_eq!(session_map["p/s1"].0.total_cost, 1.0);
+    }
+
+    #[test]
+    fn test_money_formatting() {
+        assert_eq!(format!("{:.2}", Money(1.5)), "$1.50");
+        assert_eq!(format!("{:>7.2}", Money(1.5)), "  $1.50");
+        assert_eq!(format!("{:<7.1}", Money(1.5)), "$1.5   ");
+    }
+
+    #[test]
+    fn test_with_currency_adds_field() {
+        #[derive(Serialize)]
+        struct Report {
+            total: f64,
+        }
+        let json = serde_json::to_value(with_currency(&Report { total: 1.5 })).unwrap();
+        assert_eq!(json, serde_json::json!({"currency": "USD", "total": 1.5}));
+    }
+}
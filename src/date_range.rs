@@ -0,0 +1,201 @@
+//! Human-friendly dates and ranges for `--since`/`--until`, `--last`,
+//! `--this-month`, and `--last-month`
+//!
+//! Everything resolves to local calendar dates; callers that still pass dates
+//! around as strings use `to_arg` to get the `YYYYMMDD` form back.
+
+use anyhow::{Result, bail};
+use chrono::{Datelike, Days, Duration, NaiveDate, Weekday};
+
+const DATE_FORMS: &str =
+    "YYYYMMDD, YYYY-MM-DD, today, yesterday, monday or last monday, or N days/weeks ago";
+
+/// Parse a date given on the command line, relative to `today`
+///
+/// Accepts `YYYYMMDD`, `YYYY-MM-DD`, `today`, `yesterday`, a weekday name
+/// (optionally prefixed with `last`) for its most recent occurrence before
+/// today, and `N days ago` / `N weeks ago` (also `Nd ago`, `Nw ago`).
+pub fn parse_date(input: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let normalized = input.trim().to_ascii_lowercase();
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+    let date = match words.as_slice() {
+        ["today"] => Some(today),
+        ["yesterday"] => Some(today - Duration::days(1)),
+        ["last", weekday] | [weekday] if weekday.parse::<Weekday>().is_ok() => {
+            let weekday = weekday.parse::<Weekday>().ok();
+            weekday.map(|weekday| most_recent_before(today, weekday))
+        }
+        [amount, unit, "ago"] => days_ago(&format!("{}{}", amount, unit), today)?,
+        [span, "ago"] => days_ago(span, today)?,
+        [date] if date.len() == 8 && date.chars().all(|c| c.is_ascii_digit()) => {
+            NaiveDate::parse_from_str(date, "%Y%m%d").ok()
+        }
+        [date] => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+        _ => None,
+    };
+    date.ok_or_else(|| anyhow::anyhow!("Invalid date '{}' (expected {})", input, DATE_FORMS))
+}
+
+/// Number of days in a span such as `7d`, `2w`, `3 days` (a bare number is days)
+pub fn span_days(input: &str) -> Result<i64> {
+    let normalized = input.trim().to_ascii_lowercase().replace(' ', "");
+    let split = normalized
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(normalized.len());
+    let (amount, unit) = normalized.split_at(split);
+    let Ok(amount) = amount.parse::<i64>() else {
+        bail!("Invalid span '{}' (expected e.g. 7d or 2w)", input);
+    };
+    let days = match unit {
+        "" | "d" | "day" | "days" => Some(amount),
+        "w" | "week" | "weeks" => amount.checked_mul(7),
+        _ => bail!("Invalid span '{}' (expected e.g. 7d or 2w)", input),
+    };
+    let Some(days) = days else {
+        bail!("Span '{}' is too long", input);
+    };
+    if days == 0 {
+        bail!("Span '{}' must be at least one day", input);
+    }
+    Ok(days)
+}
+
+/// Parse a range such as `last monday..yesterday`, `20240301..` (through today),
+/// a span like `7d` for the last seven days, or a single date for that day only
+pub fn parse_range(input: &str, today: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+    let (since, until) = match input.split_once("..") {
+        Some((since, until)) => {
+            let until = if until.trim().is_empty() {
+                today
+            } else {
+                parse_date(until, today)?
+            };
+            (parse_date(since, today)?, until)
+        }
+        None => match parse_date(input, today) {
+            Ok(date) => (date, date),
+            Err(e) => last(span_days(input).map_err(|_| e)?, today)?,
+        },
+    };
+    if since > until {
+        bail!("Range start {} is after its end {}", since, until);
+    }
+    Ok((since, until))
+}
+
+/// The last `days` days up to and including `today`
+pub fn last(days: i64, today: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+    Ok((days_before(today, days - 1)?, today))
+}
+
+/// The date `days` days before `today`, or an error when that is out of range
+pub fn days_before(today: NaiveDate, days: i64) -> Result<NaiveDate> {
+    u64::try_from(days)
+        .ok()
+        .and_then(|days| today.checked_sub_days(Days::new(days)))
+        .ok_or_else(|| anyhow::anyhow!("{} days before {} is out of range", days, today))
+}
+
+/// From the first of the current month to `today`
+pub fn this_month(today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    (today.with_day(1).unwrap_or(today), today)
+}
+
+/// The whole previous calendar month
+pub fn last_month(today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let end = today.with_day(1).unwrap_or(today) - Duration::days(1);
+    (end.with_day(1).unwrap_or(end), end)
+}
+
+/// `YYYYMMDD`, the form `UsageParser` and the other `--since` consumers take
+pub fn to_arg(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+/// `span` before `today`; `None` when `span` is not a span
+fn days_ago(span: &str, today: NaiveDate) -> Result<Option<NaiveDate>> {
+    match span_days(span) {
+        Ok(days) => days_before(today, days).map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Most recent `weekday` strictly before `today`
+fn most_recent_before(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    today - Duration::days(if back == 0 { 7 } else { back.into() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_date_forms() {
+        // A Wednesday
+        let today = date(2024, 3, 13);
+        assert_eq!(parse_date("20240101", today).unwrap(), date(2024, 1, 1));
+        assert_eq!(parse_date("2024-01-31", today).unwrap(), date(2024, 1, 31));
+        assert_eq!(parse_date("today", today).unwrap(), today);
+        assert_eq!(parse_date("Yesterday", today).unwrap(), date(2024, 3, 12));
+        assert_eq!(parse_date("last monday", today).unwrap(), date(2024, 3, 11));
+        assert_eq!(parse_date("monday", today).unwrap(), date(2024, 3, 11));
+        // The same weekday as today means a week ago
+        assert_eq!(parse_date("last wed", today).unwrap(), date(2024, 3, 6));
+        assert_eq!(parse_date("3 days ago", today).unwrap(), date(2024, 3, 10));
+        assert_eq!(parse_date("2w ago", today).unwrap(), date(2024, 2, 28));
+
+        for invalid in ["20241301", "next monday", "2024/01/01", "0 days ago", ""] {
+            let error = parse_date(invalid, today).unwrap_err().to_string();
+            assert!(error.contains("expected YYYYMMDD"), "{}", invalid);
+        }
+        for too_far in [
+            "99999999 days ago",
+            "9223372036854775807d ago",
+            "2000000000000000000 weeks ago",
+        ] {
+            assert!(parse_date(too_far, today).is_err(), "{}", too_far);
+        }
+    }
+
+    #[test]
+    fn test_ranges() {
+        let today = date(2024, 3, 13);
+        assert_eq!(span_days("7d").unwrap(), 7);
+        assert_eq!(span_days("2w").unwrap(), 14);
+        assert_eq!(span_days("30").unwrap(), 30);
+        assert!(span_days("0d").is_err());
+        assert!(span_days("3m").is_err());
+        assert!(span_days("9223372036854775807w").is_err());
+
+        assert_eq!(last(7, today).unwrap(), (date(2024, 3, 7), today));
+        assert!(last(100_000_000, today).is_err());
+        assert!(parse_range("100000000d", today).is_err());
+        assert_eq!(this_month(today), (date(2024, 3, 1), today));
+        assert_eq!(last_month(today), (date(2024, 2, 1), date(2024, 2, 29)));
+        assert_eq!(
+            last_month(date(2024, 1, 5)),
+            (date(2023, 12, 1), date(2023, 12, 31))
+        );
+        assert_eq!(to_arg(date(2024, 3, 7)), "20240307");
+
+        assert_eq!(
+            parse_range("last monday..yesterday", today).unwrap(),
+            (date(2024, 3, 11), date(2024, 3, 12))
+        );
+        assert_eq!(
+            parse_range("2024-03-01..", today).unwrap(),
+            (date(2024, 3, 1), today)
+        );
+        assert_eq!(parse_range("7d", today).unwrap(), last(7, today).unwrap());
+        assert_eq!(
+            parse_range("20240305", today).unwrap(),
+            (date(2024, 3, 5), date(2024, 3, 5))
+        );
+        assert!(parse_range("today..yesterday", today).is_err());
+    }
+}
@@ -0,0 +1,516 @@
+//! Narrative usage digest (`digest` command)
+//!
+//! Summarizes the last day, week or month as Markdown for a mail body or a
+//! chat post: total spend and its change against the period before, the top
+//! sessions and projects, the model mix, anomalies flagged in the period, and
+//! the projected spend for the billing cycle at the period's pace.
+
+use crate::currency::{self, Money};
+use crate::date_range;
+use crate::display::format_number;
+use crate::models::{DailyUsageMap, TokenUsage};
+use crate::models_registry::ModelsRegistry;
+use crate::projections::{AnomalyReport, BillingCycle, CycleForecast};
+use crate::reports::parse_session_path;
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+
+/// Sessions and projects listed in the digest
+const TOP_COUNT: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DigestPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl DigestPeriod {
+    pub fn days(self) -> i64 {
+        match self {
+            DigestPeriod::Day => 1,
+            DigestPeriod::Week => 7,
+            DigestPeriod::Month => 30,
+        }
+    }
+
+    /// `daily`, `weekly` or `monthly`
+    pub fn adjective(self) -> &'static str {
+        match self {
+            DigestPeriod::Day => "daily",
+            DigestPeriod::Week => "weekly",
+            DigestPeriod::Month => "monthly",
+        }
+    }
+
+    /// How the period before is referred to, e.g. "the week before"
+    fn previous(self) -> &'static str {
+        match self {
+            DigestPeriod::Day => "the day before",
+            DigestPeriod::Week => "the week before",
+            DigestPeriod::Month => "the 30 days before",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestSession {
+    pub project: String,
+    pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub cost: f64,
+    pub total_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestProject {
+    pub project: String,
+    pub sessions: usize,
+    pub cost: f64,
+    /// Percent of the period's cost
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestModel {
+    pub family: String,
+    pub cost: f64,
+    pub total_tokens: u64,
+    /// Percent of the period's cost
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    pub period: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub generated_at: String,
+    pub currency: String,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    pub previous_cost: f64,
+    /// Percent change of the cost against the period before, when it had usage
+    pub cost_change: Option<f64>,
+    pub sessions: usize,
+    pub active_days: usize,
+    pub top_sessions: Vec<DigestSession>,
+    pub top_projects: Vec<DigestProject>,
+    pub models: Vec<DigestModel>,
+    /// Anomalies of the period, one sentence each
+    pub anomalies: Vec<String>,
+    /// Billing cycle spend projected at the period's daily rate
+    pub projection: CycleForecast,
+    #[serde(skip)]
+    kind: DigestPeriod,
+}
+
+/// Assemble the digest of the `period` ending with `today`
+pub fn build_digest(
+    daily_map: &DailyUsageMap,
+    summaries: &HashMap<String, String>,
+    anomalies: &AnomalyReport,
+    period: DigestPeriod,
+    today: NaiveDate,
+    cycle_start_day: u32,
+    monthly_limit: Option<f64>,
+) -> Result<Digest> {
+    let days = period.days();
+    let (start, end) = date_range::last(days, today)?;
+    let (previous_start, previous_end) =
+        date_range::last(days, date_range::days_before(start, 1)?)?;
+    let in_range = |from: NaiveDate, to: NaiveDate| {
+        daily_map
+            .iter()
+            .filter(move |(date, _)| (from..=to).contains(*date))
+    };
+
+    let mut total = TokenUsage::default();
+    let mut active_days = 0;
+    for (_, usage) in in_range(start, end) {
+        total.add(usage);
+        active_days += 1;
+    }
+    let previous_cost: f64 = in_range(previous_start, previous_end)
+        .map(|(_, usage)| usage.total_cost)
+        .sum();
+    let share = |cost: f64| {
+        if total.total_cost > 0.0 {
+            cost / total.total_cost * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    let mut sessions: Vec<DigestSession> = total
+        .by_session
+        .iter()
+        .map(|(key, usage)| {
+            let (project, session_id) = parse_session_path(key);
+            DigestSession {
+                project,
+                session_id,
+                summary: summaries.get(key).cloned(),
+                cost: usage.total_cost,
+                total_tokens: usage.total_tokens(),
+            }
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+
+    let mut projects: BTreeMap<&str, (usize, f64)> = BTreeMap::new();
+    for session in &sessions {
+        let entry = projects.entry(&session.project).or_default();
+        entry.0 += 1;
+        entry.1 += session.cost;
+    }
+    let mut top_projects: Vec<DigestProject> = projects
+        .into_iter()
+        .map(|(project, (sessions, cost))| DigestProject {
+            project: project.to_string(),
+            sessions,
+            cost,
+            share: share(cost),
+        })
+        .collect();
+    top_projects.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+    top_projects.truncate(TOP_COUNT);
+
+    let registry = ModelsRegistry::new();
+    let mut families: BTreeMap<String, TokenUsage> = BTreeMap::new();
+    for (model, usage) in &total.by_model {
+        let family = registry
+            .get_model_family(model)
+            .unwrap_or_else(|| "unknown".to_string());
+        families.entry(family).or_default().add(usage);
+    }
+    let mut models: Vec<DigestModel> = families
+        .into_iter()
+        .map(|(family, usage)| DigestModel {
+            family,
+            cost: usage.total_cost,
+            total_tokens: usage.total_tokens(),
+            share: share(usage.total_cost),
+        })
+        .collect();
+    models.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+
+    let cycle = BillingCycle::containing(today, cycle_start_day);
+    let projection = CycleForecast::new(
+        cycle,
+        today,
+        cycle.spent_to_date(daily_map, today),
+        total.total_cost / days as f64,
+        monthly_limit,
+    );
+
+    let session_count = sessions.len();
+    sessions.truncate(TOP_COUNT);
+    Ok(Digest {
+        period: period.adjective().to_string(),
+        start,
+        end,
+        generated_at: Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        currency: currency::current().code.clone(),
+        total_cost: total.total_cost,
+        total_tokens: total.total_tokens(),
+        previous_cost,
+        cost_change: (previous_cost > 0.0)
+            .then(|| (total.total_cost - previous_cost) / previous_cost * 100.0),
+        sessions: session_count,
+        active_days,
+        top_sessions: sessions,
+        top_projects,
+        models,
+        anomalies: period_anomalies(anomalies, start, end),
+        projection,
+        kind: period,
+    })
+}
+
+fn period_anomalies(report: &AnomalyReport, start: NaiveDate, end: NaiveDate) -> Vec<String> {
+    let days = report
+        .cost_anomalies
+        .iter()
+        .filter(|anomaly| (start..=end).contains(&anomaly.date))
+        .map(|anomaly| {
+            format!(
+                "{}: cost {:.2} was {:.1}σ above the {}-day mean of {:.2}",
+                anomaly.date,
+                Money(anomaly.cost),
+                anomaly.z_score,
+                report.window_days,
+                Money(anomaly.baseline_mean)
+            )
+        });
+    let sessions = report
+        .session_anomalies
+        .iter()
+        .filter(|anomaly| {
+            (start..=end).contains(&anomaly.last_activity.with_timezone(&Local).date_naive())
+        })
+        .map(|anomaly| {
+            format!(
+                "Session `{}` used {:.0} tokens per message, {:.1}σ above typical ({:.0})",
+                anomaly.session, anomaly.tokens_per_message, anomaly.z_score, anomaly.baseline_mean
+            )
+        });
+    days.chain(sessions).collect()
+}
+
+/// The opening sentence: spend, its change, and how much activity it covers
+pub fn headline(digest: &Digest) -> String {
+    let span = match digest.kind {
+        DigestPeriod::Day => format!("on {}", digest.end),
+        _ => format!("from {} to {}", digest.start, digest.end),
+    };
+    let change = match digest.cost_change {
+        Some(change) if change.abs() < 0.05 => {
+            format!(", the same as {}", digest.kind.previous())
+        }
+        Some(change) => format!(
+            ", **{} {:.1}%** from {:.2} {}",
+            if change > 0.0 { "up" } else { "down" },
+            change.abs(),
+            Money(digest.previous_cost),
+            digest.kind.previous()
+        ),
+        None => format!(
+            ", with no usage {} to compare against",
+            digest.kind.previous()
+        ),
+    };
+    format!(
+        "You spent **{:.2}** {}{}, across {} on {} ({} tokens).",
+        Money(digest.total_cost),
+        span,
+        change,
+        plural(digest.sessions, "session"),
+        plural(digest.active_days, "active day"),
+        format_number(digest.total_tokens)
+    )
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// The projection sentence for the billing cycle
+pub fn projection_line(digest: &Digest) -> String {
+    let projection = &digest.projection;
+    let mut line = format!(
+        "At this pace ({:.2}/day) the billing cycle ending {} lands at **{:.2}** ({:.2} spent so far)",
+        Money(digest.total_cost / digest.kind.days() as f64),
+        projection.cycle_end,
+        Money(projection.projected_total),
+        Money(projection.spent_to_date)
+    );
+    match (projection.limit, projection.projected_overage) {
+        (Some(limit), Some(overage)) => {
+            let _ = write!(
+                line,
+                ", **{:.2} over** the {:.2} limit",
+                Money(overage),
+                Money(limit)
+            );
+        }
+        (Some(limit), None) => {
+            let _ = write!(line, ", within the {:.2} limit", Money(limit));
+        }
+        _ => {}
+    }
+    line.push('.');
+    line
+}
+
+pub fn digest_to_markdown(digest: &Digest) -> String {
+    let mut title = digest.period.clone();
+    title[..1].make_ascii_uppercase();
+    let mut md = format!(
+        "# Claude Code {} Digest — {}\n\n",
+        title,
+        if digest.kind == DigestPeriod::Day {
+            digest.end.to_string()
+        } else {
+            format!("{} to {}", digest.start, digest.end)
+        }
+    );
+    let _ = writeln!(md, "{}\n", headline(digest));
+
+    if !digest.top_sessions.is_empty() {
+        md.push_str("## Top sessions\n\n");
+        md.push_str("| # | Session | Project | Cost | Tokens |\n|---:|---|---|---:|---:|\n");
+        for (i, session) in digest.top_sessions.iter().enumerate() {
+            let name = session.summary.as_deref().unwrap_or(&session.session_id);
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {:.2} | {} |",
+                i + 1,
+                escape(&truncate(name, 60)),
+                escape(&session.project),
+                Money(session.cost),
+                format_number(session.total_tokens)
+            );
+        }
+        md.push('\n');
+    }
+
+    if !digest.top_projects.is_empty() {
+        md.push_str("## Top projects\n\n");
+        md.push_str("| Project | Sessions | Cost | Share |\n|---|---:|---:|---:|\n");
+        for project in &digest.top_projects {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {:.2} | {:.1}% |",
+                escape(&project.project),
+                project.sessions,
+                Money(project.cost),
+                project.share
+            );
+        }
+        md.push('\n');
+    }
+
+    if !digest.models.is_empty() {
+        md.push_str("## Model mix\n\n");
+        for model in &digest.models {
+            let _ = writeln!(
+                md,
+                "- **{}**: {:.2} ({:.1}%), {} tokens",
+                model.family,
+                Money(model.cost),
+                model.share,
+                format_number(model.total_tokens)
+            );
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Anomalies\n\n");
+    if digest.anomalies.is_empty() {
+        md.push_str("Nothing unusual.\n\n");
+    } else {
+        for anomaly in &digest.anomalies {
+            let _ = writeln!(md, "- {}", anomaly);
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Projection\n\n");
+    let _ = writeln!(md, "{}\n", projection_line(digest));
+    let _ = writeln!(md, "_Generated {} by claudelytics._", digest.generated_at);
+    md
+}
+
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        let truncated: String = text.chars().take(max - 1).collect();
+        format!("{}…", truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(cost: f64, model: &str, session: &str) -> TokenUsage {
+        TokenUsage {
+            input_tokens: 1000,
+            total_cost: cost,
+            ..Default::default()
+        }
+        .tagged(model, session)
+    }
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, d).unwrap()
+    }
+
+    fn empty_anomalies() -> AnomalyReport {
+        AnomalyReport {
+            threshold: 3.0,
+            window_days: 30,
+            cost_anomalies: Vec::new(),
+            session_anomalies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_digest() {
+        let mut daily_map = DailyUsageMap::new();
+        daily_map.insert(date(14), usage(3.0, "claude-opus-4-20250514", "-p-api/s1"));
+        let mut day = usage(1.0, "claude-sonnet-4-20250514", "-p-web/s2");
+        day.add(&usage(2.0, "claude-sonnet-4-20250514", "-p-api/s3"));
+        daily_map.insert(date(10), day);
+        // The week before
+        daily_map.insert(date(5), usage(4.0, "claude-opus-4-20250514", "-p-api/s0"));
+
+        let summaries = HashMap::from([("-p-api/s1".to_string(), "Fix | parser".to_string())]);
+        let digest = build_digest(
+            &daily_map,
+            &summaries,
+            &empty_anomalies(),
+            DigestPeriod::Week,
+            date(14),
+            1,
+            Some(10.0),
+        )
+        .unwrap();
+
+        assert_eq!((digest.start, digest.end), (date(8), date(14)));
+        assert_eq!(digest.total_cost, 6.0);
+        assert_eq!(digest.previous_cost, 4.0);
+        assert_eq!(digest.cost_change, Some(50.0));
+        assert_eq!((digest.sessions, digest.active_days), (3, 2));
+        assert_eq!(digest.top_sessions[0].session_id, "s1");
+        assert_eq!(digest.top_projects[0].project, "-p-api");
+        assert_eq!(digest.top_projects[0].sessions, 2);
+        assert_eq!(digest.top_projects[0].share, 5.0 / 6.0 * 100.0);
+        assert_eq!(digest.models.len(), 2);
+        assert_eq!(digest.models[0].cost, 3.0);
+        // 10 spent this cycle, 17 days left at 6/7 a day
+        assert_eq!(digest.projection.spent_to_date, 10.0);
+        assert!(digest.projection.projected_overage.is_some());
+
+        let md = digest_to_markdown(&digest);
+        assert!(md.starts_with("# Claude Code Weekly Digest — 2024-03-08 to 2024-03-14"));
+        assert!(md.contains("**up 50.0%** from $4.00 the week before"));
+        assert!(md.contains("| 1 | Fix \\| parser | -p-api | $3.00 | 1,000 |"));
+        assert!(md.contains("Nothing unusual."));
+        assert!(md.contains("over** the $10.00 limit"));
+    }
+
+    #[test]
+    fn test_headline_without_previous_usage() {
+        let mut daily_map = DailyUsageMap::new();
+        daily_map.insert(date(14), usage(1.0, "claude-haiku", "-p/s"));
+        let digest = build_digest(
+            &daily_map,
+            &HashMap::new(),
+            &empty_anomalies(),
+            DigestPeriod::Day,
+            date(14),
+            1,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            headline(&digest),
+            "You spent **$1.00** on 2024-03-14, with no usage the day before to compare against, across 1 session on 1 active day (1,000 tokens)."
+        );
+    }
+}
@@ -0,0 +1,466 @@
+//! Disk footprint of the Claude directories (`disk` command)
+//!
+//! Sizes every project directory under `projects/` with its session files,
+//! estimates growth from file modification times, and sets each project's
+//! footprint against the usage it recorded. Projects that take a lot of space,
+//! have been idle for a while, and cost little per megabyte are the ones worth
+//! archiving first with `claudelytics prune`.
+
+use crate::compression;
+use crate::currency::Money;
+use crate::display::{format_number, truncate_text};
+use crate::helpers::format_size;
+use crate::models::SessionUsageMap;
+use crate::reports::parse_session_path;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use colored::Colorize;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Window of the growth estimate
+const GROWTH_DAYS: i64 = 30;
+/// Projects idle for at least this long are suggested for archiving
+const IDLE_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectDisk {
+    /// Directory name under `projects/`
+    pub project: String,
+    pub bytes: u64,
+    pub session_files: usize,
+    /// Size of the files written to in the last 30 days
+    pub recent_bytes: u64,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    /// Cost recorded per megabyte on disk
+    pub cost_per_mb: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LargeFile {
+    pub path: PathBuf,
+    pub project: String,
+    pub session_id: String,
+    pub bytes: u64,
+    pub modified: DateTime<Utc>,
+    /// Cost of the session the file belongs to, if it recorded usage
+    pub total_cost: Option<f64>,
+}
+
+/// Size of the files last written in each of the two most recent windows
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskGrowth {
+    pub days: i64,
+    pub last_period_bytes: u64,
+    pub previous_period_bytes: u64,
+    pub bytes_per_day: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskReport {
+    pub claude_dirs: Vec<PathBuf>,
+    /// Everything under the Claude directories, including caches and settings
+    pub total_bytes: u64,
+    pub projects_bytes: u64,
+    pub session_files: usize,
+    /// Largest project first
+    pub projects: Vec<ProjectDisk>,
+    /// Top-level directories other than `projects/`, largest first
+    pub other_dirs: Vec<(String, u64)>,
+    pub largest_files: Vec<LargeFile>,
+    pub growth: DiskGrowth,
+    /// Idle projects, largest first: the ones to archive first
+    pub archive_first: Vec<String>,
+}
+
+/// A session file found under `projects/`
+struct SessionFile {
+    path: PathBuf,
+    project: String,
+    session_id: String,
+    bytes: u64,
+    modified: DateTime<Utc>,
+}
+
+pub fn build_disk_report(
+    claude_dirs: &[PathBuf],
+    session_map: &SessionUsageMap,
+    now: DateTime<Utc>,
+    top: usize,
+) -> Result<DiskReport> {
+    let mut files = Vec::new();
+    let mut total_bytes = 0;
+    let mut other_dirs: HashMap<String, u64> = HashMap::new();
+    for claude_dir in claude_dirs {
+        let projects = claude_dir.join("projects");
+        for entry in WalkDir::new(claude_dir).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let path = entry.path();
+            total_bytes += metadata.len();
+            let Ok(relative) = path.strip_prefix(&projects) else {
+                let top_level = path
+                    .strip_prefix(claude_dir)
+                    .ok()
+                    .and_then(|p| p.components().next())
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                *other_dirs.entry(top_level).or_default() += metadata.len();
+                continue;
+            };
+            let Some(project) = relative
+                .components()
+                .next()
+                .filter(|_| relative.components().count() > 1)
+            else {
+                continue;
+            };
+            files.push(SessionFile {
+                path: path.to_path_buf(),
+                project: project.as_os_str().to_string_lossy().into_owned(),
+                session_id: session_id_of(path),
+                bytes: metadata.len(),
+                modified: metadata.modified()?.into(),
+            });
+        }
+    }
+    Ok(summarize(
+        claude_dirs,
+        files,
+        total_bytes,
+        other_dirs,
+        session_map,
+        now,
+        top,
+    ))
+}
+
+/// Session ID a file belongs to: its stem, or for a subagent log the session
+/// directory it sits in
+fn session_id_of(path: &Path) -> String {
+    let stem = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(compression::session_stem);
+    let parent = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|name| name.to_str());
+    match (stem, parent) {
+        (Some(stem), Some(parent)) if stem.starts_with("agent-") => parent.to_string(),
+        (Some(stem), _) => stem.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn summarize(
+    claude_dirs: &[PathBuf],
+    files: Vec<SessionFile>,
+    total_bytes: u64,
+    other_dirs: HashMap<String, u64>,
+    session_map: &SessionUsageMap,
+    now: DateTime<Utc>,
+    top: usize,
+) -> DiskReport {
+    let window_start = now - Duration::days(GROWTH_DAYS);
+    let previous_start = window_start - Duration::days(GROWTH_DAYS);
+
+    let mut usage_by_project: HashMap<String, (f64, u64)> = HashMap::new();
+    for (key, (usage, _)) in session_map {
+        let entry = usage_by_project
+            .entry(parse_session_path(key).0)
+            .or_default();
+        entry.0 += usage.total_cost;
+        entry.1 += usage.total_tokens();
+    }
+
+    let mut projects: HashMap<&str, ProjectDisk> = HashMap::new();
+    let (mut last_period_bytes, mut previous_period_bytes) = (0, 0);
+    for file in &files {
+        let project = projects
+            .entry(file.project.as_str())
+            .or_insert_with(|| ProjectDisk {
+                project: file.project.clone(),
+                bytes: 0,
+                session_files: 0,
+                recent_bytes: 0,
+                last_modified: None,
+                total_cost: 0.0,
+                total_tokens: 0,
+                cost_per_mb: 0.0,
+            });
+        project.bytes += file.bytes;
+        if compression::is_session_file(&file.path) {
+            project.session_files += 1;
+        }
+        project.last_modified = project.last_modified.max(Some(file.modified));
+        if file.modified >= window_start {
+            project.recent_bytes += file.bytes;
+            last_period_bytes += file.bytes;
+        } else if file.modified >= previous_start {
+            previous_period_bytes += file.bytes;
+        }
+    }
+
+    let mut projects: Vec<ProjectDisk> = projects
+        .into_values()
+        .map(|mut project| {
+            let (cost, tokens) = usage_by_project
+                .get(&project.project)
+                .copied()
+                .unwrap_or_default();
+            project.total_cost = cost;
+            project.total_tokens = tokens;
+            project.cost_per_mb = if project.bytes > 0 {
+                cost / (project.bytes as f64 / 1_048_576.0)
+            } else {
+                0.0
+            };
+            project
+        })
+        .collect();
+    projects.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.project.cmp(&b.project)));
+
+    let idle_since = now - Duration::days(IDLE_DAYS);
+    let archive_first = projects
+        .iter()
+        .filter(|p| p.last_modified.is_some_and(|m| m < idle_since))
+        .take(top)
+        .map(|p| p.project.clone())
+        .collect();
+
+    let mut largest: Vec<&SessionFile> = files
+        .iter()
+        .filter(|f| compression::is_session_file(&f.path))
+        .collect();
+    largest.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.path.cmp(&b.path)));
+    let largest_files = largest
+        .into_iter()
+        .take(top)
+        .map(|file| LargeFile {
+            path: file.path.clone(),
+            project: file.project.clone(),
+            session_id: file.session_id.clone(),
+            bytes: file.bytes,
+            modified: file.modified,
+            total_cost: session_map
+                .get(&format!("{}/{}", file.project, file.session_id))
+                .map(|(usage, _)| usage.total_cost),
+        })
+        .collect();
+
+    let mut other_dirs: Vec<(String, u64)> = other_dirs.into_iter().collect();
+    other_dirs.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    DiskReport {
+        claude_dirs: claude_dirs.to_vec(),
+        total_bytes,
+        projects_bytes: projects.iter().map(|p| p.bytes).sum(),
+        session_files: projects.iter().map(|p| p.session_files).sum(),
+        projects,
+        other_dirs,
+        largest_files,
+        growth: DiskGrowth {
+            days: GROWTH_DAYS,
+            last_period_bytes,
+            previous_period_bytes,
+            bytes_per_day: last_period_bytes as f64 / GROWTH_DAYS as f64,
+        },
+        archive_first,
+    }
+}
+
+pub fn display_disk_report(report: &DiskReport, top: usize, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "CLAUDE DISK USAGE".bold());
+    println!("{}", "═".repeat(72));
+    for dir in &report.claude_dirs {
+        println!("  {:<18} {}", "Directory:".bold(), dir.display());
+    }
+    println!(
+        "  {:<18} {} ({} in projects, {} session files)",
+        "Total:".bold(),
+        format_size(report.total_bytes).green(),
+        format_size(report.projects_bytes),
+        report.session_files
+    );
+    let growth = &report.growth;
+    println!(
+        "  {:<18} {} written in the last {} days (~{}/day), {} in the {} days before",
+        "Growth:".bold(),
+        format_size(growth.last_period_bytes).yellow(),
+        growth.days,
+        format_size(growth.bytes_per_day as u64),
+        format_size(growth.previous_period_bytes),
+        growth.days
+    );
+    if !report.other_dirs.is_empty() {
+        let others: Vec<String> = report
+            .other_dirs
+            .iter()
+            .take(5)
+            .map(|(name, bytes)| format!("{} {}", name, format_size(*bytes)))
+            .collect();
+        println!("  {:<18} {}", "Other:".bold(), others.join(", "));
+    }
+
+    if report.projects.is_empty() {
+        println!("\n  No session files found");
+        return;
+    }
+
+    let now = Utc::now();
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec![
+            Cell::new("Project").fg(Color::Cyan),
+            Cell::new("Files").fg(Color::White),
+            Cell::new("Size").fg(Color::White),
+            Cell::new("Share").fg(Color::White),
+            Cell::new("Last 30d").fg(Color::Yellow),
+            Cell::new("Idle").fg(Color::Yellow),
+            Cell::new("Cost").fg(Color::Green),
+            Cell::new("Tokens").fg(Color::Magenta),
+            Cell::new("Cost/MB").fg(Color::Green),
+        ]);
+    for project in report.projects.iter().take(top) {
+        let share = if report.projects_bytes > 0 {
+            project.bytes as f64 / report.projects_bytes as f64 * 100.0
+        } else {
+            0.0
+        };
+        let idle = project
+            .last_modified
+            .map(|m| format!("{}d", (now - m).num_days()))
+            .unwrap_or_default();
+        table.add_row(vec![
+            Cell::new(truncate_text(&project.project, 40)),
+            Cell::new(project.session_files),
+            Cell::new(format_size(project.bytes)),
+            Cell::new(format!("{:.1}%", share)),
+            Cell::new(format_size(project.recent_bytes)).fg(Color::Yellow),
+            Cell::new(idle),
+            Cell::new(format!("{:.2}", Money(project.total_cost))).fg(Color::Green),
+            Cell::new(format_number(project.total_tokens)).fg(Color::Magenta),
+            Cell::new(format!("{:.2}", Money(project.cost_per_mb))).fg(Color::Green),
+        ]);
+    }
+    println!("\n{}", "Largest Projects".bold());
+    println!("{table}");
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec![
+            Cell::new("Project").fg(Color::Cyan),
+            Cell::new("Session").fg(Color::White),
+            Cell::new("Size").fg(Color::White),
+            Cell::new("Modified").fg(Color::Yellow),
+            Cell::new("Cost").fg(Color::Green),
+        ]);
+    for file in &report.largest_files {
+        table.add_row(vec![
+            Cell::new(truncate_text(&file.project, 40)),
+            Cell::new(file.session_id.chars().take(8).collect::<String>()),
+            Cell::new(format_size(file.bytes)),
+            Cell::new(
+                file.modified
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d"),
+            ),
+            Cell::new(
+                file.total_cost
+                    .map(|cost| format!("{:.2}", Money(cost)))
+                    .unwrap_or_else(|| "-".to_string()),
+            )
+            .fg(Color::Green),
+        ]);
+    }
+    println!("\n{}", "Largest Session Files".bold());
+    println!("{table}");
+
+    if !report.archive_first.is_empty() {
+        println!(
+            "\n{} {}",
+            format!("Archive first (idle {}+ days, largest first):", IDLE_DAYS).bold(),
+            report.archive_first.join(", ")
+        );
+        println!(
+            "  {}",
+            format!(
+                "claudelytics prune --older-than {}d --archive ~/claude-archive --dry-run",
+                IDLE_DAYS
+            )
+            .dimmed()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+
+    #[test]
+    fn test_summarize() {
+        let now = Utc::now();
+        let file = |project: &str, session: &str, bytes: u64, age_days: i64| SessionFile {
+            path: PathBuf::from(format!("/c/projects/{}/{}.jsonl", project, session)),
+            project: project.to_string(),
+            session_id: session.to_string(),
+            bytes,
+            modified: now - Duration::days(age_days),
+        };
+        let files = vec![
+            file("-old", "a", 4 * 1_048_576, 90),
+            file("-old", "b", 1_048_576, 45),
+            file("-new", "c", 2 * 1_048_576, 1),
+        ];
+        let mut session_map = SessionUsageMap::new();
+        let usage = TokenUsage {
+            input_tokens: 1000,
+            total_cost: 5.0,
+            ..Default::default()
+        };
+        session_map.insert("-old/a".to_string(), (usage, now));
+
+        let report = summarize(
+            &[PathBuf::from("/c")],
+            files,
+            8 * 1_048_576,
+            HashMap::from([("debug".to_string(), 1_048_576)]),
+            &session_map,
+            now,
+            10,
+        );
+        assert_eq!(report.projects_bytes, 7 * 1_048_576);
+        assert_eq!(report.session_files, 3);
+        assert_eq!(report.projects[0].project, "-old");
+        assert_eq!(report.projects[0].cost_per_mb, 1.0);
+        assert_eq!(report.projects[1].recent_bytes, 2 * 1_048_576);
+        assert_eq!(report.growth.last_period_bytes, 2 * 1_048_576);
+        assert_eq!(report.growth.previous_period_bytes, 1_048_576);
+        assert_eq!(report.archive_first, ["-old"]);
+        assert_eq!(report.largest_files[0].total_cost, Some(5.0));
+        assert_eq!(report.largest_files[1].total_cost, None);
+    }
+}
@@ -0,0 +1,130 @@
+use super::helpers::{format_currency, format_number, truncate_path};
+use crate::projections::AnomalyReport;
+use crate::terminal::Terminal;
+use chrono::Local;
+use colored::*;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+
+/// Anomalies listed in the daily enhanced report before pointing to `anomalies`
+const MAX_WARNINGS: usize = 3;
+
+/// Cost spikes and token-heavy sessions (`anomalies` command)
+pub fn display_anomaly_report(report: &AnomalyReport) {
+    println!(
+        "{}",
+        format!(
+            "🚨 Usage anomalies (> {:.1}σ above the trailing {}-day mean)",
+            report.threshold, report.window_days
+        )
+        .bright_blue()
+        .bold()
+    );
+
+    if report.cost_anomalies.is_empty() {
+        println!("{}", "No daily cost spikes found.".green());
+    } else {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_header(vec![
+                Cell::new("Date").fg(Color::Cyan),
+                Cell::new("Cost").fg(Color::Red),
+                Cell::new("Trailing Mean").fg(Color::Cyan),
+                Cell::new("Std Dev").fg(Color::Cyan),
+                Cell::new("σ").fg(Color::Yellow),
+            ]);
+        for anomaly in &report.cost_anomalies {
+            table.add_row(vec![
+                Cell::new(anomaly.date.format("%Y-%m-%d")),
+                Cell::new(format_currency(anomaly.cost)).fg(Color::Red),
+                Cell::new(format_currency(anomaly.baseline_mean)),
+                Cell::new(format_currency(anomaly.baseline_std_dev)),
+                Cell::new(format!("{:.1}", anomaly.z_score)).fg(Color::Yellow),
+            ]);
+        }
+        println!("\n{}", "Daily cost spikes".bold());
+        println!("{table}");
+    }
+
+    if report.session_anomalies.is_empty() {
+        println!("{}", "No token-heavy sessions found.".green());
+    } else {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_header(vec![
+                Cell::new("Session").fg(Color::Cyan),
+                Cell::new("Last Activity").fg(Color::Cyan),
+                Cell::new("Messages").fg(Color::Cyan),
+                Cell::new("Tokens/Message").fg(Color::Magenta),
+                Cell::new("Typical").fg(Color::Cyan),
+                Cell::new("σ").fg(Color::Yellow),
+                Cell::new("Cost").fg(Color::Red),
+            ]);
+        for anomaly in &report.session_anomalies {
+            table.add_row(vec![
+                Cell::new(truncate_path(&anomaly.session, 48)),
+                Cell::new(
+                    anomaly
+                        .last_activity
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M"),
+                ),
+                Cell::new(anomaly.messages),
+                Cell::new(format_number(anomaly.tokens_per_message as u64)).fg(Color::Magenta),
+                Cell::new(format_number(anomaly.baseline_mean as u64)),
+                Cell::new(format!("{:.1}", anomaly.z_score)).fg(Color::Yellow),
+                Cell::new(format_currency(anomaly.cost)).fg(Color::Red),
+            ]);
+        }
+        println!(
+            "\n{}",
+            "Sessions with unusually many tokens per message".bold()
+        );
+        println!("{table}");
+    }
+}
+
+/// Short warning block for the daily enhanced report; prints nothing without anomalies
+pub fn display_anomaly_warnings(report: &AnomalyReport) {
+    if report.is_empty() {
+        return;
+    }
+
+    println!("{}", "⚠️  ANOMALIES".bright_yellow().bold());
+    println!("{}", Terminal::separator('─').bright_black());
+    for anomaly in report.cost_anomalies.iter().take(MAX_WARNINGS) {
+        println!(
+            "{} cost {} is {:.1}σ above the {}-day mean of {}",
+            anomaly.date.format("%Y-%m-%d").to_string().bright_yellow(),
+            format_currency(anomaly.cost).bright_red(),
+            anomaly.z_score,
+            report.window_days,
+            format_currency(anomaly.baseline_mean)
+        );
+    }
+    for anomaly in report.session_anomalies.iter().take(MAX_WARNINGS) {
+        println!(
+            "Session {} uses {} tokens/message ({:.1}σ above typical {})",
+            truncate_path(&anomaly.session, 40).bright_yellow(),
+            format_number(anomaly.tokens_per_message as u64).bright_red(),
+            anomaly.z_score,
+            format_number(anomaly.baseline_mean as u64)
+        );
+    }
+
+    let hidden = report.cost_anomalies.len().saturating_sub(MAX_WARNINGS)
+        + report.session_anomalies.len().saturating_sub(MAX_WARNINGS);
+    if hidden > 0 {
+        println!(
+            "{}",
+            format!(
+                "…and {} more. Run 'claudelytics anomalies' for details.",
+                hidden
+            )
+            .dimmed()
+        );
+    }
+}
@@ -1,31 +1,50 @@
+use super::anomalies::display_anomaly_warnings;
 use super::helpers::{format_currency, format_number};
+use super::model_breakdown::capitalize_family_name;
 use super::summary::display_enhanced_summary_card;
 use crate::burn_rate::BurnRateCalculator;
+use crate::currency::{self, Money};
 use crate::models::DailyReport;
+use crate::projections::AnomalyReport;
 use crate::responsive_tables::ResponsiveTable;
+use crate::session_timeline::SPARK_LEVELS;
 use crate::terminal::{DisplayMode, Terminal};
-use chrono::Local;
+use chrono::{Duration, Local, NaiveDate};
 use colored::*;
 use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use std::collections::HashMap;
+
+/// Days covered by the sparklines at the top of the enhanced report
+const TREND_DAYS: i64 = 30;
 
 /// Compact default output: a simple table like ccusage
 pub fn display_daily_report_compact(report: &DailyReport) {
+    let show_sources = report.daily.iter().any(|entry| !entry.sources.is_empty());
+    let show_thinking = report.totals.thinking_tokens > 0;
+    let mut headers = vec![
+        Cell::new("Date").fg(Color::Cyan),
+        Cell::new("Input").fg(Color::Green),
+        Cell::new("Output").fg(Color::Yellow),
+        Cell::new("Cache Write").fg(Color::Magenta),
+        Cell::new("Cache Read").fg(Color::Magenta),
+        Cell::new("Total Tokens").fg(Color::White),
+        Cell::new(currency::cost_header()).fg(Color::Red),
+    ];
+    if show_thinking {
+        headers.push(Cell::new("Thinking").fg(Color::Yellow));
+    }
+    if show_sources {
+        headers.push(Cell::new("By Source").fg(Color::Blue));
+    }
+
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .apply_modifier(UTF8_ROUND_CORNERS)
-        .set_header(vec![
-            Cell::new("Date").fg(Color::Cyan),
-            Cell::new("Input").fg(Color::Green),
-            Cell::new("Output").fg(Color::Yellow),
-            Cell::new("Cache Write").fg(Color::Magenta),
-            Cell::new("Cache Read").fg(Color::Magenta),
-            Cell::new("Total Tokens").fg(Color::White),
-            Cell::new("Cost (USD)").fg(Color::Red),
-        ]);
+        .set_header(headers);
 
     for entry in &report.daily {
-        table.add_row(vec![
+        let mut row = vec![
             Cell::new(&entry.date),
             Cell::new(format_number(entry.input_tokens)).fg(Color::Green),
             Cell::new(format_number(entry.output_tokens)).fg(Color::Yellow),
@@ -33,13 +52,37 @@ pub fn display_daily_report_compact(report: &DailyReport) {
             Cell::new(format_number(entry.cache_read_tokens)).fg(Color::Magenta),
             Cell::new(format_number(entry.total_tokens)),
             Cell::new(format_currency(entry.total_cost)).fg(Color::Red),
-        ]);
+        ];
+        if show_thinking {
+            row.push(Cell::new(format_number(entry.thinking_tokens)).fg(Color::Yellow));
+        }
+        if show_sources {
+            row.push(Cell::new(sources_cell(&entry.sources)).fg(Color::Blue));
+        }
+        table.add_row(row);
+        for model in &entry.models {
+            let mut row = vec![
+                Cell::new(model_label(model)).fg(Color::DarkGrey),
+                Cell::new(format_number(model.input_tokens)).fg(Color::DarkGrey),
+                Cell::new(format_number(model.output_tokens)).fg(Color::DarkGrey),
+                Cell::new(format_number(model.cache_creation_tokens)).fg(Color::DarkGrey),
+                Cell::new(format_number(model.cache_read_tokens)).fg(Color::DarkGrey),
+                Cell::new(format_number(model.total_tokens)).fg(Color::DarkGrey),
+                Cell::new(format_currency(model.total_cost)).fg(Color::DarkGrey),
+            ];
+            if show_thinking {
+                row.push(Cell::new(""));
+            }
+            if show_sources {
+                row.push(Cell::new(""));
+            }
+            table.add_row(row);
+        }
     }
 
     // Totals row
     if report.daily.len() > 1 {
-        let cache_tokens = report.totals.cache_creation_tokens + report.totals.cache_read_tokens;
-        table.add_row(vec![
+        let mut row = vec![
             Cell::new("Total").fg(Color::Yellow),
             Cell::new(format_number(report.totals.input_tokens)).fg(Color::Yellow),
             Cell::new(format_number(report.totals.output_tokens)).fg(Color::Yellow),
@@ -47,14 +90,24 @@ pub fn display_daily_report_compact(report: &DailyReport) {
             Cell::new(format_number(report.totals.cache_read_tokens)).fg(Color::Yellow),
             Cell::new(format_number(report.totals.total_tokens)).fg(Color::Yellow),
             Cell::new(format_currency(report.totals.total_cost)).fg(Color::Yellow),
-        ]);
-        let _ = cache_tokens; // used above in individual cells
+        ];
+        if show_thinking {
+            row.push(Cell::new(format_number(report.totals.thinking_tokens)).fg(Color::Yellow));
+        }
+        if show_sources {
+            row.push(Cell::new(""));
+        }
+        table.add_row(row);
     }
 
     println!("{table}");
 }
 
-pub fn display_daily_report_enhanced(report: &DailyReport, _force_compact: bool) {
+pub fn display_daily_report_enhanced(
+    report: &DailyReport,
+    anomalies: &AnomalyReport,
+    _force_compact: bool,
+) {
     // Header with timestamp and separator
     let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
     println!("{}", Terminal::separator('═').bright_black());
@@ -66,6 +119,12 @@ pub fn display_daily_report_enhanced(report: &DailyReport, _force_compact: bool)
     println!("{}", Terminal::separator('═').bright_black());
     println!();
 
+    let trend = DailyTrend::new(&report.daily, Local::now().date_naive(), TREND_DAYS);
+    if trend.has_usage() {
+        display_trend_sparklines(&trend);
+        println!();
+    }
+
     // Quick summary card with insights
     display_enhanced_summary_card(&report.totals, report.daily.len());
     println!();
@@ -76,6 +135,11 @@ pub fn display_daily_report_enhanced(report: &DailyReport, _force_compact: bool)
         println!();
     }
 
+    if !anomalies.is_empty() {
+        display_anomaly_warnings(anomalies);
+        println!();
+    }
+
     // Recent activity with better visual separation
     if !report.daily.is_empty() {
         display_enhanced_recent_activity(&report.daily);
@@ -111,22 +175,28 @@ pub fn display_daily_report_enhanced(report: &DailyReport, _force_compact: bool)
 }
 
 pub fn display_daily_report_table(report: &DailyReport) {
+    let show_thinking = report.totals.thinking_tokens > 0;
+    let mut headers = vec![
+        Cell::new("Date").fg(Color::Cyan),
+        Cell::new("Input Tokens").fg(Color::Cyan),
+        Cell::new("Output Tokens").fg(Color::Cyan),
+        Cell::new("Cache Creation").fg(Color::Cyan),
+        Cell::new("Cache Read").fg(Color::Cyan),
+        Cell::new("Total Tokens").fg(Color::Cyan),
+        Cell::new(currency::cost_header()).fg(Color::Cyan),
+    ];
+    if show_thinking {
+        headers.push(Cell::new("Thinking Tokens").fg(Color::Cyan));
+    }
+
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .apply_modifier(UTF8_ROUND_CORNERS)
-        .set_header(vec![
-            Cell::new("Date").fg(Color::Cyan),
-            Cell::new("Input Tokens").fg(Color::Cyan),
-            Cell::new("Output Tokens").fg(Color::Cyan),
-            Cell::new("Cache Creation").fg(Color::Cyan),
-            Cell::new("Cache Read").fg(Color::Cyan),
-            Cell::new("Total Tokens").fg(Color::Cyan),
-            Cell::new("Cost (USD)").fg(Color::Cyan),
-        ]);
+        .set_header(headers);
 
     for daily in &report.daily {
-        table.add_row(vec![
+        let mut row = vec![
             Cell::new(&daily.date),
             Cell::new(format_number(daily.input_tokens)),
             Cell::new(format_number(daily.output_tokens)),
@@ -134,12 +204,16 @@ pub fn display_daily_report_table(report: &DailyReport) {
             Cell::new(format_number(daily.cache_read_tokens)),
             Cell::new(format_number(daily.total_tokens)),
             Cell::new(format_currency(daily.total_cost)),
-        ]);
+        ];
+        if show_thinking {
+            row.push(Cell::new(format_number(daily.thinking_tokens)));
+        }
+        table.add_row(row);
     }
 
     // Add totals row
     if !report.daily.is_empty() {
-        table.add_row(vec![
+        let mut row = vec![
             Cell::new("Total").fg(Color::Yellow),
             Cell::new(format_number(report.totals.input_tokens)).fg(Color::Yellow),
             Cell::new(format_number(report.totals.output_tokens)).fg(Color::Yellow),
@@ -147,7 +221,11 @@ pub fn display_daily_report_table(report: &DailyReport) {
             Cell::new(format_number(report.totals.cache_read_tokens)).fg(Color::Yellow),
             Cell::new(format_number(report.totals.total_tokens)).fg(Color::Yellow),
             Cell::new(format_currency(report.totals.total_cost)).fg(Color::Yellow),
-        ]);
+        ];
+        if show_thinking {
+            row.push(Cell::new(format_number(report.totals.thinking_tokens)).fg(Color::Yellow));
+        }
+        table.add_row(row);
     }
 
     println!("{}", table);
@@ -218,6 +296,7 @@ fn display_burn_rate_metrics(daily: &[crate::models::DailyUsage]) {
             cache_read_tokens: day.cache_read_tokens,
             total_cost: day.total_cost,
             fast_mode_cost: 0.0,
+            ..Default::default()
         };
         if let Ok(date) = chrono::NaiveDate::parse_from_str(&day.date, "%Y-%m-%d") {
             daily_map.insert(date, usage);
@@ -243,26 +322,26 @@ fn display_burn_rate_metrics(daily: &[crate::models::DailyUsage]) {
         };
 
         println!(
-            "24h Rate: {} tokens/hr (${:.4}/hr) {} {:.1}%",
+            "24h Rate: {} tokens/hr ({:.4}/hr) {} {:.1}%",
             format_number(metrics_24h.tokens_per_hour as u64).bright_cyan(),
-            metrics_24h.cost_per_hour,
+            Money(metrics_24h.cost_per_hour),
             trend_arrow,
             metrics_24h.trend_percentage.abs()
         );
 
         println!(
-            "Projected Today: {} tokens (${:.2})",
+            "Projected Today: {} tokens ({:.2})",
             format_number(metrics_24h.projected_daily_tokens).bright_magenta(),
-            metrics_24h.projected_daily_cost
+            Money(metrics_24h.projected_daily_cost)
         );
     }
 
     if let Some(metrics_7d) = burn_rate_7d {
         println!();
         println!(
-            "7-Day Average: {} tokens/hr (${:.4}/hr)",
+            "7-Day Average: {} tokens/hr ({:.4}/hr)",
             format_number(metrics_7d.tokens_per_hour as u64).bright_cyan(),
-            metrics_7d.cost_per_hour
+            Money(metrics_7d.cost_per_hour)
         );
 
         println!(
@@ -277,6 +356,123 @@ fn display_burn_rate_metrics(daily: &[crate::models::DailyUsage]) {
     println!("{}", Terminal::separator('─').bright_black());
 }
 
+/// Cost and tokens per day over the last `days` days, with totals of the period before
+struct DailyTrend {
+    start: NaiveDate,
+    end: NaiveDate,
+    costs: Vec<f64>,
+    tokens: Vec<u64>,
+    previous_cost: f64,
+    previous_tokens: u64,
+}
+
+impl DailyTrend {
+    /// Days without usage count as zero
+    fn new(daily: &[crate::models::DailyUsage], end: NaiveDate, days: i64) -> Self {
+        let by_date: HashMap<NaiveDate, &crate::models::DailyUsage> = daily
+            .iter()
+            .filter_map(|day| {
+                NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
+                    .ok()
+                    .map(|date| (date, day))
+            })
+            .collect();
+        let start = end - Duration::days(days - 1);
+        let range = |from: NaiveDate| (0..days).map(move |offset| from + Duration::days(offset));
+        let day = |date: NaiveDate| by_date.get(&date);
+
+        Self {
+            start,
+            end,
+            costs: range(start)
+                .map(|date| day(date).map_or(0.0, |d| d.total_cost))
+                .collect(),
+            tokens: range(start)
+                .map(|date| day(date).map_or(0, |d| d.total_tokens))
+                .collect(),
+            previous_cost: range(start - Duration::days(days))
+                .filter_map(day)
+                .map(|d| d.total_cost)
+                .sum(),
+            previous_tokens: range(start - Duration::days(days))
+                .filter_map(day)
+                .map(|d| d.total_tokens)
+                .sum(),
+        }
+    }
+
+    fn has_usage(&self) -> bool {
+        self.tokens.iter().any(|&tokens| tokens > 0)
+    }
+
+    fn cost(&self) -> f64 {
+        self.costs.iter().sum()
+    }
+
+    fn total_tokens(&self) -> u64 {
+        self.tokens.iter().sum()
+    }
+}
+
+/// One character per day scaled to the busiest day; idle days are blank
+fn sparkline(values: &[f64]) -> String {
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    values
+        .iter()
+        .map(|&value| {
+            if value <= 0.0 {
+                ' '
+            } else {
+                let level = (value / max * (SPARK_LEVELS.len() - 1) as f64).round() as usize;
+                SPARK_LEVELS[level]
+            }
+        })
+        .collect()
+}
+
+/// Percent change from `previous` to `current`, `None` without a previous value
+fn percent_change(current: f64, previous: f64) -> Option<f64> {
+    (previous > 0.0).then(|| (current - previous) / previous * 100.0)
+}
+
+fn format_change(change: Option<f64>) -> ColoredString {
+    match change {
+        Some(change) if change > 0.0 => format!("▲ {:.1}%", change).bright_red(),
+        Some(change) if change < 0.0 => format!("▼ {:.1}%", -change).bright_green(),
+        Some(_) => "= 0.0%".normal(),
+        None => "no previous data".dimmed(),
+    }
+}
+
+fn display_trend_sparklines(trend: &DailyTrend) {
+    let days = trend.costs.len();
+    println!(
+        "{}  {}",
+        format!("📉 LAST {} DAYS", days).bright_cyan().bold(),
+        format!("{} → {}", trend.start, trend.end).dimmed()
+    );
+    println!(
+        "   {:<7} {} {:>12}  {} vs previous {} days",
+        "Cost",
+        sparkline(&trend.costs).bright_green(),
+        format_currency(trend.cost()),
+        format_change(percent_change(trend.cost(), trend.previous_cost)),
+        days
+    );
+    let tokens: Vec<f64> = trend.tokens.iter().map(|&t| t as f64).collect();
+    println!(
+        "   {:<7} {} {:>12}  {} vs previous {} days",
+        "Tokens",
+        sparkline(&tokens).bright_cyan(),
+        format_number(trend.total_tokens()),
+        format_change(percent_change(
+            trend.total_tokens() as f64,
+            trend.previous_tokens as f64
+        )),
+        days
+    );
+}
+
 fn display_enhanced_recent_activity(daily: &[crate::models::DailyUsage]) {
     println!("{}", "📈 RECENT ACTIVITY TREND".bright_cyan().bold());
     println!();
@@ -399,6 +595,24 @@ fn display_daily_cards(daily: &[crate::models::DailyUsage]) {
             "  📊 O/I Ratio: {:.1}:1 │ 💡 Cache Hit Rate: {:.1}%",
             ratio, cache_efficiency
         );
+        for model in &day.models {
+            println!(
+                "  {} {} │ {} tokens │ In: {} │ Out: {}",
+                format!("{:<8}", capitalize_family_name(&model.family)).bright_white(),
+                format!("{:>10}", format_currency(model.total_cost)).bright_green(),
+                format_number(model.total_tokens).bright_cyan(),
+                format_number(model.input_tokens).green(),
+                format_number(model.output_tokens).blue()
+            );
+        }
+        for source in &day.sources {
+            println!(
+                "  {} {} │ {} tokens",
+                source.source.bright_white(),
+                format_currency(source.total_cost).bright_green(),
+                format_number(source.total_tokens).bright_cyan()
+            );
+        }
 
         if i < daily.len() - 1 {
             println!();
@@ -498,7 +712,8 @@ fn display_daily_table_complete(report: &DailyReport) {
         .apply_modifier(UTF8_ROUND_CORNERS);
 
     // Always show all columns for the Complete Daily Breakdown
-    let headers = vec![
+    let show_sources = report.daily.iter().any(|daily| !daily.sources.is_empty());
+    let mut headers = vec![
         Cell::new("Date").fg(Color::Cyan),
         Cell::new("Cost").fg(Color::Cyan),
         Cell::new("Tokens").fg(Color::Cyan),
@@ -508,6 +723,9 @@ fn display_daily_table_complete(report: &DailyReport) {
         Cell::new("Efficiency").fg(Color::Cyan),
         Cell::new("Cache Hit").fg(Color::Cyan),
     ];
+    if show_sources {
+        headers.push(Cell::new("By Source").fg(Color::Cyan));
+    }
 
     table.set_header(headers);
 
@@ -541,7 +759,7 @@ fn display_daily_table_complete(report: &DailyReport) {
                 0.0
             };
 
-        let row = vec![
+        let mut row = vec![
             Cell::new(&daily.date).fg(date_color),
             Cell::new(format!("{:>10}", format_currency(daily.total_cost))).fg(Color::Green),
             Cell::new(format_number(daily.total_tokens)).fg(Color::Magenta),
@@ -551,9 +769,92 @@ fn display_daily_table_complete(report: &DailyReport) {
             Cell::new(format!("{:.0} tok/$", tokens_per_dollar)).fg(Color::Green),
             Cell::new(format!("{:.1}%", cache_efficiency)).fg(Color::Magenta),
         ];
+        if show_sources {
+            row.push(Cell::new(sources_cell(&daily.sources)).fg(Color::Blue));
+        }
 
         table.add_row(row);
+        for model in &daily.models {
+            let mut row = vec![
+                Cell::new(model_label(model)).fg(Color::DarkGrey),
+                Cell::new(format!("{:>10}", format_currency(model.total_cost))).fg(Color::DarkGrey),
+                Cell::new(format_number(model.total_tokens)).fg(Color::DarkGrey),
+                Cell::new(format_number(model.input_tokens)).fg(Color::DarkGrey),
+                Cell::new(format_number(model.output_tokens)).fg(Color::DarkGrey),
+                Cell::new(""),
+                Cell::new(""),
+                Cell::new(""),
+            ];
+            if show_sources {
+                row.push(Cell::new(""));
+            }
+            table.add_row(row);
+        }
     }
 
     println!("{}", table);
 }
+
+/// One line per Claude directory: `label  $cost`
+fn sources_cell(sources: &[crate::models::SourceUsage]) -> String {
+    sources
+        .iter()
+        .map(|source| format!("{}  {}", source.source, format_currency(source.total_cost)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Indented label for a per-model sub-row under a daily row
+fn model_label(model: &crate::models::ModelUsage) -> String {
+    format!("  └ {}", capitalize_family_name(&model.family))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DailyUsage, TokenUsage};
+
+    fn day(date: &str, cost: f64, tokens: u64) -> DailyUsage {
+        let usage = TokenUsage {
+            input_tokens: tokens,
+            total_cost: cost,
+            ..Default::default()
+        };
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        DailyUsage::from((date, &usage))
+    }
+
+    #[test]
+    fn test_daily_trend() {
+        let end = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let daily = vec![
+            day("2024-03-10", 2.0, 200),
+            day("2024-03-08", 1.0, 100),
+            // Previous period
+            day("2024-03-06", 1.5, 600),
+            // Before either period
+            day("2024-03-01", 9.0, 900),
+        ];
+        let trend = DailyTrend::new(&daily, end, 4);
+        assert_eq!(trend.start, NaiveDate::from_ymd_opt(2024, 3, 7).unwrap());
+        assert_eq!(trend.costs, [0.0, 1.0, 0.0, 2.0]);
+        assert_eq!(trend.tokens, [0, 100, 0, 200]);
+        assert_eq!(trend.previous_cost, 1.5);
+        assert_eq!(trend.previous_tokens, 600);
+        assert!(trend.has_usage());
+
+        assert_eq!(
+            percent_change(trend.cost(), trend.previous_cost),
+            Some(100.0)
+        );
+        assert_eq!(percent_change(300.0, 600.0), Some(-50.0));
+        assert_eq!(percent_change(1.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0.0, 1.0, 4.0, 8.0]), " ▂▅█");
+        assert_eq!(sparkline(&[0.0, 0.0]), "  ");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
@@ -0,0 +1,97 @@
+use super::helpers::{format_currency, format_number};
+use crate::models::GroupReport;
+use colored::*;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+
+/// Usage rolled up by project or tag (`--group-by`)
+pub fn display_group_report_table(report: &GroupReport) {
+    let has_period = report.groups.iter().any(|g| g.period.is_some());
+    let group_label = match report.group_by.as_str() {
+        "tag" => "Tag",
+        "schedule" => "Bucket",
+        _ => "Project",
+    };
+    println!(
+        "{}",
+        format!("📊 Usage by {}", group_label.to_lowercase())
+            .bright_blue()
+            .bold()
+    );
+
+    let mut header = Vec::new();
+    if has_period {
+        header.push(Cell::new("Period").fg(Color::Cyan));
+    }
+    header.extend([
+        Cell::new(group_label).fg(Color::Cyan),
+        Cell::new("Sessions").fg(Color::Cyan),
+        Cell::new("Input Tokens").fg(Color::Green),
+        Cell::new("Output Tokens").fg(Color::Yellow),
+        Cell::new("Cache Tokens").fg(Color::Magenta),
+        Cell::new("Total Tokens").fg(Color::White),
+        Cell::new("Total Cost").fg(Color::Red),
+        Cell::new("Share").fg(Color::DarkRed),
+    ]);
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(header);
+
+    let total_cost = report.totals.total_cost;
+    for group in &report.groups {
+        let mut row = Vec::new();
+        if has_period {
+            row.push(Cell::new(group.period.as_deref().unwrap_or("")));
+        }
+        let share = if total_cost > 0.0 {
+            group.total_cost / total_cost * 100.0
+        } else {
+            0.0
+        };
+        row.extend([
+            Cell::new(&group.group),
+            Cell::new(group.sessions),
+            Cell::new(format_number(group.input_tokens)).fg(Color::Green),
+            Cell::new(format_number(group.output_tokens)).fg(Color::Yellow),
+            Cell::new(format_number(
+                group.cache_creation_tokens + group.cache_read_tokens,
+            ))
+            .fg(Color::Magenta),
+            Cell::new(format_number(group.total_tokens)),
+            Cell::new(format_currency(group.total_cost)).fg(Color::Red),
+            Cell::new(format!("{:.1}%", share)).fg(Color::DarkRed),
+        ]);
+        table.add_row(row);
+    }
+
+    let totals = &report.totals;
+    let mut row = Vec::new();
+    if has_period {
+        row.push(Cell::new("").fg(Color::Yellow));
+    }
+    row.extend([
+        Cell::new("Total").fg(Color::Yellow),
+        Cell::new("").fg(Color::Yellow),
+        Cell::new(format_number(totals.input_tokens)).fg(Color::Yellow),
+        Cell::new(format_number(totals.output_tokens)).fg(Color::Yellow),
+        Cell::new(format_number(
+            totals.cache_creation_tokens + totals.cache_read_tokens,
+        ))
+        .fg(Color::Yellow),
+        Cell::new(format_number(totals.total_tokens)).fg(Color::Yellow),
+        Cell::new(format_currency(totals.total_cost)).fg(Color::Yellow),
+        Cell::new("").fg(Color::Yellow),
+    ]);
+    table.add_row(row);
+
+    println!("{table}");
+    if report.group_by == "tag" {
+        println!(
+            "{}",
+            "Sessions with several tags count toward each of them; untagged sessions are grouped as 'untagged'."
+                .dimmed()
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::currency::Money;
 use colored::*;
 
 pub(crate) fn format_number(num: u64) -> String {
@@ -22,9 +23,9 @@ pub(crate) fn format_number(num: u64) -> String {
 
 pub(crate) fn format_currency(amount: f64) -> String {
     if amount >= 100.0 {
-        format!("${:.2}", amount)
+        format!("{:.2}", Money(amount))
     } else {
-        format!("${:.4}", amount)
+        format!("{:.4}", Money(amount))
     }
 }
 
@@ -37,10 +38,11 @@ pub(crate) fn truncate_path(path: &str, max_length: usize) -> String {
 }
 
 pub(crate) fn truncate_text(text: &str, max_length: usize) -> String {
-    if text.len() <= max_length {
+    if text.chars().count() <= max_length {
         text.to_string()
     } else {
-        format!("{}...", &text[..max_length.saturating_sub(3)])
+        let truncated: String = text.chars().take(max_length.saturating_sub(3)).collect();
+        format!("{}...", truncated)
     }
 }
 
@@ -91,5 +93,6 @@ mod tests {
     fn test_truncate_text() {
         assert_eq!(truncate_text("short", 10), "short");
         assert_eq!(truncate_text("very-long-session-id", 10), "very-lo...");
+        assert_eq!(truncate_text("セッションの要約です", 6), "セッシ...");
     }
 }
@@ -0,0 +1,73 @@
+use super::helpers::{format_currency, format_number};
+use super::summary::display_summary_card;
+use crate::models::HourlyReport;
+use chrono::Local;
+use colored::*;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+
+pub fn display_hourly_report_enhanced(report: &HourlyReport) {
+    let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+    println!("{}", "📊 Claude Code Hourly Analytics".bright_blue().bold());
+    println!(
+        "{} Generated at {}",
+        "🕐".bright_yellow(),
+        timestamp.to_string().dimmed()
+    );
+    println!();
+
+    display_summary_card(&report.totals, report.hourly.len());
+    println!();
+
+    if !report.hourly.is_empty() {
+        println!("{}", "📋 Hourly Usage Breakdown".bright_green().bold());
+        display_hourly_table(report);
+    }
+}
+
+pub fn display_hourly_report_table(report: &HourlyReport) {
+    println!("{}", "Hourly Usage Report".bold());
+    display_hourly_table(report);
+}
+
+fn display_hourly_table(report: &HourlyReport) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec![
+            Cell::new("Hour").fg(Color::Cyan),
+            Cell::new("Sessions").fg(Color::Cyan),
+            Cell::new("Messages").fg(Color::Cyan),
+            Cell::new("Input Tokens").fg(Color::Green),
+            Cell::new("Output Tokens").fg(Color::Yellow),
+            Cell::new("Total Tokens").fg(Color::White),
+            Cell::new("Total Cost").fg(Color::Red),
+        ]);
+
+    for entry in &report.hourly {
+        table.add_row(vec![
+            Cell::new(&entry.hour),
+            Cell::new(entry.sessions),
+            Cell::new(format_number(entry.message_count)),
+            Cell::new(format_number(entry.input_tokens)).fg(Color::Green),
+            Cell::new(format_number(entry.output_tokens)).fg(Color::Yellow),
+            Cell::new(format_number(entry.total_tokens)),
+            Cell::new(format_currency(entry.total_cost)).fg(Color::Red),
+        ]);
+    }
+
+    if !report.hourly.is_empty() {
+        let total_messages: u64 = report.hourly.iter().map(|h| h.message_count).sum();
+        table.add_row(vec![
+            Cell::new("Total").fg(Color::Yellow),
+            Cell::new(""),
+            Cell::new(format_number(total_messages)).fg(Color::Yellow),
+            Cell::new(format_number(report.totals.input_tokens)).fg(Color::Yellow),
+            Cell::new(format_number(report.totals.output_tokens)).fg(Color::Yellow),
+            Cell::new(format_number(report.totals.total_tokens)).fg(Color::Yellow),
+            Cell::new(format_currency(report.totals.total_cost)).fg(Color::Yellow),
+        ]);
+    }
+
+    println!("{table}");
+}
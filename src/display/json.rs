@@ -1,5 +1,21 @@
+/// Print a report as JSON, tagged with the currency its costs are in, or
+/// through the `--template` when one is loaded
 pub fn display_report_json<T: serde::Serialize>(report: &T) {
-    match serde_json::to_string_pretty(report) {
+    let report = crate::currency::with_currency(report);
+    if let Some(template) = crate::output_template::current() {
+        let rendered = serde_json::to_value(&report)
+            .map_err(anyhow::Error::from)
+            .and_then(|value| crate::output_template::render(template, &value));
+        match rendered {
+            Ok(output) => print!("{}", output),
+            Err(e) => {
+                eprintln!("Error rendering template: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    match serde_json::to_string_pretty(&report) {
         Ok(json) => println!("{}", json),
         Err(e) => eprintln!("Error serializing report to JSON: {}", e),
     }
@@ -1,6 +1,9 @@
+mod anomalies;
 mod billing;
 mod daily;
+mod groups;
 mod helpers;
+mod hourly;
 mod json;
 mod model_breakdown;
 mod monthly;
@@ -9,17 +12,21 @@ mod summary;
 mod weekly;
 
 // Re-export all public functions so `use crate::display::X` continues to work
+pub use anomalies::display_anomaly_report;
 pub use billing::display_billing_blocks_responsive;
 pub use daily::{
     display_daily_report_compact, display_daily_report_enhanced, display_daily_report_responsive,
     display_daily_report_table,
 };
+pub use groups::display_group_report_table;
+pub(crate) use helpers::{format_number, truncate_text};
 pub use helpers::{print_error, print_info, print_warning};
+pub use hourly::{display_hourly_report_enhanced, display_hourly_report_table};
 pub use json::display_report_json;
 pub use model_breakdown::display_model_breakdown_report;
 pub use monthly::{display_monthly_report_enhanced, display_monthly_report_table};
 pub use session::{
-    display_session_report_enhanced, display_session_report_responsive,
+    display_pagination_footer, display_session_report_enhanced, display_session_report_responsive,
     display_session_report_table,
 };
 pub use weekly::{display_weekly_report_enhanced, display_weekly_report_table};
@@ -364,7 +364,7 @@ fn parse_usage_by_model()
 }
 
 /// Capitalize family name for display
-fn capitalize_family_name(family: &str) -> String {
+pub(super) fn capitalize_family_name(family: &str) -> String {
     match family.to_lowercase().as_str() {
         "opus" => "Opus".to_string(),
         "sonnet" => "Sonnet".to_string(),
@@ -1,5 +1,6 @@
 use super::helpers::{format_currency, format_number, truncate_path, truncate_text};
-use crate::models::SessionReport;
+use crate::currency;
+use crate::models::{SessionReport, SessionUsage};
 use crate::responsive_tables::{ResponsiveTable, display_responsive_summary};
 use crate::terminal::Terminal;
 use chrono::Local;
@@ -19,7 +20,7 @@ pub fn display_session_report_enhanced(report: &SessionReport) {
     println!();
 
     // Enhanced session summary
-    display_enhanced_session_summary(&report.totals, report.sessions.len());
+    display_enhanced_session_summary(&report.totals, session_count(report));
     println!();
 
     // Top sessions with better formatting
@@ -33,6 +34,7 @@ pub fn display_session_report_enhanced(report: &SessionReport) {
         println!("{}", Terminal::separator('─').bright_black());
         display_session_table_compact(report);
     }
+    display_pagination_footer(report);
 
     // Footer
     println!();
@@ -47,25 +49,33 @@ pub fn display_session_report_table(report: &SessionReport) {
         .set_header(vec![
             Cell::new("Project Path").fg(Color::Cyan),
             Cell::new("Session ID").fg(Color::Cyan),
+            Cell::new("Summary").fg(Color::Cyan),
             Cell::new("Input Tokens").fg(Color::Cyan),
             Cell::new("Output Tokens").fg(Color::Cyan),
             Cell::new("Cache Creation").fg(Color::Cyan),
             Cell::new("Cache Read").fg(Color::Cyan),
             Cell::new("Total Tokens").fg(Color::Cyan),
-            Cell::new("Cost (USD)").fg(Color::Cyan),
+            Cell::new(currency::cost_header()).fg(Color::Cyan),
+            Cell::new("Score").fg(Color::Cyan),
             Cell::new("Last Activity").fg(Color::Cyan),
         ]);
 
     for session in &report.sessions {
         table.add_row(vec![
             Cell::new(truncate_path(&session.project_path, 25)),
-            Cell::new(truncate_text(&session.session_id, 20)),
+            Cell::new(format!(
+                "{}{}",
+                truncate_text(&session.session_id, 20),
+                format_resumes(session)
+            )),
+            Cell::new(truncate_text(session.summary.as_deref().unwrap_or(""), 40)),
             Cell::new(format_number(session.input_tokens)),
             Cell::new(format_number(session.output_tokens)),
             Cell::new(format_number(session.cache_creation_tokens)),
             Cell::new(format_number(session.cache_read_tokens)),
             Cell::new(format_number(session.total_tokens)),
             Cell::new(format_currency(session.total_cost)),
+            Cell::new(format_score(session.efficiency_score)),
             Cell::new(&session.last_activity),
         ]);
     }
@@ -75,6 +85,7 @@ pub fn display_session_report_table(report: &SessionReport) {
         table.add_row(vec![
             Cell::new("Total").fg(Color::Yellow),
             Cell::new("").fg(Color::Yellow),
+            Cell::new("").fg(Color::Yellow),
             Cell::new(format_number(report.totals.input_tokens)).fg(Color::Yellow),
             Cell::new(format_number(report.totals.output_tokens)).fg(Color::Yellow),
             Cell::new(format_number(report.totals.cache_creation_tokens)).fg(Color::Yellow),
@@ -82,10 +93,12 @@ pub fn display_session_report_table(report: &SessionReport) {
             Cell::new(format_number(report.totals.total_tokens)).fg(Color::Yellow),
             Cell::new(format_currency(report.totals.total_cost)).fg(Color::Yellow),
             Cell::new("").fg(Color::Yellow),
+            Cell::new("").fg(Color::Yellow),
         ]);
     }
 
     println!("{}", table);
+    display_pagination_footer(report);
 }
 
 /// Display session report with responsive table layout
@@ -102,7 +115,7 @@ pub fn display_session_report_responsive(report: &SessionReport) {
     println!();
 
     // Enhanced session summary using responsive display
-    let context = format!("{} sessions", report.sessions.len());
+    let context = format!("{} sessions", session_count(report));
     display_responsive_summary(&report.totals, &context);
     println!();
 
@@ -124,6 +137,7 @@ pub fn display_session_report_responsive(report: &SessionReport) {
         let responsive_table = ResponsiveTable::new();
         responsive_table.display_session_report(report);
     }
+    display_pagination_footer(report);
 
     // Footer
     println!();
@@ -250,7 +264,7 @@ fn display_enhanced_top_sessions(sessions: &[crate::models::SessionUsage]) {
         };
 
         println!(
-            "{} {:<34} {} {} {} {} {} {} {} {}",
+            "{} {:<34} {} {} {} {} {} {} {} {} {} {}",
             medal,
             truncated_path.bright_white(),
             "│".bright_black(),
@@ -260,8 +274,16 @@ fn display_enhanced_top_sessions(sessions: &[crate::models::SessionUsage]) {
             "│".bright_black(),
             format!("{:>7.0} tok/$", tokens_per_dollar).bright_yellow(),
             "│".bright_black(),
-            format!("{:>5.1}% cache", cache_percentage).bright_magenta()
+            format!("{:>5.1}% cache", cache_percentage).bright_magenta(),
+            "│".bright_black(),
+            format!("score {:>3}", format_score(session.efficiency_score)).bright_blue()
         );
+        if let Some(summary) = &session.summary {
+            println!("   {}", truncate_text(summary, 90).dimmed());
+        }
+        if !session.resumed_sessions.is_empty() {
+            println!("   {}", format_resumes(session).trim_start().dimmed());
+        }
     }
 
     if sessions.len() > 5 {
@@ -280,8 +302,10 @@ fn display_session_table_compact(report: &SessionReport) {
         .apply_modifier(UTF8_ROUND_CORNERS)
         .set_header(vec![
             Cell::new("Session").fg(Color::Cyan),
+            Cell::new("Summary").fg(Color::Cyan),
             Cell::new("Cost").fg(Color::Cyan),
             Cell::new("Tokens").fg(Color::Cyan),
+            Cell::new("Score").fg(Color::Cyan),
             Cell::new("Activity").fg(Color::Cyan),
         ]);
 
@@ -290,12 +314,63 @@ fn display_session_table_compact(report: &SessionReport) {
         let truncated = truncate_path(&session_path, 30);
 
         table.add_row(vec![
-            Cell::new(truncated),
+            Cell::new(format!("{}{}", truncated, format_resumes(session))),
+            Cell::new(truncate_text(session.summary.as_deref().unwrap_or(""), 40)).fg(Color::White),
             Cell::new(format!("{:>10}", format_currency(session.total_cost))).fg(Color::Green),
             Cell::new(format_number(session.total_tokens)).fg(Color::Magenta),
+            Cell::new(format_score(session.efficiency_score)).fg(Color::Blue),
             Cell::new(&session.last_activity).fg(Color::Yellow),
         ]);
     }
 
     println!("{}", table);
 }
+
+/// Sessions the totals cover, including those left out by pagination
+fn session_count(report: &SessionReport) -> usize {
+    report
+        .pagination
+        .map_or(report.sessions.len(), |pagination| pagination.total_rows)
+}
+
+/// Which rows a paginated report shows and how many it left out
+pub fn display_pagination_footer(report: &SessionReport) {
+    let Some(pagination) = report.pagination else {
+        return;
+    };
+    let shown = report.sessions.len();
+    let mut footer = if shown == 0 {
+        format!(
+            "No sessions after offset {} ({} sessions omitted)",
+            pagination.offset,
+            format_number(pagination.omitted as u64)
+        )
+    } else {
+        format!(
+            "Showing sessions {}-{} of {} ({} omitted)",
+            pagination.offset + 1,
+            pagination.offset + shown,
+            format_number(pagination.total_rows as u64),
+            format_number(pagination.omitted as u64)
+        )
+    };
+    let next = pagination.offset + shown;
+    if shown > 0 && next < pagination.total_rows {
+        footer.push_str(&format!(" · next page: --offset {}", next));
+    }
+    println!("{}", footer.dimmed());
+}
+
+/// ` (+N resumes)` for a chain of resumed sessions (`--group-resumes`), empty otherwise
+fn format_resumes(session: &SessionUsage) -> String {
+    match session.resumed_sessions.len() {
+        0 => String::new(),
+        1 => " (+1 resume)".to_string(),
+        n => format!(" (+{} resumes)", n),
+    }
+}
+
+/// Efficiency score out of 100, `-` for sessions without one
+fn format_score(score: Option<f64>) -> String {
+    score.map_or_else(|| "-".to_string(), |score| format!("{:.0}", score))
+}
@@ -0,0 +1,665 @@
+//! Environment and data diagnostics (`doctor` command)
+//!
+//! Each check inspects one aspect of the Claude Code data and reports a status
+//! together with an actionable fix when something looks wrong.
+
+use crate::models::UsageRecord;
+use crate::pricing::PricingFetcher;
+use crate::pricing_cache::PricingCache;
+use chrono::Utc;
+use colored::Colorize;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Maximum number of per-file details listed under a check
+const MAX_DETAILS: usize = 10;
+
+/// Top-level JSONL fields written by known Claude Code versions
+const KNOWN_FIELDS: &[&str] = &[
+    "agentId",
+    "compactMetadata",
+    "content",
+    "costUSD",
+    "cwd",
+    "durationMs",
+    "error",
+    "gitBranch",
+    "isApiErrorMessage",
+    "isCompactSummary",
+    "isMeta",
+    "isSidechain",
+    "isSnapshotUpdate",
+    "isVisibleInTranscriptOnly",
+    "leafUuid",
+    "level",
+    "logicalParentUuid",
+    "message",
+    "messageId",
+    "operation",
+    "parentUuid",
+    "requestId",
+    "sessionId",
+    "slug",
+    "snapshot",
+    "subtype",
+    "summary",
+    "thinkingMetadata",
+    "timestamp",
+    "todos",
+    "toolUseID",
+    "toolUseResult",
+    "type",
+    "userType",
+    "uuid",
+    "version",
+];
+
+/// Record types written by known Claude Code versions
+const KNOWN_TYPES: &[&str] = &[
+    "assistant",
+    "file-history-snapshot",
+    "progress",
+    "queue-operation",
+    "summary",
+    "system",
+    "user",
+];
+
+/// Fields of `message.usage`; an unknown one may be a token kind that is not priced
+const KNOWN_USAGE_FIELDS: &[&str] = &[
+    "cache_creation",
+    "cache_creation_input_tokens",
+    "cache_read_input_tokens",
+    "input_tokens",
+    "output_tokens",
+    "server_tool_use",
+    "service_tier",
+    "speed",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Outcome of a single diagnostic check
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub summary: String,
+    /// Per-file findings, most significant first
+    pub details: Vec<String>,
+    /// Suggested action when the check is not OK
+    pub fix: Option<String>,
+}
+
+impl CheckResult {
+    fn new(name: &str, status: CheckStatus, summary: String) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            summary,
+            details: Vec::new(),
+            fix: None,
+        }
+    }
+
+    fn with_details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+
+    fn with_fix(mut self, fix: &str) -> Self {
+        self.fix = Some(fix.to_string());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    /// Worst status over all checks
+    pub fn status(&self) -> CheckStatus {
+        if self.checks.iter().any(|c| c.status == CheckStatus::Error) {
+            CheckStatus::Error
+        } else if self.checks.iter().any(|c| c.status == CheckStatus::Warning) {
+            CheckStatus::Warning
+        } else {
+            CheckStatus::Ok
+        }
+    }
+}
+
+/// What a single pass over one JSONL file found
+#[derive(Debug, Default)]
+struct FileScan {
+    /// Index of the Claude directory the file belongs to
+    root: usize,
+    path: PathBuf,
+    lines: usize,
+    /// 1-based numbers of lines that are not valid records
+    unparsable: Vec<usize>,
+    /// Usage records: (line UUID, message.id:requestId)
+    usage_keys: Vec<(Option<String>, Option<String>)>,
+    unknown_fields: BTreeSet<String>,
+    unknown_types: BTreeSet<String>,
+    unknown_usage_fields: BTreeSet<String>,
+    /// Models of records carrying usage
+    models: BTreeSet<String>,
+}
+
+/// Run all checks against the given Claude directories
+pub fn run_doctor(claude_dirs: &[PathBuf]) -> DoctorReport {
+    let files: Vec<(usize, PathBuf)> = claude_dirs
+        .iter()
+        .enumerate()
+        .flat_map(|(root, dir)| jsonl_files(dir).into_iter().map(move |f| (root, f)))
+        .collect();
+    let scans: Vec<FileScan> = files
+        .into_par_iter()
+        .map(|(root, path)| scan_file(root, path))
+        .collect();
+
+    DoctorReport {
+        checks: vec![
+            check_directories(claude_dirs, &scans),
+            check_unparsable(&scans),
+            check_schema(&scans),
+            check_duplicates(&scans),
+            check_pricing(&scans),
+        ],
+    }
+}
+
+fn jsonl_files(claude_dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(claude_dir.join("projects"))
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    files
+}
+
+fn scan_file(root: usize, path: PathBuf) -> FileScan {
+    let mut scan = FileScan {
+        root,
+        ..Default::default()
+    };
+    let Ok(file) = File::open(&path) else {
+        scan.path = path;
+        return scan;
+    };
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line_number = index + 1;
+        let Ok(line) = line else {
+            scan.unparsable.push(line_number);
+            continue;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        scan.lines += 1;
+
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) else {
+            scan.unparsable.push(line_number);
+            continue;
+        };
+        let (Some(fields), Ok(record)) = (value.as_object(), UsageRecord::deserialize(&value))
+        else {
+            scan.unparsable.push(line_number);
+            continue;
+        };
+
+        for key in fields.keys() {
+            if !KNOWN_FIELDS.contains(&key.as_str()) {
+                scan.unknown_fields.insert(key.clone());
+            }
+        }
+        if let Some(record_type) = fields.get("type").and_then(|t| t.as_str())
+            && !KNOWN_TYPES.contains(&record_type)
+        {
+            scan.unknown_types.insert(record_type.to_string());
+        }
+        if let Some(usage) = fields
+            .get("message")
+            .and_then(|m| m.get("usage"))
+            .and_then(|u| u.as_object())
+        {
+            for key in usage.keys() {
+                if !KNOWN_USAGE_FIELDS.contains(&key.as_str()) {
+                    scan.unknown_usage_fields.insert(key.clone());
+                }
+            }
+        }
+
+        if record
+            .message
+            .as_ref()
+            .and_then(|m| m.usage.as_ref())
+            .is_some()
+        {
+            scan.usage_keys
+                .push((record.uuid.clone(), record.dedup_hash()));
+            if let Some(model) = record.get_model_name() {
+                scan.models.insert(model.to_string());
+            }
+        }
+    }
+
+    scan.path = path;
+    scan
+}
+
+/// Every configured directory should exist and contain session files
+fn check_directories(claude_dirs: &[PathBuf], scans: &[FileScan]) -> CheckResult {
+    const NAME: &str = "Claude directories";
+    let details: Vec<String> = claude_dirs
+        .iter()
+        .enumerate()
+        .map(|(root, dir)| {
+            let files = scans.iter().filter(|scan| scan.root == root).count();
+            if !dir.exists() {
+                format!("{}: not found", dir.display())
+            } else if !dir.join("projects").is_dir() {
+                format!("{}: no projects directory", dir.display())
+            } else {
+                format!("{}: {} session files", dir.display(), files)
+            }
+        })
+        .collect();
+
+    let missing = claude_dirs.iter().filter(|dir| !dir.exists()).count();
+    if scans.is_empty() {
+        CheckResult::new(
+            NAME,
+            CheckStatus::Error,
+            "No session files found".to_string(),
+        )
+        .with_details(details)
+        .with_fix(
+            "Use Claude Code at least once, or point claudelytics at your data with --path (or `claude_paths` in config)",
+        )
+    } else if missing > 0 {
+        CheckResult::new(
+            NAME,
+            CheckStatus::Warning,
+            format!("{} of {} directories not found", missing, claude_dirs.len()),
+        )
+        .with_details(details)
+        .with_fix("Remove the missing directories from --path or `claude_paths`")
+    } else {
+        CheckResult::new(
+            NAME,
+            CheckStatus::Ok,
+            format!("{} session files", scans.len()),
+        )
+        .with_details(details)
+    }
+}
+
+/// Lines that fail to parse are left out of every report
+fn check_unparsable(scans: &[FileScan]) -> CheckResult {
+    const NAME: &str = "Unparsable lines";
+    let total_lines: usize = scans.iter().map(|scan| scan.lines).sum();
+    let mut bad: Vec<&FileScan> = scans
+        .iter()
+        .filter(|scan| !scan.unparsable.is_empty())
+        .collect();
+    if bad.is_empty() {
+        return CheckResult::new(
+            NAME,
+            CheckStatus::Ok,
+            format!("All {} lines parsed", total_lines),
+        );
+    }
+
+    bad.sort_by(|a, b| {
+        b.unparsable
+            .len()
+            .cmp(&a.unparsable.len())
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    let unparsable: usize = bad.iter().map(|scan| scan.unparsable.len()).sum();
+    let details = bad
+        .iter()
+        .take(MAX_DETAILS)
+        .map(|scan| {
+            format!(
+                "{}: {} lines (first at line {})",
+                scan.path.display(),
+                scan.unparsable.len(),
+                scan.unparsable[0]
+            )
+        })
+        .collect();
+
+    CheckResult::new(
+        NAME,
+        CheckStatus::Warning,
+        format!(
+            "{} of {} lines in {} files are skipped",
+            unparsable,
+            total_lines,
+            bad.len()
+        ),
+    )
+    .with_details(details)
+    .with_fix(
+        "A broken last line is usually a write interrupted by a crash and can be deleted; inspect other lines with `sed -n '<line>p' <file>`",
+    )
+}
+
+/// Fields and record types this version does not know about
+fn check_schema(scans: &[FileScan]) -> CheckResult {
+    const NAME: &str = "Schema drift";
+    let mut unknown: BTreeMap<String, usize> = BTreeMap::new();
+    for scan in scans {
+        let found = scan
+            .unknown_fields
+            .iter()
+            .map(|field| format!("field `{}`", field))
+            .chain(scan.unknown_types.iter().map(|t| format!("type `{}`", t)))
+            .chain(
+                scan.unknown_usage_fields
+                    .iter()
+                    .map(|field| format!("usage field `{}`", field)),
+            );
+        for name in found {
+            *unknown.entry(name).or_default() += 1;
+        }
+    }
+    if unknown.is_empty() {
+        return CheckResult::new(
+            NAME,
+            CheckStatus::Ok,
+            "All fields and record types are known".to_string(),
+        );
+    }
+
+    let usage_drift = unknown.keys().any(|name| name.starts_with("usage field"));
+    let details = unknown
+        .iter()
+        .map(|(name, files)| format!("{} in {} files", name, files))
+        .collect();
+    let fix = if usage_drift {
+        "New usage fields may be token kinds that are not priced yet; check for a newer claudelytics release"
+    } else {
+        "Unknown fields are ignored and do not affect totals; check for a newer claudelytics release"
+    };
+
+    CheckResult::new(
+        NAME,
+        CheckStatus::Warning,
+        format!("{} unknown fields or record types", unknown.len()),
+    )
+    .with_details(details)
+    .with_fix(fix)
+}
+
+/// Look for usage records written more than once.
+///
+/// A repeated line UUID within one Claude directory means the line was replayed
+/// (e.g. after a crash). Repeated message.id:requestId pairs are normal, since
+/// Claude Code logs one line per content block of a response.
+fn check_duplicates(scans: &[FileScan]) -> CheckResult {
+    const NAME: &str = "Duplicate records";
+    let mut seen_uuids: HashSet<(usize, &str)> = HashSet::new();
+    let mut seen_messages: HashSet<&str> = HashSet::new();
+    let mut replayed_by_file: HashMap<&Path, usize> = HashMap::new();
+    let mut repeated_messages = 0;
+
+    for scan in scans {
+        for (uuid, message) in &scan.usage_keys {
+            if let Some(uuid) = uuid
+                && !seen_uuids.insert((scan.root, uuid.as_str()))
+            {
+                *replayed_by_file.entry(&scan.path).or_default() += 1;
+                continue;
+            }
+            if let Some(message) = message
+                && !seen_messages.insert(message.as_str())
+            {
+                repeated_messages += 1;
+            }
+        }
+    }
+
+    if replayed_by_file.is_empty() {
+        return CheckResult::new(
+            NAME,
+            CheckStatus::Ok,
+            format!(
+                "No replayed records ({} repeated message IDs are counted once)",
+                repeated_messages
+            ),
+        );
+    }
+
+    let replayed: usize = replayed_by_file.values().sum();
+    let mut by_file: Vec<_> = replayed_by_file.into_iter().collect();
+    by_file.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    let details = by_file
+        .iter()
+        .take(MAX_DETAILS)
+        .map(|(path, count)| format!("{}: {} replayed records", path.display(), count))
+        .collect();
+
+    CheckResult::new(
+        NAME,
+        CheckStatus::Warning,
+        format!(
+            "{} replayed usage records in {} files ({} repeated message IDs)",
+            replayed,
+            by_file.len(),
+            repeated_messages
+        ),
+    )
+    .with_details(details)
+    .with_fix(
+        "Duplicates are skipped automatically; compare with `claudelytics --no-dedup daily` to see the inflation",
+    )
+}
+
+/// The pricing cache should be readable and fresh, and every model should have a price
+fn check_pricing(scans: &[FileScan]) -> CheckResult {
+    const NAME: &str = "Pricing";
+    let pricing_data = PricingCache::effective_pricing();
+    let fetcher = PricingFetcher::new();
+    let unpriced: Vec<String> = scans
+        .iter()
+        .flat_map(|scan| &scan.models)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|model| !model.starts_with('<')) // e.g. "<synthetic>" error messages
+        .filter(|model| fetcher.get_model_pricing(&pricing_data, model).is_none())
+        .map(|model| format!("no price for model `{}` (counted as $0)", model))
+        .collect();
+
+    let (mut result, cache_ok) = match PricingCache::load() {
+        Err(e) => (
+            CheckResult::new(
+                NAME,
+                CheckStatus::Error,
+                format!("Pricing cache is unreadable: {:#}", e),
+            )
+            .with_fix("Run `claudelytics pricing-cache --clear` and then `claudelytics pricing-cache --update`"),
+            false,
+        ),
+        Ok(None) => (
+            CheckResult::new(
+                NAME,
+                CheckStatus::Ok,
+                "No pricing cache; using built-in prices".to_string(),
+            ),
+            true,
+        ),
+        Ok(Some(cache)) if !cache.is_valid() => (
+            CheckResult::new(
+                NAME,
+                CheckStatus::Warning,
+                format!(
+                    "Pricing cache expired ({} days old); using built-in prices",
+                    (Utc::now() - cache.last_updated).num_days()
+                ),
+            )
+            .with_fix("Run `claudelytics pricing-cache --update`"),
+            false,
+        ),
+        Ok(Some(cache)) => (
+            CheckResult::new(
+                NAME,
+                CheckStatus::Ok,
+                format!(
+                    "Pricing cache valid ({} models, {} days old)",
+                    cache.pricing_data.len(),
+                    (Utc::now() - cache.last_updated).num_days()
+                ),
+            ),
+            true,
+        ),
+    };
+
+    if !unpriced.is_empty() {
+        if cache_ok {
+            result.status = CheckStatus::Warning;
+            result.fix = Some(
+                "Run `claudelytics pricing-cache --update` to fetch current model prices; price models of other providers in models.yaml next to the config file"
+                    .to_string(),
+            );
+        }
+        result.details = unpriced;
+    }
+    result
+}
+
+pub fn display_doctor_report(report: &DoctorReport, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "CLAUDELYTICS DOCTOR".bold());
+    println!("{}", "═".repeat(60));
+
+    for check in &report.checks {
+        let icon = match check.status {
+            CheckStatus::Ok => "✓".green(),
+            CheckStatus::Warning => "!".yellow(),
+            CheckStatus::Error => "✗".red(),
+        };
+        println!("\n {} {}", icon, check.name.bold());
+        println!("   {}", check.summary);
+        for detail in &check.details {
+            println!("   {}", format!("- {}", detail).dimmed());
+        }
+        if let Some(fix) = &check.fix {
+            println!("   {} {}", "Fix:".cyan(), fix);
+        }
+    }
+
+    println!("\n{}", "─".repeat(60));
+    match report.status() {
+        CheckStatus::Ok => println!("{}", "All checks passed".green()),
+        CheckStatus::Warning => println!("{}", "Some checks need attention".yellow()),
+        CheckStatus::Error => println!("{}", "Some checks failed".red()),
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn check<'a>(report: &'a DoctorReport, name: &str) -> &'a CheckResult {
+        report.checks.iter().find(|c| c.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_replayed_records_are_reported() {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("projects").join("app");
+        fs::create_dir_all(&project).unwrap();
+        let record = r#"{"uuid":"u1","type":"assistant","timestamp":"2024-01-15T12:00:00Z","requestId":"r1","message":{"id":"m1","usage":{"input_tokens":1,"output_tokens":1}}}"#;
+        let chunk = r#"{"uuid":"u2","type":"assistant","timestamp":"2024-01-15T12:00:01Z","requestId":"r1","message":{"id":"m1","usage":{"input_tokens":1,"output_tokens":1}}}"#;
+        fs::write(project.join("s1.jsonl"), [record, chunk].join("\n")).unwrap();
+
+        let report = run_doctor(&[dir.path().to_path_buf()]);
+        assert_eq!(check(&report, "Duplicate records").status, CheckStatus::Ok);
+
+        fs::write(project.join("s2.jsonl"), record).unwrap();
+        let report = run_doctor(&[dir.path().to_path_buf()]);
+        let duplicates = check(&report, "Duplicate records");
+        assert_eq!(duplicates.status, CheckStatus::Warning);
+        assert_eq!(duplicates.details.len(), 1);
+        assert!(duplicates.details[0].contains("s2.jsonl: 1 replayed"));
+        assert!(duplicates.fix.is_some());
+    }
+
+    #[test]
+    fn test_unparsable_lines_and_schema_drift() {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("projects").join("app");
+        fs::create_dir_all(&project).unwrap();
+        let lines = [
+            r#"{"uuid":"u1","type":"assistant","timestamp":"2024-01-15T12:00:00Z","message":{"usage":{"input_tokens":1,"output_tokens":1,"audio_tokens":5}}}"#,
+            r#"{"uuid":"u2","type":"hologram","newField":true,"timestamp":"2024-01-15T12:00:01Z"}"#,
+            r#"{"uuid":"u3","timestamp":"not a date"}"#,
+            r#"{"uuid":"u4","type":"assis"#,
+        ];
+        fs::write(project.join("s1.jsonl"), lines.join("\n")).unwrap();
+
+        let report = run_doctor(&[dir.path().to_path_buf(), dir.path().join("missing")]);
+
+        let directories = check(&report, "Claude directories");
+        assert_eq!(directories.status, CheckStatus::Warning);
+        assert!(directories.details[1].ends_with("not found"));
+
+        let unparsable = check(&report, "Unparsable lines");
+        assert_eq!(unparsable.status, CheckStatus::Warning);
+        assert!(unparsable.summary.starts_with("2 of 4 lines"));
+        assert!(unparsable.details[0].contains("(first at line 3)"));
+
+        let schema = check(&report, "Schema drift");
+        assert_eq!(schema.status, CheckStatus::Warning);
+        assert_eq!(
+            schema.details,
+            vec![
+                "field `newField` in 1 files",
+                "type `hologram` in 1 files",
+                "usage field `audio_tokens` in 1 files",
+            ]
+        );
+        assert_eq!(report.status(), CheckStatus::Warning);
+    }
+
+    #[test]
+    fn test_missing_data_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let report = run_doctor(&[dir.path().join("nowhere")]);
+        let directories = check(&report, "Claude directories");
+        assert_eq!(directories.status, CheckStatus::Error);
+        assert!(directories.fix.is_some());
+        assert_eq!(report.status(), CheckStatus::Error);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::currency::Money;
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -24,7 +25,7 @@ impl fmt::Display for TokenCount {
 
 impl fmt::Display for Cost {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "${:.4}", self.0)
+        write!(f, "{:.4}", Money(self.0))
     }
 }
 
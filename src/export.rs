@@ -1,9 +1,131 @@
-use crate::models::{DailyReport, SessionReport};
+use crate::columns::{self, Column};
+use crate::currency::{self, Money};
+use crate::models::{DailyReport, GroupReport, SessionReport, TokenUsageTotals};
 use anyhow::Result;
 use csv::Writer;
-use std::fs::File;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{self, File};
 use std::path::Path;
 
+/// Output format for the `export` command
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    Parquet,
+    Markdown,
+}
+
+impl ExportFormat {
+    /// File extension used for exported files
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// Summary statistics written by the summary export
+#[derive(Debug, Serialize)]
+pub struct ExportSummary {
+    #[serde(rename = "totalDays")]
+    pub total_days: usize,
+    #[serde(rename = "totalSessions")]
+    pub total_sessions: usize,
+    pub totals: TokenUsageTotals,
+}
+
+impl ExportSummary {
+    pub fn new(daily_report: &DailyReport, session_report: &SessionReport) -> Self {
+        Self {
+            total_days: daily_report.daily.len(),
+            total_sessions: session_report.sessions.len(),
+            totals: daily_report.totals.clone(),
+        }
+    }
+}
+
+pub fn export_daily(report: &DailyReport, path: &Path, format: ExportFormat) -> Result<()> {
+    match format {
+        ExportFormat::Csv => export_daily_to_csv(report, path),
+        ExportFormat::Json => write_json(report, path),
+        ExportFormat::Parquet => parquet_export::export_daily(report, path),
+        ExportFormat::Markdown => fs::write(path, daily_to_markdown(report)).map_err(Into::into),
+    }
+}
+
+pub fn export_sessions(report: &SessionReport, path: &Path, format: ExportFormat) -> Result<()> {
+    match format {
+        ExportFormat::Csv => export_sessions_to_csv(report, path),
+        ExportFormat::Json => write_json(report, path),
+        ExportFormat::Parquet => parquet_export::export_sessions(report, path),
+        ExportFormat::Markdown => fs::write(path, sessions_to_markdown(report)).map_err(Into::into),
+    }
+}
+
+/// Export usage rolled up by project or tag (`--group-by`)
+pub fn export_groups(report: &GroupReport, path: &Path, format: ExportFormat) -> Result<()> {
+    match format {
+        ExportFormat::Csv => export_groups_to_csv(report, path),
+        ExportFormat::Json => write_json(report, path),
+        ExportFormat::Parquet => parquet_export::export_groups(report, path),
+        ExportFormat::Markdown => fs::write(path, groups_to_markdown(report)).map_err(Into::into),
+    }
+}
+
+pub fn export_summary(
+    daily_report: &DailyReport,
+    session_report: &SessionReport,
+    path: &Path,
+    format: ExportFormat,
+) -> Result<()> {
+    match format {
+        ExportFormat::Csv => export_summary_to_csv(daily_report, session_report, path),
+        ExportFormat::Json => write_json(&ExportSummary::new(daily_report, session_report), path),
+        ExportFormat::Parquet => {
+            parquet_export::export_summary(&ExportSummary::new(daily_report, session_report), path)
+        }
+        ExportFormat::Markdown => {
+            fs::write(path, summary_to_markdown(daily_report, session_report)).map_err(Into::into)
+        }
+    }
+}
+
+/// Export rows with only the columns picked by `--columns`
+pub fn export_columns<R>(
+    columns: &[Column<R>],
+    rows: &[R],
+    totals: &TokenUsageTotals,
+    rows_key: &str,
+    path: &Path,
+    format: ExportFormat,
+) -> Result<()> {
+    match format {
+        ExportFormat::Csv => columns::write_csv(columns, rows, File::create(path)?),
+        ExportFormat::Json => write_json(&columns::to_json(columns, rows, totals, rows_key), path),
+        ExportFormat::Parquet | ExportFormat::Markdown => {
+            anyhow::bail!("--columns is available for CSV and JSON exports")
+        }
+    }
+}
+
+fn write_json<T: Serialize>(value: &T, path: &Path) -> Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(file, &currency::with_currency(value))?;
+    Ok(())
+}
+
+/// CSV cost column header, e.g. `Cost EUR`
+fn csv_cost_header() -> String {
+    format!("Cost {}", currency::current().code)
+}
+
 pub fn export_daily_to_csv(report: &DailyReport, path: &Path) -> Result<()> {
     let mut wtr = Writer::from_writer(File::create(path)?);
 
@@ -15,7 +137,8 @@ pub fn export_daily_to_csv(report: &DailyReport, path: &Path) -> Result<()> {
         "Cache Creation Tokens",
         "Cache Read Tokens",
         "Total Tokens",
-        "Cost USD",
+        &csv_cost_header(),
+        "Thinking Tokens",
     ])?;
 
     // Write data
@@ -28,6 +151,7 @@ pub fn export_daily_to_csv(report: &DailyReport, path: &Path) -> Result<()> {
             daily.cache_read_tokens.to_string(),
             daily.total_tokens.to_string(),
             format!("{:.6}", daily.total_cost),
+            daily.thinking_tokens.to_string(),
         ])?;
     }
 
@@ -47,7 +171,9 @@ pub fn export_sessions_to_csv(report: &SessionReport, path: &Path) -> Result<()>
         "Cache Creation Tokens",
         "Cache Read Tokens",
         "Total Tokens",
-        "Cost USD",
+        &csv_cost_header(),
+        "Thinking Tokens",
+        "Summary",
     ])?;
 
     // Write data
@@ -61,6 +187,41 @@ pub fn export_sessions_to_csv(report: &SessionReport, path: &Path) -> Result<()>
             session.cache_read_tokens.to_string(),
             session.total_tokens.to_string(),
             format!("{:.6}", session.total_cost),
+            session.thinking_tokens.to_string(),
+            session.summary.clone().unwrap_or_default(),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn export_groups_to_csv(report: &GroupReport, path: &Path) -> Result<()> {
+    let mut wtr = Writer::from_writer(File::create(path)?);
+
+    wtr.write_record([
+        "Period",
+        "Group",
+        "Sessions",
+        "Input Tokens",
+        "Output Tokens",
+        "Cache Creation Tokens",
+        "Cache Read Tokens",
+        "Total Tokens",
+        &csv_cost_header(),
+    ])?;
+
+    for group in &report.groups {
+        wtr.write_record(&[
+            group.period.clone().unwrap_or_default(),
+            group.group.clone(),
+            group.sessions.to_string(),
+            group.input_tokens.to_string(),
+            group.output_tokens.to_string(),
+            group.cache_creation_tokens.to_string(),
+            group.cache_read_tokens.to_string(),
+            group.total_tokens.to_string(),
+            format!("{:.6}", group.total_cost),
         ])?;
     }
 
@@ -97,7 +258,11 @@ pub fn export_summary_to_csv(
         &daily_report.totals.cache_read_tokens.to_string(),
     ])?;
     wtr.write_record([
-        "Total Cost (USD)",
+        "Total Thinking Tokens",
+        &daily_report.totals.thinking_tokens.to_string(),
+    ])?;
+    wtr.write_record([
+        &format!("Total Cost ({})", currency::current().code),
         &format!("{:.6}", daily_report.totals.total_cost),
     ])?;
 
@@ -107,3 +272,588 @@ pub fn export_summary_to_csv(
     wtr.flush()?;
     Ok(())
 }
+
+/// Render the daily report as a GitHub-flavored Markdown table
+pub fn daily_to_markdown(report: &DailyReport) -> String {
+    let mut md = String::from("## Daily Usage\n\n");
+    let _ = writeln!(
+        md,
+        "| Date | Input | Output | Cache Write | Cache Read | Total Tokens | {} |",
+        currency::cost_header()
+    );
+    md.push_str(
+        "|------|------:|-------:|------------:|-----------:|-------------:|-----------:|\n",
+    );
+    for daily in &report.daily {
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} | {} | {} | {} | {:.2} |",
+            daily.date,
+            daily.input_tokens,
+            daily.output_tokens,
+            daily.cache_creation_tokens,
+            daily.cache_read_tokens,
+            daily.total_tokens,
+            Money(daily.total_cost)
+        );
+    }
+    push_totals_row(&mut md, "", &report.totals);
+    md
+}
+
+/// Render the session report as a GitHub-flavored Markdown table
+pub fn sessions_to_markdown(report: &SessionReport) -> String {
+    let mut md = String::from("## Sessions\n\n");
+    let _ = writeln!(
+        md,
+        "| Session | Summary | Last Activity | Input | Output | Cache Write | Cache Read | Total Tokens | {} |",
+        currency::cost_header()
+    );
+    md.push_str("|---------|---------|---------------|------:|-------:|------------:|-----------:|-------------:|-----------:|\n");
+    for session in &report.sessions {
+        let _ = writeln!(
+            md,
+            "| {}/{} | {} | {} | {} | {} | {} | {} | {} | {:.2} |",
+            escape_markdown_cell(&session.project_path),
+            escape_markdown_cell(&session.session_id),
+            escape_markdown_cell(session.summary.as_deref().unwrap_or("")),
+            session.last_activity,
+            session.input_tokens,
+            session.output_tokens,
+            session.cache_creation_tokens,
+            session.cache_read_tokens,
+            session.total_tokens,
+            Money(session.total_cost)
+        );
+    }
+    push_totals_row(&mut md, " | |", &report.totals);
+    md
+}
+
+/// Render usage by project or tag as a Markdown table
+pub fn groups_to_markdown(report: &GroupReport) -> String {
+    let label = if report.group_by == "tag" {
+        "Tag"
+    } else {
+        "Project"
+    };
+    let mut md = format!("## Usage by {}\n\n", label.to_lowercase());
+    let _ = writeln!(
+        md,
+        "| Period | {} | Sessions | Input | Output | Cache Write | Cache Read | Total Tokens | {} |",
+        label,
+        currency::cost_header()
+    );
+    md.push_str("|--------|------|---------:|------:|-------:|------------:|-----------:|-------------:|-----------:|\n");
+    for group in &report.groups {
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {:.2} |",
+            group.period.as_deref().unwrap_or("-"),
+            escape_markdown_cell(&group.group),
+            group.sessions,
+            group.input_tokens,
+            group.output_tokens,
+            group.cache_creation_tokens,
+            group.cache_read_tokens,
+            group.total_tokens,
+            Money(group.total_cost)
+        );
+    }
+    push_totals_row(&mut md, " | |", &report.totals);
+    md
+}
+
+/// Render summary statistics and a monthly breakdown as Markdown
+pub fn summary_to_markdown(daily_report: &DailyReport, session_report: &SessionReport) -> String {
+    let totals = &daily_report.totals;
+    let mut md = String::from("## Usage Summary\n\n");
+
+    let mut dates: Vec<&str> = daily_report.daily.iter().map(|d| d.date.as_str()).collect();
+    dates.sort_unstable();
+    if let (Some(first), Some(last)) = (dates.first(), dates.last()) {
+        let _ = writeln!(md, "**Period:** {} to {}\n", first, last);
+    }
+
+    md.push_str("| Metric | Value |\n|--------|------:|\n");
+    let avg_daily_cost = if daily_report.daily.is_empty() {
+        0.0
+    } else {
+        totals.total_cost / daily_report.daily.len() as f64
+    };
+    for (metric, value) in [
+        ("Active Days", daily_report.daily.len().to_string()),
+        ("Sessions", session_report.sessions.len().to_string()),
+        ("Input Tokens", totals.input_tokens.to_string()),
+        ("Output Tokens", totals.output_tokens.to_string()),
+        (
+            "Cache Write Tokens",
+            totals.cache_creation_tokens.to_string(),
+        ),
+        ("Cache Read Tokens", totals.cache_read_tokens.to_string()),
+        ("Total Tokens", totals.total_tokens.to_string()),
+        ("Total Cost", format!("{:.2}", Money(totals.total_cost))),
+        (
+            "Average Daily Cost",
+            format!("{:.2}", Money(avg_daily_cost)),
+        ),
+    ] {
+        let _ = writeln!(md, "| {} | {} |", metric, value);
+    }
+
+    // Monthly breakdown derived from the daily rows (dates are YYYY-MM-DD)
+    let mut months: BTreeMap<&str, (usize, u64, f64)> = BTreeMap::new();
+    for daily in &daily_report.daily {
+        let month = months
+            .entry(daily.date.get(..7).unwrap_or(&daily.date))
+            .or_default();
+        month.0 += 1;
+        month.1 += daily.total_tokens;
+        month.2 += daily.total_cost;
+    }
+    if !months.is_empty() {
+        md.push_str("\n### Monthly\n\n");
+        let _ = writeln!(
+            md,
+            "| Month | Active Days | Total Tokens | {} |",
+            currency::cost_header()
+        );
+        md.push_str("|-------|------------:|-------------:|-----------:|\n");
+        for (month, (days, tokens, cost)) in months.iter().rev() {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {:.2} |",
+                month,
+                days,
+                tokens,
+                Money(*cost)
+            );
+        }
+    }
+
+    md
+}
+
+/// Append a bold totals row; `padding` fills any extra leading columns
+fn push_totals_row(md: &mut String, padding: &str, totals: &TokenUsageTotals) {
+    let _ = writeln!(
+        md,
+        "| **Total**{} | **{}** | **{}** | **{}** | **{}** | **{}** | **{:.2}** |",
+        padding,
+        totals.input_tokens,
+        totals.output_tokens,
+        totals.cache_creation_tokens,
+        totals.cache_read_tokens,
+        totals.total_tokens,
+        Money(totals.total_cost)
+    );
+}
+
+/// Escape characters that would break a Markdown table cell
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Parquet writers, available when built with `--features parquet`
+#[cfg(feature = "parquet")]
+mod parquet_export {
+    use super::ExportSummary;
+    use crate::currency;
+    use crate::models::{DailyReport, GroupReport, SessionReport};
+    use anyhow::Result;
+    use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::fs::File;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    /// A single named, non-nullable column
+    enum Column {
+        Utf8(&'static str, Vec<String>),
+        Int64(&'static str, Vec<i64>),
+        Double(&'static str, Vec<f64>),
+    }
+
+    impl Column {
+        fn len(&self) -> usize {
+            match self {
+                Column::Utf8(_, values) => values.len(),
+                Column::Int64(_, values) => values.len(),
+                Column::Double(_, values) => values.len(),
+            }
+        }
+
+        fn schema_field(&self) -> String {
+            match self {
+                Column::Utf8(name, _) => format!("REQUIRED BYTE_ARRAY {} (UTF8);", name),
+                Column::Int64(name, _) => format!("REQUIRED INT64 {};", name),
+                Column::Double(name, _) => format!("REQUIRED DOUBLE {};", name),
+            }
+        }
+    }
+
+    fn to_i64(value: u64) -> i64 {
+        i64::try_from(value).unwrap_or(i64::MAX)
+    }
+
+    /// Cost column, named `cost_usd` for USD and `cost` for any other display currency
+    fn cost_column(costs: Vec<f64>) -> Column {
+        let name = if currency::current().is_usd() {
+            "cost_usd"
+        } else {
+            "cost"
+        };
+        Column::Double(name, costs)
+    }
+
+    /// Write all columns as a single row group, followed by a `currency` column
+    fn write_columns(path: &Path, message: &str, mut columns: Vec<Column>) -> Result<()> {
+        let rows = columns.first().map_or(0, Column::len);
+        columns.push(Column::Utf8(
+            "currency",
+            vec![currency::current().code.clone(); rows],
+        ));
+        let fields: String = columns.iter().map(Column::schema_field).collect();
+        let schema = Arc::new(parse_message_type(&format!(
+            "message {} {{ {} }}",
+            message, fields
+        ))?);
+        let props = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(File::create(path)?, schema, props)?;
+
+        let mut row_group = writer.next_row_group()?;
+        for column in columns {
+            let mut col_writer = row_group
+                .next_column()?
+                .ok_or_else(|| anyhow::anyhow!("Parquet schema has fewer columns than data"))?;
+            match column {
+                Column::Utf8(_, values) => {
+                    let values: Vec<ByteArray> = values
+                        .into_iter()
+                        .map(|v| ByteArray::from(v.into_bytes()))
+                        .collect();
+                    col_writer
+                        .typed::<ByteArrayType>()
+                        .write_batch(&values, None, None)?;
+                }
+                Column::Int64(_, values) => {
+                    col_writer
+                        .typed::<Int64Type>()
+                        .write_batch(&values, None, None)?;
+                }
+                Column::Double(_, values) => {
+                    col_writer
+                        .typed::<DoubleType>()
+                        .write_batch(&values, None, None)?;
+                }
+            }
+            col_writer.close()?;
+        }
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+
+    pub fn export_daily(report: &DailyReport, path: &Path) -> Result<()> {
+        let rows = &report.daily;
+        write_columns(
+            path,
+            "daily",
+            vec![
+                Column::Utf8("date", rows.iter().map(|d| d.date.clone()).collect()),
+                Column::Int64(
+                    "input_tokens",
+                    rows.iter().map(|d| to_i64(d.input_tokens)).collect(),
+                ),
+                Column::Int64(
+                    "output_tokens",
+                    rows.iter().map(|d| to_i64(d.output_tokens)).collect(),
+                ),
+                Column::Int64(
+                    "cache_creation_tokens",
+                    rows.iter()
+                        .map(|d| to_i64(d.cache_creation_tokens))
+                        .collect(),
+                ),
+                Column::Int64(
+                    "cache_read_tokens",
+                    rows.iter().map(|d| to_i64(d.cache_read_tokens)).collect(),
+                ),
+                Column::Int64(
+                    "total_tokens",
+                    rows.iter().map(|d| to_i64(d.total_tokens)).collect(),
+                ),
+                cost_column(rows.iter().map(|d| d.total_cost).collect()),
+                Column::Int64(
+                    "thinking_tokens",
+                    rows.iter().map(|d| to_i64(d.thinking_tokens)).collect(),
+                ),
+            ],
+        )
+    }
+
+    pub fn export_sessions(report: &SessionReport, path: &Path) -> Result<()> {
+        let rows = &report.sessions;
+        write_columns(
+            path,
+            "sessions",
+            vec![
+                Column::Utf8(
+                    "project_path",
+                    rows.iter().map(|s| s.project_path.clone()).collect(),
+                ),
+                Column::Utf8(
+                    "session_id",
+                    rows.iter().map(|s| s.session_id.clone()).collect(),
+                ),
+                Column::Utf8(
+                    "last_activity",
+                    rows.iter().map(|s| s.last_activity.clone()).collect(),
+                ),
+                Column::Int64(
+                    "input_tokens",
+                    rows.iter().map(|s| to_i64(s.input_tokens)).collect(),
+                ),
+                Column::Int64(
+                    "output_tokens",
+                    rows.iter().map(|s| to_i64(s.output_tokens)).collect(),
+                ),
+                Column::Int64(
+                    "cache_creation_tokens",
+                    rows.iter()
+                        .map(|s| to_i64(s.cache_creation_tokens))
+                        .collect(),
+                ),
+                Column::Int64(
+                    "cache_read_tokens",
+                    rows.iter().map(|s| to_i64(s.cache_read_tokens)).collect(),
+                ),
+                Column::Int64(
+                    "total_tokens",
+                    rows.iter().map(|s| to_i64(s.total_tokens)).collect(),
+                ),
+                cost_column(rows.iter().map(|s| s.total_cost).collect()),
+                Column::Int64(
+                    "thinking_tokens",
+                    rows.iter().map(|s| to_i64(s.thinking_tokens)).collect(),
+                ),
+                Column::Utf8(
+                    "summary",
+                    rows.iter()
+                        .map(|s| s.summary.clone().unwrap_or_default())
+                        .collect(),
+                ),
+            ],
+        )
+    }
+
+    pub fn export_groups(report: &GroupReport, path: &Path) -> Result<()> {
+        let rows = &report.groups;
+        write_columns(
+            path,
+            "groups",
+            vec![
+                Column::Utf8(
+                    "period",
+                    rows.iter()
+                        .map(|g| g.period.clone().unwrap_or_default())
+                        .collect(),
+                ),
+                Column::Utf8("group", rows.iter().map(|g| g.group.clone()).collect()),
+                Column::Int64(
+                    "sessions",
+                    rows.iter().map(|g| to_i64(g.sessions as u64)).collect(),
+                ),
+                Column::Int64(
+                    "input_tokens",
+                    rows.iter().map(|g| to_i64(g.input_tokens)).collect(),
+                ),
+                Column::Int64(
+                    "output_tokens",
+                    rows.iter().map(|g| to_i64(g.output_tokens)).collect(),
+                ),
+                Column::Int64(
+                    "cache_creation_tokens",
+                    rows.iter()
+                        .map(|g| to_i64(g.cache_creation_tokens))
+                        .collect(),
+                ),
+                Column::Int64(
+                    "cache_read_tokens",
+                    rows.iter().map(|g| to_i64(g.cache_read_tokens)).collect(),
+                ),
+                Column::Int64(
+                    "total_tokens",
+                    rows.iter().map(|g| to_i64(g.total_tokens)).collect(),
+                ),
+                cost_column(rows.iter().map(|g| g.total_cost).collect()),
+            ],
+        )
+    }
+
+    pub fn export_summary(summary: &ExportSummary, path: &Path) -> Result<()> {
+        let totals = &summary.totals;
+        write_columns(
+            path,
+            "summary",
+            vec![
+                Column::Int64("total_days", vec![to_i64(summary.total_days as u64)]),
+                Column::Int64(
+                    "total_sessions",
+                    vec![to_i64(summary.total_sessions as u64)],
+                ),
+                Column::Int64("input_tokens", vec![to_i64(totals.input_tokens)]),
+                Column::Int64("output_tokens", vec![to_i64(totals.output_tokens)]),
+                Column::Int64(
+                    "cache_creation_tokens",
+                    vec![to_i64(totals.cache_creation_tokens)],
+                ),
+                Column::Int64("cache_read_tokens", vec![to_i64(totals.cache_read_tokens)]),
+                Column::Int64("total_tokens", vec![to_i64(totals.total_tokens)]),
+                cost_column(vec![totals.total_cost]),
+                Column::Int64("thinking_tokens", vec![to_i64(totals.thinking_tokens)]),
+            ],
+        )
+    }
+}
+
+/// Fallback used when the binary was built without Parquet support
+#[cfg(not(feature = "parquet"))]
+mod parquet_export {
+    use super::ExportSummary;
+    use crate::models::{DailyReport, GroupReport, SessionReport};
+    use anyhow::Result;
+    use std::path::Path;
+
+    fn unsupported() -> Result<()> {
+        anyhow::bail!(
+            "Parquet export is not available in this build. Rebuild with `cargo install claudelytics --features parquet`"
+        )
+    }
+
+    pub fn export_daily(_report: &DailyReport, _path: &Path) -> Result<()> {
+        unsupported()
+    }
+
+    pub fn export_sessions(_report: &SessionReport, _path: &Path) -> Result<()> {
+        unsupported()
+    }
+
+    pub fn export_groups(_report: &GroupReport, _path: &Path) -> Result<()> {
+        unsupported()
+    }
+
+    pub fn export_summary(_summary: &ExportSummary, _path: &Path) -> Result<()> {
+        unsupported()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DailyUsage;
+    use tempfile::TempDir;
+
+    fn sample_reports() -> (DailyReport, SessionReport) {
+        let totals = TokenUsageTotals {
+            input_tokens: 100,
+            output_tokens: 200,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 50,
+            total_tokens: 350,
+            total_cost: 1.25,
+            thinking_tokens: 0,
+        };
+        let daily = DailyReport {
+            daily: vec![DailyUsage {
+                date: "2024-01-15".to_string(),
+                input_tokens: 100,
+                output_tokens: 200,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 50,
+                total_tokens: 350,
+                total_cost: 1.25,
+                thinking_tokens: 0,
+                models: Vec::new(),
+                sources: Vec::new(),
+            }],
+            totals: totals.clone(),
+        };
+        let sessions = SessionReport {
+            sessions: Vec::new(),
+            totals,
+            pagination: None,
+        };
+        (daily, sessions)
+    }
+
+    #[test]
+    fn test_export_daily_json_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("report.daily.json");
+        let (daily, _) = sample_reports();
+
+        export_daily(&daily, &path, ExportFormat::Json).unwrap();
+
+        let value: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(value["daily"][0]["date"], "2024-01-15");
+        assert_eq!(value["totals"]["totalTokens"], 350);
+    }
+
+    #[test]
+    fn test_export_summary_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("report.summary.json");
+        let (daily, sessions) = sample_reports();
+
+        export_summary(&daily, &sessions, &path, ExportFormat::Json).unwrap();
+
+        let value: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(value["totalDays"], 1);
+        assert_eq!(value["totalSessions"], 0);
+    }
+
+    #[test]
+    fn test_markdown_tables() {
+        let (daily, sessions) = sample_reports();
+
+        let md = daily_to_markdown(&daily);
+        assert!(md.contains("| 2024-01-15 | 100 | 200 | 0 | 50 | 350 | $1.25 |"));
+        assert!(md.contains("| **Total** | **100** |"));
+
+        let summary = summary_to_markdown(&daily, &sessions);
+        assert!(summary.contains("**Period:** 2024-01-15 to 2024-01-15"));
+        assert!(summary.contains("| 2024-01 | 1 | 350 | $1.25 |"));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_export_daily_parquet() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("report.daily.parquet");
+        let (daily, _) = sample_reports();
+
+        export_daily(&daily, &path, ExportFormat::Parquet).unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 1);
+        assert_eq!(metadata.file_metadata().schema_descr().num_columns(), 9);
+    }
+
+    #[cfg(not(feature = "parquet"))]
+    #[test]
+    fn test_export_parquet_unavailable() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("report.daily.parquet");
+        let (daily, _) = sample_reports();
+
+        assert!(export_daily(&daily, &path, ExportFormat::Parquet).is_err());
+    }
+}
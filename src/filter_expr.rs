@@ -0,0 +1,506 @@
+//! Filter expressions (`session --filter`)
+//!
+//! A small language evaluated against report rows, e.g.
+//! `cost > 5 && project =~ 'backend' && tokens > 1e6`. Fields are the column
+//! names of `--columns` (see `columns`), so every column can be filtered on.
+//!
+//! ```text
+//! expr       := or
+//! or         := and ("||" and)*
+//! and        := unary ("&&" unary)*
+//! unary      := "!" unary | "(" expr ")" | comparison
+//! comparison := FIELD ("==" | "!=" | ">" | ">=" | "<" | "<=") VALUE
+//!             | FIELD ("=~" | "!~") STRING
+//! VALUE      := NUMBER | STRING
+//! ```
+//!
+//! Numbers compare numerically and strings as text (so `date >= '2024-03-01'`
+//! works). Comparisons with a missing value, such as a session without a
+//! summary or score, are false. Error columns count characters, and `!` and
+//! parentheses nest at most 64 levels deep.
+
+use crate::columns::{Column, Value};
+use anyhow::{Result, bail};
+use regex::Regex;
+use std::cmp::Ordering;
+
+/// A parsed filter over rows of type `R`
+pub struct Filter<R> {
+    expr: Expr<R>,
+}
+
+/// Nesting of `!` and parentheses the parser recurses into before giving up
+const MAX_NESTING: usize = 64;
+
+enum Expr<R> {
+    /// Operands of a chain like `a && b && c`, kept flat so long chains do not
+    /// make a deep tree
+    And(Vec<Expr<R>>),
+    Or(Vec<Expr<R>>),
+    Not(Box<Expr<R>>),
+    Compare {
+        field: fn(&R) -> Value,
+        op: CompareOp,
+        literal: Literal,
+    },
+    Matches {
+        field: fn(&R) -> Value,
+        regex: Regex,
+        negate: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Text(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+}
+
+/// Operators, longest first so `>=` is not read as `>`
+const OPERATORS: [&str; 12] = [
+    "&&", "||", "==", "!=", ">=", "<=", "=~", "!~", ">", "<", "!", "=",
+];
+
+/// Tokens with the character (not byte) index they start at
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>> {
+    let mut tokens = Vec::new();
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let (byte_pos, c) = chars[i];
+        let pos = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' || c == ')' {
+            tokens.push((
+                pos,
+                if c == '(' {
+                    Token::LParen
+                } else {
+                    Token::RParen
+                },
+            ));
+            i += 1;
+        } else if c == '\'' || c == '"' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&(_, ch)| ch == c)
+                .map(|offset| i + 1 + offset);
+            let Some(end) = end else {
+                bail!("Unterminated string starting at column {}", pos + 1);
+            };
+            let text: String = chars[i + 1..end].iter().map(|&(_, ch)| ch).collect();
+            tokens.push((pos, Token::Text(text)));
+            i = end + 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() {
+                let ch = chars[i].1;
+                let exponent_sign =
+                    i > start && matches!(ch, '+' | '-') && matches!(chars[i - 1].1, 'e' | 'E');
+                if ch.is_ascii_alphanumeric() || ch == '.' || ch == '_' || exponent_sign {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            let text: String = chars[start..i]
+                .iter()
+                .map(|&(_, ch)| ch)
+                .filter(|&ch| ch != '_')
+                .collect();
+            let Ok(number) = text.parse::<f64>() else {
+                bail!("Invalid number '{}' at column {}", text, pos + 1);
+            };
+            tokens.push((pos, Token::Number(number)));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].1.is_alphanumeric() || chars[i].1 == '_') {
+                i += 1;
+            }
+            let ident: String = chars[start..i].iter().map(|&(_, ch)| ch).collect();
+            tokens.push((pos, Token::Ident(ident)));
+        } else {
+            let rest = &source[byte_pos..];
+            let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) else {
+                bail!("Unexpected '{}' at column {}", c, pos + 1);
+            };
+            if *op == "=" {
+                bail!("Use '==' instead of '=' at column {}", pos + 1);
+            }
+            tokens.push((pos, Token::Op(op)));
+            i += op.chars().count();
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a, R> {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    columns: &'a [Column<R>],
+    /// Length of the source in characters
+    source_len: usize,
+    /// `!` and parentheses entered so far
+    depth: usize,
+}
+
+impl<R> Parser<'_, R> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    /// 1-based column of the next token, for error messages
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(self.source_len, |(pos, _)| *pos)
+            + 1
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .map(|(_, token)| token.clone());
+        self.position += 1;
+        token
+    }
+
+    fn eat_op(&mut self, op: &'static str) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Step into a `!` or parenthesis at `column`, refusing to recurse too deep
+    fn enter(&mut self, column: usize) -> Result<()> {
+        if self.depth >= MAX_NESTING {
+            bail!(
+                "The filter nests more than {} levels deep at column {}",
+                MAX_NESTING,
+                column
+            );
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn or(&mut self) -> Result<Expr<R>> {
+        let mut terms = vec![self.and()?];
+        while self.eat_op("||") {
+            terms.push(self.and()?);
+        }
+        Ok(match terms.len() {
+            1 => terms.remove(0),
+            _ => Expr::Or(terms),
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr<R>> {
+        let mut terms = vec![self.unary()?];
+        while self.eat_op("&&") {
+            terms.push(self.unary()?);
+        }
+        Ok(match terms.len() {
+            1 => terms.remove(0),
+            _ => Expr::And(terms),
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr<R>> {
+        let column = self.column();
+        if self.eat_op("!") {
+            self.enter(column)?;
+            let inner = self.unary()?;
+            self.depth -= 1;
+            return Ok(Expr::Not(Box::new(inner)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.position += 1;
+            self.enter(column)?;
+            let expr = self.or()?;
+            self.depth -= 1;
+            let column = self.column();
+            if self.next() != Some(Token::RParen) {
+                bail!("Expected ')' at column {}", column);
+            }
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr<R>> {
+        let column = self.column();
+        let name = match self.next() {
+            Some(Token::Ident(name)) => name.to_ascii_lowercase(),
+            _ => bail!("Expected a field name at column {}", column),
+        };
+        let Some(field) = self
+            .columns
+            .iter()
+            .find(|c| c.id == name || c.aliases.contains(&name.as_str()))
+        else {
+            bail!(
+                "Unknown field '{}' at column {} (fields: {})",
+                name,
+                column,
+                self.columns
+                    .iter()
+                    .map(|c| c.id)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        };
+        let field = field.value;
+
+        let column = self.column();
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            _ => bail!("Expected an operator after '{}' at column {}", name, column),
+        };
+        let column = self.column();
+        let literal = match self.next() {
+            Some(Token::Number(number)) => Literal::Number(number),
+            Some(Token::Text(text)) => Literal::Text(text),
+            Some(Token::Ident(word)) => bail!(
+                "Expected a number or quoted string at column {}, found '{}' (quote text: '{}')",
+                column,
+                word,
+                word
+            ),
+            _ => bail!("Expected a value after '{}' at column {}", op, column),
+        };
+
+        let op = match op {
+            "=~" | "!~" => {
+                let Literal::Text(pattern) = literal else {
+                    bail!(
+                        "'{}' needs a quoted regular expression at column {}",
+                        op,
+                        column
+                    );
+                };
+                let regex = Regex::new(&pattern).map_err(|e| {
+                    anyhow::anyhow!("Invalid regular expression at column {}: {}", column, e)
+                })?;
+                return Ok(Expr::Matches {
+                    field,
+                    regex,
+                    negate: op == "!~",
+                });
+            }
+            "==" => CompareOp::Eq,
+            "!=" => CompareOp::Ne,
+            ">" => CompareOp::Gt,
+            ">=" => CompareOp::Ge,
+            "<" => CompareOp::Lt,
+            "<=" => CompareOp::Le,
+            _ => bail!(
+                "Expected a comparison operator at column {}, found '{}'",
+                column,
+                op
+            ),
+        };
+        Ok(Expr::Compare { field, op, literal })
+    }
+}
+
+impl<R> Filter<R> {
+    /// Parse `source`, whose fields are the ids and aliases of `columns`
+    pub fn parse(source: &str, columns: &[Column<R>]) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+            columns,
+            source_len: source.chars().count(),
+            depth: 0,
+        };
+        if parser.tokens.is_empty() {
+            bail!("The filter is empty");
+        }
+        let expr = parser.or()?;
+        if parser.position < parser.tokens.len() {
+            bail!("Unexpected input at column {}", parser.column());
+        }
+        Ok(Self { expr })
+    }
+
+    pub fn matches(&self, row: &R) -> bool {
+        self.expr.evaluate(row)
+    }
+}
+
+impl<R> Expr<R> {
+    fn evaluate(&self, row: &R) -> bool {
+        match self {
+            Expr::And(terms) => terms.iter().all(|term| term.evaluate(row)),
+            Expr::Or(terms) => terms.iter().any(|term| term.evaluate(row)),
+            Expr::Not(inner) => !inner.evaluate(row),
+            Expr::Matches {
+                field,
+                regex,
+                negate,
+            } => match field(row) {
+                Value::Empty => false,
+                value => regex.is_match(&value.csv()) != *negate,
+            },
+            Expr::Compare { field, op, literal } => {
+                let ordering = match (field(row), literal) {
+                    (Value::Empty, _) => return false,
+                    (Value::Text(text), Literal::Text(literal)) => Some(text.as_str().cmp(literal)),
+                    (Value::Text(text), Literal::Number(number)) => text
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(|value| value.partial_cmp(number)),
+                    (value, Literal::Number(number)) => {
+                        number_of(&value).and_then(|value| value.partial_cmp(number))
+                    }
+                    (value, Literal::Text(text)) => Some(value.csv().as_str().cmp(text)),
+                };
+                let Some(ordering) = ordering else {
+                    return false;
+                };
+                match op {
+                    CompareOp::Eq => ordering == Ordering::Equal,
+                    CompareOp::Ne => ordering != Ordering::Equal,
+                    CompareOp::Gt => ordering == Ordering::Greater,
+                    CompareOp::Ge => ordering != Ordering::Less,
+                    CompareOp::Lt => ordering == Ordering::Less,
+                    CompareOp::Le => ordering != Ordering::Greater,
+                }
+            }
+        }
+    }
+}
+
+fn number_of(value: &Value) -> Option<f64> {
+    match value {
+        Value::Tokens(tokens) => Some(*tokens as f64),
+        Value::Cost(number) | Value::Number(number) => Some(*number),
+        Value::Text(_) | Value::Empty => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::columns::session_columns;
+    use crate::models::SessionUsage;
+
+    fn session(project: &str, cost: f64, tokens: u64, summary: Option<&str>) -> SessionUsage {
+        SessionUsage {
+            project_path: project.to_string(),
+            session_id: "abc".to_string(),
+            input_tokens: tokens,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            total_tokens: tokens,
+            total_cost: cost,
+            thinking_tokens: 0,
+            last_activity: "2024-03-02".to_string(),
+            summary: summary.map(str::to_string),
+            efficiency_score: None,
+            context_peak_percent: None,
+            resumed_sessions: Vec::new(),
+        }
+    }
+
+    fn matches(source: &str, row: &SessionUsage) -> bool {
+        Filter::parse(source, &session_columns())
+            .unwrap()
+            .matches(row)
+    }
+
+    #[test]
+    fn test_filter_expressions() {
+        let backend = session("-home-me-backend", 7.5, 2_000_000, Some("Fix login"));
+        let docs = session("-home-me-docs", 0.5, 10_000, None);
+
+        let source = "cost > 5 && project =~ 'backend' && tokens > 1e6";
+        assert!(matches(source, &backend));
+        assert!(!matches(source, &docs));
+
+        assert!(matches("cost < 1 || summary =~ \"(?i)LOGIN\"", &docs));
+        assert!(matches("cost < 1 || summary =~ \"(?i)LOGIN\"", &backend));
+        assert!(matches("!(project =~ 'backend')", &docs));
+        assert!(matches("project !~ 'backend' && tokens <= 10_000", &docs));
+        assert!(matches(
+            "date >= '2024-03-01' && date < '2024-04-01'",
+            &docs
+        ));
+        assert!(matches("session_id == 'abc'", &docs));
+
+        // A missing value never matches, whichever the operator
+        assert!(!matches("summary =~ '.*'", &docs));
+        assert!(!matches("score < 50", &docs));
+        assert!(!matches("score >= 50", &docs));
+    }
+
+    #[test]
+    fn test_filter_errors() {
+        let error = |source: &str| {
+            Filter::parse(source, &session_columns())
+                .err()
+                .unwrap()
+                .to_string()
+        };
+        assert!(error("price > 5").contains("Unknown field 'price' at column 1"));
+        assert!(error("cost > ").contains("Expected a value after '>' at column 8"));
+        assert!(error("cost = 5").contains("Use '=='"));
+        assert!(error("project =~ 'back(end'").contains("Invalid regular expression"));
+        assert!(error("project == backend").contains("quoted string"));
+        assert!(error("(cost > 5").contains("Expected ')'"));
+        assert!(error("cost > 5 tokens").contains("Unexpected input at column 10"));
+        assert!(error("summary == 'open").contains("Unterminated string"));
+
+        // Columns count characters, not bytes
+        assert!(
+            error("project == 'café' && prix > 5").contains("Unknown field 'prix' at column 22")
+        );
+        assert!(error("project == 'ü' $").contains("Unexpected '$' at column 16"));
+        assert!(error("project == 'ü' &&").contains("Expected a field name at column 18"));
+
+        let deep = format!("{}cost > 5{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert!(error(&deep).contains("nests more than 64 levels deep at column 65"));
+        assert!(error(&"!".repeat(100_000)).contains("nests more than 64 levels"));
+    }
+
+    #[test]
+    fn test_long_chains_and_nesting_within_the_limit() {
+        let backend = session("-home-me-backend", 7.5, 2_000_000, None);
+        let chain = vec!["cost > 5"; 100_000].join(" && ");
+        assert!(matches(&chain, &backend));
+
+        let nested = format!(
+            "{}cost > 5{}",
+            "(".repeat(MAX_NESTING),
+            ")".repeat(MAX_NESTING)
+        );
+        assert!(matches(&nested, &backend));
+    }
+}
@@ -0,0 +1,384 @@
+//! Git repository correlation (`git-correlate`)
+//!
+//! Lines Claude usage of a project up with the commits of its git repository:
+//! every commit gets the usage between the previous commit and itself (its
+//! "commit window"), so the cost of a pull request is the sum over its commits.
+//! Usage after the newest commit is reported as uncommitted work. Sessions are
+//! matched to the repository through Claude's project directory name, which is
+//! the working directory with every non-alphanumeric character replaced by `-`.
+//! Reading the repository needs the `git` build feature.
+
+use crate::burn_rate::UsageEvent;
+use crate::currency::{self, Money};
+use crate::display::format_number;
+use chrono::{DateTime, Local, Utc};
+use colored::Colorize;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Characters of the commit summary shown in the table
+const SUMMARY_CHARS: usize = 50;
+
+/// A commit read from the repository
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub id: String,
+    pub summary: String,
+    pub author: String,
+    pub time: DateTime<Utc>,
+    /// Commit time of the first parent, where the first window starts
+    pub parent_time: Option<DateTime<Utc>>,
+}
+
+/// Commits of a repository from `--rev`, newest first
+#[derive(Debug)]
+pub struct RepoHistory {
+    pub workdir: PathBuf,
+    pub commits: Vec<CommitInfo>,
+}
+
+/// Usage attributed to one span of time
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WindowUsage {
+    pub requests: usize,
+    pub sessions: usize,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitWindow {
+    pub id: String,
+    pub summary: String,
+    pub author: String,
+    pub committed_at: DateTime<Utc>,
+    /// Previous commit (or first parent); `None` for a root commit
+    pub window_start: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub usage: WindowUsage,
+    /// Cost of this and all older commits in the report
+    pub cumulative_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitCorrelation {
+    pub repository: PathBuf,
+    pub project: String,
+    /// Oldest first
+    pub commits: Vec<CommitWindow>,
+    /// Usage after the newest commit
+    pub uncommitted: WindowUsage,
+    pub committed_cost: f64,
+    pub committed_tokens: u64,
+}
+
+/// Claude's project directory name for a working directory, e.g. `/home/me/my.app` -> `-home-me-my-app`
+pub fn claude_project_dir(path: &Path) -> String {
+    path.to_string_lossy()
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Attribute the usage of `project` to the commit windows of `history`
+pub fn correlate(history: RepoHistory, project: &str, events: &[UsageEvent]) -> GitCorrelation {
+    let events: Vec<&UsageEvent> = events
+        .iter()
+        .filter(|event| {
+            event
+                .session
+                .rsplit_once('/')
+                .is_some_and(|(event_project, _)| event_project == project)
+        })
+        .collect();
+    let first_usage = events.iter().map(|event| event.timestamp).min();
+
+    let mut commits = history.commits;
+    commits.sort_by_key(|commit| commit.time);
+    let mut windows: Vec<(CommitInfo, Option<DateTime<Utc>>, Accumulator)> = Vec::new();
+    let mut previous: Option<DateTime<Utc>> = None;
+    for commit in commits {
+        let start = previous.or(commit.parent_time);
+        previous = Some(commit.time);
+        // Commits made before the project's first recorded usage are left out
+        if first_usage.is_some_and(|first_usage| commit.time >= first_usage) {
+            windows.push((commit, start, Accumulator::default()));
+        }
+    }
+
+    let mut uncommitted = Accumulator::default();
+    for event in events {
+        // First commit at or after the request; its window must have started before it
+        let index = windows.partition_point(|(commit, _, _)| commit.time < event.timestamp);
+        match windows.get_mut(index) {
+            Some((_, start, usage)) if start.is_none_or(|start| start < event.timestamp) => {
+                usage.add(event)
+            }
+            Some(_) => {}
+            None if previous.is_none_or(|newest| newest < event.timestamp) => {
+                uncommitted.add(event)
+            }
+            None => {}
+        }
+    }
+
+    let mut cumulative_cost = 0.0;
+    let commits: Vec<CommitWindow> = windows
+        .into_iter()
+        .map(|(commit, window_start, usage)| {
+            let usage = usage.finish();
+            cumulative_cost += usage.cost;
+            CommitWindow {
+                id: commit.id,
+                summary: commit.summary,
+                author: commit.author,
+                committed_at: commit.time,
+                window_start,
+                usage,
+                cumulative_cost,
+            }
+        })
+        .collect();
+    GitCorrelation {
+        repository: history.workdir,
+        project: project.to_string(),
+        committed_tokens: commits.iter().map(|commit| commit.usage.tokens).sum(),
+        committed_cost: cumulative_cost,
+        commits,
+        uncommitted: uncommitted.finish(),
+    }
+}
+
+#[derive(Debug, Default)]
+struct Accumulator {
+    usage: WindowUsage,
+    sessions: HashSet<String>,
+}
+
+impl Accumulator {
+    fn add(&mut self, event: &UsageEvent) {
+        self.usage.requests += 1;
+        self.usage.tokens += event.usage.total_tokens();
+        self.usage.cost += event.usage.total_cost;
+        self.sessions.insert(event.session.clone());
+    }
+
+    fn finish(self) -> WindowUsage {
+        WindowUsage {
+            sessions: self.sessions.len(),
+            ..self.usage
+        }
+    }
+}
+
+pub use git_history::read_history;
+
+#[cfg(feature = "git")]
+mod git_history {
+    use super::{CommitInfo, RepoHistory};
+    use anyhow::{Context, Result};
+    use chrono::{DateTime, Utc};
+    use git2::{Repository, Sort};
+    use std::path::Path;
+
+    fn commit_time(commit: &git2::Commit) -> DateTime<Utc> {
+        DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_default()
+    }
+
+    /// First-parent history of `rev`, excluding commits reachable from `base`, newest first
+    pub fn read_history(path: &Path, rev: &str, base: Option<&str>) -> Result<RepoHistory> {
+        let repo = Repository::discover(path)
+            .with_context(|| format!("No git repository found at {}", path.display()))?;
+        let workdir = repo
+            .workdir()
+            .context("Bare repositories have no working directory to match sessions to")?
+            .to_path_buf();
+
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TIME)?;
+        walk.simplify_first_parent()?;
+        let head = repo
+            .revparse_single(rev)
+            .with_context(|| format!("Unknown revision: {}", rev))?;
+        walk.push(head.peel_to_commit()?.id())?;
+        if let Some(base) = base {
+            let base = repo
+                .revparse_single(base)
+                .with_context(|| format!("Unknown revision: {}", base))?;
+            walk.hide(base.peel_to_commit()?.id())?;
+        }
+
+        let mut commits = Vec::new();
+        for oid in walk {
+            let commit = repo.find_commit(oid?)?;
+            commits.push(CommitInfo {
+                id: commit.id().to_string()[..7].to_string(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                author: commit.author().name().unwrap_or_default().to_string(),
+                time: commit_time(&commit),
+                parent_time: commit.parent(0).ok().map(|parent| commit_time(&parent)),
+            });
+        }
+        Ok(RepoHistory { workdir, commits })
+    }
+}
+
+/// Fallback used when the binary was built without git support
+#[cfg(not(feature = "git"))]
+mod git_history {
+    use super::RepoHistory;
+    use anyhow::Result;
+    use std::path::Path;
+
+    pub fn read_history(_path: &Path, _rev: &str, _base: Option<&str>) -> Result<RepoHistory> {
+        anyhow::bail!(
+            "git-correlate is not available in this build. Rebuild with `cargo install claudelytics --features git`"
+        )
+    }
+}
+
+pub fn display_git_correlation(report: &GitCorrelation, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error serializing git correlation: {}", e),
+        }
+        return;
+    }
+
+    println!("{}", "🔗 Claude Usage per Commit".bright_blue().bold());
+    println!(
+        "{}",
+        format!(
+            "Repository {} · project {}",
+            report.repository.display(),
+            report.project
+        )
+        .dimmed()
+    );
+    println!();
+
+    if report.commits.is_empty() {
+        println!("No commits since the first recorded usage of this project.");
+    } else {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_header(vec![
+                Cell::new("Commit").fg(Color::Cyan),
+                Cell::new("Committed").fg(Color::Cyan),
+                Cell::new("Summary").fg(Color::Cyan),
+                Cell::new("Requests").fg(Color::Cyan),
+                Cell::new("Sessions").fg(Color::Cyan),
+                Cell::new("Tokens").fg(Color::Cyan),
+                Cell::new(currency::cost_header()).fg(Color::Cyan),
+                Cell::new("Cumulative").fg(Color::Cyan),
+            ]);
+        for commit in &report.commits {
+            let summary: String = commit.summary.chars().take(SUMMARY_CHARS).collect();
+            table.add_row(vec![
+                Cell::new(&commit.id).fg(Color::Yellow),
+                Cell::new(
+                    commit
+                        .committed_at
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M"),
+                ),
+                Cell::new(summary),
+                Cell::new(commit.usage.requests),
+                Cell::new(commit.usage.sessions),
+                Cell::new(format_number(commit.usage.tokens)),
+                Cell::new(format!("{:.4}", Money(commit.usage.cost))).fg(Color::Green),
+                Cell::new(format!("{:.4}", Money(commit.cumulative_cost))),
+            ]);
+        }
+        println!("{}", table);
+    }
+
+    println!(
+        "\nCommitted: {} tokens, {:.4} over {} commits",
+        format_number(report.committed_tokens),
+        Money(report.committed_cost),
+        report.commits.len()
+    );
+    if report.uncommitted.requests > 0 {
+        println!(
+            "{}",
+            format!(
+                "Uncommitted: {} tokens, {:.4} since the newest commit",
+                format_number(report.uncommitted.tokens),
+                Money(report.uncommitted.cost)
+            )
+            .yellow()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_correlate_assigns_usage_to_commit_windows() {
+        let at = |hour, minute| Utc.with_ymd_and_hms(2024, 3, 1, hour, minute, 0).unwrap();
+        let commit = |id: &str, time, parent_time| CommitInfo {
+            id: id.to_string(),
+            summary: format!("commit {}", id),
+            author: "me".to_string(),
+            time,
+            parent_time,
+        };
+        let history = RepoHistory {
+            workdir: PathBuf::from("/home/me/app"),
+            // Newest first, as read from the repository
+            commits: vec![
+                commit("c2", at(12, 0), Some(at(10, 0))),
+                commit("c1", at(10, 0), Some(at(8, 0))),
+            ],
+        };
+        let event = |time, session: &str, cost| UsageEvent {
+            timestamp: time,
+            session: session.to_string(),
+            model: "claude-sonnet-4".to_string(),
+            usage: TokenUsage {
+                input_tokens: 100,
+                total_cost: cost,
+                ..Default::default()
+            },
+        };
+        let events = vec![
+            // Before the first window
+            event(at(7, 0), "-home-me-app/s0", 8.0),
+            event(at(9, 0), "-home-me-app/s1", 1.0),
+            event(at(10, 0), "-home-me-app/s1", 2.0),
+            event(at(11, 0), "-home-me-app/s2", 4.0),
+            event(at(11, 30), "-home-me-other/s9", 16.0),
+            event(at(13, 0), "-home-me-app/s2", 32.0),
+        ];
+
+        assert_eq!(
+            claude_project_dir(Path::new("/home/me/app/")),
+            "-home-me-app"
+        );
+        let report = correlate(history, "-home-me-app", &events);
+        assert_eq!(report.commits.len(), 2);
+        let first = &report.commits[0];
+        assert_eq!(first.id, "c1");
+        assert_eq!(first.window_start, Some(at(8, 0)));
+        assert_eq!(first.usage.requests, 2);
+        assert_eq!(first.usage.cost, 3.0);
+        assert_eq!(first.usage.sessions, 1);
+        let second = &report.commits[1];
+        assert_eq!(second.usage.cost, 4.0);
+        assert_eq!(second.usage.tokens, 100);
+        assert_eq!(second.cumulative_cost, 7.0);
+        assert_eq!(report.committed_cost, 7.0);
+        assert_eq!(report.uncommitted.cost, 32.0);
+    }
+}
@@ -0,0 +1,337 @@
+//! Usage goals (`goals` command and the Goals view of `analytics-tui`)
+//!
+//! Every day is checked against the daily targets and the trailing 7-day
+//! targets saved in the `goals` section of the config. Days without usage are
+//! compliant, so a streak only ends on a day that went over a target.
+
+use crate::config_v2::GoalsConfig;
+use crate::currency::Money;
+use crate::models::DailyUsageMap;
+use crate::terminal::Terminal;
+use chrono::{Duration, NaiveDate};
+use colored::Colorize;
+use serde::Serialize;
+
+/// Days covered by the violation history
+pub const HISTORY_DAYS: i64 = 30;
+
+/// Length of the trailing window checked by weekly goals
+const WEEK_DAYS: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalUnit {
+    Usd,
+    Tokens,
+}
+
+impl GoalUnit {
+    pub fn format(self, value: f64) -> String {
+        match self {
+            GoalUnit::Usd => format!("{:.2}", Money(value)),
+            GoalUnit::Tokens => format_tokens(value as u64),
+        }
+    }
+}
+
+/// One configured target
+#[derive(Debug, Clone, Copy)]
+struct Goal {
+    name: &'static str,
+    weekly: bool,
+    unit: GoalUnit,
+    limit: f64,
+}
+
+impl Goal {
+    fn all(goals: &GoalsConfig) -> Vec<Goal> {
+        let tokens = |limit: Option<u64>| limit.map(|l| l as f64);
+        [
+            ("daily cost", false, GoalUnit::Usd, goals.max_daily_cost),
+            (
+                "daily tokens",
+                false,
+                GoalUnit::Tokens,
+                tokens(goals.max_daily_tokens),
+            ),
+            ("weekly cost", true, GoalUnit::Usd, goals.max_weekly_cost),
+            (
+                "weekly tokens",
+                true,
+                GoalUnit::Tokens,
+                tokens(goals.max_weekly_tokens),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, weekly, unit, limit)| {
+            limit.map(|limit| Goal {
+                name,
+                weekly,
+                unit,
+                limit,
+            })
+        })
+        .collect()
+    }
+
+    fn actual(&self, day: &DayTotals, week: &DayTotals) -> f64 {
+        let totals = if self.weekly { week } else { day };
+        match self.unit {
+            GoalUnit::Usd => totals.cost,
+            GoalUnit::Tokens => totals.tokens as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct DayTotals {
+    cost: f64,
+    tokens: u64,
+}
+
+/// Today's standing against one goal (weekly goals cover the last 7 days)
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalProgress {
+    pub goal: String,
+    pub unit: GoalUnit,
+    pub limit: f64,
+    pub actual: f64,
+    pub met: bool,
+}
+
+/// A day that went over at least one goal
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalViolation {
+    pub date: NaiveDate,
+    pub cost: f64,
+    pub tokens: u64,
+    /// Names of the goals missed, e.g. `daily cost`
+    pub missed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalsReport {
+    pub today: NaiveDate,
+    pub goals: GoalsConfig,
+    pub progress: Vec<GoalProgress>,
+    /// Compliant days in a row ending today
+    pub current_streak: usize,
+    /// Longest run of compliant days since the first recorded usage
+    pub longest_streak: usize,
+    /// Days over a goal within the last `HISTORY_DAYS` days, newest first
+    pub violations: Vec<GoalViolation>,
+}
+
+impl GoalsReport {
+    /// Check every day from the first recorded usage up to `today`
+    pub fn new(daily_map: &DailyUsageMap, goals: &GoalsConfig, today: NaiveDate) -> Self {
+        let all_goals = Goal::all(goals);
+        let first = daily_map.keys().min().copied().unwrap_or(today).min(today);
+
+        // Leading days so the first checked day has a full trailing week
+        let start = first - Duration::days(WEEK_DAYS as i64 - 1);
+        let days: Vec<(NaiveDate, DayTotals)> = start
+            .iter_days()
+            .take_while(|date| *date <= today)
+            .map(|date| {
+                let totals = daily_map
+                    .get(&date)
+                    .map_or_else(DayTotals::default, |u| DayTotals {
+                        cost: u.total_cost,
+                        tokens: u.total_tokens(),
+                    });
+                (date, totals)
+            })
+            .collect();
+
+        let history_start = today - Duration::days(HISTORY_DAYS - 1);
+        let mut current_streak = 0;
+        let mut longest_streak = 0;
+        let mut violations = Vec::new();
+        let mut progress = Vec::new();
+
+        for i in WEEK_DAYS - 1..days.len() {
+            let (date, day) = days[i];
+            let week =
+                days[i + 1 - WEEK_DAYS..=i]
+                    .iter()
+                    .fold(DayTotals::default(), |acc, (_, d)| DayTotals {
+                        cost: acc.cost + d.cost,
+                        tokens: acc.tokens + d.tokens,
+                    });
+
+            let missed: Vec<String> = all_goals
+                .iter()
+                .filter(|goal| goal.actual(&day, &week) > goal.limit)
+                .map(|goal| goal.name.to_string())
+                .collect();
+
+            if missed.is_empty() {
+                current_streak += 1;
+                longest_streak = longest_streak.max(current_streak);
+            } else {
+                current_streak = 0;
+                if date >= history_start {
+                    violations.push(GoalViolation {
+                        date,
+                        cost: day.cost,
+                        tokens: day.tokens,
+                        missed,
+                    });
+                }
+            }
+
+            if date == today {
+                progress = all_goals
+                    .iter()
+                    .map(|goal| {
+                        let actual = goal.actual(&day, &week);
+                        GoalProgress {
+                            goal: goal.name.to_string(),
+                            unit: goal.unit,
+                            limit: goal.limit,
+                            actual,
+                            met: actual <= goal.limit,
+                        }
+                    })
+                    .collect();
+            }
+        }
+        violations.reverse();
+
+        Self {
+            today,
+            goals: goals.clone(),
+            progress,
+            current_streak,
+            longest_streak,
+            violations,
+        }
+    }
+}
+
+pub fn display_goals_report(report: &GoalsReport, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "USAGE GOALS".bold());
+    println!("{}", Terminal::separator('═'));
+
+    println!("{} ({})", "Today".bold(), report.today);
+    for progress in &report.progress {
+        let status = if progress.met {
+            "✓ on track".green()
+        } else {
+            "✗ over".red()
+        };
+        println!(
+            "  {:<18} {:>10} / {:<10} {}",
+            format!("{}:", capitalize(&progress.goal)).cyan(),
+            progress.unit.format(progress.actual),
+            progress.unit.format(progress.limit),
+            status
+        );
+    }
+    if report.progress.iter().any(|p| p.goal.starts_with("weekly")) {
+        println!("  {}", "Weekly goals cover the last 7 days".dimmed());
+    }
+
+    println!();
+    println!(
+        "  {:<18} {} days",
+        "Current streak:".cyan(),
+        report.current_streak.to_string().bold()
+    );
+    println!(
+        "  {:<18} {} days",
+        "Longest streak:".cyan(),
+        report.longest_streak
+    );
+    println!(
+        "  {:<18} {} of the last {}",
+        "Days over a goal:".cyan(),
+        report.violations.len(),
+        HISTORY_DAYS
+    );
+
+    if !report.violations.is_empty() {
+        println!("\n{}", "Recent Violations".bold());
+        println!("{}", "─".repeat(60));
+        for violation in &report.violations {
+            println!(
+                "  {}  {:>9}  {:>7} tokens  {}",
+                violation.date,
+                format!("${:.2}", violation.cost),
+                format_tokens(violation.tokens),
+                violation.missed.join(", ").red()
+            );
+        }
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn format_tokens(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.0}K", n as f64 / 1_000.0)
+    } else {
+        format!("{}", n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+
+    fn usage(cost: f64, tokens: u64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: tokens,
+            total_cost: cost,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_goal_streaks_and_violations() {
+        let date = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut daily_map = DailyUsageMap::new();
+        daily_map.insert(date(1), usage(5.0, 100_000));
+        daily_map.insert(date(2), usage(12.0, 100_000)); // over the daily cost goal
+        daily_map.insert(date(3), usage(2.0, 100_000));
+        daily_map.insert(date(5), usage(3.0, 900_000)); // week reaches 1.2M tokens
+        daily_map.insert(date(12), usage(1.0, 10_000));
+
+        let goals = GoalsConfig {
+            max_daily_cost: Some(10.0),
+            max_weekly_tokens: Some(1_000_000),
+            ..Default::default()
+        };
+        let report = GoalsReport::new(&daily_map, &goals, date(14));
+
+        let violated: Vec<_> = report.violations.iter().map(|v| v.date).collect();
+        assert_eq!(violated, vec![date(8), date(7), date(6), date(5), date(2)]);
+        assert_eq!(report.violations[4].missed, vec!["daily cost"]);
+        assert_eq!(report.violations[0].missed, vec!["weekly tokens"]);
+        // 2024-03-09 through 2024-03-14, once 2024-03-05 leaves the week
+        assert_eq!(report.current_streak, 6);
+        assert_eq!(report.longest_streak, 6);
+
+        assert_eq!(report.progress.len(), 2);
+        assert!(report.progress.iter().all(|p| p.met));
+        assert_eq!(report.progress[1].actual, 10_000.0);
+    }
+}
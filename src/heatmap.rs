@@ -0,0 +1,480 @@
+//! Calendar heatmap of daily usage (`export --heatmap`)
+//!
+//! One cell per day with weeks as columns and weekdays as rows, like GitHub's
+//! contribution graph. A day's shade is its cost relative to the other active
+//! days (quartiles), so a few expensive days do not wash out the rest. Rendered
+//! as colored blocks in the terminal and as a standalone SVG file.
+
+use crate::currency::Money;
+use crate::models::DailyUsageMap;
+use crate::session_analytics::usage_streaks;
+use crate::terminal::Terminal;
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use colored::Colorize;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Weeks shown when no `--since` date is given
+const DEFAULT_WEEKS: i64 = 53;
+
+/// Cell colors from no usage (level 0) to the busiest quartile (level 4)
+const LEVEL_COLORS: [(u8, u8, u8); 5] = [
+    (235, 237, 240),
+    (155, 233, 168),
+    (64, 196, 99),
+    (48, 161, 78),
+    (33, 110, 57),
+];
+
+const CELL_SIZE: f64 = 11.0;
+const CELL_STEP: f64 = 14.0;
+const SVG_LEFT: f64 = 34.0;
+const SVG_TOP: f64 = 22.0;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatmapDay {
+    pub date: NaiveDate,
+    pub cost: f64,
+    pub tokens: u64,
+    pub sessions: usize,
+    /// Shade from 0 (no usage) to 4
+    pub level: u8,
+}
+
+/// Usage on one weekday over the whole range
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekdayUsage {
+    pub weekday: String,
+    pub cost: f64,
+    pub active_days: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Heatmap {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Every day from `start` to `end`, oldest first
+    pub days: Vec<HeatmapDay>,
+    pub active_days: usize,
+    pub total_cost: f64,
+    pub longest_streak: usize,
+    pub current_streak: usize,
+    /// Monday first
+    pub weekdays: Vec<WeekdayUsage>,
+}
+
+impl Heatmap {
+    /// Build the heatmap from `since` (default: the Monday 52 weeks before the
+    /// end's week) to `until` (default: `today`)
+    pub fn build(
+        daily_map: &DailyUsageMap,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+        today: NaiveDate,
+    ) -> Self {
+        let end = until.unwrap_or(today);
+        let start = since.unwrap_or_else(|| {
+            let week_start = end - Duration::days(i64::from(end.weekday().num_days_from_monday()));
+            week_start - Duration::weeks(DEFAULT_WEEKS - 1)
+        });
+
+        let mut days: Vec<HeatmapDay> = start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .map(|date| {
+                let usage = daily_map.get(&date);
+                HeatmapDay {
+                    date,
+                    cost: usage.map_or(0.0, |u| u.total_cost),
+                    tokens: usage.map_or(0, |u| u.total_tokens()),
+                    sessions: usage.map_or(0, |u| u.by_session.len()),
+                    level: 0,
+                }
+            })
+            .collect();
+
+        let active = |day: &HeatmapDay| day.cost > 0.0 || day.tokens > 0;
+        let mut costs: Vec<f64> = days
+            .iter()
+            .filter(|day| active(day))
+            .map(|day| day.cost)
+            .collect();
+        costs.sort_by(f64::total_cmp);
+        let quartiles: Vec<f64> = if costs.is_empty() {
+            Vec::new()
+        } else {
+            [1, 2, 3]
+                .iter()
+                .map(|q| costs[costs.len() * q / 4])
+                .collect()
+        };
+
+        let mut weekdays: Vec<WeekdayUsage> = WEEKDAYS
+            .iter()
+            .map(|weekday| WeekdayUsage {
+                weekday: format!("{:?}", weekday),
+                cost: 0.0,
+                active_days: 0,
+            })
+            .collect();
+        let mut active_dates = Vec::new();
+        for day in days.iter_mut().filter(|day| active(day)) {
+            day.level = 1 + quartiles.iter().filter(|q| day.cost > **q).count() as u8;
+            let weekday = &mut weekdays[day.date.weekday().num_days_from_monday() as usize];
+            weekday.cost += day.cost;
+            weekday.active_days += 1;
+            active_dates.push(day.date);
+        }
+
+        let (longest_streak, current_streak) = usage_streaks(&active_dates, today);
+        Self {
+            start,
+            end,
+            active_days: active_dates.len(),
+            total_cost: days.iter().map(|day| day.cost).sum(),
+            longest_streak,
+            current_streak,
+            weekdays,
+            days,
+        }
+    }
+
+    /// Weekday with the highest total cost
+    pub fn busiest_weekday(&self) -> Option<&WeekdayUsage> {
+        self.weekdays
+            .iter()
+            .filter(|weekday| weekday.active_days > 0)
+            .max_by(|a, b| a.cost.total_cmp(&b.cost))
+    }
+
+    /// Week columns (Monday to Sunday); days outside the range are `None`
+    fn weeks(&self) -> Vec<[Option<&HeatmapDay>; 7]> {
+        let offset = self.start.weekday().num_days_from_monday() as usize;
+        let mut weeks = vec![[None; 7]; (offset + self.days.len()).div_ceil(7)];
+        for (i, day) in self.days.iter().enumerate() {
+            weeks[(offset + i) / 7][(offset + i) % 7] = Some(day);
+        }
+        weeks
+    }
+
+    /// Month abbreviation for week columns in which a month starts
+    fn month_labels(weeks: &[[Option<&HeatmapDay>; 7]]) -> Vec<Option<String>> {
+        weeks
+            .iter()
+            .enumerate()
+            .map(|(i, week)| {
+                let first = week.iter().flatten().next()?;
+                let starts_month = week.iter().flatten().any(|day| day.date.day() == 1);
+                (i == 0 || starts_month).then(|| {
+                    week.iter()
+                        .flatten()
+                        .find(|day| day.date.day() == 1)
+                        .unwrap_or(first)
+                        .date
+                        .format("%b")
+                        .to_string()
+                })
+            })
+            .collect()
+    }
+
+    /// Render the heatmap as a standalone SVG document
+    pub fn render_svg(&self) -> String {
+        let weeks = self.weeks();
+        let width = SVG_LEFT + weeks.len() as f64 * CELL_STEP + 10.0;
+        let height = SVG_TOP + 7.0 * CELL_STEP + 52.0;
+
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" width="{width}" height="{height}" font-family="-apple-system, BlinkMacSystemFont, Segoe UI, Helvetica, Arial, sans-serif" font-size="10">"#
+        );
+        svg.push_str(r##"<rect width="100%" height="100%" fill="#ffffff"/>"##);
+
+        let mut last_label_x = f64::MIN;
+        for (i, label) in Self::month_labels(&weeks).into_iter().enumerate() {
+            let x = SVG_LEFT + i as f64 * CELL_STEP;
+            if let Some(label) = label
+                && x - last_label_x >= 3.0 * CELL_STEP
+            {
+                let _ = write!(
+                    svg,
+                    r##"<text x="{x}" y="{y}" fill="#656d76">{label}</text>"##,
+                    y = SVG_TOP - 8.0
+                );
+                last_label_x = x;
+            }
+        }
+        for (row, label) in [(0, "Mon"), (2, "Wed"), (4, "Fri")] {
+            let _ = write!(
+                svg,
+                r##"<text x="0" y="{y}" fill="#656d76">{label}</text>"##,
+                y = SVG_TOP + row as f64 * CELL_STEP + 9.0
+            );
+        }
+
+        for (column, week) in weeks.iter().enumerate() {
+            for (row, day) in week.iter().enumerate() {
+                let Some(day) = day else { continue };
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{x}" y="{y}" width="{CELL_SIZE}" height="{CELL_SIZE}" rx="2" fill="{fill}"><title>{date}: ${cost:.2}, {tokens} tokens, {sessions} sessions</title></rect>"#,
+                    x = SVG_LEFT + column as f64 * CELL_STEP,
+                    y = SVG_TOP + row as f64 * CELL_STEP,
+                    fill = hex_color(day.level),
+                    date = day.date,
+                    cost = day.cost,
+                    tokens = day.tokens,
+                    sessions = day.sessions,
+                );
+            }
+        }
+
+        // Legend and summary below the grid
+        let legend_y = SVG_TOP + 7.0 * CELL_STEP + 10.0;
+        let legend_x = width - 10.0 - 5.0 * CELL_STEP - 60.0;
+        let _ = write!(
+            svg,
+            r##"<text x="{x}" y="{y}" fill="#656d76">Less</text>"##,
+            x = legend_x,
+            y = legend_y + 9.0
+        );
+        for level in 0..5u8 {
+            let _ = write!(
+                svg,
+                r#"<rect x="{x}" y="{legend_y}" width="{CELL_SIZE}" height="{CELL_SIZE}" rx="2" fill="{fill}"/>"#,
+                x = legend_x + 28.0 + f64::from(level) * CELL_STEP,
+                fill = hex_color(level),
+            );
+        }
+        let _ = write!(
+            svg,
+            r##"<text x="{x}" y="{y}" fill="#656d76">More</text>"##,
+            x = legend_x + 32.0 + 5.0 * CELL_STEP,
+            y = legend_y + 9.0
+        );
+        let _ = write!(
+            svg,
+            r##"<text x="0" y="{y}" fill="#1f2328">{summary}</text>"##,
+            y = legend_y + 9.0,
+            summary = self.summary_line()
+        );
+        let _ = write!(
+            svg,
+            r##"<text x="0" y="{y}" fill="#656d76">{start} to {end}</text>"##,
+            y = legend_y + 28.0,
+            start = self.start,
+            end = self.end
+        );
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Write the SVG rendering to `path`
+    pub fn export_svg(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.render_svg())
+            .with_context(|| format!("Failed to write heatmap SVG: {}", path.display()))
+    }
+
+    fn summary_line(&self) -> String {
+        let mut line = format!(
+            "{} active days, {:.2} total · longest streak {} days · current streak {} days",
+            self.active_days,
+            Money(self.total_cost),
+            self.longest_streak,
+            self.current_streak
+        );
+        if let Some(busiest) = self.busiest_weekday() {
+            let _ = write!(line, " · busiest weekday {}", busiest.weekday);
+        }
+        line
+    }
+}
+
+fn hex_color(level: u8) -> String {
+    let (r, g, b) = LEVEL_COLORS[usize::from(level.min(4))];
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Print the heatmap as colored blocks, or as JSON
+pub fn display_heatmap(heatmap: &Heatmap, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(heatmap) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "USAGE HEATMAP".bold());
+    println!("{}", Terminal::separator('═'));
+
+    // Keep the most recent weeks that fit: 4 columns of labels, 2 per week
+    let weeks = heatmap.weeks();
+    let fit = (usize::from(Terminal::width()).saturating_sub(4) / 2).max(1);
+    let skip = weeks.len().saturating_sub(fit);
+    let weeks = &weeks[skip..];
+
+    let mut header = vec![' '; 4 + weeks.len() * 2];
+    let mut next_free = 0;
+    for (i, label) in Heatmap::month_labels(weeks).into_iter().enumerate() {
+        let column = 4 + i * 2;
+        if let Some(label) = label
+            && column >= next_free
+            && column + label.len() <= header.len()
+        {
+            header.splice(column..column + label.len(), label.chars());
+            next_free = column + label.len() + 1;
+        }
+    }
+    println!("{}", header.iter().collect::<String>().trim_end().dimmed());
+
+    for (row, weekday) in WEEKDAYS.iter().enumerate() {
+        let label = if row % 2 == 0 {
+            format!("{:?}", weekday)
+        } else {
+            String::new()
+        };
+        let mut line = format!("{:<4}", label).dimmed().to_string();
+        for week in weeks {
+            match week[row] {
+                Some(day) => {
+                    let (r, g, b) = LEVEL_COLORS[usize::from(day.level)];
+                    let _ = write!(line, "{} ", "■".truecolor(r, g, b));
+                }
+                None => line.push_str("  "),
+            }
+        }
+        println!("{}", line.trim_end());
+    }
+
+    let legend: String = (0..5)
+        .map(|level| {
+            let (r, g, b) = LEVEL_COLORS[level];
+            format!("{} ", "■".truecolor(r, g, b))
+        })
+        .collect();
+    println!("    {} {}{}", "Less".dimmed(), legend, "More".dimmed());
+
+    println!();
+    println!(
+        "  {:<18} {} to {}",
+        "Range:".cyan(),
+        heatmap.start,
+        heatmap.end
+    );
+    println!("  {:<18} {}", "Active days:".cyan(), heatmap.active_days);
+    println!(
+        "  {:<18} {} days",
+        "Longest streak:".cyan(),
+        heatmap.longest_streak
+    );
+    println!(
+        "  {:<18} {} days",
+        "Current streak:".cyan(),
+        heatmap.current_streak
+    );
+    println!(
+        "  {:<18} {}",
+        "Total cost:".bold(),
+        format!("${:.2}", heatmap.total_cost).green()
+    );
+
+    let max_cost = heatmap
+        .weekdays
+        .iter()
+        .map(|weekday| weekday.cost)
+        .fold(0.0, f64::max);
+    if max_cost > 0.0 {
+        println!("\n{}", "By Weekday".bold());
+        println!("{}", "─".repeat(48));
+        let busiest = heatmap
+            .busiest_weekday()
+            .map(|weekday| weekday.weekday.as_str());
+        for weekday in &heatmap.weekdays {
+            let bar = "█".repeat((weekday.cost / max_cost * 20.0).round() as usize);
+            let name = if Some(weekday.weekday.as_str()) == busiest {
+                weekday.weekday.bold().to_string()
+            } else {
+                weekday.weekday.clone()
+            };
+            println!(
+                "  {:<5} {:<20} {:>9} {:>4} days",
+                name,
+                bar.green(),
+                format!("${:.2}", weekday.cost),
+                weekday.active_days
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+
+    #[test]
+    fn test_heatmap_levels_and_streaks() {
+        let date = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut daily_map = DailyUsageMap::new();
+        // Mon 4th to Thu 7th, then Sat 9th and Sun 10th
+        for (day, cost) in [(4, 1.0), (5, 2.0), (6, 3.0), (7, 4.0), (9, 8.0), (10, 1.0)] {
+            let usage = TokenUsage {
+                input_tokens: 100,
+                total_cost: cost,
+                message_count: 1,
+                ..Default::default()
+            };
+            daily_map.insert(date(day), usage);
+        }
+
+        let heatmap = Heatmap::build(&daily_map, Some(date(1)), None, date(10));
+        assert_eq!(heatmap.days.len(), 10);
+        assert_eq!(heatmap.active_days, 6);
+        assert_eq!((heatmap.longest_streak, heatmap.current_streak), (4, 2));
+        assert_eq!(heatmap.total_cost, 19.0);
+
+        let level = |d: u32| heatmap.days[d as usize - 1].level;
+        assert_eq!(level(8), 0);
+        assert_eq!(level(4), 1);
+        assert_eq!(level(9), 4);
+        assert!(level(5) < level(7));
+
+        let busiest = heatmap.busiest_weekday().unwrap();
+        assert_eq!(busiest.weekday, "Sat");
+
+        // March 1st 2024 is a Friday: the first column starts with four blanks
+        let weeks = heatmap.weeks();
+        assert_eq!(weeks.len(), 2);
+        assert!(weeks[0][3].is_none());
+        assert_eq!(weeks[0][4].unwrap().date, date(1));
+
+        let svg = heatmap.render_svg();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<title>").count(), 10);
+        assert!(svg.contains("2024-03-09: $8.00"));
+    }
+
+    #[test]
+    fn test_default_range_starts_on_monday() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 12).unwrap();
+        let heatmap = Heatmap::build(&DailyUsageMap::new(), None, None, today);
+        assert_eq!(heatmap.start.weekday(), Weekday::Mon);
+        assert_eq!(heatmap.weeks().len() as i64, DEFAULT_WEEKS);
+        assert_eq!(heatmap.active_days, 0);
+        assert!(heatmap.busiest_weekday().is_none());
+    }
+}
@@ -52,6 +52,21 @@ where
     });
 }
 
+/// Human-readable size of a file or directory, e.g. `12.3 MB`
+pub fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= GB {
+        format!("{:.1} GB", bytes / GB)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes / MB)
+    } else {
+        format!("{:.1} KB", bytes / KB)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,4 +100,11 @@ mod tests {
         assert_eq!(calculate_average(&[10.0, 20.0]), 15.0);
         assert_eq!(calculate_average::<f64>(&[]), 0.0);
     }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "0.5 KB");
+        assert_eq!(format_size(5 * 1_048_576), "5.0 MB");
+        assert_eq!(format_size(3 * 1_073_741_824 / 2), "1.5 GB");
+    }
 }
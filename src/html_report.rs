@@ -0,0 +1,401 @@
+//! Standalone HTML report export
+//!
+//! Renders a single self-contained HTML file (inline CSS and pre-rendered SVG
+//! charts, no external assets or JavaScript) with cost over time, tokens by
+//! model family, and sessions by project.
+
+use crate::currency::Money;
+use crate::models::{DailyReport, SessionReport};
+use anyhow::{Context, Result};
+use chrono::Local;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Number of projects shown in the sessions-by-project chart
+const TOP_PROJECTS: usize = 10;
+
+const CHART_WIDTH: f64 = 860.0;
+const CHART_HEIGHT: f64 = 240.0;
+const BAR_ROW_HEIGHT: f64 = 28.0;
+const LABEL_WIDTH: f64 = 220.0;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; margin: 0; background: #f6f7f9; color: #1f2328; }
+main { max-width: 920px; margin: 0 auto; padding: 32px 24px; }
+h1 { margin: 0 0 4px; font-size: 26px; }
+h2 { font-size: 18px; margin: 32px 0 12px; }
+.meta { color: #656d76; font-size: 14px; }
+.cards { display: grid; grid-template-columns: repeat(4, 1fr); gap: 12px; margin-top: 24px; }
+.card { background: #fff; border: 1px solid #d0d7de; border-radius: 8px; padding: 14px 16px; }
+.card .label { color: #656d76; font-size: 12px; text-transform: uppercase; letter-spacing: .04em; }
+.card .value { font-size: 22px; font-weight: 600; margin-top: 4px; }
+section { background: #fff; border: 1px solid #d0d7de; border-radius: 8px; padding: 16px; }
+svg text { font-size: 12px; fill: #424a53; }
+table { width: 100%; border-collapse: collapse; font-size: 13px; }
+th, td { text-align: right; padding: 6px 8px; border-bottom: 1px solid #eaeef2; }
+th:first-child, td:first-child { text-align: left; }
+th { color: #656d76; font-weight: 600; }
+.empty { color: #656d76; font-style: italic; }
+"#;
+
+/// Write the HTML report for `daily_report` and `session_report` to `path`.
+///
+/// Daily rows should carry their per-model breakdown (see
+/// `reports::attach_daily_model_breakdown`) for the tokens-by-model chart.
+pub fn export_html_report(
+    daily_report: &DailyReport,
+    session_report: &SessionReport,
+    path: &Path,
+) -> Result<()> {
+    let html = render_html_report(daily_report, session_report);
+    fs::write(path, html)
+        .with_context(|| format!("Failed to write HTML report: {}", path.display()))
+}
+
+/// Render the complete HTML document
+pub fn render_html_report(daily_report: &DailyReport, session_report: &SessionReport) -> String {
+    // Chronological order for the time series and the table
+    let mut daily: Vec<_> = daily_report.daily.iter().collect();
+    daily.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let period = match (daily.first(), daily.last()) {
+        (Some(first), Some(last)) if first.date != last.date => {
+            format!("{} – {}", first.date, last.date)
+        }
+        (Some(first), _) => first.date.clone(),
+        _ => "No data".to_string(),
+    };
+
+    // Tokens by model family
+    let mut models: BTreeMap<&str, (u64, f64)> = BTreeMap::new();
+    for model in daily.iter().flat_map(|day| &day.models) {
+        let entry = models.entry(model.family.as_str()).or_default();
+        entry.0 += model.total_tokens;
+        entry.1 += model.total_cost;
+    }
+    let mut model_rows: Vec<_> = models
+        .into_iter()
+        .map(|(family, (tokens, cost))| {
+            (
+                capitalize(family),
+                tokens as f64,
+                format!("{} tokens · {:.2}", format_tokens(tokens), Money(cost)),
+            )
+        })
+        .collect();
+    model_rows.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    // Sessions by project
+    let mut projects: BTreeMap<&str, (usize, f64)> = BTreeMap::new();
+    for session in &session_report.sessions {
+        let entry = projects.entry(session.project_path.as_str()).or_default();
+        entry.0 += 1;
+        entry.1 += session.total_cost;
+    }
+    let mut project_rows: Vec<_> = projects
+        .into_iter()
+        .map(|(project, (sessions, cost))| {
+            let noun = if sessions == 1 { "session" } else { "sessions" };
+            (
+                project.to_string(),
+                cost,
+                format!("{:.2} · {} {}", Money(cost), sessions, noun),
+            )
+        })
+        .collect();
+    project_rows.sort_by(|a, b| b.1.total_cmp(&a.1));
+    project_rows.truncate(TOP_PROJECTS);
+
+    let cost_points: Vec<_> = daily
+        .iter()
+        .map(|day| (day.date.clone(), day.total_cost))
+        .collect();
+
+    let totals = &daily_report.totals;
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Claude Code Usage Report</title>
+<style>{style}</style>
+</head>
+<body>
+<main>
+<h1>Claude Code Usage Report</h1>
+<div class="meta">{period} · Generated {generated} by claudelytics {version}</div>
+<div class="cards">
+<div class="card"><div class="label">Total cost</div><div class="value">${cost:.2}</div></div>
+<div class="card"><div class="label">Total tokens</div><div class="value">{tokens}</div></div>
+<div class="card"><div class="label">Active days</div><div class="value">{days}</div></div>
+<div class="card"><div class="label">Sessions</div><div class="value">{sessions}</div></div>
+</div>
+"#,
+        style = STYLE,
+        period = escape(&period),
+        generated = Local::now().format("%Y-%m-%d %H:%M"),
+        version = env!("CARGO_PKG_VERSION"),
+        cost = totals.total_cost,
+        tokens = format_tokens(totals.total_tokens),
+        days = daily.len(),
+        sessions = session_report.sessions.len(),
+    );
+
+    let _ = write!(
+        html,
+        "<h2>Cost over time</h2>\n<section>{}</section>\n",
+        column_chart_svg(&cost_points)
+    );
+    let _ = write!(
+        html,
+        "<h2>Tokens by model</h2>\n<section>{}</section>\n",
+        bar_chart_svg(&model_rows)
+    );
+    let _ = write!(
+        html,
+        "<h2>Sessions by project (top {})</h2>\n<section>{}</section>\n",
+        TOP_PROJECTS,
+        bar_chart_svg(&project_rows)
+    );
+
+    html.push_str("<h2>Daily breakdown</h2>\n<section><table>\n");
+    html.push_str("<tr><th>Date</th><th>Input</th><th>Output</th><th>Cache write</th><th>Cache read</th><th>Total tokens</th><th>Cost</th></tr>\n");
+    for day in daily.iter().rev() {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td></tr>",
+            escape(&day.date),
+            format_tokens(day.input_tokens),
+            format_tokens(day.output_tokens),
+            format_tokens(day.cache_creation_tokens),
+            format_tokens(day.cache_read_tokens),
+            format_tokens(day.total_tokens),
+            Money(day.total_cost)
+        );
+    }
+    html.push_str("</table></section>\n</main>\n</body>\n</html>\n");
+
+    html
+}
+
+/// Vertical column chart for a (label, value) time series
+fn column_chart_svg(points: &[(String, f64)]) -> String {
+    if points.is_empty() {
+        return r#"<p class="empty">No data</p>"#.to_string();
+    }
+
+    let (left, bottom, top) = (56.0, 28.0, 12.0);
+    let plot_width = CHART_WIDTH - left - 8.0;
+    let plot_height = CHART_HEIGHT - bottom - top;
+    let max = points.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let max = if max > 0.0 { max } else { 1.0 };
+    let slot = plot_width / points.len() as f64;
+    let bar_width = (slot * 0.8).max(1.0);
+    let label_every = (points.len() as f64 / 8.0).ceil().max(1.0) as usize;
+
+    let mut svg = format!(
+        r#"<svg viewBox="0 0 {w} {h}" width="100%" role="img" xmlns="http://www.w3.org/2000/svg">"#,
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT
+    );
+
+    // Horizontal grid lines with cost labels
+    for step in 0..=4 {
+        let value = max * step as f64 / 4.0;
+        let y = top + plot_height - plot_height * step as f64 / 4.0;
+        let _ = write!(
+            svg,
+            r##"<line x1="{left}" y1="{y:.1}" x2="{x2}" y2="{y:.1}" stroke="#eaeef2"/><text x="{tx}" y="{ty:.1}" text-anchor="end">${value:.2}</text>"##,
+            x2 = CHART_WIDTH - 8.0,
+            tx = left - 6.0,
+            ty = y + 4.0,
+        );
+    }
+
+    for (i, (label, value)) in points.iter().enumerate() {
+        let height = plot_height * value / max;
+        let x = left + slot * i as f64 + (slot - bar_width) / 2.0;
+        let y = top + plot_height - height;
+        let _ = write!(
+            svg,
+            r##"<rect x="{x:.1}" y="{y:.1}" width="{bar_width:.1}" height="{height:.1}" fill="#8250df"><title>{label}: ${value:.2}</title></rect>"##,
+            label = escape(label),
+        );
+        if i % label_every == 0 {
+            let _ = write!(
+                svg,
+                r#"<text x="{cx:.1}" y="{ly}" text-anchor="middle">{label}</text>"#,
+                cx = x + bar_width / 2.0,
+                ly = CHART_HEIGHT - 8.0,
+                label = escape(label),
+            );
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Horizontal bar chart for (label, value, caption) rows
+fn bar_chart_svg(rows: &[(String, f64, String)]) -> String {
+    if rows.is_empty() {
+        return r#"<p class="empty">No data</p>"#.to_string();
+    }
+
+    let caption_width = 200.0;
+    let plot_width = CHART_WIDTH - LABEL_WIDTH - caption_width;
+    let height = BAR_ROW_HEIGHT * rows.len() as f64;
+    let max = rows.iter().map(|(_, v, _)| *v).fold(0.0, f64::max);
+    let max = if max > 0.0 { max } else { 1.0 };
+
+    let mut svg = format!(
+        r#"<svg viewBox="0 0 {w} {height}" width="100%" role="img" xmlns="http://www.w3.org/2000/svg">"#,
+        w = CHART_WIDTH,
+    );
+    for (i, (label, value, caption)) in rows.iter().enumerate() {
+        let y = BAR_ROW_HEIGHT * i as f64;
+        let width = (plot_width * value / max).max(1.0);
+        let _ = write!(
+            svg,
+            r##"<text x="{lx}" y="{ty:.1}" text-anchor="end">{label}</text><rect x="{LABEL_WIDTH}" y="{ry:.1}" width="{width:.1}" height="{bh:.1}" rx="3" fill="#0969da"/><text x="{cx:.1}" y="{ty:.1}">{caption}</text>"##,
+            lx = LABEL_WIDTH - 8.0,
+            ty = y + BAR_ROW_HEIGHT / 2.0 + 4.0,
+            label = escape(&truncate_label(label, 32)),
+            ry = y + 5.0,
+            bh = BAR_ROW_HEIGHT - 10.0,
+            cx = LABEL_WIDTH + width + 8.0,
+            caption = escape(caption),
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Escape text for HTML/SVG content and attributes
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Keep the end of long labels (project paths are most distinctive at the end)
+fn truncate_label(label: &str, max_chars: usize) -> String {
+    let count = label.chars().count();
+    if count <= max_chars {
+        label.to_string()
+    } else {
+        let tail: String = label.chars().skip(count - (max_chars - 1)).collect();
+        format!("…{}", tail)
+    }
+}
+
+fn capitalize(family: &str) -> String {
+    let mut chars = family.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Compact token counts (1.2K, 3.4M)
+fn format_tokens(tokens: u64) -> String {
+    match tokens {
+        t if t >= 1_000_000_000 => format!("{:.1}B", t as f64 / 1_000_000_000.0),
+        t if t >= 1_000_000 => format!("{:.1}M", t as f64 / 1_000_000.0),
+        t if t >= 1_000 => format!("{:.1}K", t as f64 / 1_000.0),
+        t => t.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DailyUsage, ModelUsage, SessionUsage, TokenUsageTotals};
+
+    fn totals(tokens: u64, cost: f64) -> TokenUsageTotals {
+        TokenUsageTotals {
+            input_tokens: tokens,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            total_tokens: tokens,
+            total_cost: cost,
+            thinking_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn test_render_contains_charts_and_escapes() {
+        let daily = DailyReport {
+            daily: vec![DailyUsage {
+                date: "2024-01-15".to_string(),
+                input_tokens: 1500,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                total_tokens: 1500,
+                total_cost: 1.25,
+                thinking_tokens: 0,
+                models: vec![ModelUsage {
+                    family: "opus".to_string(),
+                    input_tokens: 1500,
+                    output_tokens: 0,
+                    cache_creation_tokens: 0,
+                    cache_read_tokens: 0,
+                    total_tokens: 1500,
+                    total_cost: 1.25,
+                }],
+                sources: Vec::new(),
+            }],
+            totals: totals(1500, 1.25),
+        };
+        let sessions = SessionReport {
+            sessions: vec![SessionUsage {
+                project_path: "<script>proj".to_string(),
+                session_id: "s1".to_string(),
+                input_tokens: 1500,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                total_tokens: 1500,
+                total_cost: 1.25,
+                thinking_tokens: 0,
+                last_activity: "2024-01-15".to_string(),
+                summary: None,
+                efficiency_score: None,
+                context_peak_percent: None,
+                resumed_sessions: Vec::new(),
+            }],
+            totals: totals(1500, 1.25),
+            pagination: None,
+        };
+
+        let html = render_html_report(&daily, &sessions);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches("<svg").count(), 3);
+        assert!(html.contains("Opus"));
+        assert!(html.contains("&lt;script&gt;proj"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("$1.25 · 1 session"));
+    }
+
+    #[test]
+    fn test_truncate_label_keeps_tail() {
+        assert_eq!(truncate_label("short", 10), "short");
+        assert_eq!(truncate_label("/very/long/project/path", 8), "…ct/path");
+    }
+}
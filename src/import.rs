@@ -0,0 +1,388 @@
+//! Import of external usage data (Anthropic Console exports, generic CSV/JSON)
+//!
+//! Imported rows are normalized to one record per source, day, and model and kept
+//! in a local store. The parser merges them into the daily and session maps, with
+//! sessions keyed as `imported/<source>` so external spend is always identifiable.
+//! Re-importing an overlapping export replaces the matching rows instead of
+//! double counting them.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project name used for imported usage in session reports
+pub const IMPORTED_PROJECT: &str = "imported";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Anthropic Console usage export (CSV)
+    AnthropicConsole,
+    /// CSV or JSON with `date`, `model`, token columns and optional `cost_usd`
+    Generic,
+}
+
+impl ImportFormat {
+    pub fn default_source(self) -> &'static str {
+        match self {
+            ImportFormat::AnthropicConsole => "anthropic-console",
+            ImportFormat::Generic => "generic",
+        }
+    }
+}
+
+/// Usage for one source, day, and model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedUsage {
+    pub source: String,
+    pub date: NaiveDate,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// Cost reported by the export; calculated from tokens when absent
+    pub cost_usd: Option<f64>,
+}
+
+impl ImportedUsage {
+    fn key(&self) -> (String, NaiveDate, String) {
+        (self.source.clone(), self.date, self.model.clone())
+    }
+
+    fn add(&mut self, other: &ImportedUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cost_usd = match (self.cost_usd, other.cost_usd) {
+            (None, None) => None,
+            (a, b) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0)),
+        };
+    }
+}
+
+/// Persisted imported usage
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ImportStore {
+    pub records: Vec<ImportedUsage>,
+}
+
+impl ImportStore {
+    /// Get the store file path
+    pub fn get_store_path() -> Result<PathBuf> {
+        let data_dir = dirs::data_dir()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("share")))
+            .ok_or_else(|| anyhow::anyhow!("Could not determine data directory"))?;
+
+        Ok(data_dir.join("claudelytics").join("imported_usage.json"))
+    }
+
+    /// Load the store, returning an empty store when nothing has been imported
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::get_store_path()?)
+    }
+
+    fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read(path)
+            .with_context(|| format!("Failed to read import store: {}", path.display()))?;
+        serde_json::from_slice(&data)
+            .with_context(|| format!("Failed to parse import store: {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::get_store_path()?)
+    }
+
+    fn save_to(&self, path: &Path) -> Result<()> {
+        let dir = path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Invalid store path: {}", path.display()))?;
+        fs::create_dir_all(dir)?;
+
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        serde_json::to_writer_pretty(&mut tmp, self)
+            .context("Failed to serialize imported usage")?;
+        tmp.persist(path)
+            .with_context(|| format!("Failed to write import store: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Insert records, replacing existing rows for the same source, day, and model.
+    /// Returns the number of replaced rows.
+    pub fn merge(&mut self, records: Vec<ImportedUsage>) -> usize {
+        let mut by_key: BTreeMap<_, ImportedUsage> = self
+            .records
+            .drain(..)
+            .map(|record| (record.key(), record))
+            .collect();
+
+        let mut replaced = 0;
+        for record in records {
+            if by_key.insert(record.key(), record).is_some() {
+                replaced += 1;
+            }
+        }
+
+        self.records = by_key.into_values().collect();
+        replaced
+    }
+
+    /// Remove all imported usage
+    pub fn clear() -> Result<()> {
+        let path = Self::get_store_path()?;
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove import store: {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Read an export file and normalize it to one record per day and model
+pub fn read_import_file(
+    path: &Path,
+    format: ImportFormat,
+    source: &str,
+) -> Result<Vec<ImportedUsage>> {
+    let rows = match format {
+        ImportFormat::Generic
+            if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json")) =>
+        {
+            read_generic_json(path, source)?
+        }
+        _ => read_csv(path, format, source)?,
+    };
+
+    let mut aggregated: BTreeMap<_, ImportedUsage> = BTreeMap::new();
+    for row in rows {
+        match aggregated.get_mut(&row.key()) {
+            Some(existing) => existing.add(&row),
+            None => {
+                aggregated.insert(row.key(), row);
+            }
+        }
+    }
+    Ok(aggregated.into_values().collect())
+}
+
+/// Candidate column names for each field, per format
+struct Columns {
+    date: &'static [&'static str],
+    model: &'static [&'static str],
+    input: &'static [&'static str],
+    output: &'static [&'static str],
+    cache_creation: &'static [&'static str],
+    cache_read: &'static [&'static str],
+    cost: &'static [&'static str],
+}
+
+const CONSOLE_COLUMNS: Columns = Columns {
+    date: &["usage_date_utc", "date"],
+    model: &["model_version", "model"],
+    input: &["usage_input_tokens_no_cache", "input_tokens"],
+    output: &["usage_output_tokens", "output_tokens"],
+    // 5m and 1h cache writes are summed
+    cache_creation: &[
+        "usage_input_tokens_cache_write_5m",
+        "usage_input_tokens_cache_write_1h",
+        "usage_input_tokens_cache_write",
+    ],
+    cache_read: &["usage_input_tokens_cache_read"],
+    cost: &["cost_usd", "amount_usd"],
+};
+
+const GENERIC_COLUMNS: Columns = Columns {
+    date: &["date", "timestamp"],
+    model: &["model"],
+    input: &["input_tokens"],
+    output: &["output_tokens"],
+    cache_creation: &["cache_creation_tokens", "cache_creation_input_tokens"],
+    cache_read: &["cache_read_tokens", "cache_read_input_tokens"],
+    cost: &["cost_usd", "cost"],
+};
+
+fn read_csv(path: &Path, format: ImportFormat, source: &str) -> Result<Vec<ImportedUsage>> {
+    let columns = match format {
+        ImportFormat::AnthropicConsole => &CONSOLE_COLUMNS,
+        ImportFormat::Generic => &GENERIC_COLUMNS,
+    };
+
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let headers: Vec<String> = reader
+        .headers()?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let find = |names: &[&str]| -> Vec<usize> {
+        headers
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| names.contains(&header.as_str()))
+            .map(|(i, _)| i)
+            .collect()
+    };
+
+    let date_col = find(columns.date)
+        .first()
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("Missing date column (expected {:?})", columns.date))?;
+    let model_col = find(columns.model)
+        .first()
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("Missing model column (expected {:?})", columns.model))?;
+    let input_cols = find(columns.input);
+    let output_cols = find(columns.output);
+    let cache_creation_cols = find(columns.cache_creation);
+    let cache_read_cols = find(columns.cache_read);
+    let cost_col = find(columns.cost).first().copied();
+
+    let mut rows = Vec::new();
+    for (line, record) in reader.records().enumerate() {
+        let record = record.with_context(|| format!("Invalid CSV row {}", line + 2))?;
+        let sum = |cols: &[usize]| -> u64 {
+            cols.iter()
+                .filter_map(|&i| record.get(i))
+                .filter_map(|v| v.trim().parse::<f64>().ok())
+                .map(|v| v as u64)
+                .sum()
+        };
+
+        let date_value = record.get(date_col).unwrap_or_default();
+        let date = parse_import_date(date_value)
+            .with_context(|| format!("Invalid date '{}' on row {}", date_value, line + 2))?;
+
+        rows.push(ImportedUsage {
+            source: source.to_string(),
+            date,
+            model: record.get(model_col).unwrap_or_default().trim().to_string(),
+            input_tokens: sum(&input_cols),
+            output_tokens: sum(&output_cols),
+            cache_creation_tokens: sum(&cache_creation_cols),
+            cache_read_tokens: sum(&cache_read_cols),
+            cost_usd: cost_col
+                .and_then(|i| record.get(i))
+                .and_then(|v| v.trim().trim_start_matches('$').parse().ok()),
+        });
+    }
+    Ok(rows)
+}
+
+#[derive(Deserialize)]
+struct GenericJsonRow {
+    #[serde(alias = "timestamp")]
+    date: String,
+    model: String,
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default, alias = "cache_creation_input_tokens")]
+    cache_creation_tokens: u64,
+    #[serde(default, alias = "cache_read_input_tokens")]
+    cache_read_tokens: u64,
+    #[serde(default, alias = "cost")]
+    cost_usd: Option<f64>,
+}
+
+fn read_generic_json(path: &Path, source: &str) -> Result<Vec<ImportedUsage>> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let rows: Vec<GenericJsonRow> = serde_json::from_slice(&data)
+        .with_context(|| format!("Expected a JSON array of usage rows in {}", path.display()))?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(ImportedUsage {
+                source: source.to_string(),
+                date: parse_import_date(&row.date)
+                    .with_context(|| format!("Invalid date '{}'", row.date))?,
+                model: row.model,
+                input_tokens: row.input_tokens,
+                output_tokens: row.output_tokens,
+                cache_creation_tokens: row.cache_creation_tokens,
+                cache_read_tokens: row.cache_read_tokens,
+                cost_usd: row.cost_usd,
+            })
+        })
+        .collect()
+}
+
+/// Accepts `YYYY-MM-DD`, `YYYY-MM-DD HH:MM:SS`, `YYYYMMDD`, and RFC 3339 timestamps
+fn parse_import_date(value: &str) -> Result<NaiveDate> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Ok(date);
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.date_naive());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .map(|datetime| datetime.date())
+        .context("Unrecognized date format")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_console_csv_aggregates_rows() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("usage.csv");
+        fs::write(
+            &path,
+            "usage_date_utc,model_version,api_key,usage_input_tokens_no_cache,usage_input_tokens_cache_write_5m,usage_input_tokens_cache_write_1h,usage_input_tokens_cache_read,usage_output_tokens\n\
+             2024-03-01,claude-sonnet-4-20250514,key-a,100,10,5,1000,50\n\
+             2024-03-01,claude-sonnet-4-20250514,key-b,200,0,0,0,25\n\
+             2024-03-02,claude-opus-4-20250514,key-a,1,0,0,0,1\n",
+        )
+        .unwrap();
+
+        let rows =
+            read_import_file(&path, ImportFormat::AnthropicConsole, "anthropic-console").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].date, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        assert_eq!(rows[0].input_tokens, 300);
+        assert_eq!(rows[0].cache_creation_tokens, 15);
+        assert_eq!(rows[0].cache_read_tokens, 1000);
+        assert_eq!(rows[0].output_tokens, 75);
+        assert_eq!(rows[0].cost_usd, None);
+        assert_eq!(rows[1].model, "claude-opus-4-20250514");
+    }
+
+    #[test]
+    fn test_generic_json_and_store_merge() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("usage.json");
+        fs::write(
+            &path,
+            r#"[{"date":"2024-03-01T10:00:00Z","model":"claude-3-5-haiku-20241022","input_tokens":10,"output_tokens":5,"cost_usd":0.5}]"#,
+        )
+        .unwrap();
+
+        let rows = read_import_file(&path, ImportFormat::Generic, "api").unwrap();
+        assert_eq!(rows[0].cost_usd, Some(0.5));
+
+        let mut store = ImportStore::default();
+        assert_eq!(store.merge(rows.clone()), 0);
+        assert_eq!(store.merge(rows), 1);
+        assert_eq!(store.records.len(), 1);
+
+        let store_path = dir.path().join("store.json");
+        store.save_to(&store_path).unwrap();
+        let loaded = ImportStore::load_from(&store_path).unwrap();
+        assert_eq!(loaded.records, store.records);
+    }
+}
@@ -0,0 +1,601 @@
+//! Versioned JSON output (`--format json-v1`) and its JSON Schemas (`schema` command)
+//!
+//! The plain `--json` output serializes the internal report structs, so its
+//! shape follows them from release to release. The types here are a frozen
+//! copy for scripts: within v1, fields may be added but are never renamed,
+//! removed, or given a different type. Optional values are always present
+//! (as `null`) so `jq` paths do not depend on the data. Every document starts
+//! with `schemaVersion` and `report`.
+
+use crate::billing_blocks::BillingBlockManager;
+use crate::models::{DailyReport, MonthlyReport, SessionReport, TokenUsage, TokenUsageTotals};
+use crate::parser::CostMode;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use schemars::schema::RootSchema;
+use serde::{Deserialize, Serialize};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Reports available in the versioned format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportKind {
+    Daily,
+    Session,
+    Monthly,
+    BillingBlocks,
+}
+
+impl ReportKind {
+    pub const ALL: [ReportKind; 4] = [
+        ReportKind::Daily,
+        ReportKind::Session,
+        ReportKind::Monthly,
+        ReportKind::BillingBlocks,
+    ];
+
+    /// Value of the `report` field and key in `claudelytics schema` output
+    pub fn name(self) -> &'static str {
+        match self {
+            ReportKind::Daily => "daily",
+            ReportKind::Session => "session",
+            ReportKind::Monthly => "monthly",
+            ReportKind::BillingBlocks => "billing-blocks",
+        }
+    }
+
+    pub fn schema(self) -> RootSchema {
+        let mut schema = match self {
+            ReportKind::Daily => schemars::schema_for!(DailyReportV1),
+            ReportKind::Session => schemars::schema_for!(SessionReportV1),
+            ReportKind::Monthly => schemars::schema_for!(MonthlyReportV1),
+            ReportKind::BillingBlocks => schemars::schema_for!(BillingBlocksReportV1),
+        };
+        schema.schema.metadata().id = Some(format!(
+            "https://github.com/nwiizo/claudelytics/schemas/v{}/{}.json",
+            SCHEMA_VERSION,
+            self.name()
+        ));
+        schema
+    }
+}
+
+/// What a document's costs are, given as its `currency` and `costMode` fields
+#[derive(Debug, Clone, Copy)]
+pub struct CostBasis<'a> {
+    /// ISO 4217 code the costs were converted to
+    pub currency: &'a str,
+    /// How the costs were obtained
+    pub cost_mode: CostMode,
+}
+
+/// Token counts and cost shared by all rows and totals
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageV1 {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    /// In the document's `currency`
+    pub total_cost: f64,
+    /// Output tokens estimated to be extended thinking (included in `outputTokens`)
+    #[serde(default)]
+    pub thinking_tokens: u64,
+}
+
+impl From<&TokenUsageTotals> for UsageV1 {
+    fn from(totals: &TokenUsageTotals) -> Self {
+        Self {
+            input_tokens: totals.input_tokens,
+            output_tokens: totals.output_tokens,
+            cache_creation_tokens: totals.cache_creation_tokens,
+            cache_read_tokens: totals.cache_read_tokens,
+            total_tokens: totals.total_tokens,
+            total_cost: totals.total_cost,
+            thinking_tokens: totals.thinking_tokens,
+        }
+    }
+}
+
+impl From<&TokenUsage> for UsageV1 {
+    fn from(usage: &TokenUsage) -> Self {
+        Self::from(&TokenUsageTotals::from(usage))
+    }
+}
+
+/// Usage by day (`claudelytics --format json-v1 daily`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyReportV1 {
+    /// Always 1
+    pub schema_version: u32,
+    /// Always "daily"
+    pub report: String,
+    pub generated_at: DateTime<Utc>,
+    /// ISO 4217 code of all costs ("USD" unless `--currency` is set)
+    pub currency: String,
+    /// How costs were obtained: "auto", "calculate", "display", or "batch" (`--cost-mode`)
+    #[serde(default)]
+    pub cost_mode: String,
+    pub daily: Vec<DailyRowV1>,
+    pub totals: UsageV1,
+}
+
+/// Usage on one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyRowV1 {
+    /// Local date
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub usage: UsageV1,
+}
+
+impl DailyReportV1 {
+    pub fn new(report: &DailyReport, generated_at: DateTime<Utc>, basis: CostBasis) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            report: ReportKind::Daily.name().to_string(),
+            generated_at,
+            currency: basis.currency.to_string(),
+            cost_mode: basis.cost_mode.name().to_string(),
+            daily: report
+                .daily
+                .iter()
+                .filter_map(|day| {
+                    Some(DailyRowV1 {
+                        date: NaiveDate::parse_from_str(&day.date, "%Y-%m-%d").ok()?,
+                        usage: UsageV1 {
+                            input_tokens: day.input_tokens,
+                            output_tokens: day.output_tokens,
+                            cache_creation_tokens: day.cache_creation_tokens,
+                            cache_read_tokens: day.cache_read_tokens,
+                            total_tokens: day.total_tokens,
+                            total_cost: day.total_cost,
+                            thinking_tokens: day.thinking_tokens,
+                        },
+                    })
+                })
+                .collect(),
+            totals: (&report.totals).into(),
+        }
+    }
+}
+
+/// Usage by session (`claudelytics --format json-v1 session`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionReportV1 {
+    /// Always 1
+    pub schema_version: u32,
+    /// Always "session"
+    pub report: String,
+    pub generated_at: DateTime<Utc>,
+    /// ISO 4217 code of all costs ("USD" unless `--currency` is set)
+    pub currency: String,
+    /// How costs were obtained: "auto", "calculate", "display", or "batch" (`--cost-mode`)
+    #[serde(default)]
+    pub cost_mode: String,
+    pub sessions: Vec<SessionRowV1>,
+    /// Usage of all sessions, including those left out by pagination
+    pub totals: UsageV1,
+    /// Set when `--limit`, `--offset`, or `--top` kept only some of the sessions
+    #[serde(default)]
+    pub pagination: Option<PaginationV1>,
+}
+
+/// Which sessions were kept by `--limit`, `--offset`, or `--top`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginationV1 {
+    /// Sessions skipped from the start
+    pub offset: usize,
+    pub limit: Option<usize>,
+    /// Sessions before pagination
+    pub total_rows: usize,
+    /// Sessions left out before and after the kept ones
+    pub omitted: usize,
+}
+
+/// Usage of one session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRowV1 {
+    /// Claude's project directory name, e.g. "-home-me-api"
+    pub project_path: String,
+    pub session_id: String,
+    /// Local date of the last message
+    pub last_activity: NaiveDate,
+    /// Summary record or first user prompt
+    pub summary: Option<String>,
+    /// Composite efficiency score (0-100)
+    pub efficiency_score: Option<f64>,
+    #[serde(flatten)]
+    pub usage: UsageV1,
+}
+
+impl SessionReportV1 {
+    pub fn new(report: &SessionReport, generated_at: DateTime<Utc>, basis: CostBasis) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            report: ReportKind::Session.name().to_string(),
+            generated_at,
+            currency: basis.currency.to_string(),
+            cost_mode: basis.cost_mode.name().to_string(),
+            sessions: report
+                .sessions
+                .iter()
+                .filter_map(|session| {
+                    Some(SessionRowV1 {
+                        project_path: session.project_path.clone(),
+                        session_id: session.session_id.clone(),
+                        last_activity: NaiveDate::parse_from_str(
+                            &session.last_activity,
+                            "%Y-%m-%d",
+                        )
+                        .ok()?,
+                        summary: session.summary.clone(),
+                        efficiency_score: session.efficiency_score,
+                        usage: UsageV1 {
+                            input_tokens: session.input_tokens,
+                            output_tokens: session.output_tokens,
+                            cache_creation_tokens: session.cache_creation_tokens,
+                            cache_read_tokens: session.cache_read_tokens,
+                            total_tokens: session.total_tokens,
+                            total_cost: session.total_cost,
+                            thinking_tokens: session.thinking_tokens,
+                        },
+                    })
+                })
+                .collect(),
+            totals: (&report.totals).into(),
+            pagination: report.pagination.map(|p| PaginationV1 {
+                offset: p.offset,
+                limit: p.limit,
+                total_rows: p.total_rows,
+                omitted: p.omitted,
+            }),
+        }
+    }
+}
+
+/// Usage by calendar month (`claudelytics --format json-v1 monthly`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyReportV1 {
+    /// Always 1
+    pub schema_version: u32,
+    /// Always "monthly"
+    pub report: String,
+    pub generated_at: DateTime<Utc>,
+    /// ISO 4217 code of all costs ("USD" unless `--currency` is set)
+    pub currency: String,
+    /// How costs were obtained: "auto", "calculate", "display", or "batch" (`--cost-mode`)
+    #[serde(default)]
+    pub cost_mode: String,
+    pub monthly: Vec<MonthlyRowV1>,
+    pub totals: UsageV1,
+}
+
+/// Usage in one calendar month
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyRowV1 {
+    /// "YYYY-MM"
+    pub month: String,
+    pub days_active: u32,
+    /// Cost per active day
+    pub avg_daily_cost: f64,
+    #[serde(flatten)]
+    pub usage: UsageV1,
+}
+
+impl MonthlyReportV1 {
+    pub fn new(report: &MonthlyReport, generated_at: DateTime<Utc>, basis: CostBasis) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            report: ReportKind::Monthly.name().to_string(),
+            generated_at,
+            currency: basis.currency.to_string(),
+            cost_mode: basis.cost_mode.name().to_string(),
+            monthly: report
+                .monthly
+                .iter()
+                .map(|month| MonthlyRowV1 {
+                    month: format!(
+                        "{}-{:02}",
+                        month.year,
+                        crate::reports::month_to_num(&month.month)
+                    ),
+                    days_active: month.days_active,
+                    avg_daily_cost: month.avg_daily_cost,
+                    usage: UsageV1 {
+                        input_tokens: month.input_tokens,
+                        output_tokens: month.output_tokens,
+                        cache_creation_tokens: month.cache_creation_tokens,
+                        cache_read_tokens: month.cache_read_tokens,
+                        total_tokens: month.total_tokens,
+                        total_cost: month.total_cost,
+                        thinking_tokens: month.thinking_tokens,
+                    },
+                })
+                .collect(),
+            totals: (&report.totals).into(),
+        }
+    }
+}
+
+/// Usage by billing block (`claudelytics --format json-v1 billing-blocks`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BillingBlocksReportV1 {
+    /// Always 1
+    pub schema_version: u32,
+    /// Always "billing-blocks"
+    pub report: String,
+    pub generated_at: DateTime<Utc>,
+    /// ISO 4217 code of all costs ("USD" unless `--currency` is set)
+    pub currency: String,
+    /// How costs were obtained: "auto", "calculate", "display", or "batch" (`--cost-mode`)
+    #[serde(default)]
+    pub cost_mode: String,
+    /// "fixed" (from midnight UTC) or "rolling" (from the first activity)
+    pub anchor: String,
+    pub duration_hours: i64,
+    /// Blocks with usage, oldest first
+    pub blocks: Vec<BillingBlockV1>,
+    pub totals: UsageV1,
+}
+
+/// One billing block with usage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BillingBlockV1 {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub session_count: usize,
+    pub message_count: usize,
+    #[serde(flatten)]
+    pub usage: UsageV1,
+}
+
+impl BillingBlocksReportV1 {
+    pub fn new(
+        manager: &BillingBlockManager,
+        generated_at: DateTime<Utc>,
+        basis: CostBasis,
+    ) -> Self {
+        let report = manager.generate_report();
+        let mut blocks: Vec<BillingBlockV1> = manager
+            .get_blocks_with_usage()
+            .into_iter()
+            .map(|(_, block)| BillingBlockV1 {
+                start_time: block.start_time,
+                end_time: block.end_time,
+                session_count: block.session_count,
+                message_count: block.message_count,
+                usage: (&block.usage).into(),
+            })
+            .collect();
+        blocks.sort_by_key(|block| block.start_time);
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            report: ReportKind::BillingBlocks.name().to_string(),
+            generated_at,
+            currency: basis.currency.to_string(),
+            cost_mode: basis.cost_mode.name().to_string(),
+            anchor: serde_json::to_value(report.anchor)
+                .ok()
+                .and_then(|anchor| anchor.as_str().map(str::to_string))
+                .unwrap_or_default(),
+            duration_hours: report.duration_hours,
+            blocks,
+            totals: (&report.total_usage).into(),
+        }
+    }
+}
+
+/// Print a versioned report
+pub fn display_json_v1<T: Serialize>(report: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(report)?);
+    Ok(())
+}
+
+/// Print the JSON Schema of one report, or of all reports keyed by name
+pub fn display_schema(kind: Option<ReportKind>) -> Result<()> {
+    let json = match kind {
+        Some(kind) => serde_json::to_value(kind.schema())?,
+        None => serde_json::Value::Object(
+            ReportKind::ALL
+                .iter()
+                .map(|kind| {
+                    Ok((
+                        kind.name().to_string(),
+                        serde_json::to_value(kind.schema())?,
+                    ))
+                })
+                .collect::<Result<_>>()?,
+        ),
+    };
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DailyUsage, SessionUsage};
+    use chrono::TimeZone;
+    use serde_json::{Value, json};
+
+    fn usd() -> CostBasis<'static> {
+        CostBasis {
+            currency: "USD",
+            cost_mode: CostMode::Auto,
+        }
+    }
+
+    fn totals() -> TokenUsageTotals {
+        TokenUsageTotals {
+            input_tokens: 100,
+            output_tokens: 200,
+            cache_creation_tokens: 10,
+            cache_read_tokens: 40,
+            total_tokens: 350,
+            total_cost: 1.5,
+            thinking_tokens: 40,
+        }
+    }
+
+    fn generated_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 2, 12, 0, 0).unwrap()
+    }
+
+    fn daily_report() -> DailyReport {
+        let mut day = DailyUsage::from((
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            &TokenUsage::default(),
+        ));
+        day.input_tokens = 100;
+        day.output_tokens = 200;
+        day.cache_creation_tokens = 10;
+        day.cache_read_tokens = 40;
+        day.total_tokens = 350;
+        day.total_cost = 1.5;
+        day.thinking_tokens = 40;
+        DailyReport {
+            daily: vec![day],
+            totals: totals(),
+        }
+    }
+
+    fn session_report() -> SessionReport {
+        SessionReport {
+            sessions: vec![SessionUsage {
+                project_path: "-home-me-api".to_string(),
+                session_id: "abc".to_string(),
+                input_tokens: 100,
+                output_tokens: 200,
+                cache_creation_tokens: 10,
+                cache_read_tokens: 40,
+                total_tokens: 350,
+                total_cost: 1.5,
+                thinking_tokens: 40,
+                last_activity: "2024-03-01".to_string(),
+                summary: None,
+                efficiency_score: Some(72.5),
+                context_peak_percent: None,
+                resumed_sessions: Vec::new(),
+            }],
+            totals: totals(),
+            pagination: None,
+        }
+    }
+
+    /// The serialized field names are the contract; this must not change within v1
+    #[test]
+    fn test_daily_v1_golden() {
+        let report = DailyReportV1::new(&daily_report(), generated_at(), usd());
+        let expected = json!({
+            "schemaVersion": 1,
+            "report": "daily",
+            "generatedAt": "2024-03-02T12:00:00Z",
+            "currency": "USD",
+            "costMode": "auto",
+            "daily": [{
+                "date": "2024-03-01",
+                "inputTokens": 100,
+                "outputTokens": 200,
+                "cacheCreationTokens": 10,
+                "cacheReadTokens": 40,
+                "totalTokens": 350,
+                "totalCost": 1.5,
+                "thinkingTokens": 40
+            }],
+            "totals": {
+                "inputTokens": 100,
+                "outputTokens": 200,
+                "cacheCreationTokens": 10,
+                "cacheReadTokens": 40,
+                "totalTokens": 350,
+                "totalCost": 1.5,
+                "thinkingTokens": 40
+            }
+        });
+        assert_eq!(serde_json::to_value(&report).unwrap(), expected);
+
+        let parsed: DailyReportV1 = serde_json::from_value(expected).unwrap();
+        assert_eq!(parsed, report);
+    }
+
+    #[test]
+    fn test_session_v1_keeps_null_fields() {
+        let report = SessionReportV1::new(&session_report(), generated_at(), usd());
+        let value = serde_json::to_value(&report).unwrap();
+        let session = &value["sessions"][0];
+        assert_eq!(session["summary"], Value::Null);
+        assert_eq!(session["efficiencyScore"], json!(72.5));
+        assert_eq!(session["lastActivity"], json!("2024-03-01"));
+        assert_eq!(value["pagination"], Value::Null);
+
+        let parsed: SessionReportV1 = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, report);
+    }
+
+    /// Every schema lists exactly the fields the serialized report has
+    #[test]
+    fn test_schemas_match_serialized_fields() {
+        let properties = |kind: ReportKind| -> Vec<String> {
+            let schema = serde_json::to_value(kind.schema()).unwrap();
+            let mut keys: Vec<String> = schema["properties"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect();
+            keys.sort();
+            keys
+        };
+        let keys = |value: Value| -> Vec<String> {
+            let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+
+        let daily =
+            serde_json::to_value(DailyReportV1::new(&daily_report(), generated_at(), usd()));
+        assert_eq!(properties(ReportKind::Daily), keys(daily.unwrap()));
+        let session = serde_json::to_value(SessionReportV1::new(
+            &session_report(),
+            generated_at(),
+            usd(),
+        ));
+        assert_eq!(properties(ReportKind::Session), keys(session.unwrap()));
+        let monthly = MonthlyReportV1::new(
+            &MonthlyReport {
+                monthly: Vec::new(),
+                totals: totals(),
+            },
+            generated_at(),
+            usd(),
+        );
+        assert_eq!(
+            properties(ReportKind::Monthly),
+            keys(serde_json::to_value(monthly).unwrap())
+        );
+        let blocks = BillingBlocksReportV1::new(&BillingBlockManager::new(), generated_at(), usd());
+        assert_eq!(
+            properties(ReportKind::BillingBlocks),
+            keys(serde_json::to_value(blocks).unwrap())
+        );
+
+        let schema = serde_json::to_value(ReportKind::Daily.schema()).unwrap();
+        let row = &schema["definitions"]["DailyRowV1"]["properties"];
+        assert_eq!(row["date"]["format"], json!("date"));
+        assert!(row["totalCost"].is_object());
+    }
+}
@@ -0,0 +1,219 @@
+//! Claudelytics - Claude Code usage analytics as a library
+//!
+//! The `claudelytics` command is built on this crate, and other Rust tools can
+//! link against it instead of running the command and parsing its JSON. It
+//! reads the JSONL session logs Claude Code writes under `~/.claude/projects/`
+//! (and `~/.config/claude/projects/`) into daily and per-session usage, and
+//! turns those into the same reports the command prints.
+//!
+//! ```no_run
+//! use claudelytics::{CostMode, UsageParser, default_claude_dirs, reports};
+//!
+//! let parser = UsageParser::new_multi(
+//!     default_claude_dirs(),
+//!     Some("20240101".to_string()), // since, YYYYMMDD
+//!     None,                         // until
+//!     None,                         // model filter, e.g. "opus"
+//!     CostMode::Auto,
+//! )?
+//! .with_parse_cache(true);
+//! let (daily_map, session_map, _billing_blocks) = parser.parse_all()?;
+//!
+//! let daily = reports::generate_daily_report_sorted(daily_map, None, None);
+//! for day in &daily.daily {
+//!     println!("{} ${:.2}", day.date, day.total_cost);
+//! }
+//! let sessions = reports::generate_session_report_sorted(session_map, None, None);
+//! println!("{} sessions, ${:.2}", sessions.sessions.len(), sessions.totals.total_cost);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Report types serialize to the same JSON as `claudelytics --json`. Costs are
+//! in USD; [`TokenUsage::scale_costs`] and [`BillingBlockManager::scale_costs`]
+//! convert them to another currency at a given rate.
+//!
+//! The modules listed in these docs are the supported API and follow semver.
+//! The others are public only for the command-line tool and may change in
+//! any release.
+
+#![warn(missing_docs)]
+
+pub mod billing_blocks;
+pub mod burn_rate;
+pub mod models;
+pub mod parser;
+pub mod pricing;
+pub mod projections;
+pub mod reports;
+
+// Used by the command-line tool; not part of the supported API
+#[doc(hidden)]
+pub mod alerts;
+#[doc(hidden)]
+pub mod analytics_tui;
+#[doc(hidden)]
+pub mod archive;
+#[doc(hidden)]
+pub mod audit;
+#[doc(hidden)]
+pub mod bench;
+#[doc(hidden)]
+pub mod bookmarks;
+#[doc(hidden)]
+pub mod budget_check;
+#[doc(hidden)]
+pub mod cache_analysis;
+#[doc(hidden)]
+pub mod cache_stats;
+#[doc(hidden)]
+pub mod chargeback;
+#[doc(hidden)]
+pub mod chat_webhooks;
+#[doc(hidden)]
+pub mod claude_sessions;
+#[doc(hidden)]
+pub mod clipboard;
+#[doc(hidden)]
+pub mod columns;
+#[doc(hidden)]
+pub mod completions;
+#[doc(hidden)]
+pub mod compression;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod config_v2;
+#[doc(hidden)]
+pub mod context_usage;
+#[doc(hidden)]
+pub mod conversation_display;
+#[doc(hidden)]
+pub mod conversation_export;
+#[doc(hidden)]
+pub mod conversation_html;
+#[doc(hidden)]
+pub mod conversation_parser;
+#[doc(hidden)]
+pub mod currency;
+#[doc(hidden)]
+pub mod date_range;
+#[doc(hidden)]
+pub mod digest;
+#[doc(hidden)]
+pub mod disk_usage;
+#[doc(hidden)]
+pub mod display;
+#[doc(hidden)]
+pub mod doctor;
+#[doc(hidden)]
+pub mod domain;
+#[doc(hidden)]
+pub mod error;
+#[doc(hidden)]
+pub mod export;
+#[doc(hidden)]
+pub mod filter_expr;
+#[doc(hidden)]
+pub mod git_correlate;
+#[doc(hidden)]
+pub mod goals;
+#[doc(hidden)]
+pub mod heatmap;
+#[doc(hidden)]
+pub mod helpers;
+#[doc(hidden)]
+pub mod html_report;
+#[doc(hidden)]
+pub mod import;
+#[doc(hidden)]
+pub mod json_v1;
+#[doc(hidden)]
+pub mod lineage;
+#[doc(hidden)]
+pub mod live_dashboard;
+#[doc(hidden)]
+pub mod logging;
+#[doc(hidden)]
+pub mod mcp;
+#[doc(hidden)]
+pub mod mcp_http;
+#[doc(hidden)]
+pub mod models_registry;
+#[doc(hidden)]
+pub mod notifications;
+#[doc(hidden)]
+pub mod obsidian;
+#[doc(hidden)]
+pub mod otel_export;
+#[doc(hidden)]
+pub mod output_file;
+#[doc(hidden)]
+pub mod output_template;
+#[doc(hidden)]
+pub mod parse_cache;
+#[doc(hidden)]
+pub mod performance;
+#[doc(hidden)]
+pub mod plan;
+#[doc(hidden)]
+pub mod pricing_cache;
+#[doc(hidden)]
+pub mod pricing_strategies;
+#[doc(hidden)]
+pub mod processing;
+#[doc(hidden)]
+pub mod prune;
+#[doc(hidden)]
+pub mod quota;
+#[doc(hidden)]
+pub mod realtime_analytics;
+#[doc(hidden)]
+pub mod redact;
+#[doc(hidden)]
+pub mod responsive_tables;
+#[doc(hidden)]
+pub mod rest_api;
+#[doc(hidden)]
+pub mod schedule;
+#[doc(hidden)]
+pub mod search;
+#[doc(hidden)]
+pub mod session_analytics;
+#[doc(hidden)]
+pub mod session_blocks;
+#[doc(hidden)]
+pub mod session_timeline;
+#[doc(hidden)]
+pub mod simulate;
+#[doc(hidden)]
+pub mod state;
+#[doc(hidden)]
+pub mod status_line;
+#[doc(hidden)]
+pub mod tags;
+#[doc(hidden)]
+pub mod team;
+#[doc(hidden)]
+pub mod terminal;
+#[doc(hidden)]
+pub mod tickets;
+#[doc(hidden)]
+pub mod tool_usage;
+#[doc(hidden)]
+pub mod top_conversations;
+#[doc(hidden)]
+pub mod tui;
+#[doc(hidden)]
+pub mod tui_visuals;
+#[doc(hidden)]
+pub mod usage_db;
+#[doc(hidden)]
+pub mod watch;
+
+pub use billing_blocks::{BillingBlock, BillingBlockManager};
+pub use models::{
+    DailyReport, DailyUsage, DailyUsageMap, MonthlyReport, SessionReport, SessionUsage,
+    SessionUsageMap, TokenUsage, TokenUsageTotals, WeeklyReport,
+};
+pub use parser::{CostMode, UsageParser, default_claude_dirs};
+pub use reports::{SortField, SortOrder};
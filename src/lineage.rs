@@ -0,0 +1,307 @@
+//! Session lineage (`--group-resumes`)
+//!
+//! Resuming a conversation makes Claude Code start a new session file that
+//! points back at the one it continues: its summary records carry the
+//! `leafUuid` of the last message they summarize, its first message has a
+//! `parentUuid` in the earlier file, and earlier messages may be copied over
+//! with their UUIDs and timestamps. A session starts at its first message not
+//! found in any other file; one that references or repeats a message of an
+//! earlier session continues the latest such session, and following those
+//! links gives the chain of resumes that make up one piece of work.
+
+use crate::compression;
+use crate::models::{SessionUsageMap, TokenUsage};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Which session each resumed session continues
+#[derive(Debug, Default)]
+pub struct SessionLineage {
+    /// `project-dir/session-id` of a session to the one it continues
+    parents: HashMap<String, String>,
+}
+
+/// Session usage with each chain of resumes combined under its first session
+#[derive(Debug, Default)]
+pub struct GroupedSessions {
+    pub session_map: SessionUsageMap,
+    /// Later sessions of each chain, keyed by its first session, oldest first
+    pub resumed: HashMap<String, Vec<String>>,
+}
+
+/// Message links of one session file
+#[derive(Debug, Default)]
+struct SessionLinks {
+    key: String,
+    /// UUIDs of the records in the file, with their timestamps
+    messages: HashMap<String, Option<DateTime<Utc>>>,
+    /// `parentUuid`s and summary `leafUuid`s pointing outside the file
+    references: HashSet<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinkRecord {
+    #[serde(default)]
+    uuid: Option<String>,
+    #[serde(rename = "parentUuid", default)]
+    parent_uuid: Option<String>,
+    #[serde(rename = "leafUuid", default)]
+    leaf_uuid: Option<String>,
+    #[serde(default)]
+    timestamp: Option<DateTime<Utc>>,
+}
+
+impl SessionLineage {
+    /// Read the message links of every session file; unreadable files are left out
+    pub fn scan(claude_dirs: &[PathBuf]) -> Self {
+        let mut sessions = Vec::new();
+        for claude_dir in claude_dirs {
+            let projects_dir = claude_dir.join("projects");
+            let mut files: Vec<PathBuf> = WalkDir::new(&projects_dir)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+                .filter(|entry| compression::is_session_file(entry.path()))
+                .map(|entry| entry.path().to_path_buf())
+                .collect();
+            compression::dedup_compressed(&mut files);
+            for path in files {
+                let Some(key) = session_key(&projects_dir, &path) else {
+                    continue;
+                };
+                match read_links(key, &path) {
+                    Ok(links) => sessions.push(links),
+                    Err(e) => tracing::debug!(file = %path.display(), "skipped for lineage: {e:#}"),
+                }
+            }
+        }
+        Self::from_links(sessions)
+    }
+
+    fn from_links(sessions: Vec<SessionLinks>) -> Self {
+        let mut holders: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, session) in sessions.iter().enumerate() {
+            for uuid in session.messages.keys() {
+                holders.entry(uuid.as_str()).or_default().push(index);
+            }
+        }
+        // Copied messages keep their original timestamps, so only a session's own messages count
+        let starts: Vec<Option<DateTime<Utc>>> = sessions
+            .iter()
+            .map(|session| {
+                session
+                    .messages
+                    .iter()
+                    .filter(|(uuid, _)| holders[uuid.as_str()].len() == 1)
+                    .filter_map(|(_, timestamp)| *timestamp)
+                    .min()
+            })
+            .collect();
+
+        let mut parents = HashMap::new();
+        for (index, session) in sessions.iter().enumerate() {
+            let Some(start) = starts[index] else {
+                continue;
+            };
+            // The latest session started before this one that shares a message with it
+            let parent = session
+                .messages
+                .keys()
+                .chain(&session.references)
+                .filter_map(|uuid| holders.get(uuid.as_str()))
+                .flatten()
+                .filter(|other| **other != index)
+                .filter_map(|other| {
+                    let other_start = starts[*other].filter(|other_start| *other_start < start)?;
+                    Some((other_start, &sessions[*other].key))
+                })
+                .max();
+            if let Some((_, parent)) = parent {
+                parents.insert(session.key.clone(), parent.clone());
+            }
+        }
+        Self { parents }
+    }
+
+    /// Session that `session` was resumed from
+    pub fn parent(&self, session: &str) -> Option<&str> {
+        self.parents.get(session).map(String::as_str)
+    }
+
+    /// First session of the chain `session` belongs to
+    pub fn root<'a>(&'a self, session: &'a str) -> &'a str {
+        let mut current = session;
+        // A chain cannot be longer than the number of links; this also stops at cycles
+        for _ in 0..=self.parents.len() {
+            match self.parent(current) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        current
+    }
+
+    /// Combine the usage of every chain of resumes under its first session
+    pub fn group(&self, session_map: &SessionUsageMap) -> GroupedSessions {
+        let mut grouped = GroupedSessions::default();
+        let mut members: Vec<(&String, &DateTime<Utc>)> = session_map
+            .iter()
+            .map(|(session, (_, last_activity))| (session, last_activity))
+            .collect();
+        members.sort_by_key(|(session, last_activity)| (**last_activity, (*session).clone()));
+
+        for (session, _) in members {
+            let (usage, last_activity) = &session_map[session];
+            let root = self.root(session).to_string();
+            let entry = grouped
+                .session_map
+                .entry(root.clone())
+                .or_insert_with(|| (TokenUsage::default(), *last_activity));
+            entry.0.add(usage);
+            entry.1 = entry.1.max(*last_activity);
+            if root != *session {
+                grouped
+                    .resumed
+                    .entry(root)
+                    .or_default()
+                    .push(session.clone());
+            }
+        }
+        grouped
+    }
+}
+
+/// `project-dir/session-id` of a session file, as the parser keys sessions
+fn session_key(projects_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(projects_dir).ok()?;
+    let mut components: Vec<&str> = relative
+        .components()
+        .filter_map(|component| component.as_os_str().to_str())
+        .collect();
+    let last = components.last_mut()?;
+    *last = compression::session_stem(last)?;
+    Some(components.join("/"))
+}
+
+fn read_links(key: String, path: &Path) -> Result<SessionLinks> {
+    let reader = BufReader::new(compression::open_session_file(path)?);
+    let mut links = SessionLinks {
+        key,
+        ..Default::default()
+    };
+    let mut pointers = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let Ok(record) = serde_json::from_str::<LinkRecord>(&line) else {
+            continue;
+        };
+        if let Some(uuid) = record.uuid {
+            links.messages.insert(uuid, record.timestamp);
+        }
+        pointers.extend(record.parent_uuid);
+        pointers.extend(record.leaf_uuid);
+    }
+    links.references = pointers
+        .into_iter()
+        .filter(|uuid| !links.messages.contains_key(uuid))
+        .collect();
+    Ok(links)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::fs;
+
+    fn write_session(projects_dir: &Path, name: &str, lines: &[&str]) {
+        let dir = projects_dir.join("-home-me-app");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(format!("{name}.jsonl")), lines.join("\n")).unwrap();
+    }
+
+    #[test]
+    fn test_resumed_sessions_form_a_chain() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let projects_dir = temp_dir.path().join("projects");
+        write_session(
+            &projects_dir,
+            "first",
+            &[
+                r#"{"uuid":"u1","parentUuid":null,"type":"user","timestamp":"2024-01-01T10:00:00Z"}"#,
+                r#"{"uuid":"a1","parentUuid":"u1","type":"assistant","timestamp":"2024-01-01T10:00:05Z"}"#,
+            ],
+        );
+        // Resumed: a summary of the first session and a message continuing it
+        write_session(
+            &projects_dir,
+            "second",
+            &[
+                r#"{"type":"summary","summary":"Fix login","leafUuid":"a1"}"#,
+                r#"{"uuid":"u2","parentUuid":"a1","type":"user","timestamp":"2024-01-02T09:00:00Z"}"#,
+                r#"{"uuid":"a2","parentUuid":"u2","type":"assistant","timestamp":"2024-01-02T09:00:05Z"}"#,
+            ],
+        );
+        // Resumed again, with the earlier messages copied over
+        write_session(
+            &projects_dir,
+            "third",
+            &[
+                r#"{"uuid":"u1","parentUuid":null,"type":"user","timestamp":"2024-01-01T10:00:00Z"}"#,
+                r#"{"uuid":"a2","parentUuid":"u2","type":"assistant","timestamp":"2024-01-02T09:00:05Z"}"#,
+                r#"{"uuid":"u3","parentUuid":"a2","type":"user","timestamp":"2024-01-03T09:00:00Z"}"#,
+            ],
+        );
+        write_session(
+            &projects_dir,
+            "unrelated",
+            &[
+                r#"{"uuid":"x1","parentUuid":null,"type":"user","timestamp":"2024-01-02T12:00:00Z"}"#,
+            ],
+        );
+
+        let lineage = SessionLineage::scan(&[temp_dir.path().to_path_buf()]);
+        assert_eq!(
+            lineage.parent("-home-me-app/second"),
+            Some("-home-me-app/first")
+        );
+        // The copied first message is older, but the second session is the latest one before it
+        assert_eq!(
+            lineage.parent("-home-me-app/third"),
+            Some("-home-me-app/second")
+        );
+        assert_eq!(lineage.parent("-home-me-app/first"), None);
+        assert_eq!(lineage.root("-home-me-app/third"), "-home-me-app/first");
+        assert_eq!(
+            lineage.root("-home-me-app/unrelated"),
+            "-home-me-app/unrelated"
+        );
+
+        let at = |day| Utc.with_ymd_and_hms(2024, 1, day, 10, 0, 0).unwrap();
+        let usage = |cost| TokenUsage {
+            total_cost: cost,
+            ..Default::default()
+        };
+        let mut session_map = SessionUsageMap::new();
+        session_map.insert("-home-me-app/first".to_string(), (usage(1.0), at(1)));
+        session_map.insert("-home-me-app/second".to_string(), (usage(2.0), at(2)));
+        session_map.insert("-home-me-app/third".to_string(), (usage(4.0), at(3)));
+        session_map.insert("-home-me-app/unrelated".to_string(), (usage(8.0), at(2)));
+
+        let grouped = lineage.group(&session_map);
+        assert_eq!(grouped.session_map.len(), 2);
+        let (chain_usage, last_activity) = &grouped.session_map["-home-me-app/first"];
+        assert_eq!(chain_usage.total_cost, 7.0);
+        assert_eq!(*last_activity, at(3));
+        assert_eq!(
+            grouped.resumed["-home-me-app/first"],
+            vec!["-home-me-app/second", "-home-me-app/third"]
+        );
+        assert!(!grouped.resumed.contains_key("-home-me-app/unrelated"));
+    }
+}
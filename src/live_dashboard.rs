@@ -1,44 +1,84 @@
 //! Live dashboard module for real-time token usage monitoring
 //!
-//! Provides a live monitoring interface that displays:
-//! - Real-time token burn rate
-//! - Active session progress
-//! - Cost projections
-//! - Time to reach limits
-
-use crate::burn_rate::BurnRateMetrics;
-use crate::display::print_info;
+//! A ratatui app that refreshes in the background and displays:
+//! - Gauges for the daily budget, monthly budget, and token limit
+//! - A rolling burn-rate chart for the last hour
+//! - Burn rates over the configured windows, split by model family
+//! - Active sessions with their current burn rate
+//! - The most active projects, by burn rate over the last hour and day
+//!
+//! Limits can be adjusted while the dashboard runs and saved to the config.
+
+use crate::alerts::{AlertEvent, AlertHooks};
+use crate::burn_rate::{
+    ProjectBurnRate, UsageEvent, WindowBurnRate, project_burn_rates, windowed_burn_rates,
+};
+use crate::config_v2::{AlertsConfig, AppConfig};
+use crate::currency::{Currency, Money};
 use crate::models::{SessionUsageMap, TokenUsage};
-use crate::parser::UsageParser;
-use crate::session_blocks::{SessionBlockConfig, SessionBlockManager};
+use crate::parser::{CostMode, UsageParser};
 use anyhow::Result;
-use chrono::{DateTime, Duration, Local, Utc};
-use colored::Colorize;
-use std::collections::HashMap;
-use std::io::{self, Write};
-use std::path::Path;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::thread;
-use std::time::Duration as StdDuration;
-use terminal_size::{Width, terminal_size};
+use chrono::{DateTime, Datelike, Duration, Local, Utc};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{
+    Frame, Terminal,
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols,
+    text::{Line, Span},
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Dataset, Gauge, GraphType, Paragraph, Row, Table,
+    },
+};
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration as StdDuration, Instant};
+
+/// Sessions used within this many minutes are shown as active
+const ACTIVE_SESSION_MINUTES: i64 = 15;
+/// Span of the rolling burn-rate chart
+const CHART_MINUTES: i64 = 60;
+/// Each chart point is the burn rate over this many trailing minutes
+const CHART_SMOOTHING_MINUTES: i64 = 5;
+/// Step for the daily limit keys (+/-)
+const DAILY_LIMIT_STEP: f64 = 5.0;
+/// Step for the monthly limit keys (]/[)
+const MONTHLY_LIMIT_STEP: f64 = 50.0;
+/// Step for the token limit keys (t/T)
+const TOKEN_LIMIT_STEP: u64 = 100_000;
 
 /// Configuration for the live dashboard
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct LiveDashboardConfig {
     /// Refresh interval in seconds
     pub refresh_interval: u64,
-    /// Token limit for warnings
+    /// Daily token limit for warnings
     pub token_limit: Option<u64>,
-    /// Daily cost limit in USD
+    /// Daily cost limit, in `currency`
     pub daily_cost_limit: Option<f64>,
-    /// Monthly cost limit in USD
+    /// Monthly cost limit, in `currency`
     pub monthly_cost_limit: Option<f64>,
-    /// Show detailed session information
+    /// Fraction of the daily cost limit that triggers a budget alert
+    pub alert_threshold: f64,
+    /// Webhook/command hooks run when a threshold is crossed
+    pub alert_hooks: AlertsConfig,
+    /// Show the per-model breakdown of each burn-rate window
     pub show_details: bool,
     /// Enable alerts for high burn rates
     pub enable_alerts: bool,
+    /// Only count usage from models matching this filter
+    pub model_filter: Option<String>,
+    /// How costs are calculated (`--cost-mode`)
+    pub cost_mode: CostMode,
+    /// Currency costs are converted to after each parse (`--currency`)
+    pub currency: Currency,
+    /// Lookback windows for burn rates, with their labels
+    pub burn_rate_windows: Vec<(String, Duration)>,
 }
 
 impl Default for LiveDashboardConfig {
@@ -48,8 +88,18 @@ impl Default for LiveDashboardConfig {
             token_limit: None,
             daily_cost_limit: None,
             monthly_cost_limit: None,
+            alert_threshold: 0.8,
+            alert_hooks: AlertsConfig::default(),
             show_details: true,
             enable_alerts: true,
+            model_filter: None,
+            cost_mode: CostMode::default(),
+            currency: Currency::usd(),
+            burn_rate_windows: vec![
+                ("15m".to_string(), Duration::minutes(15)),
+                ("1h".to_string(), Duration::hours(1)),
+                ("6h".to_string(), Duration::hours(6)),
+            ],
         }
     }
 }
@@ -58,499 +108,783 @@ impl Default for LiveDashboardConfig {
 pub struct LiveDashboard {
     config: LiveDashboardConfig,
     parser: UsageParser,
-    session_manager: SessionBlockManager,
     last_update: DateTime<Local>,
-    active_sessions: HashMap<String, ActiveSessionInfo>,
-    running: Arc<AtomicBool>,
+    today_usage: TokenUsage,
+    month_cost: f64,
+    recent_events: Vec<UsageEvent>,
+    window_rates: Vec<WindowBurnRate>,
+    active_sessions: Vec<ActiveSessionInfo>,
+    project_rates: Vec<ProjectBurnRate>,
+    /// Status line shown in the footer (e.g. after saving limits)
+    message: Option<String>,
+    hooks: AlertHooks,
+    /// Latest failure of an alert hook, shown next to the alert
+    hook_failure: Option<String>,
+    should_quit: bool,
 }
 
 /// Information about an active session
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 struct ActiveSessionInfo {
-    pub project_path: String,
-    pub start_time: DateTime<Utc>,
-    pub last_activity: DateTime<Utc>,
-    pub usage: TokenUsage,
-    pub burn_rate: Option<BurnRateMetrics>,
+    /// `project-dir/session-id` key
+    session: String,
+    last_activity: DateTime<Utc>,
+    usage: TokenUsage,
+    /// Cost per hour over the shortest burn-rate window
+    cost_per_hour: f64,
 }
 
 impl LiveDashboard {
     /// Create a new live dashboard
-    pub fn new(claude_dir: &Path, config: LiveDashboardConfig) -> Result<Self> {
-        let parser = UsageParser::new(claude_dir.to_path_buf(), None, None, None)?;
-
-        let session_config = SessionBlockConfig {
-            block_hours: 1, // 1-hour blocks for fine-grained tracking
-            token_limit: config.token_limit,
-            cost_limit: config.daily_cost_limit,
-        };
-
-        let session_manager = SessionBlockManager::new(session_config);
+    pub fn new(claude_dirs: &[PathBuf], config: LiveDashboardConfig) -> Result<Self> {
+        // Keep enough history for the longest window, the rolling chart, and
+        // the daily project burn rates
+        let lookback = config
+            .burn_rate_windows
+            .iter()
+            .map(|(_, window)| *window)
+            .chain([
+                Duration::minutes(CHART_MINUTES + CHART_SMOOTHING_MINUTES),
+                Duration::hours(24),
+            ])
+            .max()
+            .unwrap_or_else(|| Duration::hours(1));
+        let parser = UsageParser::new_multi(
+            claude_dirs.to_vec(),
+            None,
+            None,
+            config.model_filter.clone(),
+            config.cost_mode,
+        )?
+        .with_recent_usage(lookback);
+        let hooks = AlertHooks::new(config.alert_hooks.clone());
 
         Ok(Self {
             config,
             parser,
-            session_manager,
             last_update: Local::now(),
-            active_sessions: HashMap::new(),
-            running: Arc::new(AtomicBool::new(true)),
+            today_usage: TokenUsage::default(),
+            month_cost: 0.0,
+            recent_events: Vec::new(),
+            window_rates: Vec::new(),
+            active_sessions: Vec::new(),
+            project_rates: Vec::new(),
+            message: None,
+            hooks,
+            hook_failure: None,
+            should_quit: false,
         })
     }
 
     /// Start the live dashboard
     pub fn run(&mut self) -> Result<()> {
-        // Clear screen and set up terminal
-        self.clear_screen();
-        self.hide_cursor();
-
-        // Set up Ctrl+C handler
-        let running = self.running.clone();
-        ctrlc::set_handler(move || {
-            running.store(false, Ordering::SeqCst);
-        })?;
-
-        print_info("🚀 Starting Live Dashboard - Press Ctrl+C to stop");
-        thread::sleep(StdDuration::from_secs(1));
-
-        // Main loop
-        while self.running.load(Ordering::SeqCst) {
-            self.update_data()?;
-            self.render_dashboard()?;
-
-            // Sleep for refresh interval
-            thread::sleep(StdDuration::from_secs(self.config.refresh_interval));
-        }
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend)?;
 
-        // Cleanup
-        self.show_cursor();
-        self.clear_screen();
-        print_info("👋 Live Dashboard stopped");
+        let result = self.run_app(&mut terminal);
 
-        Ok(())
-    }
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
 
-    /// Update session data
-    fn update_data(&mut self) -> Result<()> {
-        self.last_update = Local::now();
-
-        // Parse latest data
-        let (_daily_map, session_map, _billing_manager) = self.parser.parse_all()?;
-
-        // Update active sessions
-        self.update_active_sessions(&session_map)?;
-
-        // Update session blocks
-        self.update_session_blocks(&session_map)?;
-
-        Ok(())
+        result
     }
 
-    /// Update active sessions based on recent activity
-    fn update_active_sessions(&mut self, session_map: &SessionUsageMap) -> Result<()> {
-        let now = Utc::now();
-        let active_threshold = now - Duration::minutes(15); // Consider sessions active if used in last 15 minutes
-
-        self.active_sessions.clear();
+    fn run_app<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        let refresh = StdDuration::from_secs(self.config.refresh_interval.max(1));
+        let mut last_refresh: Option<Instant> = None;
 
-        for (session_path, (usage, last_activity)) in session_map {
-            if *last_activity > active_threshold {
-                // Calculate session start time (approximate based on first activity)
-                let start_time = *last_activity - Duration::hours(1); // Rough estimate
+        while !self.should_quit {
+            if last_refresh.is_none_or(|at| at.elapsed() >= refresh) {
+                self.update_data()?;
+                last_refresh = Some(Instant::now());
+            }
+            if let Some(failure) = self.hooks.take_failures().pop() {
+                self.hook_failure = Some(failure);
+            }
 
-                // Calculate burn rate for this session
-                let duration = now - start_time;
-                let hours_elapsed = duration.num_seconds() as f64 / 3600.0;
+            // Redrawing every tick keeps the layout right after a resize
+            terminal.draw(|f| self.ui(f))?;
 
-                let burn_rate = if hours_elapsed > 0.1 {
-                    Some(self.calculate_session_burn_rate(usage, hours_elapsed))
+            if event::poll(StdDuration::from_millis(250))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                if key.code == KeyCode::Char('r') {
+                    last_refresh = None;
                 } else {
-                    None
-                };
-
-                self.active_sessions.insert(
-                    session_path.clone(),
-                    ActiveSessionInfo {
-                        project_path: session_path.clone(),
-                        start_time,
-                        last_activity: *last_activity,
-                        usage: usage.clone(),
-                        burn_rate,
-                    },
-                );
+                    self.handle_key(key.code, key.modifiers);
+                }
             }
         }
-
         Ok(())
     }
 
-    /// Update session blocks
-    fn update_session_blocks(&mut self, session_map: &SessionUsageMap) -> Result<()> {
-        // Clear and rebuild session manager
-        let session_config = SessionBlockConfig {
-            block_hours: 1,
-            token_limit: self.config.token_limit,
-            cost_limit: self.config.daily_cost_limit,
-        };
-        self.session_manager = SessionBlockManager::new(session_config);
-
-        // Add all sessions to blocks
-        for (session_id, (usage, timestamp)) in session_map {
-            self.session_manager
-                .add_usage(*timestamp, usage, session_id);
-        }
-
-        // Calculate burn rates
-        self.session_manager.calculate_burn_rates();
-
-        Ok(())
-    }
-
-    /// Calculate burn rate metrics for a session
-    fn calculate_session_burn_rate(
-        &self,
-        usage: &TokenUsage,
-        hours_elapsed: f64,
-    ) -> BurnRateMetrics {
-        let tokens_per_hour = usage.total_tokens() as f64 / hours_elapsed;
-        let cost_per_hour = usage.total_cost / hours_elapsed;
-
-        BurnRateMetrics {
-            tokens_per_hour,
-            cost_per_hour,
-            projected_daily_tokens: (tokens_per_hour * 24.0) as u64,
-            projected_daily_cost: cost_per_hour * 24.0,
-            projected_monthly_tokens: (tokens_per_hour * 24.0 * 30.0) as u64,
-            projected_monthly_cost: cost_per_hour * 24.0 * 30.0,
-            trend_percentage: 0.0,
-            hours_until_budget_limit: self.calculate_time_to_limit(
-                usage,
-                cost_per_hour,
-                tokens_per_hour,
-            ),
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.config.daily_cost_limit =
+                    Some(self.config.daily_cost_limit.unwrap_or(0.0) + DAILY_LIMIT_STEP);
+            }
+            KeyCode::Char('-') => {
+                self.config.daily_cost_limit =
+                    step_down(self.config.daily_cost_limit, DAILY_LIMIT_STEP);
+            }
+            KeyCode::Char(']') => {
+                self.config.monthly_cost_limit =
+                    Some(self.config.monthly_cost_limit.unwrap_or(0.0) + MONTHLY_LIMIT_STEP);
+            }
+            KeyCode::Char('[') => {
+                self.config.monthly_cost_limit =
+                    step_down(self.config.monthly_cost_limit, MONTHLY_LIMIT_STEP);
+            }
+            KeyCode::Char('t') => {
+                self.config.token_limit =
+                    Some(self.config.token_limit.unwrap_or(0) + TOKEN_LIMIT_STEP);
+            }
+            KeyCode::Char('T') => {
+                self.config.token_limit = self
+                    .config
+                    .token_limit
+                    .map(|limit| limit.saturating_sub(TOKEN_LIMIT_STEP))
+                    .filter(|limit| *limit > 0);
+            }
+            KeyCode::Char('a') => {
+                self.config.enable_alerts = !self.config.enable_alerts;
+                self.message = Some(format!(
+                    "Alerts {}",
+                    if self.config.enable_alerts {
+                        "on"
+                    } else {
+                        "off"
+                    }
+                ));
+            }
+            KeyCode::Char('d') => self.config.show_details = !self.config.show_details,
+            KeyCode::Char('s') => {
+                self.message = Some(match self.save_limits() {
+                    Ok(()) => "Budget limits saved to config".to_string(),
+                    Err(e) => format!("Failed to save limits: {}", e),
+                });
+            }
+            _ => {}
         }
     }
 
-    /// Calculate time until limits are reached
-    fn calculate_time_to_limit(
-        &self,
-        current_usage: &TokenUsage,
-        cost_per_hour: f64,
-        tokens_per_hour: f64,
-    ) -> Option<f64> {
-        let mut time_to_limit = None;
-
-        // Check token limit
-        if let Some(limit) = self.config.token_limit {
-            let remaining = limit.saturating_sub(current_usage.total_tokens());
-            let hours = remaining as f64 / tokens_per_hour;
-            time_to_limit = Some(hours);
-        }
-
-        // Check daily cost limit
-        if let Some(limit) = self.config.daily_cost_limit {
-            let remaining = limit - current_usage.total_cost;
-            let hours = remaining / cost_per_hour;
-            time_to_limit = Some(time_to_limit.map_or(hours, |t| t.min(hours)));
-        }
-
-        time_to_limit.filter(|&h| h > 0.0)
+    /// Persist the current daily and monthly limits as the saved budget
+    fn save_limits(&self) -> Result<()> {
+        let mut app_config = AppConfig::load()?;
+        app_config.budget.daily_limit = self.config.daily_cost_limit;
+        app_config.budget.monthly_limit = self.config.monthly_cost_limit;
+        app_config.validate()?;
+        app_config.save()?;
+        Ok(())
     }
 
-    /// Render the dashboard
-    fn render_dashboard(&self) -> Result<()> {
-        self.clear_screen();
-
-        // Get terminal width
-        let term_width = terminal_size()
-            .map(|(Width(w), _)| w as usize)
-            .unwrap_or(80);
-
-        // Header
-        self.render_header(term_width)?;
-
-        // Active sessions
-        self.render_active_sessions(term_width)?;
+    /// Update session data
+    fn update_data(&mut self) -> Result<()> {
+        self.last_update = Local::now();
+        let today = self.last_update.date_naive();
 
-        // Burn rate summary
-        self.render_burn_rate_summary(term_width)?;
+        // Parse latest data
+        let (mut daily_map, mut session_map, mut billing_manager) = self.parser.parse_all()?;
+        let mut recent_events = self.parser.recent_usage();
+        self.config.currency.convert(
+            &mut daily_map,
+            &mut session_map,
+            &mut billing_manager,
+            &mut recent_events,
+        );
+        self.today_usage = daily_map.get(&today).cloned().unwrap_or_default();
+        self.month_cost = daily_map
+            .iter()
+            .filter(|(date, _)| date.year() == today.year() && date.month() == today.month())
+            .map(|(_, usage)| usage.total_cost)
+            .sum();
 
-        // Projections
-        self.render_projections(term_width)?;
+        let now = Utc::now();
+        self.recent_events = recent_events;
+        self.window_rates =
+            windowed_burn_rates(&self.recent_events, &self.config.burn_rate_windows, now);
+        self.active_sessions = self.collect_active_sessions(&session_map, now);
+        self.project_rates = project_burn_rates(&self.recent_events, now);
 
-        // Alerts
         if self.config.enable_alerts {
-            self.render_alerts(term_width)?;
+            for event in self.alert_events() {
+                self.hooks.fire_in_background(&event);
+            }
         }
 
-        // Footer
-        self.render_footer(term_width)?;
+        Ok(())
+    }
 
-        io::stdout().flush()?;
+    /// Sessions active within the last few minutes, busiest first
+    fn collect_active_sessions(
+        &self,
+        session_map: &SessionUsageMap,
+        now: DateTime<Utc>,
+    ) -> Vec<ActiveSessionInfo> {
+        let active_since = now - Duration::minutes(ACTIVE_SESSION_MINUTES);
+        let window = self.shortest_window();
+        let window_start = now - window;
+        let window_hours = window.num_seconds() as f64 / 3600.0;
+
+        let mut sessions: Vec<ActiveSessionInfo> = session_map
+            .iter()
+            .filter(|(_, (_, last_activity))| *last_activity > active_since)
+            .map(|(session, (usage, last_activity))| {
+                let window_cost: f64 = self
+                    .recent_events
+                    .iter()
+                    .filter(|e| e.session == *session && e.timestamp > window_start)
+                    .map(|e| e.usage.total_cost)
+                    .sum();
+                ActiveSessionInfo {
+                    session: session.clone(),
+                    last_activity: *last_activity,
+                    usage: usage.clone(),
+                    cost_per_hour: window_cost / window_hours,
+                }
+            })
+            .collect();
+        sessions.sort_by(|a, b| b.cost_per_hour.total_cmp(&a.cost_per_hour));
+        sessions
+    }
 
-        Ok(())
+    fn shortest_window(&self) -> Duration {
+        self.config
+            .burn_rate_windows
+            .iter()
+            .map(|(_, window)| *window)
+            .min()
+            .unwrap_or_else(|| Duration::minutes(15))
     }
 
-    /// Render dashboard header
-    fn render_header(&self, width: usize) -> Result<()> {
-        let title = " 🔥 CLAUDELYTICS LIVE DASHBOARD 🔥 ";
-        let padding = (width.saturating_sub(title.len())) / 2;
-
-        println!("{}", "═".repeat(width).bright_cyan());
-        println!(
-            "{}{}{}",
-            " ".repeat(padding),
-            title.bright_white().bold(),
-            " ".repeat(padding)
-        );
-        println!("{}", "═".repeat(width).bright_cyan());
-
-        println!(
-            "📅 {}",
-            self.last_update
-                .format("%Y-%m-%d %H:%M:%S")
-                .to_string()
-                .bright_yellow()
-        );
-        println!();
+    /// Burn rate over the shortest window, used for projections and alerts
+    fn current_rate(&self) -> Option<&WindowBurnRate> {
+        self.window_rates.iter().min_by_key(|rate| rate.minutes)
+    }
 
-        Ok(())
+    /// Hours until the daily cost limit is reached at the current burn rate
+    fn hours_to_daily_limit(&self) -> Option<f64> {
+        let limit = self.config.daily_cost_limit?;
+        let cost_per_hour = self.current_rate()?.cost_per_hour;
+        (cost_per_hour > 0.0)
+            .then(|| ((limit - self.today_usage.total_cost) / cost_per_hour).max(0.0))
     }
 
-    /// Render active sessions
-    fn render_active_sessions(&self, width: usize) -> Result<()> {
-        println!("{}", "ACTIVE SESSIONS".bright_green().bold());
-        println!("{}", "─".repeat(width).bright_black());
+    /// Threshold crossings for the dashboard and the alert hooks
+    fn alert_events(&self) -> Vec<AlertEvent> {
+        let now = Utc::now();
+        let mut events = Vec::new();
 
-        if self.active_sessions.is_empty() {
-            println!("{}", "No active sessions detected".dimmed());
-        } else {
-            for (path, session) in &self.active_sessions {
-                let duration = Utc::now() - session.start_time;
-                let duration_str = format_duration(duration);
-
-                println!(
-                    "📂 {} {}",
-                    path.bright_white().bold(),
-                    format!("({})", duration_str).dimmed()
-                );
-
-                if self.config.show_details {
-                    println!(
-                        "   💰 Cost: ${:.4} | 🔤 Tokens: {} | ⏱️  Last: {}",
-                        session.usage.total_cost,
-                        format_number(session.usage.total_tokens()),
-                        format_time_ago(Utc::now() - session.last_activity)
-                    );
-
-                    if let Some(burn_rate) = &session.burn_rate {
-                        println!(
-                            "   🔥 Burn: {} tok/hr (${:.4}/hr)",
-                            format_number(burn_rate.tokens_per_hour as u64),
-                            burn_rate.cost_per_hour
-                        );
-                    }
-                }
+        if let Some(limit) = self.config.daily_cost_limit {
+            let cost = self.today_usage.total_cost;
+            if cost >= limit * self.config.alert_threshold {
+                events.push(AlertEvent {
+                    kind: "budget_threshold".to_string(),
+                    severity: if cost >= limit { "critical" } else { "warning" }.to_string(),
+                    message: format!(
+                        "Daily budget utilization at {:.1}% ({:.2} of {:.2})",
+                        cost / limit * 100.0,
+                        Money(cost),
+                        Money(limit)
+                    ),
+                    cost,
+                    currency: self.config.currency.code.clone(),
+                    tokens: self.today_usage.total_tokens(),
+                    limit: Some(limit),
+                    timestamp: now,
+                });
             }
         }
 
-        println!();
-        Ok(())
-    }
+        if let Some(rate) = self.current_rate() {
+            let threshold = self.config.alert_hooks.burn_rate_threshold;
+            if rate.cost_per_hour > threshold {
+                events.push(AlertEvent {
+                    kind: "high_burn_rate".to_string(),
+                    severity: "warning".to_string(),
+                    message: format!(
+                        "High burn rate detected: {:.2}/hour ({} tokens/hour) over {}",
+                        Money(rate.cost_per_hour),
+                        format_number(rate.tokens_per_hour as u64),
+                        rate.label
+                    ),
+                    cost: rate.cost_per_hour,
+                    currency: self.config.currency.code.clone(),
+                    tokens: rate.tokens_per_hour as u64,
+                    limit: Some(threshold),
+                    timestamp: now,
+                });
+            }
+        }
 
-    /// Render burn rate summary
-    fn render_burn_rate_summary(&self, width: usize) -> Result<()> {
-        println!("{}", "BURN RATE ANALYSIS".bright_yellow().bold());
-        println!("{}", "─".repeat(width).bright_black());
+        if let Some(hours) = self.hours_to_daily_limit()
+            && hours > 0.0
+            && hours < 2.0
+        {
+            events.push(AlertEvent {
+                kind: "approaching_limit".to_string(),
+                severity: "warning".to_string(),
+                message: format!(
+                    "Daily limit reached in {} at the current rate",
+                    format_hours(hours)
+                ),
+                cost: self.today_usage.total_cost,
+                currency: self.config.currency.code.clone(),
+                tokens: self.today_usage.total_tokens(),
+                limit: self.config.daily_cost_limit,
+                timestamp: now,
+            });
+        }
 
-        // Get current active blocks
-        let active_blocks = self.session_manager.get_active_blocks();
+        events
+    }
 
-        if active_blocks.is_empty() {
-            println!("{}", "No active usage blocks".dimmed());
-        } else {
-            // Calculate aggregate burn rate
-            let mut total_tokens_per_hour = 0.0;
-            let mut total_cost_per_hour = 0.0;
-            let mut block_count = 0;
-
-            for block in active_blocks {
-                if let Some(burn_rate) = &block.burn_rate {
-                    total_tokens_per_hour += burn_rate.tokens_per_hour;
-                    total_cost_per_hour += burn_rate.cost_per_hour;
-                    block_count += 1;
-                }
-            }
+    /// Cost per hour over the trailing smoothing window, one point per minute of
+    /// the chart span (x = minutes ago, negative)
+    fn burn_rate_series(&self, now: DateTime<Utc>) -> Vec<(f64, f64)> {
+        let smoothing_hours = CHART_SMOOTHING_MINUTES as f64 / 60.0;
+        (0..=CHART_MINUTES)
+            .rev()
+            .map(|minutes_ago| {
+                let end = now - Duration::minutes(minutes_ago);
+                let start = end - Duration::minutes(CHART_SMOOTHING_MINUTES);
+                let cost: f64 = self
+                    .recent_events
+                    .iter()
+                    .filter(|e| e.timestamp > start && e.timestamp <= end)
+                    .map(|e| e.usage.total_cost)
+                    .sum();
+                (-(minutes_ago as f64), cost / smoothing_hours)
+            })
+            .collect()
+    }
 
-            if block_count > 0 {
-                println!(
-                    "🔥 Current Rate: {} tokens/hour (${:.4}/hour)",
-                    format_number(total_tokens_per_hour as u64).bright_red(),
-                    total_cost_per_hour
-                );
-
-                println!(
-                    "📊 Per Minute: {} tokens/min (${:.6}/min)",
-                    format_number((total_tokens_per_hour / 60.0) as u64),
-                    total_cost_per_hour / 60.0
-                );
-
-                // Show trend indicator
-                let trend = if total_tokens_per_hour > 10000.0 {
-                    "⚠️  HIGH".bright_red()
-                } else if total_tokens_per_hour > 5000.0 {
-                    "⚡ MODERATE".bright_yellow()
-                } else {
-                    "✅ NORMAL".bright_green()
-                };
+    fn ui(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(9),
+                Constraint::Min(8),
+                Constraint::Length(8),
+                Constraint::Length(3),
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0]);
+
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(chunks[1]);
+        self.render_gauges(f, top[0]);
+        self.render_window_rates(f, top[1]);
+
+        self.render_burn_chart(f, chunks[2]);
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunks[3]);
+        self.render_active_sessions(f, bottom[0]);
+        self.render_project_rates(f, bottom[1]);
+        self.render_footer(f, chunks[4]);
+    }
 
-                println!("📈 Activity Level: {}", trend);
-            }
+    fn render_header(&self, f: &mut Frame, area: Rect) {
+        let mut spans = vec![
+            Span::styled(
+                "🔥 CLAUDELYTICS LIVE",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  "),
+            Span::styled(
+                self.last_update.format("%Y-%m-%d %H:%M:%S").to_string(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                format!("  refresh {}s", self.config.refresh_interval),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ];
+        if let Some(rate) = self.current_rate() {
+            spans.push(Span::raw("  │  "));
+            spans.push(Span::styled(
+                format!("{:.2}/h", Money(rate.cost_per_hour)),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::styled(
+                format!(" ({})", rate.label),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        if let Some(hours) = self.hours_to_daily_limit() {
+            spans.push(Span::raw("  │  "));
+            spans.push(Span::styled(
+                format!("daily limit in {}", format_hours(hours)),
+                Style::default().fg(if hours < 2.0 {
+                    Color::Red
+                } else {
+                    Color::Green
+                }),
+            ));
         }
 
-        println!();
-        Ok(())
+        let header = Paragraph::new(Line::from(spans)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+        f.render_widget(header, area);
     }
 
-    /// Render projections
-    fn render_projections(&self, width: usize) -> Result<()> {
-        println!("{}", "PROJECTIONS".bright_magenta().bold());
-        println!("{}", "─".repeat(width).bright_black());
-
-        let active_blocks = self.session_manager.get_active_blocks();
-
-        if let Some(block) = active_blocks.first() {
-            if let Some(burn_rate) = &block.burn_rate {
-                // Daily projection
-                println!(
-                    "📅 Daily: {} tokens (${:.2})",
-                    format_number(burn_rate.projected_daily_tokens).bright_cyan(),
-                    burn_rate.projected_daily_cost
-                );
-
-                // Monthly projection
-                let projected_monthly_tokens = (burn_rate.tokens_per_hour * 24.0 * 30.0) as u64;
-                println!(
-                    "📆 Monthly: {} tokens (${:.2})",
-                    format_number(projected_monthly_tokens).bright_cyan(),
-                    burn_rate.projected_monthly_cost
-                );
-
-                // Time to limits
-                if let Some(time_to_limit) = burn_rate.time_to_limit {
-                    let hours = time_to_limit.num_hours();
-                    let minutes = time_to_limit.num_minutes() % 60;
-
-                    let time_str = if hours > 0 {
-                        format!("{}h {}m", hours, minutes)
-                    } else {
-                        format!("{}m", minutes)
-                    };
+    fn render_gauges(&self, f: &mut Frame, area: Rect) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+            ])
+            .split(area);
+
+        let today_cost = self.today_usage.total_cost;
+        let today_tokens = self.today_usage.total_tokens();
+        self.render_gauge(
+            f,
+            rows[0],
+            "Daily budget (+/-)",
+            self.config
+                .daily_cost_limit
+                .map(|limit| (today_cost, limit)),
+            format!("{:.2}", Money(today_cost)),
+            self.config
+                .daily_cost_limit
+                .map(|limit| format!("{:.2}", Money(limit))),
+        );
+        self.render_gauge(
+            f,
+            rows[1],
+            "Monthly budget (]/[)",
+            self.config
+                .monthly_cost_limit
+                .map(|limit| (self.month_cost, limit)),
+            format!("{:.2}", Money(self.month_cost)),
+            self.config
+                .monthly_cost_limit
+                .map(|limit| format!("{:.2}", Money(limit))),
+        );
+        self.render_gauge(
+            f,
+            rows[2],
+            "Daily tokens (t/T)",
+            self.config
+                .token_limit
+                .map(|limit| (today_tokens as f64, limit as f64)),
+            format_number(today_tokens),
+            self.config.token_limit.map(format_number),
+        );
+    }
 
-                    let color = if hours < 1 {
-                        time_str.bright_red().bold()
-                    } else if hours < 6 {
-                        time_str.bright_yellow()
-                    } else {
-                        time_str.bright_green()
-                    };
+    fn render_gauge(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        title: &str,
+        progress: Option<(f64, f64)>,
+        used: String,
+        limit: Option<String>,
+    ) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string());
+        let Some((value, max)) = progress.filter(|(_, max)| *max > 0.0) else {
+            let text = Paragraph::new(Line::from(vec![
+                Span::styled(used, Style::default().fg(Color::White)),
+                Span::styled("  (no limit)", Style::default().fg(Color::DarkGray)),
+            ]))
+            .block(block);
+            f.render_widget(text, area);
+            return;
+        };
 
-                    println!("⏰ Time to limit: {}", color);
-                }
-            }
+        let ratio = value / max;
+        let color = if ratio >= 1.0 {
+            Color::Red
+        } else if ratio >= self.config.alert_threshold {
+            Color::Yellow
         } else {
-            println!("{}", "No active usage to project".dimmed());
-        }
-
-        println!();
-        Ok(())
+            Color::Green
+        };
+        let gauge = Gauge::default()
+            .block(block)
+            .gauge_style(Style::default().fg(color))
+            .ratio(ratio.clamp(0.0, 1.0))
+            .label(format!(
+                "{} / {} ({:.0}%)",
+                used,
+                limit.unwrap_or_default(),
+                ratio * 100.0
+            ));
+        f.render_widget(gauge, area);
     }
 
-    /// Render alerts
-    fn render_alerts(&self, width: usize) -> Result<()> {
-        let mut alerts = Vec::new();
-
-        // Check for high burn rate
-        let active_blocks = self.session_manager.get_active_blocks();
-        for block in active_blocks {
-            if let Some(burn_rate) = &block.burn_rate {
-                if burn_rate.tokens_per_hour > 10000.0 {
-                    alerts.push(format!(
-                        "⚠️  High burn rate detected: {} tokens/hour",
-                        format_number(burn_rate.tokens_per_hour as u64)
-                    ));
-                }
-
-                // Check if approaching limits
-                if let Some(time_to_limit) = burn_rate.time_to_limit
-                    && time_to_limit.num_hours() < 2
-                {
-                    alerts.push(format!(
-                        "🚨 Approaching limit in {}!",
-                        format_duration(time_to_limit)
-                    ));
+    fn render_window_rates(&self, f: &mut Frame, area: Rect) {
+        let header = Row::new(["Window", "Model", "Tokens/h", "Cost/h"].map(|h| {
+            Cell::from(h).style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+        }));
+
+        let mut rows = Vec::new();
+        for rate in &self.window_rates {
+            rows.push(Row::new(vec![
+                Cell::from(rate.label.clone()).style(Style::default().fg(Color::White)),
+                Cell::from("all").style(Style::default().add_modifier(Modifier::BOLD)),
+                Cell::from(format_number(rate.tokens_per_hour as u64)),
+                Cell::from(format!("{:.4}", Money(rate.cost_per_hour)))
+                    .style(Style::default().fg(Color::Red)),
+            ]));
+            if self.config.show_details {
+                for model in &rate.by_model {
+                    rows.push(Row::new(vec![
+                        Cell::from(""),
+                        Cell::from(model.family.clone()).style(Style::default().fg(Color::Magenta)),
+                        Cell::from(format_number(model.tokens_per_hour as u64)),
+                        Cell::from(format!("{:.4}", Money(model.cost_per_hour)))
+                            .style(Style::default().fg(Color::LightRed)),
+                    ]));
                 }
             }
         }
 
-        if !alerts.is_empty() {
-            println!("{}", "ALERTS".bright_red().bold());
-            println!("{}", "─".repeat(width).bright_black());
-
-            for alert in alerts {
-                println!("{}", alert.bright_red());
-            }
-
-            println!();
-        }
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Length(9),
+                Constraint::Length(12),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Burn rate by window (d: models)"),
+        );
+        f.render_widget(table, area);
+    }
 
-        Ok(())
+    fn render_burn_chart(&self, f: &mut Frame, area: Rect) {
+        let series = self.burn_rate_series(Utc::now());
+        let max_rate = series
+            .iter()
+            .map(|(_, rate)| *rate)
+            .fold(0.0_f64, f64::max)
+            .max(self.config.alert_hooks.burn_rate_threshold.min(1.0))
+            .max(0.01);
+        let y_max = max_rate * 1.1;
+
+        let datasets = vec![
+            Dataset::default()
+                .name(format!(
+                    "{}/hour ({}m average)",
+                    self.config.currency.symbol().trim_end(),
+                    CHART_SMOOTHING_MINUTES
+                ))
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Red))
+                .data(&series),
+        ];
+        let chart = Chart::new(datasets)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Burn rate, last {} minutes", CHART_MINUTES)),
+            )
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(Color::DarkGray))
+                    .bounds([-(CHART_MINUTES as f64), 0.0])
+                    .labels(vec![
+                        Span::raw(format!("-{}m", CHART_MINUTES)),
+                        Span::raw(format!("-{}m", CHART_MINUTES / 2)),
+                        Span::raw("now"),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(Color::DarkGray))
+                    .bounds([0.0, y_max])
+                    .labels(vec![
+                        Span::raw(format!("{:.0}", Money(0.0))),
+                        Span::raw(format!("{:.2}", Money(y_max / 2.0))),
+                        Span::raw(format!("{:.2}", Money(y_max))),
+                    ]),
+            );
+        f.render_widget(chart, area);
     }
 
-    /// Render footer
-    fn render_footer(&self, width: usize) -> Result<()> {
-        println!("{}", "─".repeat(width).bright_black());
-        println!(
-            "{} | {} | {}",
-            "Ctrl+C to stop".dimmed(),
-            format!("Refresh: {}s", self.config.refresh_interval).dimmed(),
-            "Live monitoring active".bright_green()
+    fn render_active_sessions(&self, f: &mut Frame, area: Rect) {
+        let title = format!(
+            "Active sessions (last {} minutes): {}",
+            ACTIVE_SESSION_MINUTES,
+            self.active_sessions.len()
         );
+        if self.active_sessions.is_empty() {
+            let empty = Paragraph::new("No active sessions detected")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(Block::default().borders(Borders::ALL).title(title));
+            f.render_widget(empty, area);
+            return;
+        }
 
-        Ok(())
+        let header = Row::new(
+            ["Session", "Cost", "Tokens", "Burn", "Last activity"].map(|h| {
+                Cell::from(h).style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }),
+        );
+        let now = Utc::now();
+        let rows = self.active_sessions.iter().map(|session| {
+            Row::new(vec![
+                Cell::from(session.session.clone()),
+                Cell::from(format!("{:.4}", Money(session.usage.total_cost))),
+                Cell::from(format_number(session.usage.total_tokens())),
+                Cell::from(format!("{:.2}/h", Money(session.cost_per_hour)))
+                    .style(Style::default().fg(Color::Red)),
+                Cell::from(format_time_ago(now - session.last_activity))
+                    .style(Style::default().fg(Color::Yellow)),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(20),
+                Constraint::Length(10),
+                Constraint::Length(12),
+                Constraint::Length(10),
+                Constraint::Length(14),
+            ],
+        )
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(table, area);
     }
 
-    /// Clear the terminal screen
-    fn clear_screen(&self) {
-        print!("\x1B[2J\x1B[1;1H");
-        io::stdout().flush().unwrap();
-    }
+    fn render_project_rates(&self, f: &mut Frame, area: Rect) {
+        let title = "Most active projects (1h / 24h)";
+        if self.project_rates.is_empty() {
+            let empty = Paragraph::new("No usage in the last 24 hours")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(Block::default().borders(Borders::ALL).title(title));
+            f.render_widget(empty, area);
+            return;
+        }
 
-    /// Hide terminal cursor
-    fn hide_cursor(&self) {
-        print!("\x1B[?25l");
-        io::stdout().flush().unwrap();
+        let header = Row::new(["Project", "1h", "24h", "Sessions"].map(|h| {
+            Cell::from(h).style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+        }));
+        let threshold = self.config.alert_hooks.burn_rate_threshold;
+        let rows = self.project_rates.iter().map(|rate| {
+            let hot = rate.last_hour_cost_per_hour > threshold;
+            Row::new(vec![
+                Cell::from(rate.project.clone()),
+                Cell::from(format!("{:.2}/h", Money(rate.last_hour_cost_per_hour)))
+                    .style(Style::default().fg(if hot { Color::Red } else { Color::LightRed })),
+                Cell::from(format!("{:.2}/h", Money(rate.last_day_cost_per_hour))),
+                Cell::from(rate.active_sessions.to_string())
+                    .style(Style::default().fg(Color::Yellow)),
+            ])
+            .style(if hot {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            })
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(16),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(table, area);
     }
 
-    /// Show terminal cursor
-    fn show_cursor(&self) {
-        print!("\x1B[?25h");
-        io::stdout().flush().unwrap();
+    fn render_footer(&self, f: &mut Frame, area: Rect) {
+        let alert = self
+            .config
+            .enable_alerts
+            .then(|| self.alert_events().into_iter().next())
+            .flatten();
+        let hook_failure = self.hook_failure.as_ref().map(|failure| {
+            Span::styled(
+                format!("  ({})", failure),
+                Style::default().fg(Color::Yellow),
+            )
+        });
+        let line = match (alert, &self.message) {
+            (Some(alert), _) => Line::from(
+                std::iter::once(Span::styled(
+                    format!("⚠️  {}", alert.message),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ))
+                .chain(hook_failure)
+                .collect::<Vec<_>>(),
+            ),
+            (None, Some(message)) => Line::from(Span::styled(
+                message.clone(),
+                Style::default().fg(Color::Green),
+            )),
+            (None, None) => Line::from(Span::styled(
+                "q: quit  r: refresh  +/-: daily limit  ]/[: monthly limit  t/T: token limit  a: alerts  d: models  s: save limits",
+                Style::default().fg(Color::DarkGray),
+            )),
+        };
+        let footer = Paragraph::new(line).block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, area);
     }
 }
 
-/// Format a duration for display
-fn format_duration(duration: Duration) -> String {
-    let hours = duration.num_hours();
-    let minutes = duration.num_minutes() % 60;
-    let seconds = duration.num_seconds() % 60;
+/// Lower a limit by `step`, clearing it once it would reach zero
+fn step_down(limit: Option<f64>, step: f64) -> Option<f64> {
+    limit.map(|limit| limit - step).filter(|limit| *limit > 0.0)
+}
 
-    if hours > 0 {
-        format!("{}h {}m", hours, minutes)
-    } else if minutes > 0 {
-        format!("{}m {}s", minutes, seconds)
+/// Format fractional hours as `3h 20m` or `45m`
+fn format_hours(hours: f64) -> String {
+    let minutes = (hours * 60.0).round() as i64;
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
     } else {
-        format!("{}s", seconds)
+        format!("{}m", minutes)
     }
 }
 
@@ -588,8 +922,15 @@ pub struct LiveDashboardOptions {
     pub refresh: u64,
     pub token_limit: Option<u64>,
     pub cost_limit: Option<f64>,
+    pub monthly_cost_limit: Option<f64>,
+    pub alert_threshold: f64,
+    pub alert_hooks: AlertsConfig,
     pub show_details: bool,
     pub enable_alerts: bool,
+    pub model_filter: Option<String>,
+    pub cost_mode: CostMode,
+    pub currency: Currency,
+    pub burn_rate_windows: Vec<(String, Duration)>,
 }
 
 impl From<LiveDashboardOptions> for LiveDashboardConfig {
@@ -598,16 +939,123 @@ impl From<LiveDashboardOptions> for LiveDashboardConfig {
             refresh_interval: options.refresh,
             token_limit: options.token_limit,
             daily_cost_limit: options.cost_limit,
-            monthly_cost_limit: options.cost_limit.map(|c| c * 30.0),
+            monthly_cost_limit: options
+                .monthly_cost_limit
+                .or(options.cost_limit.map(|c| c * 30.0)),
+            alert_threshold: options.alert_threshold,
+            alert_hooks: options.alert_hooks,
             show_details: options.show_details,
             enable_alerts: options.enable_alerts,
+            model_filter: options.model_filter,
+            cost_mode: options.cost_mode,
+            currency: options.currency,
+            burn_rate_windows: options.burn_rate_windows,
         }
     }
 }
 
 /// Run the live dashboard
-pub fn run_live_dashboard(claude_dir: &Path, options: LiveDashboardOptions) -> Result<()> {
+pub fn run_live_dashboard(claude_dirs: &[PathBuf], options: LiveDashboardOptions) -> Result<()> {
     let config = LiveDashboardConfig::from(options);
-    let mut dashboard = LiveDashboard::new(claude_dir, config)?;
+    let mut dashboard = LiveDashboard::new(claude_dirs, config)?;
     dashboard.run()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    fn dashboard() -> LiveDashboard {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = LiveDashboardConfig {
+            daily_cost_limit: Some(10.0),
+            ..LiveDashboardConfig::default()
+        };
+        LiveDashboard::new(&[dir.path().to_path_buf()], config).unwrap()
+    }
+
+    fn event(minutes_ago: i64, model: &str, cost: f64, now: DateTime<Utc>) -> UsageEvent {
+        UsageEvent {
+            timestamp: now - Duration::minutes(minutes_ago),
+            session: "-home-me-app/s1".to_string(),
+            model: model.to_string(),
+            usage: TokenUsage {
+                input_tokens: 1000,
+                total_cost: cost,
+                message_count: 1,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_limit_keys() {
+        let mut dashboard = dashboard();
+        dashboard.handle_key(KeyCode::Char('+'), KeyModifiers::NONE);
+        assert_eq!(dashboard.config.daily_cost_limit, Some(15.0));
+        for _ in 0..3 {
+            dashboard.handle_key(KeyCode::Char('-'), KeyModifiers::NONE);
+        }
+        assert_eq!(dashboard.config.daily_cost_limit, None);
+
+        dashboard.handle_key(KeyCode::Char(']'), KeyModifiers::NONE);
+        assert_eq!(dashboard.config.monthly_cost_limit, Some(50.0));
+        dashboard.handle_key(KeyCode::Char('t'), KeyModifiers::NONE);
+        assert_eq!(dashboard.config.token_limit, Some(TOKEN_LIMIT_STEP));
+        dashboard.handle_key(KeyCode::Char('T'), KeyModifiers::NONE);
+        assert_eq!(dashboard.config.token_limit, None);
+
+        dashboard.handle_key(KeyCode::Char('q'), KeyModifiers::NONE);
+        assert!(dashboard.should_quit);
+    }
+
+    #[test]
+    fn test_render_with_activity() {
+        let now = Utc::now();
+        let mut dashboard = dashboard();
+        dashboard.recent_events = vec![
+            event(2, "claude-opus-4-20250514", 2.0, now),
+            event(30, "claude-sonnet-4-20250514", 0.5, now),
+        ];
+        dashboard.window_rates = windowed_burn_rates(
+            &dashboard.recent_events,
+            &dashboard.config.burn_rate_windows,
+            now,
+        );
+        dashboard.today_usage = TokenUsage {
+            input_tokens: 2000,
+            total_cost: 9.0,
+            ..Default::default()
+        };
+        let mut sessions = SessionUsageMap::new();
+        sessions.insert(
+            "-home-me-app/s1".to_string(),
+            (dashboard.today_usage.clone(), now - Duration::minutes(2)),
+        );
+        dashboard.active_sessions = dashboard.collect_active_sessions(&sessions, now);
+        dashboard.project_rates = project_burn_rates(&dashboard.recent_events, now);
+
+        // $2 in the last 15 minutes
+        assert!((dashboard.active_sessions[0].cost_per_hour - 8.0).abs() < 1e-9);
+        assert_eq!(dashboard.hours_to_daily_limit(), Some(0.125));
+        let series = dashboard.burn_rate_series(now);
+        assert_eq!(series.len(), CHART_MINUTES as usize + 1);
+        assert!((series.last().unwrap().1 - 24.0).abs() < 1e-9);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| dashboard.ui(f)).unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("Daily budget"));
+        assert!(text.contains("opus"));
+        assert!(text.contains("-home-me-app/s1"));
+        assert!(text.contains("Most active projects"));
+        assert!(text.contains("$2.50/h"));
+    }
+}
@@ -0,0 +1,162 @@
+//! Diagnostic logging for `-v`/`-vv`
+//!
+//! Parsing telemetry is emitted through `tracing` and written to stderr by a
+//! small subscriber, so reports on stdout stay clean. `-v` shows per-phase
+//! timings, per-directory file counts, and parse cache hits; `-vv` adds a line
+//! per file with the records parsed and skipped. Lines look like
+//! `[   0.012s  INFO parser] parsed files files=42 elapsed_ms=8`.
+
+use std::fmt::{self, Write as _};
+use std::io::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Install the stderr logger for the given number of `-v` flags. Nothing is
+/// installed without `-v`, so the tracing macros cost a single check.
+pub fn init(verbosity: u8) {
+    let level = match verbosity {
+        0 => return,
+        1 => Level::INFO,
+        2 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+    // Only fails if a subscriber is already set, which keeps the first one
+    let _ = tracing::subscriber::set_global_default(StderrLogger::new(level));
+}
+
+/// Writes events at or above `level` from this crate to stderr
+struct StderrLogger {
+    level: Level,
+    started: Instant,
+    next_span: AtomicU64,
+}
+
+impl StderrLogger {
+    fn new(level: Level) -> Self {
+        Self {
+            level,
+            started: Instant::now(),
+            next_span: AtomicU64::new(1),
+        }
+    }
+}
+
+impl Subscriber for StderrLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        // Dependencies such as tokio also emit events; only ours are shown
+        *metadata.level() <= self.level && metadata.target().starts_with("claudelytics")
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        Some(self.level.into())
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let line = format_event(event, self.started.elapsed().as_secs_f64());
+        let _ = writeln!(std::io::stderr().lock(), "{}", line);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+fn format_event(event: &Event<'_>, elapsed_secs: f64) -> String {
+    let metadata = event.metadata();
+    let target = metadata.target();
+    let module = target.strip_prefix("claudelytics::").unwrap_or(target);
+    let mut visitor = LineVisitor::default();
+    event.record(&mut visitor);
+    format!(
+        "[{:>8.3}s {:>5} {}] {}{}",
+        elapsed_secs,
+        metadata.level(),
+        module,
+        visitor.message,
+        visitor.fields
+    )
+}
+
+/// Collects the message and the `key=value` fields of an event
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else if value.contains(char::is_whitespace) {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Captures formatted lines instead of writing them to stderr
+    struct Capture(Arc<Mutex<Vec<String>>>, StderrLogger);
+
+    impl Subscriber for Capture {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            self.1.enabled(metadata)
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.1.new_span(span)
+        }
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            self.0.lock().unwrap().push(format_event(event, 1.5));
+        }
+        fn enter(&self, _span: &Id) {}
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn test_event_formatting_and_level_filter() {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let capture = Capture(Arc::clone(&lines), StderrLogger::new(Level::INFO));
+
+        tracing::subscriber::with_default(capture, || {
+            tracing::info!(files = 3, dir = "/tmp/my projects", "scanned directory");
+            tracing::debug!(records = 10, "parsed file");
+            tracing::info!(target: "tokio::runtime", "not ours");
+        });
+
+        let lines = lines.lock().unwrap();
+        assert_eq!(
+            *lines,
+            vec![
+                "[   1.500s  INFO logging::tests] scanned directory files=3 dir=\"/tmp/my projects\""
+                    .to_string()
+            ]
+        );
+    }
+}
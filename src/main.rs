@@ -3,46 +3,24 @@
 //! A fast CLI tool for analyzing Claude Code usage patterns, token consumption, and costs.
 //! Parses JSONL files from ~/.claude/projects/ and generates comprehensive reports.
 
-// Module declarations
-mod billing_blocks;
-mod burn_rate;
-mod cache_analysis;
-mod claude_sessions;
-mod config;
-mod config_v2;
-mod conversation_display;
-mod conversation_parser;
-mod display;
-mod domain;
-mod error;
-mod export;
-mod helpers;
-mod live_dashboard;
-mod mcp;
-mod models;
-mod models_registry;
-mod parser;
-mod performance;
-mod pricing;
-mod pricing_cache;
-mod pricing_strategies;
-mod processing;
-mod projections;
-mod realtime_analytics;
-mod reports;
-mod responsive_tables;
-mod session_analytics;
-mod session_blocks;
-mod state;
-mod terminal;
-mod tui;
-mod tui_visuals;
+use claudelytics::{
+    alerts, analytics_tui, archive, audit, bench, billing_blocks, bookmarks, budget_check,
+    burn_rate, cache_analysis, cache_stats, chargeback, chat_webhooks, columns, completions,
+    config, config_v2, context_usage, conversation_display, conversation_export, conversation_html,
+    conversation_parser, currency, date_range, digest, disk_usage, display, doctor, export,
+    filter_expr, git_correlate, goals, heatmap, html_report, import, json_v1, lineage,
+    live_dashboard, logging, mcp, mcp_http, models, models_registry, notifications, obsidian,
+    otel_export, output_file, output_template, parse_cache, parser, plan, pricing_cache,
+    projections, prune, quota, realtime_analytics, redact, reports, rest_api, schedule, search,
+    session_analytics, session_blocks, session_timeline, simulate, state, status_line, tags, team,
+    terminal, tickets, tool_usage, top_conversations, tui, usage_db, watch,
+};
 
 // Core dependencies
 use anyhow::Result;
-use chrono::Local;
-use clap::{Parser, Subcommand, ValueEnum};
-use config::Config;
+use chrono::{Datelike, Local, Utc};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use currency::Money;
 use display::{
     display_billing_blocks_responsive, display_daily_report_enhanced,
     display_daily_report_responsive, display_daily_report_table, display_model_breakdown_report,
@@ -50,27 +28,35 @@ use display::{
     display_session_report_enhanced, display_session_report_responsive,
     display_session_report_table, print_error, print_info, print_warning,
 };
-use export::{export_daily_to_csv, export_sessions_to_csv, export_summary_to_csv};
+use export::ExportFormat;
 use models::SessionUsageMap;
 use parser::UsageParser;
 use projections::ProjectionCalculator;
 use reports::{
-    SortField as ReportSortField, SortOrder as ReportSortOrder, generate_daily_report_sorted,
+    SortField as ReportSortField, SortOrder as ReportSortOrder, attach_daily_model_breakdown,
+    attach_daily_source_breakdown, attach_session_summaries, generate_daily_report_sorted,
     generate_monthly_report_sorted, generate_session_report_sorted,
 };
 use session_blocks::{SessionBlockConfig, SessionBlockManager};
 use state::{TuiMode, TuiSessionState};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use tui::TuiApp;
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum CliCostMode {
     /// Use costUSD from JSONL if available, otherwise calculate from tokens
+    #[value(alias = "stored-then-calculate", alias = "prefer-stored")]
     Auto,
     /// Always recalculate cost from token counts and model pricing
+    #[value(alias = "calculate-always")]
     Calculate,
     /// Only use costUSD field from JSONL data
+    #[value(alias = "stored-only")]
     Display,
+    /// Calculate from token counts at the 50% Message Batches API discount
+    #[value(alias = "batch-discount")]
+    Batch,
 }
 
 impl From<CliCostMode> for parser::CostMode {
@@ -79,6 +65,67 @@ impl From<CliCostMode> for parser::CostMode {
             CliCostMode::Auto => parser::CostMode::Auto,
             CliCostMode::Calculate => parser::CostMode::Calculate,
             CliCostMode::Display => parser::CostMode::Display,
+            CliCostMode::Batch => parser::CostMode::Batch,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CliOutputFormat {
+    /// Same as --json: follows the internal report structures
+    Json,
+    /// Versioned JSON with a stable, documented schema (see `claudelytics schema`)
+    JsonV1,
+}
+
+/// Where reports read usage from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum CliBackend {
+    /// Parse the Claude Code JSONL files
+    #[default]
+    Jsonl,
+    /// Read the database written by `claudelytics sync-db`
+    Sqlite,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SchemaReport {
+    Daily,
+    Session,
+    Monthly,
+    BillingBlocks,
+}
+
+impl From<SchemaReport> for json_v1::ReportKind {
+    fn from(report: SchemaReport) -> Self {
+        match report {
+            SchemaReport::Daily => json_v1::ReportKind::Daily,
+            SchemaReport::Session => json_v1::ReportKind::Session,
+            SchemaReport::Monthly => json_v1::ReportKind::Monthly,
+            SchemaReport::BillingBlocks => json_v1::ReportKind::BillingBlocks,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CliExportFormat {
+    /// Comma-separated values (default)
+    Csv,
+    /// Pretty-printed JSON matching the --json report output
+    Json,
+    /// Apache Parquet (requires the `parquet` build feature)
+    Parquet,
+    /// Markdown tables for GitHub issues, wikis, or Notion
+    Markdown,
+}
+
+impl From<CliExportFormat> for export::ExportFormat {
+    fn from(format: CliExportFormat) -> Self {
+        match format {
+            CliExportFormat::Csv => export::ExportFormat::Csv,
+            CliExportFormat::Json => export::ExportFormat::Json,
+            CliExportFormat::Parquet => export::ExportFormat::Parquet,
+            CliExportFormat::Markdown => export::ExportFormat::Markdown,
         }
     }
 }
@@ -91,6 +138,86 @@ pub enum SortOrder {
     Desc,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum GroupByField {
+    /// One row per project directory
+    Project,
+    /// One row per tag (see `claudelytics tags`)
+    Tag,
+}
+
+impl From<GroupByField> for reports::GroupBy {
+    fn from(field: GroupByField) -> Self {
+        match field {
+            GroupByField::Project => reports::GroupBy::Project,
+            GroupByField::Tag => reports::GroupBy::Tag,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SplitBy {
+    /// Time-of-day buckets from the `schedule` section of the config
+    Schedule,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum StatementFormat {
+    /// Comma-separated values for spreadsheets and accounting tools
+    Csv,
+    /// Pretty-printed JSON
+    Json,
+    /// Markdown statement that converts cleanly to PDF
+    Markdown,
+}
+
+impl From<StatementFormat> for chargeback::StatementFormat {
+    fn from(format: StatementFormat) -> Self {
+        match format {
+            StatementFormat::Csv => chargeback::StatementFormat::Csv,
+            StatementFormat::Json => chargeback::StatementFormat::Json,
+            StatementFormat::Markdown => chargeback::StatementFormat::Markdown,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum DigestPeriod {
+    /// Today, compared with yesterday
+    Day,
+    /// The last 7 days, compared with the 7 days before
+    Week,
+    /// The last 30 days, compared with the 30 days before
+    Month,
+}
+
+impl From<DigestPeriod> for digest::DigestPeriod {
+    fn from(period: DigestPeriod) -> Self {
+        match period {
+            DigestPeriod::Day => digest::DigestPeriod::Day,
+            DigestPeriod::Week => digest::DigestPeriod::Week,
+            DigestPeriod::Month => digest::DigestPeriod::Month,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum WatchFormat {
+    /// Redraw today's usage table whenever new usage arrives
+    Table,
+    /// One JSON object per usage delta, for piping into other tools
+    JsonLines,
+}
+
+impl From<WatchFormat> for watch::WatchFormat {
+    fn from(format: WatchFormat) -> Self {
+        match format {
+            WatchFormat::Table => watch::WatchFormat::Table,
+            WatchFormat::JsonLines => watch::WatchFormat::JsonLines,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum CacheSortField {
     /// Sort by write cost (default)
@@ -111,6 +238,68 @@ pub enum CacheSortField {
     BreakevenTurn,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum BillingBlockMode {
+    /// Fixed windows from midnight UTC
+    Fixed,
+    /// Blocks anchored to the first activity
+    Rolling,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum StatusFormat {
+    /// JSON with text, tooltip, class and percentage for a waybar custom module
+    Waybar,
+    /// Text colored with tmux #[fg=...] markup when the quota runs low
+    Tmux,
+    /// Plain text for a starship custom module or a shell prompt
+    Starship,
+}
+
+impl From<StatusFormat> for status_line::StatusFormat {
+    fn from(format: StatusFormat) -> Self {
+        match format {
+            StatusFormat::Waybar => status_line::StatusFormat::Waybar,
+            StatusFormat::Tmux => status_line::StatusFormat::Tmux,
+            StatusFormat::Starship => status_line::StatusFormat::Starship,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SubscriptionPlan {
+    /// Claude Pro
+    Pro,
+    /// Claude Max 5x
+    Max5,
+    /// Claude Max 20x
+    Max20,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CompletionTarget {
+    /// Session IDs, most recent first
+    Sessions,
+    /// Project directory names
+    Projects,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ImportFormatArg {
+    /// Anthropic Console usage export (CSV)
+    AnthropicConsole,
+    /// CSV or JSON with date, model, token columns and optional cost_usd
+    Generic,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum TopMetric {
+    /// Rank by estimated cost
+    Cost,
+    /// Rank by total tokens
+    Tokens,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum SortField {
     /// Sort by date/time
@@ -158,8 +347,8 @@ struct Cli {
         short,
         long,
         value_name = "DATE",
-        help = "Filter from date (YYYYMMDD)",
-        long_help = "Filter usage data from this date onwards. Format: YYYYMMDD\nExample: --since 20240101 (January 1, 2024)\nCombine with --until for date ranges: --since 20240101 --until 20240131"
+        help = "Filter from date (YYYYMMDD, yesterday, \"last monday\", ...)",
+        long_help = "Filter usage data from this date onwards\nFormats: YYYYMMDD, YYYY-MM-DD, today, yesterday, a weekday (\"monday\" or\n\"last monday\", its most recent occurrence before today), \"N days ago\", \"N weeks ago\"\nExample: --since 20240101 (January 1, 2024), --since \"last monday\"\nCombine with --until for date ranges: --since 20240101 --until 20240131"
     )]
     since: Option<String>,
 
@@ -167,8 +356,8 @@ struct Cli {
         short,
         long,
         value_name = "DATE",
-        help = "Filter until date (YYYYMMDD)",
-        long_help = "Filter usage data up to this date. Format: YYYYMMDD\nExample: --until 20241231 (December 31, 2024)\nUse alone for 'up to date' or combine with --since for ranges"
+        help = "Filter until date (YYYYMMDD, yesterday, ...)",
+        long_help = "Filter usage data up to this date; same formats as --since\nExample: --until 20241231 (December 31, 2024), --until yesterday\nUse alone for 'up to date' or combine with --since for ranges"
     )]
     until: Option<String>,
 
@@ -176,10 +365,18 @@ struct Cli {
         short,
         long,
         value_name = "PATH",
-        help = "Path to Claude directory",
-        long_help = "Custom path to Claude directory (default: ~/.claude)\nUseful if Claude Code data is in a non-standard location\nExample: --path /custom/claude or --path ~/Dropbox/.claude"
+        help = "Path to Claude directory (repeatable)",
+        long_help = "Custom path to Claude directory (default: ~/.claude)\nUseful if Claude Code data is in a non-standard location\nRepeat to merge several directories, e.g. ~/.claude synced from other machines;\nmessages present in more than one directory are counted once\nExample: --path /custom/claude or --path ~/sync/laptop --path ~/sync/desktop"
+    )]
+    path: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Use the settings of a saved profile",
+        long_help = "Apply a profile saved with `claudelytics config --create-profile`\nThe profile's Claude path, budgets, timezone, filters, and output format are\nused where the command line does not set them; TUI state is kept per profile\nExample: claudelytics --profile work daily"
     )]
-    path: Option<PathBuf>,
+    profile: Option<String>,
 
     #[arg(
         short,
@@ -189,6 +386,33 @@ struct Cli {
     )]
     json: bool,
 
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        help = "Machine-readable output format (json, json-v1)",
+        long_help = "Machine-readable output format:\n  json: same as --json; field names follow the internal reports and may change\n  json-v1: versioned output whose fields are never renamed or removed within v1\n           (daily, session, monthly, and billing-blocks; see `claudelytics schema`)\nExample: claudelytics --format json-v1 daily | jq '.totals.totalCost'"
+    )]
+    format: Option<CliOutputFormat>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "Render the report through a Tera template",
+        long_help = "Render the report through a Tera template instead of printing it\nThe template sees the fields of the --json output (e.g. daily, totals, and\ncurrency for the daily report); its output is printed as is\nAvailable for daily, session, monthly, weekly, and hourly\nExample: claudelytics daily --template waybar.tera"
+    )]
+    template: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "jsonl",
+        help = "Read usage from the JSONL files or the SQLite database",
+        long_help = "Where reports read usage from:\n  jsonl: parse the Claude Code JSONL files (default)\n  sqlite: read the database written by `claudelytics sync-db`, which is much\n          faster for long histories and keeps sessions whose files were pruned\nImported and archived usage are not merged in sqlite mode\nRequires a build with `--features sqlite`\nExample: claudelytics --backend sqlite monthly"
+    )]
+    backend: CliBackend,
+
     #[arg(
         short,
         long,
@@ -203,6 +427,29 @@ struct Cli {
     #[arg(long, help = "Show last 30 days")]
     last_30d: bool,
 
+    #[arg(
+        long,
+        value_name = "SPAN",
+        conflicts_with_all = ["since", "until", "today", "last_7d", "last_30d", "this_month", "last_month"],
+        help = "Show the last N days or weeks, e.g. 7d or 2w",
+        long_help = "Show the last N days (7d) or weeks (2w), up to and including today\nExample: claudelytics --last 14d daily"
+    )]
+    last: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["since", "until", "today", "last_7d", "last_30d", "last_month"],
+        help = "Show the current month so far"
+    )]
+    this_month: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["since", "until", "today", "last_7d", "last_30d"],
+        help = "Show the previous calendar month"
+    )]
+    last_month: bool,
+
     #[arg(
         long,
         help = "Use classic table format",
@@ -268,26 +515,203 @@ struct Cli {
     #[arg(
         long,
         value_enum,
-        default_value = "auto",
-        help = "Cost calculation mode",
-        long_help = "Control how costs are calculated:\n  auto: Use costUSD from JSONL if present, otherwise calculate\n  calculate: Always recalculate from token counts and pricing\n  display: Only show costUSD field from JSONL data"
+        help = "Cost calculation mode [default: auto]",
+        long_help = "Control how costs are calculated:\n  auto: Use costUSD from JSONL if present, otherwise calculate\n        (aliases: stored-then-calculate, prefer-stored)\n  calculate: Always recalculate from token counts and pricing (alias: calculate-always)\n  display: Only show costUSD field from JSONL data (alias: stored-only)\n  batch: Calculate at Message Batches API prices, 50% off (alias: batch-discount)\nDefaults to `defaults.cost_mode` in the config file, else auto\nThe mode is reported as `costMode` in --format json-v1 output"
+    )]
+    cost_mode: Option<CliCostMode>,
+
+    #[arg(
+        long,
+        conflicts_with = "cost_mode",
+        help = "Recompute all costs from tokens using current pricing",
+        long_help = "Ignore costUSD values stored in the JSONL files and recompute every cost\nfrom token counts using the current pricing table (same as --cost-mode calculate)\nUseful for comparing historical usage against today's prices and for\ncatching sessions where a wrong cost was recorded"
+    )]
+    recalculate_costs: bool,
+
+    #[arg(
+        long,
+        value_name = "CODE",
+        help = "Show costs in another currency (e.g. EUR, JPY)",
+        long_help = "Convert all costs from USD to this currency (ISO 4217 code) in tables,\nexports, and JSON output, which gains a `currency` field\nDefault: currency.default from the config file (USD)\nRates come from currency.rates in the config, or are fetched once a day\nand cached in ~/.cache/claudelytics/exchange_rates.json\nBudgets and goals are compared against the converted costs\nExample: claudelytics --currency EUR monthly"
+    )]
+    currency: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write the output to a file instead of stdout",
+        long_help = "Write whatever the command would print (tables, enhanced reports, JSON) to this\nfile instead of stdout, without colors; warnings and errors still go to stderr\nThe file is overwritten unless --append is given; missing directories are created\nNot available for interactive commands (tui, analytics-tui, live, blocks --live,
+mcp-server, config --edit)\nExample: claudelytics --output ~/logs/claude-daily.log --append --today daily"
+    )]
+    output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "output",
+        help = "Append to the --output file instead of overwriting it"
+    )]
+    append: bool,
+
+    #[arg(
+        long,
+        help = "Re-read all JSONL files instead of using the parse cache",
+        long_help = "Bypass the incremental parse cache and re-read every JSONL file\nBy default, unchanged files are loaded from ~/.cache/claudelytics/parse_cache.json\nUse `claudelytics cache --clear` to delete the cache"
+    )]
+    no_cache: bool,
+
+    #[arg(
+        long,
+        help = "Count duplicated JSONL records instead of skipping them",
+        long_help = "Disable deduplication of usage records\nBy default records repeating a message.id:requestId pair or a line UUID\n(e.g. lines replayed after a crash) are counted once\nUse `claudelytics doctor` to see how many duplicates your data contains"
+    )]
+    no_dedup: bool,
+
+    #[arg(
+        long,
+        help = "Include archived usage whose JSONL files were pruned",
+        long_help = "Add usage from the snapshot archive for days and sessions that no longer\nhave live JSONL data (Claude Code prunes old files)\nDefault: archive.merge from the config file (false)\nTake snapshots with `claudelytics snapshot` or archive.auto_snapshot"
+    )]
+    with_archive: bool,
+
+    #[arg(
+        long,
+        help = "Print per-file parse errors and unpriced models",
+        long_help = "After parsing, print a per-file summary (to stderr) of JSONL lines that\ncould not be deserialized and records whose model has no known pricing\n(counted as $0). Normally such lines are skipped silently"
+    )]
+    report_errors: bool,
+
+    #[arg(
+        long,
+        help = "Fail if any line is unparsable or any model is unpriced",
+        long_help = "Like --report-errors, but exit with a nonzero status when any problem is\nfound, so totals are only produced from fully understood data\nExample: claudelytics --strict --json monthly > finance.json"
     )]
-    cost_mode: CliCostMode,
+    strict: bool,
 
     #[arg(
         short,
         long,
-        help = "Show detailed output with burn rate and trends",
-        long_help = "Show detailed enhanced output with burn rate analysis,\nactivity trends, and efficiency metrics.\nDefault output is a compact table."
+        action = clap::ArgAction::Count,
+        help = "Show detailed output and parsing telemetry (-vv for per-file details)",
+        long_help = "Show detailed enhanced output with burn rate analysis,\nactivity trends, and efficiency metrics.\nDefault output is a compact table.\n\nAlso logs parsing telemetry to stderr: -v shows the scanned directories,\nparse cache hits, and the time spent per phase; -vv adds the records\nparsed and skipped for every file.\nExample: claudelytics -vv --json daily 2> parse.log"
     )]
-    verbose: bool,
+    verbose: u8,
+}
+
+#[derive(Subcommand)]
+enum BudgetAction {
+    #[command(about = "Save budget limits (only the given values are changed)")]
+    Set {
+        #[arg(long, value_name = "USD", help = "Daily budget limit (USD)")]
+        daily: Option<f64>,
+        #[arg(long, value_name = "USD", help = "Monthly budget limit (USD)")]
+        monthly: Option<f64>,
+        #[arg(long, value_name = "USD", help = "Yearly budget limit (USD)")]
+        yearly: Option<f64>,
+        #[arg(
+            long,
+            value_name = "RATIO",
+            help = "Fraction of budget that triggers alerts (0.0-1.0)"
+        )]
+        alert_threshold: Option<f64>,
+        #[arg(
+            long,
+            value_name = "DAY",
+            help = "Day of the month your billing cycle starts (1-31)",
+            long_help = "Day of the month your billing cycle starts (1-31).\nThe monthly limit and the projections/realtime month-to-date spend\nare measured from this day. Days past the end of a short month\nfall on its last day."
+        )]
+        cycle_start_day: Option<u32>,
+    },
+    #[command(about = "Show saved budget limits")]
+    Show,
+    #[command(about = "Remove all saved budget limits")]
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum GoalsAction {
+    #[command(about = "Save usage goals (only the given values are changed)")]
+    Set {
+        #[arg(long, value_name = "USD", help = "Maximum cost per day (USD)")]
+        daily_cost: Option<f64>,
+        #[arg(long, value_name = "TOKENS", help = "Maximum tokens per day")]
+        daily_tokens: Option<u64>,
+        #[arg(
+            long,
+            value_name = "USD",
+            help = "Maximum cost over the last 7 days (USD)"
+        )]
+        weekly_cost: Option<f64>,
+        #[arg(
+            long,
+            value_name = "TOKENS",
+            help = "Maximum tokens over the last 7 days"
+        )]
+        weekly_tokens: Option<u64>,
+    },
+    #[command(about = "Remove all saved goals")]
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum TagAction {
+    #[command(about = "Show tag rules and manually tagged sessions")]
+    List,
+    #[command(about = "Tag a session")]
+    Add {
+        #[arg(help = "Session ID, unique ID prefix, or project/session-id")]
+        session: String,
+        #[arg(required = true, help = "Tags to add")]
+        tags: Vec<String>,
+    },
+    #[command(about = "Remove tags from a session (all of them if none are given)")]
+    Remove {
+        #[arg(help = "Session ID, unique ID prefix, or project/session-id")]
+        session: String,
+        #[arg(help = "Tags to remove")]
+        tags: Vec<String>,
+    },
+    #[command(about = "Tag every session of projects matching a path glob")]
+    Map {
+        #[arg(help = "Project path glob, e.g. '~/work/*' (* and ? are supported)")]
+        pattern: String,
+        #[arg(required = true, help = "Tags for matching projects")]
+        tags: Vec<String>,
+    },
+    #[command(about = "Remove a project path rule")]
+    Unmap {
+        #[arg(help = "Project path glob as given to `tags map`")]
+        pattern: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum BookmarkAction {
+    #[command(about = "List bookmarked sessions")]
+    List {
+        #[arg(long, value_name = "TAG", help = "Only show bookmarks with this tag")]
+        tag: Option<String>,
+    },
+    #[command(about = "Bookmark a session, or update its note and tags")]
+    Add {
+        #[arg(help = "Session ID, unique ID prefix, or project/session-id")]
+        session: String,
+        #[arg(long, help = "Free-text note (replaces an existing note)")]
+        note: Option<String>,
+        #[arg(long = "tag", value_name = "TAG", help = "Tag to attach (repeatable)")]
+        tags: Vec<String>,
+    },
+    #[command(about = "Remove a bookmark")]
+    Remove {
+        #[arg(help = "Session ID, unique ID prefix, or project/session-id")]
+        session: String,
+    },
 }
 
 #[derive(Subcommand)]
 enum Commands {
     #[command(about = "Show daily usage report (default)")]
     #[command(
-        long_about = "Show daily usage aggregated by date\n\nDisplays token usage, costs, and activity patterns grouped by day.\nDefault enhanced format shows visual cards; use --classic for tables.\n\nEXAMPLES:\n  claudelytics daily                    # Enhanced daily report\n  claudelytics daily --classic          # Classic table format\n  claudelytics --json daily             # JSON output (global flag)\n  claudelytics --since 20240101 daily   # From specific date (global flag)\n  claudelytics --today daily            # Today only (global flag)"
+        long_about = "Show daily usage aggregated by date\n\nDisplays token usage, costs, and activity patterns grouped by day.\nDefault enhanced format shows visual cards; use --classic for tables.\n\nEXAMPLES:\n  claudelytics daily                    # Enhanced daily report\n  claudelytics daily --classic          # Classic table format\n  claudelytics --json daily             # JSON output (global flag)\n  claudelytics --since 20240101 daily   # From specific date (global flag)\n  claudelytics --today daily            # Today only (global flag)\n  claudelytics daily --group-by tag     # Split each day by tag (work, oss, ...)"
     )]
     Daily {
         #[arg(
@@ -308,10 +732,45 @@ enum Commands {
             long_help = "Sort order: asc (ascending), desc (descending)\nDefault: desc for date/cost/tokens"
         )]
         sort_order: Option<SortOrder>,
+        #[arg(
+            long,
+            help = "Break each day down by model family",
+            long_help = "Add a per-model-family sub-breakdown (Opus/Sonnet/Haiku tokens and cost)\nunder each daily row, or a nested `models` array with --json"
+        )]
+        models: bool,
+        #[arg(
+            long,
+            help = "Break each day down by Claude directory",
+            long_help = "Add a per-source column showing how much of each day came from each Claude\ndirectory, or a nested `sources` array with --json. Only meaningful when several\ndirectories are read (repeated --path or `claude_paths` in config)"
+        )]
+        by_source: bool,
+        #[arg(
+            long,
+            value_enum,
+            help = "Roll usage up by project or tag",
+            long_help = "Roll usage up by project directory or by tag\nTags come from the `tags` section of the config (see `claudelytics tags`);\nsessions without tags are grouped as 'untagged'"
+        )]
+        group_by: Option<GroupByField>,
+        #[arg(
+            long,
+            value_enum,
+            conflicts_with = "group_by",
+            help = "Split usage into time-of-day buckets such as work and personal",
+            long_help = "Split usage into the buckets of the `schedule` section of the config:\nweekday and local time windows (default: work Mon-Fri 09:00-18:00, personal\notherwise). Usage is assigned per record, so a session can count towards\nseveral buckets"
+        )]
+        split_by: Option<SplitBy>,
+        #[arg(
+            long,
+            value_name = "LIST",
+            conflicts_with_all = ["group_by", "split_by"],
+            help = "Show only these columns, e.g. date,cost,total_tokens",
+            long_help = "Comma-separated columns to show, in order, as a table or with --json\nColumns: date, input_tokens, output_tokens, cache_creation_tokens,\ncache_read_tokens, cache_tokens, total_tokens, thinking_tokens, cost,\nefficiency (tokens per dollar), ratio (output/input), models, sources"
+        )]
+        columns: Option<String>,
     },
     #[command(about = "Show session-based usage report")]
     #[command(
-        long_about = "Show usage aggregated by Claude Code sessions\n\nDisplays individual session data with project paths, activity times,\nand per-session token usage and costs.\n\nEXAMPLES:\n  claudelytics session                  # Enhanced session report\n  claudelytics session --classic        # Classic table format\n  claudelytics --json session           # JSON output (global flag)\n  claudelytics --today session          # Today's sessions only (global flag)"
+        long_about = "Show usage aggregated by Claude Code sessions\n\nDisplays individual session data with project paths, activity times,\nand per-session token usage and costs.\n\nEXAMPLES:\n  claudelytics session                  # Enhanced session report\n  claudelytics session --classic        # Classic table format\n  claudelytics --json session           # JSON output (global flag)\n  claudelytics --today session          # Today's sessions only (global flag)\n  claudelytics session --group-by tag   # One row per tag\n  claudelytics session --group-by project # One row per project"
     )]
     Session {
         #[arg(
@@ -332,18 +791,82 @@ enum Commands {
             long_help = "Sort order: asc (ascending), desc (descending)\nDefault: desc for cost/tokens/efficiency, asc for project"
         )]
         sort_order: Option<SortOrder>,
+        #[arg(
+            long,
+            value_enum,
+            help = "Roll usage up by project or tag",
+            long_help = "Roll usage up by project directory or by tag\nTags come from the `tags` section of the config (see `claudelytics tags`);\nsessions without tags are grouped as 'untagged'"
+        )]
+        group_by: Option<GroupByField>,
+        #[arg(
+            long,
+            help = "Combine resumed sessions into one row",
+            long_help = "Resuming a conversation starts a new session file; link each resumed session\nto the one it continues (via summary records and parent message UUIDs) and\nreport every chain of resumes as one row under its first session, with\ncombined usage and cost"
+        )]
+        group_resumes: bool,
+        #[arg(
+            long,
+            value_name = "N",
+            conflicts_with = "group_by",
+            help = "Show at most N sessions",
+            long_help = "Show at most N sessions, after --offset\nTotals still cover every session; a footer notes how many were omitted"
+        )]
+        limit: Option<usize>,
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 0,
+            conflicts_with = "group_by",
+            help = "Skip the first N sessions",
+            long_help = "Skip the first N sessions of the sorted list, e.g. --limit 50 --offset 50 for the second page"
+        )]
+        offset: usize,
+        #[arg(
+            long,
+            value_name = "N",
+            conflicts_with_all = ["limit", "sort_by", "sort_order", "group_by"],
+            help = "Show the N most expensive sessions",
+            long_help = "Show the N most expensive sessions (same as --sort-by cost --sort-order desc --limit N)"
+        )]
+        top: Option<usize>,
+        #[arg(
+            long,
+            value_name = "LIST",
+            conflicts_with = "group_by",
+            help = "Show only these columns, e.g. date,cost,total_tokens",
+            long_help = "Comma-separated columns to show, in order, as a table or with --json\nColumns: session, project, session_id, last_activity (or date), summary,\ninput_tokens, output_tokens, cache_creation_tokens, cache_read_tokens,\ncache_tokens, total_tokens, thinking_tokens, cost, efficiency (tokens per\ndollar), score, context_peak, resumes"
+        )]
+        columns: Option<String>,
+        #[arg(
+            long,
+            value_name = "EXPR",
+            conflicts_with = "group_by",
+            help = "Only sessions matching an expression, e.g. \"cost > 5 && project =~ 'api'\"",
+            long_help = "Only report sessions matching a filter expression; totals cover the matches\nFields are the --columns names (cost, tokens, project, date, summary, score, ...)\nOperators: == != > >= < <= for numbers and quoted text, =~ !~ for regular\nexpressions, && || ! and parentheses\nExample: --filter \"cost > 5 && project =~ 'backend' && tokens > 1e6\""
+        )]
+        filter: Option<String>,
     },
     #[command(about = "Launch terminal user interface")]
     #[command(
         long_about = "Launch interactive terminal user interface\n\nFull-featured TUI with multiple tabs, navigation, and visual charts.\nProvides comprehensive analysis in a terminal-based interface.\n\nFEATURES:\n  - Multiple tabs: Overview, Daily, Sessions, Charts, Help\n  - Keyboard navigation (j/k, arrows, Enter, Tab)\n  - Visual elements: gauges, charts, formatted tables\n  - Search and filtering capabilities\n  - Real-time data display\n\nKEYBOARD SHORTCUTS:\n  q/Esc: Quit  Tab: Next tab  j/k: Navigate  Enter: Select\n\nEXAMPLE:\n  claudelytics tui                      # Launch TUI"
     )]
     Tui,
-    // #[command(about = "Launch analytics studio TUI")]
-    // #[command(long_about = "Launch comprehensive analytics studio with AI insights\n\nData science-grade analytics interface with 17 specialized tabs,\npattern analysis, predictive modeling, and machine learning insights.\n\nFEATURES:\n  - 17 specialized analytics tabs\n  - Usage pattern detection and clustering\n  - Productivity analytics with deep work analysis\n  - Predictive cost forecasting and trend analysis\n  - Risk management with budget tracking\n  - Workflow integration (Git, projects, milestones)\n  - AI-powered insights and recommendations\n  - Interactive data exploration with correlation analysis\n  - Advanced search with smart suggestions\n  - Custom dashboards and personalization\n\nKEYBOARD SHORTCUTS:\n  F10-F12: Analytics tabs  Ctrl+F: Advanced search\n  Ctrl+D: Custom dashboard  All advanced TUI shortcuts apply\n\nEXAMPLE:\n  claudelytics analytics-tui            # Launch Analytics Studio")]
-    // AnalyticsTui, // Temporarily disabled - work in progress
-    #[command(about = "Export data to CSV")]
+    #[command(about = "Launch analytics studio TUI")]
+    #[command(
+        long_about = "Launch the Analytics Studio, a TUI for usage patterns, forecasts and budget risk\n\nVIEWS:\n  1 Patterns       Cost by hour of day and day of week, session frequency and streaks\n  2 Forecast       Daily cost for the last 30 days and a 14-day projection with trend\n  3 Budget & Risk  Today's and this month's spend against saved limits,\n                   projected month-end spend, and the most expensive sessions\n  4 Goals          Streaks of days within saved goals and recent violations\n\nBudget limits come from `claudelytics budget set`, goals from `claudelytics goals set`.\n\nKEYBOARD SHORTCUTS:\n  1-4/Tab/h/l: Switch view  q/Esc: Quit\n\nEXAMPLES:\n  claudelytics analytics-tui                 # Launch Analytics Studio\n  claudelytics analytics-tui --threshold 5   # List sessions above $5"
+    )]
+    AnalyticsTui {
+        #[arg(
+            long,
+            help = "Cost threshold for the expensive sessions list",
+            long_help = "Sessions above this cost are listed in the Budget & Risk view",
+            default_value = "1.0"
+        )]
+        threshold: f64,
+    },
+    #[command(about = "Export data to CSV, JSON, or Parquet")]
     #[command(
-        long_about = "Export usage data to CSV files for external analysis\n\nCreates CSV files containing daily reports, session data, or summaries.\nDefault behavior exports all types if no specific flags are provided.\n\nFILE NAMING:\n  Daily report: {base}.daily.csv\n  Sessions: {base}.sessions.csv\n  Summary: {base}.summary.csv\n\nEXAMPLES:\n  claudelytics export                   # Export all to default location\n  claudelytics export --daily -o report # Export daily data only\n  claudelytics export --sessions --summary # Export sessions + summary\n  claudelytics --since 20240101 export # Export data from specific date"
+        long_about = "Export usage data to files for external analysis\n\nCreates CSV, JSON, or Parquet files containing daily reports, session data, or summaries.\nDefault behavior exports all types if no specific flags are provided.\n\nFILE NAMING:\n  Daily report: {base}.daily.{ext}\n  Sessions: {base}.sessions.{ext}\n  Summary: {base}.summary.{ext}\n\nEXAMPLES:\n  claudelytics export                   # Export all to default location\n  claudelytics export --daily -o report # Export daily data only\n  claudelytics export --sessions --summary # Export sessions + summary\n  claudelytics export --format parquet  # Export for pandas/duckdb pipelines\n  claudelytics export --format markdown --summary # Paste-ready Markdown\n  claudelytics export --html -o report.html # Standalone HTML report with charts\n  claudelytics export --heatmap         # Calendar heatmap in the terminal + SVG file\n  claudelytics export --team-bundle --user alice # Bundle for `claudelytics team`\n  claudelytics export --obsidian ~/vault/claude # Daily notes + conversation notes\n  claudelytics export --group-by tag    # Daily and session rows rolled up by tag\n  claudelytics --since 20240101 export # Export data from specific date"
     )]
     Export {
         #[arg(
@@ -371,10 +894,87 @@ enum Commands {
             long_help = "Base path for output files (without extension)\nDefault: ./claudelytics_export (creates .daily.csv, .sessions.csv, etc.)\nExample: -o ~/reports/usage creates ~/reports/usage.daily.csv"
         )]
         output: Option<PathBuf>,
+        #[arg(
+            long,
+            value_enum,
+            default_value = "csv",
+            help = "Export file format",
+            long_help = "File format for exported data:\n  csv: Comma-separated values (default)\n  json: JSON with the same field names as --json output\n  parquet: Columnar Parquet files (requires the `parquet` build feature)\n  markdown: Markdown tables and a summary section for issues or docs"
+        )]
+        format: CliExportFormat,
+        #[arg(
+            long,
+            help = "Sort field for exported rows",
+            long_help = "Field to sort exported daily and session rows by: date, cost, tokens, efficiency, project\nDefault: same ordering as the terminal reports"
+        )]
+        sort_by: Option<SortField>,
+        #[arg(long, help = "Sort order for exported rows")]
+        sort_order: Option<SortOrder>,
+        #[arg(
+            long,
+            value_enum,
+            help = "Roll exported rows up by project or tag",
+            long_help = "Export daily rows split by project or tag, and sessions rolled up into\none row per project or tag. The summary is unchanged"
+        )]
+        group_by: Option<GroupByField>,
+        #[arg(
+            long,
+            value_enum,
+            conflicts_with = "group_by",
+            help = "Split exported rows into time-of-day buckets such as work and personal",
+            long_help = "Export daily rows split into the buckets of the `schedule` section of the\nconfig, and one row per bucket in place of the sessions file. The summary is\nunchanged"
+        )]
+        split_by: Option<SplitBy>,
+        #[arg(
+            long,
+            conflicts_with_all = ["daily", "sessions", "summary", "format", "group_by", "split_by"],
+            help = "Export a standalone HTML report with charts",
+            long_help = "Write a single self-contained HTML file with charts for cost over time,\ntokens by model, and sessions by project\nDefault: ./claudelytics_report.html; -o sets the file path"
+        )]
+        html: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["daily", "sessions", "summary", "format", "group_by", "split_by", "html"],
+            help = "Show a calendar heatmap and export it as SVG",
+            long_help = "Render a GitHub-style calendar heatmap of daily cost (one cell per day,\nweekdays as rows) with streaks and per-weekday totals, and write it as an SVG file\nCovers the last 53 weeks unless --since/--until are given\nDefault: ./claudelytics_heatmap.svg; -o sets the file path; --json prints the data"
+        )]
+        heatmap: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["daily", "sessions", "summary", "format", "group_by", "split_by", "html", "heatmap"],
+            help = "Export a team bundle for `claudelytics team`",
+            long_help = "Write this machine's usage per day, session, and model as a JSON bundle
+that `claudelytics team` merges with other users' bundles
+Contains token counts and costs only, no prompts or conversation content
+Default: ./claudelytics_team_<user>.json; -o sets the file path"
+        )]
+        team_bundle: bool,
+        #[arg(
+            long,
+            requires = "team_bundle",
+            help = "User name recorded in the team bundle (default: $USER)"
+        )]
+        user: Option<String>,
+        #[arg(
+            long,
+            value_name = "DIR",
+            conflicts_with_all = ["daily", "sessions", "summary", "format", "group_by", "split_by", "html", "heatmap", "team_bundle", "output"],
+            help = "Write or update daily notes in an Obsidian vault folder",
+            long_help = "Write one YYYY-MM-DD.md note per day with usage into DIR, with cost, tokens,\nand sessions as YAML frontmatter (claude_cost, claude_tokens, claude_sessions)\nand links to that day's conversations, exported as notes into DIR/conversations/\nExisting notes are updated in place: only the claude_* keys and the section\nbetween the claudelytics markers change\nRespects --since, --until, and --today"
+        )]
+        obsidian: Option<PathBuf>,
+        #[arg(
+            long,
+            value_name = "LIST",
+            conflicts_with_all = ["group_by", "split_by", "html", "heatmap", "team_bundle", "obsidian"],
+            help = "Export only these daily/session columns, e.g. date,cost,total_tokens",
+            long_help = "Comma-separated columns for the daily and sessions files (CSV or JSON), in order\nSee `claudelytics daily --help` and `claudelytics session --help` for the names;\n`date` is the last activity of a session. The summary is unchanged"
+        )]
+        columns: Option<String>,
     },
     #[command(about = "Show usage aggregated by months")]
     #[command(
-        long_about = "Show usage aggregated by calendar months\n\nDisplays monthly summaries with total usage, active days,\nand average daily costs for better long-term analysis.\n\nEXAMPLES:\n  claudelytics monthly                  # Enhanced monthly report\n  claudelytics monthly --classic        # Classic table format\n  claudelytics --json monthly           # JSON output (global flag)\n  claudelytics --since 202401 monthly   # From January 2024 onwards"
+        long_about = "Show usage aggregated by calendar months\n\nDisplays monthly summaries with total usage, active days,\nand average daily costs for better long-term analysis.\n\nEXAMPLES:\n  claudelytics monthly                  # Enhanced monthly report\n  claudelytics monthly --classic        # Classic table format\n  claudelytics --json monthly           # JSON output (global flag)\n  claudelytics --since 202401 monthly   # From January 2024 onwards\n  claudelytics monthly --group-by tag   # Work vs personal spend per month"
     )]
     Monthly {
         #[arg(
@@ -395,10 +995,25 @@ enum Commands {
             long_help = "Sort order: asc (ascending), desc (descending)\nDefault: desc for date/cost/tokens"
         )]
         sort_order: Option<SortOrder>,
-    },
-    #[command(about = "Show usage aggregated by weeks")]
-    #[command(
-        long_about = "Show usage aggregated by weeks\n\nDisplays weekly summaries with total usage, active days,\nand average daily costs. Configurable week start day.\n\nEXAMPLES:\n  claudelytics weekly                   # Weekly report (weeks start Monday)\n  claudelytics weekly --start-of-week sunday  # Weeks start Sunday\n  claudelytics --json weekly            # JSON output"
+        #[arg(
+            long,
+            value_enum,
+            help = "Roll usage up by project or tag",
+            long_help = "Roll usage up by project directory or by tag\nTags come from the `tags` section of the config (see `claudelytics tags`);\nsessions without tags are grouped as 'untagged'"
+        )]
+        group_by: Option<GroupByField>,
+        #[arg(
+            long,
+            value_enum,
+            conflicts_with = "group_by",
+            help = "Split usage into time-of-day buckets such as work and personal",
+            long_help = "Split usage into the buckets of the `schedule` section of the config:\nweekday and local time windows (default: work Mon-Fri 09:00-18:00, personal\notherwise). Usage is assigned per record, so a session can count towards\nseveral buckets"
+        )]
+        split_by: Option<SplitBy>,
+    },
+    #[command(about = "Show usage aggregated by weeks")]
+    #[command(
+        long_about = "Show usage aggregated by weeks\n\nDisplays weekly summaries with total usage, active days,\nand average daily costs. Configurable week start day.\n\nEXAMPLES:\n  claudelytics weekly                   # Weekly report (weeks start Monday)\n  claudelytics weekly --start-of-week sunday  # Weeks start Sunday\n  claudelytics --json weekly            # JSON output"
     )]
     Weekly {
         #[arg(long, help = "Use classic table format")]
@@ -414,9 +1029,27 @@ enum Commands {
         )]
         start_of_week: String,
     },
+    #[command(about = "Show usage aggregated by hour")]
+    #[command(
+        long_about = "Show usage aggregated by clock hour\n\nDisplays hour-by-hour history (local time) with tokens, cost, messages,\nand active sessions per hour. Unlike the time-of-day analytics, hours\nare not folded across days, so a single day can be lined up against\nCI runs or other activity. Only hours with usage are listed.\n\nEXAMPLES:\n  claudelytics hourly --date 20240315   # Hour-by-hour history for one day\n  claudelytics --today hourly           # Today's usage by hour\n  claudelytics hourly --sort-by cost    # Most expensive hours first\n  claudelytics --json hourly            # JSON output (global flag)"
+    )]
+    Hourly {
+        #[arg(long, help = "Use classic table format")]
+        classic: bool,
+        #[arg(
+            long,
+            help = "Show a single day (YYYYMMDD)",
+            long_help = "Limit the report to one local day, same as --since DATE --until DATE\nAccepts the same forms as --since\nExample: --date 20240315, --date yesterday"
+        )]
+        date: Option<String>,
+        #[arg(long, help = "Sort field")]
+        sort_by: Option<SortField>,
+        #[arg(long, help = "Sort order")]
+        sort_order: Option<SortOrder>,
+    },
     #[command(about = "Manage configuration")]
     #[command(
-        long_about = "Manage Claudelytics configuration settings\n\nConfiguration is stored in YAML format and persists between runs.\nUse --show to view current settings or modify specific options.\n\nCONFIG LOCATION:\n  ~/.config/claudelytics/config.yaml (or platform equivalent)\n\nAVAILABLE SETTINGS:\n  - Claude directory path\n  - Default output format (enhanced/classic/json)\n  - Default command\n  - Export directory\n  - Date format preferences\n\nEXAMPLES:\n  claudelytics config --show            # View current configuration\n  claudelytics config --set-path ~/claude # Set custom Claude directory\n  claudelytics config --reset           # Reset to defaults"
+        long_about = "Manage Claudelytics configuration settings\n\nConfiguration is stored in YAML format and persists between runs.\nUse --show to view current settings or modify specific options.\n\nCONFIG LOCATION:\n  $XDG_CONFIG_HOME/claudelytics/config.yaml (default: ~/.config/claudelytics/config.yaml)\n\nAVAILABLE SETTINGS:\n  - Claude directory path\n  - Default output format (enhanced/classic/json)\n  - Default command\n  - Export directory\n  - Date format preferences\n  - Named profiles (Claude path, budgets, timezone, filters, output format)\n\nEXAMPLES:\n  claudelytics config --show            # View current configuration\n  claudelytics config --set-path ~/claude # Set custom Claude directory\n  claudelytics config --reset           # Reset to defaults\n  claudelytics --path ~/work-claude --model-filter opus config --create-profile work --daily-limit 50\n  claudelytics config --list-profiles   # Saved profiles\n  claudelytics --profile work daily     # Use a profile"
     )]
     Config {
         #[arg(
@@ -437,10 +1070,374 @@ enum Commands {
             long_help = "Set custom path to Claude directory\nUseful for non-standard installations or shared configurations\nExample: --set-path ~/Dropbox/.claude"
         )]
         set_path: Option<PathBuf>,
+        #[arg(
+            long = "set",
+            value_name = "KEY=VALUE",
+            help = "Set a config value (repeatable)",
+            long_help = "Set any config value by its section.key path; the value is read as YAML\nand `null` removes it. Default flags live in the `defaults` section\nExamples:\n  claudelytics config --set defaults.responsive=true --set defaults.model_filter=opus\n  claudelytics config --set budget.daily_limit=20\n  claudelytics config --set currency.default=null"
+        )]
+        set_values: Vec<String>,
+        #[arg(
+            long,
+            help = "Open the config file in $EDITOR",
+            long_help = "Open config.yaml in $VISUAL or $EDITOR (default: vi, notepad on Windows)\nand check it when the editor exits: type errors and unknown keys are\nreported with their line and column"
+        )]
+        edit: bool,
+        #[arg(
+            long,
+            help = "Move v1 settings to the v2 layout",
+            long_help = "Rewrite top-level v1 keys (claude_path, default_command, date_format, ...)\ninto the v2 sections after backing the file up as config.yaml.v1.bak\nThis also happens automatically the first time another command runs"
+        )]
+        migrate: bool,
+        #[arg(long, help = "List saved profiles")]
+        list_profiles: bool,
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Save a profile from the global flags and the options below",
+            long_help = "Save (or replace) a named profile for use with --profile\nThe global --path, --model-filter, --since, and --json flags given with this\ncommand are stored in the profile, together with --description, --timezone,\n--daily-limit, and --monthly-limit\nExample: claudelytics --path ~/work-claude --json config --create-profile work --timezone Europe/Berlin"
+        )]
+        create_profile: Option<String>,
+        #[arg(long, value_name = "NAME", help = "Delete a saved profile")]
+        delete_profile: Option<String>,
+        #[arg(long, requires = "create_profile", help = "Profile description")]
+        description: Option<String>,
+        #[arg(
+            long,
+            value_name = "TZ",
+            requires = "create_profile",
+            help = "Timezone for dates and hours, e.g. Asia/Tokyo (sets TZ)"
+        )]
+        timezone: Option<String>,
+        #[arg(
+            long,
+            value_name = "USD",
+            requires = "create_profile",
+            help = "Daily budget limit of the profile"
+        )]
+        daily_limit: Option<f64>,
+        #[arg(
+            long,
+            value_name = "USD",
+            requires = "create_profile",
+            help = "Monthly budget limit of the profile"
+        )]
+        monthly_limit: Option<f64>,
+    },
+    #[command(about = "Manage persisted budget limits")]
+    #[command(
+        long_about = "Manage budget limits stored in the configuration file\n\nSaved limits are used automatically by the realtime, live, and blocks\ncommands. Explicit command-line limits always take precedence.\n\nEXAMPLES:\n  claudelytics budget set --daily 20 --monthly 400  # Save daily and monthly limits\n  claudelytics budget set --alert-threshold 0.9     # Alert at 90% of budget\n  claudelytics budget show                          # Show saved limits\n  claudelytics budget clear                         # Remove all saved limits"
+    )]
+    Budget {
+        #[command(subcommand)]
+        action: BudgetAction,
+    },
+    #[command(about = "Track streaks of days within your usage goals")]
+    #[command(
+        long_about = "Check every day against the usage goals saved in the configuration file\n\nShows today's progress toward each goal, the current streak of compliant\ndays, the longest streak, and the days that went over a goal in the last\n30 days. Daily goals apply to a single day; weekly goals apply to the\n7 days ending on each day. Days without usage count as compliant.\nToken goals include cache tokens, like the Total Tokens column.\n\nThe same summary is shown in the Goals view of `claudelytics analytics-tui`.\n\nEXAMPLES:\n  claudelytics goals set --daily-cost 10 --weekly-tokens 2000000  # Save goals\n  claudelytics goals                    # Streaks and recent violations\n  claudelytics --json goals             # JSON output\n  claudelytics goals clear              # Remove all saved goals"
+    )]
+    Goals {
+        #[command(subcommand)]
+        action: Option<GoalsAction>,
+    },
+    #[command(about = "Manage bookmarked sessions with notes and tags")]
+    #[command(
+        long_about = "Manage session bookmarks stored in bookmarks.yaml\n\nBookmarks are shared with the TUI (b to bookmark, n to add a note, and the\nBookmarks tab). Sessions can be given as a full ID, a unique ID prefix, or\nproject/session-id.\n\nEXAMPLES:\n  claudelytics bookmarks add 3f2a --note \"Refactor that blew the budget\" --tag perf\n  claudelytics bookmarks list\n  claudelytics bookmarks list --tag perf\n  claudelytics --json bookmarks list\n  claudelytics bookmarks remove 3f2a"
+    )]
+    Bookmarks {
+        #[command(subcommand)]
+        action: BookmarkAction,
+    },
+    #[command(about = "Per-project or per-tag cost statement for invoicing")]
+    #[command(
+        long_about = "Allocate one month of usage to projects or tags for invoicing clients\n\nEach line shows sessions, tokens, cost, the optional markup, and the amount\nto bill. Sessions spanning several months are split by day, so each month\nonly bills its own usage. Tags come from `claudelytics tags`.\n\nEXAMPLES:\n  claudelytics chargeback                               # Last month by project\n  claudelytics chargeback --period 2025-06 --markup 15  # June 2025 with 15% markup\n  claudelytics chargeback --group-by tag --format csv -o june.csv\n  claudelytics chargeback --format markdown -o june.md  # pandoc june.md -o june.pdf\n  claudelytics --json chargeback --period 2025-06       # JSON output (global flag)"
+    )]
+    Chargeback {
+        #[arg(
+            long,
+            value_enum,
+            default_value = "project",
+            help = "Allocate by project or tag"
+        )]
+        group_by: GroupByField,
+        #[arg(
+            long,
+            value_name = "YYYY-MM",
+            help = "Month to bill (default: last month)"
+        )]
+        period: Option<String>,
+        #[arg(
+            long,
+            value_name = "PERCENT",
+            default_value = "0",
+            help = "Markup added to the cost, in percent"
+        )]
+        markup: f64,
+        #[arg(
+            long,
+            value_enum,
+            help = "Write the statement as CSV, JSON, or Markdown",
+            long_help = "Write the statement as CSV, JSON, or Markdown instead of a table\nPrinted to stdout unless --output is given; with --output alone the format\nfollows the file extension (.csv, .json, otherwise Markdown)"
+        )]
+        format: Option<StatementFormat>,
+        #[arg(short, long, help = "File to write the statement to")]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Narrative Markdown summary of the last day, week, or month")]
+    #[command(
+        long_about = "Write a digest of recent usage as Markdown, ready to pipe into `mail` or post\nto a chat\n\nThe digest covers total spend and its change against the period before, the\ntop 5 sessions and projects, the model mix, anomalies flagged in the period\n(see `claudelytics anomalies`), and the billing cycle spend projected at the\nperiod's pace against the saved monthly budget.\n\nEXAMPLES:\n  claudelytics digest                                  # Last 7 days to stdout\n  claudelytics digest --period week --output digest.md\n  claudelytics digest --period day | mail -s \"Claude usage\" me@example.com\n  claudelytics --json digest --period month            # JSON output (global flag)"
+    )]
+    Digest {
+        #[arg(long, value_enum, default_value = "week", help = "Period to summarize")]
+        period: DigestPeriod,
+        #[arg(short, long, help = "File to write the digest to")]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Post a usage summary to a Slack or Discord webhook")]
+    #[command(
+        long_about = "Post a short usage summary to a Slack incoming webhook (as Block Kit blocks)\nor a Discord webhook (as an embed)\n\nThe summary has the same figures as `claudelytics digest`: spend and its change\nagainst the period before, the top sessions and projects, the model mix,\nanomalies, and the billing cycle projection.\n\nEXAMPLES:\n  claudelytics notify --slack-webhook https://hooks.slack.com/services/...\n  claudelytics notify --discord-webhook https://discord.com/api/webhooks/... --period day\n  claudelytics notify --slack-webhook URL --dry-run    # Print the payload instead\n\n  # Monday-morning spend post from cron\n  0 9 * * 1 claudelytics notify --slack-webhook https://hooks.slack.com/services/..."
+    )]
+    Notify {
+        #[arg(
+            long,
+            value_name = "URL",
+            required_unless_present = "discord_webhook",
+            conflicts_with = "discord_webhook",
+            help = "Slack incoming webhook URL"
+        )]
+        slack_webhook: Option<String>,
+        #[arg(long, value_name = "URL", help = "Discord webhook URL")]
+        discord_webhook: Option<String>,
+        #[arg(long, value_enum, default_value = "week", help = "Period to summarize")]
+        period: DigestPeriod,
+        #[arg(long, help = "Print the webhook payload instead of posting it")]
+        dry_run: bool,
+    },
+    #[command(about = "Find daily cost spikes and token-heavy sessions")]
+    #[command(
+        long_about = "Detect unusual usage\n\nFlags days whose cost is more than N standard deviations above the mean of\nthe active days in the trailing window, and sessions whose tokens per message\nare N standard deviations above the other sessions. N defaults to\n`alerts.anomaly_threshold` in the config file (3.0).\n\nEXAMPLES:\n  claudelytics anomalies                       # Spikes over the default threshold\n  claudelytics anomalies --threshold 2 --window 14\n  claudelytics anomalies --alert               # Send today's anomalies to the alert hooks\n  claudelytics --json anomalies                # JSON output (global flag)"
+    )]
+    Anomalies {
+        #[arg(
+            long,
+            value_name = "SIGMA",
+            help = "Standard deviations above normal to flag (default: from config)"
+        )]
+        threshold: Option<f64>,
+        #[arg(
+            long,
+            value_name = "DAYS",
+            default_value = "30",
+            help = "Trailing window for the daily cost baseline"
+        )]
+        window: i64,
+        #[arg(
+            long,
+            help = "Send today's anomalies through the configured alert hooks",
+            long_help = "Send today's anomalies through the webhook and command configured in the\n`alerts` config section, e.g. from a cron job"
+        )]
+        alert: bool,
+    },
+    #[command(about = "Watch for new usage as it happens")]
+    #[command(
+        long_about = "Watch the Claude directories for new usage\n\nPolls the JSONL files every few seconds and reports each usage delta: the\nmessages, tokens and cost a session added for a model since the last poll.\nThe table format redraws today's usage with the latest activity below it;\njson-lines prints one JSON object per delta and nothing else, so the\noutput can be piped into other tools.\n\nEXAMPLES:\n  claudelytics watch                                # Live daily table\n  claudelytics watch --project ~/work/api           # Only one project\n  claudelytics watch --model-filter opus --interval 5\n  claudelytics watch --format json-lines | jq -c '{session_id, cost}'\n  claudelytics watch --notify --notify-at 10 --notify-at 25 # Desktop alerts"
+    )]
+    Watch {
+        #[arg(
+            long,
+            value_enum,
+            default_value = "table",
+            help = "Output format (table, json-lines)"
+        )]
+        format: WatchFormat,
+        #[arg(
+            long,
+            value_name = "PROJECT",
+            help = "Only watch projects matching this path or name",
+            long_help = "Only report sessions whose project matches this path or part of a path\nExamples: --project ~/work/api, --project api"
+        )]
+        project: Option<String>,
+        #[arg(
+            long,
+            value_name = "MODEL",
+            help = "Only watch usage of this model (opus, sonnet, haiku)",
+            long_help = "Only report usage of matching models; same as the global --model-filter"
+        )]
+        model_filter: Option<String>,
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value = "2",
+            help = "Seconds between polls"
+        )]
+        interval: u64,
+        #[arg(
+            long,
+            help = "Show desktop notifications for spend, expensive sessions, and quota",
+            long_help = "Show a desktop notification when:\n  - today's cost crosses a threshold (--notify-at, or the daily budget at its\n    alert threshold and at 100%)\n  - a session costing more than --expensive-session goes idle for 5 minutes\n  - the current 5-hour block reaches --quota-warn percent of the plan quota\nUses osascript on macOS and notify-send on Linux"
+        )]
+        notify: bool,
+        #[arg(
+            long,
+            value_name = "USD",
+            requires = "notify",
+            help = "Notify when today's cost crosses this amount (repeatable)"
+        )]
+        notify_at: Vec<f64>,
+        #[arg(
+            long,
+            value_name = "USD",
+            requires = "notify",
+            help = "Notify when a session above this cost finishes (default: 95th percentile of past sessions, at least $1)"
+        )]
+        expensive_session: Option<f64>,
+        #[arg(
+            long,
+            value_name = "PERCENT",
+            default_value = "90",
+            help = "Notify when the current 5-hour block reaches this share of the quota (0 disables)"
+        )]
+        quota_warn: f64,
+    },
+    #[command(about = "Manage session tags used by --group-by tag")]
+    #[command(
+        long_about = "Manage session tags stored in the `tags` section of the config file\n\nA session gets the tags of every project path rule it matches plus its\nmanual tags. Reports roll usage up by tag with `--group-by tag`; sessions\nwithout tags are grouped as 'untagged'.\n\nEXAMPLES:\n  claudelytics tags map '~/work/*' work       # Tag all projects under ~/work\n  claudelytics tags map '*client-x*' client-x work\n  claudelytics tags add 3f2a oss               # Tag a single session\n  claudelytics tags remove 3f2a oss\n  claudelytics tags unmap '~/work/*'\n  claudelytics tags list\n  claudelytics session --group-by tag         # Spend per tag"
+    )]
+    Tags {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+    #[command(about = "Import external usage data (Anthropic API, other tools)")]
+    #[command(
+        long_about = "Merge usage from outside Claude Code into all reports\n\nImported rows are stored locally and added to daily, monthly, and other\nreports. They appear in session reports under the project 'imported' with\nthe source name as session, so external spend stays identifiable.\nRe-importing an overlapping export replaces rows for the same source, day,\nand model.\n\nFORMATS:\n  anthropic-console  Usage CSV exported from the Anthropic Console\n  generic            CSV or JSON array with date, model, input_tokens,\n                     output_tokens, cache_creation_tokens, cache_read_tokens,\n                     and optional cost_usd (cost is calculated when missing)\n\nEXAMPLES:\n  claudelytics import --file usage.csv --format anthropic-console\n  claudelytics import --file api.json --source batch-jobs\n  claudelytics import --list     # Show imported sources\n  claudelytics import --clear    # Remove all imported usage"
+    )]
+    Import {
+        #[arg(
+            long,
+            value_name = "PATH",
+            required_unless_present_any = ["list", "clear"],
+            help = "CSV or JSON file to import"
+        )]
+        file: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value = "generic",
+            help = "Input file format"
+        )]
+        format: ImportFormatArg,
+
+        #[arg(
+            long,
+            help = "Source tag for the imported rows (default: the format name)"
+        )]
+        source: Option<String>,
+
+        #[arg(long, conflicts_with_all = ["file", "clear"], help = "List imported sources")]
+        list: bool,
+
+        #[arg(long, conflicts_with = "file", help = "Remove all imported usage")]
+        clear: bool,
+    },
+    #[command(about = "Archive daily and session usage before Claude Code prunes it")]
+    #[command(
+        long_about = "Append the usage of every day, session, and model to a local archive\n\nClaude Code deletes old JSONL files, and reports lose that history. A\nsnapshot appends the current totals to an append-only JSONL file in the data\ndirectory (~/.local/share/claudelytics/usage_archive.jsonl); rows that did not\nchange since the last snapshot are skipped. Reports run with --with-archive\n(or archive.merge: true) add archived usage for days and sessions that no\nlonger have live data. Set archive.auto_snapshot: true to snapshot on every\nrun.\n\nEXAMPLES:\n  claudelytics snapshot                  # Archive all current usage\n  claudelytics snapshot --stats          # What the archive covers\n  claudelytics --with-archive monthly    # Monthly report including pruned history"
+    )]
+    Snapshot {
+        #[arg(
+            long,
+            help = "Show what the archive holds instead of taking a snapshot"
+        )]
+        stats: bool,
+    },
+    #[command(about = "Move old session logs into a compressed archive to free disk space")]
+    #[command(
+        long_about = "Move session files not modified within a retention period out of the Claude\ndirectories into an archive directory, compressed with zstd\n\nClaude Code never deletes its session logs. Before moving anything, prune takes\na usage snapshot (see `claudelytics snapshot`), so reports run with\n--with-archive keep the pruned history. The archive keeps the projects/<project>/\nlayout, so `claudelytics --path <archive>` can still read the archived sessions.\nFiles whose copy already exists in the archive are left in place.\n\nEXAMPLES:\n  claudelytics prune --older-than 180d --archive ~/claude-archive --dry-run\n  claudelytics prune --older-than 26w --archive ~/claude-archive\n  claudelytics --path ~/claude-archive monthly   # Report on archived sessions"
+    )]
+    Prune {
+        #[arg(
+            long,
+            value_name = "AGE",
+            help = "Prune sessions not modified for this long, e.g. 180d or 26w (as in --last)"
+        )]
+        older_than: String,
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Directory the compressed session files are moved into"
+        )]
+        archive: PathBuf,
+        #[arg(
+            long,
+            help = "Show what would be moved and reclaimed without touching any file"
+        )]
+        dry_run: bool,
+    },
+    #[command(about = "Show how much disk the Claude directories use and what to archive first")]
+    #[command(
+        long_about = "Report the disk footprint of the Claude directories\n\nShows the size and session files of each project directory, the largest\nsession files, and how much was written in the last 30 days (from file\nmodification times). Each project's size is set against the cost and tokens it\nrecorded, and projects idle for 30+ days are listed largest first as the ones\nto archive with `claudelytics prune`. Usage covers all dates;\n--since/--until and --model-filter are ignored.\n\nEXAMPLES:\n  claudelytics disk                 # Largest 10 projects and files\n  claudelytics disk --top 25\n  claudelytics --json disk          # JSON output"
+    )]
+    Disk {
+        #[arg(
+            long,
+            default_value = "10",
+            help = "Number of projects and files to list"
+        )]
+        top: usize,
+    },
+    #[command(about = "Load all usage records into the SQLite database")]
+    #[command(
+        long_about = "Load every usage record into ~/.local/share/claudelytics/usage.db\n\nOne row per assistant message with its timestamp, local date, project,\nsession, model, tokens, and cost, indexed by date, project, and model.\nEach sync replaces the rows of the sessions it finds, so it can be re-run\nat any time; sessions whose JSONL files were pruned keep their rows.\nReports read the database with --backend sqlite, and `claudelytics sql`\nqueries it directly. Requires a build with `--features sqlite`.\n\nEXAMPLES:\n  claudelytics sync-db\n  claudelytics --backend sqlite daily"
+    )]
+    SyncDb,
+    #[command(about = "Run a read-only SQL query against the usage database")]
+    #[command(
+        long_about = "Run an ad-hoc SQL query against the database written by `sync-db`\n\nTABLES:\n  usage     timestamp, date, project, session_id, model, input_tokens,\n            output_tokens, cache_creation_tokens, cache_read_tokens, cost,\n            fast_mode_cost, thinking_tokens (one row per assistant message)\n  sessions  project, session_id, summary, last_activity\n  meta      key, value (last_sync)\n\nThe database is opened read-only.\n\nEXAMPLES:\n  claudelytics sql \"SELECT model, SUM(cost) FROM usage GROUP BY model\"\n  claudelytics sql \"SELECT date, SUM(cost) AS cost FROM usage WHERE date >= '2024-01-01' GROUP BY date\"\n  claudelytics --json sql \"SELECT * FROM sessions LIMIT 5\""
+    )]
+    Sql {
+        #[arg(help = "SQL query to run")]
+        query: String,
+    },
+    #[command(about = "Audit costs: unpriced models and stored vs calculated costUSD")]
+    #[command(
+        long_about = "Check whether reported costs are complete\n\nLists every model in the usage records with its record count, tokens,\nreported cost, and whether it has a price; records of unpriced models count\nas $0. Days whose stored costUSD differs from the cost calculated from\ntokens by more than the tolerance are listed with the difference.\nRespects --since/--until, --model-filter, and --cost-mode.\n\nEXAMPLES:\n  claudelytics audit                      # Models, $0 records, mismatched days\n  claudelytics audit --tolerance 1        # Flag days more than 1% apart\n  claudelytics --since 20240101 audit     # Limit the date range (global flag)\n  claudelytics --json audit               # JSON output (global flag)"
+    )]
+    Audit {
+        #[arg(
+            long,
+            default_value = "5",
+            value_name = "PERCENT",
+            help = "Flag days whose stored and calculated costs differ by more than this"
+        )]
+        tolerance: f64,
+    },
+    #[command(about = "Diagnose problems with Claude Code usage data")]
+    #[command(
+        long_about = "Check the Claude Code data for problems that distort reports and\nprint suggested fixes\n\nCHECKS:\n  Claude directories  Configured directories exist and contain session files\n  Unparsable lines    Malformed JSONL lines that reports silently skip\n  Schema drift        Fields and record types this version does not know\n  Duplicate records   Usage lines replayed after a crash\n  Pricing             Pricing cache validity and models without a price\n\nEXAMPLES:\n  claudelytics doctor\n  claudelytics --json doctor"
+    )]
+    Doctor,
+    #[command(about = "Benchmark parsing of the local usage data")]
+    #[command(
+        long_about = "Measure how fast the local Claude Code data is parsed\n\nRuns several full parses and reports the median time, records per second,\nand the peak memory of the process (Linux only), then parses every project\ndirectory on its own to show which ones take longest. Each run is saved in\nthe cache directory and the next run shows the change against it.\nThe parse cache is off unless --cached is given, so every run reads all\nfiles; --since/--until and --model-filter are ignored.\n\nEXAMPLES:\n  claudelytics bench                # 3 runs, compared with the last bench\n  claudelytics bench --runs 10      # More runs for a steadier median\n  claudelytics bench --cached       # Measure warm runs with the parse cache\n  claudelytics bench --no-save      # Don't replace the saved run\n  claudelytics --json bench         # JSON output"
+    )]
+    Bench {
+        #[arg(long, default_value = "3", help = "Number of full parses to time")]
+        runs: usize,
+        #[arg(
+            long,
+            help = "Serve unchanged files from the parse cache",
+            long_help = "Serve unchanged files from the parse cache, measuring warm runs.\nThe cache is filled first, so the first timed run is warm as well."
+        )]
+        cached: bool,
+        #[arg(long, help = "Don't save this run as the baseline for the next bench")]
+        no_save: bool,
+        #[arg(long, default_value = "10", help = "Number of directories to list")]
+        top: usize,
     },
     #[command(about = "Show cost summary", hide = true)]
     #[command(
-        long_about = "Display cost analysis and summaries\n\nQuick access to cost information without full reports.\nUseful for monitoring expenses and budget tracking.\n\nCOST CALCULATION:\n  Based on Claude API pricing for input/output tokens\n  Includes cache creation and cache read tokens\n  Costs shown in USD\n\nEXAMPLES:\n  claudelytics cost                     # Total cost summary\n  claudelytics cost --today             # Today's cost only\n  claudelytics cost --date 20240315     # Specific date cost\n\nSHELL INTEGRATION:\n  alias ctoday='claudelytics cost --today'\n  alias ctotal='claudelytics cost'"
+        long_about = "Display cost analysis and summaries\n\nQuick access to cost information without full reports.\nUseful for monitoring expenses and budget tracking.\n\nCOST CALCULATION:\n  Based on Claude API pricing for input/output tokens\n  Includes cache creation and cache read tokens\n  Costs shown in USD\n\nEXAMPLES:\n  claudelytics cost                     # Total cost summary\n  claudelytics cost --today             # Today's cost only\n  claudelytics cost --date 20240315     # Specific date cost\n  claudelytics cost --week              # This week so far and its pace\n  claudelytics cost --month --budget 100  # \"$37.20 of $100.00 monthly budget (37%), on pace for $82.00\"\n\nWith --week or --month (or --budget), one summary line is printed. --month\nand --today default to the budget saved with `claudelytics budget set`.\n\nSHELL INTEGRATION:\n  alias ctoday='claudelytics cost --today'\n  alias ctotal='claudelytics cost'\n  PS1='$(claudelytics cost --month) '$PS1"
     )]
     Cost {
         #[arg(
@@ -452,13 +1449,32 @@ enum Commands {
         #[arg(
             long,
             help = "Show cost for specific date (YYYYMMDD)",
-            long_help = "Display cost for a specific date\nFormat: YYYYMMDD (e.g., 20240315 for March 15, 2024)\nShows: date, cost, tokens for that day only"
+            long_help = "Display cost for a specific date\nFormat: YYYYMMDD, YYYY-MM-DD or a relative date such as yesterday (e.g., 20240315 for March 15, 2024)\nShows: date, cost, tokens for that day only"
         )]
         date: Option<String>,
+        #[arg(
+            long,
+            conflicts_with_all = ["today", "date", "month"],
+            help = "Show this week's cost (Monday to Sunday) and its pace"
+        )]
+        week: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["today", "date"],
+            help = "Show this month's cost and its pace"
+        )]
+        month: bool,
+        #[arg(
+            long,
+            value_name = "USD",
+            conflicts_with = "date",
+            help = "Budget for the period (default: saved daily/monthly budget; implies --month without --today/--week)"
+        )]
+        budget: Option<f64>,
     },
     #[command(about = "Show billing blocks (5-hour usage blocks)")]
     #[command(
-        long_about = "Display usage organized by Claude's 5-hour billing blocks\n\nClaude uses 5-hour billing blocks for tracking usage. This command\nshows your usage patterns aligned with these actual billing periods.\n\nBILLING BLOCKS:\n  00:00-05:00 UTC  (Block 1)\n  05:00-10:00 UTC  (Block 2)\n  10:00-15:00 UTC  (Block 3)\n  15:00-20:00 UTC  (Block 4)\n  20:00-00:00 UTC  (Block 5)\n\nFEATURES:\n  - Shows usage within each 5-hour block\n  - Identifies peak usage periods\n  - Calculates average usage per block\n  - Displays usage patterns by time of day\n\nEXAMPLES:\n  claudelytics billing-blocks           # Show all billing blocks\n  claudelytics billing-blocks --today   # Today's blocks only\n  claudelytics billing-blocks --json    # JSON output\n  claudelytics --since 20240301 billing-blocks # From specific date"
+        long_about = "Display usage organized by Claude's 5-hour billing blocks\n\nClaude uses 5-hour billing blocks for tracking usage. This command\nshows your usage patterns aligned with these actual billing periods.\n\nBILLING BLOCKS:\n  00:00-05:00 UTC  (Block 1)\n  05:00-10:00 UTC  (Block 2)\n  10:00-15:00 UTC  (Block 3)\n  15:00-20:00 UTC  (Block 4)\n  20:00-00:00 UTC  (Block 5)\n\nWith --mode rolling, a block starts at the hour of the first activity and\nruns for the block duration, matching when Claude's usage limits reset.\nThe default mode and block duration can be set in the config file:\n  billing_blocks:\n    anchor: rolling\n    duration_hours: 5\n\nFEATURES:\n  - Shows usage within each 5-hour block\n  - Identifies peak usage periods\n  - Calculates average usage per block\n  - Displays usage patterns by time of day\n\nEXAMPLES:\n  claudelytics billing-blocks           # Show all billing blocks\n  claudelytics billing-blocks --today   # Today's blocks only\n  claudelytics billing-blocks --json    # JSON output\n  claudelytics billing-blocks --mode rolling # Blocks anchored to first activity\n  claudelytics billing-blocks --current # Burn-down of the in-progress block\n  claudelytics --since 20240301 billing-blocks # From specific date"
     )]
     BillingBlocks {
         #[arg(
@@ -473,10 +1489,23 @@ enum Commands {
             long_help = "Include summary statistics\nShows: peak block, average usage, patterns by time"
         )]
         summary: bool,
+        #[arg(
+            long,
+            value_enum,
+            help = "Block anchoring: fixed UTC windows or rolling from first activity",
+            long_help = "How billing blocks are positioned\n  fixed:   windows from midnight UTC (00:00, 05:00, ...)\n  rolling: start at the first activity and run for the block duration\nDefault: billing_blocks.anchor from the config file (fixed)"
+        )]
+        mode: Option<BillingBlockMode>,
+        #[arg(
+            long,
+            help = "Burn-down of the in-progress block against the quota",
+            long_help = "Focus on the in-progress block: tokens, messages, and cost used against the\nsubscription quota (`quota` config section), the burn rate, and when the quota\nruns out at that rate\nExample: claudelytics billing-blocks --current --mode rolling"
+        )]
+        current: bool,
     },
     #[command(about = "Analyze cache behavior and TTL efficiency")]
     #[command(
-        long_about = "Analyze per-turn cache behavior across sessions\n\nBreaks down cache writes by TTL bucket (5m vs 60m expiry), identifies\ncold start costs, and computes the cache balance point per session.\n\nEXAMPLES:\n  claudelytics cache                    # Cache analysis summary\n  claudelytics cache --top 20           # Show top 20 sessions\n  claudelytics --today cache            # Today only (global flag)\n  claudelytics --json cache             # JSON output (global flag)\n  claudelytics --project myproj cache   # Filter by project (global flag)"
+        long_about = "Analyze per-turn cache behavior across sessions\n\nBreaks down cache writes by TTL bucket (5m vs 60m expiry), identifies\ncold start costs, and computes the cache balance point per session.\n\nEXAMPLES:\n  claudelytics cache                    # Cache analysis summary\n  claudelytics cache --top 20           # Show top 20 sessions\n  claudelytics --today cache            # Today only (global flag)\n  claudelytics --json cache             # JSON output (global flag)\n  claudelytics --project myproj cache   # Filter by project (global flag)\n  claudelytics cache --clear            # Delete the incremental parse cache"
     )]
     Cache {
         #[arg(
@@ -518,6 +1547,184 @@ enum Commands {
 
         #[arg(long, value_name = "PCT", help = "Min churn rate % to show (e.g. 50)")]
         min_churn: Option<f64>,
+
+        #[arg(long, help = "Delete the incremental parse cache and exit")]
+        clear: bool,
+    },
+    #[command(about = "Show cache creation vs read efficiency and estimated savings")]
+    #[command(
+        long_about = "Report prompt cache efficiency per day, session and model\n\nCompares cache creation (write) tokens with cache read tokens, estimates the\nUSD saved versus paying the plain input price, and flags sessions whose cache\nwrites cost more than their reads saved.\n\nEXAMPLES:\n  claudelytics cache-stats                    # Cache efficiency summary\n  claudelytics cache-stats --top 20           # Show 20 days/sessions\n  claudelytics --since 20240101 cache-stats   # Limit the date range (global flag)\n  claudelytics --json cache-stats             # JSON output (global flag)"
+    )]
+    CacheStats {
+        #[arg(
+            long,
+            default_value = "10",
+            help = "Number of days and sessions to show"
+        )]
+        top: usize,
+
+        #[arg(
+            long,
+            default_value = "10000",
+            value_name = "TOKENS",
+            help = "Minimum cache creation tokens before a session can be flagged as wasteful"
+        )]
+        min_creation: u64,
+    },
+    #[command(about = "Track usage against Claude Pro/Max 5-hour quotas")]
+    #[command(
+        long_about = "Show how much of your subscription's 5-hour allowance is used\n\nBlocks are anchored to your first activity, like Claude's own limits. Shows\nthe current block's token, message, and cost consumption, the time until it\nresets, and percent-of-quota for previous blocks.\n\nThe built-in allowances are estimates. Set your plan and exact limits in the\nconfig file:\n  quota:\n    plan: max5\n    token_limit: 88000\n    message_limit: 1000\n\nEXAMPLES:\n  claudelytics quota                  # Current block vs plan allowance\n  claudelytics quota --plan max20     # Use the Max 20x allowance\n  claudelytics quota --history 20     # Show 20 previous blocks\n  claudelytics --json quota           # JSON output (global flag)"
+    )]
+    Quota {
+        #[arg(
+            long,
+            value_enum,
+            help = "Subscription plan (default: quota.plan from the config file)"
+        )]
+        plan: Option<SubscriptionPlan>,
+
+        #[arg(long, default_value = "10", help = "Number of previous blocks to show")]
+        history: usize,
+    },
+    #[command(about = "Print a one-line usage summary for status bars and prompts")]
+    #[command(
+        long_about = "Print today's cost, the quota left in the current 5-hour block, and the\nblock's burn rate on one line, for waybar, polybar, tmux or starship\n\nOnly the last day of logs is read, through the parse cache, so the command is\nfast enough to refresh every few seconds. The quota uses the plan and limits\nfrom the `quota` section of the config file. The output turns yellow (tmux)\nor gets the `warning` class (waybar) below 25% quota left, and red /\n`critical` below 10%.\n\nEXAMPLES:\n  claudelytics status                       # $12.34 · 62% · $4.10/h\n  claudelytics status --format waybar       # JSON for a waybar custom module\n  claudelytics status --format tmux         # For tmux status-right\n  claudelytics --json status                # All fields as JSON (global flag)"
+    )]
+    Status {
+        #[arg(
+            long,
+            value_enum,
+            default_value = "starship",
+            help = "Output style for the status bar"
+        )]
+        format: StatusFormat,
+    },
+    #[command(about = "Print the JSON Schema of the versioned (json-v1) reports")]
+    #[command(
+        long_about = "Print the JSON Schema (draft-07) describing `--format json-v1` output\n\nWithout an argument, prints one object with the schema of every report,\nkeyed by report name.\n\nEXAMPLES:\n  claudelytics schema                   # All report schemas\n  claudelytics schema daily > daily.schema.json\n  claudelytics --format json-v1 daily   # Output matching the daily schema"
+    )]
+    Schema {
+        #[arg(value_enum, help = "Report whose schema to print (default: all)")]
+        report: Option<SchemaReport>,
+    },
+    #[command(about = "Generate shell completion scripts")]
+    #[command(
+        long_about = "Print a completion script for your shell\n\nThe zsh and fish scripts also complete session IDs and project names for\n--session and --project from your Claude data.\n\nINSTALL:\n  bash:  claudelytics completions bash > ~/.local/share/bash-completion/completions/claudelytics\n  zsh:   claudelytics completions zsh > \"${fpath[1]}/_claudelytics\"\n  fish:  claudelytics completions fish > ~/.config/fish/completions/claudelytics.fish"
+    )]
+    Completions {
+        #[arg(value_enum, help = "Shell to generate the script for")]
+        shell: clap_complete::Shell,
+    },
+    /// List completion candidates (used by the completion scripts)
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(value_enum)]
+        target: CompletionTarget,
+    },
+    #[command(about = "Check spend against budget limits (exit code for scripts)")]
+    #[command(
+        long_about = "Compare today's and this month's spend with budget limits and exit with\na status code, for cron jobs and shell prompts\n\nEXIT CODES:\n  0  All periods below the warning threshold\n  1  A period reached the warning threshold (default: budget alert threshold, 80%)\n  2  A limit was reached or exceeded\n\nLimits default to those saved with `claudelytics budget set`.\n\nEXAMPLES:\n  claudelytics check --max-daily-cost 25 --max-monthly-cost 400\n  claudelytics check --quiet || notify-send 'Claude budget'\n  claudelytics --json check            # JSON output (global flag)"
+    )]
+    Check {
+        #[arg(
+            long,
+            value_name = "USD",
+            help = "Daily cost limit (default: saved daily budget)"
+        )]
+        max_daily_cost: Option<f64>,
+
+        #[arg(
+            long,
+            value_name = "USD",
+            help = "Monthly cost limit (default: saved monthly budget)"
+        )]
+        max_monthly_cost: Option<f64>,
+
+        #[arg(
+            long,
+            value_name = "RATIO",
+            help = "Fraction of a limit that counts as a warning (0.0-1.0)"
+        )]
+        warn_threshold: Option<f64>,
+
+        #[arg(short, long, help = "Print nothing; only set the exit code")]
+        quiet: bool,
+    },
+    #[command(about = "List the most expensive conversations")]
+    #[command(
+        long_about = "List the most expensive conversations across all sessions\n\nSessions are split into conversations by message thread, so each task in a\nlong-running session is ranked on its own. Each entry shows the first user\nprompt, the dominant model, token count, and estimated cost.\n\nEXAMPLES:\n  claudelytics top                          # Top 20 conversations by cost\n  claudelytics top --by tokens --limit 50   # Top 50 by total tokens\n  claudelytics --since 20240101 top         # Limit the date range (global flag)\n  claudelytics --json top                   # JSON output (global flag)"
+    )]
+    Top {
+        #[arg(long, default_value = "cost", help = "Metric to rank conversations by")]
+        by: TopMetric,
+
+        #[arg(long, default_value = "20", help = "Number of conversations to show")]
+        limit: usize,
+    },
+    #[command(about = "Aggregate tool calls (Bash, Edit, Read, ...) across sessions")]
+    #[command(
+        long_about = "Aggregate the tool calls made in all conversations by tool name\n\nFor each tool shows the number of calls, how many failed (tool results flagged\nas errors), and the tokens and cost of the assistant responses that issued the\ncalls, split evenly when a response called several tools.\n\nEXAMPLES:\n  claudelytics tools                        # Calls, failures and cost per tool\n  claudelytics tools --by-project           # Per-project breakdown for each tool\n  claudelytics tools --project myapp        # Only projects matching 'myapp'\n  claudelytics --since 20240101 tools       # Limit the date range (global flag)\n  claudelytics --json tools                 # JSON output (global flag)"
+    )]
+    Tools {
+        #[arg(long, help = "Show the per-project breakdown for each tool")]
+        by_project: bool,
+        #[arg(long, help = "Only include projects whose name contains this")]
+        project: Option<String>,
+        #[arg(long, default_value = "20", help = "Number of tools to show")]
+        top: usize,
+    },
+    #[command(about = "Attribute cost to ticket IDs found in conversations")]
+    #[command(
+        long_about = "Attribute cost to ticket IDs (such as PROJ-1234) found in conversations\n\nTicket IDs are extracted from each conversation's summary, user prompts, and\ngit branch. A response is attributed to the tickets in the branch it was made\non; otherwise its cost is split evenly over the tickets mentioned in the\nconversation. Configure the patterns in the `tickets` section of the config\nfile; the default matches JIRA-style keys.\n\nEXAMPLES:\n  claudelytics tickets                          # Cost per ticket\n  claudelytics tickets --pattern '#(\\d+)'       # GitHub issue numbers instead\n  claudelytics tickets --project api            # Only projects matching 'api'\n  claudelytics tickets -o tickets.csv           # Export as CSV\n  claudelytics --since 20240101 tickets         # Limit the date range (global flag)\n  claudelytics --json tickets                   # JSON output (global flag)"
+    )]
+    Tickets {
+        #[arg(
+            long,
+            value_name = "REGEX",
+            help = "Ticket ID pattern, instead of the configured ones (repeatable)",
+            long_help = "Regular expression matching ticket IDs, used instead of the patterns in the\n`tickets` config section; can be given several times\nWhen the pattern has a capture group, the first group is the ID"
+        )]
+        pattern: Vec<String>,
+        #[arg(long, help = "Only include projects whose name contains this")]
+        project: Option<String>,
+        #[arg(long, default_value = "20", help = "Number of tickets to show")]
+        limit: usize,
+        #[arg(long, help = "Print the report as CSV")]
+        csv: bool,
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "Write the report to a CSV file (JSON with --json)"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Search conversation content")]
+    #[command(
+        long_about = "Search messages, thinking, tool calls, and tool results in all conversations\n\nConversations are indexed under the cache directory on first use; later\nsearches only re-read new or changed files. Plain queries match messages\ncontaining every word (as a word prefix, case-insensitive); --regex scans\nall messages with a regular expression. Matches are shown newest first with a\nhighlighted snippet.\n\nEXAMPLES:\n  claudelytics search \"borrow checker\"        # Messages containing both words\n  claudelytics search --regex 'TODO|FIXME'     # Regular expression\n  claudelytics search parser --project api     # Only projects matching 'api'\n  claudelytics search deploy --model-filter opus\n  claudelytics --since 20240101 search error   # Limit the date range (global flag)\n  claudelytics --json search panic             # JSON output (global flag)"
+    )]
+    Search {
+        #[arg(help = "Words to search for (or a regex with --regex)")]
+        query: String,
+        #[arg(
+            long,
+            help = "Treat the query as a regular expression",
+            long_help = "Treat the query as a case-insensitive regular expression\nRegex searches scan every indexed message instead of using the word index"
+        )]
+        regex: bool,
+        #[arg(long, help = "Only include projects whose name contains this")]
+        project: Option<String>,
+        #[arg(
+            long,
+            value_name = "MODEL",
+            help = "Only match messages from this model (opus, sonnet, haiku)",
+            long_help = "Only match assistant messages from matching models; same as the global --model-filter\nUser messages have no model and are left out when a filter is set"
+        )]
+        model_filter: Option<String>,
+        #[arg(long, default_value = "20", help = "Number of matches to show")]
+        limit: usize,
+        #[arg(long, help = "Rebuild the search index from scratch")]
+        rebuild: bool,
     },
     #[command(about = "Start Model Context Protocol (MCP) server", hide = true)]
     #[command(
@@ -527,9 +1734,17 @@ enum Commands {
         #[arg(
             long,
             help = "Start HTTP server on specified port",
-            long_help = "Start MCP server using HTTP with Server-Sent Events\nAllows remote connections from MCP clients"
+            long_help = "Start MCP server using HTTP with Server-Sent Events\nClients connect to /sse and post JSON-RPC messages to the announced endpoint\nAllows remote connections from MCP clients"
         )]
         http: Option<u16>,
+        #[arg(
+            long,
+            value_name = "ADDR",
+            requires = "http",
+            help = "Address to bind the HTTP server to",
+            long_help = "Address to bind the HTTP server to\nDefault: mcp.http_bind_address from the config file (127.0.0.1)\nExample: --bind 0.0.0.0 to accept connections from other machines"
+        )]
+        bind: Option<String>,
         #[arg(
             long,
             help = "List available MCP tools",
@@ -543,6 +1758,26 @@ enum Commands {
         )]
         list_resources: bool,
     },
+    #[command(about = "Serve reports as JSON over a REST API")]
+    #[command(
+        long_about = "Start an HTTP server that answers report queries with JSON\n\nEndpoints (GET):\n  /daily        Daily usage report\n  /sessions     Session usage report\n  /projections  Cost projections and budget forecast\n  /blocks       5-hour billing blocks\n\nQuery parameters mirror the CLI flags: since, until (YYYYMMDD), today,\nmodel-filter, sort-by, sort-order for /daily and /sessions, days,\ntoken-limit, cost-limit for /projections, and mode (fixed, rolling) for\n/blocks. Add format=json-v1 to /daily, /sessions or /blocks for the\nversioned schema. Every request reads the current usage data.\n\nEXAMPLES:\n  claudelytics serve                          # Listen on 127.0.0.1:8080\n  claudelytics serve --port 9000 --bind 0.0.0.0\n  curl 'localhost:8080/daily?since=20240101&sort-by=cost'\n  curl 'localhost:8080/projections?days=7&cost-limit=200'"
+    )]
+    Serve {
+        #[arg(
+            long,
+            default_value = "8080",
+            help = "Port to listen on",
+            long_help = "TCP port for the API server\nDefault: 8080"
+        )]
+        port: u16,
+        #[arg(
+            long,
+            value_name = "ADDR",
+            help = "Address to bind the server to",
+            long_help = "Address to bind the API server to\nDefault: mcp.http_bind_address from the config file (127.0.0.1)\nExample: --bind 0.0.0.0 to accept connections from other machines"
+        )]
+        bind: Option<String>,
+    },
     #[command(about = "Debug resume state", hide = true)]
     #[command(long_about = "Debug command to show TUI session state information")]
     DebugState,
@@ -569,13 +1804,20 @@ enum Commands {
         #[arg(
             long,
             help = "Update the pricing cache",
-            long_help = "Force update the pricing cache with latest data (currently uses fallback data)"
+            long_help = "Fetch up-to-date model pricing and store it in the cache\nDefault source: LiteLLM's model_prices_and_context_window.json\nUses ETag conditional requests; keeps the existing cache if the fetch fails"
         )]
         update: bool,
+        #[arg(
+            long,
+            value_name = "URL",
+            requires = "update",
+            help = "Pricing source URL or local JSON file for --update"
+        )]
+        source: Option<String>,
     },
     #[command(about = "Show session blocks (configurable time windows)", hide = true)]
     #[command(
-        long_about = "Analyze usage in configurable session blocks\n\nSession blocks provide flexible time-based analysis similar to billing blocks\nbut with customizable durations. Default is 8-hour blocks.\n\nFor real-time monitoring, use `claudelytics live`.\n\nFEATURES:\n  - Configurable block duration (default: 8 hours)\n  - Active session tracking with burn rate\n  - Usage projections based on current activity\n  - Time to limit calculations\n\nEXAMPLES:\n  claudelytics blocks                  # Show all session blocks\n  claudelytics blocks --active         # Show only active sessions\n  claudelytics blocks --length 4       # Use 4-hour blocks\n  claudelytics blocks --recent         # Show last 30 days"
+        long_about = "Analyze usage in configurable session blocks\n\nSession blocks provide flexible time-based analysis similar to billing blocks\nbut with customizable durations. Default is 8-hour blocks.\n\nFor real-time monitoring, use `claudelytics live`.\n\nFEATURES:\n  - Configurable block duration (default: 8 hours)\n  - Active session tracking with burn rate\n  - Usage projections based on current activity\n  - Time to limit calculations\n\nEXAMPLES:\n  claudelytics blocks                  # Show all session blocks\n  claudelytics blocks --active         # Show only active sessions\n  claudelytics blocks --length 4       # Use 4-hour blocks\n  claudelytics blocks --recent         # Show last 30 days\n  claudelytics blocks --live --windows 15m,1h  # Live burn rates per model"
     )]
     Blocks {
         #[arg(
@@ -606,13 +1848,135 @@ enum Commands {
         #[arg(
             long,
             help = "Cost limit for warnings",
-            long_help = "Set cost limit (USD) for burn rate warnings"
+            long_help = "Set cost limit (USD) for burn rate warnings\nDefault: daily limit saved with `claudelytics budget set`"
         )]
         cost_limit: Option<f64>,
+        #[arg(
+            long,
+            help = "Open the live dashboard",
+            long_help = "Monitor usage in the live dashboard instead of listing blocks\nUses --token-limit, --cost-limit and --windows"
+        )]
+        live: bool,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "WINDOWS",
+            help = "Burn rate lookback windows for --live, e.g. 15m,1h,6h",
+            long_help = "Lookback windows for burn rates in --live mode\nUnits: m (minutes), h (hours), d (days)\nDefault: burn_rate.windows from the config file (15m,1h,6h)"
+        )]
+        windows: Option<Vec<String>>,
+    },
+    #[command(about = "Send usage to an OpenTelemetry collector (OTLP/HTTP)")]
+    #[command(
+        long_about = "Replay usage records to an OpenTelemetry collector over OTLP/HTTP (JSON)\n\nSends one `claude_code.session` span per session to /v1/traces, and hourly\ndelta sums per model and project to /v1/metrics:\n  claude_code.token.usage  Tokens, with a `type` attribute\n                           (input, output, cache_creation, cache_read)\n  claude_code.cost.usage   Cost in USD\n\nThe endpoint and headers default to the standard OTEL_EXPORTER_OTLP_ENDPOINT\nand OTEL_EXPORTER_OTLP_HEADERS environment variables. Every run sends all\nusage in the date range, so use --since/--until for incremental exports.\n\nEXAMPLES:\n  claudelytics otel-export --endpoint http://collector:4318\n  claudelytics --today otel-export --header 'authorization=Bearer TOKEN'\n  claudelytics otel-export --no-traces             # Metrics only\n  claudelytics otel-export --dry-run | jq .url     # Print the payloads instead"
+    )]
+    OtelExport {
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "OTLP/HTTP base URL (default: $OTEL_EXPORTER_OTLP_ENDPOINT or http://localhost:4318)",
+            long_help = "Base URL of the collector's OTLP/HTTP receiver\n/v1/traces and /v1/metrics are appended\nDefault: $OTEL_EXPORTER_OTLP_ENDPOINT, then http://localhost:4318"
+        )]
+        endpoint: Option<String>,
+        #[arg(
+            long = "header",
+            value_name = "KEY=VALUE",
+            help = "Extra HTTP header, e.g. for authentication (repeatable)"
+        )]
+        headers: Vec<String>,
+        #[arg(long, help = "Send metrics only")]
+        no_traces: bool,
+        #[arg(long, conflicts_with = "no_traces", help = "Send traces only")]
+        no_metrics: bool,
+        #[arg(
+            long,
+            help = "Print the payloads as JSON lines instead of sending them"
+        )]
+        dry_run: bool,
+    },
+    #[command(about = "Simulate savings from switching models")]
+    #[command(
+        long_about = "Reprice historical usage as if some models had been replaced by another\n\nEach --map FROM=TO moves the usage of models matching FROM (a model name,\nalias, or family, as in --model-filter) to the TO model's per-token rates.\nA family as TO means its newest model. Token counts stay as recorded, so the\nresult does not account for a different model needing more or fewer tokens.\nReports actual and simulated cost per month and per remapped model.\n\nEXAMPLES:\n  claudelytics simulate --map opus=sonnet            # All Opus usage at Sonnet rates\n  claudelytics simulate --map opus=sonnet-4 --map sonnet=haiku\n  claudelytics --since 20250101 simulate --map opus=sonnet\n  claudelytics --json simulate --map opus=sonnet     # JSON output"
+    )]
+    Simulate {
+        #[arg(
+            long = "map",
+            value_name = "FROM=TO",
+            required = true,
+            help = "Reprice usage of FROM at the rates of TO (repeatable)",
+            long_help = "Reprice the usage of models matching FROM at the rates of TO\nFROM: model name, alias, or family (opus, sonnet, haiku)\nTO: model name, alias, or family (newest model of the family)\nThe first matching rule wins when several are given"
+        )]
+        mappings: Vec<String>,
+    },
+    #[command(about = "Plan a monthly budget from past daily costs")]
+    #[command(
+        long_about = "Check a monthly budget against how you have been spending\n\nShows the daily allowance that spreads the budget left in the current\nbilling cycle evenly over its remaining days, next to the average daily cost\nof the last 30 days. The rest of the cycle is then simulated many times by\ndrawing each day's cost from those 30 days (idle days count as zero), giving\nthe probability of ending the cycle over budget, the likely cycle total, and\nthe date the budget would likely run out.\n\nThe billing cycle starts on the day set with `budget set --cycle-start-day`.\n\nEXAMPLES:\n  claudelytics plan --monthly-budget 300   # Plan against $300 per cycle\n  claudelytics plan                        # Use the saved monthly budget\n  claudelytics --json plan --monthly-budget 300"
+    )]
+    Plan {
+        #[arg(
+            long,
+            value_name = "USD",
+            help = "Monthly budget (default: saved monthly budget)"
+        )]
+        monthly_budget: Option<f64>,
+        #[arg(
+            long,
+            default_value = "10000",
+            help = "Number of simulated billing cycles"
+        )]
+        simulations: usize,
+    },
+    #[command(about = "Claude usage and cost per git commit of a repository")]
+    #[command(
+        long_about = "Line up a project's Claude usage with the commits of its git repository\n\nEach commit gets the usage between the previous commit and itself (its commit\nwindow): requests, sessions, tokens, and cost spent before it was made, plus a\nrunning total. Usage after the newest commit is shown as uncommitted work. With\n--base, only the commits of a branch are listed, which answers \"how much Claude\nwent into this PR?\". History follows first parents from --rev.\n\nSessions are matched through Claude's project directory for the repository's\nworking directory; use --project when the sessions ran elsewhere.\n\nRequires a build with `--features git`.\n\nEXAMPLES:\n  claudelytics git-correlate                     # Repository in the current directory\n  claudelytics git-correlate --base main         # Commits of the current branch\n  claudelytics git-correlate --repo ~/src/api --rev v2.0 --base v1.0\n  claudelytics --since 20250601 --json git-correlate"
+    )]
+    GitCorrelate {
+        #[arg(
+            long,
+            value_name = "PATH",
+            default_value = ".",
+            help = "Git repository (or a directory inside it)"
+        )]
+        repo: PathBuf,
+        #[arg(
+            short = 'p',
+            long,
+            help = "Claude project directory to match (default: from the working directory)",
+            long_help = "Claude project directory name whose sessions are matched, e.g. -home-me-api\nDefault: the repository's working directory with every non-alphanumeric\ncharacter replaced by '-', as Claude Code names project directories"
+        )]
+        project: Option<String>,
+        #[arg(long, default_value = "HEAD", help = "Newest commit to report")]
+        rev: String,
+        #[arg(
+            long,
+            value_name = "REV",
+            help = "Leave out commits reachable from REV, e.g. main for a branch"
+        )]
+        base: Option<String>,
+    },
+    #[command(about = "Combine team members' exported usage into one report")]
+    #[command(
+        long_about = "Merge team bundles from several users into combined reports\n\nEach member exports a bundle with `claudelytics export --team-bundle`.\nBundles hold usage per day, session, and model (token counts and costs,\nno conversation content). The report shows a per-user summary with each\nuser's share of the cost, daily cost per user, and totals by model and\nproject. Bundles with the same user name are added up as one user.\n--since/--until filter the bundled days.\n\nEXAMPLES:\n  claudelytics export --team-bundle --user alice   # On each member's machine\n  claudelytics team --inputs alice.json bob.json carol.json\n  claudelytics team --inputs *.json --anonymize      # Hide user and project names\n  claudelytics --since 20250601 team --inputs *.json\n  claudelytics --json team --inputs *.json          # JSON output"
+    )]
+    Team {
+        #[arg(
+            long,
+            value_name = "PATH",
+            num_args = 1..,
+            required = true,
+            help = "Team bundles to merge"
+        )]
+        inputs: Vec<PathBuf>,
+        #[arg(
+            long,
+            help = "Replace user and project names with user-N and project-N",
+            long_help = "Replace user names with user-1, user-2, ... and project directories with\nproject-1, project-2, ... in order of appearance, for sharing the report"
+        )]
+        anonymize: bool,
     },
     #[command(about = "Show usage projections and forecasts", hide = true)]
     #[command(
-        long_about = "Project future usage based on historical patterns\n\nProjections analyze your usage history to forecast future token consumption\nand costs. Includes trend analysis, growth rates, and limit predictions.\n\nFEATURES:\n  - Daily, weekly, and monthly averages\n  - Trend detection (increasing/decreasing/stable)\n  - Confidence intervals for projections\n  - Time to limit calculations\n  - Cost estimates for future periods\n\nEXAMPLES:\n  claudelytics projections             # Show 30-day projection\n  claudelytics projections --days 90   # Project 90 days ahead\n  claudelytics projections --json      # JSON output for scripts"
+        long_about = "Project future usage based on historical patterns\n\nProjections analyze your usage history to forecast future token consumption\nand costs. Includes trend analysis, growth rates, and limit predictions.\n\nFEATURES:\n  - Daily, weekly, and monthly averages\n  - Trend detection (increasing/decreasing/stable)\n  - Confidence intervals for projections\n  - Time to limit calculations\n  - Cost estimates for future periods\n  - Billing cycle spend, end-of-cycle forecast and projected overage\n  - Weekday-aware forecast (separate day-of-week baselines) and a backtest\n    of both forecasts on the last week (MAPE)\n\nThe billing cycle starts on the first of the month unless configured with\n`claudelytics budget set --cycle-start-day <DAY>`.\n\nEXAMPLES:\n  claudelytics projections             # Show 30-day projection\n  claudelytics projections --days 90   # Project 90 days ahead\n  claudelytics projections --cost-limit 200  # Forecast against a $200 cycle limit\n  claudelytics projections --backtest 30     # Accuracy of past forecasts over 30 days\n  claudelytics projections --json      # JSON output for scripts"
     )]
     Projections {
         #[arg(
@@ -631,9 +1995,16 @@ enum Commands {
         #[arg(
             long,
             help = "Cost limit for projections",
-            long_help = "Set cost limit (USD) to calculate when it will be reached"
+            long_help = "Set cost limit (USD) per billing cycle to calculate when it will be reached\nand the projected overage (default: saved monthly budget)"
         )]
         cost_limit: Option<f64>,
+        #[arg(
+            long,
+            value_name = "DAYS",
+            help = "Replay the last N days to measure forecast accuracy",
+            long_help = "Replay the last N full days: forecast each day from the history before it,\nas the projection would have that morning, and compare with what was spent.\nReports MAE, RMSE, MAPE and bias of the naive and weekday-aware forecasts"
+        )]
+        backtest: Option<i64>,
         #[arg(
             long,
             help = "JSON output",
@@ -643,7 +2014,7 @@ enum Commands {
     },
     #[command(about = "Advanced session analytics", hide = true)]
     #[command(
-        long_about = "Analyze session patterns and behaviors in depth\n\nProvides detailed insights into:\n  - Time of day usage patterns\n  - Day of week trends\n  - Session duration analysis\n  - Usage frequency and streaks\n  - Cost efficiency metrics\n\nEXAMPLES:\n  claudelytics analytics              # Show all analytics\n  claudelytics analytics --time-of-day # Time patterns only\n  claudelytics analytics --efficiency  # Cost efficiency analysis"
+        long_about = "Analyze session patterns and behaviors in depth\n\nProvides detailed insights into:\n  - Time of day usage patterns\n  - Day of week trends\n  - Session duration analysis\n  - Usage frequency and streaks\n  - Cost efficiency metrics\n  - Efficiency score leaderboard\n  - Extended thinking token share\n\nThe efficiency score (0-100) of a session weighs output tokens per dollar\n(40%), cache hit ratio (40%), and the share of output that was not extended\nthinking (20%).\n\nEXAMPLES:\n  claudelytics analytics              # Show all analytics\n  claudelytics analytics --time-of-day # Time patterns only\n  claudelytics analytics --efficiency  # Cost efficiency analysis\n  claudelytics analytics --leaderboard # Best and worst sessions by score, with tips\n  claudelytics --json analytics --leaderboard --top 10\n  claudelytics analytics --thinking    # Thinking token share by model, day, and session"
     )]
     Analytics {
         #[arg(
@@ -682,41 +2053,65 @@ enum Commands {
             long_help = "Sessions above this cost will be highlighted",
             default_value = "1.0"
         )]
-        threshold: f64,
+        threshold: f64,
+        #[arg(
+            long,
+            help = "Rank sessions by efficiency score",
+            long_help = "List the best and worst sessions by efficiency score, with tips for the worst\nSessions with fewer than 3 messages are not ranked"
+        )]
+        leaderboard: bool,
+        #[arg(
+            long,
+            help = "Show extended thinking token share",
+            long_help = "Break down the estimated extended thinking tokens (billed as output) by model, day, and session"
+        )]
+        thinking: bool,
+        #[arg(
+            long,
+            default_value = "5",
+            help = "Sessions in each half of the leaderboard, or listed by --thinking"
+        )]
+        top: usize,
     },
     #[command(
         about = "Real-time analytics with burn rates and projections",
         hide = true
     )]
     #[command(
-        long_about = "Show comprehensive real-time analytics including burn rates and budget projections\n\nProvides detailed analytics on:\n  - Token and cost burn rates (per minute/hour/day)\n  - Budget projections and time to limits\n  - Session analytics and efficiency trends\n  - Usage alerts and recommendations\n\nFEATURES:\n  - Multi-window burn rate analysis (1hr, 3hr, 24hr)\n  - Budget utilization and projections\n  - Peak usage detection\n  - Efficiency scoring\n  - Smart alerts for unusual patterns\n\nEXAMPLES:\n  claudelytics realtime                # Show all real-time analytics\n  claudelytics realtime --json         # Output as JSON\n  claudelytics realtime --daily-limit 50  # Set $50 daily budget\n  claudelytics realtime --monthly-limit 1000  # Set $1000 monthly budget"
+        long_about = "Show comprehensive real-time analytics including burn rates and budget projections\n\nProvides detailed analytics on:\n  - Token and cost burn rates (per minute/hour/day)\n  - Budget projections and time to limits\n  - Session analytics and efficiency trends\n  - Concurrent sessions over the last 24 hours, per project\n  - Usage alerts and recommendations\n\nFEATURES:\n  - Multi-window burn rate analysis (1hr, 3hr, 24hr)\n  - Budget utilization and projections\n  - Peak usage detection\n  - Efficiency scoring\n  - Smart alerts for unusual patterns\n\nEXAMPLES:\n  claudelytics realtime                # Show all real-time analytics\n  claudelytics realtime --json         # Output as JSON\n  claudelytics realtime --daily-limit 50  # Set $50 daily budget\n  claudelytics realtime --monthly-limit 1000  # Set $1000 monthly budget\n  claudelytics realtime --max-concurrent 2    # Flag more than 2 parallel sessions"
     )]
     Realtime {
         #[arg(
             long,
             help = "Daily budget limit (USD)",
-            long_help = "Set daily budget limit for projections and alerts"
+            long_help = "Set daily budget limit for projections and alerts\nDefault: limit saved with `claudelytics budget set`"
         )]
         daily_limit: Option<f64>,
         #[arg(
             long,
             help = "Monthly budget limit (USD)",
-            long_help = "Set monthly budget limit for projections and alerts"
+            long_help = "Set monthly budget limit for projections and alerts\nDefault: limit saved with `claudelytics budget set`"
         )]
         monthly_limit: Option<f64>,
         #[arg(
             long,
             help = "Yearly budget limit (USD)",
-            long_help = "Set yearly budget limit for projections and alerts"
+            long_help = "Set yearly budget limit for projections and alerts\nDefault: limit saved with `claudelytics budget set`"
         )]
         yearly_limit: Option<f64>,
         #[arg(
             long,
             help = "Alert threshold percentage",
-            long_help = "Percentage of budget to trigger alerts (0.0-1.0, default: 0.8)",
-            default_value = "0.8"
+            long_help = "Percentage of budget to trigger alerts (0.0-1.0, default: 0.8 or the saved budget threshold)"
+        )]
+        alert_threshold: Option<f64>,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Flag periods with more than N concurrent sessions",
+            long_help = "Flag 10-minute intervals in which more than N sessions were consuming tokens\n(default: alerts.max_concurrent_sessions in the config, 3)"
         )]
-        alert_threshold: f64,
+        max_concurrent: Option<usize>,
         #[arg(
             long,
             help = "Output as JSON",
@@ -726,7 +2121,7 @@ enum Commands {
     },
     #[command(about = "Live dashboard for real-time monitoring", hide = true)]
     #[command(
-        long_about = "Launch live dashboard for real-time token usage monitoring\n\nProvides a continuously updating view of:\n  - Real-time token burn rate (tokens/minute, tokens/hour)\n  - Active session progress tracking\n  - Cost projections based on current usage rate\n  - Estimated time to reach daily/monthly limits\n  - Auto-refresh display every 5 seconds\n\nFEATURES:\n  - Real-time burn rate calculation\n  - Active session monitoring\n  - Cost accumulation tracking\n  - Limit warnings and alerts\n  - Configurable refresh interval\n\nEXAMPLES:\n  claudelytics live                    # Start live dashboard\n  claudelytics live --refresh 10       # Update every 10 seconds\n  claudelytics live --token-limit 1000000  # Set token limit\n  claudelytics live --cost-limit 50    # Set daily cost limit ($50)"
+        long_about = "Launch live dashboard for real-time token usage monitoring\n\nProvides a continuously updating view of:\n  - Real-time token burn rate (tokens/minute, tokens/hour)\n  - Active session progress tracking\n  - Cost projections based on current usage rate\n  - Estimated time to reach daily/monthly limits\n  - Auto-refresh display every 5 seconds\n\nFEATURES:\n  - Real-time burn rate calculation\n  - Active session monitoring\n  - Cost accumulation tracking\n  - Limit warnings and alerts\n  - Configurable refresh interval\n\nEXAMPLES:\n  claudelytics live                    # Start live dashboard\n  claudelytics live --refresh 10       # Update every 10 seconds\n  claudelytics live --token-limit 1000000  # Set token limit\n  claudelytics live --cost-limit 50    # Set daily cost limit ($50)\n  claudelytics live --windows 5m,1h,1d # Burn rates over custom windows"
     )]
     Live {
         #[arg(
@@ -745,7 +2140,7 @@ enum Commands {
         #[arg(
             long,
             help = "Daily cost limit for warnings",
-            long_help = "Set daily cost limit (USD) to show time remaining"
+            long_help = "Set daily cost limit (USD) to show time remaining\nDefault: daily limit saved with `claudelytics budget set`"
         )]
         cost_limit: Option<f64>,
         #[arg(
@@ -762,10 +2157,18 @@ enum Commands {
             default_value = "true"
         )]
         enable_alerts: bool,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "WINDOWS",
+            help = "Burn rate lookback windows, e.g. 15m,1h,6h",
+            long_help = "Lookback windows for burn rates, overall and per model family\nUnits: m (minutes), h (hours), d (days)\nDefault: burn_rate.windows from the config file (15m,1h,6h)"
+        )]
+        windows: Option<Vec<String>>,
     },
     #[command(about = "Display conversation content")]
     #[command(
-        long_about = "Display full conversation content from Claude sessions\n\nProvides detailed view of conversations including messages, thinking blocks,\ntool usage, and token usage. Supports multiple output formats and filtering.\n\nFEATURES:\n  - Full conversation thread display with parent/child relationships\n  - Syntax highlighting for code blocks\n  - Thinking block extraction and display\n  - Tool usage tracking\n  - Multiple export formats (terminal, markdown, JSON)\n  - Search and filter capabilities\n\nEXAMPLES:\n  claudelytics conversation --session abc123  # Show specific session\n  claudelytics conversation --project myproj  # Filter by project\n  claudelytics conversation --search \"error\" # Search in conversations\n  claudelytics conversation --export markdown # Export as markdown\n  claudelytics conversation --recent          # Show recent conversations"
+        long_about = "Display full conversation content from Claude sessions\n\nProvides detailed view of conversations including messages, thinking blocks,\ntool usage, and token usage. Supports multiple output formats and filtering.\n\nFEATURES:\n  - Full conversation thread display with parent/child relationships\n  - Syntax highlighting for code blocks\n  - Thinking block extraction and display\n  - Tool usage tracking\n  - Multiple export formats (terminal, markdown, JSON, HTML)\n  - Search and filter capabilities\n\nEXAMPLES:\n  claudelytics conversation --session abc123  # Show specific session\n  claudelytics conversation --project myproj  # Filter by project\n  claudelytics conversation --search \"error\" # Search in conversations\n  claudelytics conversation --export markdown # Export as markdown\n  claudelytics conversation --session abc123 --export html -o chat.html\n  claudelytics conversation --session abc123 --export markdown --redact # Mask secrets\n  claudelytics --since 20240101 conversation --export markdown --split-files -o chats/\n  claudelytics conversation --recent          # Show recent conversations"
     )]
     Conversation {
         #[arg(
@@ -792,7 +2195,7 @@ enum Commands {
             short = 'e',
             long,
             help = "Export format",
-            long_help = "Export conversation in specified format\nOptions: markdown, json, txt\nDefault: terminal display"
+            long_help = "Export conversation in specified format\nOptions: markdown, json, txt, html\nhtml: self-contained page with collapsible threads, highlighted code,\nthinking blocks and tool calls that expand on click\nDefault: terminal display"
         )]
         export: Option<String>,
         #[arg(
@@ -802,6 +2205,13 @@ enum Commands {
             long_help = "Path to save exported conversation\nIf not specified, outputs to stdout"
         )]
         output: Option<PathBuf>,
+        #[arg(
+            long,
+            requires_all = ["export", "output"],
+            help = "Write one file per conversation into the --output directory",
+            long_help = "Write every matching conversation to its own file in the --output directory,\nnamed project__session__date (e.g. -home-me-app__abc123__2024-01-15.md),\nplus an index.md linking to each file with its summary, messages, and cost\nRespects --project, --search, --recent, --since, --until, and --today"
+        )]
+        split_files: bool,
         #[arg(
             long,
             help = "Show only recent conversations",
@@ -836,6 +2246,12 @@ enum Commands {
             long_help = "List all available conversations instead of displaying content"
         )]
         list: bool,
+        #[arg(
+            long,
+            help = "Mask secrets such as API keys, tokens, and emails",
+            long_help = "Mask secrets in the displayed or exported conversation\nBuilt-in detectors: API keys (sk-...), AWS access and secret keys, bearer\ntokens, email addresses, and home directory paths (shown as ~)\nAdd patterns in the `redaction` section of the config file\nThe number of masked items is reported on stderr"
+        )]
+        redact: bool,
     },
     #[command(
         about = "View conversation content (alias for conversation)",
@@ -874,7 +2290,7 @@ enum Commands {
             short = 'e',
             long,
             help = "Export format",
-            long_help = "Export conversation in specified format\nOptions: markdown, json, txt"
+            long_help = "Export conversation in specified format\nOptions: markdown, json, txt, html"
         )]
         export: Option<String>,
         #[arg(
@@ -884,10 +2300,12 @@ enum Commands {
             long_help = "Path to save exported conversation"
         )]
         output: Option<PathBuf>,
+        #[arg(long, help = "Mask secrets such as API keys, tokens, and emails")]
+        redact: bool,
     },
     #[command(about = "Inspect session details and metadata", hide = true)]
     #[command(
-        long_about = "Inspect detailed session information including metadata and statistics\n\nProvides comprehensive information about sessions including:\n  - Session metadata (ID, project, timestamps)\n  - Token usage breakdown by model\n  - Cost analysis and efficiency metrics\n  - Conversation count and structure\n  - Activity timeline\n\nEXAMPLES:\n  claudelytics inspect abc123           # Inspect specific session\n  claudelytics inspect --project myproj # Inspect sessions from project\n  claudelytics inspect --recent         # Inspect recent sessions\n  claudelytics inspect --json           # Output as JSON"
+        long_about = "Inspect detailed session information including metadata and statistics\n\nProvides comprehensive information about sessions including:\n  - Session metadata (ID, project, timestamps)\n  - Token usage breakdown by model\n  - Cost analysis and efficiency metrics\n  - Conversation count and structure\n  - Activity timeline\n\nEXAMPLES:\n  claudelytics inspect abc123           # Inspect specific session\n  claudelytics inspect --project myproj # Inspect sessions from project\n  claudelytics inspect --recent         # Inspect recent sessions\n  claudelytics inspect abc123 --context-usage  # Context window use per turn\n  claudelytics inspect abc123 --timeline       # Activity per 15 minutes\n  claudelytics inspect --json           # Output as JSON"
     )]
     Inspect {
         #[arg(
@@ -929,9 +2347,21 @@ enum Commands {
         #[arg(
             long,
             help = "Show activity timeline",
-            long_help = "Display timeline of session activity"
+            long_help = "Show messages, tokens, and cost of the session per 15-minute interval,\nwith a sparkline of the whole session (a `timeline` array of buckets with --json)"
         )]
         timeline: bool,
+        #[arg(
+            long,
+            help = "Chart how full the context window got over the conversation",
+            long_help = "For every assistant turn, show the prompt size (input, cache creation, and\ncache read tokens) against the model's context window, so you can see when a\nconversation approached the limit. Turns above 80% are shown in red\nExample: claudelytics inspect abc123 --context-usage"
+        )]
+        context_usage: bool,
+        #[arg(
+            long,
+            help = "Combine resumed sessions into one logical session",
+            long_help = "Treat a session and the sessions resumed from it as one piece of work:\nthe chain is listed under its first session with combined usage and cost,\nand inspecting any session of the chain shows the whole chain"
+        )]
+        group_resumes: bool,
     },
 }
 
@@ -943,9 +2373,139 @@ fn main() {
     }
 }
 
+/// Commands that take over the terminal or speak a protocol on stdout
+fn is_interactive(cli: &Cli) -> bool {
+    cli.tui
+        || matches!(
+            cli.command,
+            Some(
+                Commands::Tui
+                    | Commands::AnalyticsTui { .. }
+                    | Commands::Live { .. }
+                    | Commands::Blocks { live: true, .. }
+                    | Commands::McpServer { .. }
+                    | Commands::Config { edit: true, .. }
+            )
+        )
+}
+
 /// Main application logic
 fn run() -> Result<()> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    logging::init(cli.verbose);
+
+    let _output_file = match &cli.output {
+        Some(path) => {
+            if is_interactive(&cli) {
+                anyhow::bail!("--output cannot be used with interactive commands");
+            }
+            Some(output_file::OutputFile::redirect(path, cli.append)?)
+        }
+        None => None,
+    };
+
+    if let Some(Commands::Completions { shell }) = &cli.command {
+        print!(
+            "{}",
+            completions::completion_script(*shell, &mut Cli::command())
+        );
+        return Ok(());
+    }
+
+    if let Some(Commands::Schema { report }) = &cli.command {
+        return json_v1::display_schema(report.map(Into::into));
+    }
+
+    // `--format json` is an alias for --json; json-v1 has its own output paths
+    cli.json |= cli.format == Some(CliOutputFormat::Json);
+    if let Some(template) = &cli.template {
+        if !matches!(
+            &cli.command,
+            None | Some(
+                Commands::Daily { .. }
+                    | Commands::Session { .. }
+                    | Commands::Monthly { .. }
+                    | Commands::Weekly { .. }
+                    | Commands::Hourly { .. }
+            )
+        ) {
+            anyhow::bail!(
+                "--template is available for daily, session, monthly, weekly, and hourly"
+            );
+        }
+        if cli.format.is_some() {
+            anyhow::bail!("--template cannot be combined with --format");
+        }
+        output_template::load(template)?;
+        cli.json = true;
+    }
+    let json_v1 = cli.format == Some(CliOutputFormat::JsonV1);
+    if json_v1
+        && !matches!(
+            &cli.command,
+            None | Some(
+                Commands::Daily {
+                    group_by: None,
+                    split_by: None,
+                    ..
+                } | Commands::Session { group_by: None, .. }
+                    | Commands::Monthly {
+                        group_by: None,
+                        split_by: None,
+                        ..
+                    }
+                    | Commands::BillingBlocks { .. }
+            )
+        )
+    {
+        anyhow::bail!(
+            "--format json-v1 is available for daily, session, monthly, and billing-blocks (without --group-by or --split-by)"
+        );
+    }
+    if json_v1
+        && matches!(
+            &cli.command,
+            Some(
+                Commands::Daily {
+                    columns: Some(_),
+                    ..
+                } | Commands::Session {
+                    columns: Some(_),
+                    ..
+                }
+            )
+        )
+    {
+        anyhow::bail!("--columns cannot be combined with --format json-v1, whose fields are fixed");
+    }
+
+    // Settings saved in the v1 layout move to the v2 sections on the first run;
+    // until then they are still read, so nothing is lost if this fails
+    if !matches!(cli.command, Some(Commands::Config { .. })) {
+        match config::migrate() {
+            Ok(Some(backup)) => print_warning(&format!(
+                "Migrated the configuration from the deprecated v1 layout (backup: {})",
+                backup.display()
+            )),
+            Ok(None) => {}
+            Err(e) => print_warning(&format!(
+                "The configuration uses the deprecated v1 layout and could not be migrated: {:#}\nRun `claudelytics config --migrate` once the problem is fixed",
+                e
+            )),
+        }
+    }
+
+    // A profile fills in what the command line leaves unset
+    if let Some(name) = cli.profile.clone() {
+        let app_config = config_v2::AppConfig::load()?;
+        apply_profile(&mut cli, &app_config.profile(&name)?.overrides);
+        state::use_profile(&name);
+    }
+    // Then the `defaults` section of the config (not while editing the config,
+    // so `config --create-profile` only stores flags given on the command line)
+    if !matches!(cli.command, Some(Commands::Config { .. })) {
+        apply_default_flags(&mut cli, &config_v2::AppConfig::load()?.defaults);
+    }
 
     // Handle --list-models flag
     if cli.list_models {
@@ -979,33 +2539,27 @@ fn run() -> Result<()> {
     }
 
     // Load configuration
-    let mut config = Config::load().unwrap_or_default();
+    let config = config_v2::AppConfig::load()?;
 
     // Get Claude directory paths (supports both legacy ~/.claude and XDG ~/.config/claude)
-    let (claude_dir, claude_dirs) = if let Some(path) = cli.path {
-        (path.clone(), vec![path])
+    // Explicit directories win: repeated --path, then `claude_paths` in config
+    let explicit_dirs = if cli.path.is_empty() {
+        config.core.claude_paths.clone()
     } else {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let legacy = PathBuf::from(&home).join(".claude");
-        let xdg = PathBuf::from(&home).join(".config").join("claude");
-
-        let mut dirs = Vec::new();
-        if legacy.exists() {
-            dirs.push(legacy.clone());
-        }
-        if xdg.exists() {
-            dirs.push(xdg.clone());
-        }
+        cli.path.clone()
+    };
+    let (claude_dir, claude_dirs) = if let Some(primary) = explicit_dirs.first() {
+        (primary.clone(), explicit_dirs)
+    } else {
+        let mut dirs = parser::default_claude_dirs();
 
         // Primary dir: prefer config, then legacy, then XDG
-        let primary = config.get_claude_path().unwrap_or_else(|_| {
-            if legacy.exists() {
-                legacy.clone()
-            } else if xdg.exists() {
-                xdg
-            } else {
-                legacy // default for error message
-            }
+        let primary = config.core.claude_path.clone().unwrap_or_else(|| {
+            dirs.first().cloned().unwrap_or_else(|| {
+                // default for error message
+                let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+                PathBuf::from(home).join(".claude")
+            })
         });
 
         if dirs.is_empty() {
@@ -1015,26 +2569,66 @@ fn run() -> Result<()> {
         (primary, dirs)
     };
 
-    // Handle date shortcut flags: today > last_7d > last_30d > explicit
-    let (since_date, until_date) = if cli.today {
-        let today = Local::now().date_naive().format("%Y%m%d").to_string();
-        (Some(today.clone()), Some(today))
+    // --recalculate-costs ignores stored costUSD values entirely
+    let cost_mode = if cli.recalculate_costs {
+        CliCostMode::Calculate
+    } else {
+        cli.cost_mode.unwrap_or(CliCostMode::Auto)
+    };
+
+    // Handle date shortcut flags: today > last_7d > last_30d > ranges > explicit;
+    // everything is passed on as YYYYMMDD
+    let today = Local::now().date_naive();
+    let range = if cli.today {
+        Some((today, today))
     } else if cli.last_7d {
-        let today = Local::now().date_naive();
-        let since = (today - chrono::Duration::days(6))
-            .format("%Y%m%d")
-            .to_string();
-        let until = today.format("%Y%m%d").to_string();
-        (Some(since), Some(until))
+        Some(date_range::last(7, today)?)
     } else if cli.last_30d {
-        let today = Local::now().date_naive();
-        let since = (today - chrono::Duration::days(29))
-            .format("%Y%m%d")
-            .to_string();
-        let until = today.format("%Y%m%d").to_string();
-        (Some(since), Some(until))
+        Some(date_range::last(30, today)?)
+    } else if let Some(span) = &cli.last {
+        Some(date_range::last(date_range::span_days(span)?, today)?)
+    } else if cli.this_month {
+        Some(date_range::this_month(today))
+    } else if cli.last_month {
+        Some(date_range::last_month(today))
     } else {
-        (cli.since, cli.until)
+        None
+    };
+    let (since_date, until_date) = match range {
+        Some((since, until)) => (
+            Some(date_range::to_arg(since)),
+            Some(date_range::to_arg(until)),
+        ),
+        None => {
+            let resolve = |date: &Option<String>| {
+                date.as_deref()
+                    .map(|date| date_range::parse_date(date, today).map(date_range::to_arg))
+                    .transpose()
+            };
+            (resolve(&cli.since)?, resolve(&cli.until)?)
+        }
+    };
+    // `check` only looks at the current month, so skip parsing older data
+    let (since_date, until_date) = match &cli.command {
+        Some(Commands::Check { .. }) => {
+            let today = Local::now().date_naive();
+            let month_start = today.with_day(1).unwrap_or(today);
+            (Some(month_start.format("%Y%m%d").to_string()), None)
+        }
+        Some(Commands::Hourly {
+            date: Some(date), ..
+        }) => {
+            let date = date_range::to_arg(date_range::parse_date(date, Local::now().date_naive())?);
+            (Some(date.clone()), Some(date))
+        }
+        // The status line needs today and any block that started before midnight
+        Some(Commands::Status { .. }) => {
+            let yesterday = Local::now().date_naive() - chrono::Duration::days(1);
+            (Some(yesterday.format("%Y%m%d").to_string()), None)
+        }
+        // The snapshot taken before pruning and the disk report cover all usage
+        Some(Commands::Prune { .. } | Commands::Disk { .. }) => (None, None),
+        _ => (since_date, until_date),
     };
 
     // Handle configuration commands first
@@ -1042,9 +2636,135 @@ fn run() -> Result<()> {
         show,
         reset,
         set_path,
+        set_values,
+        edit,
+        migrate,
+        list_profiles,
+        create_profile,
+        delete_profile,
+        description,
+        timezone,
+        daily_limit,
+        monthly_limit,
+    }) = &cli.command
+    {
+        if !set_values.is_empty() {
+            return handle_config_set_command(set_values);
+        }
+        if *edit {
+            return handle_config_edit_command();
+        }
+        if *migrate {
+            return handle_config_migrate_command();
+        }
+        if let Some(name) = create_profile {
+            let overrides = config_v2::ConfigOverrides {
+                claude_path: cli.path.first().cloned(),
+                output_format: cli.json.then_some(config_v2::OutputFormat::Json),
+                daily_limit: *daily_limit,
+                monthly_limit: *monthly_limit,
+                timezone: timezone.clone(),
+                model_filter: cli.model_filter.clone(),
+                since: cli.since.clone(),
+                ..Default::default()
+            };
+            return handle_create_profile_command(name, description.as_deref(), overrides);
+        }
+        if let Some(name) = delete_profile {
+            return handle_delete_profile_command(name);
+        }
+        if *list_profiles {
+            return handle_list_profiles_command();
+        }
+        return handle_config_command(config, *show, *reset, set_path.clone());
+    }
+
+    if let Some(Commands::Budget { action }) = &cli.command {
+        return handle_budget_command(action);
+    }
+
+    if let Some(Commands::Goals {
+        action: Some(action),
+    }) = &cli.command
+    {
+        return handle_goals_command(action);
+    }
+
+    if let Some(Commands::Import {
+        file,
+        format,
+        source,
+        list,
+        clear,
     }) = &cli.command
     {
-        return handle_config_command(&mut config, *show, *reset, set_path.clone());
+        return handle_import_command(file.as_deref(), *format, source.as_deref(), *list, *clear);
+    }
+
+    if let Some(Commands::Team { inputs, anonymize }) = &cli.command {
+        let parse_date = |date: &Option<String>| {
+            date.as_deref()
+                .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+        };
+        let bundles = inputs
+            .iter()
+            .map(|path| team::TeamBundle::load(path))
+            .collect::<Result<Vec<_>>>()?;
+        let report = team::TeamReport::new(
+            &bundles,
+            parse_date(&since_date),
+            parse_date(&until_date),
+            *anonymize,
+        );
+        team::display_team_report(&report, cli.json);
+        return Ok(());
+    }
+
+    // Budget limits saved via `claudelytics budget set`
+    let config_v2::AppConfig {
+        budget,
+        alerts,
+        billing_blocks: billing_blocks_config,
+        quota: quota_config,
+        tags: tags_config,
+        burn_rate: burn_rate_config,
+        archive: archive_config,
+        goals: goals_config,
+        redaction: redaction_config,
+        tickets: tickets_config,
+        currency: currency_config,
+        schedule: schedule_config,
+        clipboard: clipboard_config,
+        ..
+    } = match &cli.profile {
+        Some(name) => config_v2::AppConfig::load_with_profile(name)?,
+        None => config_v2::AppConfig::load()?,
+    };
+    let include_archive = cli.with_archive || archive_config.merge;
+
+    if let Some(Commands::Complete { target }) = &cli.command {
+        let kind = match target {
+            CompletionTarget::Sessions => completions::CompletionKind::Sessions,
+            CompletionTarget::Projects => completions::CompletionKind::Projects,
+        };
+        for candidate in completions::completion_candidates(&claude_dirs, kind) {
+            println!("{}", candidate);
+        }
+        return Ok(());
+    }
+
+    if let Some(Commands::Bookmarks { action }) = &cli.command {
+        return handle_bookmarks_command(action, &claude_dirs, cli.json);
+    }
+
+    if let Some(Commands::Tags { action }) = &cli.command {
+        return handle_tags_command(action, &claude_dirs, cli.json);
+    }
+
+    if let Some(Commands::Doctor) = &cli.command {
+        let report = doctor::run_doctor(&claude_dirs);
+        doctor::display_doctor_report(&report, cli.json);
+        return Ok(());
     }
 
     // Validate at least one Claude directory exists
@@ -1061,40 +2781,538 @@ fn run() -> Result<()> {
         );
     }
 
+    if let Some(Commands::Bench {
+        runs,
+        cached,
+        no_save,
+        top,
+    }) = &cli.command
+    {
+        let parser = UsageParser::new_multi(claude_dirs, None, None, None, cost_mode.into())?
+            .with_parse_cache(*cached)
+            .with_dedup(!cli.no_dedup);
+        return handle_bench_command(&parser, *runs, *cached, *no_save, *top, cli.json);
+    }
+
+    // Handle MCP server command (parses data per request, so before the up-front parse)
+    if let Some(Commands::McpServer {
+        http,
+        bind,
+        list_tools,
+        list_resources,
+    }) = &cli.command
+    {
+        let bind_address = bind.clone().unwrap_or_else(|| {
+            config_v2::AppConfig::load()
+                .map(|c| c.mcp.http_bind_address)
+                .unwrap_or_else(|_| config_v2::McpConfig::default().http_bind_address)
+        });
+        return handle_mcp_server_command(
+            claude_dirs,
+            http.map(|port| (bind_address, port)),
+            *list_tools,
+            *list_resources,
+        );
+    }
+
+    // The API server parses data per request as well
+    if let Some(Commands::Serve { port, bind }) = &cli.command {
+        let bind_address = bind.clone().unwrap_or_else(|| {
+            config_v2::AppConfig::load()
+                .map(|c| c.mcp.http_bind_address)
+                .unwrap_or_else(|_| config_v2::McpConfig::default().http_bind_address)
+        });
+        let server = rest_api::ApiServer::new(
+            claude_dirs,
+            rest_api::ApiOptions {
+                budget,
+                billing_config: billing_blocks::BillingBlockConfig::from(&billing_blocks_config),
+                cost_mode: cost_mode.into(),
+                use_parse_cache: !cli.no_cache,
+                dedup: !cli.no_dedup,
+                include_archive,
+            },
+        );
+        return rest_api::run(server, &socket_address(&bind_address, *port));
+    }
+
+    if let Some(Commands::Snapshot { stats: true }) = &cli.command {
+        let stats = archive::UsageArchive::open()?.stats()?;
+        archive::display_archive_stats(&stats, cli.json);
+        return Ok(());
+    }
+
+    if let Some(Commands::Sql { query }) = &cli.command {
+        let result = usage_db::query(query)?;
+        usage_db::display_query_result(&result, cli.json);
+        return Ok(());
+    }
+
+    if let Some(Commands::Cache { clear: true, .. }) = &cli.command {
+        parse_cache::ParseCache::clear()?;
+        print_info("Parse cache cleared");
+        return Ok(());
+    }
+
+    // Billing block layout: config file default, overridden by `billing-blocks --mode`
+    let mut billing_config = billing_blocks::BillingBlockConfig::from(&billing_blocks_config);
+    if let Some(Commands::BillingBlocks {
+        mode: Some(mode), ..
+    }) = &cli.command
+    {
+        billing_config.anchor = match mode {
+            BillingBlockMode::Fixed => billing_blocks::BlockAnchor::Fixed,
+            BillingBlockMode::Rolling => billing_blocks::BlockAnchor::Rolling,
+        };
+    }
+    if let Some(Commands::Quota { .. } | Commands::Status { .. }) = &cli.command {
+        // Subscription limits reset on rolling 5-hour windows
+        billing_config = billing_blocks::BillingBlockConfig {
+            anchor: billing_blocks::BlockAnchor::Rolling,
+            duration_hours: billing_blocks::BILLING_BLOCK_HOURS,
+        };
+    }
+
+    let model_filter = match &cli.command {
+        Some(Commands::Watch {
+            model_filter: Some(model),
+            ..
+        }) => Some(model.clone()),
+        Some(Commands::Prune { .. } | Commands::Disk { .. }) => None,
+        _ => cli.model_filter.clone(),
+    };
+
     // Create parser with all discovered directories
     let parser = UsageParser::new_multi(
         claude_dirs,
         since_date.clone(),
         until_date.clone(),
-        cli.model_filter.clone(),
-        cli.cost_mode.into(),
-    )?;
+        model_filter,
+        cost_mode.into(),
+    )?
+    .with_parse_cache(!cli.no_cache)
+    .with_billing_config(billing_config)
+    .with_imports(true)
+    .with_archive(include_archive)
+    .with_dedup(!cli.no_dedup);
+    // The hourly report, session blocks, OTLP export, git correlation, and schedule splits need
+    // individual records, which the maps don't keep
+    let parser = if matches!(
+        cli.command,
+        Some(
+            Commands::Hourly { .. }
+                | Commands::Blocks { .. }
+                | Commands::OtelExport { .. }
+                | Commands::GitCorrelate { .. }
+                | Commands::Daily {
+                    split_by: Some(_),
+                    ..
+                }
+                | Commands::Monthly {
+                    split_by: Some(_),
+                    ..
+                }
+                | Commands::Export {
+                    split_by: Some(_),
+                    ..
+                }
+        )
+    ) {
+        parser.with_all_usage_events()
+    } else if matches!(cli.command, Some(Commands::Realtime { .. })) || cli.realtime {
+        // Per-project burn rates cover the last day
+        parser.with_recent_usage(chrono::Duration::hours(24))
+    } else {
+        parser
+    };
+
+    if let Some(Commands::SyncDb) = &cli.command {
+        let summary = usage_db::sync(parser)?;
+        usage_db::display_sync_summary(&summary, cli.json);
+        return Ok(());
+    }
+
+    // The audit needs every record's stored and calculated cost, which the maps don't keep
+    if let Some(Commands::Audit { tolerance }) = &cli.command {
+        let parser = parser.with_cost_audit();
+        parser.parse_all()?;
+        audit::display_audit_report(&parser.cost_audit().report(*tolerance), cli.json);
+        return Ok(());
+    }
+
+    // Parse all usage data, or read it from the database written by sync-db
+    let (mut daily_map, mut session_map, mut billing_manager, session_summaries, mut usage_events) =
+        if cli.backend == CliBackend::Sqlite {
+            let parse_date = |date: &Option<String>| {
+                date.as_deref()
+                    .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+            };
+            let stored = usage_db::load(
+                parse_date(&since_date),
+                parse_date(&until_date),
+                cli.model_filter.as_deref(),
+                billing_config,
+            )?;
+            tracing::info!(
+                days = stored.daily_map.len(),
+                sessions = stored.session_map.len(),
+                "loaded usage from the database"
+            );
+            (
+                stored.daily_map,
+                stored.session_map,
+                stored.billing_manager,
+                stored.session_summaries,
+                stored.events,
+            )
+        } else {
+            let (daily_map, session_map, billing_manager) = parser.parse_all()?;
+            (
+                daily_map,
+                session_map,
+                billing_manager,
+                parser.session_summaries(),
+                parser.recent_usage(),
+            )
+        };
+    if let Some(Commands::Prune {
+        older_than,
+        archive: archive_dir,
+        dry_run,
+    }) = &cli.command
+    {
+        if cli.backend == CliBackend::Sqlite || cli.no_dedup {
+            anyhow::bail!(
+                "prune snapshots the parsed usage before moving files; run it without --backend sqlite and --no-dedup"
+            );
+        }
+        let cutoff = prune::cutoff(older_than, Utc::now())?;
+        let candidates = prune::find_candidates(parser.claude_dirs(), cutoff)?;
+        let snapshot_rows = if *dry_run || candidates.is_empty() {
+            None
+        } else {
+            let summary =
+                archive::UsageArchive::open()?.snapshot(&daily_map, &session_map, Utc::now())?;
+            Some(summary.appended)
+        };
+        let mut summary = prune::prune(&candidates, archive_dir, cutoff, *dry_run)?;
+        summary.snapshot_rows = snapshot_rows;
+        prune::display_prune_summary(&summary, cli.json);
+        return Ok(());
+    }
+
+    // Status bars re-run `status` every few seconds; it shouldn't write the archive each time
+    let skip_auto_snapshot = matches!(
+        cli.command,
+        Some(Commands::Snapshot { .. } | Commands::Status { .. })
+    );
+    // Duplicate-inflated totals would replace the archived ones, so --no-dedup runs are skipped
+    if archive_config.auto_snapshot && !skip_auto_snapshot && !cli.no_dedup {
+        let snapshot = archive::UsageArchive::open()
+            .and_then(|archive| archive.snapshot(&daily_map, &session_map, Utc::now()));
+        match snapshot {
+            Ok(summary) if cli.verbose > 0 && !cli.json => print_info(&format!(
+                "Archived {} new or changed rows",
+                summary.appended
+            )),
+            Ok(_) => {}
+            Err(e) => print_warning(&format!("Auto-snapshot failed: {:#}", e)),
+        }
+    }
+    // Archived snapshots stay in USD, so costs are converted only after the snapshot
+    if let Some(code) = cli.currency.as_ref().or(currency_config.default.as_ref()) {
+        let currency = currency::resolve(code, &currency_config)?;
+        currency.convert(
+            &mut daily_map,
+            &mut session_map,
+            &mut billing_manager,
+            &mut usage_events,
+        );
+        if cli.verbose > 0 && !cli.json && !currency.is_usd() {
+            print_info(&format!(
+                "Costs shown in {} (1 USD = {} {})",
+                currency.code, currency.rate, currency.code
+            ));
+        }
+        currency::set_current(currency);
+    }
+    let cost_basis = json_v1::CostBasis {
+        currency: &currency::current().code,
+        cost_mode: cost_mode.into(),
+    };
+    if cli.verbose > 0 && !cli.json && parser.duplicates_skipped() > 0 {
+        print_info(&format!(
+            "Skipped {} duplicate records (use --no-dedup to include them)",
+            parser.duplicates_skipped()
+        ));
+    }
+    if cli.report_errors || cli.strict {
+        let issues = parser.parse_issues();
+        report_parse_issues(&issues);
+        if cli.strict && !issues.is_empty() {
+            anyhow::bail!(
+                "Strict mode: {} unparsable lines and {} records with unknown models",
+                issues.invalid_lines(),
+                issues.unknown_model_records()
+            );
+        }
+    }
 
-    // Parse all usage data
-    let (daily_map, session_map, billing_manager) = parser.parse_all()?;
+    if let Some(Commands::Status { format }) = &cli.command {
+        let status = status_line::build_status_line(
+            &daily_map,
+            &billing_manager,
+            quota_config.plan,
+            quota_config
+                .limits_for(quota_config.plan)
+                .in_currency(currency::current()),
+            Utc::now(),
+        );
+        if cli.json {
+            println!("{}", serde_json::to_string_pretty(&status)?);
+        } else {
+            println!("{}", status_line::render(&status, (*format).into()));
+        }
+        return Ok(());
+    }
+
+    if let Some(Commands::Check {
+        max_daily_cost,
+        max_monthly_cost,
+        warn_threshold,
+        quiet,
+    }) = &cli.command
+    {
+        let report = budget_check::check_budget(
+            &daily_map,
+            Local::now().date_naive(),
+            max_daily_cost.or(budget.daily_limit),
+            max_monthly_cost.or(budget.monthly_limit),
+            warn_threshold.unwrap_or(budget.alert_threshold),
+        );
+        if report.checks.is_empty() {
+            anyhow::bail!(
+                "No limits to check: pass --max-daily-cost/--max-monthly-cost or save them with `claudelytics budget set`"
+            );
+        }
+        if !quiet {
+            budget_check::display_budget_check(&report, cli.json);
+        }
+        std::process::exit(report.exit_code);
+    }
+
+    // Check if we have any data
+    if daily_map.is_empty() && session_map.is_empty() {
+        print_warning("No usage data found for the specified criteria");
+        return Ok(());
+    }
+
+    // Clone maps for potential re-generation with different sorting
+    let daily_map_clone = daily_map.clone();
+    let session_map_clone = session_map.clone();
+    let tagger = tags::Tagger::new(&tags_config);
+
+    // Generate default reports
+    let mut daily_report = generate_daily_report_sorted(daily_map, None, None);
+    let mut session_report = generate_session_report_sorted(session_map, None, None);
+    attach_session_summaries(&mut session_report, &session_summaries);
+
+    // Handle export command
+    if let Some(Commands::Export {
+        daily,
+        sessions,
+        summary,
+        output,
+        format,
+        sort_by,
+        sort_order,
+        group_by,
+        split_by,
+        html,
+        heatmap,
+        team_bundle,
+        user,
+        obsidian,
+        columns,
+    }) = &cli.command
+    {
+        // Apply the same sorting as the terminal reports
+        if sort_by.is_some() || sort_order.is_some() {
+            daily_report = generate_daily_report_sorted(
+                daily_map_clone.clone(),
+                convert_sort_field(*sort_by),
+                convert_sort_order(*sort_order),
+            );
+            session_report = generate_session_report_sorted(
+                session_map_clone.clone(),
+                convert_sort_field(*sort_by),
+                convert_sort_order(*sort_order),
+            );
+            attach_session_summaries(&mut session_report, &session_summaries);
+        }
+
+        // `--columns` applies to the daily and sessions files being written
+        let export_all = !*daily && !*sessions && !*summary;
+        let daily_columns = columns
+            .as_deref()
+            .filter(|_| *daily || export_all)
+            .map(|spec| columns::select(spec, columns::daily_columns(), "daily"))
+            .transpose()?;
+        let session_columns = columns
+            .as_deref()
+            .filter(|_| *sessions || export_all)
+            .map(|spec| columns::select(spec, columns::session_columns(), "session"))
+            .transpose()?;
+        if let Some(columns) = &daily_columns {
+            if columns::contains(columns, "models") {
+                attach_daily_model_breakdown(&mut daily_report, &daily_map_clone);
+            }
+            if columns::contains(columns, "sources") {
+                attach_daily_source_breakdown(&mut daily_report, &daily_map_clone);
+            }
+        }
+
+        if let Some(vault_dir) = obsidian {
+            let parser = conversation_parser::ConversationParser::new(claude_dir.clone());
+            let conversations = parser
+                .find_conversation_files()?
+                .iter()
+                .filter_map(|path| parser.parse_conversation(path).ok())
+                .collect();
+            let export = obsidian::export_vault(
+                vault_dir,
+                &daily_map_clone,
+                conversations,
+                &claude_dir.join("projects"),
+                &pricing_cache::PricingCache::effective_pricing(),
+                |conversation| Ok(format_conversation_as_markdown(conversation, true, true)),
+            )?;
+            print_info(&format!(
+                "Daily notes written to {}: {} created, {} updated, {} conversation notes",
+                vault_dir.display(),
+                export.notes_created,
+                export.notes_updated,
+                export.conversations
+            ));
+            return Ok(());
+        }
+
+        if *html {
+            let mut report = daily_report.clone();
+            attach_daily_model_breakdown(&mut report, &daily_map_clone);
+            let path = match output {
+                Some(path) if path.extension().is_some_and(|ext| ext == "html") => path.clone(),
+                Some(path) => path.with_extension("html"),
+                None => config
+                    .get_export_directory()
+                    .join("claudelytics_report.html"),
+            };
+            html_report::export_html_report(&report, &session_report, &path)?;
+            print_info(&format!("HTML report exported to: {}", path.display()));
+            return Ok(());
+        }
+
+        if *heatmap {
+            let parse_date = |date: &Option<String>| {
+                date.as_deref()
+                    .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+            };
+            let heatmap = heatmap::Heatmap::build(
+                &daily_map_clone,
+                parse_date(&since_date),
+                parse_date(&until_date),
+                chrono::Local::now().date_naive(),
+            );
+            let path = match output {
+                Some(path) if path.extension().is_some_and(|ext| ext == "svg") => path.clone(),
+                Some(path) => path.with_extension("svg"),
+                None => config
+                    .get_export_directory()
+                    .join("claudelytics_heatmap.svg"),
+            };
+            heatmap.export_svg(&path)?;
+            heatmap::display_heatmap(&heatmap, cli.json);
+            if !cli.json {
+                print_info(&format!("Heatmap exported to: {}", path.display()));
+            }
+            return Ok(());
+        }
 
-    // Check if we have any data
-    if daily_map.is_empty() && session_map.is_empty() {
-        print_warning("No usage data found for the specified criteria");
-        return Ok(());
-    }
+        if *team_bundle {
+            let user = user.clone().unwrap_or_else(team::TeamBundle::default_user);
+            let bundle = team::TeamBundle::new(
+                &user,
+                &daily_map_clone,
+                &session_map_clone,
+                chrono::Utc::now(),
+            );
+            let path = match output {
+                Some(path) if path.extension().is_some_and(|ext| ext == "json") => path.clone(),
+                Some(path) => path.with_extension("json"),
+                None => config
+                    .get_export_directory()
+                    .join(format!("claudelytics_team_{}.json", user)),
+            };
+            bundle.save(&path)?;
+            print_info(&format!(
+                "Team bundle for '{}' exported to: {}",
+                user,
+                path.display()
+            ));
+            return Ok(());
+        }
 
-    // Clone maps for potential re-generation with different sorting
-    let daily_map_clone = daily_map.clone();
-    let session_map_clone = session_map.clone();
+        if split_by.is_some() {
+            let schedule = schedule::Schedule::new(&schedule_config)?;
+            let split = |period| {
+                reports::generate_schedule_report(
+                    &usage_events,
+                    &schedule,
+                    period,
+                    convert_sort_field(*sort_by),
+                    convert_sort_order(*sort_order),
+                )
+            };
+            return handle_grouped_export_command(
+                &split(reports::GroupPeriod::Day),
+                &split(reports::GroupPeriod::Total),
+                &daily_report,
+                &session_report,
+                *daily,
+                *sessions,
+                *summary,
+                output,
+                (*format).into(),
+                &config,
+            );
+        }
 
-    // Generate default reports
-    let mut daily_report = generate_daily_report_sorted(daily_map, None, None);
-    let mut session_report = generate_session_report_sorted(session_map, None, None);
+        if let Some(group_by) = group_by {
+            let grouped = |period| {
+                reports::generate_group_report(
+                    &daily_map_clone,
+                    &session_map_clone,
+                    (*group_by).into(),
+                    period,
+                    &tagger,
+                    convert_sort_field(*sort_by),
+                    convert_sort_order(*sort_order),
+                )
+            };
+            return handle_grouped_export_command(
+                &grouped(reports::GroupPeriod::Day),
+                &grouped(reports::GroupPeriod::Total),
+                &daily_report,
+                &session_report,
+                *daily,
+                *sessions,
+                *summary,
+                output,
+                (*format).into(),
+                &config,
+            );
+        }
 
-    // Handle export command
-    if let Some(Commands::Export {
-        daily,
-        sessions,
-        summary,
-        output,
-    }) = &cli.command
-    {
         return handle_export_command(
             &daily_report,
             &session_report,
@@ -1102,12 +3320,50 @@ fn run() -> Result<()> {
             *sessions,
             *summary,
             output,
+            (*format).into(),
             &config,
+            daily_columns.as_deref(),
+            session_columns.as_deref(),
         );
     }
 
     // Handle cost command
-    if let Some(Commands::Cost { today, date }) = &cli.command {
+    if let Some(Commands::Cost {
+        today,
+        date,
+        week,
+        month,
+        budget: period_budget,
+    }) = &cli.command
+    {
+        let period = if *week {
+            Some(budget_check::CostPeriod::Week)
+        } else if *month || (period_budget.is_some() && !*today) {
+            Some(budget_check::CostPeriod::Month)
+        } else if *today && period_budget.is_some() {
+            Some(budget_check::CostPeriod::Today)
+        } else {
+            None
+        };
+        if let Some(period) = period {
+            let limit = period_budget.or(match period {
+                budget_check::CostPeriod::Today => budget.daily_limit,
+                budget_check::CostPeriod::Week => None,
+                budget_check::CostPeriod::Month => budget.monthly_limit,
+            });
+            let spend = budget_check::period_spend(
+                &daily_map_clone,
+                Local::now().date_naive(),
+                period,
+                limit,
+            );
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&spend)?);
+            } else {
+                println!("{}", spend.summary_line());
+            }
+            return Ok(());
+        }
         return handle_cost_command(&daily_report, *today, date.as_deref());
     }
 
@@ -1121,21 +3377,6 @@ fn run() -> Result<()> {
         return handle_test_resume_command(daily_report, session_report, &billing_manager);
     }
 
-    // Handle MCP server command
-    if let Some(Commands::McpServer {
-        http,
-        list_tools,
-        list_resources,
-    }) = &cli.command
-    {
-        return handle_mcp_server_command(
-            claude_dir.as_path(),
-            *http,
-            *list_tools,
-            *list_resources,
-        );
-    }
-
     // Handle --by-model flag
     if cli.by_model {
         display_model_breakdown_report(&daily_map_clone, &session_map_clone);
@@ -1144,7 +3385,19 @@ fn run() -> Result<()> {
 
     // Handle TUI flag or command
     if cli.tui {
-        let mut tui_app = TuiApp::new(daily_report, session_report, billing_manager.clone());
+        let mut tui_app = TuiApp::new(daily_report, session_report, billing_manager.clone())
+            .with_claude_dirs(parser.claude_dirs().to_vec())
+            .with_clipboard(&clipboard_config)
+            .with_quota(
+                quota_config.plan,
+                quota_config
+                    .limits_for(quota_config.plan)
+                    .in_currency(currency::current()),
+            );
+        // The palette's model filter re-reads the logs, which the database backend skips
+        if cli.backend != CliBackend::Sqlite {
+            tui_app = tui_app.with_usage_parser(parser);
+        }
 
         // Try to restore previous session state
         if let Ok(state) = TuiSessionState::load()
@@ -1166,13 +3419,50 @@ fn run() -> Result<()> {
         classic: false,
         sort_by: None,
         sort_order: None,
+        models: false,
+        by_source: false,
+        group_by: None,
+        split_by: None,
+        columns: None,
     });
     match command {
         Commands::Daily {
             classic,
             sort_by,
             sort_order,
+            models,
+            by_source,
+            group_by,
+            split_by,
+            columns,
         } => {
+            let columns = columns
+                .map(|spec| columns::select(&spec, columns::daily_columns(), "daily"))
+                .transpose()?;
+            if let Some(group_by) = group_by {
+                let report = reports::generate_group_report(
+                    &daily_map_clone,
+                    &session_map_clone,
+                    group_by.into(),
+                    reports::GroupPeriod::Day,
+                    &tagger,
+                    convert_sort_field(sort_by),
+                    convert_sort_order(sort_order),
+                );
+                display_group_report(&report, cli.json);
+                return Ok(());
+            }
+            if split_by.is_some() {
+                return display_schedule_split(
+                    &usage_events,
+                    &schedule_config,
+                    reports::GroupPeriod::Day,
+                    convert_sort_field(sort_by),
+                    convert_sort_order(sort_order),
+                    cli.json,
+                );
+            }
+
             // Re-generate with sorting if specified
             if sort_by.is_some() || sort_order.is_some() {
                 daily_report = generate_daily_report_sorted(
@@ -1181,8 +3471,34 @@ fn run() -> Result<()> {
                     convert_sort_order(sort_order),
                 );
             }
+            let column_selected = |id| columns.as_deref().is_some_and(|c| columns::contains(c, id));
+            if models || column_selected("models") {
+                attach_daily_model_breakdown(&mut daily_report, &daily_map_clone);
+            }
+            if by_source || column_selected("sources") {
+                attach_daily_source_breakdown(&mut daily_report, &daily_map_clone);
+            }
 
-            if daily_report.daily.is_empty() {
+            if let Some(columns) = &columns {
+                if daily_report.daily.is_empty() {
+                    print_warning("No daily usage data found for the specified date range");
+                } else if cli.json {
+                    display_report_json(&columns::to_json(
+                        columns,
+                        &daily_report.daily,
+                        &daily_report.totals,
+                        "daily",
+                    ));
+                } else {
+                    columns::display_table(columns, &daily_report.daily, &daily_report.totals);
+                }
+            } else if json_v1 {
+                json_v1::display_json_v1(&json_v1::DailyReportV1::new(
+                    &daily_report,
+                    Utc::now(),
+                    cost_basis,
+                ))?;
+            } else if daily_report.daily.is_empty() {
                 print_warning("No daily usage data found for the specified date range");
             } else if cli.json {
                 display_report_json(&daily_report);
@@ -1190,8 +3506,10 @@ fn run() -> Result<()> {
                 display_daily_report_responsive(&daily_report);
             } else if cli.classic || classic {
                 display_daily_report_table(&daily_report);
-            } else if cli.verbose {
-                display_daily_report_enhanced(&daily_report, cli.compact);
+            } else if cli.verbose > 0 {
+                let anomalies = projections::AnomalyDetector::new(alerts.anomaly_threshold)
+                    .detect(&daily_map_clone, &session_map_clone);
+                display_daily_report_enhanced(&daily_report, &anomalies, cli.compact);
             } else {
                 display::display_daily_report_compact(&daily_report);
             }
@@ -1202,10 +3520,9 @@ fn run() -> Result<()> {
                 handle_realtime_analytics_command(
                     &daily_map_clone,
                     &session_map_clone,
-                    None, // Use default budget limits
-                    None,
-                    None,
-                    0.8,   // Default alert threshold
+                    &usage_events,
+                    realtime_analytics::BudgetConfig::from_settings(&budget, &alerts),
+                    &alerts,
                     false, // Not JSON since we're appending to existing output
                 )?;
             }
@@ -1214,18 +3531,100 @@ fn run() -> Result<()> {
             classic,
             sort_by,
             sort_order,
+            group_by,
+            group_resumes,
+            limit,
+            offset,
+            top,
+            columns,
+            filter,
         } => {
-            // Re-generate with sorting if specified
-            if sort_by.is_some() || sort_order.is_some() {
+            let columns = columns
+                .map(|spec| columns::select(&spec, columns::session_columns(), "session"))
+                .transpose()?;
+            let filter = filter
+                .map(|source| {
+                    filter_expr::Filter::parse(&source, &columns::session_columns())
+                        .map_err(|e| anyhow::anyhow!("Invalid --filter \"{}\": {}", source, e))
+                })
+                .transpose()?;
+            let (sort_by, sort_order, limit) = match top {
+                Some(top) => (Some(SortField::Cost), Some(SortOrder::Desc), Some(top)),
+                None => (sort_by, sort_order, limit),
+            };
+            if let Some(group_by) = group_by {
+                let report = reports::generate_group_report(
+                    &daily_map_clone,
+                    &session_map_clone,
+                    group_by.into(),
+                    reports::GroupPeriod::Total,
+                    &tagger,
+                    convert_sort_field(sort_by),
+                    convert_sort_order(sort_order),
+                );
+                display_group_report(&report, cli.json);
+                return Ok(());
+            }
+
+            if group_resumes {
+                let grouped =
+                    lineage::SessionLineage::scan(parser.claude_dirs()).group(&session_map_clone);
+                session_report = generate_session_report_sorted(
+                    grouped.session_map,
+                    convert_sort_field(sort_by),
+                    convert_sort_order(sort_order),
+                );
+                attach_session_summaries(&mut session_report, &session_summaries);
+                reports::attach_resumed_sessions(&mut session_report, &grouped.resumed);
+            } else if sort_by.is_some() || sort_order.is_some() {
+                // Re-generate with sorting if specified
                 session_report = generate_session_report_sorted(
                     session_map_clone.clone(),
                     convert_sort_field(sort_by),
                     convert_sort_order(sort_order),
                 );
+                attach_session_summaries(&mut session_report, &session_summaries);
+            }
+            if let Some(filter) = &filter {
+                reports::filter_session_report(&mut session_report, filter);
             }
+            reports::paginate_session_report(&mut session_report, offset, limit);
+            let no_sessions = if filter.is_some() {
+                "No sessions match the filter in the specified date range"
+            } else {
+                "No session usage data found for the specified date range"
+            };
 
-            if session_report.sessions.is_empty() {
-                print_warning("No session usage data found for the specified date range");
+            if let Some(columns) = &columns {
+                if session_report.sessions.is_empty() && session_report.pagination.is_none() {
+                    print_warning(no_sessions);
+                } else if cli.json {
+                    let mut value = columns::to_json(
+                        columns,
+                        &session_report.sessions,
+                        &session_report.totals,
+                        "sessions",
+                    );
+                    if let Some(pagination) = session_report.pagination {
+                        value["pagination"] = serde_json::to_value(pagination)?;
+                    }
+                    display_report_json(&value);
+                } else {
+                    columns::display_table(
+                        columns,
+                        &session_report.sessions,
+                        &session_report.totals,
+                    );
+                    display::display_pagination_footer(&session_report);
+                }
+            } else if json_v1 {
+                json_v1::display_json_v1(&json_v1::SessionReportV1::new(
+                    &session_report,
+                    Utc::now(),
+                    cost_basis,
+                ))?;
+            } else if session_report.sessions.is_empty() && session_report.pagination.is_none() {
+                print_warning(no_sessions);
             } else if cli.json {
                 display_report_json(&session_report);
             } else if cli.responsive {
@@ -1235,6 +3634,9 @@ fn run() -> Result<()> {
             } else {
                 display_session_report_enhanced(&session_report);
             }
+            if !cli.json && !json_v1 {
+                context_usage::display_session_context_warnings(&session_report);
+            }
 
             // Show real-time analytics if requested
             if cli.realtime {
@@ -1242,10 +3644,9 @@ fn run() -> Result<()> {
                 handle_realtime_analytics_command(
                     &daily_map_clone,
                     &session_map_clone,
-                    None, // Use default budget limits
-                    None,
-                    None,
-                    0.8,   // Default alert threshold
+                    &usage_events,
+                    realtime_analytics::BudgetConfig::from_settings(&budget, &alerts),
+                    &alerts,
                     false, // Not JSON since we're appending to existing output
                 )?;
             }
@@ -1254,7 +3655,33 @@ fn run() -> Result<()> {
             classic,
             sort_by,
             sort_order,
+            group_by,
+            split_by,
         } => {
+            if let Some(group_by) = group_by {
+                let report = reports::generate_group_report(
+                    &daily_map_clone,
+                    &session_map_clone,
+                    group_by.into(),
+                    reports::GroupPeriod::Month,
+                    &tagger,
+                    convert_sort_field(sort_by),
+                    convert_sort_order(sort_order),
+                );
+                display_group_report(&report, cli.json);
+                return Ok(());
+            }
+            if split_by.is_some() {
+                return display_schedule_split(
+                    &usage_events,
+                    &schedule_config,
+                    reports::GroupPeriod::Month,
+                    convert_sort_field(sort_by),
+                    convert_sort_order(sort_order),
+                    cli.json,
+                );
+            }
+
             // Generate monthly report from daily data with sorting
             let monthly_report = generate_monthly_report_sorted(
                 daily_map_clone.clone(),
@@ -1262,7 +3689,13 @@ fn run() -> Result<()> {
                 convert_sort_order(sort_order),
             );
 
-            if monthly_report.monthly.is_empty() {
+            if json_v1 {
+                json_v1::display_json_v1(&json_v1::MonthlyReportV1::new(
+                    &monthly_report,
+                    Utc::now(),
+                    cost_basis,
+                ))?;
+            } else if monthly_report.monthly.is_empty() {
                 print_warning("No monthly usage data found for the specified date range");
             } else if cli.json {
                 display_report_json(&monthly_report);
@@ -1307,8 +3740,42 @@ fn run() -> Result<()> {
                 display::display_weekly_report_enhanced(&weekly_report);
             }
         }
+        Commands::Hourly {
+            classic,
+            sort_by,
+            sort_order,
+            ..
+        } => {
+            let hourly_report = reports::generate_hourly_report_sorted(
+                &usage_events,
+                convert_sort_field(sort_by),
+                convert_sort_order(sort_order),
+            );
+
+            if hourly_report.hourly.is_empty() {
+                print_warning("No hourly usage data found for the specified date range");
+            } else if cli.json {
+                display::display_report_json(&hourly_report);
+            } else if cli.classic || classic {
+                display::display_hourly_report_table(&hourly_report);
+            } else {
+                display::display_hourly_report_enhanced(&hourly_report);
+            }
+        }
         Commands::Tui => {
-            let mut tui_app = TuiApp::new(daily_report, session_report, billing_manager.clone());
+            let mut tui_app = TuiApp::new(daily_report, session_report, billing_manager.clone())
+                .with_claude_dirs(parser.claude_dirs().to_vec())
+                .with_clipboard(&clipboard_config)
+                .with_quota(
+                    quota_config.plan,
+                    quota_config
+                        .limits_for(quota_config.plan)
+                        .in_currency(currency::current()),
+                );
+            // The palette's model filter re-reads the logs, which the database backend skips
+            if cli.backend != CliBackend::Sqlite {
+                tui_app = tui_app.with_usage_parser(parser);
+            }
 
             // Try to restore previous session state
             if let Ok(state) = TuiSessionState::load()
@@ -1323,7 +3790,27 @@ fn run() -> Result<()> {
             save_tui_state(&tui_app, TuiMode::Basic).ok();
             result?;
         }
-        Commands::BillingBlocks { classic, summary } => {
+        Commands::BillingBlocks { current: true, .. } => {
+            let burn_down = quota::build_burn_down(
+                &billing_manager,
+                quota_config.plan,
+                quota_config
+                    .limits_for(quota_config.plan)
+                    .in_currency(currency::current()),
+                Utc::now(),
+            );
+            quota::display_burn_down(burn_down.as_ref(), cli.json || json_v1);
+        }
+        Commands::BillingBlocks { .. } if json_v1 => {
+            json_v1::display_json_v1(&json_v1::BillingBlocksReportV1::new(
+                &billing_manager,
+                Utc::now(),
+                cost_basis,
+            ))?;
+        }
+        Commands::BillingBlocks {
+            classic, summary, ..
+        } => {
             handle_billing_blocks_command(
                 &billing_manager,
                 cli.json,
@@ -1336,8 +3823,34 @@ fn run() -> Result<()> {
             show,
             clear,
             update,
+            source,
         } => {
-            handle_pricing_cache_command(show, clear, update)?;
+            handle_pricing_cache_command(show, clear, update, source.as_deref())?;
+        }
+        Commands::Blocks {
+            token_limit,
+            cost_limit,
+            live: true,
+            windows,
+            ..
+        } => {
+            let options = live_dashboard::LiveDashboardOptions {
+                refresh: 5,
+                token_limit,
+                cost_limit: cost_limit.or(budget.daily_limit),
+                monthly_cost_limit: budget.monthly_limit,
+                alert_threshold: budget.alert_threshold,
+                alert_hooks: alerts.clone(),
+                show_details: true,
+                enable_alerts: true,
+                model_filter: cli.model_filter.clone(),
+                cost_mode: cost_mode.into(),
+                currency: currency::current().clone(),
+                burn_rate_windows: burn_rate::parse_windows(
+                    &windows.unwrap_or(burn_rate_config.windows),
+                )?,
+            };
+            live_dashboard::run_live_dashboard(parser.claude_dirs(), options)?;
         }
         Commands::Blocks {
             active,
@@ -1345,33 +3858,91 @@ fn run() -> Result<()> {
             recent,
             token_limit,
             cost_limit,
+            live: false,
+            ..
         } => {
             handle_blocks_command(
-                &claude_dir,
+                &usage_events,
                 BlocksCommandOptions {
                     active,
                     length,
                     recent,
                     token_limit,
-                    cost_limit,
-                    since: since_date.clone(),
-                    until: until_date.clone(),
+                    cost_limit: cost_limit.or(budget.daily_limit),
                 },
             )?;
         }
+        Commands::OtelExport {
+            endpoint,
+            headers,
+            no_traces,
+            no_metrics,
+            dry_run,
+        } => {
+            let options = otel_export::OtlpOptions {
+                traces: !no_traces,
+                metrics: !no_metrics,
+                dry_run,
+                ..otel_export::OtlpOptions::from_args(endpoint.as_deref(), &headers)?
+            };
+            let summary = otel_export::export(&usage_events, &session_summaries, &options)?;
+            otel_export::display_export_summary(&summary, cli.json);
+        }
+        Commands::Plan {
+            monthly_budget,
+            simulations,
+        } => {
+            let Some(monthly_budget) = monthly_budget.or(budget.monthly_limit) else {
+                anyhow::bail!(
+                    "No monthly budget given. Use --monthly-budget or `claudelytics budget set --monthly <USD>`"
+                );
+            };
+            let plan = plan::plan(
+                &daily_map_clone,
+                monthly_budget,
+                budget.billing_cycle_start_day,
+                simulations,
+                Local::now().date_naive(),
+            )?;
+            plan::display_plan(&plan, cli.json);
+        }
+        Commands::GitCorrelate {
+            repo,
+            project,
+            rev,
+            base,
+        } => {
+            let history = git_correlate::read_history(&repo, &rev, base.as_deref())?;
+            let project =
+                project.unwrap_or_else(|| git_correlate::claude_project_dir(&history.workdir));
+            let report = git_correlate::correlate(history, &project, &usage_events);
+            git_correlate::display_git_correlation(&report, cli.json);
+        }
+        Commands::Simulate { mappings } => {
+            let report = simulate::simulate(&daily_map_clone, &mappings, currency::current())?;
+            simulate::display_simulation(&report, cli.json);
+        }
         Commands::Projections {
             days,
             token_limit,
             cost_limit,
+            backtest,
             json,
         } => {
             handle_projections_command(
-                &claude_dir,
+                parser.claude_dirs(),
                 days,
                 token_limit,
-                cost_limit,
+                cost_limit.or(budget.monthly_limit),
+                budget.billing_cycle_start_day,
+                backtest,
                 json,
                 since_date.clone(),
+                cli.model_filter.clone(),
+                cost_mode.into(),
+                currency::current(),
+                !cli.no_cache,
+                !cli.no_dedup,
             )?;
         }
         Commands::Analytics {
@@ -1381,7 +3952,22 @@ fn run() -> Result<()> {
             frequency,
             efficiency,
             threshold,
+            leaderboard,
+            thinking,
+            top,
         } => {
+            if thinking {
+                let breakdown = session_analytics::SessionAnalytics::new(&session_map_clone)
+                    .thinking_breakdown(&daily_map_clone, top);
+                session_analytics::display_thinking_breakdown(&breakdown, cli.json);
+                return Ok(());
+            }
+            if leaderboard {
+                let board = session_analytics::SessionAnalytics::new(&session_map_clone)
+                    .efficiency_leaderboard(top, &session_summaries);
+                session_analytics::display_efficiency_leaderboard(&board, cli.json);
+                return Ok(());
+            }
             handle_analytics_command(
                 &session_map_clone,
                 time_of_day,
@@ -1392,6 +3978,286 @@ fn run() -> Result<()> {
                 threshold,
             )?;
         }
+        Commands::Goals { .. } => {
+            if !goals_config.has_goals() {
+                print_info(
+                    "No goals saved. Example: claudelytics goals set --daily-cost 10 --weekly-tokens 2000000",
+                );
+                return Ok(());
+            }
+            let report =
+                goals::GoalsReport::new(&daily_map_clone, &goals_config, Local::now().date_naive());
+            goals::display_goals_report(&report, cli.json);
+        }
+        Commands::AnalyticsTui { threshold } => {
+            let data = analytics_tui::StudioData::build(
+                &daily_map_clone,
+                &session_map_clone,
+                &budget,
+                &goals_config,
+                threshold,
+                Local::now().date_naive(),
+            );
+            analytics_tui::AnalyticsStudio::new(data).run()?;
+        }
+        Commands::Digest { period, output } => {
+            let anomalies = projections::AnomalyDetector::new(alerts.anomaly_threshold)
+                .detect(&daily_map_clone, &session_map_clone);
+            let digest = digest::build_digest(
+                &daily_map_clone,
+                &session_summaries,
+                &anomalies,
+                period.into(),
+                Local::now().date_naive(),
+                budget.billing_cycle_start_day,
+                budget.monthly_limit,
+            )?;
+            let content = if cli.json {
+                serde_json::to_string_pretty(&digest)? + "\n"
+            } else {
+                digest::digest_to_markdown(&digest)
+            };
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, content)?;
+                    print_info(&format!("Digest written to: {}", path.display()));
+                }
+                None => print!("{}", content),
+            }
+        }
+        Commands::Notify {
+            slack_webhook,
+            discord_webhook,
+            period,
+            dry_run,
+        } => {
+            let (target, url) = match (slack_webhook, discord_webhook) {
+                (Some(url), _) => (chat_webhooks::NotifyTarget::Slack, url),
+                (None, Some(url)) => (chat_webhooks::NotifyTarget::Discord, url),
+                (None, None) => anyhow::bail!("Pass --slack-webhook or --discord-webhook"),
+            };
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                anyhow::bail!("Webhook URL must start with http:// or https://");
+            }
+            let anomalies = projections::AnomalyDetector::new(alerts.anomaly_threshold)
+                .detect(&daily_map_clone, &session_map_clone);
+            let digest = digest::build_digest(
+                &daily_map_clone,
+                &session_summaries,
+                &anomalies,
+                period.into(),
+                Local::now().date_naive(),
+                budget.billing_cycle_start_day,
+                budget.monthly_limit,
+            )?;
+            let payload = target.payload(&digest);
+            if dry_run {
+                println!("{}", serde_json::to_string_pretty(&payload)?);
+            } else {
+                chat_webhooks::post(&url, &payload)?;
+                print_info(&format!(
+                    "Posted the {} summary to {}",
+                    digest.period,
+                    target.name()
+                ));
+            }
+        }
+        Commands::Chargeback {
+            group_by,
+            period,
+            markup,
+            format,
+            output,
+        } => {
+            let month = match period {
+                Some(period) => chargeback::parse_period(&period)?,
+                None => chargeback::previous_month(Local::now().date_naive()),
+            };
+            let statement = chargeback::build_statement(
+                &daily_map_clone,
+                &session_map_clone,
+                month,
+                group_by.into(),
+                &tagger,
+                markup,
+            );
+            let format = match (format, cli.json) {
+                (Some(format), _) => Some(format.into()),
+                (None, true) => Some(chargeback::StatementFormat::Json),
+                // Without --format, pick the format from the output file extension
+                (None, false) => output.as_ref().map(|path| {
+                    match path.extension().and_then(|ext| ext.to_str()) {
+                        Some("csv") => chargeback::StatementFormat::Csv,
+                        Some("json") => chargeback::StatementFormat::Json,
+                        _ => chargeback::StatementFormat::Markdown,
+                    }
+                }),
+            };
+            match (format, output) {
+                (Some(format), Some(path)) => {
+                    let mut file = std::fs::File::create(&path)?;
+                    chargeback::write_statement(&statement, format, &mut file)?;
+                    print_info(&format!("Statement written to: {}", path.display()));
+                }
+                (Some(format), None) => {
+                    chargeback::write_statement(&statement, format, &mut std::io::stdout())?;
+                }
+                (None, _) => chargeback::display_statement(&statement),
+            }
+        }
+        Commands::Anomalies {
+            threshold,
+            window,
+            alert,
+        } => {
+            let report =
+                projections::AnomalyDetector::new(threshold.unwrap_or(alerts.anomaly_threshold))
+                    .with_window_days(window)
+                    .detect(&daily_map_clone, &session_map_clone);
+            if cli.json {
+                display_report_json(&report);
+            } else {
+                display::display_anomaly_report(&report);
+            }
+            if alert {
+                let mut hooks = alerts::AlertHooks::new(alerts.clone());
+                for event in report.alert_events(Local::now().date_naive()) {
+                    hooks.fire(&event);
+                }
+            }
+        }
+        Commands::Watch {
+            format,
+            project,
+            interval,
+            notify,
+            notify_at,
+            expensive_session,
+            quota_warn,
+            ..
+        } => {
+            let notify = notify.then(|| {
+                let mut daily_thresholds = notify_at;
+                if daily_thresholds.is_empty()
+                    && let Some(limit) = budget.daily_limit
+                {
+                    daily_thresholds = vec![limit * budget.alert_threshold, limit];
+                }
+                daily_thresholds.sort_by(f64::total_cmp);
+                notifications::NotifyRules {
+                    daily_thresholds,
+                    expensive_session: expensive_session.unwrap_or_else(|| {
+                        notifications::NotifyRules::expensive_session_default(&session_map_clone)
+                    }),
+                    quota_warn_pct: (quota_warn > 0.0).then_some(quota_warn),
+                    plan: quota_config.plan,
+                    quota_limits: quota_config
+                        .limits_for(quota_config.plan)
+                        .in_currency(currency::current()),
+                }
+            });
+            let options = watch::WatchOptions {
+                interval,
+                format: format.into(),
+                project,
+                notify,
+                currency: currency::current().clone(),
+            };
+            watch::run_watch(&parser, (daily_map_clone, session_map_clone), &options)?;
+        }
+        Commands::CacheStats { top, min_creation } => {
+            let report =
+                cache_stats::build_cache_stats(&daily_map_clone, &session_map_clone, min_creation);
+            cache_stats::display_cache_stats(&report, cli.json, top);
+        }
+        Commands::Quota { plan, history } => {
+            let plan = match plan {
+                Some(SubscriptionPlan::Pro) => quota::Plan::Pro,
+                Some(SubscriptionPlan::Max5) => quota::Plan::Max5,
+                Some(SubscriptionPlan::Max20) => quota::Plan::Max20,
+                None => quota_config.plan,
+            };
+            let report = quota::build_quota_report(
+                &billing_manager,
+                plan,
+                quota_config
+                    .limits_for(plan)
+                    .in_currency(currency::current()),
+                history,
+                chrono::Utc::now(),
+            );
+            quota::display_quota_report(&report, cli.json);
+        }
+        Commands::Top { by, limit } => {
+            handle_top_command(
+                &claude_dir,
+                since_date.as_deref(),
+                until_date.as_deref(),
+                by,
+                limit,
+                cli.json,
+            )?;
+        }
+        Commands::Tools {
+            by_project,
+            project,
+            top,
+        } => {
+            handle_tools_command(
+                &claude_dir,
+                since_date.as_deref(),
+                until_date.as_deref(),
+                project.as_deref(),
+                by_project,
+                top,
+                cli.json,
+            )?;
+        }
+        Commands::Tickets {
+            pattern,
+            project,
+            limit,
+            csv,
+            output,
+        } => {
+            handle_tickets_command(
+                &claude_dir,
+                since_date.as_deref(),
+                until_date.as_deref(),
+                &tickets_config,
+                &pattern,
+                project.as_deref(),
+                limit,
+                csv,
+                output.as_deref(),
+                cli.json,
+            )?;
+        }
+        Commands::Search {
+            query,
+            regex,
+            project,
+            model_filter,
+            limit,
+            rebuild,
+        } => {
+            let query = search::SearchQuery {
+                pattern: query,
+                regex,
+                project,
+                model_filter: model_filter.or_else(|| cli.model_filter.clone()),
+                ..Default::default()
+            };
+            handle_search_command(
+                &claude_dir,
+                since_date.as_deref(),
+                until_date.as_deref(),
+                query,
+                limit,
+                rebuild,
+                cli.json,
+            )?;
+        }
         Commands::Cache {
             top,
             top_projects,
@@ -1402,6 +4268,7 @@ fn run() -> Result<()> {
             threshold,
             min_hit,
             min_churn,
+            clear: _,
         } => {
             let sort_asc = if asc {
                 Some(true)
@@ -1430,15 +4297,25 @@ fn run() -> Result<()> {
             monthly_limit,
             yearly_limit,
             alert_threshold,
+            max_concurrent,
             json,
         } => {
+            let defaults = realtime_analytics::BudgetConfig::from_settings(&budget, &alerts);
             handle_realtime_analytics_command(
                 &daily_map_clone,
                 &session_map_clone,
-                daily_limit,
-                monthly_limit,
-                yearly_limit,
-                alert_threshold,
+                &usage_events,
+                realtime_analytics::BudgetConfig {
+                    daily_limit: daily_limit.or(defaults.daily_limit),
+                    monthly_limit: monthly_limit.or(defaults.monthly_limit),
+                    yearly_limit: yearly_limit.or(defaults.yearly_limit),
+                    alert_threshold: alert_threshold.unwrap_or(defaults.alert_threshold),
+                    max_concurrent_sessions: max_concurrent
+                        .unwrap_or(defaults.max_concurrent_sessions)
+                        .max(1),
+                    ..defaults
+                },
+                &alerts,
                 json,
             )?;
         }
@@ -1448,18 +4325,28 @@ fn run() -> Result<()> {
             cost_limit,
             show_details,
             enable_alerts,
+            windows,
         } => {
             use live_dashboard::{LiveDashboardOptions, run_live_dashboard};
 
             let options = LiveDashboardOptions {
                 refresh,
                 token_limit,
-                cost_limit,
+                cost_limit: cost_limit.or(budget.daily_limit),
+                monthly_cost_limit: budget.monthly_limit,
+                alert_threshold: budget.alert_threshold,
+                alert_hooks: alerts.clone(),
                 show_details,
                 enable_alerts,
+                model_filter: cli.model_filter.clone(),
+                cost_mode: cost_mode.into(),
+                currency: currency::current().clone(),
+                burn_rate_windows: burn_rate::parse_windows(
+                    &windows.unwrap_or(burn_rate_config.windows),
+                )?,
             };
 
-            run_live_dashboard(&claude_dir, options)?;
+            run_live_dashboard(parser.claude_dirs(), options)?;
         }
         Commands::Conversation {
             session,
@@ -1472,19 +4359,28 @@ fn run() -> Result<()> {
             include_thinking,
             include_tools,
             list,
+            redact,
+            split_files,
         } => {
+            let redactor = redact
+                .then(|| redact::Redactor::new(&redaction_config))
+                .transpose()?;
             handle_conversation_command(
                 &claude_dir,
+                since_date.as_deref(),
+                until_date.as_deref(),
                 session,
                 project,
                 search,
                 export,
                 output,
+                split_files,
                 recent,
                 mode,
                 include_thinking,
                 include_tools,
                 list,
+                redactor.as_ref(),
             )?;
         }
         Commands::View {
@@ -1494,6 +4390,7 @@ fn run() -> Result<()> {
             list,
             export,
             output,
+            redact,
         } => {
             // View is an alias for conversation with simplified options
             let session = if let Some(ref t) = target {
@@ -1521,16 +4418,23 @@ fn run() -> Result<()> {
 
             handle_conversation_command(
                 &claude_dir,
+                since_date.as_deref(),
+                until_date.as_deref(),
                 session,
                 project_filter,
                 None, // search
                 export,
                 output,
+                false, // split_files
                 recent,
                 "detailed".to_string(), // mode
                 true,                   // include_thinking
                 true,                   // include_tools
                 list,
+                redact
+                    .then(|| redact::Redactor::new(&redaction_config))
+                    .transpose()?
+                    .as_ref(),
             )?;
         }
         Commands::Inspect {
@@ -1541,10 +4445,20 @@ fn run() -> Result<()> {
             json,
             conversations,
             timeline,
+            context_usage,
+            group_resumes,
         } => {
+            let grouped = group_resumes.then(|| {
+                lineage::SessionLineage::scan(parser.claude_dirs()).group(&session_map_clone)
+            });
+            let (session_map, resumed) = match &grouped {
+                Some(grouped) => (&grouped.session_map, grouped.resumed.clone()),
+                None => (&session_map_clone, HashMap::new()),
+            };
             handle_inspect_command(
                 &claude_dir,
-                &session_map_clone,
+                session_map,
+                &resumed,
                 target,
                 project,
                 recent,
@@ -1552,7 +4466,25 @@ fn run() -> Result<()> {
                 json,
                 conversations,
                 timeline,
+                context_usage,
+            )?;
+        }
+        Commands::Snapshot { .. } => {
+            let summary = archive::UsageArchive::open()?.snapshot(
+                &daily_map_clone,
+                &session_map_clone,
+                Utc::now(),
+            )?;
+            archive::display_snapshot(&summary, cli.json);
+        }
+        Commands::Disk { top } => {
+            let report = disk_usage::build_disk_report(
+                parser.claude_dirs(),
+                &session_map_clone,
+                Utc::now(),
+                top,
             )?;
+            disk_usage::display_disk_report(&report, top, cli.json);
         }
         _ => {} // Other commands handled above
     }
@@ -1579,16 +4511,25 @@ fn convert_sort_order(order: Option<SortOrder>) -> Option<ReportSortOrder> {
     })
 }
 
+/// `host:port` for binding, bracketing bare IPv6 addresses so the port separator is unambiguous
+fn socket_address(host: &str, port: u16) -> String {
+    if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
 /// Handle MCP server command
 fn handle_mcp_server_command(
-    claude_dir: &Path,
-    http_port: Option<u16>,
+    claude_dirs: Vec<PathBuf>,
+    http_bind: Option<(String, u16)>,
     list_tools: bool,
     list_resources: bool,
 ) -> Result<()> {
     use mcp::{McpServer, get_server_info};
 
-    let server = McpServer::new(claude_dir.to_path_buf());
+    let server = McpServer::new_multi(claude_dirs);
 
     // Handle list commands
     if list_tools {
@@ -1617,111 +4558,856 @@ fn handle_mcp_server_command(
     }
 
     // Start server
-    if let Some(port) = http_port {
-        print_info(&format!("Starting MCP HTTP server on port {}", port));
-        println!(
+    if let Some((host, port)) = http_bind {
+        let addr = socket_address(&host, port);
+        eprintln!(
             "Server info: {}",
             serde_json::to_string_pretty(&get_server_info())?
         );
-        print_warning("HTTP MCP server not yet fully implemented - use stdio mode");
+        mcp_http::run_http(server, &addr)?;
     } else {
-        print_info("Starting MCP stdio server");
-        println!(
-            "Server info: {}",
-            serde_json::to_string_pretty(&get_server_info())?
+        // stdout carries the JSON-RPC stream, so status goes to stderr
+        eprintln!(
+            "claudelytics MCP stdio server ready (protocol {})",
+            mcp::PROTOCOL_VERSION
         );
-        print_warning("Stdio MCP server not yet fully implemented - this is a preview");
+        server.run_stdio()?;
+    }
 
-        // Show what would be available
-        println!("\n📋 Available Resources:");
-        for resource in server.list_resources() {
-            println!("  - {}", resource.uri);
-        }
+    Ok(())
+}
 
-        println!("\n🔧 Available Tools:");
-        for tool in server.list_tools() {
-            println!("  - {}", tool.name);
+/// Fill unset command-line options from a profile
+fn apply_profile(cli: &mut Cli, overrides: &config_v2::ConfigOverrides) {
+    if cli.path.is_empty()
+        && let Some(path) = &overrides.claude_path
+    {
+        cli.path = vec![path.clone()];
+    }
+    if cli.model_filter.is_none() {
+        cli.model_filter = overrides.model_filter.clone();
+    }
+    if cli.since.is_none() {
+        cli.since = overrides.since.clone();
+    }
+    match overrides.output_format {
+        Some(config_v2::OutputFormat::Json) => cli.json = true,
+        Some(config_v2::OutputFormat::Table) => cli.classic = true,
+        _ => {}
+    }
+    if let Some(timezone) = &overrides.timezone {
+        // SAFETY: called at startup, before any other thread is spawned
+        unsafe { std::env::set_var("TZ", timezone) };
+    }
+}
+
+/// Fill in flags the command line and profile left unset from the `defaults`
+/// config section; an explicit output mode replaces all default output modes
+fn apply_default_flags(cli: &mut Cli, defaults: &config_v2::DefaultFlagsConfig) {
+    let explicit_mode =
+        cli.json || cli.classic || cli.compact || cli.responsive || cli.tui || cli.format.is_some();
+    if !explicit_mode {
+        cli.json = defaults.json.unwrap_or(false);
+        cli.classic = defaults.classic.unwrap_or(false);
+        cli.compact = defaults.compact.unwrap_or(false);
+        cli.responsive = defaults.responsive.unwrap_or(false);
+    }
+    if cli.model_filter.is_none() {
+        cli.model_filter = defaults.model_filter.clone();
+    }
+    if cli.currency.is_none() {
+        cli.currency = defaults.currency.clone();
+    }
+    if cli.cost_mode.is_none()
+        && !cli.recalculate_costs
+        && let Some(mode) = &defaults.cost_mode
+    {
+        match CliCostMode::from_str(mode, true) {
+            Ok(mode) => cli.cost_mode = Some(mode),
+            Err(_) => print_warning(&format!(
+                "Ignoring unknown defaults.cost_mode '{}' in the config file",
+                mode
+            )),
         }
     }
+}
+
+/// `config --edit`: open the config in the user's editor, then check it
+fn handle_config_edit_command() -> Result<()> {
+    let path = config_v2::AppConfig::config_path()?;
+    if !path.exists() {
+        config_v2::AppConfig::default().save()?;
+    }
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    // Editors are often given with arguments, e.g. `code --wait`
+    let mut words = editor.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("$EDITOR is empty"))?;
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .map_err(|e| anyhow::anyhow!("Failed to start editor '{}': {}", editor, e))?;
+    if !status.success() {
+        anyhow::bail!("Editor '{}' exited with {}", editor, status);
+    }
+
+    config_v2::AppConfig::load()?;
+    print_info(&format!("Configuration is valid: {}", path.display()));
+    Ok(())
+}
+
+/// `config --set key=value`
+fn handle_config_set_command(assignments: &[String]) -> Result<()> {
+    let mut app_config = config_v2::AppConfig::load()?;
+    for assignment in assignments {
+        let (key, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Expected KEY=VALUE, got '{}'", assignment))?;
+        app_config.set(key.trim(), value.trim())?;
+    }
+    app_config.save()?;
+    for assignment in assignments {
+        print_info(&format!("Set {}", assignment.trim()));
+    }
+    Ok(())
+}
+
+/// Save a profile built from the global flags
+fn handle_create_profile_command(
+    name: &str,
+    description: Option<&str>,
+    overrides: config_v2::ConfigOverrides,
+) -> Result<()> {
+    if name.is_empty() || name.contains(['/', '\\', '.']) {
+        anyhow::bail!("Profile names cannot be empty or contain '/', '\\', or '.'");
+    }
+    if [overrides.daily_limit, overrides.monthly_limit]
+        .into_iter()
+        .flatten()
+        .any(|limit| limit <= 0.0)
+    {
+        anyhow::bail!("Budget limits must be positive");
+    }
+
+    let mut app_config = config_v2::AppConfig::load()?;
+    let replaced = app_config.profiles.contains_key(name);
+    app_config.add_profile(config_v2::ProfileConfig {
+        name: name.to_string(),
+        description: description.unwrap_or_default().to_string(),
+        overrides,
+    });
+    app_config.save()?;
+    print_info(&format!(
+        "Profile '{}' {}. Use it with: claudelytics --profile {} <command>",
+        name,
+        if replaced { "replaced" } else { "saved" },
+        name
+    ));
+    Ok(())
+}
+
+fn handle_delete_profile_command(name: &str) -> Result<()> {
+    let mut app_config = config_v2::AppConfig::load()?;
+    if app_config.remove_profile(name).is_none() {
+        anyhow::bail!("Profile '{}' not found", name);
+    }
+    app_config.save()?;
+    print_info(&format!("Profile '{}' deleted", name));
+    Ok(())
+}
+
+fn handle_list_profiles_command() -> Result<()> {
+    use colored::Colorize;
+
+    let app_config = config_v2::AppConfig::load()?;
+    let mut names = app_config.list_profiles();
+    if names.is_empty() {
+        print_info(
+            "No profiles saved. Example: claudelytics --path ~/work-claude config --create-profile work",
+        );
+        return Ok(());
+    }
+    names.sort();
 
+    for name in names {
+        let profile = &app_config.profiles[name];
+        let overrides = &profile.overrides;
+        if profile.description.is_empty() {
+            println!("{}", name.bold());
+        } else {
+            println!("{}  {}", name.bold(), profile.description.dimmed());
+        }
+        let mut settings = Vec::new();
+        if let Some(path) = &overrides.claude_path {
+            settings.push(format!("path {}", path.display()));
+        }
+        if let Some(limit) = overrides.daily_limit {
+            settings.push(format!("daily limit {:.2}", Money(limit)));
+        }
+        if let Some(limit) = overrides.monthly_limit {
+            settings.push(format!("monthly limit {:.2}", Money(limit)));
+        }
+        if let Some(timezone) = &overrides.timezone {
+            settings.push(format!("timezone {}", timezone));
+        }
+        if let Some(model) = &overrides.model_filter {
+            settings.push(format!("model {}", model));
+        }
+        if let Some(since) = &overrides.since {
+            settings.push(format!("since {}", since));
+        }
+        if let Some(format) = &overrides.output_format {
+            settings.push(format!("output {}", format));
+        }
+        if !settings.is_empty() {
+            println!("  {}", settings.join(" · "));
+        }
+    }
     Ok(())
 }
 
 /// Handle configuration management commands
 fn handle_config_command(
-    config: &mut Config,
+    mut config: config_v2::AppConfig,
     show: bool,
     reset: bool,
     set_path: Option<PathBuf>,
 ) -> Result<()> {
+    let config_path = config_v2::AppConfig::config_path()?;
+
     if reset {
-        *config = Config::default();
-        config.save()?;
+        // Start from an empty file so no v1 keys or stale sections survive
+        if config_path.exists() {
+            std::fs::remove_file(&config_path)?;
+        }
+        config_v2::AppConfig::default().save()?;
         print_info("Configuration reset to defaults");
         return Ok(());
     }
 
     if let Some(path) = set_path {
-        config.set_claude_path(path.clone());
+        config.core.claude_path = Some(path.clone());
         config.save()?;
         print_info(&format!("Claude path set to: {}", path.display()));
         return Ok(());
     }
 
-    if show {
-        println!("Current Configuration:");
-        println!("Claude Path: {:?}", config.claude_path);
-        println!("Default Output Format: {:?}", config.default_output_format);
-        println!("Default Command: {:?}", config.default_command);
-        println!("Export Directory: {:?}", config.export_directory);
-        println!("Date Format: {}", config.date_format);
-        println!("Config File: {:?}", Config::config_path()?);
+    if show {
+        println!("Current Configuration:");
+        println!("Claude Path: {:?}", config.core.claude_path);
+        if !config.core.claude_paths.is_empty() {
+            println!("Claude Paths: {:?}", config.core.claude_paths);
+        }
+        println!("Default Output Format: {}", config.display.output_format);
+        println!("Default Command: {}", config.core.default_command);
+        println!("Export Directory: {:?}", config.export.directory);
+        println!("Date Format: {}", config.display.date_format);
+        let flags = config.defaults.describe();
+        if !flags.is_empty() {
+            println!("Default Flags: {}", flags.join(" "));
+        }
+        println!("Config File: {:?}", config_path);
+    }
+
+    Ok(())
+}
+
+/// `config --migrate`
+fn handle_config_migrate_command() -> Result<()> {
+    match config::migrate()? {
+        Some(backup) => print_info(&format!(
+            "Configuration migrated to the v2 layout; the original is saved as {}",
+            backup.display()
+        )),
+        None => print_info("Configuration already uses the v2 layout"),
+    }
+    Ok(())
+}
+
+/// Print per-file parse problems to stderr
+fn report_parse_issues(issues: &parser::ParseIssues) {
+    use colored::Colorize;
+
+    if issues.is_empty() {
+        eprintln!("{} No parse errors or unknown models", "✓".green());
+        return;
+    }
+
+    print_warning(&format!(
+        "{} unparsable lines and {} records with unknown models in {} files",
+        issues.invalid_lines(),
+        issues.unknown_model_records(),
+        issues.files.len()
+    ));
+    for (path, file) in &issues.files {
+        let mut problems = Vec::new();
+        if file.invalid_lines > 0 {
+            problems.push(format!("{} unparsable lines", file.invalid_lines));
+        }
+        if !file.unknown_models.is_empty() {
+            let models: Vec<String> = file
+                .unknown_models
+                .iter()
+                .map(|(model, count)| format!("{} ({})", model, count))
+                .collect();
+            problems.push(format!("unknown models: {}", models.join(", ")));
+        }
+        eprintln!("  {}: {}", path.display(), problems.join("; "));
+    }
+    eprintln!(
+        "  {}",
+        "Run `claudelytics doctor` for details and suggested fixes".dimmed()
+    );
+}
+
+/// Handle the bench command: time the parser and compare with the saved run
+fn handle_bench_command(
+    parser: &UsageParser,
+    runs: usize,
+    cached: bool,
+    no_save: bool,
+    top: usize,
+    json: bool,
+) -> Result<()> {
+    if cached {
+        // Fill the parse cache so every timed run is warm
+        parser.parse_all()?;
+    }
+    let current = bench::run_bench(parser, runs, cached)?;
+    let previous = bench::load_last_run().map(|last| bench::BenchComparison::new(&last, &current));
+    if !no_save && let Err(e) = bench::save_run(&current) {
+        print_warning(&format!("Failed to save benchmark: {}", e));
+    }
+    bench::display_bench_report(&bench::BenchReport { current, previous }, top, json);
+    Ok(())
+}
+
+/// Handle the import command: read a usage export into the import store, or list/clear it
+fn handle_import_command(
+    file: Option<&Path>,
+    format: ImportFormatArg,
+    source: Option<&str>,
+    list: bool,
+    clear: bool,
+) -> Result<()> {
+    use import::{ImportFormat, ImportStore};
+
+    if clear {
+        ImportStore::clear()?;
+        print_info("Imported usage removed");
+        return Ok(());
+    }
+
+    let mut store = ImportStore::load()?;
+
+    if list {
+        if store.records.is_empty() {
+            print_info("No imported usage");
+            return Ok(());
+        }
+        let mut sources: std::collections::BTreeMap<
+            &str,
+            (usize, chrono::NaiveDate, chrono::NaiveDate),
+        > = std::collections::BTreeMap::new();
+        for record in &store.records {
+            let entry = sources
+                .entry(&record.source)
+                .or_insert((0, record.date, record.date));
+            entry.0 += 1;
+            entry.1 = entry.1.min(record.date);
+            entry.2 = entry.2.max(record.date);
+        }
+        for (source, (rows, first, last)) in sources {
+            println!("{:<24} {:>6} rows  {} to {}", source, rows, first, last);
+        }
+        return Ok(());
+    }
+
+    let Some(file) = file else {
+        anyhow::bail!("--file is required");
+    };
+    let format = match format {
+        ImportFormatArg::AnthropicConsole => ImportFormat::AnthropicConsole,
+        ImportFormatArg::Generic => ImportFormat::Generic,
+    };
+    let source = source.unwrap_or(format.default_source());
+
+    let records = import::read_import_file(file, format, source)?;
+    let count = records.len();
+    let replaced = store.merge(records);
+    store.save()?;
+    print_info(&format!(
+        "Imported {} day/model rows from {} as source '{}' ({} replaced)",
+        count,
+        file.display(),
+        source,
+        replaced
+    ));
+    Ok(())
+}
+
+/// Handle budget management commands
+fn handle_budget_command(action: &BudgetAction) -> Result<()> {
+    let mut app_config = config_v2::AppConfig::load()?;
+
+    match action {
+        BudgetAction::Set {
+            daily,
+            monthly,
+            yearly,
+            alert_threshold,
+            cycle_start_day,
+        } => {
+            if daily.is_none()
+                && monthly.is_none()
+                && yearly.is_none()
+                && alert_threshold.is_none()
+                && cycle_start_day.is_none()
+            {
+                anyhow::bail!(
+                    "Nothing to set. Use --daily, --monthly, --yearly, --alert-threshold, or --cycle-start-day"
+                );
+            }
+            let budget = &mut app_config.budget;
+            budget.daily_limit = daily.or(budget.daily_limit);
+            budget.monthly_limit = monthly.or(budget.monthly_limit);
+            budget.yearly_limit = yearly.or(budget.yearly_limit);
+            budget.alert_threshold = alert_threshold.unwrap_or(budget.alert_threshold);
+            budget.billing_cycle_start_day =
+                cycle_start_day.unwrap_or(budget.billing_cycle_start_day);
+            app_config.validate()?;
+            app_config.save()?;
+            print_info("Budget saved");
+        }
+        BudgetAction::Clear => {
+            // The billing cycle is not a limit, so it survives clearing
+            app_config.budget = config_v2::BudgetConfig {
+                billing_cycle_start_day: app_config.budget.billing_cycle_start_day,
+                ..Default::default()
+            };
+            app_config.save()?;
+            print_info("Budget limits cleared");
+            return Ok(());
+        }
+        BudgetAction::Show => {}
+    }
+
+    let budget = &app_config.budget;
+    let format_limit = |limit: Option<f64>| {
+        limit
+            .map(|l| format!("{:.2}", Money(l)))
+            .unwrap_or_else(|| "not set".to_string())
+    };
+    println!("💰 Budget Configuration");
+    println!("Daily Limit: {}", format_limit(budget.daily_limit));
+    println!("Monthly Limit: {}", format_limit(budget.monthly_limit));
+    println!("Yearly Limit: {}", format_limit(budget.yearly_limit));
+    println!("Alert Threshold: {:.0}%", budget.alert_threshold * 100.0);
+    println!(
+        "Billing Cycle Starts: day {} of each month",
+        budget.billing_cycle_start_day
+    );
+    println!("Config File: {:?}", config_v2::AppConfig::config_path()?);
+    if !budget.has_limits() {
+        print_info("No limits saved. Example: claudelytics budget set --daily 20");
+    }
+
+    Ok(())
+}
+
+fn handle_goals_command(action: &GoalsAction) -> Result<()> {
+    let mut app_config = config_v2::AppConfig::load()?;
+
+    match action {
+        GoalsAction::Set {
+            daily_cost,
+            daily_tokens,
+            weekly_cost,
+            weekly_tokens,
+        } => {
+            if daily_cost.is_none()
+                && daily_tokens.is_none()
+                && weekly_cost.is_none()
+                && weekly_tokens.is_none()
+            {
+                anyhow::bail!(
+                    "Nothing to set. Use --daily-cost, --daily-tokens, --weekly-cost, or --weekly-tokens"
+                );
+            }
+            let goals = &mut app_config.goals;
+            goals.max_daily_cost = daily_cost.or(goals.max_daily_cost);
+            goals.max_daily_tokens = daily_tokens.or(goals.max_daily_tokens);
+            goals.max_weekly_cost = weekly_cost.or(goals.max_weekly_cost);
+            goals.max_weekly_tokens = weekly_tokens.or(goals.max_weekly_tokens);
+            app_config.validate()?;
+            app_config.save()?;
+            print_info("Goals saved. Run `claudelytics goals` to see your streak");
+        }
+        GoalsAction::Clear => {
+            app_config.goals = config_v2::GoalsConfig::default();
+            app_config.save()?;
+            print_info("Goals cleared");
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_bookmarks_command(
+    action: &BookmarkAction,
+    claude_dirs: &[PathBuf],
+    json: bool,
+) -> Result<()> {
+    let mut store = bookmarks::BookmarkStore::load()?;
+
+    match action {
+        BookmarkAction::Add {
+            session,
+            note,
+            tags,
+        } => {
+            // Already bookmarked sessions resolve even if their files are gone
+            let key = store
+                .find(session)
+                .or_else(|_| bookmarks::resolve_session(claude_dirs, session))?;
+            let created = !store.contains(&key);
+            if let Some(note) = note {
+                store.entry(&key).note = Some(note.clone());
+            }
+            store.add_tags(&key, tags);
+            store.save()?;
+            print_info(&format!(
+                "{} bookmark: {}",
+                if created { "Added" } else { "Updated" },
+                key
+            ));
+        }
+        BookmarkAction::Remove { session } => {
+            let key = store.find(session)?;
+            store.remove(&key);
+            store.save()?;
+            print_info(&format!("Removed bookmark: {}", key));
+        }
+        BookmarkAction::List { tag } => {
+            let bookmarks: Vec<&bookmarks::Bookmark> = store
+                .bookmarks
+                .iter()
+                .filter(|b| tag.as_ref().is_none_or(|t| b.tags.contains(t)))
+                .collect();
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&bookmarks)?);
+                return Ok(());
+            }
+            if bookmarks.is_empty() {
+                print_info(
+                    "No bookmarks. Example: claudelytics bookmarks add <session> --note \"...\"",
+                );
+                return Ok(());
+            }
+
+            use comfy_table::{Cell, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .apply_modifier(UTF8_ROUND_CORNERS)
+                .set_header(vec![
+                    Cell::new("Project"),
+                    Cell::new("Session"),
+                    Cell::new("Note"),
+                    Cell::new("Tags"),
+                    Cell::new("Added"),
+                ]);
+            for bookmark in bookmarks {
+                table.add_row(vec![
+                    Cell::new(bookmark.project()),
+                    Cell::new(bookmark.session_id()),
+                    Cell::new(bookmark.note.as_deref().unwrap_or("")),
+                    Cell::new(bookmark.tags.join(", ")),
+                    Cell::new(
+                        bookmark
+                            .created_at
+                            .with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M"),
+                    ),
+                ]);
+            }
+            println!("{}", table);
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_tags_command(action: &TagAction, claude_dirs: &[PathBuf], json: bool) -> Result<()> {
+    let mut app_config = config_v2::AppConfig::load()?;
+    let tags = &mut app_config.tags;
+
+    let message = match action {
+        TagAction::Add {
+            session,
+            tags: new_tags,
+        } => {
+            let key = bookmarks::match_session(tags.sessions.keys().cloned(), session)
+                .or_else(|_| bookmarks::resolve_session(claude_dirs, session))?;
+            let session_tags = tags.sessions.entry(key.clone()).or_default();
+            for tag in new_tags {
+                if !session_tags.contains(tag) {
+                    session_tags.push(tag.clone());
+                }
+            }
+            format!("Tagged {}: {}", key, session_tags.join(", "))
+        }
+        TagAction::Remove {
+            session,
+            tags: removed,
+        } => {
+            let key = bookmarks::match_session(tags.sessions.keys().cloned(), session)?;
+            let session_tags = tags.sessions.entry(key.clone()).or_default();
+            session_tags.retain(|tag| !removed.is_empty() && !removed.contains(tag));
+            if session_tags.is_empty() {
+                tags.sessions.remove(&key);
+                format!("Removed all tags from {}", key)
+            } else {
+                format!("Tags of {}: {}", key, session_tags.join(", "))
+            }
+        }
+        TagAction::Map {
+            pattern,
+            tags: new_tags,
+        } => {
+            match tags
+                .projects
+                .iter_mut()
+                .find(|rule| rule.pattern == *pattern)
+            {
+                Some(rule) => rule.tags = new_tags.clone(),
+                None => tags.projects.push(config_v2::ProjectTagRule {
+                    pattern: pattern.clone(),
+                    tags: new_tags.clone(),
+                }),
+            }
+            format!(
+                "Projects matching {} are tagged: {}",
+                pattern,
+                new_tags.join(", ")
+            )
+        }
+        TagAction::Unmap { pattern } => {
+            let before = tags.projects.len();
+            tags.projects.retain(|rule| rule.pattern != *pattern);
+            if tags.projects.len() == before {
+                anyhow::bail!("No project rule for '{}'", pattern);
+            }
+            format!("Removed project rule: {}", pattern)
+        }
+        TagAction::List => {
+            if json {
+                println!("{}", serde_json::to_string_pretty(&tags)?);
+                return Ok(());
+            }
+            if tags.projects.is_empty() && tags.sessions.is_empty() {
+                print_info("No tags. Example: claudelytics tags map '~/work/*' work");
+                return Ok(());
+            }
+
+            use comfy_table::{Cell, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+            if !tags.projects.is_empty() {
+                let mut table = Table::new();
+                table
+                    .load_preset(UTF8_FULL)
+                    .apply_modifier(UTF8_ROUND_CORNERS)
+                    .set_header(vec![Cell::new("Project pattern"), Cell::new("Tags")]);
+                for rule in &tags.projects {
+                    table.add_row(vec![
+                        Cell::new(&rule.pattern),
+                        Cell::new(rule.tags.join(", ")),
+                    ]);
+                }
+                println!("{}", table);
+            }
+            if !tags.sessions.is_empty() {
+                let mut table = Table::new();
+                table
+                    .load_preset(UTF8_FULL)
+                    .apply_modifier(UTF8_ROUND_CORNERS)
+                    .set_header(vec![Cell::new("Session"), Cell::new("Tags")]);
+                for (session, session_tags) in &tags.sessions {
+                    table.add_row(vec![Cell::new(session), Cell::new(session_tags.join(", "))]);
+                }
+                println!("{}", table);
+            }
+            return Ok(());
+        }
+    };
+
+    app_config.save()?;
+    print_info(&message);
+    Ok(())
+}
+
+/// Show a `--group-by` report
+fn display_group_report(report: &models::GroupReport, json: bool) {
+    if report.groups.is_empty() {
+        print_warning("No usage data found for the specified date range");
+    } else if json {
+        display_report_json(report);
+    } else {
+        display::display_group_report_table(report);
+    }
+}
+
+/// `--split-by schedule`: usage per period and bucket, then each bucket's total
+fn display_schedule_split(
+    usage_events: &[burn_rate::UsageEvent],
+    config: &config_v2::ScheduleConfig,
+    period: reports::GroupPeriod,
+    sort_field: Option<reports::SortField>,
+    sort_order: Option<reports::SortOrder>,
+    json: bool,
+) -> Result<()> {
+    let schedule = schedule::Schedule::new(config)?;
+    let report =
+        reports::generate_schedule_report(usage_events, &schedule, period, sort_field, sort_order);
+    if report.groups.is_empty() || json {
+        display_group_report(&report, json);
+        return Ok(());
+    }
+    display::display_group_report_table(&report);
+    println!();
+    let totals = reports::generate_schedule_report(
+        usage_events,
+        &schedule,
+        reports::GroupPeriod::Total,
+        Some(reports::SortField::Cost),
+        None,
+    );
+    display::display_group_report_table(&totals);
+    Ok(())
+}
+
+/// Handle `export --group-by`: daily rows split by group, sessions rolled up per group
+#[allow(clippy::too_many_arguments)]
+fn handle_grouped_export_command(
+    daily_groups: &models::GroupReport,
+    session_groups: &models::GroupReport,
+    daily_report: &models::DailyReport,
+    session_report: &models::SessionReport,
+    export_daily: bool,
+    export_sessions: bool,
+    export_summary: bool,
+    output_path: &Option<PathBuf>,
+    format: ExportFormat,
+    config: &config_v2::AppConfig,
+) -> Result<()> {
+    let base_path = output_path
+        .clone()
+        .unwrap_or_else(|| config.get_export_directory().join("claudelytics_export"));
+    let ext = format.extension();
+    let export_all = !export_daily && !export_sessions && !export_summary;
+
+    if export_daily || export_all {
+        let path = base_path.with_extension(format!("daily.{}", ext));
+        export::export_groups(daily_groups, &path, format)?;
+        print_info(&format!(
+            "Daily report by {} exported to: {}",
+            daily_groups.group_by,
+            path.display()
+        ));
+    }
+
+    if export_sessions || export_all {
+        let path = base_path.with_extension(format!("sessions.{}", ext));
+        export::export_groups(session_groups, &path, format)?;
+        print_info(&format!(
+            "Sessions by {} exported to: {}",
+            session_groups.group_by,
+            path.display()
+        ));
+    }
+
+    if export_summary || export_all {
+        let path = base_path.with_extension(format!("summary.{}", ext));
+        export::export_summary(daily_report, session_report, &path, format)?;
+        print_info(&format!("Summary exported to: {}", path.display()));
     }
 
     Ok(())
 }
 
 /// Handle data export commands
+#[allow(clippy::too_many_arguments)]
 fn handle_export_command(
-    daily_report: &crate::models::DailyReport,
-    session_report: &crate::models::SessionReport,
+    daily_report: &models::DailyReport,
+    session_report: &models::SessionReport,
     export_daily: bool,
     export_sessions: bool,
     export_summary: bool,
     output_path: &Option<PathBuf>,
-    config: &Config,
+    format: ExportFormat,
+    config: &config_v2::AppConfig,
+    daily_columns: Option<&[columns::Column<models::DailyUsage>]>,
+    session_columns: Option<&[columns::Column<models::SessionUsage>]>,
 ) -> Result<()> {
     let base_path = output_path
         .clone()
         .unwrap_or_else(|| config.get_export_directory().join("claudelytics_export"));
+    let ext = format.extension();
+    let write_daily = |path: &Path| match daily_columns {
+        Some(columns) => export::export_columns(
+            columns,
+            &daily_report.daily,
+            &daily_report.totals,
+            "daily",
+            path,
+            format,
+        ),
+        None => export::export_daily(daily_report, path, format),
+    };
+    let write_sessions = |path: &Path| match session_columns {
+        Some(columns) => export::export_columns(
+            columns,
+            &session_report.sessions,
+            &session_report.totals,
+            "sessions",
+            path,
+            format,
+        ),
+        None => export::export_sessions(session_report, path, format),
+    };
 
     if export_daily {
-        let path = base_path.with_extension("daily.csv");
-        export_daily_to_csv(daily_report, &path)?;
+        let path = base_path.with_extension(format!("daily.{}", ext));
+        write_daily(&path)?;
         print_info(&format!("Daily report exported to: {}", path.display()));
     }
 
     if export_sessions {
-        let path = base_path.with_extension("sessions.csv");
-        export_sessions_to_csv(session_report, &path)?;
+        let path = base_path.with_extension(format!("sessions.{}", ext));
+        write_sessions(&path)?;
         print_info(&format!("Sessions report exported to: {}", path.display()));
     }
 
     if export_summary {
-        let path = base_path.with_extension("summary.csv");
-        export_summary_to_csv(daily_report, session_report, &path)?;
+        let path = base_path.with_extension(format!("summary.{}", ext));
+        export::export_summary(daily_report, session_report, &path, format)?;
         print_info(&format!("Summary exported to: {}", path.display()));
     }
 
     if !export_daily && !export_sessions && !export_summary {
         // Export all by default
-        let daily_path = base_path.with_extension("daily.csv");
-        let sessions_path = base_path.with_extension("sessions.csv");
-        let summary_path = base_path.with_extension("summary.csv");
+        let daily_path = base_path.with_extension(format!("daily.{}", ext));
+        let sessions_path = base_path.with_extension(format!("sessions.{}", ext));
+        let summary_path = base_path.with_extension(format!("summary.{}", ext));
 
-        export_daily_to_csv(daily_report, &daily_path)?;
-        export_sessions_to_csv(session_report, &sessions_path)?;
-        export_summary_to_csv(daily_report, session_report, &summary_path)?;
+        write_daily(&daily_path)?;
+        write_sessions(&sessions_path)?;
+        export::export_summary(daily_report, session_report, &summary_path, format)?;
 
         print_info(&format!("All reports exported to: {}", base_path.display()));
     }
@@ -1731,7 +5417,7 @@ fn handle_export_command(
 
 /// Handle cost summary commands
 fn handle_cost_command(
-    daily_report: &crate::models::DailyReport,
+    daily_report: &models::DailyReport,
     today_only: bool,
     specific_date: Option<&str>,
 ) -> Result<()> {
@@ -1740,41 +5426,36 @@ fn handle_cost_command(
         if let Some(daily_usage) = daily_report.daily.iter().find(|d| d.date == today) {
             println!("💰 Today's Usage Cost");
             println!("Date: {}", daily_usage.date);
-            println!("Cost: ${:.4}", daily_usage.total_cost);
+            println!("Cost: {:.4}", Money(daily_usage.total_cost));
             println!("Tokens: {}", daily_usage.total_tokens);
         } else {
             print_warning("No usage data found for today");
         }
     } else if let Some(date_str) = specific_date {
-        // Parse YYYYMMDD format to YYYY-MM-DD
-        if date_str.len() == 8 {
-            let formatted_date = format!(
-                "{}-{}-{}",
-                &date_str[0..4],
-                &date_str[4..6],
-                &date_str[6..8]
-            );
-            if let Some(daily_usage) = daily_report.daily.iter().find(|d| d.date == formatted_date)
-            {
-                println!("💰 Usage Cost for {}", formatted_date);
-                println!("Date: {}", daily_usage.date);
-                println!("Cost: ${:.4}", daily_usage.total_cost);
-                println!("Tokens: {}", daily_usage.total_tokens);
-            } else {
-                print_warning(&format!("No usage data found for {}", formatted_date));
-            }
+        let formatted_date = date_range::parse_date(date_str, Local::now().date_naive())?
+            .format("%Y-%m-%d")
+            .to_string();
+        if let Some(daily_usage) = daily_report.daily.iter().find(|d| d.date == formatted_date) {
+            println!("💰 Usage Cost for {}", formatted_date);
+            println!("Date: {}", daily_usage.date);
+            println!("Cost: {:.4}", Money(daily_usage.total_cost));
+            println!("Tokens: {}", daily_usage.total_tokens);
         } else {
-            anyhow::bail!("Date must be in YYYYMMDD format");
+            print_warning(&format!("No usage data found for {}", formatted_date));
         }
     } else {
         // Show total cost summary
         println!("💰 Total Cost Summary");
-        println!("Total Cost: ${:.4}", daily_report.totals.total_cost);
+        println!("Total Cost: {:.4}", Money(daily_report.totals.total_cost));
         println!("Total Tokens: {}", daily_report.totals.total_tokens);
         println!("Days with usage: {}", daily_report.daily.len());
 
         if let Some(latest) = daily_report.daily.first() {
-            println!("Latest usage: {} (${:.4})", latest.date, latest.total_cost);
+            println!(
+                "Latest usage: {} ({:.4})",
+                latest.date,
+                Money(latest.total_cost)
+            );
         }
     }
 
@@ -1794,7 +5475,8 @@ fn save_tui_state(tui_app: &TuiApp, mode: TuiMode) -> Result<()> {
     } else {
         Some(tui_app.get_search_query())
     };
-    state.bookmarked_sessions = tui_app.get_bookmarked_sessions();
+    // Bookmarks live in bookmarks.yaml; the state field only feeds migration
+    state.bookmarked_sessions.clear();
     state.comparison_sessions = tui_app.get_comparison_sessions();
     state.last_session_path = tui_app.get_selected_session_path();
 
@@ -1815,9 +5497,9 @@ fn restore_tui_state(tui_app: &mut TuiApp, state: &TuiSessionState) {
         tui_app.set_search_query(search_query.clone());
     }
 
-    // Restore bookmarked sessions
+    // Move bookmarks saved by older versions into bookmarks.yaml
     if !state.bookmarked_sessions.is_empty() {
-        tui_app.set_bookmarked_sessions(state.bookmarked_sessions.clone());
+        let _ = tui_app.import_legacy_bookmarks(&state.bookmarked_sessions);
     }
 
     // Restore comparison sessions
@@ -1842,12 +5524,19 @@ fn handle_debug_state_command() -> Result<()> {
     println!("Last Session Path: {:?}", state.last_session_path);
     println!("Last Search Query: {:?}", state.last_search_query);
     println!(
-        "Bookmarked Sessions: {} items",
+        "Legacy Bookmarks (migrated on next TUI start): {} items",
         state.bookmarked_sessions.len()
     );
     for (i, bookmark) in state.bookmarked_sessions.iter().enumerate() {
         println!("  {}. {}", i + 1, bookmark);
     }
+    if let Ok(store) = bookmarks::BookmarkStore::load() {
+        println!(
+            "Bookmarks: {} items in {:?}",
+            store.bookmarks.len(),
+            bookmarks::BookmarkStore::store_path()?
+        );
+    }
     println!(
         "Comparison Sessions: {} items",
         state.comparison_sessions.len()
@@ -1858,11 +5547,10 @@ fn handle_debug_state_command() -> Result<()> {
     println!("Timestamp: {}", state.timestamp);
     println!("Should Resume: {}", state.should_resume());
 
-    let state_path = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    let state_file = format!("{}/.claude/claudelytics/tui_session.json", state_path);
-    println!("State File: {}", state_file);
+    let state_file = TuiSessionState::get_state_path()?;
+    println!("State File: {}", state_file.display());
 
-    if std::path::Path::new(&state_file).exists() {
+    if state_file.exists() {
         println!("\n📄 Raw State File Content:");
         if let Ok(content) = std::fs::read_to_string(&state_file) {
             println!("{}", content);
@@ -1876,8 +5564,8 @@ fn handle_debug_state_command() -> Result<()> {
 
 /// Handle test resume command to verify resume functionality
 fn handle_test_resume_command(
-    daily_report: crate::models::DailyReport,
-    session_report: crate::models::SessionReport,
+    daily_report: models::DailyReport,
+    session_report: models::SessionReport,
     billing_manager: &billing_blocks::BillingBlockManager,
 ) -> Result<()> {
     print_info("🧪 Testing resume functionality...");
@@ -1888,10 +5576,6 @@ fn handle_test_resume_command(
     // Set some test state
     tui_app.set_current_tab(2); // Sessions tab
     tui_app.set_search_query("test_query".to_string());
-    tui_app.set_bookmarked_sessions(vec![
-        "test/bookmark1".to_string(),
-        "test/bookmark2".to_string(),
-    ]);
     tui_app.set_comparison_sessions(vec!["test/comparison1".to_string()]);
 
     print_info("  ✓ Set test state in TUI app");
@@ -1918,7 +5602,6 @@ fn handle_test_resume_command(
     // Verify state was restored correctly
     let restored_tab = new_tui_app.get_current_tab_index();
     let restored_query = new_tui_app.get_search_query();
-    let restored_bookmarks = new_tui_app.get_bookmarked_sessions();
     let restored_comparisons = new_tui_app.get_comparison_sessions();
 
     println!("🔍 Verification Results:");
@@ -1936,15 +5619,6 @@ fn handle_test_resume_command(
             "❌"
         }
     );
-    println!(
-        "  Bookmarks: {} (expected: 2) {}",
-        restored_bookmarks.len(),
-        if restored_bookmarks.len() == 2 {
-            "✓"
-        } else {
-            "❌"
-        }
-    );
     println!(
         "  Comparisons: {} (expected: 1) {}",
         restored_comparisons.len(),
@@ -1955,11 +5629,7 @@ fn handle_test_resume_command(
         }
     );
 
-    if restored_tab == 2
-        && restored_query == "test_query"
-        && restored_bookmarks.len() == 2
-        && restored_comparisons.len() == 1
-    {
+    if restored_tab == 2 && restored_query == "test_query" && restored_comparisons.len() == 1 {
         print_info("🎉 Resume functionality test PASSED!");
     } else {
         print_error("❌ Resume functionality test FAILED!");
@@ -2003,9 +5673,18 @@ fn display_billing_blocks_enhanced(
 ) {
     use colored::Colorize;
 
+    let mode = match report.anchor {
+        billing_blocks::BlockAnchor::Fixed => "fixed",
+        billing_blocks::BlockAnchor::Rolling => "rolling",
+    };
     println!(
         "\n{}",
-        "📊 Claude Usage by 5-Hour Billing Blocks".bold().cyan()
+        format!(
+            "📊 Claude Usage by {}-Hour Billing Blocks ({})",
+            report.duration_hours, mode
+        )
+        .bold()
+        .cyan()
     );
     println!("{}", "═".repeat(50).blue());
 
@@ -2032,10 +5711,10 @@ fn display_billing_blocks_enhanced(
         };
 
         println!(
-            "  {} │ {} tokens │ ${:.4} │ {} sessions",
+            "  {} │ {} tokens │ {:.4} │ {} sessions",
             block.time_range.cyan(),
             format!("{:>8}", block.usage.total_tokens()).white(),
-            block.usage.total_cost.to_string().color(cost_color),
+            Money(block.usage.total_cost).to_string().color(cost_color),
             block.session_count
         );
     }
@@ -2056,9 +5735,9 @@ fn display_billing_blocks_enhanced(
 
         // Average usage
         println!(
-            "Average per Block: {} tokens, ${:.4}",
+            "Average per Block: {} tokens, {:.4}",
             report.average_per_block.total_tokens(),
-            report.average_per_block.total_cost
+            Money(report.average_per_block.total_cost)
         );
 
         // Usage by time of day
@@ -2082,7 +5761,7 @@ fn display_billing_blocks_enhanced(
     println!("\n{}", "💰 Total Usage".bold().cyan());
     println!("{}", "─".repeat(40));
     println!("Total Tokens: {}", report.total_usage.total_tokens());
-    println!("Total Cost: ${:.4}", report.total_usage.total_cost);
+    println!("Total Cost: {:.4}", Money(report.total_usage.total_cost));
     println!("Active Blocks: {}", report.blocks.len());
 }
 
@@ -2101,7 +5780,7 @@ fn display_billing_blocks_table(report: &billing_blocks::BillingBlockReport) {
             Cell::new("Input Tokens"),
             Cell::new("Output Tokens"),
             Cell::new("Total Tokens"),
-            Cell::new("Cost ($)"),
+            Cell::new(currency::cost_header()),
             Cell::new("Sessions"),
         ]);
 
@@ -2132,8 +5811,13 @@ fn display_billing_blocks_table(report: &billing_blocks::BillingBlockReport) {
 }
 
 /// Handle pricing cache command
-fn handle_pricing_cache_command(show: bool, clear: bool, update: bool) -> Result<()> {
-    use pricing_cache::PricingCache;
+fn handle_pricing_cache_command(
+    show: bool,
+    clear: bool,
+    update: bool,
+    source: Option<&str>,
+) -> Result<()> {
+    use pricing_cache::{DEFAULT_PRICING_SOURCE, PricingCache, UpdateOutcome};
 
     if show {
         println!("📦 Pricing Cache Status");
@@ -2155,6 +5839,10 @@ fn handle_pricing_cache_command(show: bool, clear: bool, update: bool) -> Result
                     }
                 );
                 println!("Version: {}", cache.version);
+                println!(
+                    "Source: {}",
+                    cache.source.as_deref().unwrap_or("built-in fallback data")
+                );
                 println!("Models Cached: {}", cache.pricing_data.len());
 
                 if cache.is_valid() {
@@ -2176,14 +5864,30 @@ fn handle_pricing_cache_command(show: bool, clear: bool, update: bool) -> Result
         PricingCache::clear()?;
         println!("✅ Pricing cache cleared successfully");
     } else if update {
-        print_info("Updating pricing cache...");
+        let source = source.unwrap_or(DEFAULT_PRICING_SOURCE);
+        print_info(&format!("Fetching pricing from {}...", source));
 
-        // For now, just create a new cache with fallback data
-        let new_cache = PricingCache::new();
-        new_cache.save()?;
-
-        println!("✅ Pricing cache updated successfully");
-        println!("Cache will remain valid for 7 days");
+        match PricingCache::update_from_source(source) {
+            Ok(UpdateOutcome::Updated { model_count }) => {
+                println!(
+                    "✅ Pricing cache updated successfully ({} models)",
+                    model_count
+                );
+                println!("Cache will remain valid for 7 days");
+            }
+            Ok(UpdateOutcome::NotModified) => {
+                println!("✅ Pricing is unchanged since the last update");
+                println!("Cache will remain valid for 7 days");
+            }
+            Err(e) => {
+                print_warning(&format!("Failed to update pricing: {:#}", e));
+                if PricingCache::load().ok().flatten().is_some() {
+                    println!("Keeping the existing pricing cache");
+                } else {
+                    println!("Using built-in fallback pricing data");
+                }
+            }
+        }
     } else {
         // Show help if no flags provided
         println!("Use --show, --clear, or --update to manage the pricing cache");
@@ -2200,12 +5904,13 @@ struct BlocksCommandOptions {
     recent: bool,
     token_limit: Option<u64>,
     cost_limit: Option<f64>,
-    since: Option<String>,
-    until: Option<String>,
 }
 
 /// Handle session blocks command
-fn handle_blocks_command(claude_dir: &Path, options: BlocksCommandOptions) -> Result<()> {
+fn handle_blocks_command(
+    usage_events: &[burn_rate::UsageEvent],
+    options: BlocksCommandOptions,
+) -> Result<()> {
     use colored::Colorize;
 
     // Create session block configuration
@@ -2215,22 +5920,10 @@ fn handle_blocks_command(claude_dir: &Path, options: BlocksCommandOptions) -> Re
         cost_limit: options.cost_limit,
     };
 
-    // Parse usage data
-    let parser = UsageParser::new(
-        claude_dir.to_path_buf(),
-        options.since.clone(),
-        options.until.clone(),
-        None, // No model filter for session blocks
-    )?;
-    let (_daily_map, session_map, _billing_manager) = parser.parse_all()?;
-
-    // Create session block manager
+    // Every record counts towards the block it was made in, so long sessions
+    // are split across blocks
     let mut block_manager = SessionBlockManager::new(config.clone());
-
-    // Add all usage records to blocks
-    for (session_path, (usage, last_activity)) in &session_map {
-        block_manager.add_usage(*last_activity, usage, session_path);
-    }
+    block_manager.add_events(usage_events);
 
     // Generate report
     let report = block_manager.generate_report();
@@ -2243,7 +5936,7 @@ fn handle_blocks_command(claude_dir: &Path, options: BlocksCommandOptions) -> Re
         println!("Token Limit: {}", format_number(limit));
     }
     if let Some(limit) = options.cost_limit {
-        println!("Cost Limit: ${:.2}", limit);
+        println!("Cost Limit: {:.2}", Money(limit));
     }
     println!();
 
@@ -2266,7 +5959,7 @@ fn handle_blocks_command(claude_dir: &Path, options: BlocksCommandOptions) -> Re
     } else {
         // Sort blocks by start time (newest first)
         let mut sorted_blocks = blocks_to_show;
-        sorted_blocks.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+        sorted_blocks.sort_by_key(|b| std::cmp::Reverse(b.start_time));
 
         // Display blocks
         for block in sorted_blocks {
@@ -2278,24 +5971,25 @@ fn handle_blocks_command(claude_dir: &Path, options: BlocksCommandOptions) -> Re
             );
 
             println!(
-                "{} {} │ {} tokens │ ${:.4} │ {} sessions",
+                "{} {} │ {} tokens │ {:.4} │ {} sessions",
                 is_active_indicator,
                 time_range.cyan(),
                 format!("{:>8}", block.usage.total_tokens()).white(),
-                block.usage.total_cost,
+                Money(block.usage.total_cost),
                 block.session_count
             );
 
             // Show burn rate for active blocks
             if let Some(ref burn_rate) = block.burn_rate {
                 println!(
-                    "   ├─ Burn Rate: {} tokens/hr, ${:.2}/hr",
-                    burn_rate.tokens_per_hour as u64, burn_rate.cost_per_hour
+                    "   ├─ Burn Rate: {} tokens/hr, {:.2}/hr",
+                    burn_rate.tokens_per_hour as u64,
+                    Money(burn_rate.cost_per_hour)
                 );
                 println!(
-                    "   ├─ Projected Daily: {} tokens, ${:.2}",
+                    "   ├─ Projected Daily: {} tokens, {:.2}",
                     format_number(burn_rate.projected_daily_tokens),
-                    burn_rate.projected_daily_cost
+                    Money(burn_rate.projected_daily_cost)
                 );
 
                 if let Some(time_to_limit) = burn_rate.time_to_limit {
@@ -2311,17 +6005,18 @@ fn handle_blocks_command(claude_dir: &Path, options: BlocksCommandOptions) -> Re
             println!("\n{}", "🔥 Current Burn Rate".bold().yellow());
             println!("{}", "─".repeat(40));
             println!(
-                "Hourly: {} tokens, ${:.2}",
-                current_burn.tokens_per_hour as u64, current_burn.cost_per_hour
+                "Hourly: {} tokens, {:.2}",
+                current_burn.tokens_per_hour as u64,
+                Money(current_burn.cost_per_hour)
             );
             println!(
-                "Daily Projection: {} tokens, ${:.2}",
+                "Daily Projection: {} tokens, {:.2}",
                 format_number(current_burn.projected_daily_tokens),
-                current_burn.projected_daily_cost
+                Money(current_burn.projected_daily_cost)
             );
             println!(
-                "Monthly Projection: ${:.2}",
-                current_burn.projected_monthly_cost
+                "Monthly Projection: {:.2}",
+                Money(current_burn.projected_monthly_cost)
             );
         }
 
@@ -2333,36 +6028,136 @@ fn handle_blocks_command(claude_dir: &Path, options: BlocksCommandOptions) -> Re
             "Total Tokens: {}",
             format_number(report.total_usage.total_tokens())
         );
-        println!("Total Cost: ${:.4}", report.total_usage.total_cost);
+        println!("Total Cost: {:.4}", Money(report.total_usage.total_cost));
+    }
+
+    Ok(())
+}
+
+/// Print the error metrics of `projections --backtest` and the replayed days
+fn display_projection_backtest(
+    report: &projections::BacktestReport,
+    requested_days: i64,
+    json: bool,
+) -> Result<()> {
+    use colored::Colorize;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(report)?);
+        return Ok(());
+    }
+
+    println!("\n{}", "🧪 Projection Backtest".bold().cyan());
+    println!("{}", "═".repeat(50).blue());
+    if report.days == 0 {
+        println!("No usage history before the last {} days", requested_days);
+        return Ok(());
+    }
+    println!(
+        "Replayed {} of the last {} days, each forecast from the history before it",
+        report.days, requested_days
+    );
+    println!("Actual Spend: {:.2}", Money(report.actual_total));
+
+    println!(
+        "\n{:<16} {:>10} {:>10} {:>8} {:>10} {:>10}",
+        "Model", "MAE", "RMSE", "MAPE", "Bias", "Predicted"
+    );
+    println!("{}", "─".repeat(69));
+    for (name, metrics) in [
+        ("Naive", &report.naive),
+        ("Weekday-aware", &report.seasonal),
+    ] {
+        println!(
+            "{:<16} {:>10} {:>10} {:>8} {:>10} {:>10}",
+            name,
+            format!("{:.2}", Money(metrics.mae)),
+            format!("{:.2}", Money(metrics.rmse)),
+            metrics
+                .mape
+                .map(|mape| format!("{:.1}%", mape))
+                .unwrap_or_else(|| "-".to_string()),
+            format!("{:+.2}", metrics.bias),
+            format!("{:.2}", Money(metrics.predicted_total))
+        );
+    }
+    match report.better {
+        Some(projections::ForecastModel::Seasonal) => {
+            println!(
+                "\nThe {} forecast was closer (lower MAE)",
+                "weekday-aware".bold()
+            )
+        }
+        Some(projections::ForecastModel::Naive) => {
+            println!("\nThe {} forecast was closer (lower MAE)", "naive".bold())
+        }
+        None => println!("\nBoth forecasts were equally close"),
     }
 
+    println!("\n{}", "📅 Replayed Days".bold());
+    println!("{}", "─".repeat(50));
+    println!(
+        "{:<16} {:>10} {:>10} {:>10}",
+        "Day", "Actual", "Naive", "Seasonal"
+    );
+    for day in &report.daily {
+        println!(
+            "{:<16} {:>10} {:>10} {:>10}",
+            day.date.format("%Y-%m-%d %a").to_string(),
+            format!("{:.2}", Money(day.actual)),
+            format!("{:.2}", Money(day.naive)),
+            format!("{:.2}", Money(day.seasonal))
+        );
+    }
     Ok(())
 }
 
 /// Handle projections command
+#[allow(clippy::too_many_arguments)]
 fn handle_projections_command(
-    claude_dir: &Path,
+    claude_dirs: &[PathBuf],
     days: i64,
     token_limit: Option<u64>,
     cost_limit: Option<f64>,
+    billing_cycle_start_day: u32,
+    backtest: Option<i64>,
     json: bool,
     since: Option<String>,
+    model_filter: Option<String>,
+    cost_mode: parser::CostMode,
+    currency: &currency::Currency,
+    use_parse_cache: bool,
+    dedup: bool,
 ) -> Result<()> {
     use colored::Colorize;
 
     // Parse usage data
-    let parser = UsageParser::new(
-        claude_dir.to_path_buf(),
-        since,
-        None,
-        None, // No model filter for projections
-    )?;
-    let (daily_usage, _, _) = parser.parse_all()?;
+    let parser =
+        UsageParser::new_multi(claude_dirs.to_vec(), since, None, model_filter, cost_mode)?
+            .with_parse_cache(use_parse_cache)
+            .with_dedup(dedup);
+    let (mut daily_usage, mut session_usage, mut billing_manager) = parser.parse_all()?;
+    currency.convert(
+        &mut daily_usage,
+        &mut session_usage,
+        &mut billing_manager,
+        &mut [],
+    );
 
     // Calculate projections
     let calculator = ProjectionCalculator::new()
         .with_projection_days(days)
-        .with_limits(token_limit, cost_limit);
+        .with_limits(token_limit, cost_limit)
+        .with_billing_cycle_start_day(billing_cycle_start_day);
+
+    if let Some(backtest_days) = backtest {
+        if backtest_days < 1 {
+            anyhow::bail!("--backtest needs at least 1 day");
+        }
+        let report =
+            calculator.backtest_history(&daily_usage, backtest_days, Utc::now().date_naive());
+        return display_projection_backtest(&report, backtest_days, json);
+    }
 
     let projection = calculator.calculate_projections(&daily_usage);
 
@@ -2377,9 +6172,9 @@ fn handle_projections_command(
         // Current averages
         println!("\n{}", "📈 Current Usage Patterns".bold());
         println!("{}", "─".repeat(40));
-        println!("Daily Average: ${:.2}", projection.daily_average);
-        println!("Weekly Average: ${:.2}", projection.weekly_average);
-        println!("Monthly Average: ${:.2}", projection.monthly_average);
+        println!("Daily Average: {:.2}", Money(projection.daily_average));
+        println!("Weekly Average: {:.2}", Money(projection.weekly_average));
+        println!("Monthly Average: {:.2}", Money(projection.monthly_average));
 
         // Trend analysis
         let trend_emoji = match projection.trend {
@@ -2402,8 +6197,8 @@ fn handle_projections_command(
         println!("\n{}", "🔮 Future Projections".bold());
         println!("{}", "─".repeat(40));
         println!(
-            "Estimated Monthly Cost: ${:.2}",
-            projection.estimated_monthly_cost
+            "Estimated Monthly Cost: {:.2}",
+            Money(projection.estimated_monthly_cost)
         );
 
         if let Some(days_until) = projection.days_until_limit
@@ -2418,6 +6213,34 @@ fn handle_projections_command(
             );
         }
 
+        // Current billing cycle
+        let cycle = &projection.billing_cycle;
+        println!("\n{}", "🗓️  Billing Cycle".bold());
+        println!("{}", "─".repeat(40));
+        println!(
+            "Cycle: {} to {} ({} days remaining)",
+            cycle.cycle_start.format("%Y-%m-%d"),
+            cycle.cycle_end.format("%Y-%m-%d"),
+            cycle.days_remaining
+        );
+        println!("Spent This Cycle: {:.2}", Money(cycle.spent_to_date));
+        match cycle.limit {
+            Some(limit) => println!(
+                "End-of-Cycle Forecast: {:.2} / {:.2}",
+                Money(cycle.projected_total),
+                Money(limit)
+            ),
+            None => println!("End-of-Cycle Forecast: {:.2}", Money(cycle.projected_total)),
+        }
+        if let Some(overage) = cycle.projected_overage {
+            println!(
+                "{}",
+                format!("⚠️  Projected Overage: {:.2}", Money(overage))
+                    .red()
+                    .bold()
+            );
+        }
+
         // Show projection details for key dates
         if !projection.projections.is_empty() {
             println!("\n{}", "📅 Projection Details".bold());
@@ -2427,13 +6250,66 @@ fn handle_projections_command(
             for days_ahead in &[7, 14, 30] {
                 if let Some(proj) = projection.projections.get((*days_ahead - 1) as usize) {
                     println!(
-                        "{} days: ${:.2} (${:.2} - ${:.2})",
-                        days_ahead, proj.value, proj.lower_bound, proj.upper_bound
+                        "{} days: {:.2} ({:.2} - {:.2})",
+                        days_ahead,
+                        Money(proj.value),
+                        Money(proj.lower_bound),
+                        Money(proj.upper_bound)
                     );
                 }
             }
         }
 
+        // Weekday-aware forecast next to the naive one
+        let seasonal = &projection.seasonal;
+        println!("\n{}", "📆 Weekday-Aware Forecast".bold());
+        println!("{}", "─".repeat(40));
+        println!(
+            "Weekday Baseline: {:.2}/day  Weekend Baseline: {:.2}/day",
+            Money(seasonal.weekday_baseline),
+            Money(seasonal.weekend_baseline)
+        );
+        println!(
+            "Estimated Monthly Cost: {:.2} (naive {:.2})",
+            Money(seasonal.estimated_monthly_cost),
+            Money(projection.estimated_monthly_cost)
+        );
+        if !seasonal.projections.is_empty() {
+            println!("{:<16} {:>10} {:>10}", "Day", "Seasonal", "Naive");
+            for (seasonal_day, naive_day) in seasonal
+                .projections
+                .iter()
+                .zip(&projection.projections)
+                .take(7)
+            {
+                println!(
+                    "{:<16} {:>10} {:>10}",
+                    seasonal_day.date.format("%Y-%m-%d %a").to_string(),
+                    format!("{:.2}", Money(seasonal_day.value)),
+                    format!("{:.2}", Money(naive_day.value))
+                );
+            }
+        }
+        match &seasonal.backtest {
+            Some(backtest) => {
+                let better = match backtest.better {
+                    projections::ForecastModel::Seasonal => "weekday-aware",
+                    projections::ForecastModel::Naive => "naive",
+                };
+                println!(
+                    "Backtest (last {} days): naive MAPE {:.1}%, weekday-aware MAPE {:.1}% — {} fits better",
+                    backtest.holdout_days,
+                    backtest.naive_mape,
+                    backtest.seasonal_mape,
+                    better.bold()
+                );
+            }
+            None => println!(
+                "{}",
+                "Backtest needs at least two weeks of history".bright_black()
+            ),
+        }
+
         // Recommendations
         println!("\n{}", "💡 Recommendations".bold());
         println!("{}", "─".repeat(40));
@@ -2522,8 +6398,8 @@ fn handle_analytics_command(
             business_pct
         );
         println!(
-            "  Cost: ${:.4}",
-            time_analysis.business_hours_usage.total_cost
+            "  Cost: {:.4}",
+            Money(time_analysis.business_hours_usage.total_cost)
         );
 
         println!("\nAfter Hours:");
@@ -2532,7 +6408,10 @@ fn handle_analytics_command(
             format_number(after_hours_tokens),
             100.0 - business_pct
         );
-        println!("  Cost: ${:.4}", time_analysis.after_hours_usage.total_cost);
+        println!(
+            "  Cost: {:.4}",
+            Money(time_analysis.after_hours_usage.total_cost)
+        );
 
         // Show hourly distribution
         println!("\nHourly Distribution:");
@@ -2577,10 +6456,10 @@ fn handle_analytics_command(
         ] {
             if let Some(usage) = dow_analysis.daily_usage.get(day) {
                 println!(
-                    "  {:?}: {} tokens, ${:.4}",
+                    "  {:?}: {} tokens, {:.4}",
                     day,
                     format_number(usage.total_tokens()),
-                    usage.total_cost
+                    Money(usage.total_cost)
                 );
             }
         }
@@ -2656,7 +6535,10 @@ fn handle_analytics_command(
 
         println!("\nMost Expensive Session:");
         println!("  Path: {}", eff_analysis.most_expensive_session.path);
-        println!("  Cost: ${:.4}", eff_analysis.most_expensive_session.cost);
+        println!(
+            "  Cost: {:.4}",
+            Money(eff_analysis.most_expensive_session.cost)
+        );
         println!(
             "  Tokens: {}",
             format_number(eff_analysis.most_expensive_session.tokens)
@@ -2673,9 +6555,9 @@ fn handle_analytics_command(
         println!("  Efficiency: {:.0} tokens/$", eff);
 
         if !eff_analysis.sessions_above_threshold.is_empty() {
-            println!("\n⚠️  Sessions Above ${} Threshold:", threshold);
+            println!("\n⚠️  Sessions Above {} Threshold:", Money(threshold));
             for session in &eff_analysis.sessions_above_threshold {
-                println!("  - {} (${:.4})", session.path, session.cost);
+                println!("  - {} ({:.4})", session.path, Money(session.cost));
             }
         }
     }
@@ -2732,28 +6614,130 @@ fn handle_cache_command(
     Ok(())
 }
 
+fn handle_top_command(
+    claude_dir: &Path,
+    since: Option<&str>,
+    until: Option<&str>,
+    by: TopMetric,
+    limit: usize,
+    json: bool,
+) -> Result<()> {
+    use chrono::NaiveDate;
+    let since_date = since.and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok());
+    let until_date = until.and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok());
+    let rank_by = match by {
+        TopMetric::Cost => top_conversations::RankBy::Cost,
+        TopMetric::Tokens => top_conversations::RankBy::Tokens,
+    };
+    let conversations = top_conversations::find_top_conversations(
+        claude_dir,
+        rank_by,
+        limit,
+        since_date,
+        until_date,
+        currency::current(),
+    )?;
+    top_conversations::display_top_conversations(&conversations, rank_by, json);
+    Ok(())
+}
+
+fn handle_tools_command(
+    claude_dir: &Path,
+    since: Option<&str>,
+    until: Option<&str>,
+    project: Option<&str>,
+    by_project: bool,
+    top: usize,
+    json: bool,
+) -> Result<()> {
+    use chrono::NaiveDate;
+    let since_date = since.and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok());
+    let until_date = until.and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok());
+    let report = tool_usage::analyze_tool_usage(claude_dir, since_date, until_date, project)?;
+    tool_usage::display_tool_usage(&report, json, by_project, top);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_tickets_command(
+    claude_dir: &Path,
+    since: Option<&str>,
+    until: Option<&str>,
+    config: &config_v2::TicketsConfig,
+    patterns: &[String],
+    project: Option<&str>,
+    limit: usize,
+    csv: bool,
+    output: Option<&Path>,
+    json: bool,
+) -> Result<()> {
+    use chrono::NaiveDate;
+    let since_date = since.and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok());
+    let until_date = until.and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok());
+    let matcher = tickets::TicketMatcher::new(config, patterns)?;
+    let report = tickets::analyze_tickets(claude_dir, &matcher, since_date, until_date, project)?;
+
+    let content = if json {
+        Some(serde_json::to_string_pretty(&report)? + "\n")
+    } else if csv || output.is_some() {
+        Some(tickets::report_to_csv(&report)?)
+    } else {
+        None
+    };
+    match (content, output) {
+        (Some(content), Some(path)) => {
+            std::fs::write(path, content)?;
+            print_info(&format!("Ticket report written to: {}", path.display()));
+        }
+        (Some(content), None) => print!("{}", content),
+        (None, _) => tickets::display_ticket_report(&report, limit),
+    }
+    Ok(())
+}
+
+fn handle_search_command(
+    claude_dir: &Path,
+    since: Option<&str>,
+    until: Option<&str>,
+    mut query: search::SearchQuery,
+    limit: usize,
+    rebuild: bool,
+    json: bool,
+) -> Result<()> {
+    use chrono::NaiveDate;
+    query.since = since.and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok());
+    query.until = until.and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok());
+
+    let mut index = if rebuild {
+        search::SearchIndex::default()
+    } else {
+        search::SearchIndex::load()
+    };
+    let update = index.update(claude_dir)?;
+    if (update.indexed > 0 || update.removed > 0)
+        && let Err(e) = index.save()
+    {
+        eprintln!("Warning: failed to save search index: {}", e);
+    }
+    let hits = index.search(&query)?;
+    search::display_search_results(&hits, limit, json);
+    Ok(())
+}
+
 /// Handle real-time analytics command
 fn handle_realtime_analytics_command(
     daily_map: &models::DailyUsageMap,
     session_map: &SessionUsageMap,
-    daily_limit: Option<f64>,
-    monthly_limit: Option<f64>,
-    yearly_limit: Option<f64>,
-    alert_threshold: f64,
+    usage_events: &[burn_rate::UsageEvent],
+    budget_config: realtime_analytics::BudgetConfig,
+    alerts: &config_v2::AlertsConfig,
     json: bool,
 ) -> Result<()> {
-    use realtime_analytics::{BudgetConfig, RealtimeAnalytics, format_realtime_analytics};
-
-    // Create budget configuration
-    let budget_config = BudgetConfig {
-        daily_limit,
-        monthly_limit,
-        yearly_limit,
-        alert_threshold,
-    };
+    use realtime_analytics::{RealtimeAnalytics, format_realtime_analytics};
 
     // Create real-time analytics instance
-    let analytics = RealtimeAnalytics::new(daily_map, session_map, budget_config);
+    let analytics = RealtimeAnalytics::new(daily_map, session_map, budget_config)
+        .with_usage_events(usage_events);
 
     // Generate comprehensive report
     let report = analytics.generate_report();
@@ -2767,6 +6751,12 @@ fn handle_realtime_analytics_command(
         println!("{}", formatted_output);
     }
 
+    // Trigger configured alert hooks for threshold crossings
+    let mut hooks = alerts::AlertHooks::new(alerts.clone());
+    for event in report.alert_events() {
+        hooks.fire(&event);
+    }
+
     Ok(())
 }
 
@@ -2774,22 +6764,40 @@ fn handle_realtime_analytics_command(
 #[allow(clippy::too_many_arguments)]
 fn handle_conversation_command(
     claude_dir: &Path,
+    since: Option<&str>,
+    until: Option<&str>,
     session: Option<String>,
     project: Option<String>,
     search: Option<String>,
     export: Option<String>,
     output: Option<PathBuf>,
+    split_files: bool,
     recent: bool,
     mode: String,
     include_thinking: bool,
     include_tools: bool,
     list: bool,
+    redactor: Option<&redact::Redactor>,
 ) -> Result<()> {
+    use chrono::NaiveDate;
     use colored::Colorize;
     use conversation_display::{ConversationDisplay, DisplayMode};
     use conversation_parser::{Conversation, ConversationParser};
 
     let parser = ConversationParser::new(claude_dir.to_path_buf());
+    let since_date = since.and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok());
+    let until_date = until.and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok());
+    // Conversations with a message within --since/--until (local dates)
+    let in_date_range = |conversation: &Conversation| -> bool {
+        if since_date.is_none() && until_date.is_none() {
+            return true;
+        }
+        let (Some(start), Some(end)) = (conversation.started_at, conversation.ended_at) else {
+            return false;
+        };
+        since_date.is_none_or(|since| end.with_timezone(&Local).date_naive() >= since)
+            && until_date.is_none_or(|until| start.with_timezone(&Local).date_naive() <= until)
+    };
 
     // Find all conversation files
     let mut conversation_files = parser.find_conversation_files()?;
@@ -2812,6 +6820,8 @@ fn handle_conversation_command(
         });
     }
 
+    let mut redaction_report = redact::RedactionReport::default();
+
     // If listing conversations
     if list {
         println!("{}", "📋 Available Conversations".bold().cyan());
@@ -2822,28 +6832,44 @@ fn handle_conversation_command(
             return Ok(());
         }
 
-        for (idx, file_path) in conversation_files.iter().enumerate() {
+        let mut listed = 0;
+        for file_path in &conversation_files {
+            let parsed = parser.parse_conversation(file_path);
+            if parsed
+                .as_ref()
+                .is_ok_and(|conversation| !in_date_range(conversation))
+            {
+                continue;
+            }
+            listed += 1;
+
             // Extract project and session from path
             let path_str = file_path.to_string_lossy();
             let relative_path = path_str
                 .strip_prefix(&format!("{}/projects/", claude_dir.display()))
                 .unwrap_or(&path_str);
 
-            println!("{}. {}", idx + 1, relative_path.dimmed());
+            println!("{}. {}", listed, relative_path.dimmed());
 
             // Try to parse and show summary
-            if let Ok(conversation) = parser.parse_conversation(file_path) {
+            if let Ok(mut conversation) = parsed {
+                if let Some(redactor) = redactor {
+                    redactor.redact_conversation(&mut conversation, &mut redaction_report);
+                }
                 if let Some(summary) = &conversation.summary {
                     println!("   📄 {}", summary.summary.bright_white());
                 }
                 println!(
-                    "   💬 {} messages | 💰 ${:.4} | 📊 {} tokens",
+                    "   💬 {} messages | 💰 {:.4} | 📊 {} tokens",
                     conversation.messages.len(),
-                    conversation.total_usage.total_cost,
+                    Money(conversation.total_usage.total_cost),
                     conversation.total_usage.total_tokens()
                 );
             }
         }
+        if redactor.is_some() {
+            redact::display_redaction_report(&redaction_report);
+        }
         return Ok(());
     }
 
@@ -2910,11 +6936,19 @@ fn handle_conversation_command(
         });
     }
 
+    filtered_conversations.retain(|conversation| in_date_range(conversation));
+
     if filtered_conversations.is_empty() {
         print_warning("No conversations found matching criteria");
         return Ok(());
     }
 
+    if let Some(redactor) = redactor {
+        for conv in &mut filtered_conversations {
+            redactor.redact_conversation(conv, &mut redaction_report);
+        }
+    }
+
     // Set display mode
     let display_mode = match mode.as_str() {
         "compact" => DisplayMode::Compact,
@@ -2927,51 +6961,83 @@ fn handle_conversation_command(
 
     // Handle export
     if let Some(export_format) = &export {
-        let content = match export_format.as_str() {
-            "json" => {
-                // Export as JSON
-                serde_json::to_string_pretty(
-                    &filtered_conversations
-                        .iter()
-                        .map(|conv| {
-                            serde_json::json!({
-                                "file_path": conv.file_path,
-                                "summary": conv.summary,
-                                "messages": conv.messages.len(),
-                                "total_tokens": conv.total_usage.total_tokens(),
-                                "total_cost": conv.total_usage.total_cost,
-                                "started_at": conv.started_at,
-                                "ended_at": conv.ended_at,
-                                "conversation": conv.messages
+        let render = |conversations: &[Conversation]| -> Result<String> {
+            Ok(match export_format.as_str() {
+                "json" => {
+                    // Export as JSON
+                    serde_json::to_string_pretty(
+                        &conversations
+                            .iter()
+                            .map(|conv| {
+                                serde_json::json!({
+                                    "file_path": conv.file_path,
+                                    "summary": conv.summary,
+                                    "messages": conv.messages.len(),
+                                    "total_tokens": conv.total_usage.total_tokens(),
+                                    "total_cost": conv.total_usage.total_cost,
+                                    "started_at": conv.started_at,
+                                    "ended_at": conv.ended_at,
+                                    "conversation": conv.messages
+                                })
                             })
-                        })
-                        .collect::<Vec<_>>(),
-                )?
-            }
-            "markdown" => {
-                // Export as markdown
-                let mut markdown = String::new();
-                for conv in &filtered_conversations {
-                    markdown.push_str(&format_conversation_as_markdown(
-                        conv,
-                        include_thinking,
-                        include_tools,
-                    ));
-                    markdown.push_str("\n\n---\n\n");
+                            .collect::<Vec<_>>(),
+                    )?
+                }
+                "markdown" => {
+                    // Export as markdown
+                    let mut markdown = String::new();
+                    for conv in conversations {
+                        markdown.push_str(&format_conversation_as_markdown(
+                            conv,
+                            include_thinking,
+                            include_tools,
+                        ));
+                        markdown.push_str("\n\n---\n\n");
+                    }
+                    markdown
                 }
-                markdown
-            }
-            _ => {
-                // Default to text export
-                let mut text = String::new();
-                for conv in &filtered_conversations {
-                    text.push_str(&display.format_conversation(conv));
-                    text.push_str("\n\n");
+                "html" => conversation_html::render_conversations_html(
+                    conversations,
+                    include_thinking,
+                    include_tools,
+                ),
+                _ => {
+                    // Default to text export
+                    let mut text = String::new();
+                    for conv in conversations {
+                        text.push_str(&display.format_conversation(conv));
+                        text.push_str("\n\n");
+                    }
+                    text
                 }
-                text
-            }
+            })
         };
 
+        if split_files {
+            // clap requires --output with --split-files
+            let dir = output.unwrap_or_default();
+            let exported = conversation_export::export_split_files(
+                &filtered_conversations,
+                &claude_dir.join("projects"),
+                &dir,
+                export_format,
+                &pricing_cache::PricingCache::effective_pricing(),
+                |conversation| render(std::slice::from_ref(conversation)),
+            )?;
+            print_info(&format!(
+                "Exported {} conversations to {} (index: {})",
+                exported.len(),
+                dir.display(),
+                dir.join(conversation_export::INDEX_FILE).display()
+            ));
+            if redactor.is_some() {
+                redact::display_redaction_report(&redaction_report);
+            }
+            return Ok(());
+        }
+
+        let content = render(&filtered_conversations)?;
+
         // Write to file or stdout
         if let Some(output_path) = output {
             std::fs::write(&output_path, content)?;
@@ -2999,6 +7065,10 @@ fn handle_conversation_command(
         }
     }
 
+    if redactor.is_some() {
+        redact::display_redaction_report(&redaction_report);
+    }
+
     Ok(())
 }
 
@@ -3038,8 +7108,8 @@ fn format_conversation_as_markdown(
         conversation.total_usage.total_tokens()
     ));
     markdown.push_str(&format!(
-        "**Total Cost:** ${:.4}\n\n",
-        conversation.total_usage.total_cost
+        "**Total Cost:** {:.4}\n\n",
+        Money(conversation.total_usage.total_cost)
     ));
 
     markdown.push_str("---\n\n");
@@ -3176,6 +7246,7 @@ fn format_number(num: u64) -> String {
 fn handle_inspect_command(
     claude_dir: &Path,
     session_map: &SessionUsageMap,
+    resumed: &HashMap<String, Vec<String>>,
     target: Option<String>,
     project: Option<String>,
     recent: bool,
@@ -3183,11 +7254,60 @@ fn handle_inspect_command(
     json: bool,
     conversations: bool,
     timeline: bool,
+    context_usage: bool,
 ) -> Result<()> {
     use colored::Colorize;
     use conversation_parser::ConversationParser;
     use serde_json::json;
 
+    let registry = models_registry::ModelsRegistry::new();
+    // A session and, with --group-resumes, the sessions resumed from it
+    let chain = |session_path: &str| -> Vec<String> {
+        std::iter::once(session_path.to_string())
+            .chain(resumed.get(session_path).into_iter().flatten().cloned())
+            .collect()
+    };
+    // Conversation files of a session (and its resumes)
+    let session_files = |session_path: &str| -> Vec<PathBuf> {
+        let members = chain(session_path);
+        ConversationParser::new(claude_dir.to_path_buf())
+            .find_conversation_files()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|path| {
+                let path = path.to_string_lossy();
+                members.iter().any(|member| path.contains(member.as_str()))
+            })
+            .collect()
+    };
+    // All conversations of a session
+    let session_conversations = |session_path: &str| -> Vec<conversation_parser::Conversation> {
+        let parser = ConversationParser::new(claude_dir.to_path_buf());
+        session_files(session_path)
+            .iter()
+            .filter_map(|path| parser.parse_conversation(path).ok())
+            .collect()
+    };
+    // Prompt size of every turn of a session
+    let session_context_timeline = |session_path: &str| -> Vec<context_usage::ContextPoint> {
+        let mut points: Vec<_> = session_conversations(session_path)
+            .iter()
+            .flat_map(|conversation| context_usage::context_timeline(conversation, &registry))
+            .collect();
+        points.sort_by_key(|point| point.timestamp);
+        points
+    };
+    let pricing_data = pricing_cache::PricingCache::effective_pricing();
+    // Messages, tokens, and cost of a session per 15-minute interval
+    let session_activity_timeline = |session_path: &str| -> Vec<session_timeline::TimelineBucket> {
+        let conversations = session_conversations(session_path);
+        let messages: Vec<_> = conversations
+            .iter()
+            .flat_map(|conversation| &conversation.messages)
+            .collect();
+        session_timeline::bucket_messages(&messages, &pricing_data)
+    };
+
     // Filter sessions based on criteria
     let mut sessions: Vec<_> = session_map.iter().collect();
 
@@ -3202,13 +7322,13 @@ fn handle_inspect_command(
     }
 
     if let Some(ref t) = target {
-        // Check if it's a session ID or project name
+        // Check if it's a session ID or project name; any session of a chain selects the chain
         if t.contains('-') || t.len() > 20 {
             // Looks like a session ID
-            sessions.retain(|(path, _)| path.contains(t));
+            sessions.retain(|(path, _)| chain(path).iter().any(|member| member.contains(t)));
         } else {
             // Treat as project name
-            sessions.retain(|(path, _)| path.contains(t));
+            sessions.retain(|(path, _)| chain(path).iter().any(|member| member.contains(t)));
         }
     }
 
@@ -3243,26 +7363,34 @@ fn handle_inspect_command(
                     (usage.total_tokens() as f64 / usage.total_cost) as u64
                 } else {
                     0
-                }
+                },
+                "context_peak_percent": context_usage::peak_percent(usage, &registry),
             });
 
+            if let Some(members) = resumed.get(session_path.as_str()) {
+                session_info["resumed_sessions"] = json!(members);
+            }
+
+            if context_usage {
+                session_info["context_timeline"] = json!(session_context_timeline(session_path));
+            }
+            if timeline {
+                session_info["timeline"] = json!(session_activity_timeline(session_path));
+            }
+
             if conversations {
                 // Add conversation list
-                let parser = ConversationParser::new(claude_dir.to_path_buf());
-                if let Ok(conv_files) = parser.find_conversation_files() {
-                    let session_convs: Vec<_> = conv_files
-                        .iter()
-                        .filter(|path| path.to_string_lossy().contains(session_path.as_str()))
-                        .map(|p| {
-                            p.file_name()
-                                .unwrap_or_default()
-                                .to_string_lossy()
-                                .to_string()
-                        })
-                        .collect();
-                    session_info["conversations"] = json!(session_convs);
-                    session_info["conversation_count"] = json!(session_convs.len());
-                }
+                let session_convs: Vec<_> = session_files(session_path)
+                    .iter()
+                    .map(|p| {
+                        p.file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .to_string()
+                    })
+                    .collect();
+                session_info["conversations"] = json!(session_convs);
+                session_info["conversation_count"] = json!(session_convs.len());
             }
 
             json_output.push(session_info);
@@ -3290,6 +7418,15 @@ fn handle_inspect_command(
                     .to_string()
                     .bright_white()
             );
+            if let Some(members) = resumed.get(session_path.as_str()) {
+                println!("   Resumed {} times:", members.len());
+                for member in members {
+                    let member_id = member
+                        .rsplit_once('/')
+                        .map_or(member.as_str(), |(_, id)| id);
+                    println!("   ↳ {}", member_id.bright_black());
+                }
+            }
 
             // Token usage breakdown
             println!("\n   {} Token Usage:", "📈".cyan());
@@ -3317,7 +7454,7 @@ fn handle_inspect_command(
 
             // Cost analysis
             println!("\n   {} Cost Analysis:", "💰".cyan());
-            println!("   ├─ Total Cost: ${:.6}", usage.total_cost);
+            println!("   ├─ Total Cost: {:.6}", Money(usage.total_cost));
             let efficiency = if usage.total_cost > 0.0 {
                 (usage.total_tokens() as f64 / usage.total_cost) as u64
             } else {
@@ -3325,42 +7462,51 @@ fn handle_inspect_command(
             };
             println!("   └─ Efficiency: {} tokens/$", format_number(efficiency));
 
+            if let Some(percent) = context_usage::peak_percent(usage, &registry) {
+                let line = format!(
+                    "Peak context: {} tokens ({:.0}% of the window)",
+                    format_number(usage.peak_context_tokens),
+                    percent
+                );
+                if percent >= context_usage::WARN_PERCENT {
+                    println!("\n   {} {}", "⚠️".yellow(), line.yellow());
+                } else {
+                    println!("\n   {} {}", "🧠".cyan(), line);
+                }
+            }
+            if context_usage {
+                context_usage::display_context_timeline(
+                    session_path,
+                    &session_context_timeline(session_path),
+                );
+            }
+
             if conversations || detailed {
                 // Show conversation count
-                let parser = ConversationParser::new(claude_dir.to_path_buf());
-                if let Ok(conv_files) = parser.find_conversation_files() {
-                    let session_convs: Vec<_> = conv_files
-                        .iter()
-                        .filter(|path| path.to_string_lossy().contains(session_path.as_str()))
-                        .collect();
+                let session_convs = session_files(session_path);
 
-                    println!(
-                        "\n   {} Conversations: {}",
-                        "💬".cyan(),
-                        session_convs.len()
-                    );
+                println!(
+                    "\n   {} Conversations: {}",
+                    "💬".cyan(),
+                    session_convs.len()
+                );
 
-                    if conversations && !session_convs.is_empty() {
-                        println!("   Conversation files:");
-                        for (i, conv_path) in session_convs.iter().take(5).enumerate() {
-                            let conv_name =
-                                conv_path.file_name().unwrap_or_default().to_string_lossy();
-                            println!("   {}. {}", i + 1, conv_name.bright_black());
-                        }
-                        if session_convs.len() > 5 {
-                            println!("   ... and {} more", session_convs.len() - 5);
-                        }
+                if conversations && !session_convs.is_empty() {
+                    println!("   Conversation files:");
+                    for (i, conv_path) in session_convs.iter().take(5).enumerate() {
+                        let conv_name = conv_path.file_name().unwrap_or_default().to_string_lossy();
+                        println!("   {}. {}", i + 1, conv_name.bright_black());
+                    }
+                    if session_convs.len() > 5 {
+                        println!("   ... and {} more", session_convs.len() - 5);
                     }
                 }
             }
 
             if timeline {
-                // Show activity timeline (simplified for now)
-                println!("\n   {} Activity Timeline:", "📅".cyan());
-                println!(
-                    "   └─ Active for approximately {}",
-                    format_duration(&chrono::Duration::hours(2))
-                ); // Placeholder
+                session_timeline::display_session_timeline(&session_activity_timeline(
+                    session_path,
+                ));
             }
 
             println!("\n   {}", "─".repeat(50).bright_black());
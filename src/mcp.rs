@@ -3,11 +3,49 @@
 //! Provides MCP resources and tools for accessing Claude usage analytics data
 //! through a standardized protocol that other applications can consume.
 
+use crate::models::{DailyUsageMap, SessionUsageMap};
+use crate::parser::UsageParser;
+use crate::reports::{
+    SortField, SortOrder, generate_daily_report_sorted, generate_monthly_report_sorted,
+    generate_session_report_sorted,
+};
+use anyhow::{Context, Result};
+use chrono::Local;
+use regex::Regex;
 use serde_json::{Value, json};
+use std::io::{BufRead, Write};
 use std::path::PathBuf;
 
+/// MCP protocol revision implemented by the server
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
 /// MCP server for claudelytics data access
-pub struct McpServer;
+pub struct McpServer {
+    claude_dirs: Vec<PathBuf>,
+}
+
+/// JSON-RPC error returned to the client
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
 
 /// MCP Resource definition
 #[derive(Debug, Clone)]
@@ -28,8 +66,288 @@ pub struct McpTool {
 
 impl McpServer {
     /// Create a new MCP server instance
-    pub fn new(_claude_path: PathBuf) -> Self {
-        Self
+    #[allow(dead_code)]
+    pub fn new(claude_path: PathBuf) -> Self {
+        Self::new_multi(vec![claude_path])
+    }
+
+    /// Create an MCP server reading from multiple Claude directories
+    pub fn new_multi(claude_dirs: Vec<PathBuf>) -> Self {
+        Self { claude_dirs }
+    }
+
+    /// Serve JSON-RPC messages over stdin/stdout until stdin is closed.
+    /// Each message is a single line of JSON, as required by the MCP stdio transport.
+    pub fn run_stdio(&self) -> Result<()> {
+        let stdin = std::io::stdin();
+        let mut stdout = std::io::stdout();
+
+        for line in stdin.lock().lines() {
+            let line = line.context("Failed to read from stdin")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(response) = self.handle_message(&line) {
+                writeln!(stdout, "{}", response)?;
+                stdout.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a raw JSON-RPC message. Returns `None` for notifications.
+    pub fn handle_message(&self, message: &str) -> Option<Value> {
+        match serde_json::from_str::<Value>(message) {
+            Ok(Value::Array(batch)) => {
+                let responses: Vec<Value> = batch
+                    .iter()
+                    .filter_map(|request| self.handle_request(request))
+                    .collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(request) => self.handle_request(&request),
+            Err(e) => Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, format!("Parse error: {}", e)),
+            )),
+        }
+    }
+
+    /// Handle a single decoded JSON-RPC request
+    pub fn handle_request(&self, request: &Value) -> Option<Value> {
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                RpcError::new(INVALID_REQUEST, "Missing method"),
+            ));
+        };
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = self.dispatch(method, &params);
+
+        // Notifications never receive a response, even on error
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    fn dispatch(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": {
+                    "tools": {},
+                    "resources": {}
+                },
+                "serverInfo": {
+                    "name": "claudelytics",
+                    "version": env!("CARGO_PKG_VERSION")
+                }
+            })),
+            "ping" => Ok(json!({})),
+            "notifications/initialized" | "notifications/cancelled" => Ok(Value::Null),
+            "tools/list" => Ok(json!({
+                "tools": self
+                    .list_tools()
+                    .into_iter()
+                    .map(|tool| json!({
+                        "name": tool.name,
+                        "description": tool.description,
+                        "inputSchema": tool.input_schema
+                    }))
+                    .collect::<Vec<_>>()
+            })),
+            "tools/call" => {
+                let name = params
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing tool name"))?;
+                let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+                Ok(match self.call_tool(name, &arguments) {
+                    Ok(value) => tool_result(&value, false),
+                    Err(e) => tool_result(&json!({ "error": e.to_string() }), true),
+                })
+            }
+            "resources/list" => Ok(json!({
+                "resources": self
+                    .list_resources()
+                    .into_iter()
+                    .map(|resource| json!({
+                        "uri": resource.uri,
+                        "name": resource.name,
+                        "description": resource.description,
+                        "mimeType": resource.mime_type
+                    }))
+                    .collect::<Vec<_>>()
+            })),
+            "resources/read" => {
+                let uri = params
+                    .get("uri")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing resource uri"))?;
+                let contents = self.read_resource(uri)?;
+                Ok(json!({
+                    "contents": [{
+                        "uri": uri,
+                        "mimeType": "application/json",
+                        "text": serde_json::to_string_pretty(&contents)
+                            .map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))?
+                    }]
+                }))
+            }
+            other => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", other),
+            )),
+        }
+    }
+
+    /// Read a resource by URI
+    fn read_resource(&self, uri: &str) -> Result<Value, RpcError> {
+        let internal = |e: anyhow::Error| RpcError::new(INTERNAL_ERROR, e.to_string());
+        match uri {
+            "claudelytics://daily-usage" => {
+                let (daily_map, _) = self.load_usage(None, None).map_err(internal)?;
+                to_value(&generate_daily_report_sorted(daily_map, None, None))
+            }
+            "claudelytics://session-usage" => {
+                let (_, session_map) = self.load_usage(None, None).map_err(internal)?;
+                to_value(&generate_session_report_sorted(session_map, None, None))
+            }
+            "claudelytics://monthly-usage" => {
+                let (daily_map, _) = self.load_usage(None, None).map_err(internal)?;
+                to_value(&generate_monthly_report_sorted(daily_map, None, None))
+            }
+            "claudelytics://cost-summary" => self.cost_summary(None).map_err(internal),
+            other => Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("Unknown resource: {}", other),
+            )),
+        }
+    }
+
+    /// Execute a tool with the given arguments
+    fn call_tool(&self, name: &str, arguments: &Value) -> Result<Value> {
+        match name {
+            "get_usage_data" => self.get_usage_data(arguments),
+            "get_cost_summary" => self.cost_summary(arguments.get("date").and_then(Value::as_str)),
+            "find_sessions" => self.find_sessions(arguments),
+            other => anyhow::bail!("Unknown tool: {}", other),
+        }
+    }
+
+    fn get_usage_data(&self, arguments: &Value) -> Result<Value> {
+        let report_type = arguments
+            .get("report_type")
+            .and_then(Value::as_str)
+            .context("report_type is required")?;
+        let since = string_arg(arguments, "since");
+        let until = string_arg(arguments, "until");
+        let sort_field = match arguments.get("sort_field").and_then(Value::as_str) {
+            None => None,
+            Some("date") => Some(SortField::Date),
+            Some("cost") => Some(SortField::Cost),
+            Some("tokens") => Some(SortField::Tokens),
+            Some("efficiency") => Some(SortField::Efficiency),
+            Some("project") => Some(SortField::Project),
+            Some(other) => anyhow::bail!("Invalid sort_field: {}", other),
+        };
+        let sort_order = match arguments.get("sort_order").and_then(Value::as_str) {
+            None => None,
+            Some("asc") => Some(SortOrder::Asc),
+            Some("desc") => Some(SortOrder::Desc),
+            Some(other) => anyhow::bail!("Invalid sort_order: {}", other),
+        };
+
+        let (daily_map, session_map) = self.load_usage(since, until)?;
+        let value = match report_type {
+            "daily" => serde_json::to_value(generate_daily_report_sorted(
+                daily_map, sort_field, sort_order,
+            ))?,
+            "session" => serde_json::to_value(generate_session_report_sorted(
+                session_map,
+                sort_field,
+                sort_order,
+            ))?,
+            "monthly" => serde_json::to_value(generate_monthly_report_sorted(
+                daily_map, sort_field, sort_order,
+            ))?,
+            other => anyhow::bail!("Invalid report_type: {}", other),
+        };
+        Ok(value)
+    }
+
+    /// Cost summary for a YYYYMMDD date, `today`, or all time
+    fn cost_summary(&self, date: Option<&str>) -> Result<Value> {
+        let date = match date {
+            Some("today") => Some(Local::now().date_naive().format("%Y%m%d").to_string()),
+            other => other.map(str::to_string),
+        };
+        let (daily_map, session_map) = self.load_usage(date.clone(), date.clone())?;
+        let report = generate_daily_report_sorted(daily_map, None, None);
+
+        Ok(json!({
+            "date": date,
+            "totalCost": report.totals.total_cost,
+            "totalTokens": report.totals.total_tokens,
+            "daysWithUsage": report.daily.len(),
+            "sessionCount": session_map.len(),
+            "latestDay": report.daily.first()
+        }))
+    }
+
+    fn find_sessions(&self, arguments: &Value) -> Result<Value> {
+        let project_filter = arguments
+            .get("project_filter")
+            .and_then(Value::as_str)
+            .map(Regex::new)
+            .transpose()
+            .context("Invalid project_filter regex")?;
+        let min_cost = arguments.get("min_cost").and_then(Value::as_f64);
+        let max_cost = arguments.get("max_cost").and_then(Value::as_f64);
+        let min_tokens = arguments.get("min_tokens").and_then(Value::as_u64);
+        let date_range = arguments.get("date_range").unwrap_or(&Value::Null);
+
+        let (_, session_map) = self.load_usage(
+            string_arg(date_range, "start"),
+            string_arg(date_range, "end"),
+        )?;
+        let mut report = generate_session_report_sorted(session_map, None, None);
+        report.sessions.retain(|session| {
+            project_filter
+                .as_ref()
+                .is_none_or(|re| re.is_match(&session.project_path))
+                && min_cost.is_none_or(|min| session.total_cost >= min)
+                && max_cost.is_none_or(|max| session.total_cost <= max)
+                && min_tokens.is_none_or(|min| session.total_tokens >= min)
+        });
+
+        Ok(json!({
+            "count": report.sessions.len(),
+            "sessions": report.sessions
+        }))
+    }
+
+    /// Parse usage data for the given YYYYMMDD date range
+    fn load_usage(
+        &self,
+        since: Option<String>,
+        until: Option<String>,
+    ) -> Result<(DailyUsageMap, SessionUsageMap)> {
+        let parser = UsageParser::new_multi(
+            self.claude_dirs.clone(),
+            since,
+            until,
+            None,
+            Default::default(),
+        )?;
+        let (daily_map, session_map, _) = parser.parse_all()?;
+        Ok((daily_map, session_map))
     }
 
     /// Get list of available MCP resources
@@ -151,11 +469,41 @@ impl McpServer {
     }
 }
 
+fn string_arg(arguments: &Value, key: &str) -> Option<String> {
+    arguments
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))
+}
+
+/// Wrap a tool's JSON output in an MCP `CallToolResult`
+fn tool_result(value: &Value, is_error: bool) -> Value {
+    json!({
+        "content": [{
+            "type": "text",
+            "text": serde_json::to_string_pretty(value).unwrap_or_default()
+        }],
+        "isError": is_error
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message }
+    })
+}
+
 /// MCP server capability advertisement
 pub fn get_server_info() -> Value {
     json!({
         "name": "claudelytics",
-        "version": "0.3.0",
+        "version": env!("CARGO_PKG_VERSION"),
         "description": "Claude Code usage analytics via Model Context Protocol",
         "author": "nwiizo",
         "homepage": "https://github.com/nwiizo/claudelytics",
@@ -181,6 +529,98 @@ mod tests {
         assert_eq!(server.list_tools().len(), 3);
     }
 
+    fn test_server_with_data() -> (tempfile::TempDir, McpServer) {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("projects").join("test-project");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        std::fs::write(
+            project_dir.join("session1.jsonl"),
+            r#"{"timestamp":"2024-01-15T12:00:00Z","costUSD":0.5,"message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":100,"output_tokens":200}}}
+"#,
+        )
+        .unwrap();
+        let server = McpServer::new(temp_dir.path().to_path_buf());
+        (temp_dir, server)
+    }
+
+    #[test]
+    fn test_initialize_and_notifications() {
+        let server = McpServer::new(PathBuf::from("/tmp"));
+
+        let response = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#)
+            .unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSION);
+
+        let response =
+            server.handle_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#);
+        assert!(response.is_none());
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        let server = McpServer::new(PathBuf::from("/tmp"));
+
+        let response = server.handle_message("not json").unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let response = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"bogus"}"#)
+            .unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_tools_and_resources_list() {
+        let server = McpServer::new(PathBuf::from("/tmp"));
+
+        let response = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":3,"method":"tools/list"}"#)
+            .unwrap();
+        assert_eq!(response["result"]["tools"].as_array().unwrap().len(), 3);
+        assert!(response["result"]["tools"][0]["inputSchema"].is_object());
+
+        let response = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":4,"method":"resources/list"}"#)
+            .unwrap();
+        assert_eq!(response["result"]["resources"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_tools_call_and_resources_read() {
+        let (_temp_dir, server) = test_server_with_data();
+
+        let response = server
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"get_usage_data","arguments":{"report_type":"daily"}}}"#,
+            )
+            .unwrap();
+        assert_eq!(response["result"]["isError"], false);
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        let report: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(report["totals"]["inputTokens"], 100);
+
+        let response = server
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":6,"method":"tools/call","params":{"name":"find_sessions","arguments":{"min_cost":1.0}}}"#,
+            )
+            .unwrap();
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        assert_eq!(serde_json::from_str::<Value>(text).unwrap()["count"], 0);
+
+        let response = server
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":7,"method":"resources/read","params":{"uri":"claudelytics://cost-summary"}}"#,
+            )
+            .unwrap();
+        let text = response["result"]["contents"][0]["text"].as_str().unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(text).unwrap()["totalCost"],
+            0.5
+        );
+    }
+
     #[test]
     fn test_server_info() {
         let info = get_server_info();
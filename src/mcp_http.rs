@@ -0,0 +1,560 @@
+//! HTTP + Server-Sent Events transport for the MCP server
+//!
+//! Implements the MCP HTTP/SSE transport: a client opens `GET /sse`, receives an
+//! `endpoint` event naming its message URL, then POSTs JSON-RPC requests to that
+//! URL. Responses are delivered asynchronously as `message` events on the stream.
+//! A plain `POST /messages` without a session returns the response inline, which
+//! is convenient for scripting with curl.
+
+use crate::mcp::McpServer;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+/// Maximum accepted request body size (1 MiB)
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Maximum length of the request line and of each header line
+const MAX_LINE_BYTES: usize = 8 * 1024;
+/// Maximum number of header lines
+const MAX_HEADERS: usize = 100;
+/// Time a client gets to send the whole request, so slow clients can't hold connections open
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Interval between SSE keep-alive comments
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Open SSE sessions keyed by session ID
+type Sessions = Arc<Mutex<HashMap<String, mpsc::UnboundedSender<String>>>>;
+
+/// Minimal parsed HTTP request
+pub(crate) struct HttpRequest {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) query: HashMap<String, String>,
+    pub(crate) body: Vec<u8>,
+}
+
+/// Bind to `addr` and serve MCP over HTTP until the process is interrupted
+pub fn run_http(server: McpServer, addr: &str) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().context("Failed to start async runtime")?;
+    runtime.block_on(async {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind MCP HTTP server to {}", addr))?;
+        eprintln!(
+            "claudelytics MCP HTTP server listening on http://{}/sse",
+            listener.local_addr()?
+        );
+        serve(Arc::new(server), listener).await
+    })
+}
+
+/// Accept connections on `listener`, handling each client concurrently
+pub async fn serve(server: Arc<McpServer>, listener: TcpListener) -> Result<()> {
+    let sessions: Sessions = Arc::new(Mutex::new(HashMap::new()));
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let server = Arc::clone(&server);
+        let sessions = Arc::clone(&sessions);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, server, sessions).await {
+                eprintln!("Warning: MCP HTTP connection error: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    server: Arc<McpServer>,
+    sessions: Sessions,
+) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let Some(request) = read_request(&mut reader).await? else {
+        return Ok(());
+    };
+    let mut stream = reader.into_inner();
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("OPTIONS", _) => write_response(&mut stream, "204 No Content", "text/plain", "").await,
+        ("GET", "/sse") => handle_sse(stream, sessions).await,
+        ("POST", "/messages") | ("POST", "/message") => {
+            handle_post(&mut stream, request, server, sessions).await
+        }
+        ("GET", "/") | ("GET", "/health") => {
+            let info = crate::mcp::get_server_info().to_string();
+            write_response(&mut stream, "200 OK", "application/json", &info).await
+        }
+        _ => write_response(&mut stream, "404 Not Found", "text/plain", "Not Found").await,
+    }
+}
+
+/// Open an event stream and forward responses for this session until the client leaves
+async fn handle_sse(mut stream: TcpStream, sessions: Sessions) -> Result<()> {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    sessions
+        .lock()
+        .map_err(|_| anyhow::anyhow!("session registry poisoned"))?
+        .insert(session_id.clone(), tx);
+
+    let headers = "HTTP/1.1 200 OK\r\n\
+        Content-Type: text/event-stream\r\n\
+        Cache-Control: no-cache\r\n\
+        Connection: keep-alive\r\n\
+        Access-Control-Allow-Origin: *\r\n\r\n";
+    let result = async {
+        stream.write_all(headers.as_bytes()).await?;
+        stream
+            .write_all(
+                sse_event("endpoint", &format!("/messages?sessionId={}", session_id)).as_bytes(),
+            )
+            .await?;
+        stream.flush().await?;
+
+        let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+        keepalive.tick().await;
+        loop {
+            tokio::select! {
+                message = rx.recv() => match message {
+                    Some(message) => {
+                        stream.write_all(sse_event("message", &message).as_bytes()).await?;
+                        stream.flush().await?;
+                    }
+                    None => break,
+                },
+                _ = keepalive.tick() => {
+                    stream.write_all(b": keep-alive\n\n").await?;
+                    stream.flush().await?;
+                }
+            }
+        }
+        Ok::<(), std::io::Error>(())
+    }
+    .await;
+
+    if let Ok(mut sessions) = sessions.lock() {
+        sessions.remove(&session_id);
+    }
+    // A client disconnecting is the normal way for a stream to end
+    match result {
+        Err(e) if is_disconnect(&e) => Ok(()),
+        other => other.map_err(Into::into),
+    }
+}
+
+/// Accept a JSON-RPC message, replying on the SSE stream or inline
+async fn handle_post(
+    stream: &mut TcpStream,
+    request: HttpRequest,
+    server: Arc<McpServer>,
+    sessions: Sessions,
+) -> Result<()> {
+    let body = match String::from_utf8(request.body) {
+        Ok(body) => body,
+        Err(_) => {
+            return write_response(
+                stream,
+                "400 Bad Request",
+                "text/plain",
+                "Body must be UTF-8",
+            )
+            .await;
+        }
+    };
+
+    let Some(session_id) = request.query.get("sessionId") else {
+        // No session: answer synchronously in the HTTP response
+        let response = tokio::task::spawn_blocking(move || server.handle_message(&body)).await?;
+        return match response {
+            Some(response) => {
+                write_response(stream, "200 OK", "application/json", &response.to_string()).await
+            }
+            None => write_response(stream, "202 Accepted", "text/plain", "Accepted").await,
+        };
+    };
+
+    let sender = sessions
+        .lock()
+        .map_err(|_| anyhow::anyhow!("session registry poisoned"))?
+        .get(session_id)
+        .cloned();
+    let Some(sender) = sender else {
+        return write_response(stream, "404 Not Found", "text/plain", "Unknown session").await;
+    };
+
+    write_response(stream, "202 Accepted", "text/plain", "Accepted").await?;
+
+    // Parsing usage data is blocking work; keep it off the async workers
+    if let Some(response) =
+        tokio::task::spawn_blocking(move || server.handle_message(&body)).await?
+    {
+        // The stream may have closed in the meantime; nothing left to deliver to
+        let _ = sender.send(response.to_string());
+    }
+    Ok(())
+}
+
+/// Read the request line, headers, and body. Returns `None` if the client sent
+/// nothing, or if the request was too large or too slow and has already been
+/// answered with an error status.
+pub(crate) async fn read_request(reader: &mut BufReader<TcpStream>) -> Result<Option<HttpRequest>> {
+    read_request_within(reader, REQUEST_TIMEOUT).await
+}
+
+async fn read_request_within(
+    reader: &mut BufReader<TcpStream>,
+    timeout: Duration,
+) -> Result<Option<HttpRequest>> {
+    match tokio::time::timeout(timeout, read_request_unbounded(reader)).await {
+        Ok(request) => request,
+        Err(_) => {
+            write_response(
+                reader.get_mut(),
+                "408 Request Timeout",
+                "text/plain",
+                "Request Timeout",
+            )
+            .await?;
+            Ok(None)
+        }
+    }
+}
+
+async fn read_request_unbounded(reader: &mut BufReader<TcpStream>) -> Result<Option<HttpRequest>> {
+    let mut request_line = String::new();
+    match read_line_capped(reader, &mut request_line).await? {
+        Line::Read(0) => return Ok(None),
+        Line::Read(_) => {}
+        Line::TooLong => {
+            write_response(
+                reader.get_mut(),
+                "414 URI Too Long",
+                "text/plain",
+                "URI Too Long",
+            )
+            .await?;
+            return Ok(None);
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_uppercase();
+    let target = parts.next().unwrap_or("/");
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), parse_query(query)),
+        None => (target.to_string(), HashMap::new()),
+    };
+
+    let mut content_length = 0usize;
+    let mut headers = 0;
+    loop {
+        let mut line = String::new();
+        match read_line_capped(reader, &mut line).await? {
+            Line::Read(0) => break,
+            Line::Read(_) if headers < MAX_HEADERS => {}
+            Line::Read(_) | Line::TooLong => {
+                write_response(
+                    reader.get_mut(),
+                    "431 Request Header Fields Too Large",
+                    "text/plain",
+                    "Request Header Fields Too Large",
+                )
+                .await?;
+                return Ok(None);
+            }
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        headers += 1;
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().context("Invalid Content-Length")?;
+        }
+    }
+
+    if content_length > MAX_BODY_BYTES {
+        anyhow::bail!("Request body too large ({} bytes)", content_length);
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+
+    Ok(Some(HttpRequest {
+        method,
+        path,
+        query,
+        body,
+    }))
+}
+
+enum Line {
+    /// Bytes read, 0 at end of stream
+    Read(usize),
+    TooLong,
+}
+
+/// `read_line` that stops after `MAX_LINE_BYTES`, so a client can't make a line grow unbounded
+async fn read_line_capped(reader: &mut BufReader<TcpStream>, line: &mut String) -> Result<Line> {
+    let read = (&mut *reader)
+        .take(MAX_LINE_BYTES as u64 + 1)
+        .read_line(line)
+        .await?;
+    Ok(if read > MAX_LINE_BYTES {
+        Line::TooLong
+    } else {
+        Line::Read(read)
+    })
+}
+
+/// Parse `a=1&b=two%20words`, percent-decoding keys and values and reading `+` as a space
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (percent_decode(key), percent_decode(value)))
+        .collect()
+}
+
+/// Decode `%XX` escapes and `+`; malformed escapes are kept as they are
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => match bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Format a Server-Sent Event, splitting multi-line data as the spec requires
+fn sse_event(event: &str, data: &str) -> String {
+    let mut out = format!("event: {}\n", event);
+    for line in data.lines() {
+        out.push_str("data: ");
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push('\n');
+    out
+}
+
+pub(crate) async fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\n\
+         Content-Type: {}\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+         Access-Control-Allow-Headers: Content-Type\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+fn is_disconnect(error: &std::io::Error) -> bool {
+    matches!(
+        error.kind(),
+        std::io::ErrorKind::BrokenPipe
+            | std::io::ErrorKind::ConnectionReset
+            | std::io::ErrorKind::ConnectionAborted
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::path::PathBuf;
+
+    async fn start_server() -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Arc::new(McpServer::new(PathBuf::from("/tmp")));
+        tokio::spawn(serve(server, listener));
+        addr
+    }
+
+    async fn post(addr: std::net::SocketAddr, path: &str, body: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            path,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    /// Read from the SSE stream until the next complete event, returning its data
+    async fn next_event(reader: &mut BufReader<TcpStream>) -> (String, String) {
+        let (mut event, mut data) = (String::new(), String::new());
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).await.unwrap();
+            let line = line.trim_end();
+            if let Some(value) = line.strip_prefix("event: ") {
+                event = value.to_string();
+            } else if let Some(value) = line.strip_prefix("data: ") {
+                data.push_str(value);
+            } else if line.is_empty() && !event.is_empty() {
+                return (event, data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sse_event_format() {
+        assert_eq!(
+            sse_event("message", "a\nb"),
+            "event: message\ndata: a\ndata: b\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query = parse_query("sessionId=abc&x=1");
+        assert_eq!(query.get("sessionId").map(String::as_str), Some("abc"));
+        assert_eq!(query.get("x").map(String::as_str), Some("1"));
+
+        let query =
+            parse_query("since=7%20days+ago&project=%2Fhome%2Fme%2Fapi&bad=100%&%C3%A9t%C3%A9=1");
+        assert_eq!(query["since"], "7 days ago");
+        assert_eq!(query["project"], "/home/me/api");
+        assert_eq!(query["bad"], "100%");
+        assert_eq!(query["été"], "1");
+    }
+
+    #[tokio::test]
+    async fn test_long_lines_are_rejected() {
+        let addr = start_server().await;
+        let long = "a".repeat(MAX_LINE_BYTES + 10);
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET /health?q={} HTTP/1.1\r\n\r\n", long);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 414"), "{}", response);
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET /health HTTP/1.1\r\nX-Long: {}\r\n\r\n", long);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 431"), "{}", response);
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let headers = "X-Filler: 1\r\n".repeat(MAX_HEADERS + 1);
+        let request = format!("GET /health HTTP/1.1\r\n{}\r\n", headers);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 431"), "{}", response);
+    }
+
+    #[tokio::test]
+    async fn test_slow_requests_time_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        // The headers are never finished
+        client
+            .write_all(b"GET /health HTTP/1.1\r\nHost: x\r\n")
+            .await
+            .unwrap();
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut reader = BufReader::new(stream);
+        let request = read_request_within(&mut reader, Duration::from_millis(100))
+            .await
+            .unwrap();
+        assert!(request.is_none());
+        drop(reader);
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 408"), "{}", response);
+    }
+
+    #[tokio::test]
+    async fn test_inline_post() {
+        let addr = start_server().await;
+        let response = post(
+            addr,
+            "/messages",
+            r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#,
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#"{"id":1,"jsonrpc":"2.0","result":{}}"#));
+    }
+
+    #[tokio::test]
+    async fn test_sse_session_round_trip() {
+        let addr = start_server().await;
+
+        let mut sse = TcpStream::connect(addr).await.unwrap();
+        sse.write_all(b"GET /sse HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut reader = BufReader::new(sse);
+
+        let (event, endpoint) = next_event(&mut reader).await;
+        assert_eq!(event, "endpoint");
+        assert!(endpoint.starts_with("/messages?sessionId="));
+
+        let response = post(
+            addr,
+            &endpoint,
+            r#"{"jsonrpc":"2.0","id":7,"method":"tools/list"}"#,
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 202 Accepted"));
+
+        let (event, data) = next_event(&mut reader).await;
+        assert_eq!(event, "message");
+        let message: Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(message["id"], 7);
+        assert_eq!(message["result"]["tools"].as_array().unwrap().len(), 3);
+
+        let response = post(addr, "/messages?sessionId=missing", "{}").await;
+        assert!(response.starts_with("HTTP/1.1 404"));
+    }
+}
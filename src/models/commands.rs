@@ -1,31 +1,47 @@
-// Command palette actions
-#[allow(dead_code)]
+/// Command palette actions
 #[derive(Debug, Clone)]
 pub enum CommandAction {
+    /// Switch to the tab at this index
     SwitchTab(usize),
-    #[allow(dead_code)]
-    ToggleSort(String),
-    #[allow(dead_code)]
+    /// Sort key: date, cost, tokens, efficiency or project
+    SetSort(String),
+    /// Time filter: all, today, week or month
     SetFilter(String),
-    #[allow(dead_code)]
-    ExportData(String),
-    #[allow(dead_code)]
-    BookmarkSession(String),
-    #[allow(dead_code)]
+    /// Prompt for a model name and re-read usage of matching models
+    FilterModel,
+    /// Step the model filter through All, Opus, Sonnet and Haiku
+    CycleModelFamily,
+    /// Prompt for a date range the Daily and Sessions tabs are limited to
+    PickDateRange,
+    /// Drop the sort, time, model and date filters
+    ClearFilters,
+    /// Open the export dialog
+    ExportData,
+    /// Bookmark the selected session, or remove its bookmark
+    BookmarkSession,
+    /// Mark the selected session for comparison
     CompareSelected,
-    #[allow(dead_code)]
-    ShowBenchmark,
+    /// Show the sessions marked for comparison side by side
+    ShowComparison,
+    /// Re-read the usage logs
     RefreshData,
-    #[allow(dead_code)]
-    OpenSessionDetail(String),
+    /// Open the selected session in the conversation viewer
+    OpenConversation,
+    /// Show the key bindings
     ShowHelp,
 }
 
+/// An entry in the command palette
 #[derive(Debug, Clone)]
 pub struct Command {
+    /// Name matched against the typed query
     pub name: String,
+    /// One-line description shown next to the name
     pub description: String,
+    /// Key that runs the action outside the palette
     pub shortcut: Option<String>,
+    /// What running the command does
     pub action: CommandAction,
+    /// Group the command is listed under
     pub category: String,
 }
@@ -1,21 +1,29 @@
+//! Usage records, aggregated usage maps, and report types
+
 mod commands;
 mod reports;
 mod sessions;
 mod types;
 
 // Re-export all public types so `use crate::models::X` continues to work.
-// MessageData, Usage, and MessageContent are not directly imported externally
-// yet but are re-exported to keep the full public API surface intact.
+// The raw JSONL records and TUI command types are used by the command-line
+// tool only and are not part of the supported API.
+#[doc(hidden)]
 #[allow(unused_imports)]
 pub use commands::{Command, CommandAction};
 #[allow(unused_imports)]
 pub use reports::{
-    DailyReport, DailyUsage, MonthlyReport, MonthlyUsage, SessionReport, SessionUsage,
+    DailyReport, DailyUsage, GroupReport, GroupUsage, HourlyReport, HourlyUsage, ModelUsage,
+    MonthlyReport, MonthlyUsage, Pagination, SessionReport, SessionUsage, SourceUsage,
     TokenUsageTotals, WeeklyReport, WeeklyUsage,
 };
+#[doc(hidden)]
 #[allow(unused_imports)]
 pub use sessions::{
     ClaudeMessage, ClaudeSession, ClaudeSessionSummary, ContentPart, MessageContent,
 };
 #[allow(unused_imports)]
-pub use types::{DailyUsageMap, MessageData, SessionUsageMap, TokenUsage, Usage, UsageRecord};
+pub use types::{DailyUsageMap, SessionUsageMap, TokenUsage};
+#[doc(hidden)]
+#[allow(unused_imports)]
+pub use types::{MessageData, Usage, UsageRecord, estimate_thinking_tokens};
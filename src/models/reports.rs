@@ -3,21 +3,116 @@ use serde::Serialize;
 
 use super::types::TokenUsage;
 
+/// Usage on one day
 #[derive(Debug, Serialize, Clone)]
 pub struct DailyUsage {
+    /// Local date, `YYYY-MM-DD`
     pub date: String,
+    /// Uncached input tokens
     #[serde(rename = "inputTokens")]
     pub input_tokens: u64,
+    /// Output tokens, including thinking
     #[serde(rename = "outputTokens")]
     pub output_tokens: u64,
+    /// Tokens written to the prompt cache
     #[serde(rename = "cacheCreationTokens")]
     pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
     #[serde(rename = "cacheReadTokens")]
     pub cache_read_tokens: u64,
+    /// Input, output, and cache tokens together
     #[serde(rename = "totalTokens")]
     pub total_tokens: u64,
+    /// Cost of the usage
     #[serde(rename = "totalCost")]
     pub total_cost: f64,
+    /// Output tokens estimated to be extended thinking
+    #[serde(rename = "thinkingTokens")]
+    pub thinking_tokens: u64,
+    /// Per-model-family sub-breakdown, only populated when requested
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<ModelUsage>,
+    /// Per-source (Claude directory) sub-breakdown, only populated when requested
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<SourceUsage>,
+}
+
+/// Token usage and cost of a single model family within a report row
+#[derive(Debug, Serialize, Clone)]
+pub struct ModelUsage {
+    /// Model family, e.g. `sonnet`
+    pub family: String,
+    /// Uncached input tokens
+    #[serde(rename = "inputTokens")]
+    pub input_tokens: u64,
+    /// Output tokens, including thinking
+    #[serde(rename = "outputTokens")]
+    pub output_tokens: u64,
+    /// Tokens written to the prompt cache
+    #[serde(rename = "cacheCreationTokens")]
+    pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
+    #[serde(rename = "cacheReadTokens")]
+    pub cache_read_tokens: u64,
+    /// Input, output, and cache tokens together
+    #[serde(rename = "totalTokens")]
+    pub total_tokens: u64,
+    /// Cost of the usage
+    #[serde(rename = "totalCost")]
+    pub total_cost: f64,
+}
+
+impl From<(&String, &TokenUsage)> for ModelUsage {
+    fn from((family, usage): (&String, &TokenUsage)) -> Self {
+        ModelUsage {
+            family: family.clone(),
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_tokens: usage.cache_creation_tokens,
+            cache_read_tokens: usage.cache_read_tokens,
+            total_tokens: usage.total_tokens(),
+            total_cost: usage.total_cost,
+        }
+    }
+}
+
+/// Token usage and cost read from a single Claude directory within a report row
+#[derive(Debug, Serialize, Clone)]
+pub struct SourceUsage {
+    /// Claude directory the usage was read from
+    pub source: String,
+    /// Uncached input tokens
+    #[serde(rename = "inputTokens")]
+    pub input_tokens: u64,
+    /// Output tokens, including thinking
+    #[serde(rename = "outputTokens")]
+    pub output_tokens: u64,
+    /// Tokens written to the prompt cache
+    #[serde(rename = "cacheCreationTokens")]
+    pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
+    #[serde(rename = "cacheReadTokens")]
+    pub cache_read_tokens: u64,
+    /// Input, output, and cache tokens together
+    #[serde(rename = "totalTokens")]
+    pub total_tokens: u64,
+    /// Cost of the usage
+    #[serde(rename = "totalCost")]
+    pub total_cost: f64,
+}
+
+impl From<(&String, &TokenUsage)> for SourceUsage {
+    fn from((source, usage): (&String, &TokenUsage)) -> Self {
+        SourceUsage {
+            source: source.clone(),
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_tokens: usage.cache_creation_tokens,
+            cache_read_tokens: usage.cache_read_tokens,
+            total_tokens: usage.total_tokens(),
+            total_cost: usage.total_cost,
+        }
+    }
 }
 
 impl From<(NaiveDate, &TokenUsage)> for DailyUsage {
@@ -30,116 +125,290 @@ impl From<(NaiveDate, &TokenUsage)> for DailyUsage {
             cache_read_tokens: usage.cache_read_tokens,
             total_tokens: usage.total_tokens(),
             total_cost: usage.total_cost,
+            thinking_tokens: usage.thinking_tokens,
+            models: Vec::new(),
+            sources: Vec::new(),
         }
     }
 }
 
+/// Usage of one session
 #[derive(Debug, Serialize, Clone)]
 pub struct SessionUsage {
+    /// Project directory under `projects/`, as Claude Code names it
     #[serde(rename = "projectPath")]
     pub project_path: String,
+    /// Session ID (the JSONL file name)
     #[serde(rename = "sessionId")]
     pub session_id: String,
+    /// Uncached input tokens
     #[serde(rename = "inputTokens")]
     pub input_tokens: u64,
+    /// Output tokens, including thinking
     #[serde(rename = "outputTokens")]
     pub output_tokens: u64,
+    /// Tokens written to the prompt cache
     #[serde(rename = "cacheCreationTokens")]
     pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
     #[serde(rename = "cacheReadTokens")]
     pub cache_read_tokens: u64,
+    /// Input, output, and cache tokens together
     #[serde(rename = "totalTokens")]
     pub total_tokens: u64,
+    /// Cost of the usage
     #[serde(rename = "totalCost")]
     pub total_cost: f64,
+    /// Output tokens estimated to be extended thinking
+    #[serde(rename = "thinkingTokens")]
+    pub thinking_tokens: u64,
+    /// Local date of the last message, `YYYY-MM-DD`
     #[serde(rename = "lastActivity")]
     pub last_activity: String,
+    /// Summary record or first user prompt of the session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Composite efficiency score (0-100), see `session_analytics::EfficiencyScore`
+    #[serde(rename = "efficiencyScore", skip_serializing_if = "Option::is_none")]
+    pub efficiency_score: Option<f64>,
+    /// Largest share of the model's context window a single request used (percent)
+    #[serde(rename = "contextPeakPercent", skip_serializing_if = "Option::is_none")]
+    pub context_peak_percent: Option<f64>,
+    /// Later sessions resumed from this one, when grouping resumes (`--group-resumes`)
+    #[serde(rename = "resumedSessions", skip_serializing_if = "Vec::is_empty")]
+    pub resumed_sessions: Vec<String>,
 }
 
+/// Usage by day, newest first unless sorted otherwise
 #[derive(Debug, Serialize, Clone)]
 pub struct DailyReport {
+    /// One row per day with usage
     pub daily: Vec<DailyUsage>,
+    /// Usage over all days
     pub totals: TokenUsageTotals,
 }
 
+/// Usage in one calendar month
 #[derive(Debug, Serialize, Clone)]
 pub struct MonthlyUsage {
+    /// Month name, e.g. `March`
     pub month: String,
+    /// Year of the month
     pub year: u32,
+    /// Uncached input tokens
     #[serde(rename = "inputTokens")]
     pub input_tokens: u64,
+    /// Output tokens, including thinking
     #[serde(rename = "outputTokens")]
     pub output_tokens: u64,
+    /// Tokens written to the prompt cache
     #[serde(rename = "cacheCreationTokens")]
     pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
     #[serde(rename = "cacheReadTokens")]
     pub cache_read_tokens: u64,
+    /// Input, output, and cache tokens together
     #[serde(rename = "totalTokens")]
     pub total_tokens: u64,
+    /// Cost of the usage
     #[serde(rename = "totalCost")]
     pub total_cost: f64,
+    /// Output tokens estimated to be extended thinking
+    #[serde(rename = "thinkingTokens")]
+    pub thinking_tokens: u64,
+    /// Days in the month with usage
     #[serde(rename = "daysActive")]
     pub days_active: u32,
+    /// Cost per active day
     #[serde(rename = "avgDailyCost")]
     pub avg_daily_cost: f64,
 }
 
+/// Usage by calendar month
 #[derive(Debug, Serialize, Clone)]
 pub struct MonthlyReport {
+    /// One row per month with usage
     pub monthly: Vec<MonthlyUsage>,
+    /// Usage over all months
     pub totals: TokenUsageTotals,
 }
 
+/// Usage in one week
 #[derive(Debug, Serialize, Clone)]
 pub struct WeeklyUsage {
+    /// First day of the week, `YYYY-MM-DD`
     #[serde(rename = "weekStart")]
     pub week_start: String,
+    /// Last day of the week, `YYYY-MM-DD`
     #[serde(rename = "weekEnd")]
     pub week_end: String,
+    /// Uncached input tokens
     #[serde(rename = "inputTokens")]
     pub input_tokens: u64,
+    /// Output tokens, including thinking
     #[serde(rename = "outputTokens")]
     pub output_tokens: u64,
+    /// Tokens written to the prompt cache
     #[serde(rename = "cacheCreationTokens")]
     pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
     #[serde(rename = "cacheReadTokens")]
     pub cache_read_tokens: u64,
+    /// Input, output, and cache tokens together
     #[serde(rename = "totalTokens")]
     pub total_tokens: u64,
+    /// Cost of the usage
     #[serde(rename = "totalCost")]
     pub total_cost: f64,
+    /// Days in the week with usage
     #[serde(rename = "daysActive")]
     pub days_active: u32,
+    /// Cost per active day
     #[serde(rename = "avgDailyCost")]
     pub avg_daily_cost: f64,
 }
 
+/// Usage by week
 #[derive(Debug, Serialize, Clone)]
 pub struct WeeklyReport {
+    /// One row per week with usage
     pub weekly: Vec<WeeklyUsage>,
+    /// Usage over all weeks
     pub totals: TokenUsageTotals,
 }
 
+/// Usage within one local clock hour
+#[derive(Debug, Serialize, Clone)]
+pub struct HourlyUsage {
+    /// Start of the hour in local time (`YYYY-MM-DD HH:00`)
+    pub hour: String,
+    /// Uncached input tokens
+    #[serde(rename = "inputTokens")]
+    pub input_tokens: u64,
+    /// Output tokens, including thinking
+    #[serde(rename = "outputTokens")]
+    pub output_tokens: u64,
+    /// Tokens written to the prompt cache
+    #[serde(rename = "cacheCreationTokens")]
+    pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
+    #[serde(rename = "cacheReadTokens")]
+    pub cache_read_tokens: u64,
+    /// Input, output, and cache tokens together
+    #[serde(rename = "totalTokens")]
+    pub total_tokens: u64,
+    /// Cost of the usage
+    #[serde(rename = "totalCost")]
+    pub total_cost: f64,
+    /// Assistant messages in the hour
+    #[serde(rename = "messageCount")]
+    pub message_count: u64,
+    /// Sessions with at least one record in the hour
+    pub sessions: usize,
+}
+
+/// Usage by local clock hour
+#[derive(Debug, Serialize, Clone)]
+pub struct HourlyReport {
+    /// One row per hour with usage
+    pub hourly: Vec<HourlyUsage>,
+    /// Usage over all hours
+    pub totals: TokenUsageTotals,
+}
+
+/// Usage by session, most expensive first unless sorted otherwise
 #[derive(Debug, Serialize, Clone)]
 pub struct SessionReport {
+    /// One row per session
     pub sessions: Vec<SessionUsage>,
+    /// Usage of all sessions, including those left out by pagination
+    pub totals: TokenUsageTotals,
+    /// Set when `--limit`, `--offset`, or `--top` kept only some of the rows
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+}
+
+/// Which rows of a report were kept by `--limit`, `--offset`, or `--top`
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    /// Rows skipped from the start
+    pub offset: usize,
+    /// Rows kept, or `None` for all rows after `offset`
+    pub limit: Option<usize>,
+    /// Rows before pagination
+    #[serde(rename = "totalRows")]
+    pub total_rows: usize,
+    /// Rows left out before and after the kept ones
+    pub omitted: usize,
+}
+
+/// Usage of one project or tag, optionally within a day or month
+#[derive(Debug, Serialize, Clone)]
+pub struct GroupUsage {
+    /// Day (`YYYY-MM-DD`) or month (`YYYY-MM`) for daily and monthly reports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<String>,
+    /// Project directory or tag name
+    pub group: String,
+    /// Sessions counted toward the group
+    pub sessions: usize,
+    /// Uncached input tokens
+    #[serde(rename = "inputTokens")]
+    pub input_tokens: u64,
+    /// Output tokens, including thinking
+    #[serde(rename = "outputTokens")]
+    pub output_tokens: u64,
+    /// Tokens written to the prompt cache
+    #[serde(rename = "cacheCreationTokens")]
+    pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
+    #[serde(rename = "cacheReadTokens")]
+    pub cache_read_tokens: u64,
+    /// Input, output, and cache tokens together
+    #[serde(rename = "totalTokens")]
+    pub total_tokens: u64,
+    /// Cost of the usage
+    #[serde(rename = "totalCost")]
+    pub total_cost: f64,
+}
+
+/// Usage rolled up by project or tag. A session with several tags counts
+/// toward each of them, so with tags the rows can add up to more than `totals`.
+#[derive(Debug, Serialize, Clone)]
+pub struct GroupReport {
+    /// `project` or `tag`
+    #[serde(rename = "groupBy")]
+    pub group_by: String,
+    /// One row per group (and period)
+    pub groups: Vec<GroupUsage>,
+    /// Usage of all sessions, each counted once
     pub totals: TokenUsageTotals,
 }
 
+/// Token counts and cost summed over a report's rows
 #[derive(Debug, Serialize, Clone)]
 pub struct TokenUsageTotals {
+    /// Uncached input tokens
     #[serde(rename = "inputTokens")]
     pub input_tokens: u64,
+    /// Output tokens, including thinking
     #[serde(rename = "outputTokens")]
     pub output_tokens: u64,
+    /// Tokens written to the prompt cache
     #[serde(rename = "cacheCreationTokens")]
     pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
     #[serde(rename = "cacheReadTokens")]
     pub cache_read_tokens: u64,
+    /// Input, output, and cache tokens together
     #[serde(rename = "totalTokens")]
     pub total_tokens: u64,
+    /// Cost of the usage
     #[serde(rename = "totalCost")]
     pub total_cost: f64,
+    /// Output tokens estimated to be extended thinking
+    #[serde(rename = "thinkingTokens")]
+    pub thinking_tokens: u64,
 }
 
 impl From<&TokenUsage> for TokenUsageTotals {
@@ -151,6 +420,7 @@ impl From<&TokenUsage> for TokenUsageTotals {
             cache_read_tokens: usage.cache_read_tokens,
             total_tokens: usage.total_tokens(),
             total_cost: usage.total_cost,
+            thinking_tokens: usage.thinking_tokens,
         }
     }
 }
@@ -8,9 +8,12 @@ use super::types::{TokenUsage, Usage};
 #[derive(Debug, Deserialize, Clone)]
 #[allow(dead_code)]
 pub struct ClaudeSessionSummary {
+    /// Always `summary`
     #[serde(rename = "type")]
     pub record_type: String,
+    /// Title Claude Code generated for the session
     pub summary: String,
+    /// UUID of the last message the summary covers
     #[serde(rename = "leafUuid")]
     pub leaf_uuid: String,
 }
@@ -19,13 +22,21 @@ pub struct ClaudeSessionSummary {
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct ClaudeSession {
+    /// Path of the JSONL file
     pub file_path: PathBuf,
+    /// Encoded project directory the file is under
     pub project_path: String,
+    /// File name without the extension
     pub session_id: String,
+    /// Summary from the first line of the file
     pub summary: String,
+    /// Timestamp of the first message
     pub created_at: DateTime<Utc>,
+    /// Modification time of the file
     pub modified_at: DateTime<Utc>,
+    /// Number of messages in the file
     pub message_count: usize,
+    /// Usage of all assistant messages
     pub usage: TokenUsage,
 }
 
@@ -33,13 +44,19 @@ pub struct ClaudeSession {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[allow(dead_code)]
 pub struct ClaudeMessage {
+    /// `user` or `assistant`
     #[serde(rename = "type")]
     pub message_type: String,
+    /// When the message was written
     pub timestamp: DateTime<Utc>,
+    /// Role, content and usage of the message
     pub message: MessageContent,
+    /// Unique ID of the JSONL line
     pub uuid: String,
+    /// UUID of the message this one replies to
     #[serde(rename = "parentUuid")]
     pub parent_uuid: Option<String>,
+    /// Session the message belongs to
     #[serde(rename = "sessionId")]
     pub session_id: String,
 }
@@ -48,8 +65,11 @@ pub struct ClaudeMessage {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[allow(dead_code)]
 pub struct MessageContent {
+    /// `user` or `assistant`
     pub role: String,
+    /// Text and tool blocks of the message
     pub content: Vec<ContentPart>,
+    /// Token usage, on assistant messages only
     pub usage: Option<Usage>,
 }
 
@@ -57,7 +77,9 @@ pub struct MessageContent {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[allow(dead_code)]
 pub struct ContentPart {
+    /// Block type, e.g. `text`, `tool_use` or `thinking`
     #[serde(rename = "type")]
     pub content_type: String,
+    /// Text of a `text` block
     pub text: Option<String>,
 }
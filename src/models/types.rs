@@ -1,6 +1,6 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 // Type aliases for better code readability
 /// Map of date to daily token usage aggregation
@@ -10,38 +10,132 @@ pub type SessionUsageMap = HashMap<String, (TokenUsage, DateTime<Utc>)>;
 
 /// Core data structures for Claude Code usage analysis
 /// Main structure representing a single usage record from JSONL files
-#[derive(Debug, Deserialize)]
+///
+/// Records are stored in the parse cache; changing its fields needs a bump of
+/// the cache's `SCHEMA_VERSION`.
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UsageRecord {
+    /// When the record was written
     #[serde(default)]
     pub timestamp: Option<DateTime<Utc>>,
+    /// The API response, with its model and usage
     #[serde(default)]
     pub message: Option<MessageData>,
+    /// Cost Claude Code recorded for the request, in USD
     #[serde(rename = "costUSD", default)]
     pub cost_usd: Option<f64>,
     /// Request ID for deduplication (paired with message.id)
     #[serde(rename = "requestId", default)]
     pub request_id: Option<String>,
+    /// Unique ID of the JSONL line; lines replayed after a crash repeat it
+    #[serde(default)]
+    pub uuid: Option<String>,
 }
 
 /// Message data containing usage information and model details
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct MessageData {
+    /// Token usage reported by the API
     #[serde(default)]
     pub usage: Option<Usage>,
+    /// Model that answered, e.g. `claude-sonnet-4-20250514`
     #[serde(default)]
     pub model: Option<String>,
     /// Message ID for deduplication (paired with requestId)
     #[serde(default)]
     pub id: Option<String>,
+    /// Length of the message's thinking blocks in characters, read from
+    /// `content` (only the total is kept, under its own name in the parse cache)
+    #[serde(
+        rename = "thinkingChars",
+        alias = "content",
+        default,
+        deserialize_with = "deserialize_thinking_chars",
+        skip_serializing_if = "is_zero"
+    )]
+    pub thinking_chars: u64,
+}
+
+/// Rough characters per token, for estimating thinking tokens from text length
+const THINKING_CHARS_PER_TOKEN: u64 = 4;
+
+/// Estimated tokens of `chars` characters of thinking text
+pub fn estimate_thinking_tokens(chars: u64) -> u64 {
+    chars.div_ceil(THINKING_CHARS_PER_TOKEN)
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// Sum the text length of `thinking` blocks in message content without
+/// keeping the content itself. Also accepts the number stored in the parse cache.
+fn deserialize_thinking_chars<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::{IgnoredAny, MapAccess, SeqAccess, Visitor};
+    use std::fmt;
+
+    #[derive(Deserialize)]
+    struct Block {
+        #[serde(rename = "type", default)]
+        block_type: Option<String>,
+        #[serde(default)]
+        thinking: Option<String>,
+    }
+
+    struct ThinkingChars;
+
+    impl<'de> Visitor<'de> for ThinkingChars {
+        type Value = u64;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("message content or a character count")
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<u64, E> {
+            Ok(value)
+        }
+
+        fn visit_str<E>(self, _: &str) -> Result<u64, E> {
+            Ok(0)
+        }
+
+        fn visit_unit<E>(self) -> Result<u64, E> {
+            Ok(0)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<u64, A::Error> {
+            let mut chars = 0;
+            while let Some(block) = seq.next_element::<Block>()? {
+                if block.block_type.as_deref() == Some("thinking") {
+                    chars += block.thinking.map_or(0, |t| t.chars().count() as u64);
+                }
+            }
+            Ok(chars)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<u64, A::Error> {
+            while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+            Ok(0)
+        }
+    }
+
+    deserializer.deserialize_any(ThinkingChars)
 }
 
 /// Token usage breakdown from API response
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Usage {
+    /// Uncached input tokens
     pub input_tokens: u64,
+    /// Output tokens, including thinking
     pub output_tokens: u64,
+    /// Tokens written to the prompt cache
     #[serde(default)]
     pub cache_creation_input_tokens: u64,
+    /// Tokens read from the prompt cache
     #[serde(default)]
     pub cache_read_input_tokens: u64,
     /// Speed mode: "standard" or "fast" (fast mode uses 6x pricing)
@@ -59,14 +153,41 @@ impl Usage {
 /// Aggregated token usage with cost calculation
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TokenUsage {
+    /// Uncached input tokens
     pub input_tokens: u64,
+    /// Output tokens, including thinking
     pub output_tokens: u64,
+    /// Tokens written to the prompt cache
     pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
     pub cache_read_tokens: u64,
+    /// Cost in USD, or in the currency it was converted to with `scale_costs`
     pub total_cost: f64,
     /// Cost attributed to fast mode (6x multiplier) usage
     #[serde(default)]
     pub fast_mode_cost: f64,
+    /// Number of assistant messages (usage records) aggregated into this usage
+    #[serde(default)]
+    pub message_count: u64,
+    /// Output tokens estimated to be extended thinking, from the length of
+    /// thinking blocks (the API reports thinking as ordinary output tokens)
+    #[serde(default)]
+    pub thinking_tokens: u64,
+    /// Largest prompt (input, cache creation, and cache read tokens) of a single
+    /// request, i.e. how full the context window got
+    #[serde(default)]
+    pub peak_context_tokens: u64,
+    /// Per-model breakdown (model name -> usage), filled in by the parser
+    #[serde(skip)]
+    pub by_model: BTreeMap<String, TokenUsage>,
+    /// Per-source breakdown (Claude directory label -> usage), only filled in
+    /// when more than one Claude directory is parsed
+    #[serde(skip)]
+    pub by_source: BTreeMap<String, TokenUsage>,
+    /// Per-session breakdown (`project-dir/session-id` -> usage), filled in by
+    /// the parser so daily rows can be split by project or tag
+    #[serde(skip)]
+    pub by_session: BTreeMap<String, TokenUsage>,
 }
 
 impl TokenUsage {
@@ -84,6 +205,48 @@ impl TokenUsage {
         self.cache_read_tokens += other.cache_read_tokens;
         self.total_cost += other.total_cost;
         self.fast_mode_cost += other.fast_mode_cost;
+        self.message_count += other.message_count;
+        self.thinking_tokens += other.thinking_tokens;
+        self.peak_context_tokens = self.peak_context_tokens.max(other.peak_context_tokens);
+        for (model, usage) in &other.by_model {
+            self.by_model.entry(model.clone()).or_default().add(usage);
+        }
+        for (source, usage) in &other.by_source {
+            self.by_source.entry(source.clone()).or_default().add(usage);
+        }
+        for (session, usage) in &other.by_session {
+            self.by_session
+                .entry(session.clone())
+                .or_default()
+                .add(usage);
+        }
+    }
+
+    /// Multiply all costs, including the breakdowns, by `factor` (currency conversion)
+    pub fn scale_costs(&mut self, factor: f64) {
+        self.total_cost *= factor;
+        self.fast_mode_cost *= factor;
+        for usage in self
+            .by_model
+            .values_mut()
+            .chain(self.by_source.values_mut())
+            .chain(self.by_session.values_mut())
+        {
+            usage.scale_costs(factor);
+        }
+    }
+
+    /// This usage tagged with its model and session the way the parser records
+    /// a single message (the session entry keeps its own model split)
+    pub fn tagged(self, model: &str, session: &str) -> TokenUsage {
+        let mut usage = self.clone();
+        let mut session_usage = self.clone();
+        session_usage
+            .by_model
+            .insert(model.to_string(), self.clone());
+        usage.by_session.insert(session.to_string(), session_usage);
+        usage.by_model.insert(model.to_string(), self);
+        usage
     }
 
     /// Calculate efficiency metrics
@@ -119,6 +282,7 @@ impl TokenUsage {
 }
 
 impl UsageRecord {
+    /// Model of the response, if the record has one
     pub fn get_model_name(&self) -> Option<&str> {
         self.message.as_ref()?.model.as_deref()
     }
@@ -131,11 +295,19 @@ impl UsageRecord {
         let request_id = self.request_id.as_deref()?;
         Some(format!("{}:{}", message_id, request_id))
     }
+
+    /// Key used to skip duplicate records: message.id:requestId, falling back to
+    /// the line UUID for records that lack either ID
+    pub fn dedup_key(&self) -> Option<String> {
+        self.dedup_hash()
+            .or_else(|| self.uuid.as_ref().map(|uuid| format!("uuid:{}", uuid)))
+    }
 }
 
 impl From<&UsageRecord> for TokenUsage {
     fn from(record: &UsageRecord) -> Self {
         let usage = record.message.as_ref().and_then(|m| m.usage.as_ref());
+        let thinking_chars = record.message.as_ref().map_or(0, |m| m.thinking_chars);
 
         match usage {
             Some(u) => TokenUsage {
@@ -145,6 +317,12 @@ impl From<&UsageRecord> for TokenUsage {
                 cache_read_tokens: u.cache_read_input_tokens,
                 total_cost: 0.0, // Cost is set by apply_cost_mode
                 fast_mode_cost: 0.0,
+                message_count: 1,
+                thinking_tokens: estimate_thinking_tokens(thinking_chars),
+                peak_context_tokens: u.input_tokens
+                    + u.cache_creation_input_tokens
+                    + u.cache_read_input_tokens,
+                ..Default::default()
             },
             None => TokenUsage::default(),
         }
@@ -1,5 +1,25 @@
+//! Known models, their aliases and families (`--model-filter`, `--list-models`)
+//!
+//! Besides the built-in Claude models, models routed through Claude Code from
+//! other providers can be described in `models.yaml` next to the config file:
+//! glob patterns matching their names, a family to group them under, and
+//! per-million-token prices so they are not reported as unknown or free.
+
+use crate::pricing::ModelPricing;
+use crate::tags::glob_match;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Context window of current Claude models, also assumed for unknown ones
+pub const DEFAULT_CONTEXT_WINDOW: u64 = 200_000;
+
+fn default_context_window() -> u64 {
+    DEFAULT_CONTEXT_WINDOW
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
@@ -8,19 +28,138 @@ pub struct ModelInfo {
     pub family: String,
     pub version: Option<String>,
     pub release_date: Option<String>,
+    /// Maximum prompt size in tokens (input, cache creation, and cache read)
+    #[serde(default = "default_context_window")]
+    pub context_window: u64,
+}
+
+/// `models.yaml`: models of other providers
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomModelsFile {
+    #[serde(default)]
+    pub models: Vec<CustomModel>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomModel {
+    /// Name the model is listed, priced, and filtered under
+    pub name: String,
+    /// Globs (`*`, `?`) of the model names found in the logs, e.g. `gpt-4o*`
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    pub family: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default = "default_context_window")]
+    pub context_window: u64,
+    pub pricing: Option<CustomPricing>,
+}
+
+/// Prices in USD per million tokens
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomPricing {
+    pub input: f64,
+    pub output: f64,
+    #[serde(default)]
+    pub cache_creation: f64,
+    #[serde(default)]
+    pub cache_read: f64,
+}
+
+impl From<&CustomPricing> for ModelPricing {
+    fn from(pricing: &CustomPricing) -> Self {
+        let per_token = |per_million: f64| Some(per_million / 1_000_000.0);
+        Self {
+            input_cost_per_token: per_token(pricing.input),
+            output_cost_per_token: per_token(pricing.output),
+            cache_creation_input_token_cost: per_token(pricing.cache_creation),
+            cache_read_input_token_cost: per_token(pricing.cache_read),
+            input_cost_per_token_above_200k: None,
+            output_cost_per_token_above_200k: None,
+            cache_creation_cost_above_200k: None,
+            cache_read_cost_above_200k: None,
+        }
+    }
+}
+
+impl CustomModelsFile {
+    /// `models.yaml` in the claudelytics configuration directory
+    pub fn path() -> Result<PathBuf> {
+        let config_path = crate::config_v2::AppConfig::config_path()?;
+        let dir = config_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Invalid config path: {}", config_path.display()))?;
+        Ok(dir.join("models.yaml"))
+    }
+
+    fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read custom models: {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse custom models: {}", path.display()))
+    }
+
+    /// Pricing of the custom models that have one, keyed by model name
+    pub fn pricing(&self) -> HashMap<String, ModelPricing> {
+        self.models
+            .iter()
+            .filter_map(|model| Some((model.name.clone(), model.pricing.as_ref()?.into())))
+            .collect()
+    }
+
+    /// Custom model whose name or patterns match `model_name`
+    pub fn find(&self, model_name: &str) -> Option<&CustomModel> {
+        self.models.iter().find(|model| {
+            model.name == model_name
+                || model
+                    .patterns
+                    .iter()
+                    .any(|pattern| glob_match(pattern, model_name))
+        })
+    }
+}
+
+/// The user's custom models, read once per run; a broken file is reported and ignored
+pub fn custom_models() -> &'static CustomModelsFile {
+    static CUSTOM_MODELS: OnceLock<CustomModelsFile> = OnceLock::new();
+    CUSTOM_MODELS.get_or_init(|| {
+        match CustomModelsFile::path().and_then(|path| CustomModelsFile::load_from(&path)) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("Warning: {:#}", e);
+                CustomModelsFile::default()
+            }
+        }
+    })
 }
 
 #[derive(Debug, Clone)]
 pub struct ModelsRegistry {
     models: HashMap<String, ModelInfo>,
     families: HashMap<String, Vec<String>>,
+    /// Name globs of custom models and the model they stand for
+    patterns: Vec<(String, String)>,
 }
 
 impl ModelsRegistry {
     pub fn new() -> Self {
+        let mut registry = Self::builtin();
+        registry.register_custom_models(custom_models());
+        registry
+    }
+
+    /// Only the built-in Claude models
+    pub fn builtin() -> Self {
         let mut registry = ModelsRegistry {
             models: HashMap::new(),
             families: HashMap::new(),
+            patterns: Vec::new(),
         };
 
         // Initialize with known models
@@ -28,6 +167,25 @@ impl ModelsRegistry {
         registry
     }
 
+    pub fn register_custom_models(&mut self, file: &CustomModelsFile) {
+        for model in &file.models {
+            self.patterns.extend(
+                model
+                    .patterns
+                    .iter()
+                    .map(|pattern| (pattern.clone(), model.name.clone())),
+            );
+            self.register_model(ModelInfo {
+                name: model.name.clone(),
+                aliases: model.aliases.clone(),
+                family: model.family.clone(),
+                version: None,
+                release_date: None,
+                context_window: model.context_window,
+            });
+        }
+    }
+
     fn register_default_models(&mut self) {
         // Opus models
         self.register_model(ModelInfo {
@@ -36,6 +194,7 @@ impl ModelsRegistry {
             family: "opus".to_string(),
             version: Some("4.0".to_string()),
             release_date: Some("2025-05-14".to_string()),
+            context_window: DEFAULT_CONTEXT_WINDOW,
         });
 
         self.register_model(ModelInfo {
@@ -44,6 +203,7 @@ impl ModelsRegistry {
             family: "opus".to_string(),
             version: Some("3.0".to_string()),
             release_date: Some("2024-02-29".to_string()),
+            context_window: DEFAULT_CONTEXT_WINDOW,
         });
 
         // Sonnet models
@@ -53,6 +213,7 @@ impl ModelsRegistry {
             family: "sonnet".to_string(),
             version: Some("4.0".to_string()),
             release_date: Some("2025-05-14".to_string()),
+            context_window: DEFAULT_CONTEXT_WINDOW,
         });
 
         self.register_model(ModelInfo {
@@ -61,6 +222,7 @@ impl ModelsRegistry {
             family: "sonnet".to_string(),
             version: Some("3.5".to_string()),
             release_date: Some("2024-10-22".to_string()),
+            context_window: DEFAULT_CONTEXT_WINDOW,
         });
 
         // Haiku models
@@ -70,6 +232,7 @@ impl ModelsRegistry {
             family: "haiku".to_string(),
             version: Some("3.5".to_string()),
             release_date: Some("2024-10-22".to_string()),
+            context_window: DEFAULT_CONTEXT_WINDOW,
         });
 
         self.register_model(ModelInfo {
@@ -78,6 +241,7 @@ impl ModelsRegistry {
             family: "haiku".to_string(),
             version: Some("3.0".to_string()),
             release_date: Some("2024-03-07".to_string()),
+            context_window: DEFAULT_CONTEXT_WINDOW,
         });
 
         // Claude 4.6 models
@@ -91,6 +255,7 @@ impl ModelsRegistry {
             family: "opus".to_string(),
             version: Some("4.6".to_string()),
             release_date: Some("2026-03-10".to_string()),
+            context_window: DEFAULT_CONTEXT_WINDOW,
         });
 
         self.register_model(ModelInfo {
@@ -103,6 +268,7 @@ impl ModelsRegistry {
             family: "sonnet".to_string(),
             version: Some("4.6".to_string()),
             release_date: Some("2026-03-10".to_string()),
+            context_window: DEFAULT_CONTEXT_WINDOW,
         });
 
         // Claude 4.5 Haiku
@@ -116,6 +282,7 @@ impl ModelsRegistry {
             family: "haiku".to_string(),
             version: Some("4.5".to_string()),
             release_date: Some("2025-10-01".to_string()),
+            context_window: DEFAULT_CONTEXT_WINDOW,
         });
     }
 
@@ -166,6 +333,15 @@ impl ModelsRegistry {
             return Some(info);
         }
 
+        // Name patterns of custom models
+        if let Some((_, name)) = self
+            .patterns
+            .iter()
+            .find(|(pattern, _)| glob_match(pattern, model_name))
+        {
+            return self.models.get(name);
+        }
+
         // Try to find by partial match
         for (name, info) in &self.models {
             if model_name.contains(name) || name.contains(model_name) {
@@ -176,6 +352,12 @@ impl ModelsRegistry {
         None
     }
 
+    /// Context window of a model, `DEFAULT_CONTEXT_WINDOW` for unknown ones
+    pub fn context_window(&self, model_name: &str) -> u64 {
+        self.get_model_info(model_name)
+            .map_or(DEFAULT_CONTEXT_WINDOW, |info| info.context_window)
+    }
+
     #[allow(dead_code)]
     pub fn get_model_family(&self, model_name: &str) -> Option<String> {
         // First try exact lookup
@@ -209,7 +391,6 @@ impl ModelsRegistry {
         families
     }
 
-    #[allow(dead_code)]
     pub fn get_models_by_family(&self, family: &str) -> Vec<&ModelInfo> {
         self.families
             .get(family)
@@ -221,6 +402,29 @@ impl ModelsRegistry {
             })
             .unwrap_or_default()
     }
+
+    /// Resolve a model name, alias, or family to a registered model. A family
+    /// resolves to its most recently released model.
+    pub fn resolve(&self, name: &str) -> Option<&ModelInfo> {
+        let name_lower = name.to_lowercase();
+        if let Some(info) = self.models.get(&name_lower) {
+            return Some(info);
+        }
+
+        if let Some(info) = self
+            .models
+            .values()
+            .find(|info| info.aliases.iter().any(|a| a.to_lowercase() == name_lower))
+        {
+            return Some(info);
+        }
+
+        let newest = self
+            .get_models_by_family(&name_lower)
+            .into_iter()
+            .max_by(|a, b| a.release_date.cmp(&b.release_date));
+        newest.or_else(|| self.get_model_info(&name_lower))
+    }
 }
 
 impl Default for ModelsRegistry {
@@ -251,6 +455,69 @@ mod tests {
         assert!(!registry.matches_filter("claude-opus-4-20250514", "sonnet"));
     }
 
+    #[test]
+    fn test_resolve() {
+        let registry = ModelsRegistry::new();
+
+        let resolved = |name| registry.resolve(name).map(|info| info.name.as_str());
+        assert_eq!(resolved("sonnet"), Some("claude-sonnet-4-6-20260310"));
+        assert_eq!(resolved("haiku-3.5"), Some("claude-3-5-haiku-20241022"));
+        assert_eq!(
+            resolved("claude-opus-4-20250514"),
+            Some("claude-opus-4-20250514")
+        );
+        assert_eq!(resolved("gpt-4"), None);
+    }
+
+    #[test]
+    fn test_custom_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("models.yaml");
+        fs::write(
+            &path,
+            r#"
+models:
+  - name: gpt-4o
+    patterns: ["gpt-4o*", "openai/gpt-4o*"]
+    family: gpt
+    context_window: 128000
+    pricing: { input: 2.5, output: 10, cache_read: 1.25 }
+  - name: local-llama
+    patterns: ["llama*"]
+    family: llama
+"#,
+        )
+        .unwrap();
+        let file = CustomModelsFile::load_from(&path).unwrap();
+        assert!(CustomModelsFile::load_from(&dir.path().join("missing.yaml")).is_ok());
+
+        let mut registry = ModelsRegistry::builtin();
+        registry.register_custom_models(&file);
+        let info = registry.get_model_info("openai/gpt-4o-mini").unwrap();
+        assert_eq!(info.name, "gpt-4o");
+        assert_eq!(registry.context_window("gpt-4o-2024-08-06"), 128_000);
+        assert_eq!(
+            registry.get_model_family("llama-3.1-70b"),
+            Some("llama".to_string())
+        );
+        assert!(registry.matches_filter("gpt-4o-2024-08-06", "gpt"));
+        assert!(!registry.matches_filter("claude-opus-4-20250514", "gpt"));
+
+        let pricing = file.pricing();
+        assert_eq!(pricing.len(), 1);
+        let gpt = &pricing["gpt-4o"];
+        assert_eq!(gpt.input_cost_per_token, Some(2.5 / 1_000_000.0));
+        assert_eq!(gpt.cache_creation_input_token_cost, Some(0.0));
+        assert_eq!(
+            file.find("gpt-4o-2024-08-06").map(|m| m.name.as_str()),
+            Some("gpt-4o")
+        );
+        assert!(file.find("claude-sonnet-4-20250514").is_none());
+
+        fs::write(&path, "models:\n  - name: x\n    famly: y\n").unwrap();
+        assert!(CustomModelsFile::load_from(&path).is_err());
+    }
+
     #[test]
     fn test_family_detection() {
         let registry = ModelsRegistry::new();
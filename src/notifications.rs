@@ -0,0 +1,288 @@
+//! Desktop notifications for `watch --notify`
+//!
+//! After every poll the watcher checks three rules: today's cost crossing a
+//! threshold, a session finishing above the expensive-session cost, and the
+//! current 5-hour block nearing its quota. Each alert fires once (per day,
+//! session, or block). Notifications are shown with the platform's own tool:
+//! `osascript` on macOS and `notify-send` on Linux and the BSDs.
+
+use crate::billing_blocks::BillingBlockManager;
+use crate::currency::Money;
+use crate::models::{DailyUsageMap, SessionUsageMap};
+use crate::quota::{self, Plan, QuotaLimits};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Duration, Local, Utc};
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+/// Minutes without activity after which a session counts as finished
+pub const SESSION_IDLE_MINUTES: i64 = 5;
+
+/// Lowest default expensive-session cost, so a short history does not make
+/// every session look expensive
+const MIN_EXPENSIVE_SESSION_COST: f64 = 1.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesktopNotification {
+    pub title: String,
+    pub message: String,
+}
+
+/// What `watch --notify` alerts on
+#[derive(Debug, Clone)]
+pub struct NotifyRules {
+    /// Today's cost thresholds in USD, ascending
+    pub daily_thresholds: Vec<f64>,
+    /// Cost in USD above which a finished session is reported
+    pub expensive_session: f64,
+    /// Quota share of the current block (percent) that triggers a warning;
+    /// `None` disables quota alerts
+    pub quota_warn_pct: Option<f64>,
+    pub plan: Plan,
+    pub quota_limits: QuotaLimits,
+}
+
+impl NotifyRules {
+    /// Default expensive-session cost: the 95th percentile of past sessions
+    pub fn expensive_session_default(session_map: &SessionUsageMap) -> f64 {
+        let mut costs: Vec<f64> = session_map
+            .values()
+            .map(|(usage, _)| usage.total_cost)
+            .collect();
+        if costs.is_empty() {
+            return MIN_EXPENSIVE_SESSION_COST;
+        }
+        costs.sort_by(f64::total_cmp);
+        let p95 = costs[(costs.len() - 1) * 95 / 100];
+        p95.max(MIN_EXPENSIVE_SESSION_COST)
+    }
+}
+
+/// Tracks which alerts already fired between polls
+pub struct Notifier {
+    rules: NotifyRules,
+    fired: HashSet<String>,
+    /// Sessions with activity since watching started, by last activity
+    active_sessions: HashMap<String, DateTime<Utc>>,
+}
+
+impl Notifier {
+    /// Thresholds already crossed in `daily_map` do not fire
+    pub fn new(rules: NotifyRules, daily_map: &DailyUsageMap) -> Self {
+        let mut notifier = Self {
+            rules,
+            fired: HashSet::new(),
+            active_sessions: HashMap::new(),
+        };
+        let today_cost = today_cost(daily_map);
+        for threshold in &notifier.rules.daily_thresholds {
+            if today_cost >= *threshold {
+                notifier.fired.insert(daily_key(*threshold));
+            }
+        }
+        notifier
+    }
+
+    /// Notifications due after a poll. `changed` lists the sessions that
+    /// received usage in this poll.
+    pub fn check(
+        &mut self,
+        daily_map: &DailyUsageMap,
+        session_map: &SessionUsageMap,
+        changed: &[String],
+        billing_manager: &BillingBlockManager,
+        now: DateTime<Utc>,
+    ) -> Vec<DesktopNotification> {
+        let mut notifications = Vec::new();
+
+        // Only the highest newly crossed threshold is shown
+        let today_cost = today_cost(daily_map);
+        let mut crossed = None;
+        for threshold in &self.rules.daily_thresholds {
+            if today_cost >= *threshold && self.fired.insert(daily_key(*threshold)) {
+                crossed = Some(*threshold);
+            }
+        }
+        if let Some(threshold) = crossed {
+            notifications.push(DesktopNotification {
+                title: "Daily spend threshold crossed".to_string(),
+                message: format!(
+                    "Today's cost is {:.2} (threshold {:.2})",
+                    Money(today_cost),
+                    Money(threshold)
+                ),
+            });
+        }
+
+        for session in changed {
+            if let Some((_, last_activity)) = session_map.get(session) {
+                self.active_sessions.insert(session.clone(), *last_activity);
+            }
+        }
+        let idle_since = now - Duration::minutes(SESSION_IDLE_MINUTES);
+        let finished: Vec<String> = self
+            .active_sessions
+            .iter()
+            .filter(|(_, last_activity)| **last_activity <= idle_since)
+            .map(|(session, _)| session.clone())
+            .collect();
+        for session in finished {
+            self.active_sessions.remove(&session);
+            let Some((usage, _)) = session_map.get(&session) else {
+                continue;
+            };
+            if usage.total_cost >= self.rules.expensive_session
+                && self.fired.insert(format!("session:{}", session))
+            {
+                let (project, session_id) = session.rsplit_once('/').unwrap_or(("", &session));
+                notifications.push(DesktopNotification {
+                    title: "Expensive session finished".to_string(),
+                    message: format!(
+                        "{} ({}) cost {:.2} over {} messages",
+                        project,
+                        &session_id[..session_id.len().min(8)],
+                        Money(usage.total_cost),
+                        usage.message_count
+                    ),
+                });
+            }
+        }
+
+        if let Some(warn_pct) = self.rules.quota_warn_pct {
+            let report = quota::build_quota_report(
+                billing_manager,
+                self.rules.plan,
+                self.rules.quota_limits,
+                0,
+                now,
+            );
+            if let Some(block) = &report.current
+                && block.quota_pct() >= warn_pct
+                && self.fired.insert(format!("quota:{}", block.start_time))
+            {
+                notifications.push(DesktopNotification {
+                    title: "5-hour block quota nearly used".to_string(),
+                    message: format!(
+                        "{:.0}% of the estimated {} quota used; resets in {} min",
+                        block.quota_pct(),
+                        self.rules.plan.name(),
+                        report.minutes_until_reset.unwrap_or(0)
+                    ),
+                });
+            }
+        }
+
+        notifications
+    }
+}
+
+fn today_cost(daily_map: &DailyUsageMap) -> f64 {
+    daily_map
+        .get(&Local::now().date_naive())
+        .map_or(0.0, |usage| usage.total_cost)
+}
+
+fn daily_key(threshold: f64) -> String {
+    format!("daily:{}:{:.2}", Local::now().date_naive(), threshold)
+}
+
+/// Show a notification with the platform's notification tool
+pub fn send(notification: &DesktopNotification) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title \"claudelytics\" subtitle {}",
+            applescript_string(&notification.message),
+            applescript_string(&notification.title)
+        );
+        let mut command = Command::new("osascript");
+        command.args(["-e", &script]);
+        command
+    } else if cfg!(unix) {
+        let mut command = Command::new("notify-send");
+        command.args([
+            "--app-name=claudelytics",
+            &notification.title,
+            &notification.message,
+        ]);
+        command
+    } else {
+        bail!("Desktop notifications are not supported on this platform");
+    };
+
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .status()
+        .with_context(|| format!("Failed to run `{}`", program))?;
+    if !status.success() {
+        bail!("`{}` exited with {}", program, status);
+    }
+    Ok(())
+}
+
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+
+    fn usage(cost: f64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: 100,
+            total_cost: cost,
+            message_count: 4,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_notifier_fires_once_per_rule() {
+        let now = Utc::now();
+        let today = Local::now().date_naive();
+        let rules = NotifyRules {
+            daily_thresholds: vec![5.0, 10.0, 20.0],
+            expensive_session: 3.0,
+            quota_warn_pct: None,
+            plan: Plan::Pro,
+            quota_limits: Plan::Pro.limits(),
+        };
+        let blocks = BillingBlockManager::new();
+
+        let mut daily_map = DailyUsageMap::new();
+        daily_map.insert(today, usage(6.0));
+        let mut notifier = Notifier::new(rules, &daily_map);
+
+        let mut session_map = SessionUsageMap::new();
+        session_map.insert("-home-me-api/s1".to_string(), (usage(4.0), now));
+        session_map.insert("-home-me-api/s2".to_string(), (usage(0.5), now));
+        let changed = vec!["-home-me-api/s1".to_string(), "-home-me-api/s2".to_string()];
+
+        // $6 was already over $5 when watching started; both sessions still active
+        assert!(
+            notifier
+                .check(&daily_map, &session_map, &changed, &blocks, now)
+                .is_empty()
+        );
+
+        daily_map.insert(today, usage(21.0));
+        let later = now + Duration::minutes(SESSION_IDLE_MINUTES);
+        let notifications = notifier.check(&daily_map, &session_map, &[], &blocks, later);
+        assert_eq!(notifications.len(), 2);
+        assert!(notifications[0].message.contains("threshold $20.00"));
+        assert!(
+            notifications[1]
+                .message
+                .starts_with("-home-me-api (s1) cost $4.00")
+        );
+
+        assert!(
+            notifier
+                .check(&daily_map, &session_map, &[], &blocks, later)
+                .is_empty()
+        );
+
+        assert_eq!(NotifyRules::expensive_session_default(&session_map), 1.0);
+    }
+}
@@ -0,0 +1,292 @@
+//! `export --obsidian DIR`: daily notes for an Obsidian vault
+//!
+//! Every day with usage gets a `YYYY-MM-DD.md` note in `DIR` with its cost,
+//! tokens, and session count as YAML frontmatter (`claude_cost`,
+//! `claude_tokens`, `claude_sessions`) and a section linking to that day's
+//! conversations, which are exported as notes into `DIR/conversations/`.
+//!
+//! Existing notes are updated in place: only the `claude_*` frontmatter keys
+//! and the section between the claudelytics markers are rewritten, so the
+//! folder can double as the vault's daily notes folder.
+
+use crate::conversation_export;
+use crate::conversation_parser::Conversation;
+use crate::currency::Money;
+use crate::display::format_number;
+use crate::models::{DailyUsageMap, TokenUsage};
+use crate::pricing::ModelPricing;
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+pub const CONVERSATIONS_DIR: &str = "conversations";
+const START_MARKER: &str = "<!-- claudelytics:start -->";
+const END_MARKER: &str = "<!-- claudelytics:end -->";
+/// Frontmatter keys owned by claudelytics; others are left alone
+const FRONTMATTER_KEYS: [&str; 3] = ["claude_cost", "claude_tokens", "claude_sessions"];
+
+/// What `export_vault` wrote
+#[derive(Debug, Default)]
+pub struct VaultExport {
+    pub notes_created: usize,
+    pub notes_updated: usize,
+    pub conversations: usize,
+}
+
+/// A conversation note linked from a daily note
+#[derive(Debug)]
+struct ConversationLink {
+    note: String,
+    project: String,
+    title: String,
+    cost: f64,
+}
+
+/// Write or update a daily note for every day of `daily_map` and export the
+/// conversations active on those days, rendered by `render`
+pub fn export_vault(
+    vault_dir: &Path,
+    daily_map: &DailyUsageMap,
+    mut conversations: Vec<Conversation>,
+    projects_dir: &Path,
+    pricing_data: &HashMap<String, ModelPricing>,
+    render: impl Fn(&Conversation) -> Result<String>,
+) -> Result<VaultExport> {
+    conversations.retain(|conversation| {
+        active_dates(conversation)
+            .iter()
+            .any(|date| daily_map.contains_key(date))
+    });
+    conversation_export::export_split_files(
+        &conversations,
+        projects_dir,
+        &vault_dir.join(CONVERSATIONS_DIR),
+        "markdown",
+        pricing_data,
+        render,
+    )?;
+
+    let mut links: HashMap<NaiveDate, Vec<ConversationLink>> = HashMap::new();
+    for conversation in &conversations {
+        let note = conversation_export::file_stem(projects_dir, conversation);
+        let project = note.split("__").next().unwrap_or_default().to_string();
+        let title = conversation
+            .summary
+            .as_ref()
+            .map_or_else(|| "Conversation".to_string(), |s| s.summary.clone());
+        let cost = conversation_export::conversation_cost(conversation, pricing_data);
+        for date in active_dates(conversation) {
+            links.entry(date).or_default().push(ConversationLink {
+                note: note.clone(),
+                project: project.clone(),
+                title: title.clone(),
+                cost,
+            });
+        }
+    }
+
+    let mut export = VaultExport {
+        conversations: conversations.len(),
+        ..Default::default()
+    };
+    for (date, usage) in daily_map {
+        let mut day_links = links.remove(date).unwrap_or_default();
+        day_links.sort_by(|a, b| a.note.cmp(&b.note));
+        let frontmatter = [
+            ("claude_cost", format!("{:.4}", usage.total_cost)),
+            ("claude_tokens", usage.total_tokens().to_string()),
+            ("claude_sessions", day_links.len().to_string()),
+        ];
+        let section = render_section(usage, &day_links);
+
+        let path = vault_dir.join(format!("{}.md", date.format("%Y-%m-%d")));
+        let existing = match fs::read_to_string(&path) {
+            Ok(existing) => Some(existing),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let note = update_note(existing.as_deref(), &frontmatter, &section);
+        fs::write(&path, note).with_context(|| format!("Failed to write {}", path.display()))?;
+        if existing.is_some() {
+            export.notes_updated += 1;
+        } else {
+            export.notes_created += 1;
+        }
+    }
+    Ok(export)
+}
+
+/// Local dates with messages in the conversation
+fn active_dates(conversation: &Conversation) -> BTreeSet<NaiveDate> {
+    conversation
+        .messages
+        .iter()
+        .map(|message| message.timestamp.with_timezone(&Local).date_naive())
+        .collect()
+}
+
+fn render_section(usage: &TokenUsage, links: &[ConversationLink]) -> String {
+    let mut section = String::from("## Claude Code\n\n");
+    let _ = writeln!(section, "- **Cost:** {:.4}", Money(usage.total_cost));
+    let _ = writeln!(
+        section,
+        "- **Tokens:** {} (input {} · output {} · cache {})",
+        format_number(usage.total_tokens()),
+        format_number(usage.input_tokens),
+        format_number(usage.output_tokens),
+        format_number(usage.cache_creation_tokens + usage.cache_read_tokens)
+    );
+    let _ = writeln!(section, "- **Sessions:** {}", links.len());
+    if !links.is_empty() {
+        section.push_str("\n### Conversations\n\n");
+        for link in links {
+            let _ = writeln!(
+                section,
+                "- [[{}|{}: {}]] ({:.4})",
+                link.note,
+                link.project,
+                link.title.replace(['[', ']', '|'], ""),
+                Money(link.cost)
+            );
+        }
+    }
+    section
+}
+
+/// Set the claudelytics frontmatter keys and section of a note, keeping
+/// everything else in `existing`
+fn update_note(existing: Option<&str>, frontmatter: &[(&str, String)], section: &str) -> String {
+    let existing = existing.unwrap_or_default();
+    let (mut yaml, body) = match existing
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---"))
+    {
+        Some((yaml, body)) => (
+            yaml.lines()
+                .filter(|line| {
+                    !FRONTMATTER_KEYS
+                        .iter()
+                        .any(|key| line.strip_prefix(key).is_some_and(|v| v.starts_with(':')))
+                })
+                .map(|line| format!("{}\n", line))
+                .collect::<String>(),
+            body.strip_prefix('\n').unwrap_or(body),
+        ),
+        None => (String::new(), existing),
+    };
+    for (key, value) in frontmatter {
+        let _ = writeln!(yaml, "{}: {}", key, value);
+    }
+
+    let block = format!("{}\n{}{}", START_MARKER, section, END_MARKER);
+    let body = match (body.find(START_MARKER), body.find(END_MARKER)) {
+        (Some(start), Some(end)) if start < end => format!(
+            "{}{}{}",
+            &body[..start],
+            block,
+            &body[end + END_MARKER.len()..]
+        ),
+        _ if body.trim().is_empty() => format!("{}\n", block),
+        _ => format!("{}\n\n{}\n", body.trim_end(), block),
+    };
+    format!("---\n{}---\n{}", yaml, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation_parser::ConversationParser;
+
+    #[test]
+    fn test_update_note_keeps_user_content() {
+        let frontmatter = [
+            ("claude_cost", "1.5000".to_string()),
+            ("claude_tokens", "1200".to_string()),
+            ("claude_sessions", "2".to_string()),
+        ];
+        let created = update_note(None, &frontmatter, "## Claude Code\n");
+        assert_eq!(
+            created,
+            "---\nclaude_cost: 1.5000\nclaude_tokens: 1200\nclaude_sessions: 2\n---\n\
+             <!-- claudelytics:start -->\n## Claude Code\n<!-- claudelytics:end -->\n"
+        );
+
+        let existing = "---\ntags: [log]\nclaude_cost: 0.1000\n---\n# Monday\n\nShipped the fix.\n";
+        let updated = update_note(Some(existing), &frontmatter, "## Claude Code\n");
+        assert!(updated.starts_with("---\ntags: [log]\nclaude_cost: 1.5000\n"));
+        assert!(!updated.contains("0.1000"));
+        assert!(updated.contains("# Monday\n\nShipped the fix.\n\n<!-- claudelytics:start -->"));
+
+        // A second run replaces the section in place
+        let edited = updated.replace(END_MARKER, &format!("{}\nMore notes.", END_MARKER));
+        let rerun = update_note(Some(&edited), &frontmatter, "## Claude Code\nnew\n");
+        assert_eq!(rerun.matches(START_MARKER).count(), 1);
+        assert!(rerun.contains("## Claude Code\nnew\n<!-- claudelytics:end -->\nMore notes."));
+        assert_eq!(rerun.matches("claude_cost").count(), 1);
+    }
+
+    #[test]
+    fn test_export_vault_links_conversations() {
+        let claude_dir = tempfile::tempdir().unwrap();
+        let projects_dir = claude_dir.path().join("projects");
+        let project_dir = projects_dir.join("-home-me-app");
+        fs::create_dir_all(&project_dir).unwrap();
+        let lines = [
+            r#"{"type":"summary","summary":"Fix login","leafUuid":"a1"}"#,
+            r#"{"type":"user","uuid":"u1","parentUuid":null,"sessionId":"abc","timestamp":"2024-01-01T12:00:00Z","message":{"role":"user","content":"Fix login"}}"#,
+            r#"{"type":"assistant","uuid":"a1","parentUuid":"u1","sessionId":"abc","timestamp":"2024-01-01T12:00:05Z","message":{"role":"assistant","model":"claude-sonnet-4","content":[{"type":"text","text":"Done"}],"usage":{"input_tokens":10,"output_tokens":5}}}"#,
+        ];
+        fs::write(project_dir.join("abc.jsonl"), lines.join("\n")).unwrap();
+        let parser = ConversationParser::new(claude_dir.path().to_path_buf());
+        let conversations: Vec<_> = parser
+            .find_conversation_files()
+            .unwrap()
+            .iter()
+            .map(|path| parser.parse_conversation(path).unwrap())
+            .collect();
+        let date = conversations[0]
+            .started_at
+            .unwrap()
+            .with_timezone(&Local)
+            .date_naive();
+
+        let mut daily_map = DailyUsageMap::new();
+        daily_map.insert(
+            date,
+            TokenUsage {
+                input_tokens: 10,
+                output_tokens: 5,
+                total_cost: 0.25,
+                ..Default::default()
+            },
+        );
+        let vault = tempfile::tempdir().unwrap();
+        let export = export_vault(
+            vault.path(),
+            &daily_map,
+            conversations,
+            &projects_dir,
+            &HashMap::new(),
+            |_| Ok("conversation".to_string()),
+        )
+        .unwrap();
+        assert_eq!(export.notes_created, 1);
+        assert_eq!(export.conversations, 1);
+
+        let stem = format!("-home-me-app__abc__{}", date.format("%Y-%m-%d"));
+        let note = fs::read_to_string(vault.path().join(format!("{}.md", date.format("%Y-%m-%d"))))
+            .unwrap();
+        assert!(note.contains("claude_cost: 0.2500\nclaude_tokens: 15\nclaude_sessions: 1\n"));
+        assert!(note.contains(&format!("[[{}|-home-me-app: Fix login]]", stem)));
+        assert!(
+            vault
+                .path()
+                .join(CONVERSATIONS_DIR)
+                .join(format!("{}.md", stem))
+                .exists()
+        );
+    }
+}
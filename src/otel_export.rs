@@ -0,0 +1,454 @@
+//! OpenTelemetry export of usage records (`otel-export` command)
+//!
+//! Replays parsed usage to an OTLP/HTTP collector using the JSON encoding, so
+//! no protobuf or SDK dependencies are needed:
+//! - Traces (`/v1/traces`): one `claude_code.session` span per session, from
+//!   its first to its last record, with token, cost and message totals
+//! - Metrics (`/v1/metrics`): delta sums per UTC hour, model and project,
+//!   `claude_code.token.usage` (with a `type` attribute: input, output,
+//!   cache_creation, cache_read) and `claude_code.cost.usage` in USD. The
+//!   names match the metrics Claude Code emits with its own telemetry enabled.
+//!
+//! Trace and span IDs are derived from the session key, so exporting the same
+//! session again produces the same IDs.
+
+use crate::burn_rate::UsageEvent;
+use crate::models::TokenUsage;
+use crate::reports::parse_session_path;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
+
+/// Collector used when neither `--endpoint` nor `OTEL_EXPORTER_OTLP_ENDPOINT` is set
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:4318";
+
+/// Spans or metric buckets per request
+const BATCH_SIZE: usize = 500;
+
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// `AGGREGATION_TEMPORALITY_DELTA`
+const TEMPORALITY_DELTA: u8 = 1;
+
+/// `SPAN_KIND_INTERNAL`
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+/// Where and how to send the payloads
+pub struct OtlpOptions {
+    /// Base URL; `/v1/traces` and `/v1/metrics` are appended
+    pub endpoint: String,
+    pub headers: Vec<(String, String)>,
+    pub traces: bool,
+    pub metrics: bool,
+    /// Print the payloads as JSON lines instead of sending them
+    pub dry_run: bool,
+}
+
+impl OtlpOptions {
+    /// Resolve the endpoint and headers from the flags, falling back to the
+    /// standard `OTEL_EXPORTER_OTLP_ENDPOINT` / `OTEL_EXPORTER_OTLP_HEADERS`
+    pub fn from_args(endpoint: Option<&str>, headers: &[String]) -> Result<Self> {
+        let endpoint = endpoint
+            .map(str::to_string)
+            .or_else(|| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok())
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            bail!("OTLP endpoint must start with http:// or https://");
+        }
+
+        let env_headers = std::env::var("OTEL_EXPORTER_OTLP_HEADERS").unwrap_or_default();
+        let headers = env_headers
+            .split(',')
+            .filter(|h| !h.trim().is_empty())
+            .chain(headers.iter().map(String::as_str))
+            .map(|header| {
+                header
+                    .split_once('=')
+                    .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                    .with_context(|| format!("Invalid header '{}'. Use KEY=VALUE", header))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            headers,
+            traces: true,
+            metrics: true,
+            dry_run: false,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OtelExportSummary {
+    pub endpoint: String,
+    pub dry_run: bool,
+    pub spans: usize,
+    pub data_points: usize,
+    pub requests: usize,
+}
+
+/// Totals of one session, in record order
+struct SessionSpan {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    usage: TokenUsage,
+    models: BTreeSet<String>,
+}
+
+/// Build the `/v1/traces` request bodies, one span per session
+pub fn trace_payloads(events: &[UsageEvent], summaries: &HashMap<String, String>) -> Vec<Value> {
+    let mut sessions: BTreeMap<&str, SessionSpan> = BTreeMap::new();
+    for event in events {
+        let span = sessions
+            .entry(event.session.as_str())
+            .or_insert_with(|| SessionSpan {
+                start: event.timestamp,
+                end: event.timestamp,
+                usage: TokenUsage::default(),
+                models: BTreeSet::new(),
+            });
+        span.start = span.start.min(event.timestamp);
+        span.end = span.end.max(event.timestamp);
+        span.usage.add(&event.usage);
+        span.models.insert(event.model.clone());
+    }
+
+    let spans: Vec<Value> = sessions
+        .into_iter()
+        .map(|(session, span)| {
+            let (project, session_id) = parse_session_path(session);
+            let mut attributes = vec![
+                string_attribute("session.id", &session_id),
+                string_attribute("project", &project),
+                json!({
+                    "key": "models",
+                    "value": {"arrayValue": {"values": span
+                        .models
+                        .iter()
+                        .map(|m| json!({"stringValue": m}))
+                        .collect::<Vec<_>>()}}
+                }),
+                int_attribute("tokens.input", span.usage.input_tokens),
+                int_attribute("tokens.output", span.usage.output_tokens),
+                int_attribute("tokens.cache_creation", span.usage.cache_creation_tokens),
+                int_attribute("tokens.cache_read", span.usage.cache_read_tokens),
+                int_attribute("messages", span.usage.message_count),
+                json!({"key": "cost.usd", "value": {"doubleValue": span.usage.total_cost}}),
+            ];
+            if let Some(summary) = summaries.get(session) {
+                attributes.push(string_attribute("session.summary", summary));
+            }
+
+            json!({
+                "traceId": hex_id(session, 0x5452_4143, 2),
+                "spanId": hex_id(session, 0x5350_414e, 1),
+                "name": "claude_code.session",
+                "kind": SPAN_KIND_INTERNAL,
+                "startTimeUnixNano": unix_nanos(span.start),
+                "endTimeUnixNano": unix_nanos(span.end),
+                "attributes": attributes,
+            })
+        })
+        .collect();
+
+    spans
+        .chunks(BATCH_SIZE)
+        .map(|batch| {
+            json!({
+                "resourceSpans": [{
+                    "resource": resource(),
+                    "scopeSpans": [{"scope": scope(), "spans": batch}],
+                }]
+            })
+        })
+        .collect()
+}
+
+/// Build the `/v1/metrics` request bodies with hourly delta sums
+pub fn metric_payloads(events: &[UsageEvent]) -> Vec<Value> {
+    let mut buckets: BTreeMap<(DateTime<Utc>, String, &str), TokenUsage> = BTreeMap::new();
+    for event in events {
+        let hour = event
+            .timestamp
+            .duration_trunc(TimeDelta::hours(1))
+            .unwrap_or(event.timestamp);
+        let (project, _) = parse_session_path(&event.session);
+        buckets
+            .entry((hour, project, event.model.as_str()))
+            .or_default()
+            .add(&event.usage);
+    }
+
+    let buckets: Vec<_> = buckets.into_iter().collect();
+    buckets
+        .chunks(BATCH_SIZE)
+        .map(|batch| {
+            let mut token_points = Vec::new();
+            let mut cost_points = Vec::new();
+            for ((hour, project, model), usage) in batch {
+                let start = unix_nanos(*hour);
+                let end = unix_nanos(*hour + TimeDelta::hours(1));
+                for (kind, tokens) in [
+                    ("input", usage.input_tokens),
+                    ("output", usage.output_tokens),
+                    ("cache_creation", usage.cache_creation_tokens),
+                    ("cache_read", usage.cache_read_tokens),
+                ] {
+                    if tokens == 0 {
+                        continue;
+                    }
+                    token_points.push(json!({
+                        "attributes": [
+                            string_attribute("type", kind),
+                            string_attribute("model", model),
+                            string_attribute("project", project),
+                        ],
+                        "startTimeUnixNano": start,
+                        "timeUnixNano": end,
+                        "asInt": tokens.to_string(),
+                    }));
+                }
+                cost_points.push(json!({
+                    "attributes": [
+                        string_attribute("model", model),
+                        string_attribute("project", project),
+                    ],
+                    "startTimeUnixNano": start,
+                    "timeUnixNano": end,
+                    "asDouble": usage.total_cost,
+                }));
+            }
+
+            json!({
+                "resourceMetrics": [{
+                    "resource": resource(),
+                    "scopeMetrics": [{
+                        "scope": scope(),
+                        "metrics": [
+                            sum_metric(
+                                "claude_code.token.usage",
+                                "tokens",
+                                "Number of tokens used",
+                                token_points,
+                            ),
+                            sum_metric(
+                                "claude_code.cost.usage",
+                                "USD",
+                                "Cost of usage",
+                                cost_points,
+                            ),
+                        ],
+                    }],
+                }]
+            })
+        })
+        .collect()
+}
+
+/// Send (or with `dry_run`, print) every payload
+pub fn export(
+    events: &[UsageEvent],
+    summaries: &HashMap<String, String>,
+    options: &OtlpOptions,
+) -> Result<OtelExportSummary> {
+    let mut summary = OtelExportSummary {
+        endpoint: options.endpoint.clone(),
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+
+    let mut requests: Vec<(String, Value)> = Vec::new();
+    if options.traces {
+        for payload in trace_payloads(events, summaries) {
+            summary.spans += payload["resourceSpans"][0]["scopeSpans"][0]["spans"]
+                .as_array()
+                .map_or(0, Vec::len);
+            requests.push((format!("{}/v1/traces", options.endpoint), payload));
+        }
+    }
+    if options.metrics {
+        for payload in metric_payloads(events) {
+            summary.data_points += payload["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+                .as_array()
+                .map_or(0, |metrics| {
+                    metrics
+                        .iter()
+                        .filter_map(|m| m["sum"]["dataPoints"].as_array())
+                        .map(Vec::len)
+                        .sum()
+                });
+            requests.push((format!("{}/v1/metrics", options.endpoint), payload));
+        }
+    }
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build();
+    for (url, payload) in &requests {
+        if options.dry_run {
+            println!("{}", json!({"url": url, "body": payload}));
+            continue;
+        }
+        let mut request = agent.post(url);
+        for (key, value) in &options.headers {
+            request = request.set(key, value);
+        }
+        request
+            .send_json(payload)
+            .with_context(|| format!("Failed to send OTLP payload to {}", url))?;
+    }
+    summary.requests = requests.len();
+
+    Ok(summary)
+}
+
+pub fn display_export_summary(summary: &OtelExportSummary, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(summary) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+    // Dry runs print the payloads to stdout, so keep the summary off it
+    if summary.dry_run {
+        eprintln!(
+            "Dry run: {} requests ({} spans, {} data points) for {}",
+            summary.requests, summary.spans, summary.data_points, summary.endpoint
+        );
+        return;
+    }
+
+    println!("{}", "📡 OTLP Export".bright_blue().bold());
+    println!("  {:<14} {}", "Endpoint:".cyan(), summary.endpoint);
+    println!("  {:<14} {}", "Spans:".cyan(), summary.spans);
+    println!("  {:<14} {}", "Data points:".cyan(), summary.data_points);
+    println!("  {:<14} {}", "Requests:".cyan(), summary.requests);
+}
+
+fn resource() -> Value {
+    json!({
+        "attributes": [
+            string_attribute("service.name", "claudelytics"),
+            string_attribute("service.version", env!("CARGO_PKG_VERSION")),
+        ]
+    })
+}
+
+fn scope() -> Value {
+    json!({"name": "claudelytics", "version": env!("CARGO_PKG_VERSION")})
+}
+
+fn sum_metric(name: &str, unit: &str, description: &str, data_points: Vec<Value>) -> Value {
+    json!({
+        "name": name,
+        "unit": unit,
+        "description": description,
+        "sum": {
+            "aggregationTemporality": TEMPORALITY_DELTA,
+            "isMonotonic": true,
+            "dataPoints": data_points,
+        }
+    })
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({"key": key, "value": {"stringValue": value}})
+}
+
+/// OTLP/JSON encodes 64-bit integers as strings
+fn int_attribute(key: &str, value: u64) -> Value {
+    json!({"key": key, "value": {"intValue": value.to_string()}})
+}
+
+fn unix_nanos(timestamp: DateTime<Utc>) -> String {
+    timestamp
+        .timestamp_nanos_opt()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Deterministic hex ID of `words` 64-bit FNV-1a hashes of `key`
+fn hex_id(key: &str, seed: u64, words: u64) -> String {
+    (0..words)
+        .map(|word| {
+            let hash = key.bytes().fold(
+                0xcbf2_9ce4_8422_2325 ^ seed.wrapping_add(word),
+                |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3),
+            );
+            format!("{:016x}", hash)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(minute: u32, session: &str, model: &str) -> UsageEvent {
+        UsageEvent {
+            timestamp: Utc.with_ymd_and_hms(2025, 6, 3, 10, minute, 0).unwrap(),
+            session: session.to_string(),
+            model: model.to_string(),
+            usage: TokenUsage {
+                input_tokens: 100,
+                output_tokens: 20,
+                total_cost: 0.5,
+                message_count: 1,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_otlp_payloads() {
+        let events = vec![
+            event(5, "-home-me-api/s1", "claude-sonnet-4-20250514"),
+            event(50, "-home-me-api/s1", "claude-opus-4-20250514"),
+            event(30, "-home-me-web/s2", "claude-sonnet-4-20250514"),
+        ];
+        let summaries = HashMap::from([("-home-me-api/s1".to_string(), "Fix CI".to_string())]);
+
+        let traces = trace_payloads(&events, &summaries);
+        assert_eq!(traces.len(), 1);
+        let spans = traces[0]["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+        assert_eq!(spans.len(), 2);
+        let span = &spans[0];
+        assert_eq!(span["traceId"].as_str().unwrap().len(), 32);
+        assert_eq!(span["spanId"].as_str().unwrap().len(), 16);
+        assert_eq!(span["startTimeUnixNano"], "1748945100000000000");
+        assert_eq!(span["endTimeUnixNano"], "1748947800000000000");
+        let attribute = |key: &str| {
+            span["attributes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|a| a["key"] == key)
+                .map(|a| a["value"].clone())
+                .unwrap()
+        };
+        assert_eq!(attribute("session.id")["stringValue"], "s1");
+        assert_eq!(attribute("tokens.input")["intValue"], "200");
+        assert_eq!(attribute("session.summary")["stringValue"], "Fix CI");
+
+        let metrics = metric_payloads(&events);
+        let metrics = metrics[0]["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+            .as_array()
+            .unwrap();
+        // Three (project, model) buckets in the 10:00 hour, input and output each
+        assert_eq!(metrics[0]["sum"]["dataPoints"].as_array().unwrap().len(), 6);
+        assert_eq!(metrics[1]["sum"]["dataPoints"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            metrics[1]["sum"]["dataPoints"][0]["startTimeUnixNano"],
+            "1748944800000000000"
+        );
+    }
+}
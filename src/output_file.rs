@@ -0,0 +1,51 @@
+//! `--output`: send everything a command prints to a file
+//!
+//! Standard output is redirected at the file descriptor level for as long as
+//! the returned guard lives, so tables, JSON, and enhanced reports all end up
+//! in the file exactly as they would have been printed, minus colors.
+//! Warnings and errors still go to stderr.
+
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Restores stdout when dropped
+pub struct OutputFile {
+    _redirect: gag::Redirect<File>,
+}
+
+impl OutputFile {
+    /// Redirect stdout to `path`, creating its directory if needed; `append`
+    /// keeps what the file already contains
+    pub fn redirect(path: &Path, append: bool) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)
+            .with_context(|| format!("Failed to open output file: {}", path.display()))?;
+
+        std::io::stdout().flush()?;
+        let redirect = gag::Redirect::stdout(file).map_err(|e| {
+            anyhow::anyhow!("Failed to redirect output to {}: {}", path.display(), e)
+        })?;
+        // ANSI colors are noise in a log file
+        colored::control::set_override(false);
+        Ok(Self {
+            _redirect: redirect,
+        })
+    }
+}
+
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        // Write out what is still buffered before stdout is restored
+        let _ = std::io::stdout().flush();
+    }
+}
@@ -0,0 +1,94 @@
+//! User-supplied output templates (`--template FILE`)
+//!
+//! Reports that print JSON can instead render it through a Tera template, so
+//! status-bar widgets, polybar or waybar modules, and custom JSON shapes don't
+//! need a built-in format. The template sees the same fields as `--json`
+//! output (e.g. `daily`, `totals`, and `currency` for the daily report) and
+//! its output is printed as is, without a trailing newline or HTML escaping.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::OnceLock;
+use tera::Tera;
+
+const TEMPLATE_NAME: &str = "report";
+
+static CURRENT: OnceLock<Tera> = OnceLock::new();
+
+/// Compile the template at `path` and render JSON reports through it from now on
+pub fn load(path: &Path) -> Result<()> {
+    let source = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read template: {}", path.display()))?;
+    let tera = compile(&source).with_context(|| format!("Invalid template {}", path.display()))?;
+    let _ = CURRENT.set(tera);
+    Ok(())
+}
+
+/// The template loaded with `load`, if any
+pub fn current() -> Option<&'static Tera> {
+    CURRENT.get()
+}
+
+fn compile(source: &str) -> Result<Tera> {
+    let mut tera = Tera::default();
+    tera.autoescape_on(Vec::new());
+    tera.add_raw_template(TEMPLATE_NAME, source)
+        .map_err(describe)?;
+    Ok(tera)
+}
+
+/// Render a serialized report; its top-level fields become template variables
+pub fn render(tera: &Tera, report: &serde_json::Value) -> Result<String> {
+    let context = match report {
+        serde_json::Value::Object(_) => tera::Context::from_value(report.clone()),
+        _ => tera::Context::from_serialize(serde_json::json!({ "report": report })),
+    }
+    .map_err(describe)?;
+    tera.render(TEMPLATE_NAME, &context).map_err(describe)
+}
+
+/// Tera keeps the useful part of an error (line, unknown variable) in its source chain
+fn describe(error: tera::Error) -> anyhow::Error {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(&error);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    anyhow::anyhow!(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let tera = compile(
+            "{% for day in daily %}{{ day.date }} {{ day.totalCost | round(precision=2) }}\n{% endfor %}\
+             total={{ totals.totalCost }}{{ currency }}",
+        )
+        .unwrap();
+        let report = serde_json::json!({
+            "daily": [{"date": "2024-03-02", "totalCost": 1.234}, {"date": "2024-03-01", "totalCost": 0.5}],
+            "totals": {"totalCost": 1.734},
+            "currency": "<USD>",
+        });
+        assert_eq!(
+            render(&tera, &report).unwrap(),
+            "2024-03-02 1.23\n2024-03-01 0.5\ntotal=1.734<USD>"
+        );
+    }
+
+    #[test]
+    fn test_errors_name_the_problem() {
+        let error = compile("{{ daily ").unwrap_err().to_string();
+        assert!(error.contains("1:"), "{}", error);
+
+        let tera = compile("{{ missing.field }}").unwrap();
+        let error = render(&tera, &serde_json::json!({}))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("missing.field"), "{}", error);
+    }
+}
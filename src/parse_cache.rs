@@ -0,0 +1,302 @@
+//! Incremental on-disk cache of parsed JSONL usage records
+//!
+//! Each JSONL file's usage records are stored keyed by path, modification time,
+//! and size. Unchanged files are served from the cache so that only new or
+//! modified files are re-read and re-parsed. Filtering, deduplication, and cost
+//! calculation still run on every invocation, so cached results never depend on
+//! the command-line flags of the run that produced them.
+//!
+//! Compressing a session (`s.jsonl` to `s.jsonl.gz`) keeps its records: the
+//! entry of the removed original is moved to the compressed path when the
+//! compressed file's recorded uncompressed size matches it.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::compression;
+use crate::models::UsageRecord;
+
+/// Layout of the cached records. Bump it whenever `UsageRecord` or `ParsedFile`
+/// changes shape, so caches written by a build with the old layout are dropped
+/// instead of being read with missing or misread fields.
+const SCHEMA_VERSION: u32 = 1;
+
+/// Usage records read from a single JSONL file
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ParsedFile {
+    /// Usage records extracted from the file
+    pub records: Vec<UsageRecord>,
+    /// Number of lines that could not be deserialized
+    #[serde(default)]
+    pub invalid_lines: usize,
+    /// Session summary record, or else the first user prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Cached records for a single JSONL file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedFile {
+    /// Modification time in nanoseconds since the Unix epoch
+    pub modified_nanos: u128,
+    /// File size in bytes
+    pub size: u64,
+    #[serde(flatten)]
+    pub parsed: ParsedFile,
+}
+
+/// File identity used to detect changes since the cache was written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    pub modified_nanos: u128,
+    pub size: u64,
+}
+
+impl FileStamp {
+    /// Read the current stamp of a file from its metadata
+    pub fn of(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path)
+            .with_context(|| format!("Failed to stat file: {}", path.display()))?;
+        let modified_nanos = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0);
+        Ok(Self {
+            modified_nanos,
+            size: metadata.len(),
+        })
+    }
+}
+
+/// Incremental parse cache persisted between runs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParseCache {
+    /// Version for cache compatibility
+    pub version: String,
+    /// `SCHEMA_VERSION` of the build that wrote the cache; 0 before it was recorded
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Cached files keyed by absolute path
+    pub files: HashMap<PathBuf, CachedFile>,
+}
+
+impl Default for ParseCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParseCache {
+    /// Create an empty cache for the current version
+    pub fn new() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: SCHEMA_VERSION,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Get cache file path
+    pub fn get_cache_path() -> Result<PathBuf> {
+        let cache_dir = dirs::cache_dir()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".cache")))
+            .ok_or_else(|| anyhow::anyhow!("Could not determine cache directory"))?;
+
+        Ok(cache_dir.join("claudelytics").join("parse_cache.json"))
+    }
+
+    /// Load cache from disk, starting fresh if it is missing, corrupt, or from another
+    /// version or schema
+    pub fn load() -> Self {
+        Self::get_cache_path()
+            .ok()
+            .and_then(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    fn load_from(path: &Path) -> Option<Self> {
+        let data = fs::read(path).ok()?;
+        let cache: ParseCache = serde_json::from_slice(&data).ok()?;
+        (cache.version == env!("CARGO_PKG_VERSION") && cache.schema_version == SCHEMA_VERSION)
+            .then_some(cache)
+    }
+
+    /// Save cache to disk atomically so concurrent runs never see a partial file
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::get_cache_path()?)
+    }
+
+    fn save_to(&self, cache_path: &Path) -> Result<()> {
+        let dir = cache_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Invalid cache path: {}", cache_path.display()))?;
+        fs::create_dir_all(dir)?;
+
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        serde_json::to_writer(&mut tmp, self).context("Failed to serialize parse cache")?;
+        tmp.persist(cache_path)
+            .with_context(|| format!("Failed to write cache file: {}", cache_path.display()))?;
+
+        Ok(())
+    }
+
+    /// Clear the cache
+    pub fn clear() -> Result<()> {
+        let cache_path = Self::get_cache_path()?;
+
+        if cache_path.exists() {
+            fs::remove_file(&cache_path).with_context(|| {
+                format!("Failed to remove cache file: {}", cache_path.display())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Take the cached records for `path` if the file has not changed
+    pub fn take_if_fresh(&mut self, path: &Path, stamp: FileStamp) -> Option<ParsedFile> {
+        let cached = self.files.get(path)?;
+        if cached.modified_nanos != stamp.modified_nanos || cached.size != stamp.size {
+            return None;
+        }
+        self.files.remove(path).map(|cached| cached.parsed)
+    }
+
+    /// Take the cached records of the `.jsonl` file that `path` is a compressed copy of
+    pub fn take_compressed_original(&mut self, path: &Path) -> Option<ParsedFile> {
+        let original = compression::uncompressed_path(path)?;
+        let cached = self.files.get(&original)?;
+        if !compression::replaces_original(path, cached.size) {
+            return None;
+        }
+        self.files.remove(&original).map(|cached| cached.parsed)
+    }
+
+    /// Store records for `path`
+    pub fn insert(&mut self, path: PathBuf, stamp: FileStamp, parsed: ParsedFile) {
+        self.files.insert(
+            path,
+            CachedFile {
+                modified_nanos: stamp.modified_nanos,
+                size: stamp.size,
+                parsed,
+            },
+        );
+    }
+
+    /// Drop entries for files that no longer exist; returns how many were dropped
+    pub fn prune_missing(&mut self) -> usize {
+        let before = self.files.len();
+        self.files.retain(|path, _| path.exists());
+        before - self.files.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_file() -> ParsedFile {
+        ParsedFile {
+            records: vec![serde_json::from_str(
+                r#"{"timestamp":"2024-01-15T12:00:00Z","costUSD":0.5,"requestId":"req1","message":{"id":"msg1","model":"claude-3-opus-20240229","usage":{"input_tokens":100,"output_tokens":200}}}"#,
+            )
+            .unwrap()],
+            invalid_lines: 3,
+            summary: None,
+        }
+    }
+
+    #[test]
+    fn test_stale_entries_are_not_returned() {
+        let mut cache = ParseCache::new();
+        let path = PathBuf::from("/tmp/session.jsonl");
+        let stamp = FileStamp {
+            modified_nanos: 10,
+            size: 100,
+        };
+        cache.insert(path.clone(), stamp, sample_file());
+
+        let changed = FileStamp { size: 120, ..stamp };
+        assert!(cache.take_if_fresh(&path, changed).is_none());
+        assert_eq!(
+            cache.take_if_fresh(&path, stamp).map(|f| f.records.len()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_compressed_file_takes_over_original_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = b"{}\n";
+        let compressed = temp_dir.path().join("s.jsonl.zst");
+        fs::write(&compressed, zstd::bulk::compress(content, 3).unwrap()).unwrap();
+
+        let mut cache = ParseCache::new();
+        let original = FileStamp {
+            modified_nanos: 1,
+            size: content.len() as u64,
+        };
+        cache.insert(temp_dir.path().join("s.jsonl"), original, sample_file());
+
+        let parsed = cache
+            .take_compressed_original(&compressed)
+            .expect("records of the original file");
+        assert_eq!(parsed.invalid_lines, 3);
+        assert!(cache.files.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("parse_cache.json");
+        let mut cache = ParseCache::new();
+        let stamp = FileStamp {
+            modified_nanos: 1,
+            size: 2,
+        };
+        cache.insert(PathBuf::from("/a.jsonl"), stamp, sample_file());
+        cache.save_to(&cache_path).unwrap();
+
+        let mut loaded = ParseCache::load_from(&cache_path).unwrap();
+        let parsed = loaded
+            .take_if_fresh(Path::new("/a.jsonl"), stamp)
+            .expect("cached records");
+        assert_eq!(parsed.records[0].dedup_hash().as_deref(), Some("msg1:req1"));
+        assert_eq!(parsed.records[0].cost_usd, Some(0.5));
+        assert_eq!(parsed.invalid_lines, 3);
+    }
+
+    #[test]
+    fn test_other_schema_is_discarded() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("parse_cache.json");
+        let mut cache = ParseCache::new();
+        let stamp = FileStamp {
+            modified_nanos: 1,
+            size: 2,
+        };
+        cache.insert(PathBuf::from("/a.jsonl"), stamp, sample_file());
+
+        cache.schema_version = SCHEMA_VERSION + 1;
+        cache.save_to(&cache_path).unwrap();
+        assert!(ParseCache::load_from(&cache_path).is_none());
+
+        // Caches written before the schema was recorded
+        let mut value = serde_json::to_value(&cache).unwrap();
+        value.as_object_mut().unwrap().remove("schema_version");
+        fs::write(&cache_path, value.to_string()).unwrap();
+        assert!(ParseCache::load_from(&cache_path).is_none());
+
+        cache.schema_version = SCHEMA_VERSION;
+        cache.save_to(&cache_path).unwrap();
+        assert!(ParseCache::load_from(&cache_path).is_some());
+    }
+}
@@ -1,15 +1,30 @@
-use crate::billing_blocks::BillingBlockManager;
+//! Reading Claude Code's JSONL session logs into usage maps
+//!
+//! `UsageParser` walks the `projects/` directory of each Claude directory,
+//! prices every assistant message, and aggregates the result per day, per
+//! session, and per billing block.
+
+use crate::archive::UsageArchive;
+use crate::audit::{AuditEntry, CostAudit};
+use crate::billing_blocks::{BillingBlockConfig, BillingBlockManager};
+use crate::burn_rate::UsageEvent;
+use crate::compression;
+use crate::import::{IMPORTED_PROJECT, ImportStore, ImportedUsage};
 use crate::models::{DailyUsageMap, SessionUsageMap, TokenUsage, UsageRecord};
 use crate::models_registry::ModelsRegistry;
-use crate::pricing::{FAST_MODE_MULTIPLIER, PricingFetcher, get_fallback_pricing};
-use anyhow::{Context, Result};
-use chrono::{Local, NaiveDate, TimeZone};
+use crate::parse_cache::{FileStamp, ParseCache, ParsedFile};
+use crate::pricing::{BATCH_DISCOUNT_MULTIPLIER, FAST_MODE_MULTIPLIER, PricingFetcher};
+use crate::pricing_cache::PricingCache;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
 use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use walkdir::WalkDir;
 
 /// Cost calculation mode
@@ -22,8 +37,76 @@ pub enum CostMode {
     Calculate,
     /// Only show costUSD field from JSONL data
     Display,
+    /// Calculate from token counts at Message Batches API prices
+    Batch,
 }
 
+impl CostMode {
+    /// Name of the mode as accepted by `--cost-mode`
+    pub fn name(self) -> &'static str {
+        match self {
+            CostMode::Auto => "auto",
+            CostMode::Calculate => "calculate",
+            CostMode::Display => "display",
+            CostMode::Batch => "batch",
+        }
+    }
+}
+
+/// Problems found while reading one JSONL file
+#[derive(Debug, Clone, Default)]
+pub struct FileIssues {
+    /// Lines that could not be deserialized
+    pub invalid_lines: usize,
+    /// Records whose model has no known pricing, by model name
+    pub unknown_models: BTreeMap<String, usize>,
+}
+
+impl FileIssues {
+    fn is_empty(&self) -> bool {
+        self.invalid_lines == 0 && self.unknown_models.is_empty()
+    }
+
+    /// Records of all unknown models together
+    pub fn unknown_model_records(&self) -> usize {
+        self.unknown_models.values().sum()
+    }
+}
+
+/// Per-file parse problems collected by the last `parse_all`
+#[derive(Debug, Clone, Default)]
+pub struct ParseIssues {
+    /// Problems by file, only files that had any
+    pub files: BTreeMap<PathBuf, FileIssues>,
+}
+
+impl ParseIssues {
+    /// Whether no file had problems
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Unparseable lines in all files
+    pub fn invalid_lines(&self) -> usize {
+        self.files.values().map(|f| f.invalid_lines).sum()
+    }
+
+    /// Records of unknown models in all files
+    pub fn unknown_model_records(&self) -> usize {
+        self.files.values().map(|f| f.unknown_model_records()).sum()
+    }
+}
+
+/// The Claude directories that exist: `~/.claude` first, then `~/.config/claude`
+pub fn default_claude_dirs() -> Vec<PathBuf> {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".to_string()));
+    [home.join(".claude"), home.join(".config").join("claude")]
+        .into_iter()
+        .filter(|dir| dir.exists())
+        .collect()
+}
+
+/// Parses session logs; configure with the `with_*` builders, then call `parse_all`
 pub struct UsageParser {
     claude_dirs: Vec<PathBuf>,
     since: Option<NaiveDate>,
@@ -33,6 +116,32 @@ pub struct UsageParser {
     pricing_fetcher: PricingFetcher,
     fallback_pricing: HashMap<String, crate::pricing::ModelPricing>,
     models_registry: ModelsRegistry,
+    use_parse_cache: bool,
+    billing_config: BillingBlockConfig,
+    include_imports: bool,
+    include_archive: bool,
+    dedup: bool,
+    duplicates_skipped: AtomicUsize,
+    issues: Mutex<ParseIssues>,
+    /// Lookback for collecting timestamped usage events, off when `None`
+    recent_lookback: Option<Duration>,
+    recent_usage: Mutex<Vec<UsageEvent>>,
+    /// Session key -> summary record or first user prompt
+    session_summaries: Mutex<HashMap<String, String>>,
+    /// Cost figures of every record, collected when enabled
+    cost_audit: Option<Mutex<CostAudit>>,
+}
+
+impl std::fmt::Debug for UsageParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UsageParser")
+            .field("claude_dirs", &self.claude_dirs)
+            .field("since", &self.since)
+            .field("until", &self.until)
+            .field("model_filter", &self.model_filter)
+            .field("cost_mode", &self.cost_mode)
+            .finish_non_exhaustive()
+    }
 }
 
 impl UsageParser {
@@ -53,6 +162,9 @@ impl UsageParser {
     }
 
     /// Create a parser with multiple Claude directories (supports XDG + legacy)
+    ///
+    /// `since` and `until` are inclusive `YYYYMMDD` dates; `model_filter` is a
+    /// model name, alias, or family such as `opus`.
     pub fn new_multi(
         claude_dirs: Vec<PathBuf>,
         since: Option<String>,
@@ -73,16 +185,130 @@ impl UsageParser {
             claude_dirs,
             since,
             until,
-            fallback_pricing: get_fallback_pricing(),
+            fallback_pricing: PricingCache::effective_pricing(),
             model_filter,
             cost_mode,
             pricing_fetcher: PricingFetcher::new(),
             models_registry: ModelsRegistry::new(),
+            use_parse_cache: false,
+            billing_config: BillingBlockConfig::default(),
+            include_imports: false,
+            include_archive: false,
+            dedup: true,
+            duplicates_skipped: AtomicUsize::new(0),
+            issues: Mutex::new(ParseIssues::default()),
+            recent_lookback: None,
+            recent_usage: Mutex::new(Vec::new()),
+            session_summaries: Mutex::new(HashMap::new()),
+            cost_audit: None,
         })
     }
 
+    /// Enable the incremental on-disk parse cache (see `parse_cache`)
+    pub fn with_parse_cache(mut self, enabled: bool) -> Self {
+        self.use_parse_cache = enabled;
+        self
+    }
+
+    /// Set the billing block anchor and duration (fixed 5-hour blocks by default)
+    pub fn with_billing_config(mut self, config: BillingBlockConfig) -> Self {
+        self.billing_config = config;
+        self
+    }
+
+    /// Skip records seen before (same message.id:requestId or line UUID). On by default.
+    pub fn with_dedup(mut self, enabled: bool) -> Self {
+        self.dedup = enabled;
+        self
+    }
+
+    /// Replace the model filter, e.g. when the TUI re-reads usage of other models
+    pub fn set_model_filter(&mut self, model_filter: Option<String>) {
+        self.model_filter = model_filter;
+    }
+
+    /// Claude directories this parser reads from
+    pub fn claude_dirs(&self) -> &[PathBuf] {
+        &self.claude_dirs
+    }
+
+    /// Number of duplicate records skipped by the last `parse_all`
+    pub fn duplicates_skipped(&self) -> usize {
+        self.duplicates_skipped.load(Ordering::Relaxed)
+    }
+
+    /// Unparsable lines and unpriced models found by the last `parse_all`
+    pub fn parse_issues(&self) -> ParseIssues {
+        self.issues.lock().map(|i| i.clone()).unwrap_or_default()
+    }
+
+    /// Also keep every record from the last `lookback` (relative to each
+    /// `parse_all`) as a timestamped event, for windowed burn rates
+    pub fn with_recent_usage(mut self, lookback: Duration) -> Self {
+        self.recent_lookback = Some(lookback);
+        self
+    }
+
+    /// Collect the stored, calculated, and reported cost of every record (`audit` command)
+    #[doc(hidden)]
+    pub fn with_cost_audit(mut self) -> Self {
+        self.cost_audit = Some(Mutex::new(CostAudit::default()));
+        self
+    }
+
+    /// Cost figures collected by the last `parse_all` (empty unless `with_cost_audit`)
+    #[doc(hidden)]
+    pub fn cost_audit(&self) -> CostAudit {
+        self.cost_audit
+            .as_ref()
+            .and_then(|audit| audit.lock().ok().map(|audit| audit.clone()))
+            .unwrap_or_default()
+    }
+
+    /// Keep every record as a timestamped event (see `with_recent_usage`)
+    pub fn with_all_usage_events(self) -> Self {
+        self.with_recent_usage(Duration::MAX)
+    }
+
+    /// Usage events within the lookback collected by the last `parse_all`
+    pub fn recent_usage(&self) -> Vec<UsageEvent> {
+        self.recent_usage
+            .lock()
+            .map(|events| events.clone())
+            .unwrap_or_default()
+    }
+
+    /// What each session parsed by the last `parse_all` was about (its summary
+    /// record, or else its first user prompt), keyed like the session map
+    pub fn session_summaries(&self) -> HashMap<String, String> {
+        self.session_summaries
+            .lock()
+            .map(|summaries| summaries.clone())
+            .unwrap_or_default()
+    }
+
+    /// Merge usage imported with `claudelytics import` into the results
+    pub fn with_imports(mut self, enabled: bool) -> Self {
+        self.include_imports = enabled;
+        self
+    }
+
+    /// Add archived usage (see `archive`) for days and sessions with no live data left
+    pub fn with_archive(mut self, enabled: bool) -> Self {
+        self.include_archive = enabled;
+        self
+    }
+
+    /// Parse every session file: usage per local date, per session
+    /// (`project/session` -> usage and last activity), and per billing block
     pub fn parse_all(&self) -> Result<(DailyUsageMap, SessionUsageMap, BillingBlockManager)> {
+        let started = Instant::now();
         let jsonl_files = self.find_jsonl_files()?;
+        tracing::info!(
+            files = jsonl_files.len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "discovery finished"
+        );
 
         if jsonl_files.is_empty() {
             let dir_list: Vec<String> = self
@@ -91,28 +317,78 @@ impl UsageParser {
                 .map(|d| d.display().to_string())
                 .collect();
             eprintln!("Warning: No JSONL files found in {}", dir_list.join(", "));
-            return Ok((HashMap::new(), HashMap::new(), BillingBlockManager::new()));
+        }
+
+        let parsed = self.parse_files(&jsonl_files)?;
+        tracing::info!(
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "parsing finished"
+        );
+        Ok(parsed)
+    }
+
+    /// Parse the given JSONL files, such as a subset of `find_jsonl_files`
+    pub fn parse_files(
+        &self,
+        jsonl_files: &[PathBuf],
+    ) -> Result<(DailyUsageMap, SessionUsageMap, BillingBlockManager)> {
+        self.duplicates_skipped.store(0, Ordering::Relaxed);
+        if let Ok(mut issues) = self.issues.lock() {
+            *issues = ParseIssues::default();
+        }
+        if let Ok(mut events) = self.recent_usage.lock() {
+            events.clear();
+        }
+        if let Ok(mut summaries) = self.session_summaries.lock() {
+            summaries.clear();
+        }
+
+        // Pruned history may still be in the archive
+        if jsonl_files.is_empty() && !self.include_archive {
+            return Ok((
+                HashMap::new(),
+                HashMap::new(),
+                BillingBlockManager::with_config(self.billing_config),
+            ));
         }
 
         // Use thread-safe billing block manager and dedup set
-        let billing_manager = Arc::new(Mutex::new(BillingBlockManager::new()));
+        let billing_manager = Arc::new(Mutex::new(BillingBlockManager::with_config(
+            self.billing_config,
+        )));
         let dedup_set: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
 
-        let results: Vec<(DailyUsageMap, SessionUsageMap)> = jsonl_files
-            .par_iter()
-            .filter_map(|file_path| {
-                let billing_manager_clone = Arc::clone(&billing_manager);
-                let dedup_clone = Arc::clone(&dedup_set);
-                match self.parse_file_with_billing(file_path, billing_manager_clone, dedup_clone) {
-                    Ok(result) => Some(result),
-                    Err(e) => {
-                        eprintln!("Warning: Failed to parse {}: {}", file_path.display(), e);
-                        None
+        let phase = Instant::now();
+        let results: Vec<(DailyUsageMap, SessionUsageMap)> = if self.use_parse_cache {
+            self.parse_files_cached(jsonl_files, &billing_manager, &dedup_set)
+        } else {
+            jsonl_files
+                .par_iter()
+                .filter_map(|file_path| {
+                    let billing_manager_clone = Arc::clone(&billing_manager);
+                    let dedup_clone = Arc::clone(&dedup_set);
+                    match self.parse_file_with_billing(
+                        file_path,
+                        billing_manager_clone,
+                        dedup_clone,
+                    ) {
+                        Ok(result) => Some(result),
+                        Err(e) => {
+                            eprintln!("Warning: Failed to parse {}: {}", file_path.display(), e);
+                            None
+                        }
                     }
-                }
-            })
-            .collect();
+                })
+                .collect()
+        };
+        tracing::info!(
+            files = results.len(),
+            duplicates_skipped = self.duplicates_skipped(),
+            elapsed_ms = phase.elapsed().as_millis() as u64,
+            "parse finished"
+        );
 
+        let phase = Instant::now();
         let mut daily_map = HashMap::new();
         let mut session_map = HashMap::new();
 
@@ -135,40 +411,190 @@ impl UsageParser {
             }
         }
 
+        if self.include_archive {
+            self.merge_archived_usage(&mut daily_map, &mut session_map);
+        }
+        if self.include_imports {
+            self.merge_imported_usage(&mut daily_map, &mut session_map);
+        }
+
         // Extract the billing manager from Arc<Mutex<>>
-        let billing_manager = Arc::try_unwrap(billing_manager)
+        let mut billing_manager = Arc::try_unwrap(billing_manager)
             .map(|mutex| mutex.into_inner().expect("mutex not poisoned"))
             .unwrap_or_else(|arc| arc.lock().expect("mutex not poisoned").clone());
+        billing_manager.finalize();
+        tracing::info!(
+            days = daily_map.len(),
+            sessions = session_map.len(),
+            elapsed_ms = phase.elapsed().as_millis() as u64,
+            "aggregation finished"
+        );
 
         Ok((daily_map, session_map, billing_manager))
     }
 
-    fn find_jsonl_files(&self) -> Result<Vec<PathBuf>> {
+    /// Add archived rows for the days and sessions the live data no longer has
+    /// (their JSONL files were pruned). Archived rows have no time of day, so
+    /// they are not assigned to billing blocks.
+    fn merge_archived_usage(
+        &self,
+        daily_map: &mut DailyUsageMap,
+        session_map: &mut SessionUsageMap,
+    ) {
+        let rows = match UsageArchive::open().and_then(|archive| archive.load_latest()) {
+            Ok(rows) => rows,
+            Err(e) => {
+                eprintln!("Warning: Ignoring usage archive: {:#}", e);
+                return;
+            }
+        };
+
+        let live: HashSet<(NaiveDate, String)> = daily_map
+            .iter()
+            .flat_map(|(date, usage)| {
+                usage
+                    .by_session
+                    .keys()
+                    .map(move |session| (*date, session.clone()))
+            })
+            .collect();
+
+        for row in rows {
+            if self.since.is_some_and(|since| row.date < since)
+                || self.until.is_some_and(|until| row.date > until)
+                || self
+                    .model_filter
+                    .as_ref()
+                    .is_some_and(|filter| !self.models_registry.matches_filter(&row.model, filter))
+                || live.contains(&(row.date, row.session.clone()))
+            {
+                continue;
+            }
+
+            let usage = row.to_usage();
+            daily_map.entry(row.date).or_default().add(&usage);
+            let entry = session_map
+                .entry(row.session.clone())
+                .or_insert((TokenUsage::default(), row.last_activity));
+            entry.0.add(&usage);
+            if row.last_activity > entry.1 {
+                entry.1 = row.last_activity;
+            }
+        }
+    }
+
+    /// Add imported external usage to the daily map and to `imported/<source>` sessions.
+    /// Imported rows have no time of day, so they are not assigned to billing blocks.
+    fn merge_imported_usage(
+        &self,
+        daily_map: &mut DailyUsageMap,
+        session_map: &mut SessionUsageMap,
+    ) {
+        let store = match ImportStore::load() {
+            Ok(store) => store,
+            Err(e) => {
+                eprintln!("Warning: Ignoring imported usage: {:#}", e);
+                return;
+            }
+        };
+
+        for record in store.records {
+            if self.since.is_some_and(|since| record.date < since)
+                || self.until.is_some_and(|until| record.date > until)
+                || self.model_filter.as_ref().is_some_and(|filter| {
+                    !self.models_registry.matches_filter(&record.model, filter)
+                })
+            {
+                continue;
+            }
+
+            let mut usage = TokenUsage {
+                input_tokens: record.input_tokens,
+                output_tokens: record.output_tokens,
+                cache_creation_tokens: record.cache_creation_tokens,
+                cache_read_tokens: record.cache_read_tokens,
+                total_cost: self.imported_cost(&record),
+                ..Default::default()
+            };
+            let session = format!("{}/{}", IMPORTED_PROJECT, record.source);
+            let untagged = usage.clone();
+            usage
+                .by_model
+                .insert(record.model.clone(), untagged.clone());
+            usage.by_session.insert(session.clone(), untagged);
+
+            daily_map.entry(record.date).or_default().add(&usage);
+
+            let last_activity = record
+                .date
+                .and_hms_opt(23, 59, 59)
+                .expect("valid time")
+                .and_utc();
+            let entry = session_map
+                .entry(session)
+                .or_insert((TokenUsage::default(), last_activity));
+            entry.0.add(&usage);
+            if last_activity > entry.1 {
+                entry.1 = last_activity;
+            }
+        }
+    }
+
+    /// Cost of an imported row, following the same cost mode as JSONL records
+    fn imported_cost(&self, record: &ImportedUsage) -> f64 {
+        let calculate = || {
+            self.pricing_fetcher
+                .get_model_pricing(&self.fallback_pricing, &record.model)
+                .map(|pricing| {
+                    // Rows are per-day totals, so the per-request 200k tier cannot apply
+                    self.pricing_fetcher.calculate_flat_cost(
+                        &pricing,
+                        record.input_tokens,
+                        record.output_tokens,
+                        record.cache_creation_tokens,
+                        record.cache_read_tokens,
+                    )
+                })
+                .unwrap_or(0.0)
+        };
+
+        match self.cost_mode {
+            CostMode::Display => record.cost_usd.unwrap_or(0.0),
+            CostMode::Calculate => calculate(),
+            CostMode::Batch => calculate() * BATCH_DISCOUNT_MULTIPLIER,
+            CostMode::Auto => record
+                .cost_usd
+                .filter(|cost| *cost > 0.0)
+                .unwrap_or_else(calculate),
+        }
+    }
+
+    /// JSONL session files under the `projects` directory of every Claude directory
+    pub fn find_jsonl_files(&self) -> Result<Vec<PathBuf>> {
         let mut all_files = Vec::new();
         let mut found_any_dir = false;
 
         for claude_dir in &self.claude_dirs {
             let projects_dir = claude_dir.join("projects");
             if !projects_dir.exists() {
+                tracing::info!(dir = %projects_dir.display(), "projects directory not found");
                 continue;
             }
             found_any_dir = true;
 
-            let files: Vec<PathBuf> = WalkDir::new(projects_dir)
+            let files: Vec<PathBuf> = WalkDir::new(&projects_dir)
                 .into_iter()
                 .filter_map(|entry| entry.ok())
                 .filter(|entry| entry.file_type().is_file())
-                .filter(|entry| {
-                    entry
-                        .path()
-                        .extension()
-                        .and_then(|ext| ext.to_str())
-                        .map(|ext| ext == "jsonl")
-                        .unwrap_or(false)
-                })
+                .filter(|entry| compression::is_session_file(entry.path()))
                 .map(|entry| entry.path().to_path_buf())
                 .collect();
 
+            tracing::info!(
+                dir = %projects_dir.display(),
+                files = files.len(),
+                "scanned projects directory"
+            );
             all_files.extend(files);
         }
 
@@ -184,92 +610,360 @@ impl UsageParser {
             );
         }
 
-        // Deduplicate by path
+        // Deduplicate by path, reading a session only once if it is also compressed
         all_files.sort();
         all_files.dedup();
+        compression::dedup_compressed(&mut all_files);
 
         Ok(all_files)
     }
 
+    /// Parse files through the incremental parse cache, re-reading only changed files
+    fn parse_files_cached(
+        &self,
+        jsonl_files: &[PathBuf],
+        billing_manager: &Arc<Mutex<BillingBlockManager>>,
+        dedup_set: &Arc<Mutex<HashSet<String>>>,
+    ) -> Vec<(DailyUsageMap, SessionUsageMap)> {
+        let mut cache = ParseCache::load();
+        let (results, dirty) =
+            self.parse_with_cache(&mut cache, jsonl_files, billing_manager, dedup_set);
+        if dirty && let Err(e) = cache.save() {
+            eprintln!("Warning: Failed to save parse cache: {}", e);
+        }
+        results
+    }
+
+    /// Parse through `cache`, updating it; also returns whether it changed and needs saving
+    fn parse_with_cache(
+        &self,
+        cache: &mut ParseCache,
+        jsonl_files: &[PathBuf],
+        billing_manager: &Arc<Mutex<BillingBlockManager>>,
+        dedup_set: &Arc<Mutex<HashSet<String>>>,
+    ) -> (Vec<(DailyUsageMap, SessionUsageMap)>, bool) {
+        // Split files into cache hits and files that need reading. `fresh` marks
+        // entries taken out unchanged, which can go back without a save.
+        let mut loaded: Vec<_> = jsonl_files
+            .iter()
+            .map(|path| {
+                let stamp = FileStamp::of(path).ok();
+                match stamp.and_then(|stamp| cache.take_if_fresh(path, stamp)) {
+                    Some(cached) => (path, stamp, Some(cached), true),
+                    None => (path, stamp, cache.take_compressed_original(path), false),
+                }
+            })
+            .collect();
+        let hits = loaded
+            .iter()
+            .filter(|(_, _, cached, _)| cached.is_some())
+            .count();
+        tracing::info!(hits, misses = loaded.len() - hits, "parse cache lookup");
+        for (path, _, cached, _) in &loaded {
+            tracing::debug!(
+                file = %path.display(),
+                hit = cached.is_some(),
+                "parse cache"
+            );
+        }
+
+        loaded
+            .par_iter_mut()
+            .filter(|(_, _, cached, _)| cached.is_none())
+            .for_each(|(path, _, parsed, _)| match Self::read_file(path) {
+                Ok(read) => *parsed = Some(read),
+                Err(e) => eprintln!("Warning: Failed to parse {}: {}", path.display(), e),
+            });
+
+        let results = loaded
+            .par_iter()
+            .filter_map(|(path, _, parsed, _)| {
+                let parsed = parsed.as_ref()?;
+                match self.aggregate_records(
+                    path,
+                    parsed,
+                    Arc::clone(billing_manager),
+                    Arc::clone(dedup_set),
+                ) {
+                    Ok(result) => Some(result),
+                    Err(e) => {
+                        eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        // Put every file's records back, including hits taken out above
+        let mut dirty = false;
+        for (path, stamp, parsed, fresh) in loaded {
+            if parsed.is_some() && !fresh {
+                dirty = true;
+            }
+            if let (Some(stamp), Some(parsed)) = (stamp, parsed) {
+                cache.insert(path.clone(), stamp, parsed);
+            }
+        }
+        dirty |= cache.prune_missing() > 0;
+
+        (results, dirty)
+    }
+
     fn parse_file_with_billing(
         &self,
         file_path: &Path,
         billing_manager: Arc<Mutex<BillingBlockManager>>,
         dedup_set: Arc<Mutex<HashSet<String>>>,
     ) -> Result<(DailyUsageMap, SessionUsageMap)> {
-        let file = File::open(file_path)
-            .with_context(|| format!("Failed to open file: {}", file_path.display()))?;
-        let reader = BufReader::new(file);
+        let parsed = Self::read_file(file_path)?;
+        self.aggregate_records(file_path, &parsed, billing_manager, dedup_set)
+    }
+
+    /// Read the usage-relevant records from a JSONL file
+    ///
+    /// Lines are read one at a time into a single buffer and only the small
+    /// `UsageRecord` of each is kept, so memory does not grow with file size.
+    fn read_file(file_path: &Path) -> Result<ParsedFile> {
+        let mut reader = BufReader::new(compression::open_session_file(file_path)?);
+
+        let mut parsed = ParsedFile::default();
+        let mut first_prompt = None;
+        let mut buffer = String::new();
+        loop {
+            buffer.clear();
+            if reader.read_line(&mut buffer)? == 0 {
+                break;
+            }
+            let line = buffer.trim_end_matches(['\n', '\r']);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // Summary records precede the messages they describe, so only a few
+            // lines are looked at once the first prompt is known
+            if parsed.summary.is_none()
+                && (first_prompt.is_none() || line.contains("\"summary\""))
+                && let Ok(record) = serde_json::from_str::<SummaryRecord>(line)
+            {
+                match record.summary_text() {
+                    Some(SessionSummary::Summary(text)) => parsed.summary = Some(text),
+                    Some(SessionSummary::Prompt(text)) if first_prompt.is_none() => {
+                        first_prompt = Some(text)
+                    }
+                    _ => {}
+                }
+            }
 
+            // Skip invalid lines, counting them for --report-errors/--strict
+            let Ok(record) = serde_json::from_str::<UsageRecord>(line) else {
+                parsed.invalid_lines += 1;
+                continue;
+            };
+            // Keep records that carry usage or take part in deduplication
+            let has_usage = record
+                .message
+                .as_ref()
+                .and_then(|m| m.usage.as_ref())
+                .is_some();
+            if has_usage || record.dedup_hash().is_some() {
+                parsed.records.push(record);
+            }
+        }
+        if parsed.summary.is_none() {
+            parsed.summary = first_prompt;
+        }
+
+        Ok(parsed)
+    }
+
+    /// Aggregate a file's records into daily/session maps and billing blocks
+    fn aggregate_records(
+        &self,
+        file_path: &Path,
+        parsed: &ParsedFile,
+        billing_manager: Arc<Mutex<BillingBlockManager>>,
+        dedup_set: Arc<Mutex<HashSet<String>>>,
+    ) -> Result<(DailyUsageMap, SessionUsageMap)> {
         let mut daily_map = HashMap::new();
         let mut session_map = HashMap::new();
+        let mut issues = FileIssues {
+            invalid_lines: parsed.invalid_lines,
+            ..Default::default()
+        };
 
         let session_info = self.extract_session_info(file_path)?;
-
-        for line in reader.lines() {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
+        let source = self.source_label(file_path);
+        let recent_since = self.recent_lookback.map(|lookback| {
+            Utc::now()
+                .checked_sub_signed(lookback)
+                .unwrap_or(DateTime::<Utc>::MIN_UTC)
+        });
+        let mut recent_events = Vec::new();
+        let mut audit = CostAudit::default();
+        let (mut used, mut duplicates) = (0usize, 0usize);
+
+        for record in &parsed.records {
+            // Deduplicate by message.id:requestId (matching ccusage behavior), or by
+            // line UUID for records replayed without those IDs
+            if self.dedup
+                && let Some(key) = record.dedup_key()
+                && let Ok(mut set) = dedup_set.lock()
+                && !set.insert(key)
+            {
+                if record
+                    .message
+                    .as_ref()
+                    .and_then(|m| m.usage.as_ref())
+                    .is_some()
+                {
+                    self.duplicates_skipped.fetch_add(1, Ordering::Relaxed);
+                    duplicates += 1;
+                }
+                continue; // Duplicate record, skip
             }
 
-            match serde_json::from_str::<UsageRecord>(&line) {
-                Ok(record) => {
-                    // Deduplicate by message.id:requestId (matching ccusage behavior)
-                    if let Some(hash) = record.dedup_hash()
-                        && let Ok(mut set) = dedup_set.lock()
-                        && !set.insert(hash)
-                    {
-                        continue; // Duplicate record, skip
-                    }
+            // Skip records without timestamp or usage data
+            if let Some(timestamp) = record.timestamp
+                && record
+                    .message
+                    .as_ref()
+                    .and_then(|m| m.usage.as_ref())
+                    .is_some()
+                && self.should_include_record(record)
+            {
+                used += 1;
+                let mut usage = TokenUsage::from(record);
+                let is_fast = Self::is_fast_mode_record(record);
+
+                // Calculate cost based on cost mode
+                self.apply_cost_mode(&mut usage, record, is_fast);
+
+                let priced = self.has_pricing(record.get_model_name());
+                if !priced {
+                    let model = record.get_model_name().unwrap_or("(none)");
+                    *issues.unknown_models.entry(model.to_string()).or_default() += 1;
+                }
 
-                    // Skip records without timestamp or usage data
-                    if let Some(timestamp) = record.timestamp
-                        && record
-                            .message
-                            .as_ref()
-                            .and_then(|m| m.usage.as_ref())
-                            .is_some()
-                        && self.should_include_record(&record)
-                    {
-                        let mut usage = TokenUsage::from(&record);
-                        let is_fast = Self::is_fast_mode_record(&record);
-
-                        // Calculate cost based on cost mode
-                        self.apply_cost_mode(&mut usage, &record, is_fast);
-
-                        let date = Local.from_utc_datetime(&timestamp.naive_utc()).date_naive();
-
-                        // Add to daily map
-                        daily_map
-                            .entry(date)
-                            .or_insert_with(TokenUsage::default)
-                            .add(&usage);
-
-                        // Add to session map
-                        let session_entry = session_map
-                            .entry(session_info.clone())
-                            .or_insert((TokenUsage::default(), timestamp));
-                        session_entry.0.add(&usage);
-                        if timestamp > session_entry.1 {
-                            session_entry.1 = timestamp;
-                        }
+                // Tag the usage with its model, session (and source directory) for breakdowns
+                let model = record.get_model_name().unwrap_or("unknown").to_string();
+                let untagged = usage.clone();
+                if recent_since.is_some_and(|since| timestamp > since) {
+                    recent_events.push(UsageEvent {
+                        timestamp,
+                        session: session_info.clone(),
+                        model: model.clone(),
+                        usage: untagged.clone(),
+                    });
+                }
+                let mut usage = usage.tagged(&model, &session_info);
+                if let Some(source) = &source {
+                    usage.by_source.insert(source.clone(), untagged);
+                }
 
-                        // Add to billing blocks
-                        if let Ok(mut manager) = billing_manager.lock() {
-                            manager.add_usage(timestamp, &usage, Some(&session_info));
-                        }
-                    }
+                let date = Local.from_utc_datetime(&timestamp.naive_utc()).date_naive();
+
+                if self.cost_audit.is_some() {
+                    audit.add(AuditEntry {
+                        date,
+                        model: &model,
+                        tokens: usage.total_tokens(),
+                        priced,
+                        stored_cost: record.cost_usd,
+                        calculated_cost: record
+                            .get_model_name()
+                            .map_or(0.0, |name| self.calculate_cost_for_record(record, name)),
+                        reported_cost: usage.total_cost,
+                    });
                 }
-                Err(_) => {
-                    // Silently skip invalid JSON lines as per spec
-                    continue;
+
+                // Add to daily map
+                daily_map
+                    .entry(date)
+                    .or_insert_with(TokenUsage::default)
+                    .add(&usage);
+
+                // Add to session map
+                let session_entry = session_map
+                    .entry(session_info.clone())
+                    .or_insert((TokenUsage::default(), timestamp));
+                session_entry.0.add(&usage);
+                if timestamp > session_entry.1 {
+                    session_entry.1 = timestamp;
+                }
+
+                // Add to billing blocks
+                if let Ok(mut manager) = billing_manager.lock() {
+                    manager.add_usage(timestamp, &usage, Some(&session_info));
                 }
             }
         }
 
+        // Records left over were outside the date range, filtered by model, or had
+        // no usage (kept only for deduplication)
+        tracing::debug!(
+            file = %file_path.display(),
+            records = parsed.records.len(),
+            used,
+            duplicates,
+            skipped = parsed.records.len() - used - duplicates,
+            invalid_lines = parsed.invalid_lines,
+            "parsed file"
+        );
+
+        if let Some(summary) = &parsed.summary
+            && !session_map.is_empty()
+            && let Ok(mut summaries) = self.session_summaries.lock()
+        {
+            summaries
+                .entry(session_info.clone())
+                .or_insert_with(|| summary.clone());
+        }
+
+        if !recent_events.is_empty()
+            && let Ok(mut events) = self.recent_usage.lock()
+        {
+            events.extend(recent_events);
+        }
+
+        if !issues.is_empty()
+            && let Ok(mut all) = self.issues.lock()
+        {
+            all.files.insert(file_path.to_path_buf(), issues);
+        }
+
+        if !audit.is_empty()
+            && let Some(Ok(mut all)) = self.cost_audit.as_ref().map(|audit| audit.lock())
+        {
+            all.merge(audit);
+        }
+
         Ok((daily_map, session_map))
     }
 
+    /// Whether a cost can be calculated for the model. Placeholder names such as
+    /// `<synthetic>` (used for local error messages) carry no billable usage.
+    fn has_pricing(&self, model_name: Option<&str>) -> bool {
+        model_name.is_some_and(|name| {
+            name.starts_with('<')
+                || self
+                    .pricing_fetcher
+                    .get_model_pricing(&self.fallback_pricing, name)
+                    .is_some()
+        })
+    }
+
+    /// Label of the Claude directory a file belongs to, for per-source breakdowns.
+    /// Returns `None` when only one directory is being parsed.
+    fn source_label(&self, file_path: &Path) -> Option<String> {
+        if self.claude_dirs.len() < 2 {
+            return None;
+        }
+        self.claude_dirs
+            .iter()
+            .find(|dir| file_path.starts_with(dir.join("projects")))
+            .map(|dir| source_label(dir))
+    }
+
     fn extract_session_info(&self, file_path: &Path) -> Result<String> {
         for claude_dir in &self.claude_dirs {
             let projects_dir = claude_dir.join("projects");
@@ -283,10 +977,10 @@ impl UsageParser {
                     continue;
                 }
 
-                // Last component is the filename (e.g., "uuid.jsonl")
-                // Strip .jsonl extension to get the session UUID
+                // Last component is the filename (e.g., "uuid.jsonl" or "uuid.jsonl.gz")
+                // Strip the extension to get the session UUID
                 if let Some(last) = components.last_mut()
-                    && let Some(stem) = last.strip_suffix(".jsonl")
+                    && let Some(stem) = compression::session_stem(last)
                 {
                     *last = stem;
                 }
@@ -379,7 +1073,7 @@ impl UsageParser {
                 // Only use costUSD from JSONL
                 usage.total_cost = record.cost_usd.unwrap_or(0.0);
             }
-            CostMode::Calculate => {
+            CostMode::Calculate | CostMode::Batch => {
                 // Always recalculate from tokens - zero out any costUSD leak
                 usage.total_cost = 0.0;
                 if let Some(model_name) = record.get_model_name() {
@@ -388,6 +1082,9 @@ impl UsageParser {
                         usage.total_cost = calculated;
                     }
                 }
+                if matches!(self.cost_mode, CostMode::Batch) {
+                    usage.total_cost *= BATCH_DISCOUNT_MULTIPLIER;
+                }
             }
             CostMode::Auto => {
                 // Use costUSD if positive, otherwise calculate from tokens
@@ -410,29 +1107,105 @@ impl UsageParser {
     }
 }
 
+/// `--since`/`--until` (and the REST API's `since`/`until`), see `date_range::parse_date`
 fn parse_date(date_str: &str) -> Result<NaiveDate> {
-    if date_str.len() != 8 {
-        anyhow::bail!("Date must be in YYYYMMDD format");
-    }
-
-    let year: i32 = date_str[0..4]
-        .parse()
-        .with_context(|| "Invalid year in date")?;
-    let month: u32 = date_str[4..6]
-        .parse()
-        .with_context(|| "Invalid month in date")?;
-    let day: u32 = date_str[6..8]
-        .parse()
-        .with_context(|| "Invalid day in date")?;
-
-    NaiveDate::from_ymd_opt(year, month, day)
-        .ok_or_else(|| anyhow::anyhow!("Invalid date: {}", date_str))
+    crate::date_range::parse_date(date_str, Local::now().date_naive())
+}
+
+/// Display label for a Claude directory, with the home directory shortened to `~`
+fn source_label(claude_dir: &Path) -> String {
+    if let Some(home) = dirs::home_dir()
+        && let Ok(relative) = claude_dir.strip_prefix(&home)
+    {
+        return format!("~/{}", relative.display());
+    }
+    claude_dir.display().to_string()
+}
+
+/// Longest session summary kept; reports truncate further to fit their columns
+const SUMMARY_MAX_CHARS: usize = 200;
+
+/// The fields of a JSONL line that can describe what a session was about
+#[derive(Debug, Deserialize)]
+struct SummaryRecord {
+    #[serde(rename = "type", default)]
+    record_type: Option<String>,
+    #[serde(default)]
+    summary: Option<String>,
+    /// Set on messages Claude Code inserts itself (e.g. local command caveats)
+    #[serde(rename = "isMeta", default)]
+    is_meta: bool,
+    #[serde(default)]
+    message: Option<SummaryMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SummaryMessage {
+    #[serde(default)]
+    role: Option<String>,
+    #[serde(default)]
+    content: serde_json::Value,
+}
+
+enum SessionSummary {
+    /// A `summary` record written by Claude Code
+    Summary(String),
+    /// Text typed by the user
+    Prompt(String),
+}
+
+impl SummaryRecord {
+    fn summary_text(&self) -> Option<SessionSummary> {
+        match self.record_type.as_deref() {
+            Some("summary") => self
+                .summary
+                .as_deref()
+                .and_then(collapse_summary)
+                .map(SessionSummary::Summary),
+            Some("user") if !self.is_meta => {
+                let message = self.message.as_ref()?;
+                if message.role.as_deref() != Some("user") {
+                    return None;
+                }
+                let text = match &message.content {
+                    serde_json::Value::String(text) => Some(text.as_str()),
+                    // Tool results are sent as user messages without text blocks
+                    serde_json::Value::Array(blocks) => blocks.iter().find_map(|block| {
+                        (block.get("type").and_then(|t| t.as_str()) == Some("text"))
+                            .then(|| block.get("text").and_then(|t| t.as_str()))
+                            .flatten()
+                    }),
+                    _ => None,
+                }?;
+                // Slash commands and their output are wrapped in tags like <command-name>
+                if text.trim_start().starts_with('<') {
+                    return None;
+                }
+                collapse_summary(text).map(SessionSummary::Prompt)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whitespace-collapsed text cut to `SUMMARY_MAX_CHARS`, `None` when empty
+fn collapse_summary(text: &str) -> Option<String> {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
+    Some(if collapsed.chars().count() > SUMMARY_MAX_CHARS {
+        let truncated: String = collapsed.chars().take(SUMMARY_MAX_CHARS - 3).collect();
+        format!("{}...", truncated)
+    } else {
+        collapsed
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+    use std::fs::{self, File};
     use std::io::Write;
     use tempfile::TempDir;
 
@@ -467,11 +1240,15 @@ mod tests {
             parse_date("20231231").unwrap(),
             NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()
         );
+        assert_eq!(
+            parse_date("2024-01-01").unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+        );
     }
 
     #[test]
     fn test_parse_date_invalid_format() {
-        assert!(parse_date("2024-01-01").is_err());
+        assert!(parse_date("2024/01/01").is_err());
         assert!(parse_date("240101").is_err());
         assert!(parse_date("202401001").is_err());
     }
@@ -678,8 +1455,8 @@ mod tests {
         let content2 = r#"{"uuid":"uuid2","type":"response.done","timestamp":"2024-01-16T12:00:00Z","message":{"model":"claude-3-sonnet-20240229","usage":{"input_tokens":50,"output_tokens":100,"cache_creation_input_tokens":0,"cache_read_input_tokens":0}},"sessionId":"session2"}
 "#;
 
-        create_test_jsonl_file(&projects_dir1, "session1.jsonl", &content1);
-        create_test_jsonl_file(&projects_dir2, "session2.jsonl", &content2);
+        create_test_jsonl_file(&projects_dir1, "session1.jsonl", content1);
+        create_test_jsonl_file(&projects_dir2, "session2.jsonl", content2);
 
         let parser = UsageParser::new(temp_dir.path().to_path_buf(), None, None, None)
             .expect("Failed to create parser");
@@ -693,6 +1470,40 @@ mod tests {
         assert!(!billing_manager.get_all_blocks().is_empty());
     }
 
+    #[test]
+    fn test_compressed_session_files() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let projects_dir = temp_dir.path().join("projects").join("test-project");
+        fs::create_dir_all(&projects_dir).expect("Failed to create projects dir");
+
+        let archived = r#"{"uuid":"uuid1","timestamp":"2024-01-15T12:00:00Z","message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":100,"output_tokens":200}}}
+"#;
+        let current = r#"{"uuid":"uuid2","timestamp":"2024-01-16T12:00:00Z","message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":50,"output_tokens":100}}}
+"#;
+        fs::write(
+            projects_dir.join("old.jsonl.zst"),
+            zstd::encode_all(archived.as_bytes(), 3).unwrap(),
+        )
+        .unwrap();
+        // A compressed copy of a session that is still written to is ignored
+        create_test_jsonl_file(&projects_dir, "live.jsonl", current);
+        fs::write(
+            projects_dir.join("live.jsonl.zst"),
+            zstd::encode_all(current.as_bytes(), 3).unwrap(),
+        )
+        .unwrap();
+
+        let parser = UsageParser::new(temp_dir.path().to_path_buf(), None, None, None)
+            .expect("Failed to create parser")
+            .with_dedup(false);
+        assert_eq!(parser.find_jsonl_files().unwrap().len(), 2);
+
+        let (daily_map, session_map, _) = parser.parse_all().expect("Failed to parse all files");
+        assert_eq!(daily_map.len(), 2);
+        assert!(session_map.contains_key("test-project/old"));
+        assert_eq!(session_map["test-project/live"].0.input_tokens, 50);
+    }
+
     #[test]
     fn test_model_filter() {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
@@ -757,4 +1568,244 @@ mod tests {
         assert!(daily_map.contains_key(&date));
         assert_eq!(daily_map[&date].input_tokens, 150);
     }
+
+    #[test]
+    fn test_multiple_roots_dedup_and_source_breakdown() {
+        let laptop = TempDir::new().expect("Failed to create temp dir");
+        let desktop = TempDir::new().expect("Failed to create temp dir");
+        let shared = r#"{"uuid":"uuid1","type":"assistant","timestamp":"2024-01-15T12:00:00Z","requestId":"req1","message":{"id":"msg1","model":"claude-3-opus-20240229","usage":{"input_tokens":100,"output_tokens":200}},"sessionId":"session1"}"#;
+        let laptop_only = r#"{"uuid":"uuid2","type":"assistant","timestamp":"2024-01-15T13:00:00Z","requestId":"req2","message":{"id":"msg2","model":"claude-3-opus-20240229","usage":{"input_tokens":20,"output_tokens":20}},"sessionId":"session2"}"#;
+        let desktop_only = r#"{"uuid":"uuid3","type":"assistant","timestamp":"2024-01-15T14:00:00Z","requestId":"req3","message":{"id":"msg3","model":"claude-3-opus-20240229","usage":{"input_tokens":10,"output_tokens":20}},"sessionId":"session3"}"#;
+
+        // Both machines synced session1; each also has a session of its own
+        let laptop_project = laptop.path().join("projects").join("app");
+        let desktop_project = desktop.path().join("projects").join("app");
+        fs::create_dir_all(&laptop_project).expect("Failed to create projects dir");
+        fs::create_dir_all(&desktop_project).expect("Failed to create projects dir");
+        create_test_jsonl_file(&laptop_project, "session1.jsonl", shared);
+        create_test_jsonl_file(&laptop_project, "session2.jsonl", laptop_only);
+        create_test_jsonl_file(&desktop_project, "session1.jsonl", shared);
+        create_test_jsonl_file(&desktop_project, "session3.jsonl", desktop_only);
+
+        let parser = UsageParser::new_multi(
+            vec![laptop.path().to_path_buf(), desktop.path().to_path_buf()],
+            None,
+            None,
+            None,
+            CostMode::Auto,
+        )
+        .expect("Failed to create parser")
+        .with_imports(false);
+        let (daily_map, session_map, _) = parser.parse_all().expect("Failed to parse");
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let day = &daily_map[&date];
+        // The shared message is counted once, attributed to whichever copy was read first
+        assert_eq!(day.input_tokens, 130);
+        assert_eq!(session_map.len(), 3);
+        assert_eq!(day.by_source.len(), 2);
+        let per_source: u64 = day.by_source.values().map(|u| u.input_tokens).sum();
+        assert_eq!(per_source, 130);
+        assert!(day.by_source[&source_label(laptop.path())].input_tokens >= 20);
+        assert!(day.by_source[&source_label(desktop.path())].input_tokens >= 10);
+    }
+
+    #[test]
+    fn test_cost_modes() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let projects_dir = temp_dir.path().join("projects").join("test-project");
+        fs::create_dir_all(&projects_dir).expect("Failed to create projects dir");
+        let stored = r#"{"uuid":"uuid1","type":"assistant","timestamp":"2024-01-15T12:00:00Z","costUSD":1.0,"message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":1000,"output_tokens":1000}}}"#;
+        create_test_jsonl_file(&projects_dir, "session1.jsonl", stored);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let cost = |mode| {
+            let parser =
+                UsageParser::new_multi(vec![temp_dir.path().to_path_buf()], None, None, None, mode)
+                    .expect("Failed to create parser")
+                    .with_imports(false);
+            parser.parse_all().expect("Failed to parse").0[&date].total_cost
+        };
+        assert_eq!(cost(CostMode::Auto), 1.0);
+        assert_eq!(cost(CostMode::Display), 1.0);
+        let calculated = cost(CostMode::Calculate);
+        assert!(calculated > 0.0 && calculated != 1.0);
+        assert!((cost(CostMode::Batch) - calculated * BATCH_DISCOUNT_MULTIPLIER).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_replayed_records_are_skipped_unless_disabled() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let projects_dir = temp_dir.path().join("projects").join("test-project");
+        fs::create_dir_all(&projects_dir).expect("Failed to create projects dir");
+
+        // The same line written twice after a crash, plus a distinct record
+        let replayed = r#"{"uuid":"uuid1","type":"assistant","timestamp":"2024-01-15T12:00:00Z","message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":100,"output_tokens":200}}}"#;
+        let other = r#"{"uuid":"uuid2","type":"assistant","timestamp":"2024-01-15T12:01:00Z","message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":10,"output_tokens":20}}}"#;
+        let content = format!("{}\n{}\n{}\n", replayed, replayed, other);
+        create_test_jsonl_file(&projects_dir, "session1.jsonl", &content);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let parser = UsageParser::new(temp_dir.path().to_path_buf(), None, None, None)
+            .expect("Failed to create parser");
+        let (daily_map, _, _) = parser.parse_all().expect("Failed to parse");
+        assert_eq!(daily_map[&date].input_tokens, 110);
+        assert_eq!(parser.duplicates_skipped(), 1);
+
+        let parser = UsageParser::new(temp_dir.path().to_path_buf(), None, None, None)
+            .expect("Failed to create parser")
+            .with_dedup(false);
+        let (daily_map, _, _) = parser.parse_all().expect("Failed to parse");
+        assert_eq!(daily_map[&date].input_tokens, 210);
+        assert_eq!(parser.duplicates_skipped(), 0);
+    }
+
+    #[test]
+    fn test_parse_issues_per_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let projects_dir = temp_dir.path().join("projects").join("test-project");
+        fs::create_dir_all(&projects_dir).expect("Failed to create projects dir");
+
+        let content = format!(
+            "{}\n{}\n{}\n",
+            r#"{"uuid":"uuid1","type":"assistant","timestamp":"2024-01-15T12:00:00Z","message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":100,"output_tokens":200}}}"#,
+            r#"{"uuid":"uuid2","type":"assistant","timestamp":"2024-01-15T12:01:00Z","message":{"model":"gpt-banana","usage":{"input_tokens":10,"output_tokens":20}}}"#,
+            r#"{"uuid":"uuid3","timestamp":"2024-01-15T12:02"#
+        );
+        let file_path = create_test_jsonl_file(&projects_dir, "session1.jsonl", &content);
+        create_test_jsonl_file(
+            &projects_dir,
+            "session2.jsonl",
+            r#"{"uuid":"uuid4","type":"assistant","timestamp":"2024-01-15T12:03:00Z","message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":1,"output_tokens":1}}}"#,
+        );
+
+        let parser = UsageParser::new(temp_dir.path().to_path_buf(), None, None, None)
+            .expect("Failed to create parser");
+        parser.parse_all().expect("Failed to parse");
+
+        let issues = parser.parse_issues();
+        assert_eq!(issues.files.len(), 1);
+        assert_eq!(issues.invalid_lines(), 1);
+        assert_eq!(issues.unknown_model_records(), 1);
+        assert_eq!(issues.files[&file_path].unknown_models["gpt-banana"], 1);
+    }
+
+    #[test]
+    fn test_session_summaries() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let projects_dir = temp_dir.path().join("projects").join("test-project");
+        fs::create_dir_all(&projects_dir).expect("Failed to create projects dir");
+
+        let usage = r#"{"uuid":"a1","type":"assistant","timestamp":"2024-01-15T12:00:05Z","message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":1,"output_tokens":1}}}"#;
+        let prompted = [
+            r#"{"uuid":"m1","type":"user","isMeta":true,"timestamp":"2024-01-15T12:00:00Z","message":{"role":"user","content":"Caveat: local commands below"}}"#,
+            r#"{"uuid":"m2","type":"user","timestamp":"2024-01-15T12:00:01Z","message":{"role":"user","content":"<command-name>/model</command-name>"}}"#,
+            r#"{"uuid":"u1","type":"user","timestamp":"2024-01-15T12:00:02Z","message":{"role":"user","content":[{"type":"text","text":"Fix the\n  flaky   login test"}]}}"#,
+            usage,
+        ];
+        let summarized = [
+            r#"{"type":"summary","summary":"Parser refactoring","leafUuid":"a1"}"#,
+            r#"{"uuid":"u2","type":"user","timestamp":"2024-01-15T12:00:02Z","message":{"role":"user","content":"Refactor the parser"}}"#,
+            usage,
+        ];
+        create_test_jsonl_file(&projects_dir, "prompted.jsonl", &prompted.join("\n"));
+        create_test_jsonl_file(&projects_dir, "summarized.jsonl", &summarized.join("\n"));
+        create_test_jsonl_file(&projects_dir, "empty.jsonl", usage);
+
+        let parser = UsageParser::new(temp_dir.path().to_path_buf(), None, None, None)
+            .expect("Failed to create parser")
+            .with_dedup(false);
+        let (_, session_map, _) = parser.parse_all().expect("Failed to parse");
+        assert_eq!(session_map.len(), 3);
+
+        let summaries = parser.session_summaries();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(
+            summaries["test-project/prompted"],
+            "Fix the flaky login test"
+        );
+        assert_eq!(summaries["test-project/summarized"], "Parser refactoring");
+    }
+
+    #[test]
+    fn test_parse_cache_saved_only_when_changed() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let projects_dir = temp_dir.path().join("projects").join("test-project");
+        fs::create_dir_all(&projects_dir).expect("Failed to create projects dir");
+        let record = r#"{"uuid":"a1","timestamp":"2024-01-15T12:00:00Z","message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":100,"output_tokens":200}}}"#;
+        let path = create_test_jsonl_file(&projects_dir, "s1.jsonl", &format!("{}\n", record));
+
+        let parser = UsageParser::new(temp_dir.path().to_path_buf(), None, None, None)
+            .expect("Failed to create parser")
+            .with_dedup(false);
+        let mut cache = ParseCache::new();
+        let mut run = |files: &[PathBuf]| {
+            let billing = Arc::new(Mutex::new(BillingBlockManager::new()));
+            let dedup = Arc::new(Mutex::new(HashSet::new()));
+            let (results, dirty) = parser.parse_with_cache(&mut cache, files, &billing, &dedup);
+            assert_eq!(results.len(), files.len());
+            dirty
+        };
+        let files = vec![path.clone()];
+
+        assert!(run(&files), "new entries need saving");
+        assert!(!run(&files), "unchanged files are cache hits");
+        fs::write(
+            &path,
+            format!("{}\n{}\n", record, record.replace("a1", "a2")),
+        )
+        .unwrap();
+        assert!(run(&files), "a changed file is re-read");
+        assert!(!run(&files));
+        fs::remove_file(&path).unwrap();
+        assert!(run(&[]), "entries of removed files are pruned");
+    }
+
+    #[test]
+    fn test_cached_files_yield_the_same_usage_events() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let projects_dir = temp_dir.path().join("projects").join("test-project");
+        fs::create_dir_all(&projects_dir).expect("Failed to create projects dir");
+        let records = [
+            r#"{"uuid":"a1","sessionId":"s1","timestamp":"2024-01-15T12:00:00Z","message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":100,"output_tokens":200}}}"#,
+            r#"{"uuid":"a2","sessionId":"s1","timestamp":"2024-01-15T13:00:00Z","message":{"model":"claude-3-sonnet-20240229","usage":{"input_tokens":50,"output_tokens":10}}}"#,
+        ];
+        create_test_jsonl_file(&projects_dir, "s1.jsonl", &records.join("\n"));
+        create_test_jsonl_file(&projects_dir, "s2.jsonl", &records[0].replace("a1", "b1"));
+
+        let parser = UsageParser::new(temp_dir.path().to_path_buf(), None, None, None)
+            .expect("Failed to create parser")
+            .with_all_usage_events();
+        let files = parser.find_jsonl_files().unwrap();
+        let events = |parser: &UsageParser| {
+            let mut events: Vec<_> = parser
+                .recent_usage()
+                .into_iter()
+                .map(|e| {
+                    (
+                        e.timestamp,
+                        e.session,
+                        e.model,
+                        e.usage.total_tokens(),
+                        e.usage.total_cost,
+                    )
+                })
+                .collect();
+            events.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            events
+        };
+
+        parser.parse_files(&files).unwrap();
+        let uncached = events(&parser);
+        assert_eq!(uncached.len(), 3);
+
+        let mut cache = ParseCache::new();
+        for _ in 0..2 {
+            parser.recent_usage.lock().unwrap().clear();
+            let billing = Arc::new(Mutex::new(BillingBlockManager::new()));
+            let dedup = Arc::new(Mutex::new(HashSet::new()));
+            parser.parse_with_cache(&mut cache, &files, &billing, &dedup);
+            assert_eq!(events(&parser), uncached);
+        }
+    }
 }
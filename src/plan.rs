@@ -0,0 +1,363 @@
+//! What-if budget planner (`plan` command)
+//!
+//! Plays the rest of the billing cycle forward many times by drawing each
+//! remaining day's cost from the daily costs of the last 30 days (idle days
+//! included as zero). The share of runs that end over the budget is the
+//! probability of exceeding it if usage stays as it has been. Today's spend so
+//! far counts as spent; the simulated days start tomorrow.
+
+use crate::currency::Money;
+use crate::helpers::{calculate_average, compare_floats};
+use crate::models::DailyUsageMap;
+use crate::projections::BillingCycle;
+use anyhow::{Result, bail};
+use chrono::{Duration, NaiveDate};
+use colored::Colorize;
+use serde::Serialize;
+
+/// Days of history the simulated days are drawn from
+const HISTORY_DAYS: i64 = 30;
+
+/// Seed of the simulation, so the same history gives the same plan
+const SEED: u64 = 0x5eed_b0d6_e7a1_1ce5;
+
+/// Budget outlook for the current billing cycle
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetPlan {
+    pub monthly_budget: f64,
+    pub cycle_start: NaiveDate,
+    pub cycle_end: NaiveDate,
+    /// Days after today until the cycle ends
+    pub days_remaining: i64,
+    pub spent_to_date: f64,
+    /// Budget left; negative once it is exceeded
+    pub remaining_budget: f64,
+    /// Even split of the remaining budget over the remaining days
+    pub sustainable_daily_allowance: f64,
+    pub history: DailyCostStats,
+    pub simulations: usize,
+    /// Share of simulated cycles that end over the budget (0.0-1.0)
+    pub exceed_probability: f64,
+    /// Cycle total at the 10th, 50th and 90th percentile of the simulations
+    pub projected_total: Percentiles,
+    /// Median day the budget runs out; `None` when most simulated cycles stay within it
+    pub likely_run_out: Option<NaiveDate>,
+}
+
+/// Daily costs the simulation draws from
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyCostStats {
+    pub days: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub p90: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Percentiles {
+    pub p10: f64,
+    pub p50: f64,
+    pub p90: f64,
+}
+
+/// Plan the rest of the billing cycle containing `today` against `monthly_budget`
+pub fn plan(
+    daily_usage: &DailyUsageMap,
+    monthly_budget: f64,
+    billing_cycle_start_day: u32,
+    simulations: usize,
+    today: NaiveDate,
+) -> Result<BudgetPlan> {
+    if monthly_budget <= 0.0 {
+        bail!("The monthly budget must be greater than zero");
+    }
+    if simulations == 0 {
+        bail!("At least one simulation is needed");
+    }
+    let history = daily_history(daily_usage, today);
+    if history.is_empty() {
+        bail!(
+            "No usage in the last {} days to plan from; try without --since",
+            HISTORY_DAYS
+        );
+    }
+
+    let cycle = BillingCycle::containing(today, billing_cycle_start_day);
+    let spent_to_date = cycle.spent_to_date(daily_usage, today).max(0.0);
+    let remaining_budget = monthly_budget - spent_to_date;
+    let days_remaining = cycle.days_remaining(today);
+    let sustainable_daily_allowance = if days_remaining > 0 {
+        remaining_budget.max(0.0) / days_remaining as f64
+    } else {
+        0.0
+    };
+
+    let mut rng = SplitMix64(SEED);
+    let mut totals = Vec::with_capacity(simulations);
+    // Days after today the budget ran out, `i64::MAX` for runs that stayed within it
+    let mut run_out_days = Vec::with_capacity(simulations);
+    for _ in 0..simulations {
+        let mut total = spent_to_date;
+        let mut run_out = i64::MAX;
+        for day in 1..=days_remaining {
+            total += history[rng.below(history.len())];
+            if total > monthly_budget && run_out == i64::MAX {
+                run_out = day;
+            }
+        }
+        totals.push(total);
+        run_out_days.push(run_out);
+    }
+
+    let exceed_probability = if remaining_budget < 0.0 {
+        1.0
+    } else {
+        run_out_days.iter().filter(|day| **day != i64::MAX).count() as f64 / simulations as f64
+    };
+    let likely_run_out = if remaining_budget < 0.0 {
+        first_day_over(daily_usage, cycle, monthly_budget, today)
+    } else {
+        run_out_days.sort_unstable();
+        let median = run_out_days[(simulations - 1) / 2];
+        (median != i64::MAX).then(|| today + Duration::days(median))
+    };
+
+    totals.sort_by(|a, b| compare_floats(*a, *b));
+    let mut sorted_history = history.clone();
+    sorted_history.sort_by(|a, b| compare_floats(*a, *b));
+
+    Ok(BudgetPlan {
+        monthly_budget,
+        cycle_start: cycle.start,
+        cycle_end: cycle.end,
+        days_remaining,
+        spent_to_date,
+        remaining_budget,
+        sustainable_daily_allowance,
+        history: DailyCostStats {
+            days: history.len(),
+            mean: calculate_average(&history),
+            median: percentile(&sorted_history, 0.5),
+            p90: percentile(&sorted_history, 0.9),
+            max: sorted_history.last().copied().unwrap_or(0.0),
+        },
+        simulations,
+        exceed_probability,
+        projected_total: Percentiles {
+            p10: percentile(&totals, 0.1),
+            p50: percentile(&totals, 0.5),
+            p90: percentile(&totals, 0.9),
+        },
+        likely_run_out,
+    })
+}
+
+/// Daily costs of the last `HISTORY_DAYS` days before today, idle days as zero,
+/// starting at the first day with usage
+fn daily_history(daily_usage: &DailyUsageMap, today: NaiveDate) -> Vec<f64> {
+    let window_start = today - Duration::days(HISTORY_DAYS);
+    let Some(first) = daily_usage
+        .keys()
+        .filter(|date| **date >= window_start && **date < today)
+        .min()
+    else {
+        return Vec::new();
+    };
+    first
+        .iter_days()
+        .take_while(|date| *date < today)
+        .map(|date| {
+            daily_usage
+                .get(&date)
+                .map_or(0.0, |usage| usage.total_cost.max(0.0))
+        })
+        .collect()
+}
+
+/// Day in the cycle on which the recorded spend went over the budget
+fn first_day_over(
+    daily_usage: &DailyUsageMap,
+    cycle: BillingCycle,
+    budget: f64,
+    today: NaiveDate,
+) -> Option<NaiveDate> {
+    let mut total = 0.0;
+    cycle
+        .start
+        .iter_days()
+        .take_while(|date| *date <= today)
+        .find(|date| {
+            total += daily_usage.get(date).map_or(0.0, |usage| usage.total_cost);
+            total > budget
+        })
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    sorted[((sorted.len() - 1) as f64 * q).round() as usize]
+}
+
+/// Small deterministic generator for drawing history days
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index below `n`
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+pub fn display_plan(plan: &BudgetPlan, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(plan) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "BUDGET PLAN".bold());
+    println!("{}", "═".repeat(60));
+    println!(
+        "  Cycle:            {} to {} ({} days remaining)",
+        plan.cycle_start.format("%Y-%m-%d"),
+        plan.cycle_end.format("%Y-%m-%d"),
+        plan.days_remaining
+    );
+    println!(
+        "  Budget:           {:.2} ({:.2} spent, {:.2} left)",
+        Money(plan.monthly_budget),
+        Money(plan.spent_to_date),
+        Money(plan.remaining_budget.max(0.0))
+    );
+
+    println!("\n{}", "Daily Allowance".bold());
+    println!(
+        "  Sustainable:      {}/day for the rest of the cycle",
+        format!("{:.2}", Money(plan.sustainable_daily_allowance)).green()
+    );
+    let stats = &plan.history;
+    println!(
+        "  Recent average:   {:.2}/day (median {:.2}, 90th percentile {:.2}, last {} days)",
+        Money(stats.mean),
+        Money(stats.median),
+        Money(stats.p90),
+        stats.days
+    );
+    if plan.days_remaining > 0 && stats.mean > plan.sustainable_daily_allowance {
+        let cut = if stats.mean > 0.0 {
+            (1.0 - plan.sustainable_daily_allowance / stats.mean) * 100.0
+        } else {
+            0.0
+        };
+        println!(
+            "  {}",
+            format!("Spend {:.0}% less per day to stay within the budget", cut).yellow()
+        );
+    }
+
+    println!(
+        "\n{} ({} simulated cycles)",
+        "Outlook at Current Behaviour".bold(),
+        plan.simulations
+    );
+    let probability = plan.exceed_probability * 100.0;
+    let probability_text = format!("{:.0}%", probability);
+    println!(
+        "  Chance to exceed: {}",
+        if probability >= 50.0 {
+            probability_text.red().bold()
+        } else if probability >= 10.0 {
+            probability_text.yellow()
+        } else {
+            probability_text.green()
+        }
+    );
+    println!(
+        "  Cycle total:      {:.2} likely ({:.2} - {:.2})",
+        Money(plan.projected_total.p50),
+        Money(plan.projected_total.p10),
+        Money(plan.projected_total.p90)
+    );
+    match plan.likely_run_out {
+        Some(date) if plan.remaining_budget < 0.0 => println!(
+            "  Budget ran out:   {}",
+            date.format("%Y-%m-%d").to_string().red()
+        ),
+        Some(date) => println!(
+            "  Likely runs out:  {}",
+            date.format("%Y-%m-%d").to_string().red()
+        ),
+        None => println!("  Likely runs out:  not before the cycle ends"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+
+    fn usage(cost: f64) -> TokenUsage {
+        TokenUsage {
+            total_cost: cost,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_against_budget() {
+        // $10 every day from the 1st through the 10th; today is the 11th
+        let today = NaiveDate::from_ymd_opt(2024, 4, 11).unwrap();
+        let mut daily_usage = DailyUsageMap::new();
+        for day in 1..=10 {
+            daily_usage.insert(NaiveDate::from_ymd_opt(2024, 4, day).unwrap(), usage(10.0));
+        }
+
+        // 100 spent and 19 days left at $10 a day
+        let roomy = plan(&daily_usage, 400.0, 1, 200, today).unwrap();
+        assert_eq!(roomy.spent_to_date, 100.0);
+        assert_eq!(roomy.days_remaining, 19);
+        assert!((roomy.sustainable_daily_allowance - 300.0 / 19.0).abs() < 1e-9);
+        assert_eq!(roomy.history.days, 10);
+        assert_eq!(roomy.history.mean, 10.0);
+        assert_eq!(roomy.exceed_probability, 0.0);
+        assert_eq!(roomy.projected_total.p50, 290.0);
+        assert_eq!(roomy.likely_run_out, None);
+
+        // At $10 a day a $200 budget is used up on the 21st and exceeded on the 22nd
+        let tight = plan(&daily_usage, 200.0, 1, 200, today).unwrap();
+        assert_eq!(tight.exceed_probability, 1.0);
+        assert_eq!(tight.likely_run_out, NaiveDate::from_ymd_opt(2024, 4, 22));
+
+        // Already over the budget
+        let over = plan(&daily_usage, 55.0, 1, 10, today).unwrap();
+        assert_eq!(over.exceed_probability, 1.0);
+        assert_eq!(over.sustainable_daily_allowance, 0.0);
+        assert_eq!(over.likely_run_out, NaiveDate::from_ymd_opt(2024, 4, 6));
+
+        assert!(plan(&DailyUsageMap::new(), 100.0, 1, 10, today).is_err());
+    }
+
+    #[test]
+    fn test_idle_days_count_as_zero() {
+        let today = NaiveDate::from_ymd_opt(2024, 4, 11).unwrap();
+        let mut daily_usage = DailyUsageMap::new();
+        daily_usage.insert(NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), usage(10.0));
+        daily_usage.insert(NaiveDate::from_ymd_opt(2024, 4, 10).unwrap(), usage(10.0));
+        let history = daily_history(&daily_usage, today);
+        assert_eq!(history.len(), 10);
+        assert_eq!(history.iter().sum::<f64>(), 20.0);
+    }
+}
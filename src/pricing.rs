@@ -1,29 +1,45 @@
+//! Per-model token prices used to calculate costs
+
+use crate::models_registry;
 use serde::Deserialize;
 use std::collections::HashMap;
 
 /// Fast mode pricing multiplier (Claude Code /fast uses 6x pricing)
 pub const FAST_MODE_MULTIPLIER: f64 = 6.0;
 
+/// Message Batches API pricing multiplier (batched requests cost half)
+pub const BATCH_DISCOUNT_MULTIPLIER: f64 = 0.5;
+
 /// Token threshold for tiered pricing on 1M context models
 pub const TIERED_THRESHOLD: u64 = 200_000;
 
+/// Per-token prices of one model, in USD
 #[derive(Debug, Deserialize, Clone, serde::Serialize)]
 pub struct ModelPricing {
+    /// Price of an uncached input token
     pub input_cost_per_token: Option<f64>,
+    /// Price of an output token
     pub output_cost_per_token: Option<f64>,
+    /// Price of a token written to the prompt cache
     pub cache_creation_input_token_cost: Option<f64>,
+    /// Price of a token read from the prompt cache
     pub cache_read_input_token_cost: Option<f64>,
     /// Tiered pricing for tokens above 200k (1M context models)
-    #[serde(default)]
+    /// (aliases accept the field names used by LiteLLM's pricing JSON)
+    #[serde(default, alias = "input_cost_per_token_above_200k_tokens")]
     pub input_cost_per_token_above_200k: Option<f64>,
-    #[serde(default)]
+    /// Output token price when the prompt is above 200k tokens
+    #[serde(default, alias = "output_cost_per_token_above_200k_tokens")]
     pub output_cost_per_token_above_200k: Option<f64>,
-    #[serde(default)]
+    /// Cache write price when the prompt is above 200k tokens
+    #[serde(default, alias = "cache_creation_input_token_cost_above_200k_tokens")]
     pub cache_creation_cost_above_200k: Option<f64>,
-    #[serde(default)]
+    /// Cache read price when the prompt is above 200k tokens
+    #[serde(default, alias = "cache_read_input_token_cost_above_200k_tokens")]
     pub cache_read_cost_above_200k: Option<f64>,
 }
 
+/// Looks up model prices and calculates request costs
 pub struct PricingFetcher;
 
 impl Default for PricingFetcher {
@@ -33,10 +49,14 @@ impl Default for PricingFetcher {
 }
 
 impl PricingFetcher {
+    /// Create a fetcher
     pub fn new() -> Self {
         Self
     }
 
+    /// Pricing of `model_name`: an exact match, then a model from `models.yaml`,
+    /// then known name variations, then any entry whose name contains it or is
+    /// contained in it
     pub fn get_model_pricing(
         &self,
         pricing_data: &HashMap<String, ModelPricing>,
@@ -47,6 +67,11 @@ impl PricingFetcher {
             return Some(pricing.clone());
         }
 
+        // Models of other providers described in models.yaml
+        if let Some(model) = models_registry::custom_models().find(model_name) {
+            return pricing_data.get(&model.name).cloned();
+        }
+
         // Try variations for Claude models
         let claude_variations = [
             // Exact model name variations
@@ -95,6 +120,8 @@ impl PricingFetcher {
         None
     }
 
+    /// Cost of one request, at the above-200k rates when its prompt (input plus
+    /// cache tokens) is larger than 200k and the model has them
     pub fn calculate_cost(
         &self,
         pricing: &ModelPricing,
@@ -127,7 +154,9 @@ impl PricingFetcher {
         }
     }
 
-    fn calculate_flat_cost(
+    /// Cost at base per-token rates, ignoring the >200k tier. Used for pre-aggregated
+    /// usage where the per-request context size is unknown.
+    pub fn calculate_flat_cost(
         &self,
         pricing: &ModelPricing,
         input_tokens: u64,
@@ -220,8 +249,8 @@ impl PricingFetcher {
     }
 }
 
-// Fallback pricing for common Claude models (as of 2025)
-// Updated to match ccusage pricing methodology
+/// Built-in prices of the Claude models, by model name, used when no pricing
+/// data has been fetched
 pub fn get_fallback_pricing() -> HashMap<String, ModelPricing> {
     let mut pricing = HashMap::new();
 
@@ -5,11 +5,19 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::models_registry;
 use crate::pricing::{ModelPricing, get_fallback_pricing};
 
 /// Cache duration for pricing data (7 days)
 const CACHE_DURATION_DAYS: i64 = 7;
 
+/// Default online pricing source (LiteLLM's model price table)
+pub const DEFAULT_PRICING_SOURCE: &str =
+    "https://raw.githubusercontent.com/BerriAI/litellm/main/model_prices_and_context_window.json";
+
+/// Timeout for the pricing HTTP request
+const FETCH_TIMEOUT_SECS: u64 = 15;
+
 /// Offline pricing cache for storing model pricing data
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PricingCache {
@@ -19,6 +27,26 @@ pub struct PricingCache {
     pub last_updated: DateTime<Utc>,
     /// Version for cache compatibility
     pub version: String,
+    /// Source URL the pricing was fetched from (None for built-in data)
+    #[serde(default)]
+    pub source: Option<String>,
+    /// ETag returned by the source, used for conditional requests
+    #[serde(default)]
+    pub etag: Option<String>,
+}
+
+/// Result of an online pricing update
+pub enum UpdateOutcome {
+    /// New pricing was downloaded and cached
+    Updated { model_count: usize },
+    /// The source reported no changes since the cached ETag
+    NotModified,
+}
+
+impl Default for PricingCache {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PricingCache {
@@ -28,6 +56,62 @@ impl PricingCache {
             pricing_data: get_fallback_pricing(),
             last_updated: Utc::now(),
             version: env!("CARGO_PKG_VERSION").to_string(),
+            source: None,
+            etag: None,
+        }
+    }
+
+    /// Pricing used for cost calculation: a valid cache if present, otherwise fallback data,
+    /// plus the prices of the custom models in `models.yaml`
+    pub fn effective_pricing() -> HashMap<String, ModelPricing> {
+        let mut pricing_data = match Self::load() {
+            Ok(Some(cache)) if cache.is_valid() => cache.pricing_data,
+            _ => get_fallback_pricing(),
+        };
+        pricing_data.extend(models_registry::custom_models().pricing());
+        pricing_data
+    }
+
+    /// Fetch pricing from `source` and save it to the cache.
+    ///
+    /// Sends the cached ETag as `If-None-Match` when the source is unchanged, so an
+    /// unmodified price table only refreshes the cache timestamp. On any failure the
+    /// existing cache is left untouched and the error is returned.
+    pub fn update_from_source(source: &str) -> Result<UpdateOutcome> {
+        let existing = Self::load().unwrap_or(None);
+        let etag = existing
+            .as_ref()
+            .filter(|cache| cache.source.as_deref() == Some(source))
+            .and_then(|cache| cache.etag.clone());
+
+        match fetch_pricing(source, etag.as_deref())? {
+            Fetched::NotModified => {
+                if let Some(mut cache) = existing {
+                    cache.last_updated = Utc::now();
+                    cache.save()?;
+                }
+                Ok(UpdateOutcome::NotModified)
+            }
+            Fetched::Body { json, etag } => {
+                let fetched = parse_litellm_pricing(&json);
+                if fetched.is_empty() {
+                    anyhow::bail!("No Claude model pricing found in {}", source);
+                }
+                let model_count = fetched.len();
+
+                // Keep built-in entries for models the source does not list
+                let mut pricing_data = get_fallback_pricing();
+                pricing_data.extend(fetched);
+
+                let cache = Self {
+                    pricing_data,
+                    source: Some(source.to_string()),
+                    etag,
+                    ..Self::new()
+                };
+                cache.save()?;
+                Ok(UpdateOutcome::Updated { model_count })
+            }
         }
     }
 
@@ -105,6 +189,66 @@ impl PricingCache {
     }
 }
 
+/// Raw response from a pricing source
+enum Fetched {
+    NotModified,
+    Body {
+        json: serde_json::Value,
+        etag: Option<String>,
+    },
+}
+
+/// Fetch the pricing JSON from an HTTP(S) URL or a local file path
+fn fetch_pricing(source: &str, etag: Option<&str>) -> Result<Fetched> {
+    if !source.starts_with("http://") && !source.starts_with("https://") {
+        let path = source.strip_prefix("file://").unwrap_or(source);
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read pricing file: {}", path))?;
+        let json = serde_json::from_str(&data).context("Failed to parse pricing JSON")?;
+        return Ok(Fetched::Body { json, etag: None });
+    }
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(std::time::Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .build();
+    let mut request = agent.get(source);
+    if let Some(etag) = etag {
+        request = request.set("If-None-Match", etag);
+    }
+
+    // ureq errors already include the URL
+    let response = request.call().context("Failed to fetch pricing")?;
+    if response.status() == 304 {
+        return Ok(Fetched::NotModified);
+    }
+
+    let etag = response.header("ETag").map(str::to_string);
+    let json = response
+        .into_json()
+        .context("Failed to parse pricing JSON")?;
+    Ok(Fetched::Body { json, etag })
+}
+
+/// Extract Claude model pricing from a LiteLLM-style `model_prices` JSON object
+fn parse_litellm_pricing(json: &serde_json::Value) -> HashMap<String, ModelPricing> {
+    let Some(models) = json.as_object() else {
+        return HashMap::new();
+    };
+
+    models
+        .iter()
+        // Only Anthropic's own model ids; provider-prefixed entries (bedrock, vertex) are skipped
+        .filter(|(name, _)| name.starts_with("claude-"))
+        .filter_map(|(name, entry)| {
+            let pricing: ModelPricing = serde_json::from_value(entry.clone()).ok()?;
+            pricing
+                .input_cost_per_token
+                .is_some()
+                .then(|| (name.clone(), pricing))
+        })
+        .collect()
+}
+
 /// Enhanced pricing fetcher with offline cache support
 #[allow(dead_code)]
 pub struct CachedPricingFetcher {
@@ -138,16 +282,12 @@ impl CachedPricingFetcher {
         &self.fallback_pricing
     }
 
-    /// Try to fetch pricing from online source (placeholder for future implementation)
+    /// Fetch pricing from the default online source and reload the cache
     pub async fn fetch_online_pricing(&mut self) -> Result<()> {
-        // In the future, this would fetch from an API
-        // For now, we'll just update the cache with fallback data
-
-        let mut new_cache = PricingCache::new();
-        new_cache.update(self.fallback_pricing.clone());
-        new_cache.save()?;
+        tokio::task::spawn_blocking(|| PricingCache::update_from_source(DEFAULT_PRICING_SOURCE))
+            .await??;
 
-        self.cache = Some(new_cache);
+        self.cache = PricingCache::load()?;
         Ok(())
     }
 
@@ -267,6 +407,43 @@ mod tests {
         assert!(!cache.is_valid());
     }
 
+    #[test]
+    fn test_parse_litellm_pricing() {
+        let json = serde_json::json!({
+            "sample_spec": { "input_cost_per_token": "per token cost" },
+            "claude-sonnet-4-20250514": {
+                "input_cost_per_token": 3e-06,
+                "output_cost_per_token": 1.5e-05,
+                "cache_creation_input_token_cost": 3.75e-06,
+                "cache_read_input_token_cost": 3e-07,
+                "input_cost_per_token_above_200k_tokens": 6e-06,
+                "litellm_provider": "anthropic"
+            },
+            "bedrock/claude-sonnet-4-20250514": { "input_cost_per_token": 1.0 },
+            "claude-embedding": { "mode": "embedding" }
+        });
+
+        let pricing = parse_litellm_pricing(&json);
+        assert_eq!(pricing.len(), 1);
+        let sonnet = &pricing["claude-sonnet-4-20250514"];
+        assert_eq!(sonnet.output_cost_per_token, Some(1.5e-05));
+        assert_eq!(sonnet.input_cost_per_token_above_200k, Some(6e-06));
+    }
+
+    #[test]
+    fn test_fetch_pricing_from_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("prices.json");
+        fs::write(&path, r#"{"claude-x": {"input_cost_per_token": 1e-06}}"#).unwrap();
+
+        let Fetched::Body { json, etag } = fetch_pricing(path.to_str().unwrap(), None).unwrap()
+        else {
+            panic!("expected a body");
+        };
+        assert!(etag.is_none());
+        assert_eq!(parse_litellm_pricing(&json).len(), 1);
+    }
+
     #[test]
     fn test_model_variations() {
         let variations = CachedPricingFetcher::get_model_variations("claude-sonnet-4");
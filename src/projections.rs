@@ -1,45 +1,291 @@
-use chrono::{Datelike, Duration, NaiveDate, Utc};
+//! Cost and token forecasts: month-end projections, billing cycles, and backtests
+
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, Utc};
 use serde::Serialize;
 
+use crate::alerts::AlertEvent;
+use crate::currency::{self, Money};
 use crate::helpers::calculate_average;
-use crate::models::DailyUsageMap;
+use crate::models::{DailyUsageMap, SessionUsageMap};
 
 /// Time series data point for projections
 #[derive(Debug, Clone, Serialize)]
 pub struct DataPoint {
+    /// Day of the value
     pub date: NaiveDate,
+    /// Cost or token count of the day
     pub value: f64,
 }
 
 /// Projection result with confidence intervals
 #[derive(Debug, Clone, Serialize)]
 pub struct Projection {
+    /// Projected day
     pub date: NaiveDate,
+    /// Projected value for the day
     pub value: f64,
+    /// Lower end of the 95% interval
     pub lower_bound: f64,
+    /// Upper end of the 95% interval
     pub upper_bound: f64,
+    /// Confidence in the value, falling from 1 the further ahead the day is
     pub confidence: f64,
 }
 
 /// Usage projection analysis
 #[derive(Debug, Clone, Serialize)]
 pub struct UsageProjection {
+    /// Average value per day over the history window
     pub daily_average: f64,
+    /// Total of the last 7 days, or 7 average days with less history
     pub weekly_average: f64,
+    /// Total of the last 30 days, or 30 average days with less history
     pub monthly_average: f64,
+    /// Direction of the fitted linear trend
     pub trend: TrendDirection,
+    /// Daily growth of the trend, in percent
     pub growth_rate: f64,
+    /// Day-by-day forecast from the latest day and the trend
     pub projections: Vec<Projection>,
+    /// Sum of the next 30 projected days
     pub estimated_monthly_cost: f64,
+    /// Days until the limit is reached; `None` without a limit or growth
     pub days_until_limit: Option<i64>,
+    /// Date the limit is reached
     pub limit_date: Option<NaiveDate>,
+    /// Spend and forecast for the current billing cycle
+    pub billing_cycle: CycleForecast,
+    /// Weekday-aware alternative to `projections`
+    pub seasonal: SeasonalForecast,
+}
+
+/// Forecast model compared by the backtest
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ForecastModel {
+    /// Latest day compounded by the linear trend (`projections`)
+    Naive,
+    /// Average day scaled by its day-of-week index
+    Seasonal,
+}
+
+/// Forecast that gives every day of the week its own share of the average day,
+/// so quiet weekends are not projected at weekday rates
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonalForecast {
+    /// Average spend per Monday–Friday, idle days counting as zero
+    pub weekday_baseline: f64,
+    /// Average spend per Saturday/Sunday, idle days counting as zero
+    pub weekend_baseline: f64,
+    /// Multiple of the average day, Monday through Sunday
+    pub day_of_week_index: [f64; 7],
+    /// Day-by-day forecast from the day-of-week index
+    pub projections: Vec<Projection>,
+    /// Sum of the next 30 seasonal projected days
+    pub estimated_monthly_cost: f64,
+    /// `None` until there are two weeks of history
+    pub backtest: Option<Backtest>,
+}
+
+/// Both models fitted on history up to the last week and scored on that week
+#[derive(Debug, Clone, Serialize)]
+pub struct Backtest {
+    /// Days held out of the fit
+    pub holdout_days: i64,
+    /// Mean absolute percentage error over holdout days with usage
+    pub naive_mape: f64,
+    /// Mean absolute percentage error of the seasonal model
+    pub seasonal_mape: f64,
+    /// Model with the lower percentage error
+    pub better: ForecastModel,
+}
+
+/// Days held out of the fit to score the models
+const BACKTEST_DAYS: i64 = 7;
+
+/// Replay of past days, each forecast one day ahead from the history before it
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    /// Days replayed; days with no earlier history are skipped
+    pub days: usize,
+    /// Actual spend over the replayed days
+    pub actual_total: f64,
+    /// Errors of the naive model
+    pub naive: ErrorMetrics,
+    /// Errors of the seasonal model
+    pub seasonal: ErrorMetrics,
+    /// Model with the lower mean absolute error, `None` on a tie or without days
+    pub better: Option<ForecastModel>,
+    /// Per-day predictions, oldest first
+    pub daily: Vec<BacktestDay>,
+}
+
+/// What each model predicted for one replayed day
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestDay {
+    /// Replayed day
+    pub date: NaiveDate,
+    /// Actual spend of the day
+    pub actual: f64,
+    /// Naive model's prediction
+    pub naive: f64,
+    /// Seasonal model's prediction
+    pub seasonal: f64,
+}
+
+/// Forecast errors of one model over the replayed days
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ErrorMetrics {
+    /// Mean absolute error
+    pub mae: f64,
+    /// Root mean squared error
+    pub rmse: f64,
+    /// Mean absolute percentage error over days with usage
+    pub mape: Option<f64>,
+    /// Mean of predicted minus actual; positive means the model over-forecasts
+    pub bias: f64,
+    /// Sum of the predictions
+    pub predicted_total: f64,
+}
+
+impl ErrorMetrics {
+    fn from_pairs(pairs: impl Iterator<Item = (f64, f64)>) -> Self {
+        let pairs: Vec<(f64, f64)> = pairs.collect();
+        if pairs.is_empty() {
+            return Self::default();
+        }
+        let n = pairs.len() as f64;
+        let errors: Vec<f64> = pairs
+            .iter()
+            .map(|(predicted, actual)| predicted - actual)
+            .collect();
+        let percentages: Vec<f64> = pairs
+            .iter()
+            .filter(|(_, actual)| *actual > 0.0)
+            .map(|(predicted, actual)| (predicted - actual).abs() / actual * 100.0)
+            .collect();
+        Self {
+            mae: errors.iter().map(|e| e.abs()).sum::<f64>() / n,
+            rmse: (errors.iter().map(|e| e * e).sum::<f64>() / n).sqrt(),
+            mape: (!percentages.is_empty()).then(|| calculate_average(&percentages)),
+            bias: errors.iter().sum::<f64>() / n,
+            predicted_total: pairs.iter().map(|(predicted, _)| predicted).sum(),
+        }
+    }
+}
+
+/// Billing period that starts on a fixed day of the month, e.g. the 12th
+/// through the 11th of the next month
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct BillingCycle {
+    /// First day of the cycle
+    pub start: NaiveDate,
+    /// Last day of the cycle (inclusive)
+    pub end: NaiveDate,
+}
+
+impl BillingCycle {
+    /// The cycle containing `date`. A start day past the end of a short month
+    /// falls on that month's last day.
+    pub fn containing(date: NaiveDate, start_day: u32) -> Self {
+        let this_month = cycle_anchor(date, start_day);
+        let start = if date >= this_month {
+            this_month
+        } else {
+            cycle_anchor(first_of_month(date) - Months::new(1), start_day)
+        };
+        let next_start = cycle_anchor(first_of_month(start) + Months::new(1), start_day);
+        Self {
+            start,
+            end: next_start - Duration::days(1),
+        }
+    }
+
+    /// Whether `date` falls in the cycle
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        (self.start..=self.end).contains(&date)
+    }
+
+    /// Days after `today` until the cycle ends
+    pub fn days_remaining(&self, today: NaiveDate) -> i64 {
+        (self.end - today).num_days().max(0)
+    }
+
+    /// Spend from the start of the cycle up to and including `today`
+    pub fn spent_to_date(&self, daily_usage: &DailyUsageMap, today: NaiveDate) -> f64 {
+        daily_usage
+            .iter()
+            .filter(|(date, _)| self.contains(**date) && **date <= today)
+            .map(|(_, usage)| usage.total_cost)
+            .sum()
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// `start_day` of `date`'s month, clamped to the month's length
+fn cycle_anchor(date: NaiveDate, start_day: u32) -> NaiveDate {
+    let first = first_of_month(date);
+    let month_days = ((first + Months::new(1)) - first).num_days() as u32;
+    first + Duration::days(i64::from(start_day.clamp(1, month_days)) - 1)
+}
+
+/// Spend so far in the current billing cycle and the forecast for its end
+#[derive(Debug, Clone, Serialize)]
+pub struct CycleForecast {
+    /// First day of the cycle
+    pub cycle_start: NaiveDate,
+    /// Last day of the cycle (inclusive)
+    pub cycle_end: NaiveDate,
+    /// Days after today until the cycle ends
+    pub days_remaining: i64,
+    /// Spend from the start of the cycle through today
+    pub spent_to_date: f64,
+    /// Spend so far plus the daily rate for the remaining days
+    pub projected_total: f64,
+    /// Cost limit per cycle, if any
+    pub limit: Option<f64>,
+    /// How far the projected total goes over the limit
+    pub projected_overage: Option<f64>,
+}
+
+impl CycleForecast {
+    /// Forecast for `cycle` from the spend so far and a daily rate
+    pub fn new(
+        cycle: BillingCycle,
+        today: NaiveDate,
+        spent_to_date: f64,
+        daily_rate: f64,
+        limit: Option<f64>,
+    ) -> Self {
+        // An empty sum of f64 is -0.0
+        let spent_to_date = spent_to_date.max(0.0);
+        let days_remaining = cycle.days_remaining(today);
+        let projected_total = spent_to_date + daily_rate.max(0.0) * days_remaining as f64;
+        Self {
+            cycle_start: cycle.start,
+            cycle_end: cycle.end,
+            days_remaining,
+            spent_to_date,
+            projected_total,
+            limit,
+            projected_overage: limit
+                .map(|limit| projected_total - limit)
+                .filter(|overage| *overage > 0.0),
+        }
+    }
 }
 
 /// Trend direction for usage patterns
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub enum TrendDirection {
+    /// Growing by more than 5% a day
     Increasing,
+    /// Shrinking by more than 5% a day
     Decreasing,
+    /// Neither growing nor shrinking
     Stable,
 }
 
@@ -49,29 +295,50 @@ pub struct ProjectionCalculator {
     projection_days: i64,
     token_limit: Option<u64>,
     cost_limit: Option<f64>,
+    billing_cycle_start_day: u32,
+}
+
+impl Default for ProjectionCalculator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ProjectionCalculator {
+    /// Calculator with 30 days of history, 30 projected days and no limits
     pub fn new() -> Self {
         Self {
             history_days: 30,
             projection_days: 30,
             token_limit: None,
             cost_limit: None,
+            billing_cycle_start_day: 1,
         }
     }
 
+    /// Token and cost limits per billing cycle
     pub fn with_limits(mut self, token_limit: Option<u64>, cost_limit: Option<f64>) -> Self {
         self.token_limit = token_limit;
         self.cost_limit = cost_limit;
         self
     }
 
+    /// Number of days to project ahead
     pub fn with_projection_days(mut self, days: i64) -> Self {
         self.projection_days = days;
         self
     }
 
+    /// Day of the month the billing cycle starts on; limits apply per cycle
+    pub fn with_billing_cycle_start_day(mut self, day: u32) -> Self {
+        self.billing_cycle_start_day = day;
+        self
+    }
+
+    fn current_cycle(&self) -> BillingCycle {
+        BillingCycle::containing(Utc::now().date_naive(), self.billing_cycle_start_day)
+    }
+
     /// Calculate usage projections from daily data
     pub fn calculate_projections(&self, daily_usage: &DailyUsageMap) -> UsageProjection {
         let data_points = self.collect_data_points(daily_usage, |usage| usage.total_cost);
@@ -105,6 +372,16 @@ impl ProjectionCalculator {
         // Estimate monthly cost based on projections
         let estimated_monthly_cost = self.estimate_monthly_cost(&projections, daily_average);
 
+        let today = Utc::now().date_naive();
+        let cycle = self.current_cycle();
+        let spent_to_date = data_points
+            .iter()
+            .filter(|p| cycle.contains(p.date) && p.date <= today)
+            .map(|p| p.value)
+            .sum();
+        let billing_cycle = CycleForecast::new(cycle, today, spent_to_date, daily_average, limit);
+        let seasonal = self.calculate_seasonal(data_points);
+
         UsageProjection {
             daily_average,
             weekly_average,
@@ -115,6 +392,8 @@ impl ProjectionCalculator {
             estimated_monthly_cost,
             days_until_limit,
             limit_date,
+            billing_cycle,
+            seasonal,
         }
     }
 
@@ -220,7 +499,6 @@ impl ProjectionCalculator {
         let mut projections = Vec::new();
         let last_date = data_points.last().unwrap().date;
         let last_value = data_points.last().unwrap().value;
-        let daily_growth = 1.0 + (growth_rate / 100.0);
 
         // Calculate standard deviation for confidence intervals
         let avg = self.calculate_daily_average(data_points);
@@ -233,7 +511,7 @@ impl ProjectionCalculator {
 
         for i in 1..=self.projection_days {
             let date = last_date + Duration::days(i);
-            let value = last_value * daily_growth.powi(i as i32);
+            let value = naive_value(last_value, growth_rate, i);
 
             // Confidence decreases as we project further into the future
             let confidence = 1.0 / (1.0 + (i as f64 * 0.03));
@@ -255,6 +533,165 @@ impl ProjectionCalculator {
         projections
     }
 
+    /// Weekday-aware forecast over the same horizon, with a backtest against the naive one
+    fn calculate_seasonal(&self, data_points: &[DataPoint]) -> SeasonalForecast {
+        let series = fill_calendar(data_points);
+        let (level, index) = seasonal_index(&series);
+        let fitted =
+            |date: NaiveDate| level * index[date.weekday().num_days_from_monday() as usize];
+
+        let baseline = |weekend: bool| {
+            let values: Vec<f64> = series
+                .iter()
+                .filter(|p| (p.date.weekday().num_days_from_monday() >= 5) == weekend)
+                .map(|p| p.value)
+                .collect();
+            calculate_average(&values)
+        };
+
+        let residuals: Vec<f64> = series.iter().map(|p| p.value - fitted(p.date)).collect();
+        let (_, std_dev) = mean_std_dev(&residuals);
+        let projections: Vec<Projection> = match series.last() {
+            Some(last) => (1..=self.projection_days)
+                .map(|i| {
+                    let date = last.date + Duration::days(i);
+                    let value = fitted(date);
+                    Projection {
+                        date,
+                        value,
+                        lower_bound: (value - 1.96 * std_dev).max(0.0),
+                        upper_bound: value + 1.96 * std_dev,
+                        confidence: 1.0 / (1.0 + (i as f64 * 0.03)),
+                    }
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        let estimated_monthly_cost = self.estimate_monthly_cost(&projections, level);
+
+        SeasonalForecast {
+            weekday_baseline: baseline(false),
+            weekend_baseline: baseline(true),
+            day_of_week_index: index,
+            projections,
+            estimated_monthly_cost,
+            backtest: self.backtest(data_points),
+        }
+    }
+
+    /// Fit both models on all but the last week and compare their errors on it
+    fn backtest(&self, data_points: &[DataPoint]) -> Option<Backtest> {
+        let last_date = data_points.last()?.date;
+        let holdout_start = last_date - Duration::days(BACKTEST_DAYS - 1);
+        let training: Vec<DataPoint> = data_points
+            .iter()
+            .filter(|p| p.date < holdout_start)
+            .cloned()
+            .collect();
+        let training_series = fill_calendar(&training);
+        // Every day of the week needs to be seen at least once
+        if (training_series.len() as i64) < BACKTEST_DAYS {
+            return None;
+        }
+
+        let (level, index) = seasonal_index(&training_series);
+        let (_, growth_rate) = self.calculate_trend(&training);
+        let last_training = training.last()?;
+
+        let mut naive_errors = Vec::new();
+        let mut seasonal_errors = Vec::new();
+        for actual in fill_calendar(data_points)
+            .iter()
+            .filter(|p| p.date >= holdout_start && p.value > 0.0)
+        {
+            let days_ahead = (actual.date - last_training.date).num_days();
+            let naive = naive_value(last_training.value, growth_rate, days_ahead);
+            let seasonal = level * index[actual.date.weekday().num_days_from_monday() as usize];
+            naive_errors.push((naive - actual.value).abs() / actual.value * 100.0);
+            seasonal_errors.push((seasonal - actual.value).abs() / actual.value * 100.0);
+        }
+        if naive_errors.is_empty() {
+            return None;
+        }
+
+        let naive_mape = calculate_average(&naive_errors);
+        let seasonal_mape = calculate_average(&seasonal_errors);
+        Some(Backtest {
+            holdout_days: BACKTEST_DAYS,
+            naive_mape,
+            seasonal_mape,
+            better: if seasonal_mape < naive_mape {
+                ForecastModel::Seasonal
+            } else {
+                ForecastModel::Naive
+            },
+        })
+    }
+
+    /// Replay the `days` full days before `today`: forecast each one from the
+    /// history before it, as the projection would have on that morning, and
+    /// score both models against what was actually spent
+    pub fn backtest_history(
+        &self,
+        daily_usage: &DailyUsageMap,
+        days: i64,
+        today: NaiveDate,
+    ) -> BacktestReport {
+        let mut points: Vec<DataPoint> = daily_usage
+            .iter()
+            .map(|(date, usage)| DataPoint {
+                date: *date,
+                value: usage.total_cost,
+            })
+            .collect();
+        points.sort_by_key(|p| p.date);
+
+        let mut daily = Vec::new();
+        for offset in (1..=days).rev() {
+            let date = today - Duration::days(offset);
+            let history: Vec<DataPoint> = points
+                .iter()
+                .filter(|p| p.date < date && p.date >= date - Duration::days(self.history_days))
+                .cloned()
+                .collect();
+            let Some(last) = history.last() else {
+                continue;
+            };
+
+            let (_, growth_rate) = self.calculate_trend(&history);
+            let series = fill_calendar_until(&history, date - Duration::days(1));
+            let (level, index) = seasonal_index(&series);
+            daily.push(BacktestDay {
+                date,
+                // Idle days have no point; an empty f64 sum would be -0.0
+                actual: points
+                    .iter()
+                    .find(|p| p.date == date)
+                    .map_or(0.0, |p| p.value.max(0.0)),
+                naive: naive_value(last.value, growth_rate, (date - last.date).num_days()),
+                seasonal: level * index[date.weekday().num_days_from_monday() as usize],
+            });
+        }
+
+        let naive = ErrorMetrics::from_pairs(daily.iter().map(|d| (d.naive, d.actual)));
+        let seasonal = ErrorMetrics::from_pairs(daily.iter().map(|d| (d.seasonal, d.actual)));
+        let better = if daily.is_empty() || naive.mae == seasonal.mae {
+            None
+        } else if seasonal.mae < naive.mae {
+            Some(ForecastModel::Seasonal)
+        } else {
+            Some(ForecastModel::Naive)
+        };
+        BacktestReport {
+            days: daily.len(),
+            actual_total: daily.iter().fold(0.0, |total, d| total + d.actual),
+            naive,
+            seasonal,
+            better,
+            daily,
+        }
+    }
+
     /// Calculate when limits will be reached (internal method with explicit limit)
     fn calculate_limit_timing_internal(
         &self,
@@ -269,11 +706,11 @@ impl ProjectionCalculator {
 
         let today = Utc::now().date_naive();
 
-        // Calculate cumulative cost for current month
-        let month_start = NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap();
+        // Calculate cumulative cost for the current billing cycle
+        let cycle_start = self.current_cycle().start;
         let current_month_cost: f64 = data_points
             .iter()
-            .filter(|p| p.date >= month_start)
+            .filter(|p| p.date >= cycle_start)
             .map(|p| p.value)
             .sum();
 
@@ -307,15 +744,76 @@ impl ProjectionCalculator {
     }
 }
 
+/// `last_value` compounded by the daily growth rate (percent) for `days`
+fn naive_value(last_value: f64, growth_rate: f64, days: i64) -> f64 {
+    last_value * (1.0 + growth_rate / 100.0).powi(days as i32)
+}
+
+/// Every calendar day from the first to the last point, idle days as zero
+fn fill_calendar(data_points: &[DataPoint]) -> Vec<DataPoint> {
+    match data_points.last() {
+        Some(last) => fill_calendar_until(data_points, last.date),
+        None => Vec::new(),
+    }
+}
+
+/// Every calendar day from the first point through `end`, idle days as zero
+fn fill_calendar_until(data_points: &[DataPoint], end: NaiveDate) -> Vec<DataPoint> {
+    let Some(first) = data_points.first() else {
+        return Vec::new();
+    };
+    first
+        .date
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| DataPoint {
+            date,
+            value: data_points
+                .iter()
+                .filter(|p| p.date == date)
+                .map(|p| p.value)
+                .sum(),
+        })
+        .collect()
+}
+
+/// Average day of a calendar series and each weekday's multiple of it (Monday first);
+/// weekdays without data keep a multiple of 1
+fn seasonal_index(series: &[DataPoint]) -> (f64, [f64; 7]) {
+    let values: Vec<f64> = series.iter().map(|p| p.value).collect();
+    let level = calculate_average(&values);
+    let mut sums = [0.0; 7];
+    let mut counts = [0usize; 7];
+    for point in series {
+        let day = point.date.weekday().num_days_from_monday() as usize;
+        sums[day] += point.value;
+        counts[day] += 1;
+    }
+    let mut index = [1.0; 7];
+    for day in 0..7 {
+        if counts[day] > 0 && level > 0.0 {
+            index[day] = sums[day] / counts[day] as f64 / level;
+        }
+    }
+    (level, index)
+}
+
 /// Token-based projections (similar structure but for token counts)
 #[derive(Debug, Clone, Serialize)]
 pub struct TokenProjection {
+    /// Average tokens per day over the history window
     pub daily_average_tokens: u64,
+    /// Tokens of the last 7 days, or 7 average days with less history
     pub weekly_average_tokens: u64,
+    /// Tokens of the last 30 days, or 30 average days with less history
     pub monthly_average_tokens: u64,
+    /// Direction of the fitted linear trend
     pub trend: TrendDirection,
+    /// Daily growth of the trend, in percent
     pub growth_rate: f64,
+    /// Days until the token limit is reached
     pub days_until_token_limit: Option<i64>,
+    /// Date the token limit is reached
     pub token_limit_date: Option<NaiveDate>,
 }
 
@@ -368,9 +866,10 @@ impl ProjectionCalculator {
 
         // Calculate token limit timing
         let (days_until_token_limit, token_limit_date) = if let Some(limit) = self.token_limit {
+            let cycle = self.current_cycle();
             let current_month_tokens: u64 = token_points
                 .iter()
-                .filter(|(date, _)| date.month() == today.month() && date.year() == today.year())
+                .filter(|(date, _)| cycle.contains(*date))
                 .map(|(_, tokens)| tokens)
                 .sum();
 
@@ -380,8 +879,8 @@ impl ProjectionCalculator {
                 let remaining = limit - current_month_tokens;
                 let daily_burn = (daily_average as f64 * (1.0 + growth_rate / 100.0)) as u64;
 
-                if daily_burn > 0 {
-                    let days = (remaining / daily_burn) as i64;
+                if let Some(days) = remaining.checked_div(daily_burn) {
+                    let days = days as i64;
                     (Some(days), Some(today + Duration::days(days)))
                 } else {
                     (None, None)
@@ -403,6 +902,248 @@ impl ProjectionCalculator {
     }
 }
 
+/// Day whose cost is far above the trailing average
+#[derive(Debug, Clone, Serialize)]
+pub struct CostAnomaly {
+    /// Day with the anomaly
+    pub date: NaiveDate,
+    /// Cost of the day
+    pub cost: f64,
+    /// Mean daily cost of the active days in the trailing window
+    pub baseline_mean: f64,
+    /// Standard deviation of the daily cost in the window
+    pub baseline_std_dev: f64,
+    /// Standard deviations above the baseline mean
+    pub z_score: f64,
+}
+
+/// Session whose tokens per message are far above those of the other sessions
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionAnomaly {
+    /// `project-dir/session-id` key
+    pub session: String,
+    /// Messages in the session
+    pub messages: u64,
+    /// Tokens used by the session
+    pub total_tokens: u64,
+    /// Tokens per message of the session
+    pub tokens_per_message: f64,
+    /// Mean tokens per message of the other sessions
+    pub baseline_mean: f64,
+    /// Standard deviations above the baseline mean
+    pub z_score: f64,
+    /// Cost of the session
+    pub cost: f64,
+    /// Time of the session's last message
+    pub last_activity: DateTime<Utc>,
+}
+
+/// Result of [`AnomalyDetector::detect`]
+#[derive(Debug, Clone, Serialize)]
+pub struct AnomalyReport {
+    /// Standard deviations above normal that count as an anomaly
+    pub threshold: f64,
+    /// Days of trailing history each day is compared with
+    pub window_days: i64,
+    /// Most recent first
+    pub cost_anomalies: Vec<CostAnomaly>,
+    /// Highest z-score first
+    pub session_anomalies: Vec<SessionAnomaly>,
+}
+
+impl AnomalyReport {
+    /// Whether nothing was flagged
+    pub fn is_empty(&self) -> bool {
+        self.cost_anomalies.is_empty() && self.session_anomalies.is_empty()
+    }
+
+    /// Anomalies of `today` (local date) as events for the alert hooks
+    #[doc(hidden)]
+    pub fn alert_events(&self, today: NaiveDate) -> Vec<AlertEvent> {
+        let now = Utc::now();
+        let days = self
+            .cost_anomalies
+            .iter()
+            .filter(|anomaly| anomaly.date == today)
+            .map(|anomaly| AlertEvent {
+                kind: "cost_anomaly".to_string(),
+                severity: "warning".to_string(),
+                message: format!(
+                    "Cost {:.2} on {} is {:.1} standard deviations above the {}-day mean ({:.2})",
+                    Money(anomaly.cost),
+                    anomaly.date,
+                    anomaly.z_score,
+                    self.window_days,
+                    Money(anomaly.baseline_mean)
+                ),
+                cost: anomaly.cost,
+                currency: currency::current().code.clone(),
+                tokens: 0,
+                limit: Some(anomaly.baseline_mean + self.threshold * anomaly.baseline_std_dev),
+                timestamp: now,
+            });
+        let sessions = self
+            .session_anomalies
+            .iter()
+            .filter(|anomaly| anomaly.last_activity.with_timezone(&Local).date_naive() == today)
+            .map(|anomaly| AlertEvent {
+                kind: "session_anomaly".to_string(),
+                severity: "warning".to_string(),
+                message: format!(
+                    "Session {} uses {:.0} tokens per message, {:.1} standard deviations above typical ({:.0})",
+                    anomaly.session, anomaly.tokens_per_message, anomaly.z_score, anomaly.baseline_mean
+                ),
+                cost: anomaly.cost,
+                currency: currency::current().code.clone(),
+                tokens: anomaly.tokens_per_message as u64,
+                limit: None,
+                timestamp: now,
+            });
+        days.chain(sessions).collect()
+    }
+}
+
+/// Flags days and sessions more than `threshold` standard deviations above normal
+pub struct AnomalyDetector {
+    threshold: f64,
+    window_days: i64,
+    /// Active days (or other sessions) needed before anything is flagged
+    min_history: usize,
+    /// Sessions with fewer messages are too small to judge
+    min_messages: u64,
+}
+
+impl AnomalyDetector {
+    /// Detector flagging values `threshold` standard deviations above normal
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            window_days: 30,
+            min_history: 7,
+            min_messages: 3,
+        }
+    }
+
+    /// Days of trailing history each day is compared with (at least 1)
+    pub fn with_window_days(mut self, days: i64) -> Self {
+        self.window_days = days.max(1);
+        self
+    }
+
+    /// Flag anomalous days in `daily_usage` and sessions in `sessions`
+    pub fn detect(&self, daily_usage: &DailyUsageMap, sessions: &SessionUsageMap) -> AnomalyReport {
+        AnomalyReport {
+            threshold: self.threshold,
+            window_days: self.window_days,
+            cost_anomalies: self.detect_cost_anomalies(daily_usage),
+            session_anomalies: self.detect_session_anomalies(sessions),
+        }
+    }
+
+    /// Compare each day's cost with the active days of the preceding window.
+    /// Days without usage are not counted, so occasional users are not flagged
+    /// for every working day.
+    pub fn detect_cost_anomalies(&self, daily_usage: &DailyUsageMap) -> Vec<CostAnomaly> {
+        let mut days: Vec<(NaiveDate, f64)> = daily_usage
+            .iter()
+            .map(|(date, usage)| (*date, usage.total_cost))
+            .collect();
+        days.sort_by_key(|(date, _)| *date);
+
+        let mut anomalies: Vec<CostAnomaly> = days
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &(date, cost))| {
+                let window_start = date - Duration::days(self.window_days);
+                let history: Vec<f64> = days[..i]
+                    .iter()
+                    .filter(|(d, _)| *d >= window_start)
+                    .map(|(_, c)| *c)
+                    .collect();
+                if history.len() < self.min_history {
+                    return None;
+                }
+
+                let (mean, std_dev) = mean_std_dev(&history);
+                let z_score = z_score(cost, mean, std_dev)?;
+                (z_score > self.threshold).then_some(CostAnomaly {
+                    date,
+                    cost,
+                    baseline_mean: mean,
+                    baseline_std_dev: std_dev,
+                    z_score,
+                })
+            })
+            .collect();
+
+        anomalies.reverse();
+        anomalies
+    }
+
+    /// Compare each session's tokens per message with all other sessions
+    pub fn detect_session_anomalies(&self, sessions: &SessionUsageMap) -> Vec<SessionAnomaly> {
+        let rates: Vec<(&String, f64)> = sessions
+            .iter()
+            .filter(|(_, (usage, _))| usage.message_count >= self.min_messages)
+            .map(|(key, (usage, _))| {
+                (
+                    key,
+                    usage.total_tokens() as f64 / usage.message_count as f64,
+                )
+            })
+            .collect();
+        if rates.len() <= self.min_history {
+            return Vec::new();
+        }
+
+        // Leave-one-out baseline, so a single huge session cannot hide itself
+        // by inflating the deviation it is measured against
+        let n = rates.len() as f64;
+        let sum: f64 = rates.iter().map(|(_, r)| r).sum();
+        let sum_sq: f64 = rates.iter().map(|(_, r)| r * r).sum();
+
+        let mut anomalies: Vec<SessionAnomaly> = rates
+            .iter()
+            .filter_map(|&(key, rate)| {
+                let others = n - 1.0;
+                let mean = (sum - rate) / others;
+                let variance = ((sum_sq - rate * rate) / others - mean * mean).max(0.0);
+                let z_score = z_score(rate, mean, variance.sqrt())?;
+                if z_score <= self.threshold {
+                    return None;
+                }
+
+                let (usage, last_activity) = &sessions[key];
+                Some(SessionAnomaly {
+                    session: key.clone(),
+                    messages: usage.message_count,
+                    total_tokens: usage.total_tokens(),
+                    tokens_per_message: rate,
+                    baseline_mean: mean,
+                    z_score,
+                    cost: usage.total_cost,
+                    last_activity: *last_activity,
+                })
+            })
+            .collect();
+
+        anomalies.sort_by(|a, b| b.z_score.total_cmp(&a.z_score));
+        anomalies
+    }
+}
+
+/// Population mean and standard deviation
+fn mean_std_dev(values: &[f64]) -> (f64, f64) {
+    let mean = calculate_average(values);
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    (mean, variance.sqrt())
+}
+
+/// Standard score, or `None` when the baseline has no spread to measure against
+fn z_score(value: f64, mean: f64, std_dev: f64) -> Option<f64> {
+    (std_dev > f64::EPSILON).then(|| (value - mean) / std_dev)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -422,6 +1163,7 @@ mod tests {
                 cache_read_tokens: 0,
                 total_cost: 0.15 + ((9 - i) as f64 * 0.01), // Increasing cost over time
                 fast_mode_cost: 0.0,
+                ..Default::default()
             };
             daily_usage.insert(date, usage);
         }
@@ -448,6 +1190,7 @@ mod tests {
                 cache_read_tokens: 0,
                 total_cost: 1.0,
                 fast_mode_cost: 0.0,
+                ..Default::default()
             };
             daily_usage.insert(date, usage);
         }
@@ -461,6 +1204,166 @@ mod tests {
         assert!(projection.limit_date.is_some());
     }
 
+    #[test]
+    fn test_billing_cycle() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        let cycle = BillingCycle::containing(date(2024, 3, 20), 12);
+        assert_eq!(
+            (cycle.start, cycle.end),
+            (date(2024, 3, 12), date(2024, 4, 11))
+        );
+        assert_eq!(cycle.days_remaining(date(2024, 3, 20)), 22);
+
+        // Before the anchor day the cycle started last month
+        let cycle = BillingCycle::containing(date(2024, 1, 5), 12);
+        assert_eq!(
+            (cycle.start, cycle.end),
+            (date(2023, 12, 12), date(2024, 1, 11))
+        );
+
+        // Anchors past the end of a short month fall on its last day
+        let cycle = BillingCycle::containing(date(2024, 2, 29), 31);
+        assert_eq!(
+            (cycle.start, cycle.end),
+            (date(2024, 2, 29), date(2024, 3, 30))
+        );
+        let cycle = BillingCycle::containing(date(2024, 3, 1), 1);
+        assert_eq!(
+            (cycle.start, cycle.end),
+            (date(2024, 3, 1), date(2024, 3, 31))
+        );
+
+        let mut daily_usage = DailyUsageMap::new();
+        for day in 10..=20 {
+            let usage = TokenUsage {
+                total_cost: 2.0,
+                ..Default::default()
+            };
+            daily_usage.insert(date(2024, 3, day), usage);
+        }
+        let cycle = BillingCycle::containing(date(2024, 3, 20), 12);
+        let spent = cycle.spent_to_date(&daily_usage, date(2024, 3, 20));
+        assert_eq!(spent, 18.0);
+
+        let forecast = CycleForecast::new(cycle, date(2024, 3, 20), spent, 2.0, Some(50.0));
+        assert_eq!(forecast.projected_total, 62.0);
+        assert_eq!(forecast.projected_overage, Some(12.0));
+        let forecast = CycleForecast::new(cycle, date(2024, 3, 20), spent, 2.0, Some(100.0));
+        assert_eq!(forecast.projected_overage, None);
+    }
+
+    #[test]
+    fn test_seasonal_forecast_beats_naive_on_weekly_pattern() {
+        // Four weeks of $10 weekdays and $1 weekends, ending yesterday
+        let today = Utc::now().date_naive();
+        let mut daily_usage = DailyUsageMap::new();
+        for i in 1..=28 {
+            let date = today - Duration::days(i);
+            let weekend = date.weekday().num_days_from_monday() >= 5;
+            let usage = TokenUsage {
+                total_cost: if weekend { 1.0 } else { 10.0 },
+                ..Default::default()
+            };
+            daily_usage.insert(date, usage);
+        }
+
+        let projection = ProjectionCalculator::new()
+            .with_projection_days(7)
+            .calculate_projections(&daily_usage);
+        let seasonal = &projection.seasonal;
+        assert_eq!(seasonal.weekday_baseline, 10.0);
+        assert_eq!(seasonal.weekend_baseline, 1.0);
+        assert_eq!(seasonal.projections.len(), 7);
+        for forecast in &seasonal.projections {
+            let weekend = forecast.date.weekday().num_days_from_monday() >= 5;
+            let expected = if weekend { 1.0 } else { 10.0 };
+            assert!((forecast.value - expected).abs() < 1e-9);
+        }
+        assert!(
+            (seasonal.estimated_monthly_cost
+                - seasonal.projections.iter().map(|p| p.value).sum::<f64>())
+            .abs()
+                < 1e-9
+        );
+
+        let backtest = seasonal.backtest.as_ref().unwrap();
+        assert!(backtest.seasonal_mape < 1e-9);
+        assert!(backtest.naive_mape > 0.0);
+        assert_eq!(backtest.better, ForecastModel::Seasonal);
+
+        // A week of history is too short to backtest
+        let recent: DailyUsageMap = daily_usage
+            .into_iter()
+            .filter(|(date, _)| *date >= today - Duration::days(7))
+            .collect();
+        let projection = ProjectionCalculator::new().calculate_projections(&recent);
+        assert!(projection.seasonal.backtest.is_none());
+    }
+
+    #[test]
+    fn test_backtest_history_replays_each_day() {
+        // Three weeks of $10 weekdays and idle weekends, starting on a Monday
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut daily_usage = DailyUsageMap::new();
+        for i in 0..21 {
+            let date = monday + Duration::days(i);
+            if date.weekday().num_days_from_monday() < 5 {
+                let usage = TokenUsage {
+                    total_cost: 10.0,
+                    ..Default::default()
+                };
+                daily_usage.insert(date, usage);
+            }
+        }
+
+        let today = monday + Duration::days(21);
+        let report = ProjectionCalculator::new().backtest_history(&daily_usage, 7, today);
+        assert_eq!(report.days, 7);
+        assert_eq!(report.actual_total, 50.0);
+        assert_eq!(
+            report.daily.first().unwrap().date,
+            monday + Duration::days(14)
+        );
+        // Idle weekend days are replayed with an actual of zero
+        let sunday = report.daily.last().unwrap();
+        assert_eq!(sunday.actual, 0.0);
+        assert!(sunday.seasonal.abs() < 1e-9);
+        assert!(report.seasonal.mae < 1e-9);
+        assert_eq!(report.seasonal.mape, Some(0.0));
+        assert!(report.naive.mae > 0.0);
+        assert!(report.naive.bias > 0.0);
+        assert_eq!(report.better, Some(ForecastModel::Seasonal));
+
+        // Days before the first recorded day have nothing to forecast from
+        let report = ProjectionCalculator::new().backtest_history(&daily_usage, 30, today);
+        assert_eq!(report.days, 20);
+    }
+
+    #[test]
+    fn test_backtest_idle_days_are_positive_zero() {
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut daily_usage = DailyUsageMap::new();
+        let usage = TokenUsage {
+            total_cost: 4.0,
+            ..Default::default()
+        };
+        daily_usage.insert(monday, usage);
+
+        // Nothing was spent on any of the replayed days
+        let report = ProjectionCalculator::new().backtest_history(
+            &daily_usage,
+            3,
+            monday + Duration::days(4),
+        );
+        assert_eq!(report.days, 3);
+        for day in &report.daily {
+            assert!(day.actual.is_sign_positive());
+            assert_eq!(format!("{:.2}", day.actual), "0.00");
+        }
+        assert!(report.actual_total.is_sign_positive());
+    }
+
     #[test]
     fn test_period_average_calculation() {
         let calculator = ProjectionCalculator::new();
@@ -483,4 +1386,74 @@ mod tests {
         assert_eq!(weekly_avg, 70.0); // 7 days * 10.0
         assert_eq!(monthly_avg, 300.0); // daily_avg (10.0) * 30 days
     }
+
+    #[test]
+    fn test_cost_anomaly_detection() {
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut daily_usage = DailyUsageMap::new();
+        for i in 0..20 {
+            let usage = TokenUsage {
+                total_cost: if i % 2 == 0 { 1.0 } else { 1.2 },
+                ..Default::default()
+            };
+            daily_usage.insert(start + Duration::days(i), usage);
+        }
+        let spike = start + Duration::days(20);
+        daily_usage.insert(
+            spike,
+            TokenUsage {
+                total_cost: 5.0,
+                ..Default::default()
+            },
+        );
+
+        let anomalies = AnomalyDetector::new(3.0).detect_cost_anomalies(&daily_usage);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].date, spike);
+        assert!((anomalies[0].baseline_mean - 1.1).abs() < 1e-9);
+        assert!(anomalies[0].z_score > 3.0);
+
+        // Too little history to judge
+        let recent: DailyUsageMap = daily_usage
+            .into_iter()
+            .filter(|(date, _)| *date > start + Duration::days(15))
+            .collect();
+        assert!(
+            AnomalyDetector::new(3.0)
+                .detect_cost_anomalies(&recent)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_session_anomaly_detection() {
+        let now = Utc::now();
+        let mut sessions = SessionUsageMap::new();
+        for i in 0..10 {
+            let usage = TokenUsage {
+                input_tokens: 1000 + i * 50,
+                message_count: 10,
+                ..Default::default()
+            };
+            sessions.insert(format!("-p/s{}", i), (usage, now));
+        }
+        let heavy = TokenUsage {
+            input_tokens: 200_000,
+            message_count: 10,
+            ..Default::default()
+        };
+        sessions.insert("-p/heavy".to_string(), (heavy, now));
+        // Too few messages to count, however large
+        let tiny = TokenUsage {
+            input_tokens: 500_000,
+            message_count: 1,
+            ..Default::default()
+        };
+        sessions.insert("-p/tiny".to_string(), (tiny, now));
+
+        let anomalies = AnomalyDetector::new(3.0).detect_session_anomalies(&sessions);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].session, "-p/heavy");
+        assert_eq!(anomalies[0].tokens_per_message, 20_000.0);
+    }
 }
@@ -0,0 +1,296 @@
+//! Moving old session logs out of the Claude directory (`prune` command)
+//!
+//! Claude Code never deletes its session logs, so `~/.claude/projects` keeps
+//! growing. Pruning moves session files not modified within the retention
+//! period into an archive directory, compressed with zstd and under the same
+//! `projects/<project>/` layout, so `claudelytics --path <archive>` still reads
+//! them. The aggregated usage is snapshotted first (see `archive`), so reports
+//! run with `--with-archive` keep the pruned history.
+
+use crate::compression::{self, Compression};
+use crate::date_range;
+use crate::helpers::format_size;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use colored::Colorize;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// zstd level: noticeably smaller than the default for text, still fast
+const ZSTD_LEVEL: i32 = 9;
+
+/// A session file older than the retention period
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneCandidate {
+    pub path: PathBuf,
+    /// Path under `projects/`, kept in the archive
+    pub relative: PathBuf,
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+}
+
+/// What a prune moved, or would move with `--dry-run`
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneSummary {
+    pub dry_run: bool,
+    pub cutoff: DateTime<Utc>,
+    pub archive_dir: PathBuf,
+    pub files: usize,
+    /// Space freed in the Claude directories
+    pub bytes_reclaimed: u64,
+    /// Space the compressed copies take in the archive (0 for a dry run)
+    pub archived_bytes: u64,
+    /// Files left in place because a copy already exists in the archive
+    pub skipped: Vec<PathBuf>,
+    /// Rows written by the snapshot taken before pruning
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_rows: Option<usize>,
+}
+
+/// The time a retention period such as `180d` or `26w` (a span as taken by
+/// `--last`) before `now`
+pub fn cutoff(age: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let days = date_range::span_days(age)?;
+    Duration::try_days(days)
+        .and_then(|age| now.checked_sub_signed(age))
+        .ok_or_else(|| anyhow::anyhow!("Age '{}' is out of range", age))
+}
+
+/// Session files under each directory's `projects/` last modified before `cutoff`,
+/// oldest first
+pub fn find_candidates(
+    claude_dirs: &[PathBuf],
+    cutoff: DateTime<Utc>,
+) -> Result<Vec<PruneCandidate>> {
+    let mut candidates = Vec::new();
+    for claude_dir in claude_dirs {
+        let projects = claude_dir.join("projects");
+        if !projects.is_dir() {
+            continue;
+        }
+        for entry in WalkDir::new(&projects).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !entry.file_type().is_file() || !compression::is_session_file(path) {
+                continue;
+            }
+            let metadata = entry
+                .metadata()
+                .with_context(|| format!("Failed to read metadata: {}", path.display()))?;
+            let modified: DateTime<Utc> = metadata.modified()?.into();
+            if modified >= cutoff {
+                continue;
+            }
+            candidates.push(PruneCandidate {
+                path: path.to_path_buf(),
+                relative: path.strip_prefix(&projects)?.to_path_buf(),
+                size: metadata.len(),
+                modified,
+            });
+        }
+    }
+    candidates.sort_by_key(|candidate| candidate.modified);
+    Ok(candidates)
+}
+
+/// Where a candidate goes in the archive: plain files gain a `.zst` suffix,
+/// compressed files keep their name
+fn archive_path(archive_dir: &Path, candidate: &PruneCandidate) -> PathBuf {
+    let target = archive_dir.join("projects").join(&candidate.relative);
+    match Compression::of(&target) {
+        Some(Compression::None) => {
+            let mut name = target.file_name().unwrap_or_default().to_os_string();
+            name.push(".zst");
+            target.with_file_name(name)
+        }
+        _ => target,
+    }
+}
+
+/// Move the candidates into `archive_dir`, or only total them up with `dry_run`
+pub fn prune(
+    candidates: &[PruneCandidate],
+    archive_dir: &Path,
+    cutoff: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<PruneSummary> {
+    let mut summary = PruneSummary {
+        dry_run,
+        cutoff,
+        archive_dir: archive_dir.to_path_buf(),
+        files: 0,
+        bytes_reclaimed: 0,
+        archived_bytes: 0,
+        skipped: Vec::new(),
+        snapshot_rows: None,
+    };
+    for candidate in candidates {
+        let target = archive_path(archive_dir, candidate);
+        if target.exists() {
+            summary.skipped.push(candidate.path.clone());
+            continue;
+        }
+        if !dry_run {
+            summary.archived_bytes += archive_file(&candidate.path, &target)?;
+            fs::remove_file(&candidate.path)
+                .with_context(|| format!("Failed to remove {}", candidate.path.display()))?;
+        }
+        summary.files += 1;
+        summary.bytes_reclaimed += candidate.size;
+    }
+    Ok(summary)
+}
+
+/// Write `source` to `target` (compressing plain files) and return the archived size
+///
+/// The copy is written next to the target and renamed into place, so an
+/// interrupted prune never leaves a truncated file that looks complete.
+fn archive_file(source: &Path, target: &Path) -> Result<u64> {
+    let parent = target.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    let mut partial = target.as_os_str().to_os_string();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+
+    let mut reader = BufReader::new(
+        File::open(source).with_context(|| format!("Failed to open {}", source.display()))?,
+    );
+    let mut writer = BufWriter::new(
+        File::create(&partial)
+            .with_context(|| format!("Failed to create {}", partial.display()))?,
+    );
+    if Compression::of(source) == Some(Compression::None) {
+        let size = fs::metadata(source)?.len();
+        let mut encoder = zstd::stream::write::Encoder::new(&mut writer, ZSTD_LEVEL)?;
+        // Record the uncompressed size in the frame header, as the `zstd` tool does
+        encoder.set_pledged_src_size(Some(size))?;
+        io::copy(&mut reader, &mut encoder)?;
+        encoder.finish()?;
+    } else {
+        io::copy(&mut reader, &mut writer)?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    fs::rename(&partial, target)
+        .with_context(|| format!("Failed to move archive into {}", target.display()))?;
+    Ok(fs::metadata(target)?.len())
+}
+
+pub fn display_prune_summary(summary: &PruneSummary, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(summary) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    let cutoff = summary
+        .cutoff
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d");
+    if summary.files == 0 && summary.skipped.is_empty() {
+        println!("No session files last modified before {}", cutoff);
+        return;
+    }
+    if summary.dry_run {
+        println!(
+            "🧹 Would archive {} session files last modified before {}, reclaiming {}",
+            summary.files.to_string().yellow(),
+            cutoff,
+            format_size(summary.bytes_reclaimed).green()
+        );
+        println!("   Archive: {}", summary.archive_dir.display());
+        println!("   Run without --dry-run to move them");
+    } else {
+        println!(
+            "🧹 Archived {} session files last modified before {}",
+            summary.files.to_string().green(),
+            cutoff
+        );
+        println!(
+            "   Reclaimed {} ({} compressed in {})",
+            format_size(summary.bytes_reclaimed).green(),
+            format_size(summary.archived_bytes),
+            summary.archive_dir.display()
+        );
+        if let Some(rows) = summary.snapshot_rows {
+            println!(
+                "   Snapshot: {} new or changed rows; use --with-archive to keep them in reports",
+                rows
+            );
+        }
+    }
+    if !summary.skipped.is_empty() {
+        println!(
+            "   {} files left in place because the archive already has a copy",
+            summary.skipped.len().to_string().yellow()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_cutoff() {
+        let now = Utc::now();
+        assert_eq!(cutoff("180d", now).unwrap(), now - Duration::days(180));
+        assert_eq!(cutoff("2w", now).unwrap(), now - Duration::days(14));
+        assert_eq!(cutoff("30 days", now).unwrap(), now - Duration::days(30));
+        assert_eq!(cutoff("90", now).unwrap(), now - Duration::days(90));
+        assert!(cutoff("0d", now).is_err());
+        assert!(cutoff("6m", now).is_err());
+        assert!(cutoff("1y", now).is_err());
+        assert!(cutoff("100000000000d", now).is_err());
+        assert!(cutoff("9223372036854775807w", now).is_err());
+    }
+
+    #[test]
+    fn test_prune_moves_old_files() {
+        let claude = tempdir().unwrap();
+        let archive = tempdir().unwrap();
+        let project = claude.path().join("projects/-home-me-app");
+        fs::create_dir_all(&project).unwrap();
+        let content = "{\"type\":\"assistant\"}\n".repeat(100);
+        fs::write(project.join("old.jsonl"), &content).unwrap();
+        fs::write(project.join("notes.txt"), "not a session").unwrap();
+
+        let dirs = [claude.path().to_path_buf()];
+        let past = Utc::now() - Duration::days(1);
+        assert!(find_candidates(&dirs, past).unwrap().is_empty());
+
+        let cutoff = Utc::now() + Duration::days(1);
+        let candidates = find_candidates(&dirs, cutoff).unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].relative, Path::new("-home-me-app/old.jsonl"));
+
+        let dry = prune(&candidates, archive.path(), cutoff, true).unwrap();
+        assert_eq!(dry.files, 1);
+        assert_eq!(dry.bytes_reclaimed, content.len() as u64);
+        assert!(project.join("old.jsonl").exists());
+
+        let summary = prune(&candidates, archive.path(), cutoff, false).unwrap();
+        assert_eq!(summary.files, 1);
+        assert!(summary.archived_bytes < summary.bytes_reclaimed);
+        assert!(!project.join("old.jsonl").exists());
+        let archived = archive.path().join("projects/-home-me-app/old.jsonl.zst");
+        let mut restored = String::new();
+        compression::open_session_file(&archived)
+            .unwrap()
+            .read_to_string(&mut restored)
+            .unwrap();
+        assert_eq!(restored, content);
+        assert_eq!(
+            compression::uncompressed_size(&archived),
+            Some(content.len() as u64)
+        );
+    }
+}
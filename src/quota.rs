@@ -0,0 +1,542 @@
+//! Subscription quota tracking (`quota` command)
+//!
+//! Claude Pro/Max plans limit usage per rolling 5-hour block. Anthropic does not
+//! publish exact numbers, so the built-in allowances are community estimates and
+//! can be overridden in the `quota` config section.
+
+use crate::billing_blocks::{BillingBlock, BillingBlockManager};
+use crate::currency::{Currency, Money};
+use chrono::{DateTime, Duration, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+/// Claude subscription plan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Plan {
+    #[default]
+    Pro,
+    /// Max 5x
+    Max5,
+    /// Max 20x
+    Max20,
+}
+
+impl Plan {
+    pub fn name(self) -> &'static str {
+        match self {
+            Plan::Pro => "Pro",
+            Plan::Max5 => "Max 5x",
+            Plan::Max20 => "Max 20x",
+        }
+    }
+
+    /// Estimated allowance per 5-hour block
+    pub fn limits(self) -> QuotaLimits {
+        match self {
+            Plan::Pro => QuotaLimits {
+                tokens: 19_000,
+                messages: 250,
+                cost: 18.0,
+            },
+            Plan::Max5 => QuotaLimits {
+                tokens: 88_000,
+                messages: 1_000,
+                cost: 35.0,
+            },
+            Plan::Max20 => QuotaLimits {
+                tokens: 220_000,
+                messages: 2_000,
+                cost: 140.0,
+            },
+        }
+    }
+}
+
+/// Allowance per block. Tokens count input + output only, since cache reads are
+/// not charged against subscription limits the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct QuotaLimits {
+    pub tokens: u64,
+    pub messages: u64,
+    pub cost: f64,
+}
+
+impl QuotaLimits {
+    /// The same limits with the cost allowance, which is in USD, converted to
+    /// `currency` so it can be compared with converted costs
+    pub fn in_currency(self, currency: &Currency) -> Self {
+        Self {
+            cost: self.cost * currency.rate,
+            ..self
+        }
+    }
+}
+
+/// Usage of one block relative to the plan allowance
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockQuotaUsage {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub tokens: u64,
+    pub messages: usize,
+    pub cost: f64,
+    pub tokens_pct: f64,
+    pub messages_pct: f64,
+    pub cost_pct: f64,
+}
+
+impl BlockQuotaUsage {
+    fn new(block: &BillingBlock, limits: &QuotaLimits) -> Self {
+        let tokens = block.usage.input_tokens + block.usage.output_tokens;
+        Self {
+            start_time: block.start_time,
+            end_time: block.end_time,
+            tokens,
+            messages: block.message_count,
+            cost: block.usage.total_cost,
+            tokens_pct: percent(tokens as f64, limits.tokens as f64),
+            messages_pct: percent(block.message_count as f64, limits.messages as f64),
+            cost_pct: percent(block.usage.total_cost, limits.cost),
+        }
+    }
+
+    /// The most consumed share of the allowance; the block is exhausted at 100%
+    pub fn quota_pct(&self) -> f64 {
+        self.tokens_pct.max(self.messages_pct).max(self.cost_pct)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaReport {
+    pub plan: String,
+    pub limits: QuotaLimits,
+    /// Block containing the current time, if any activity happened in the last block
+    pub current: Option<BlockQuotaUsage>,
+    /// Minutes until the current block resets
+    pub minutes_until_reset: Option<i64>,
+    /// Past blocks, most recent first
+    pub history: Vec<BlockQuotaUsage>,
+}
+
+/// Build a quota report from rolling billing blocks
+pub fn build_quota_report(
+    manager: &BillingBlockManager,
+    plan: Plan,
+    limits: QuotaLimits,
+    history: usize,
+    now: DateTime<Utc>,
+) -> QuotaReport {
+    let mut blocks: Vec<BlockQuotaUsage> = manager
+        .get_blocks_with_usage()
+        .into_iter()
+        .map(|(_, block)| BlockQuotaUsage::new(block, &limits))
+        .collect();
+    blocks.reverse();
+
+    let current = blocks
+        .first()
+        .filter(|block| block.start_time <= now && now < block.end_time)
+        .cloned();
+    let minutes_until_reset = current
+        .as_ref()
+        .map(|block| (block.end_time - now).num_minutes());
+
+    let skip = usize::from(current.is_some());
+    blocks = blocks.into_iter().skip(skip).take(history).collect();
+
+    QuotaReport {
+        plan: plan.name().to_string(),
+        limits,
+        current,
+        minutes_until_reset,
+        history: blocks,
+    }
+}
+
+/// How fast the in-progress block is using up the allowance
+/// (`billing-blocks --current` and the TUI Billing tab)
+#[derive(Debug, Clone, Serialize)]
+pub struct BurnDown {
+    pub plan: String,
+    pub limits: QuotaLimits,
+    pub block: BlockQuotaUsage,
+    pub minutes_elapsed: i64,
+    pub minutes_until_reset: i64,
+    pub tokens_per_hour: f64,
+    pub messages_per_hour: f64,
+    pub cost_per_hour: f64,
+    /// When the most consumed allowance runs out at the current rate; `None`
+    /// when the block resets first
+    pub projected_exhaustion: Option<DateTime<Utc>>,
+}
+
+impl BurnDown {
+    pub fn is_exhausted(&self) -> bool {
+        self.block.quota_pct() >= 100.0
+    }
+}
+
+/// Burn-down of the block containing `now`, if it has any usage
+pub fn build_burn_down(
+    manager: &BillingBlockManager,
+    plan: Plan,
+    limits: QuotaLimits,
+    now: DateTime<Utc>,
+) -> Option<BurnDown> {
+    let block = manager
+        .get_blocks_with_usage()
+        .into_iter()
+        .map(|(_, block)| block)
+        .find(|block| block.contains(now))?;
+    let usage = BlockQuotaUsage::new(block, &limits);
+
+    // At least a minute, so the first request does not project an absurd rate
+    let minutes_elapsed = (now - block.start_time).num_minutes().max(1);
+    let hours = minutes_elapsed as f64 / 60.0;
+    let pct = usage.quota_pct();
+    let projected_exhaustion = if pct >= 100.0 {
+        Some(now)
+    } else if pct > 0.0 {
+        let hours_left = (100.0 - pct) / (pct / hours);
+        let exhaustion = now + Duration::seconds((hours_left * 3600.0) as i64);
+        (exhaustion < block.end_time).then_some(exhaustion)
+    } else {
+        None
+    };
+
+    Some(BurnDown {
+        plan: plan.name().to_string(),
+        limits,
+        minutes_elapsed,
+        minutes_until_reset: (block.end_time - now).num_minutes(),
+        tokens_per_hour: usage.tokens as f64 / hours,
+        messages_per_hour: usage.messages as f64 / hours,
+        cost_per_hour: usage.cost / hours,
+        projected_exhaustion,
+        block: usage,
+    })
+}
+
+/// Print the burn-down of the current block, or a note that there is none
+pub fn display_burn_down(burn_down: Option<&BurnDown>, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(&burn_down) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    let Some(burn_down) = burn_down else {
+        println!(
+            "\n  {}\n",
+            "No usage in the current block — your next message starts a fresh window".green()
+        );
+        return;
+    };
+    let block = &burn_down.block;
+    let limits = &burn_down.limits;
+
+    println!(
+        "\n{}",
+        format!(
+            "CURRENT BLOCK — {} (Claude {} plan)",
+            block_range(block),
+            burn_down.plan
+        )
+        .bold()
+    );
+    println!("{}", "═".repeat(60));
+    print_meter("Quota", block.quota_pct(), "most used allowance");
+    print_meter(
+        "Tokens",
+        block.tokens_pct,
+        &format!("{} / {}", block.tokens, limits.tokens),
+    );
+    print_meter(
+        "Messages",
+        block.messages_pct,
+        &format!("{} / {}", block.messages, limits.messages),
+    );
+    print_meter(
+        "Cost",
+        block.cost_pct,
+        &format!("{:.2} / {:.2}", Money(block.cost), Money(limits.cost)),
+    );
+
+    println!(
+        "\n  {} {:.0} tokens/h · {:.1} messages/h · {:.2}/h",
+        "Burn rate:".cyan(),
+        burn_down.tokens_per_hour,
+        burn_down.messages_per_hour,
+        Money(burn_down.cost_per_hour)
+    );
+    let exhaustion = match burn_down.projected_exhaustion {
+        _ if burn_down.is_exhausted() => "quota used up".red().to_string(),
+        Some(time) => format!(
+            "at {} ({} from now)",
+            time.with_timezone(&chrono::Local).format("%H:%M"),
+            format_minutes((time - Utc::now()).num_minutes().max(0))
+        )
+        .yellow()
+        .to_string(),
+        None => "not before the reset at this rate".green().to_string(),
+    };
+    println!("  {} {}", "Exhaustion:".cyan(), exhaustion);
+    println!(
+        "  {} {}",
+        "Resets in:".cyan(),
+        format_minutes(burn_down.minutes_until_reset)
+    );
+    println!();
+}
+
+fn format_minutes(minutes: i64) -> String {
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+fn percent(value: f64, limit: f64) -> f64 {
+    if limit > 0.0 {
+        value / limit * 100.0
+    } else {
+        0.0
+    }
+}
+
+pub fn display_quota_report(report: &QuotaReport, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!(
+        "\n{}",
+        format!("QUOTA — Claude {} plan (per 5-hour block)", report.plan).bold()
+    );
+    println!("{}", "═".repeat(60));
+    println!(
+        "  {}",
+        format!(
+            "Allowance: {} tokens · {} messages · {:.2} (estimated)",
+            report.limits.tokens,
+            report.limits.messages,
+            Money(report.limits.cost)
+        )
+        .dimmed()
+    );
+
+    println!("\n{}", "Current Block".bold());
+    println!("{}", "─".repeat(60));
+    match &report.current {
+        Some(block) => {
+            println!(
+                "  {}  {}",
+                block_range(block),
+                format!("{:.0}% of quota", block.quota_pct()).bold()
+            );
+            print_meter(
+                "Tokens",
+                block.tokens_pct,
+                &format!("{} / {}", block.tokens, report.limits.tokens),
+            );
+            print_meter(
+                "Messages",
+                block.messages_pct,
+                &format!("{} / {}", block.messages, report.limits.messages),
+            );
+            print_meter(
+                "Cost",
+                block.cost_pct,
+                &format!(
+                    "{:.2} / {:.2}",
+                    Money(block.cost),
+                    Money(report.limits.cost)
+                ),
+            );
+            if let Some(minutes) = report.minutes_until_reset {
+                println!(
+                    "\n  {} {}h {:02}m",
+                    "Resets in:".cyan(),
+                    minutes / 60,
+                    minutes % 60
+                );
+            }
+        }
+        None => println!(
+            "  {}",
+            "No active block — your next message starts a fresh 5-hour window".green()
+        ),
+    }
+
+    if !report.history.is_empty() {
+        println!("\n{}", "Previous Blocks".bold());
+        println!("{}", "─".repeat(60));
+        println!(
+            "  {:<24} {:>9} {:>8} {:>9} {:>7}",
+            "Block (local)", "Tokens", "Msgs", "Cost", "Quota"
+        );
+        for block in &report.history {
+            let pct = format!("{:>6.0}%", block.quota_pct());
+            println!(
+                "  {:<24} {:>9} {:>8} {:>9} {}",
+                block_range(block),
+                block.tokens,
+                block.messages,
+                format!("{:.2}", Money(block.cost)),
+                colorize_pct(&pct, block.quota_pct())
+            );
+        }
+    }
+    println!();
+}
+
+fn block_range(block: &BlockQuotaUsage) -> String {
+    let start = block.start_time.with_timezone(&chrono::Local);
+    let end = block.end_time.with_timezone(&chrono::Local);
+    format!("{}-{}", start.format("%m-%d %H:%M"), end.format("%H:%M"))
+}
+
+fn print_meter(label: &str, pct: f64, detail: &str) {
+    const WIDTH: usize = 30;
+    let filled = ((pct / 100.0) * WIDTH as f64)
+        .round()
+        .clamp(0.0, WIDTH as f64) as usize;
+    let bar = format!("{}{}", "█".repeat(filled), "░".repeat(WIDTH - filled));
+    println!(
+        "  {:<9} {} {:>5.1}%  {}",
+        label,
+        colorize_pct(&bar, pct),
+        pct,
+        detail.dimmed()
+    );
+}
+
+fn colorize_pct(text: &str, pct: f64) -> String {
+    if pct >= 90.0 {
+        text.red().to_string()
+    } else if pct >= 70.0 {
+        text.yellow().to_string()
+    } else {
+        text.green().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::billing_blocks::{BillingBlockConfig, BlockAnchor};
+    use crate::models::TokenUsage;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_quota_report_current_and_history() {
+        let mut manager = BillingBlockManager::with_config(BillingBlockConfig {
+            anchor: BlockAnchor::Rolling,
+            duration_hours: 5,
+        });
+        let usage = TokenUsage {
+            input_tokens: 4_000,
+            output_tokens: 5_500,
+            total_cost: 1.0,
+            ..Default::default()
+        };
+        for hour in [1, 2, 10] {
+            let time = Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap();
+            manager.add_usage(time, &usage, Some("session"));
+        }
+        manager.finalize();
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 30, 0).unwrap();
+        let report = build_quota_report(&manager, Plan::Pro, Plan::Pro.limits(), 10, now);
+
+        let current = report.current.as_ref().unwrap();
+        assert_eq!(current.tokens, 9_500);
+        assert_eq!(current.tokens_pct, 50.0);
+        assert_eq!(report.minutes_until_reset, Some(150));
+        assert_eq!(report.history.len(), 1);
+        assert_eq!(report.history[0].tokens_pct, 100.0);
+        assert_eq!(report.history[0].quota_pct(), 100.0);
+
+        let later = Utc.with_ymd_and_hms(2024, 1, 1, 16, 0, 0).unwrap();
+        let report = build_quota_report(&manager, Plan::Pro, Plan::Pro.limits(), 10, later);
+        assert!(report.current.is_none());
+        assert_eq!(report.history.len(), 2);
+    }
+
+    #[test]
+    fn test_burn_down_projects_exhaustion() {
+        let mut manager = BillingBlockManager::with_config(BillingBlockConfig {
+            anchor: BlockAnchor::Rolling,
+            duration_hours: 5,
+        });
+        let usage = TokenUsage {
+            input_tokens: 1_000,
+            output_tokens: 900,
+            total_cost: 0.5,
+            ..Default::default()
+        };
+        manager.add_usage(
+            Utc.with_ymd_and_hms(2024, 1, 1, 10, 5, 0).unwrap(),
+            &usage,
+            Some("session"),
+        );
+        manager.finalize();
+
+        // 10% of the Pro token allowance in the first hour
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 11, 0, 0).unwrap();
+        let burn_down = build_burn_down(&manager, Plan::Pro, Plan::Pro.limits(), now).unwrap();
+        assert_eq!(burn_down.minutes_elapsed, 60);
+        assert_eq!(burn_down.minutes_until_reset, 240);
+        assert_eq!(burn_down.tokens_per_hour, 1_900.0);
+        // The remaining 90% would take 9 more hours, after the reset
+        assert_eq!(burn_down.projected_exhaustion, None);
+
+        let limits = QuotaLimits {
+            tokens: 3_800,
+            ..Plan::Pro.limits()
+        };
+        let burn_down = build_burn_down(&manager, Plan::Pro, limits, now).unwrap();
+        assert_eq!(
+            burn_down.projected_exhaustion,
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap())
+        );
+        assert!(!burn_down.is_exhausted());
+
+        let later = Utc.with_ymd_and_hms(2024, 1, 1, 16, 0, 0).unwrap();
+        assert!(build_burn_down(&manager, Plan::Pro, limits, later).is_none());
+    }
+
+    #[test]
+    fn test_cost_share_is_the_same_in_any_currency() {
+        let mut manager = BillingBlockManager::with_config(BillingBlockConfig {
+            anchor: BlockAnchor::Rolling,
+            duration_hours: 5,
+        });
+        let usage = TokenUsage {
+            input_tokens: 10,
+            total_cost: 9.0,
+            ..Default::default()
+        };
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        manager.add_usage(start, &usage, Some("session"));
+        manager.finalize();
+        let now = start + Duration::hours(1);
+        let usd = build_burn_down(&manager, Plan::Pro, Plan::Pro.limits(), now).unwrap();
+        assert_eq!(usd.block.cost_pct, 50.0);
+
+        let yen = Currency {
+            code: "JPY".to_string(),
+            rate: 150.0,
+        };
+        manager.scale_costs(yen.rate);
+        let limits = Plan::Pro.limits().in_currency(&yen);
+        assert_eq!(limits.cost, 2_700.0);
+        let converted = build_burn_down(&manager, Plan::Pro, limits, now).unwrap();
+        assert_eq!(converted.block.cost_pct, 50.0);
+        assert_eq!(converted.block.cost, 1_350.0);
+    }
+}
@@ -1,10 +1,15 @@
-use crate::burn_rate::{BurnRateCalculator, BurnRateMetrics};
+use crate::alerts::AlertEvent;
+use crate::burn_rate::{
+    BurnRateCalculator, BurnRateMetrics, ProjectBurnRate, UsageEvent, project_burn_rates,
+};
+use crate::config_v2;
+use crate::currency::{self, Money};
 use crate::models::{DailyUsageMap, SessionUsageMap};
-use crate::projections::TrendDirection;
+use crate::projections::{BillingCycle, CycleForecast, TrendDirection};
 use crate::session_analytics::{SessionAnalytics, format_duration};
 use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike, Utc};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Real-time analytics module for comprehensive usage analysis
 /// Provides burn rate calculations, budget projections, and session analytics
@@ -12,6 +17,8 @@ pub struct RealtimeAnalytics<'a> {
     daily_usage: &'a DailyUsageMap,
     session_usage: &'a SessionUsageMap,
     budget_config: BudgetConfig,
+    /// Records of the last day, for the per-project burn rates
+    usage_events: &'a [UsageEvent],
 }
 
 /// Budget configuration for projections and alerts
@@ -21,6 +28,9 @@ pub struct BudgetConfig {
     pub monthly_limit: Option<f64>,
     pub yearly_limit: Option<f64>,
     pub alert_threshold: f64, // Percentage (0.0-1.0) of budget to trigger alert
+    pub burn_rate_threshold: f64, // Cost per hour (USD) considered a high burn rate
+    pub billing_cycle_start_day: u32, // Day of the month the monthly limit resets
+    pub max_concurrent_sessions: usize, // More sessions consuming tokens at once are flagged
 }
 
 impl Default for BudgetConfig {
@@ -29,7 +39,28 @@ impl Default for BudgetConfig {
             daily_limit: None,
             monthly_limit: None,
             yearly_limit: None,
-            alert_threshold: 0.8, // Alert at 80% of budget
+            alert_threshold: 0.8,      // Alert at 80% of budget
+            burn_rate_threshold: 10.0, // Alert above $10/hour
+            billing_cycle_start_day: 1,
+            max_concurrent_sessions: 3,
+        }
+    }
+}
+
+impl BudgetConfig {
+    /// Build from the saved `budget` and `alerts` config sections
+    pub fn from_settings(
+        budget: &config_v2::BudgetConfig,
+        alerts: &config_v2::AlertsConfig,
+    ) -> Self {
+        Self {
+            daily_limit: budget.daily_limit,
+            monthly_limit: budget.monthly_limit,
+            yearly_limit: budget.yearly_limit,
+            alert_threshold: budget.alert_threshold,
+            burn_rate_threshold: alerts.burn_rate_threshold,
+            billing_cycle_start_day: budget.billing_cycle_start_day,
+            max_concurrent_sessions: alerts.max_concurrent_sessions,
         }
     }
 }
@@ -38,6 +69,9 @@ impl Default for BudgetConfig {
 #[derive(Debug, Clone, Serialize)]
 pub struct RealtimeAnalyticsReport {
     pub burn_rates: BurnRateAnalysis,
+    /// Projects with usage in the last 24 hours, most active right now first
+    pub project_burn_rates: Vec<ProjectBurnRate>,
+    pub concurrency: ConcurrencyAnalysis,
     pub budget_projections: BudgetProjections,
     pub session_metrics: SessionMetrics,
     pub alerts: Vec<UsageAlert>,
@@ -63,6 +97,52 @@ pub struct PeakBurnRate {
     pub occurred_at: DateTime<Utc>,
 }
 
+/// Sessions consuming tokens at the same time over the last 24 hours. A session
+/// counts as active in an interval when it has a usage record in it.
+#[derive(Debug, Serialize, Clone)]
+pub struct ConcurrencyAnalysis {
+    pub interval_minutes: i64,
+    /// Sessions active in the latest interval
+    pub current: usize,
+    pub peak: usize,
+    pub peak_at: Option<DateTime<Utc>>,
+    /// Average over the intervals with any activity
+    pub average: f64,
+    /// Sessions above this count are flagged
+    pub threshold: usize,
+    /// One sample per interval, oldest first
+    pub timeline: Vec<ConcurrencySample>,
+    /// Projects that had several sessions active at once, highest peak first
+    pub by_project: Vec<ProjectConcurrency>,
+    /// Runs of consecutive intervals above the threshold
+    pub periods: Vec<ConcurrencyPeriod>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConcurrencySample {
+    pub start: DateTime<Utc>,
+    pub sessions: usize,
+    pub cost: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectConcurrency {
+    pub project: String,
+    pub peak: usize,
+    pub peak_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConcurrencyPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub peak_sessions: usize,
+    pub cost: f64,
+}
+
+/// Length of the intervals concurrency is sampled in
+const CONCURRENCY_INTERVAL_MINUTES: i64 = 10;
+
 /// Budget projection with time estimates
 #[derive(Debug, Serialize, Clone)]
 pub struct BudgetProjections {
@@ -70,6 +150,7 @@ pub struct BudgetProjections {
     pub monthly_projection: BudgetProjection,
     pub yearly_projection: BudgetProjection,
     pub time_to_limits: TimeToLimits,
+    pub billing_cycle: CycleForecast,
 }
 
 /// Individual budget projection
@@ -140,6 +221,7 @@ pub enum AlertType {
     HighBurnRate,
     IneffientUsage,
     ProjectionWarning,
+    ConcurrentSessions,
 }
 
 /// Alert severity levels
@@ -150,6 +232,75 @@ pub enum AlertSeverity {
     Critical,
 }
 
+impl RealtimeAnalyticsReport {
+    /// Warning and critical alerts as events for the alert hooks
+    pub fn alert_events(&self) -> Vec<AlertEvent> {
+        let daily = &self.budget_projections.daily_projection;
+        let monthly = &self.budget_projections.monthly_projection;
+        let current_hour = &self.burn_rates.current_hour;
+
+        self.alerts
+            .iter()
+            .filter(|alert| alert.severity != AlertSeverity::Info)
+            .map(|alert| {
+                let (kind, cost, tokens, limit) = match alert.alert_type {
+                    AlertType::BudgetThreshold => (
+                        "budget_threshold",
+                        daily.estimated_cost,
+                        current_hour.projected_daily_tokens,
+                        daily.budget_limit,
+                    ),
+                    AlertType::ProjectionWarning => (
+                        "projection_warning",
+                        monthly.estimated_cost,
+                        current_hour.projected_monthly_tokens,
+                        monthly.budget_limit,
+                    ),
+                    AlertType::HighBurnRate => (
+                        "high_burn_rate",
+                        current_hour.cost_per_hour,
+                        current_hour.tokens_per_hour as u64,
+                        None,
+                    ),
+                    AlertType::UnusualSpike => (
+                        "unusual_spike",
+                        current_hour.cost_per_hour,
+                        current_hour.tokens_per_hour as u64,
+                        None,
+                    ),
+                    AlertType::IneffientUsage => (
+                        "inefficient_usage",
+                        current_hour.cost_per_hour,
+                        current_hour.tokens_per_hour as u64,
+                        None,
+                    ),
+                    AlertType::ConcurrentSessions => (
+                        "concurrent_sessions",
+                        current_hour.cost_per_hour,
+                        current_hour.tokens_per_hour as u64,
+                        Some(self.concurrency.threshold as f64),
+                    ),
+                };
+                AlertEvent {
+                    kind: kind.to_string(),
+                    severity: match alert.severity {
+                        AlertSeverity::Info => "info",
+                        AlertSeverity::Warning => "warning",
+                        AlertSeverity::Critical => "critical",
+                    }
+                    .to_string(),
+                    message: alert.message.clone(),
+                    cost,
+                    currency: currency::current().code.clone(),
+                    tokens,
+                    limit,
+                    timestamp: alert.timestamp,
+                }
+            })
+            .collect()
+    }
+}
+
 impl<'a> RealtimeAnalytics<'a> {
     /// Create new real-time analytics instance
     pub fn new(
@@ -161,19 +312,41 @@ impl<'a> RealtimeAnalytics<'a> {
             daily_usage,
             session_usage,
             budget_config,
+            usage_events: &[],
         }
     }
 
+    /// Records with timestamps and sessions, needed for the per-project burn rates
+    pub fn with_usage_events(mut self, usage_events: &'a [UsageEvent]) -> Self {
+        self.usage_events = usage_events;
+        self
+    }
+
     /// Generate comprehensive real-time analytics report
     pub fn generate_report(&self) -> RealtimeAnalyticsReport {
         let burn_rates = self.calculate_burn_rates();
+        let now = Utc::now();
+        let project_burn_rates = project_burn_rates(self.usage_events, now);
+        let concurrency = concurrency_analysis(
+            self.usage_events,
+            now,
+            self.budget_config.max_concurrent_sessions,
+        );
         let budget_projections = self.calculate_budget_projections(&burn_rates);
         let session_metrics = self.calculate_session_metrics();
         let efficiency_trends = self.calculate_efficiency_trends();
-        let alerts = self.generate_alerts(&burn_rates, &budget_projections, &efficiency_trends);
+        let alerts = self.generate_alerts(
+            &burn_rates,
+            &project_burn_rates,
+            &concurrency,
+            &budget_projections,
+            &efficiency_trends,
+        );
 
         RealtimeAnalyticsReport {
             burn_rates,
+            project_burn_rates,
+            concurrency,
             budget_projections,
             session_metrics,
             alerts,
@@ -224,9 +397,19 @@ impl<'a> RealtimeAnalytics<'a> {
             self.budget_config.daily_limit,
         );
 
-        // Monthly projection
+        // Monthly projection: spend so far in the billing cycle plus the
+        // current daily rate for the rest of it
+        let today = Local::now().date_naive();
+        let cycle = BillingCycle::containing(today, self.budget_config.billing_cycle_start_day);
+        let billing_cycle = CycleForecast::new(
+            cycle,
+            today,
+            cycle.spent_to_date(self.daily_usage, today),
+            current_rate.projected_daily_cost,
+            self.budget_config.monthly_limit,
+        );
         let monthly_projection = self.calculate_budget_projection(
-            current_rate.projected_monthly_cost,
+            billing_cycle.projected_total,
             self.budget_config.monthly_limit,
         );
 
@@ -243,6 +426,7 @@ impl<'a> RealtimeAnalytics<'a> {
             monthly_projection,
             yearly_projection,
             time_to_limits,
+            billing_cycle,
         }
     }
 
@@ -306,13 +490,9 @@ impl<'a> RealtimeAnalytics<'a> {
 
         // Calculate days to monthly limit
         let days_to_monthly_limit = if let Some(monthly_limit) = self.budget_config.monthly_limit {
-            let month_start = NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap();
-            let month_usage: f64 = self
-                .daily_usage
-                .iter()
-                .filter(|(date, _)| **date >= month_start && **date <= today)
-                .map(|(_, usage)| usage.total_cost)
-                .sum();
+            let month_usage =
+                BillingCycle::containing(today, self.budget_config.billing_cycle_start_day)
+                    .spent_to_date(self.daily_usage, today);
 
             if month_usage >= monthly_limit {
                 Some(0.0)
@@ -419,7 +599,7 @@ impl<'a> RealtimeAnalytics<'a> {
             .iter()
             .map(|(hour, metrics)| (*hour, metrics.usage.total_tokens()))
             .collect();
-        peak_hours.sort_by(|a, b| b.1.cmp(&a.1));
+        peak_hours.sort_by_key(|(_, tokens)| std::cmp::Reverse(*tokens));
         let peak_usage_hours: Vec<u32> = peak_hours.iter().take(3).map(|(h, _)| *h).collect();
 
         // Calculate efficiency score (tokens per dollar normalized)
@@ -482,6 +662,8 @@ impl<'a> RealtimeAnalytics<'a> {
     fn generate_alerts(
         &self,
         burn_rates: &BurnRateAnalysis,
+        project_burn_rates: &[ProjectBurnRate],
+        concurrency: &ConcurrencyAnalysis,
         budget_projections: &BudgetProjections,
         efficiency_trends: &EfficiencyTrends,
     ) -> Vec<UsageAlert> {
@@ -500,10 +682,10 @@ impl<'a> RealtimeAnalytics<'a> {
                         AlertSeverity::Warning
                     },
                     message: format!(
-                        "Daily budget utilization at {:.1}% (${:.2} of ${:.2})",
+                        "Daily budget utilization at {:.1}% ({:.2} of {:.2})",
                         utilization * 100.0,
-                        budget_projections.daily_projection.estimated_cost,
-                        daily_limit
+                        Money(budget_projections.daily_projection.estimated_cost),
+                        Money(daily_limit)
                     ),
                     timestamp: now,
                     recommended_action: Some(
@@ -514,13 +696,13 @@ impl<'a> RealtimeAnalytics<'a> {
         }
 
         // High burn rate alert
-        if burn_rates.current_hour.cost_per_hour > 10.0 {
+        if burn_rates.current_hour.cost_per_hour > self.budget_config.burn_rate_threshold {
             alerts.push(UsageAlert {
                 alert_type: AlertType::HighBurnRate,
                 severity: AlertSeverity::Warning,
                 message: format!(
-                    "High burn rate detected: ${:.2}/hour ({} tokens/hour)",
-                    burn_rates.current_hour.cost_per_hour,
+                    "High burn rate detected: {:.2}/hour ({} tokens/hour)",
+                    Money(burn_rates.current_hour.cost_per_hour),
                     burn_rates.current_hour.tokens_per_hour as u64
                 ),
                 timestamp: now,
@@ -530,6 +712,43 @@ impl<'a> RealtimeAnalytics<'a> {
             });
         }
 
+        // A single project burning fast, e.g. an agent left running in one repository
+        for rate in project_burn_rates
+            .iter()
+            .filter(|rate| rate.last_hour_cost_per_hour > self.budget_config.burn_rate_threshold)
+        {
+            alerts.push(UsageAlert {
+                alert_type: AlertType::HighBurnRate,
+                severity: AlertSeverity::Warning,
+                message: format!(
+                    "High burn rate in {}: {:.2}/hour over the last hour",
+                    rate.project,
+                    Money(rate.last_hour_cost_per_hour)
+                ),
+                timestamp: now,
+                recommended_action: Some(format!(
+                    "Check the {} active session(s) in this project",
+                    rate.active_sessions
+                )),
+            });
+        }
+
+        // Parallel agents multiply the burn rate
+        if concurrency.current > concurrency.threshold {
+            alerts.push(UsageAlert {
+                alert_type: AlertType::ConcurrentSessions,
+                severity: AlertSeverity::Warning,
+                message: format!(
+                    "{} sessions are consuming tokens concurrently (limit {})",
+                    concurrency.current, concurrency.threshold
+                ),
+                timestamp: now,
+                recommended_action: Some(
+                    "Check for parallel agents that are no longer needed".to_string(),
+                ),
+            });
+        }
+
         // Unusual spike detection
         if burn_rates.current_hour.trend_percentage > 100.0 {
             alerts.push(UsageAlert {
@@ -568,8 +787,8 @@ impl<'a> RealtimeAnalytics<'a> {
                 alert_type: AlertType::ProjectionWarning,
                 severity: AlertSeverity::Warning,
                 message: format!(
-                    "Monthly budget projection exceeds limit by ${:.2}",
-                    -budget_projections.monthly_projection.margin
+                    "Monthly budget projection exceeds limit by {:.2}",
+                    Money(-budget_projections.monthly_projection.margin)
                 ),
                 timestamp: now,
                 recommended_action: Some(
@@ -690,6 +909,141 @@ impl<'a> RealtimeAnalytics<'a> {
     }
 }
 
+/// Sample how many sessions were consuming tokens in each interval of the
+/// 24 hours before `now`, overall and per project
+pub fn concurrency_analysis(
+    events: &[UsageEvent],
+    now: DateTime<Utc>,
+    threshold: usize,
+) -> ConcurrencyAnalysis {
+    let interval = Duration::minutes(CONCURRENCY_INTERVAL_MINUTES);
+    let intervals = (24 * 60 / CONCURRENCY_INTERVAL_MINUTES) as usize;
+    let window_start = now - interval * intervals as i32;
+
+    // Sessions and cost per interval; sessions per project per interval
+    let mut sessions: Vec<HashSet<&str>> = vec![HashSet::new(); intervals];
+    let mut costs = vec![0.0; intervals];
+    let mut projects: HashMap<&str, Vec<HashSet<&str>>> = HashMap::new();
+    for event in events
+        .iter()
+        .filter(|e| e.timestamp > window_start && e.timestamp <= now)
+    {
+        let offset = (event.timestamp - window_start).num_seconds() - 1;
+        let index = (offset / interval.num_seconds()).clamp(0, intervals as i64 - 1) as usize;
+        sessions[index].insert(event.session.as_str());
+        costs[index] += event.usage.total_cost;
+        let project = event
+            .session
+            .rsplit_once('/')
+            .map_or(event.session.as_str(), |(project, _)| project);
+        projects
+            .entry(project)
+            .or_insert_with(|| vec![HashSet::new(); intervals])[index]
+            .insert(event.session.as_str());
+    }
+
+    let sample_start = |index: usize| window_start + interval * index as i32;
+    let timeline: Vec<ConcurrencySample> = sessions
+        .iter()
+        .zip(&costs)
+        .enumerate()
+        .map(|(index, (sessions, cost))| ConcurrencySample {
+            start: sample_start(index),
+            sessions: sessions.len(),
+            cost: *cost,
+        })
+        .collect();
+
+    // The first interval of equal peaks
+    let peak_sample = timeline
+        .iter()
+        .filter(|sample| sample.sessions > 0)
+        .max_by(|a, b| a.sessions.cmp(&b.sessions).then(b.start.cmp(&a.start)));
+    let active_counts: Vec<f64> = timeline
+        .iter()
+        .filter(|sample| sample.sessions > 0)
+        .map(|sample| sample.sessions as f64)
+        .collect();
+
+    let mut by_project: Vec<ProjectConcurrency> = projects
+        .into_iter()
+        .filter_map(|(project, samples)| {
+            let (index, peak) = samples
+                .iter()
+                .map(HashSet::len)
+                .enumerate()
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))?;
+            (peak > 1).then(|| ProjectConcurrency {
+                project: project.to_string(),
+                peak,
+                peak_at: sample_start(index),
+            })
+        })
+        .collect();
+    by_project.sort_by(|a, b| b.peak.cmp(&a.peak).then(a.project.cmp(&b.project)));
+
+    let mut periods: Vec<ConcurrencyPeriod> = Vec::new();
+    let mut extends_previous = false;
+    for sample in &timeline {
+        if sample.sessions <= threshold {
+            extends_previous = false;
+            continue;
+        }
+        match periods.last_mut() {
+            Some(period) if extends_previous => {
+                period.end = sample.start + interval;
+                period.peak_sessions = period.peak_sessions.max(sample.sessions);
+                period.cost += sample.cost;
+            }
+            _ => periods.push(ConcurrencyPeriod {
+                start: sample.start,
+                end: sample.start + interval,
+                peak_sessions: sample.sessions,
+                cost: sample.cost,
+            }),
+        }
+        extends_previous = true;
+    }
+
+    ConcurrencyAnalysis {
+        interval_minutes: CONCURRENCY_INTERVAL_MINUTES,
+        current: timeline.last().map_or(0, |sample| sample.sessions),
+        peak: peak_sample.map_or(0, |sample| sample.sessions),
+        peak_at: peak_sample.map(|sample| sample.start),
+        average: if active_counts.is_empty() {
+            0.0
+        } else {
+            active_counts.iter().sum::<f64>() / active_counts.len() as f64
+        },
+        threshold,
+        timeline,
+        by_project,
+        periods,
+    }
+}
+
+/// Hourly peaks of the concurrency timeline as a one-line chart, oldest first
+fn concurrency_sparkline(concurrency: &ConcurrencyAnalysis) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let per_hour = (60 / concurrency.interval_minutes).max(1) as usize;
+    let peak = concurrency.peak.max(1);
+    concurrency
+        .timeline
+        .chunks(per_hour)
+        .map(|hour| {
+            let sessions = hour.iter().map(|sample| sample.sessions).max().unwrap_or(0);
+            if sessions == 0 {
+                ' '
+            } else {
+                BARS[sessions * (BARS.len() - 1) / peak]
+            }
+        })
+        .collect()
+}
+
+/// Projects listed under "Most Active Projects"
+const MAX_PROJECTS_SHOWN: usize = 5;
+
 /// Format real-time analytics for display
 pub fn format_realtime_analytics(report: &RealtimeAnalyticsReport) -> String {
     let mut output = String::new();
@@ -698,28 +1052,86 @@ pub fn format_realtime_analytics(report: &RealtimeAnalyticsReport) -> String {
     output.push_str("🔥 Burn Rates\n");
     output.push_str("─────────────\n");
     output.push_str(&format!(
-        "Current Hour: {} tok/hr (${:.4}/hr) {} {:.1}%\n",
+        "Current Hour: {} tok/hr ({:.4}/hr) {} {:.1}%\n",
         format_number(report.burn_rates.current_hour.tokens_per_hour as u64),
-        report.burn_rates.current_hour.cost_per_hour,
+        Money(report.burn_rates.current_hour.cost_per_hour),
         trend_arrow(report.burn_rates.current_hour.trend_percentage),
         report.burn_rates.current_hour.trend_percentage.abs()
     ));
     output.push_str(&format!(
-        "Per Minute: {} tok/min (${:.4}/min)\n",
+        "Per Minute: {} tok/min ({:.4}/min)\n",
         format_number(report.burn_rates.tokens_per_minute as u64),
-        report.burn_rates.cost_per_minute
+        Money(report.burn_rates.cost_per_minute)
     ));
     output.push_str(&format!(
-        "24-Hour Avg: {} tok/hr (${:.4}/hr)\n",
+        "24-Hour Avg: {} tok/hr ({:.4}/hr)\n",
         format_number(report.burn_rates.last_24_hours.tokens_per_hour as u64),
-        report.burn_rates.last_24_hours.cost_per_hour
+        Money(report.burn_rates.last_24_hours.cost_per_hour)
     ));
     output.push_str(&format!(
-        "Peak Rate: ${:.2}/hr at {}\n\n",
-        report.burn_rates.peak_burn_rate.cost_per_hour,
+        "Peak Rate: {:.2}/hr at {}\n\n",
+        Money(report.burn_rates.peak_burn_rate.cost_per_hour),
         report.burn_rates.peak_burn_rate.occurred_at.format("%H:%M")
     ));
 
+    // Most active projects, to spot one burning through the budget
+    if !report.project_burn_rates.is_empty() {
+        output.push_str("🏗️  Most Active Projects\n");
+        output.push_str("───────────────────────\n");
+        for rate in report.project_burn_rates.iter().take(MAX_PROJECTS_SHOWN) {
+            output.push_str(&format!(
+                "{}: {:.4}/hr last hour, {:.4}/hr over 24h, {} active session{}\n",
+                rate.project,
+                Money(rate.last_hour_cost_per_hour),
+                Money(rate.last_day_cost_per_hour),
+                rate.active_sessions,
+                if rate.active_sessions == 1 { "" } else { "s" }
+            ));
+        }
+        output.push('\n');
+    }
+
+    // Concurrency Section
+    let concurrency = &report.concurrency;
+    if concurrency.peak > 0 {
+        output.push_str("🤖 Concurrent Sessions (last 24h)\n");
+        output.push_str("────────────────────────────────\n");
+        output.push_str(&format!(
+            "Now: {}  Peak: {}{}  Average: {:.1} (per {}-minute interval)\n",
+            concurrency.current,
+            concurrency.peak,
+            concurrency
+                .peak_at
+                .map(|at| format!(" at {}", at.with_timezone(&Local).format("%H:%M")))
+                .unwrap_or_default(),
+            concurrency.average,
+            concurrency.interval_minutes
+        ));
+        output.push_str(&format!(
+            "Hourly peak: [{}]\n",
+            concurrency_sparkline(concurrency)
+        ));
+        for project in concurrency.by_project.iter().take(MAX_PROJECTS_SHOWN) {
+            output.push_str(&format!(
+                "  {}: {} sessions at once at {}\n",
+                project.project,
+                project.peak,
+                project.peak_at.with_timezone(&Local).format("%H:%M")
+            ));
+        }
+        for period in &concurrency.periods {
+            output.push_str(&format!(
+                "⚠️  {}-{}: up to {} sessions (over {}), {:.2} spent\n",
+                period.start.with_timezone(&Local).format("%H:%M"),
+                period.end.with_timezone(&Local).format("%H:%M"),
+                period.peak_sessions,
+                concurrency.threshold,
+                Money(period.cost)
+            ));
+        }
+        output.push('\n');
+    }
+
     // Budget Projections Section
     output.push_str("💰 Budget Projections\n");
     output.push_str("────────────────────\n");
@@ -751,6 +1163,21 @@ pub fn format_realtime_analytics(report: &RealtimeAnalyticsReport) -> String {
     {
         output.push_str(&format!("⏱️  Monthly limit in: {:.1} days\n", days));
     }
+    let cycle = &report.budget_projections.billing_cycle;
+    output.push_str(&format!(
+        "🗓️  Billing cycle: {} - {} ({} days left), {:.2} spent\n",
+        cycle.cycle_start.format("%b %d"),
+        cycle.cycle_end.format("%b %d"),
+        cycle.days_remaining,
+        Money(cycle.spent_to_date)
+    ));
+    if let Some(overage) = cycle.projected_overage {
+        output.push_str(&format!(
+            "❌ Projected overage: {:.2} by {}\n",
+            Money(overage),
+            cycle.cycle_end.format("%b %d")
+        ));
+    }
     output.push('\n');
 
     // Session Analytics Section
@@ -761,9 +1188,9 @@ pub fn format_realtime_analytics(report: &RealtimeAnalyticsReport) -> String {
         report.session_metrics.active_session_count
     ));
     output.push_str(&format!(
-        "Avg per Session: {} tokens (${:.4})\n",
+        "Avg per Session: {} tokens ({:.4})\n",
         format_number(report.session_metrics.avg_tokens_per_session as u64),
-        report.session_metrics.avg_cost_per_session
+        Money(report.session_metrics.avg_cost_per_session)
     ));
     output.push_str(&format!(
         "Avg Duration: {}\n",
@@ -820,13 +1247,18 @@ fn format_budget_projection(label: &str, projection: &BudgetProjection) -> Strin
     if let Some(limit) = projection.budget_limit {
         let status = if projection.will_exceed { "❌" } else { "✅" };
         format!(
-            "{}: ${:.2} / ${:.2} ({:.1}%) {}\n",
-            label, projection.estimated_cost, limit, projection.utilization_percentage, status
+            "{}: {:.2} / {:.2} ({:.1}%) {}\n",
+            label,
+            Money(projection.estimated_cost),
+            Money(limit),
+            projection.utilization_percentage,
+            status
         )
     } else {
         format!(
-            "{}: ${:.2} (no limit set)\n",
-            label, projection.estimated_cost
+            "{}: {:.2} (no limit set)\n",
+            label,
+            Money(projection.estimated_cost)
         )
     }
 }
@@ -886,6 +1318,65 @@ mod tests {
         assert_eq!(over_budget.margin, -20.0);
     }
 
+    fn event(minutes_ago: i64, session: &str, cost: f64, now: DateTime<Utc>) -> UsageEvent {
+        UsageEvent {
+            timestamp: now - Duration::minutes(minutes_ago),
+            session: session.to_string(),
+            model: "claude-sonnet-4-20250514".to_string(),
+            usage: crate::models::TokenUsage {
+                total_cost: cost,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_concurrency_analysis() {
+        let now = Utc::now();
+        let mut events = vec![
+            // Three agents in one project for the last 20 minutes
+            event(2, "-repo/a", 1.0, now),
+            event(3, "-repo/b", 1.0, now),
+            event(4, "-repo/c", 1.0, now),
+            event(12, "-repo/a", 1.0, now),
+            event(13, "-repo/b", 1.0, now),
+            event(14, "-repo/c", 1.0, now),
+            // Plus one elsewhere in the latest interval
+            event(5, "-docs/d", 0.5, now),
+            // A lone session earlier in the day
+            event(300, "-docs/d", 0.5, now),
+            // Outside the window
+            event(25 * 60, "-old/e", 9.0, now),
+        ];
+        events.reverse();
+
+        let concurrency = concurrency_analysis(&events, now, 3);
+        assert_eq!(concurrency.interval_minutes, CONCURRENCY_INTERVAL_MINUTES);
+        assert_eq!(concurrency.timeline.len(), 144);
+        assert_eq!(concurrency.current, 4);
+        assert_eq!(concurrency.peak, 4);
+        assert_eq!(concurrency.average, (4.0 + 3.0 + 1.0) / 3.0);
+        assert_eq!(concurrency.by_project.len(), 1);
+        assert_eq!(concurrency.by_project[0].project, "-repo");
+        assert_eq!(concurrency.by_project[0].peak, 3);
+
+        // Only the latest interval has more than three sessions
+        assert_eq!(concurrency.periods.len(), 1);
+        assert_eq!(concurrency.periods[0].peak_sessions, 4);
+        assert_eq!(concurrency.periods[0].end, now);
+        assert!((concurrency.periods[0].cost - 3.5).abs() < 1e-9);
+
+        // With a limit of two, both intervals of the agents form one period
+        let strict = concurrency_analysis(&events, now, 2);
+        assert_eq!(strict.periods.len(), 1);
+        assert_eq!(strict.periods[0].start, now - Duration::minutes(20));
+        assert!((strict.periods[0].cost - 6.5).abs() < 1e-9);
+
+        let sparkline = concurrency_sparkline(&concurrency);
+        assert_eq!(sparkline.chars().count(), 24);
+        assert!(sparkline.ends_with('█'));
+    }
+
     #[test]
     fn test_trend_metric_creation() {
         let daily_map = HashMap::new();
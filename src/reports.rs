@@ -1,26 +1,77 @@
+//! Turning usage maps into the daily, session, monthly, weekly, hourly, and
+//! grouped reports the commands print (and serialize with `--json`)
+
+use crate::burn_rate::UsageEvent;
+use crate::context_usage;
+use crate::filter_expr::Filter;
 use crate::helpers::{calculate_efficiency, compare_floats};
 use crate::models::{
-    DailyReport, DailyUsage, DailyUsageMap, MonthlyReport, MonthlyUsage, SessionReport,
-    SessionUsage, SessionUsageMap, TokenUsage, TokenUsageTotals, WeeklyReport, WeeklyUsage,
+    DailyReport, DailyUsage, DailyUsageMap, GroupReport, GroupUsage, HourlyReport, HourlyUsage,
+    ModelUsage, MonthlyReport, MonthlyUsage, Pagination, SessionReport, SessionUsage,
+    SessionUsageMap, SourceUsage, TokenUsage, TokenUsageTotals, WeeklyReport, WeeklyUsage,
 };
-use chrono::{Datelike, Duration, NaiveDate, Weekday};
-use std::collections::HashMap;
+use crate::models_registry::ModelsRegistry;
+use crate::schedule::Schedule;
+use crate::session_analytics::EfficiencyScore;
+use crate::tags::Tagger;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, Timelike, Weekday};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
+/// Column a report is sorted by; each report falls back to its natural order
 #[derive(Clone, Copy, Debug)]
 pub enum SortField {
+    /// Date of the row (its start for weeks and months)
     Date,
+    /// Total cost
     Cost,
+    /// Total tokens
     Tokens,
+    /// Efficiency score (session reports)
     Efficiency,
+    /// Project path (session reports)
     Project,
 }
 
+/// Sort direction; descending unless given
 #[derive(Clone, Copy, Debug)]
 pub enum SortOrder {
+    /// Smallest first
     Asc,
+    /// Largest first
     Desc,
 }
 
+/// What `--group-by` rolls usage up by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupBy {
+    /// The session's project directory
+    Project,
+    /// Tags assigned to the session's project
+    Tag,
+}
+
+impl GroupBy {
+    /// Value of the report's `groupBy` field
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GroupBy::Project => "project",
+            GroupBy::Tag => "tag",
+        }
+    }
+}
+
+/// Time bucket of a grouped report
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupPeriod {
+    /// One row per group over the whole range
+    Total,
+    /// One row per group and day
+    Day,
+    /// One row per group and month
+    Month,
+}
+
+/// Daily report, newest day first unless sorted otherwise
 pub fn generate_daily_report_sorted(
     daily_map: DailyUsageMap,
     sort_field: Option<SortField>,
@@ -48,15 +99,60 @@ pub fn generate_daily_report_sorted(
     }
 }
 
+/// Attach the per-model-family breakdown from `daily_map` to each daily row
+pub fn attach_daily_model_breakdown(report: &mut DailyReport, daily_map: &DailyUsageMap) {
+    let registry = ModelsRegistry::new();
+    for entry in &mut report.daily {
+        let Some(usage) = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| daily_map.get(&date))
+        else {
+            continue;
+        };
+
+        let mut families: BTreeMap<String, TokenUsage> = BTreeMap::new();
+        for (model, model_usage) in &usage.by_model {
+            let family = registry
+                .get_model_family(model)
+                .unwrap_or_else(|| "unknown".to_string());
+            families.entry(family).or_default().add(model_usage);
+        }
+
+        let mut models: Vec<ModelUsage> = families.iter().map(ModelUsage::from).collect();
+        models.sort_by(|a, b| compare_floats(b.total_cost, a.total_cost));
+        entry.models = models;
+    }
+}
+
+/// Fill in the per-source breakdown of each daily row when usage was read from
+/// more than one Claude directory
+pub fn attach_daily_source_breakdown(report: &mut DailyReport, daily_map: &DailyUsageMap) {
+    for entry in &mut report.daily {
+        let Some(usage) = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| daily_map.get(&date))
+        else {
+            continue;
+        };
+
+        let mut sources: Vec<SourceUsage> = usage.by_source.iter().map(SourceUsage::from).collect();
+        sources.sort_by(|a, b| compare_floats(b.total_cost, a.total_cost));
+        entry.sources = sources;
+    }
+}
+
+/// Session report, most expensive session first unless sorted otherwise
 pub fn generate_session_report_sorted(
     session_map: SessionUsageMap,
     sort_field: Option<SortField>,
     sort_order: Option<SortOrder>,
 ) -> SessionReport {
+    let registry = ModelsRegistry::new();
     let mut session_entries: Vec<SessionUsage> = session_map
         .into_iter()
         .map(|(session_path, (usage, last_activity))| {
             let (project_path, session_id) = parse_session_path(&session_path);
+            let context_peak_percent = context_usage::peak_percent(&usage, &registry);
             SessionUsage {
                 project_path,
                 session_id,
@@ -66,7 +162,12 @@ pub fn generate_session_report_sorted(
                 cache_read_tokens: usage.cache_read_tokens,
                 total_tokens: usage.total_tokens(),
                 total_cost: usage.total_cost,
+                thinking_tokens: usage.thinking_tokens,
                 last_activity: last_activity.date_naive().format("%Y-%m-%d").to_string(),
+                summary: None,
+                efficiency_score: EfficiencyScore::of(&usage).map(|e| e.score),
+                context_peak_percent,
+                resumed_sessions: Vec::new(),
             }
         })
         .collect();
@@ -74,8 +175,15 @@ pub fn generate_session_report_sorted(
     // Apply sorting
     sort_session_entries(&mut session_entries, sort_field, sort_order);
 
-    // Calculate totals
-    let totals = session_entries
+    SessionReport {
+        totals: session_totals(&session_entries),
+        sessions: session_entries,
+        pagination: None,
+    }
+}
+
+fn session_totals(sessions: &[SessionUsage]) -> TokenUsageTotals {
+    let totals = sessions
         .iter()
         .fold(TokenUsage::default(), |mut acc, session| {
             acc.input_tokens += session.input_tokens;
@@ -83,15 +191,65 @@ pub fn generate_session_report_sorted(
             acc.cache_creation_tokens += session.cache_creation_tokens;
             acc.cache_read_tokens += session.cache_read_tokens;
             acc.total_cost += session.total_cost;
+            acc.thinking_tokens += session.thinking_tokens;
             acc
         });
+    TokenUsageTotals::from(&totals)
+}
 
-    SessionReport {
-        sessions: session_entries,
-        totals: TokenUsageTotals::from(&totals),
+/// Keep the sessions matching `filter` (`--filter`), with totals of those only
+#[doc(hidden)]
+pub fn filter_session_report(report: &mut SessionReport, filter: &Filter<SessionUsage>) {
+    report.sessions.retain(|session| filter.matches(session));
+    report.totals = session_totals(&report.sessions);
+}
+
+/// Keep `limit` sessions after skipping `offset` (`--limit`, `--offset`, `--top`),
+/// recording how many were left out; totals still cover every session
+pub fn paginate_session_report(report: &mut SessionReport, offset: usize, limit: Option<usize>) {
+    if offset == 0 && limit.is_none() {
+        return;
+    }
+    let total_rows = report.sessions.len();
+    report.sessions.drain(..offset.min(total_rows));
+    if let Some(limit) = limit {
+        report.sessions.truncate(limit);
+    }
+    report.pagination = Some(Pagination {
+        offset,
+        limit,
+        total_rows,
+        omitted: total_rows - report.sessions.len(),
+    });
+}
+
+/// Fill in each session's summary (see `UsageParser::session_summaries`)
+pub fn attach_session_summaries(report: &mut SessionReport, summaries: &HashMap<String, String>) {
+    for session in &mut report.sessions {
+        let key = if session.project_path.is_empty() {
+            session.session_id.clone()
+        } else {
+            format!("{}/{}", session.project_path, session.session_id)
+        };
+        session.summary = summaries.get(&key).cloned();
+    }
+}
+
+/// Record the sessions folded into each chain of resumes (see `lineage::SessionLineage::group`)
+pub fn attach_resumed_sessions(report: &mut SessionReport, resumed: &HashMap<String, Vec<String>>) {
+    for session in &mut report.sessions {
+        let key = if session.project_path.is_empty() {
+            session.session_id.clone()
+        } else {
+            format!("{}/{}", session.project_path, session.session_id)
+        };
+        if let Some(members) = resumed.get(&key) {
+            session.resumed_sessions = members.clone();
+        }
     }
 }
 
+/// Monthly report, newest month first unless sorted otherwise
 pub fn generate_monthly_report_sorted(
     daily_map: DailyUsageMap,
     sort_field: Option<SortField>,
@@ -140,6 +298,7 @@ pub fn generate_monthly_report_sorted(
                 cache_read_tokens: usage.cache_read_tokens,
                 total_tokens: usage.total_tokens(),
                 total_cost: usage.total_cost,
+                thinking_tokens: usage.thinking_tokens,
                 days_active,
                 avg_daily_cost: if days_active > 0 {
                     usage.total_cost / days_active as f64
@@ -167,6 +326,8 @@ pub fn generate_monthly_report_sorted(
     }
 }
 
+/// Weekly report with weeks starting on `start_of_week`, newest first unless
+/// sorted otherwise
 pub fn generate_weekly_report_sorted(
     daily_map: DailyUsageMap,
     sort_field: Option<SortField>,
@@ -247,6 +408,209 @@ pub fn generate_weekly_report_sorted(
     }
 }
 
+/// Bucket usage events by local clock hour. Only hours with usage are listed.
+pub fn generate_hourly_report_sorted(
+    events: &[UsageEvent],
+    sort_field: Option<SortField>,
+    sort_order: Option<SortOrder>,
+) -> HourlyReport {
+    let mut hourly_map: HashMap<NaiveDateTime, (TokenUsage, HashSet<&str>)> = HashMap::new();
+    let mut totals = TokenUsage::default();
+
+    for event in events {
+        let local = event.timestamp.with_timezone(&Local).naive_local();
+        let hour = local
+            .date()
+            .and_hms_opt(local.hour(), 0, 0)
+            .unwrap_or(local);
+
+        let entry = hourly_map.entry(hour).or_default();
+        entry.0.add(&event.usage);
+        entry.1.insert(event.session.as_str());
+        totals.add(&event.usage);
+    }
+
+    let mut hourly_entries: Vec<HourlyUsage> = hourly_map
+        .into_iter()
+        .map(|(hour, (usage, sessions))| HourlyUsage {
+            hour: hour.format("%Y-%m-%d %H:00").to_string(),
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_tokens: usage.cache_creation_tokens,
+            cache_read_tokens: usage.cache_read_tokens,
+            total_tokens: usage.total_tokens(),
+            total_cost: usage.total_cost,
+            message_count: usage.message_count,
+            sessions: sessions.len(),
+        })
+        .collect();
+
+    // Sort by hour by default
+    match sort_field {
+        Some(SortField::Cost) => {
+            let order = sort_order.unwrap_or(SortOrder::Desc);
+            hourly_entries.sort_by(|a, b| match order {
+                SortOrder::Asc => compare_floats(a.total_cost, b.total_cost),
+                SortOrder::Desc => compare_floats(b.total_cost, a.total_cost),
+            });
+        }
+        Some(SortField::Tokens) => {
+            let order = sort_order.unwrap_or(SortOrder::Desc);
+            hourly_entries.sort_by(|a, b| match order {
+                SortOrder::Asc => a.total_tokens.cmp(&b.total_tokens),
+                SortOrder::Desc => b.total_tokens.cmp(&a.total_tokens),
+            });
+        }
+        _ => {
+            let order = sort_order.unwrap_or(SortOrder::Desc);
+            hourly_entries.sort_by(|a, b| match order {
+                SortOrder::Asc => a.hour.cmp(&b.hour),
+                SortOrder::Desc => b.hour.cmp(&a.hour),
+            });
+        }
+    }
+
+    HourlyReport {
+        hourly: hourly_entries,
+        totals: TokenUsageTotals::from(&totals),
+    }
+}
+
+/// Roll usage up by project or tag. Daily and monthly periods use the
+/// per-session breakdown of `daily_map`, so a session spanning several days is
+/// split across them; `Total` uses `session_map`.
+#[doc(hidden)]
+pub fn generate_group_report(
+    daily_map: &DailyUsageMap,
+    session_map: &SessionUsageMap,
+    group_by: GroupBy,
+    period: GroupPeriod,
+    tagger: &Tagger,
+    sort_field: Option<SortField>,
+    sort_order: Option<SortOrder>,
+) -> GroupReport {
+    let groups_for = |session: &str| match group_by {
+        GroupBy::Project => vec![parse_session_path(session).0],
+        GroupBy::Tag => tagger.tags_for(session),
+    };
+
+    let mut grouped: BTreeMap<(Option<String>, String), (TokenUsage, HashSet<String>)> =
+        BTreeMap::new();
+    let mut add = |period: Option<String>, session: &str, usage: &TokenUsage| {
+        for group in groups_for(session) {
+            let entry = grouped.entry((period.clone(), group)).or_default();
+            entry.0.add(usage);
+            entry.1.insert(session.to_string());
+        }
+    };
+
+    let totals = match period {
+        GroupPeriod::Total => {
+            for (session, (usage, _)) in session_map {
+                add(None, session, usage);
+            }
+            session_map
+                .values()
+                .fold(TokenUsage::default(), |mut acc, (usage, _)| {
+                    acc.add(usage);
+                    acc
+                })
+        }
+        GroupPeriod::Day | GroupPeriod::Month => {
+            for (date, usage) in daily_map {
+                let key = if period == GroupPeriod::Day {
+                    date.format("%Y-%m-%d").to_string()
+                } else {
+                    date.format("%Y-%m").to_string()
+                };
+                for (session, session_usage) in &usage.by_session {
+                    add(Some(key.clone()), session, session_usage);
+                }
+            }
+            daily_map
+                .values()
+                .fold(TokenUsage::default(), |mut acc, usage| {
+                    acc.add(usage);
+                    acc
+                })
+        }
+    };
+
+    let mut groups: Vec<GroupUsage> = grouped
+        .into_iter()
+        .map(|((period, group), (usage, sessions))| GroupUsage {
+            period,
+            group,
+            sessions: sessions.len(),
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_tokens: usage.cache_creation_tokens,
+            cache_read_tokens: usage.cache_read_tokens,
+            total_tokens: usage.total_tokens(),
+            total_cost: usage.total_cost,
+        })
+        .collect();
+    sort_entries(&mut groups, sort_field, sort_order);
+
+    GroupReport {
+        group_by: group_by.as_str().to_string(),
+        groups,
+        totals: TokenUsageTotals::from(&totals),
+    }
+}
+
+/// Split usage into the buckets of a time-of-day schedule (`--split-by schedule`)
+///
+/// Works on individual records because a session or day can span several buckets.
+#[doc(hidden)]
+pub fn generate_schedule_report(
+    events: &[UsageEvent],
+    schedule: &Schedule,
+    period: GroupPeriod,
+    sort_field: Option<SortField>,
+    sort_order: Option<SortOrder>,
+) -> GroupReport {
+    let mut grouped: BTreeMap<(Option<String>, String), (TokenUsage, HashSet<String>)> =
+        BTreeMap::new();
+    let mut totals = TokenUsage::default();
+    for event in events {
+        let date = event.timestamp.with_timezone(&Local).date_naive();
+        let key = match period {
+            GroupPeriod::Total => None,
+            GroupPeriod::Day => Some(date.format("%Y-%m-%d").to_string()),
+            GroupPeriod::Month => Some(date.format("%Y-%m").to_string()),
+        };
+        let entry = grouped
+            .entry((key, schedule.bucket_for(event.timestamp).to_string()))
+            .or_default();
+        entry.0.add(&event.usage);
+        entry.1.insert(event.session.clone());
+        totals.add(&event.usage);
+    }
+
+    let mut groups: Vec<GroupUsage> = grouped
+        .into_iter()
+        .map(|((period, bucket), (usage, sessions))| GroupUsage {
+            period,
+            group: bucket,
+            sessions: sessions.len(),
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_tokens: usage.cache_creation_tokens,
+            cache_read_tokens: usage.cache_read_tokens,
+            total_tokens: usage.total_tokens(),
+            total_cost: usage.total_cost,
+        })
+        .collect();
+    sort_entries(&mut groups, sort_field, sort_order);
+
+    GroupReport {
+        group_by: "schedule".to_string(),
+        groups,
+        totals: TokenUsageTotals::from(&totals),
+    }
+}
+
 /// Calculate the start of the week containing the given date
 fn get_week_start(date: NaiveDate, start_of_week: Weekday) -> NaiveDate {
     let current_weekday = date.weekday();
@@ -257,7 +621,7 @@ fn get_week_start(date: NaiveDate, start_of_week: Weekday) -> NaiveDate {
     date - Duration::days(days_since_start)
 }
 
-fn month_to_num(month: &str) -> u32 {
+pub(crate) fn month_to_num(month: &str) -> u32 {
     match month {
         "January" => 1,
         "February" => 2,
@@ -345,6 +709,30 @@ impl Sortable for MonthlyUsage {
     }
 }
 
+impl Sortable for GroupUsage {
+    fn compare_by_field(&self, other: &Self, field: SortField) -> std::cmp::Ordering {
+        match field {
+            SortField::Cost => compare_floats(self.total_cost, other.total_cost),
+            SortField::Tokens => self.total_tokens.cmp(&other.total_tokens),
+            SortField::Efficiency => {
+                let eff_a = calculate_efficiency(self.total_tokens, self.total_cost);
+                let eff_b = calculate_efficiency(other.total_tokens, other.total_cost);
+                compare_floats(eff_a, eff_b)
+            }
+            SortField::Project => self.group.cmp(&other.group),
+            // Most recent period first, then the most expensive group within it
+            SortField::Date => self
+                .period
+                .cmp(&other.period)
+                .then_with(|| compare_floats(self.total_cost, other.total_cost)),
+        }
+    }
+
+    fn default_sort_field() -> SortField {
+        SortField::Date
+    }
+}
+
 // Generic sort function
 fn sort_entries<T: Sortable>(
     entries: &mut [T],
@@ -388,7 +776,7 @@ fn sort_monthly_entries(
     sort_entries(entries, sort_field, sort_order);
 }
 
-fn parse_session_path(session_path: &str) -> (String, String) {
+pub(crate) fn parse_session_path(session_path: &str) -> (String, String) {
     let parts: Vec<&str> = session_path.split('/').collect();
     if let Some(session_id) = parts.last() {
         let project_path = if parts.len() > 1 {
@@ -430,6 +818,7 @@ mod tests {
             cache_read_tokens: 300,
             total_cost: 0.15,
             fast_mode_cost: 0.0,
+            ..Default::default()
         };
         daily_map.insert(date, usage);
 
@@ -496,6 +885,85 @@ mod tests {
         assert!(week.days_active > 0);
     }
 
+    #[test]
+    fn test_hourly_report_generation() {
+        use chrono::{TimeZone, Utc};
+
+        let event = |hour: u32, minute: u32, session: &str| UsageEvent {
+            timestamp: Local
+                .with_ymd_and_hms(2024, 3, 1, hour, minute, 0)
+                .single()
+                .expect("valid time")
+                .with_timezone(&Utc),
+            session: session.to_string(),
+            model: "claude-sonnet-4".to_string(),
+            usage: TokenUsage {
+                input_tokens: 100,
+                output_tokens: 50,
+                total_cost: 0.25,
+                message_count: 1,
+                ..Default::default()
+            },
+        };
+        let events = vec![
+            event(9, 5, "proj/a"),
+            event(9, 55, "proj/b"),
+            event(9, 59, "proj/a"),
+            event(14, 0, "proj/a"),
+        ];
+
+        let report = generate_hourly_report_sorted(&events, None, Some(SortOrder::Asc));
+        assert_eq!(report.hourly.len(), 2);
+        assert_eq!(report.hourly[0].hour, "2024-03-01 09:00");
+        assert_eq!(report.hourly[0].message_count, 3);
+        assert_eq!(report.hourly[0].sessions, 2);
+        assert_eq!(report.hourly[0].total_tokens, 450);
+        assert_eq!(report.hourly[1].hour, "2024-03-01 14:00");
+        assert_eq!(report.totals.input_tokens, 400);
+    }
+
+    #[test]
+    fn test_schedule_report_generation() {
+        use chrono::{TimeZone, Utc};
+
+        // 2024-03-01 is a Friday
+        let event = |day: u32, hour: u32, session: &str| UsageEvent {
+            timestamp: Local
+                .with_ymd_and_hms(2024, 3, day, hour, 0, 0)
+                .single()
+                .expect("valid time")
+                .with_timezone(&Utc),
+            session: session.to_string(),
+            model: "claude-sonnet-4".to_string(),
+            usage: TokenUsage {
+                input_tokens: 100,
+                total_cost: 1.0,
+                ..Default::default()
+            },
+        };
+        let events = vec![
+            event(1, 10, "proj/a"),
+            event(1, 11, "proj/b"),
+            event(1, 20, "proj/a"),
+            event(2, 10, "proj/c"),
+        ];
+        let schedule = Schedule::new(&Default::default()).expect("default schedule");
+
+        let report = generate_schedule_report(&events, &schedule, GroupPeriod::Day, None, None);
+        assert_eq!(report.group_by, "schedule");
+        assert_eq!(report.groups.len(), 3);
+        assert_eq!(report.groups[0].period.as_deref(), Some("2024-03-02"));
+        assert_eq!(report.groups[0].group, "personal");
+        assert_eq!(report.groups[1].group, "work");
+        assert_eq!(report.groups[1].sessions, 2);
+        assert_eq!(report.groups[1].total_cost, 2.0);
+
+        let report = generate_schedule_report(&events, &schedule, GroupPeriod::Total, None, None);
+        assert_eq!(report.groups.len(), 2);
+        assert!(report.groups.iter().all(|g| g.total_cost == 2.0));
+        assert_eq!(report.totals.total_cost, 4.0);
+    }
+
     #[test]
     fn test_monthly_report_generation() {
         let mut daily_map = HashMap::new();
@@ -557,4 +1025,161 @@ mod tests {
             generate_daily_report_sorted(daily_map, Some(SortField::Tokens), Some(SortOrder::Desc));
         assert!(report.daily[0].total_tokens >= report.daily[1].total_tokens);
     }
+
+    #[test]
+    fn test_paginate_session_report() {
+        let mut session_map = SessionUsageMap::new();
+        for (i, cost) in [3.0, 1.0, 4.0, 2.0, 5.0].into_iter().enumerate() {
+            session_map.insert(
+                format!("project/session-{}", i),
+                (
+                    TokenUsage {
+                        input_tokens: 100,
+                        total_cost: cost,
+                        ..Default::default()
+                    },
+                    chrono::Utc::now(),
+                ),
+            );
+        }
+
+        let mut report =
+            generate_session_report_sorted(session_map.clone(), Some(SortField::Cost), None);
+        paginate_session_report(&mut report, 1, Some(2));
+        let costs: Vec<f64> = report.sessions.iter().map(|s| s.total_cost).collect();
+        assert_eq!(costs, vec![4.0, 3.0]);
+        assert_eq!(
+            report.pagination,
+            Some(Pagination {
+                offset: 1,
+                limit: Some(2),
+                total_rows: 5,
+                omitted: 3,
+            })
+        );
+        // Totals still cover every session
+        assert_eq!(report.totals.total_cost, 15.0);
+
+        let mut past_end = generate_session_report_sorted(session_map.clone(), None, None);
+        paginate_session_report(&mut past_end, 10, None);
+        assert!(past_end.sessions.is_empty());
+        assert_eq!(past_end.pagination.unwrap().omitted, 5);
+
+        let mut unpaginated = generate_session_report_sorted(session_map, None, None);
+        paginate_session_report(&mut unpaginated, 0, None);
+        assert_eq!(unpaginated.sessions.len(), 5);
+        assert!(unpaginated.pagination.is_none());
+    }
+
+    #[test]
+    fn test_attach_daily_model_breakdown() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).expect("valid date");
+        let opus = TokenUsage {
+            input_tokens: 100,
+            total_cost: 1.0,
+            ..Default::default()
+        };
+        let haiku = TokenUsage {
+            input_tokens: 400,
+            total_cost: 0.1,
+            ..Default::default()
+        };
+        let mut usage = TokenUsage::default();
+        for (model, part) in [
+            ("claude-3-5-haiku-20241022", haiku),
+            ("claude-opus-4-20250514", opus),
+        ] {
+            let mut tagged = part.clone();
+            tagged.by_model.insert(model.to_string(), part);
+            usage.add(&tagged);
+        }
+        let daily_map = HashMap::from([(date, usage)]);
+
+        let mut report = generate_daily_report_sorted(daily_map.clone(), None, None);
+        assert!(report.daily[0].models.is_empty());
+
+        attach_daily_model_breakdown(&mut report, &daily_map);
+        let models = &report.daily[0].models;
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].family, "opus");
+        assert_eq!(models[1].input_tokens, 400);
+        assert_eq!(report.daily[0].input_tokens, 500);
+    }
+
+    #[test]
+    fn test_group_report_by_tag() {
+        use crate::config_v2::{ProjectTagRule, TagsConfig};
+        use chrono::{TimeZone, Utc};
+
+        let usage = |cost: f64| TokenUsage {
+            input_tokens: 100,
+            total_cost: cost,
+            ..Default::default()
+        };
+        let mut daily_map: DailyUsageMap = HashMap::new();
+        for (day, session, cost) in [
+            (1, "-home-me-work-api/s1", 2.0),
+            (2, "-home-me-work-api/s1", 3.0),
+            (2, "-home-me-oss/s2", 1.0),
+        ] {
+            let mut day_usage = usage(cost);
+            day_usage
+                .by_session
+                .insert(session.to_string(), usage(cost));
+            daily_map
+                .entry(NaiveDate::from_ymd_opt(2024, 1, day).expect("valid"))
+                .or_default()
+                .add(&day_usage);
+        }
+        let last = Utc.with_ymd_and_hms(2024, 1, 2, 12, 0, 0).unwrap();
+        let session_map: SessionUsageMap = HashMap::from([
+            ("-home-me-work-api/s1".to_string(), (usage(5.0), last)),
+            ("-home-me-oss/s2".to_string(), (usage(1.0), last)),
+        ]);
+        let tagger = Tagger::new(&TagsConfig {
+            projects: vec![ProjectTagRule {
+                pattern: "-home-me-work-*".to_string(),
+                tags: vec!["work".to_string()],
+            }],
+            ..Default::default()
+        });
+
+        let report = generate_group_report(
+            &daily_map,
+            &session_map,
+            GroupBy::Tag,
+            GroupPeriod::Total,
+            &tagger,
+            None,
+            None,
+        );
+        assert_eq!(report.group_by, "tag");
+        assert_eq!(report.groups[0].group, "work");
+        assert_eq!(report.groups[0].total_cost, 5.0);
+        assert_eq!(report.groups[1].group, "untagged");
+        assert_eq!(report.totals.total_cost, 6.0);
+
+        let report = generate_group_report(
+            &daily_map,
+            &session_map,
+            GroupBy::Project,
+            GroupPeriod::Day,
+            &tagger,
+            None,
+            None,
+        );
+        let rows: Vec<(Option<&str>, &str, f64)> = report
+            .groups
+            .iter()
+            .map(|g| (g.period.as_deref(), g.group.as_str(), g.total_cost))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (Some("2024-01-02"), "-home-me-work-api", 3.0),
+                (Some("2024-01-02"), "-home-me-oss", 1.0),
+                (Some("2024-01-01"), "-home-me-work-api", 2.0),
+            ]
+        );
+    }
 }
@@ -2,6 +2,7 @@ use colored::*;
 use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
 
 use crate::billing_blocks::BillingBlock;
+use crate::currency::{self, Money};
 use crate::models::{DailyReport, SessionReport, TokenUsageTotals};
 use crate::terminal::Terminal;
 
@@ -66,6 +67,17 @@ pub struct TableColumn {
     pub merge_with: Option<&'static str>,
 }
 
+impl TableColumn {
+    /// Full header; the cost column names the display currency
+    fn full_header(&self) -> String {
+        if self.id == "cost" {
+            currency::cost_header()
+        } else {
+            self.header.to_string()
+        }
+    }
+}
+
 /// Responsive table builder
 pub struct ResponsiveTable {
     mode: TableMode,
@@ -73,6 +85,12 @@ pub struct ResponsiveTable {
     columns: Vec<TableColumn>,
 }
 
+impl Default for ResponsiveTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ResponsiveTable {
     /// Create a new responsive table with auto-detected mode
     pub fn new() -> Self {
@@ -105,7 +123,7 @@ impl ResponsiveTable {
             },
             TableColumn {
                 id: "cost",
-                header: "Cost (USD)",
+                header: "Cost",
                 header_short: "Cost",
                 priority: 1,
                 min_width: 10,
@@ -183,7 +201,7 @@ impl ResponsiveTable {
             },
             TableColumn {
                 id: "cost",
-                header: "Cost (USD)",
+                header: "Cost",
                 header_short: "Cost",
                 priority: 1,
                 min_width: 10,
@@ -199,6 +217,15 @@ impl ResponsiveTable {
                 can_merge: false,
                 merge_with: None,
             },
+            TableColumn {
+                id: "summary",
+                header: "Summary",
+                header_short: "Summary",
+                priority: 3,
+                min_width: 30,
+                can_merge: false,
+                merge_with: None,
+            },
             TableColumn {
                 id: "input_tokens",
                 header: "Input Tokens",
@@ -244,6 +271,15 @@ impl ResponsiveTable {
                 can_merge: false,
                 merge_with: None,
             },
+            TableColumn {
+                id: "score",
+                header: "Efficiency Score",
+                header_short: "Score",
+                priority: 4,
+                min_width: 6,
+                can_merge: false,
+                merge_with: None,
+            },
         ]
     }
 
@@ -270,7 +306,7 @@ impl ResponsiveTable {
             },
             TableColumn {
                 id: "cost",
-                header: "Cost (USD)",
+                header: "Cost",
                 header_short: "Cost",
                 priority: 1,
                 min_width: 10,
@@ -387,9 +423,9 @@ impl ResponsiveTable {
             .map(|col| {
                 let header_text =
                     if self.mode == TableMode::UltraCompact || self.mode == TableMode::Compact {
-                        col.header_short
+                        col.header_short.to_string()
                     } else {
-                        col.header
+                        col.full_header()
                     };
                 Cell::new(header_text).fg(Color::Cyan)
             })
@@ -526,9 +562,9 @@ impl ResponsiveTable {
             .map(|col| {
                 let header_text =
                     if self.mode == TableMode::UltraCompact || self.mode == TableMode::Compact {
-                        col.header_short
+                        col.header_short.to_string()
                     } else {
-                        col.header
+                        col.full_header()
                     };
                 Cell::new(header_text).fg(Color::Cyan)
             })
@@ -562,6 +598,18 @@ impl ResponsiveTable {
                     "last_activity" => {
                         row.push(Cell::new(&session.last_activity).fg(Color::Yellow))
                     }
+                    "score" => row.push(
+                        Cell::new(
+                            session
+                                .efficiency_score
+                                .map_or_else(|| "-".to_string(), |score| format!("{:.0}", score)),
+                        )
+                        .fg(Color::Blue),
+                    ),
+                    "summary" => row.push(Cell::new(truncate_text(
+                        session.summary.as_deref().unwrap_or(""),
+                        col.min_width as usize,
+                    ))),
                     "cache_tokens" => {
                         let cache_total = session.cache_creation_tokens + session.cache_read_tokens;
                         row.push(Cell::new(format_number(cache_total)).fg(Color::Yellow));
@@ -608,9 +656,9 @@ impl ResponsiveTable {
             .map(|col| {
                 let header_text =
                     if self.mode == TableMode::UltraCompact || self.mode == TableMode::Compact {
-                        col.header_short
+                        col.header_short.to_string()
                     } else {
-                        col.header
+                        col.full_header()
                     };
                 Cell::new(header_text).fg(Color::Cyan)
             })
@@ -799,9 +847,9 @@ fn format_number_short(num: u64) -> String {
 
 fn format_currency(amount: f64) -> String {
     if amount >= 100.0 {
-        format!("${:.2}", amount)
+        format!("{:.2}", Money(amount))
     } else {
-        format!("${:.4}", amount)
+        format!("{:.4}", Money(amount))
     }
 }
 
@@ -813,6 +861,15 @@ fn truncate_path(path: &str, max_length: usize) -> String {
     }
 }
 
+fn truncate_text(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        text.to_string()
+    } else {
+        let truncated: String = text.chars().take(max_length.saturating_sub(3)).collect();
+        format!("{}...", truncated)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,512 @@
+//! REST query API (`serve` command)
+//!
+//! Serves the reports as JSON over HTTP so dashboards can poll a machine for
+//! its usage: `GET /daily`, `/sessions`, `/projections` and `/blocks`. Query
+//! parameters mirror the CLI flags (`since`, `until`, `today`, `model-filter`,
+//! `sort-by`, `sort-order`, ...) and `format=json-v1` selects the versioned
+//! schema. Each request parses the usage data again (the parse cache keeps
+//! that cheap), so responses are always current. Uses the same HTTP plumbing as
+//! the MCP HTTP transport.
+
+use crate::billing_blocks::{BillingBlockConfig, BlockAnchor};
+use crate::config_v2::BudgetConfig;
+use crate::date_range;
+use crate::json_v1;
+use crate::mcp_http::{HttpRequest, read_request, write_response};
+use crate::parser::{CostMode, UsageParser};
+use crate::projections::ProjectionCalculator;
+use crate::reports::{
+    SortField, SortOrder, attach_session_summaries, generate_daily_report_sorted,
+    generate_session_report_sorted,
+};
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, Utc};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::BufReader;
+use tokio::net::{TcpListener, TcpStream};
+
+/// Endpoints listed by `GET /`
+const ENDPOINTS: &[(&str, &str)] = &[
+    ("/daily", "Daily usage report"),
+    ("/sessions", "Session usage report"),
+    ("/projections", "Cost projections and budget forecast"),
+    ("/blocks", "5-hour billing blocks"),
+];
+
+/// Longest forecast `/projections` computes; every day is a row of the response
+const MAX_PROJECTION_DAYS: i64 = 365;
+
+/// Settings shared by every request
+#[derive(Debug, Clone)]
+pub struct ApiOptions {
+    /// Default monthly limit and billing cycle for `/projections`
+    pub budget: BudgetConfig,
+    /// Block layout for `/blocks` unless the request sets `mode`
+    pub billing_config: BillingBlockConfig,
+    pub cost_mode: CostMode,
+    pub use_parse_cache: bool,
+    pub dedup: bool,
+    /// Add archived usage for pruned history (see `archive`)
+    pub include_archive: bool,
+}
+
+/// A request that cannot be answered, with its HTTP status
+#[derive(Debug)]
+pub struct ApiError {
+    status: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl std::fmt::Display) -> Self {
+        Self {
+            status: "400 Bad Request",
+            message: message.to_string(),
+        }
+    }
+
+    fn not_found(path: &str) -> Self {
+        Self {
+            status: "404 Not Found",
+            message: format!("Unknown endpoint: {}", path),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self {
+            status: "500 Internal Server Error",
+            message: format!("{:#}", error),
+        }
+    }
+}
+
+pub struct ApiServer {
+    claude_dirs: Vec<PathBuf>,
+    options: ApiOptions,
+}
+
+impl ApiServer {
+    pub fn new(claude_dirs: Vec<PathBuf>, options: ApiOptions) -> Self {
+        Self {
+            claude_dirs,
+            options,
+        }
+    }
+
+    /// Answer a GET request. Parses usage data, so call it off the async workers.
+    pub fn handle_get(
+        &self,
+        path: &str,
+        query: &HashMap<String, String>,
+    ) -> std::result::Result<Value, ApiError> {
+        let params = Params(query);
+        match path.trim_end_matches('/') {
+            "" | "/health" => Ok(json!({
+                "name": "claudelytics",
+                "version": env!("CARGO_PKG_VERSION"),
+                "endpoints": ENDPOINTS
+                    .iter()
+                    .map(|(path, description)| json!({"path": path, "description": description}))
+                    .collect::<Vec<_>>(),
+            })),
+            "/daily" => self.daily(&params),
+            "/sessions" => self.sessions(&params),
+            "/projections" => self.projections(&params),
+            "/blocks" => self.blocks(&params),
+            _ => Err(ApiError::not_found(path)),
+        }
+    }
+
+    fn daily(&self, params: &Params) -> std::result::Result<Value, ApiError> {
+        let (sort_field, sort_order) = (params.sort_field()?, params.sort_order()?);
+        let json_v1 = params.json_v1()?;
+        let parser = self.parser(params, self.options.billing_config)?;
+        let (daily_map, _, _) = parser.parse_all()?;
+        let report = generate_daily_report_sorted(daily_map, sort_field, sort_order);
+        if json_v1 {
+            to_value(&json_v1::DailyReportV1::new(
+                &report,
+                Utc::now(),
+                self.cost_basis(),
+            ))
+        } else {
+            to_value(&report)
+        }
+    }
+
+    fn sessions(&self, params: &Params) -> std::result::Result<Value, ApiError> {
+        let (sort_field, sort_order) = (params.sort_field()?, params.sort_order()?);
+        let json_v1 = params.json_v1()?;
+        let parser = self.parser(params, self.options.billing_config)?;
+        let (_, session_map, _) = parser.parse_all()?;
+        let mut report = generate_session_report_sorted(session_map, sort_field, sort_order);
+        attach_session_summaries(&mut report, &parser.session_summaries());
+        if json_v1 {
+            to_value(&json_v1::SessionReportV1::new(
+                &report,
+                Utc::now(),
+                self.cost_basis(),
+            ))
+        } else {
+            to_value(&report)
+        }
+    }
+
+    fn projections(&self, params: &Params) -> std::result::Result<Value, ApiError> {
+        let days = params.parse::<i64>("days")?.unwrap_or(30);
+        if !(1..=MAX_PROJECTION_DAYS).contains(&days) {
+            return Err(ApiError::bad_request(format!(
+                "days must be between 1 and {}",
+                MAX_PROJECTION_DAYS
+            )));
+        }
+        let token_limit = params.parse::<u64>("token-limit")?;
+        let cost_limit = params
+            .parse::<f64>("cost-limit")?
+            .or(self.options.budget.monthly_limit);
+
+        let parser = self.parser(params, self.options.billing_config)?;
+        let (daily_map, _, _) = parser.parse_all()?;
+        let projection = ProjectionCalculator::new()
+            .with_projection_days(days)
+            .with_limits(token_limit, cost_limit)
+            .with_billing_cycle_start_day(self.options.budget.billing_cycle_start_day)
+            .calculate_projections(&daily_map);
+        to_value(&projection)
+    }
+
+    fn blocks(&self, params: &Params) -> std::result::Result<Value, ApiError> {
+        let mut billing_config = self.options.billing_config;
+        match params.get("mode") {
+            None => {}
+            Some("fixed") => billing_config.anchor = BlockAnchor::Fixed,
+            Some("rolling") => billing_config.anchor = BlockAnchor::Rolling,
+            Some(other) => {
+                return Err(ApiError::bad_request(format!(
+                    "Invalid mode '{}' (expected fixed or rolling)",
+                    other
+                )));
+            }
+        }
+
+        let json_v1 = params.json_v1()?;
+        let parser = self.parser(params, billing_config)?;
+        let (_, _, manager) = parser.parse_all()?;
+        if json_v1 {
+            to_value(&json_v1::BillingBlocksReportV1::new(
+                &manager,
+                Utc::now(),
+                self.cost_basis(),
+            ))
+        } else {
+            to_value(&manager.generate_report())
+        }
+    }
+
+    /// Parser for the request's date range and model filter
+    fn parser(
+        &self,
+        params: &Params,
+        billing_config: BillingBlockConfig,
+    ) -> std::result::Result<UsageParser, ApiError> {
+        let today = Local::now().date_naive();
+        let (since, until) = match params.get("today") {
+            None | Some("false") => (params.date("since", today)?, params.date("until", today)?),
+            Some("" | "true") => {
+                let today = date_range::to_arg(today);
+                (Some(today.clone()), Some(today))
+            }
+            Some(other) => {
+                return Err(ApiError::bad_request(format!(
+                    "Invalid today '{}' (expected true or false)",
+                    other
+                )));
+            }
+        };
+
+        // Construction only fails on invalid dates
+        let parser = UsageParser::new_multi(
+            self.claude_dirs.clone(),
+            since,
+            until,
+            params.get("model-filter").map(str::to_string),
+            self.options.cost_mode,
+        )
+        .map_err(ApiError::bad_request)?
+        .with_parse_cache(self.options.use_parse_cache)
+        .with_billing_config(billing_config)
+        .with_imports(true)
+        .with_archive(self.options.include_archive)
+        .with_dedup(self.options.dedup);
+        Ok(parser)
+    }
+
+    /// Costs are served unconverted, in USD
+    fn cost_basis(&self) -> json_v1::CostBasis<'static> {
+        json_v1::CostBasis {
+            currency: "USD",
+            cost_mode: self.options.cost_mode,
+        }
+    }
+}
+
+/// Query parameters, looked up by their CLI flag name (`sort-by` or `sort_by`)
+struct Params<'a>(&'a HashMap<String, String>);
+
+impl Params<'_> {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .get(name)
+            .or_else(|| self.0.get(&name.replace('-', "_")))
+            .map(String::as_str)
+    }
+
+    fn parse<T: std::str::FromStr>(&self, name: &str) -> std::result::Result<Option<T>, ApiError> {
+        self.get(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| ApiError::bad_request(format!("Invalid {} '{}'", name, value)))
+            })
+            .transpose()
+    }
+
+    /// A `since`/`until` date in any form the CLI accepts, as `YYYYMMDD`
+    fn date(&self, name: &str, today: NaiveDate) -> std::result::Result<Option<String>, ApiError> {
+        self.get(name)
+            .map(|value| {
+                date_range::parse_date(value, today)
+                    .map(date_range::to_arg)
+                    .map_err(ApiError::bad_request)
+            })
+            .transpose()
+    }
+
+    fn sort_field(&self) -> std::result::Result<Option<SortField>, ApiError> {
+        Ok(match self.get("sort-by") {
+            None => None,
+            Some("date") => Some(SortField::Date),
+            Some("cost") => Some(SortField::Cost),
+            Some("tokens") => Some(SortField::Tokens),
+            Some("efficiency") => Some(SortField::Efficiency),
+            Some("project") => Some(SortField::Project),
+            Some(other) => {
+                return Err(ApiError::bad_request(format!(
+                    "Invalid sort-by '{}' (expected date, cost, tokens, efficiency or project)",
+                    other
+                )));
+            }
+        })
+    }
+
+    fn sort_order(&self) -> std::result::Result<Option<SortOrder>, ApiError> {
+        Ok(match self.get("sort-order") {
+            None => None,
+            Some("asc") => Some(SortOrder::Asc),
+            Some("desc") => Some(SortOrder::Desc),
+            Some(other) => {
+                return Err(ApiError::bad_request(format!(
+                    "Invalid sort-order '{}' (expected asc or desc)",
+                    other
+                )));
+            }
+        })
+    }
+
+    fn json_v1(&self) -> std::result::Result<bool, ApiError> {
+        match self.get("format") {
+            None | Some("json") => Ok(false),
+            Some("json-v1") => Ok(true),
+            Some(other) => Err(ApiError::bad_request(format!(
+                "Invalid format '{}' (expected json or json-v1)",
+                other
+            ))),
+        }
+    }
+}
+
+fn to_value<T: serde::Serialize>(report: &T) -> std::result::Result<Value, ApiError> {
+    Ok(serde_json::to_value(report).context("Failed to serialize report")?)
+}
+
+/// Bind to `addr` and serve the API until the process is interrupted
+pub fn run(server: ApiServer, addr: &str) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().context("Failed to start async runtime")?;
+    runtime.block_on(async {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind API server to {}", addr))?;
+        eprintln!(
+            "claudelytics API server listening on http://{}",
+            listener.local_addr()?
+        );
+        serve(Arc::new(server), listener).await
+    })
+}
+
+/// Accept connections on `listener`, handling each client concurrently
+pub async fn serve(server: Arc<ApiServer>, listener: TcpListener) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let server = Arc::clone(&server);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, server).await {
+                eprintln!("Warning: API connection error: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, server: Arc<ApiServer>) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let Some(request) = read_request(&mut reader).await? else {
+        return Ok(());
+    };
+    let mut stream = reader.into_inner();
+
+    let HttpRequest {
+        method,
+        path,
+        query,
+        ..
+    } = request;
+    let (status, body) = match method.as_str() {
+        "OPTIONS" => {
+            return write_response(&mut stream, "204 No Content", "text/plain", "").await;
+        }
+        "GET" => {
+            match tokio::task::spawn_blocking(move || server.handle_get(&path, &query)).await? {
+                Ok(value) => ("200 OK", value),
+                Err(e) => (e.status, json!({ "error": e.message })),
+            }
+        }
+        _ => (
+            "405 Method Not Allowed",
+            json!({ "error": format!("Method {} not allowed", method) }),
+        ),
+    };
+    write_response(&mut stream, status, "application/json", &body.to_string()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn options() -> ApiOptions {
+        ApiOptions {
+            budget: BudgetConfig::default(),
+            billing_config: BillingBlockConfig::default(),
+            cost_mode: CostMode::default(),
+            use_parse_cache: false,
+            dedup: true,
+            include_archive: false,
+        }
+    }
+
+    async fn start_server() -> (tempfile::TempDir, std::net::SocketAddr) {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("projects").join("test-project");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        std::fs::write(
+            project_dir.join("session1.jsonl"),
+            r#"{"timestamp":"2024-01-15T12:00:00Z","costUSD":0.5,"message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":100,"output_tokens":200}}}
+{"timestamp":"2024-01-16T12:00:00Z","costUSD":1.5,"message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":300,"output_tokens":400}}}
+"#,
+        )
+        .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = ApiServer::new(vec![temp_dir.path().to_path_buf()], options());
+        tokio::spawn(serve(Arc::new(server), listener));
+        (temp_dir, addr)
+    }
+
+    /// Send a request and split the response into status line and JSON body
+    async fn request(addr: std::net::SocketAddr, method: &str, target: &str) -> (String, Value) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                format!("{} {} HTTP/1.1\r\nHost: localhost\r\n\r\n", method, target).as_bytes(),
+            )
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.lines().next().unwrap().to_string();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_report_endpoints() {
+        let (_dir, addr) = start_server().await;
+
+        let (status, body) = request(addr, "GET", "/daily?since=20240116").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(body["daily"].as_array().unwrap().len(), 1);
+        assert_eq!(body["daily"][0]["date"], "2024-01-16");
+
+        let (_, body) = request(addr, "GET", "/daily?since=2024-01-16&until=2024-01-16").await;
+        assert_eq!(body["daily"].as_array().unwrap().len(), 1);
+
+        let (status, body) = request(addr, "GET", "/daily?since=yesterday").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert!(body["daily"].as_array().unwrap().is_empty());
+
+        let (_, body) = request(addr, "GET", "/daily?sort_by=cost&sort-order=asc").await;
+        assert_eq!(body["daily"][0]["date"], "2024-01-15");
+
+        let (status, body) = request(addr, "GET", "/sessions?format=json-v1").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(body["schemaVersion"], json_v1::SCHEMA_VERSION);
+        assert_eq!(body["sessions"].as_array().unwrap().len(), 1);
+
+        let (status, body) = request(addr, "GET", "/projections?days=7").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert!(body["daily_average"].is_number());
+
+        let (status, body) = request(addr, "GET", "/blocks?mode=rolling").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert!(body["blocks"].is_array());
+
+        let (status, body) = request(addr, "GET", "/").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(body["endpoints"].as_array().unwrap().len(), ENDPOINTS.len());
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let (_dir, addr) = start_server().await;
+
+        let (status, body) = request(addr, "GET", "/daily?since=2024/01/01").await;
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+        assert!(body["error"].as_str().unwrap().contains("YYYYMMDD"));
+
+        let (status, body) = request(addr, "GET", "/daily?until=99999999+days+ago").await;
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+        assert!(body["error"].as_str().unwrap().contains("99999999"));
+
+        let (status, _) = request(addr, "GET", "/sessions?sort-by=name").await;
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+
+        let (status, _) = request(addr, "GET", "/projections?days=-1").await;
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+
+        let (status, body) = request(addr, "GET", "/projections?days=1000000000").await;
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+        assert!(body["error"].as_str().unwrap().contains("365"));
+
+        let (status, _) = request(addr, "GET", "/monthly").await;
+        assert_eq!(status, "HTTP/1.1 404 Not Found");
+
+        let (status, _) = request(addr, "POST", "/daily").await;
+        assert_eq!(status, "HTTP/1.1 405 Method Not Allowed");
+    }
+}
@@ -0,0 +1,166 @@
+//! Time-of-day cost centers for `--split-by schedule`
+//!
+//! The `schedule` section of the configuration file maps weekdays and local
+//! time windows to named buckets, e.g. `work` on weekdays from 09:00 to 18:00.
+//! Each usage record goes to the bucket of the first matching rule, or to
+//! `default_bucket` when no rule matches. A window whose end is not after its
+//! start runs past midnight, so `22:00`–`02:00` on Friday also covers the early
+//! hours of Saturday.
+
+use crate::config_v2::ScheduleConfig;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, NaiveTime, Utc, Weekday};
+
+pub struct Schedule {
+    rules: Vec<Rule>,
+    default_bucket: String,
+}
+
+struct Rule {
+    bucket: String,
+    /// Empty for every day
+    days: Vec<Weekday>,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl Rule {
+    fn applies_on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    fn matches(&self, at: NaiveDateTime) -> bool {
+        let (day, time) = (at.weekday(), at.time());
+        if self.start < self.end {
+            self.applies_on(day) && (self.start..self.end).contains(&time)
+        } else {
+            // Overnight: the evening part belongs to this day, the rest to the day before
+            (time >= self.start && self.applies_on(day))
+                || (time < self.end && self.applies_on(day.pred()))
+        }
+    }
+}
+
+impl Schedule {
+    pub fn new(config: &ScheduleConfig) -> Result<Self> {
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                if rule.bucket.trim().is_empty() {
+                    bail!(
+                        "schedule rule for {}–{} has no bucket",
+                        rule.start,
+                        rule.end
+                    );
+                }
+                let days = rule
+                    .days
+                    .iter()
+                    .map(|day| {
+                        day.parse::<Weekday>().map_err(|_| {
+                            anyhow::anyhow!(
+                                "invalid weekday '{}' in schedule rule '{}' (use mon..sun)",
+                                day,
+                                rule.bucket
+                            )
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Rule {
+                    bucket: rule.bucket.clone(),
+                    days,
+                    start: parse_time(&rule.start, &rule.bucket)?,
+                    end: parse_time(&rule.end, &rule.bucket)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            rules,
+            default_bucket: config.default_bucket.clone(),
+        })
+    }
+
+    /// Bucket of usage recorded at `timestamp`, judged by local time
+    pub fn bucket_for(&self, timestamp: DateTime<Utc>) -> &str {
+        self.bucket_at(timestamp.with_timezone(&Local).naive_local())
+    }
+
+    fn bucket_at(&self, at: NaiveDateTime) -> &str {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(at))
+            .map_or(self.default_bucket.as_str(), |rule| rule.bucket.as_str())
+    }
+}
+
+fn parse_time(value: &str, bucket: &str) -> Result<NaiveTime> {
+    // 24:00 is the natural way to write "until midnight"
+    if value == "24:00" {
+        return Ok(NaiveTime::MIN);
+    }
+    NaiveTime::parse_from_str(value, "%H:%M").with_context(|| {
+        format!(
+            "invalid time '{}' in schedule rule '{}' (use HH:MM)",
+            value, bucket
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_v2::ScheduleRule;
+    use chrono::NaiveDate;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // 2024-06-03 is a Monday
+        NaiveDate::from_ymd_opt(2024, 6, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_default_schedule() {
+        let schedule = Schedule::new(&ScheduleConfig::default()).unwrap();
+        assert_eq!(schedule.bucket_at(at(3, 9, 0)), "work");
+        assert_eq!(schedule.bucket_at(at(7, 17, 59)), "work");
+        assert_eq!(schedule.bucket_at(at(3, 18, 0)), "personal");
+        assert_eq!(schedule.bucket_at(at(4, 8, 30)), "personal");
+        assert_eq!(schedule.bucket_at(at(8, 12, 0)), "personal");
+    }
+
+    #[test]
+    fn test_overnight_rule_and_order() {
+        let rule = |bucket: &str, days: &[&str], start: &str, end: &str| ScheduleRule {
+            bucket: bucket.to_string(),
+            days: days.iter().map(|d| d.to_string()).collect(),
+            start: start.to_string(),
+            end: end.to_string(),
+        };
+        let config = ScheduleConfig {
+            rules: vec![
+                rule("on-call", &["fri"], "22:00", "02:00"),
+                rule("work", &[], "08:00", "24:00"),
+            ],
+            default_bucket: "other".to_string(),
+        };
+        let schedule = Schedule::new(&config).unwrap();
+        assert_eq!(schedule.bucket_at(at(7, 23, 0)), "on-call");
+        assert_eq!(schedule.bucket_at(at(8, 1, 30)), "on-call");
+        assert_eq!(schedule.bucket_at(at(9, 1, 30)), "other");
+        assert_eq!(schedule.bucket_at(at(6, 23, 0)), "work");
+
+        let invalid = ScheduleConfig {
+            rules: vec![rule("work", &["someday"], "09:00", "18:00")],
+            ..Default::default()
+        };
+        assert!(Schedule::new(&invalid).is_err());
+        let invalid = ScheduleConfig {
+            rules: vec![rule("work", &[], "9am", "18:00")],
+            ..Default::default()
+        };
+        assert!(Schedule::new(&invalid).is_err());
+    }
+}
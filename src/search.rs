@@ -0,0 +1,587 @@
+//! Full-text search across conversation content (`search` command)
+//!
+//! Message text, thinking, tool inputs, and tool results are kept in an on-disk
+//! index under the cache directory (`search_index.json`). Like the parse cache,
+//! each JSONL file is stored with its modification time and size so only new or
+//! changed files are re-read. Every file carries a small inverted index from
+//! word to message, which narrows plain queries down to the messages containing
+//! all query words (matched as word prefixes) before snippets are built. Regex
+//! queries scan the stored text of every message.
+
+use crate::conversation_parser::{ConversationParser, MessageContentBlock};
+use crate::models_registry::ModelsRegistry;
+use crate::parse_cache::FileStamp;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use colored::Colorize;
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Characters kept from each tool result (results can be whole files)
+const MAX_TOOL_RESULT_CHARS: usize = 2000;
+/// Characters of context on each side of the first match in a snippet
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
+/// One searchable message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedMessage {
+    pub uuid: String,
+    pub timestamp: DateTime<Utc>,
+    pub role: String,
+    pub model: Option<String>,
+    pub text: String,
+}
+
+/// Indexed content of a single JSONL file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexedFile {
+    pub modified_nanos: u128,
+    pub size: u64,
+    pub project: String,
+    pub session_id: String,
+    pub messages: Vec<IndexedMessage>,
+    /// Lowercased word -> indices into `messages`
+    pub words: BTreeMap<String, Vec<u32>>,
+}
+
+impl IndexedFile {
+    fn build(path: &Path, stamp: FileStamp, parser: &ConversationParser) -> Result<Self> {
+        let conversation = parser.parse_conversation(path)?;
+        let messages: Vec<IndexedMessage> = conversation
+            .messages
+            .iter()
+            .filter_map(|message| {
+                let text = message_text(&message.content);
+                (!text.trim().is_empty()).then(|| IndexedMessage {
+                    uuid: message.uuid.clone(),
+                    timestamp: message.timestamp,
+                    role: message.role.clone(),
+                    model: message.model.clone(),
+                    text,
+                })
+            })
+            .collect();
+
+        let mut words: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+        for (index, message) in messages.iter().enumerate() {
+            let unique: BTreeSet<String> = tokenize(&message.text).collect();
+            for word in unique {
+                words.entry(word).or_default().push(index as u32);
+            }
+        }
+
+        Ok(Self {
+            modified_nanos: stamp.modified_nanos,
+            size: stamp.size,
+            project: path
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            session_id: path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            messages,
+            words,
+        })
+    }
+
+    /// Messages containing a word starting with each of `terms`
+    fn candidates(&self, terms: &[String]) -> BTreeSet<u32> {
+        let mut result: Option<BTreeSet<u32>> = None;
+        for term in terms {
+            let matching: BTreeSet<u32> = self
+                .words
+                .range(term.clone()..)
+                .take_while(|(word, _)| word.starts_with(term.as_str()))
+                .flat_map(|(_, messages)| messages.iter().copied())
+                .collect();
+            result = Some(match result {
+                Some(previous) => previous.intersection(&matching).copied().collect(),
+                None => matching,
+            });
+        }
+        result.unwrap_or_default()
+    }
+}
+
+/// Text, thinking, tool inputs, and (truncated) tool results of a message
+fn message_text(content: &[MessageContentBlock]) -> String {
+    let parts: Vec<String> = content
+        .iter()
+        .map(|block| match block {
+            MessageContentBlock::Text { text, .. } => text.clone(),
+            MessageContentBlock::ToolUse { name, input, .. } => format!("{} {}", name, input),
+            MessageContentBlock::ToolResult { content, .. } => {
+                content.chars().take(MAX_TOOL_RESULT_CHARS).collect()
+            }
+        })
+        .collect();
+    parts.join("\n")
+}
+
+/// Lowercased words of at least two characters
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| word.chars().count() >= 2)
+        .map(|word| word.to_lowercase())
+}
+
+/// Search index persisted between runs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchIndex {
+    pub version: String,
+    pub files: HashMap<PathBuf, IndexedFile>,
+}
+
+impl Default for SearchIndex {
+    fn default() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            files: HashMap::new(),
+        }
+    }
+}
+
+/// Files re-read by the last `update`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IndexUpdate {
+    pub indexed: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+impl SearchIndex {
+    /// Get index file path
+    pub fn get_index_path() -> Result<PathBuf> {
+        let cache_dir = dirs::cache_dir()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".cache")))
+            .ok_or_else(|| anyhow::anyhow!("Could not determine cache directory"))?;
+
+        Ok(cache_dir.join("claudelytics").join("search_index.json"))
+    }
+
+    /// Load the index, starting fresh if it is missing, corrupt, or from another version
+    pub fn load() -> Self {
+        Self::get_index_path()
+            .ok()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|data| serde_json::from_slice::<SearchIndex>(&data).ok())
+            .filter(|index| index.version == env!("CARGO_PKG_VERSION"))
+            .unwrap_or_default()
+    }
+
+    /// Save the index atomically
+    pub fn save(&self) -> Result<()> {
+        let path = Self::get_index_path()?;
+        let dir = path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Invalid index path: {}", path.display()))?;
+        fs::create_dir_all(dir)?;
+
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        serde_json::to_writer(&mut tmp, self).context("Failed to serialize search index")?;
+        tmp.persist(&path)
+            .with_context(|| format!("Failed to write search index: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Re-index new and changed conversation files under `claude_dir` and drop
+    /// files that no longer exist there
+    pub fn update(&mut self, claude_dir: &Path) -> Result<IndexUpdate> {
+        let parser = ConversationParser::new(claude_dir.to_path_buf());
+        let files = parser.find_conversation_files()?;
+        let mut update = IndexUpdate::default();
+
+        let stale: Vec<(PathBuf, FileStamp)> = files
+            .into_iter()
+            .filter_map(|path| {
+                let stamp = FileStamp::of(&path).ok()?;
+                let fresh = self.files.get(&path).is_some_and(|file| {
+                    file.modified_nanos == stamp.modified_nanos && file.size == stamp.size
+                });
+                if fresh {
+                    update.unchanged += 1;
+                    None
+                } else {
+                    Some((path, stamp))
+                }
+            })
+            .collect();
+
+        let indexed: Vec<(PathBuf, IndexedFile)> = stale
+            .into_par_iter()
+            .filter_map(|(path, stamp)| {
+                let file = IndexedFile::build(&path, stamp, &parser).ok()?;
+                Some((path, file))
+            })
+            .collect();
+        update.indexed = indexed.len();
+        self.files.extend(indexed);
+
+        let before = self.files.len();
+        self.files
+            .retain(|path, _| !path.starts_with(claude_dir) || path.exists());
+        update.removed = before - self.files.len();
+
+        Ok(update)
+    }
+
+    /// Messages matching `query`, newest first
+    pub fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>> {
+        let matcher = Matcher::new(&query.pattern, query.regex)?;
+        let registry = ModelsRegistry::new();
+
+        let mut hits: Vec<SearchHit> = Vec::new();
+        for file in self.files.values() {
+            if query
+                .project
+                .as_ref()
+                .is_some_and(|project| !file.project.contains(project.as_str()))
+            {
+                continue;
+            }
+
+            let candidates: Box<dyn Iterator<Item = &IndexedMessage>> = match matcher.terms() {
+                Some(terms) => Box::new(
+                    file.candidates(terms)
+                        .into_iter()
+                        .filter_map(|index| file.messages.get(index as usize)),
+                ),
+                None => Box::new(file.messages.iter()),
+            };
+
+            for message in candidates {
+                let date = message.timestamp.with_timezone(&Local).date_naive();
+                if query.since.is_some_and(|since| date < since)
+                    || query.until.is_some_and(|until| date > until)
+                {
+                    continue;
+                }
+                if let Some(filter) = &query.model_filter
+                    && !message
+                        .model
+                        .as_deref()
+                        .is_some_and(|model| registry.matches_filter(model, filter))
+                {
+                    continue;
+                }
+                let Some(first) = matcher.first_match(&message.text) else {
+                    continue;
+                };
+
+                let (snippet, matches) = build_snippet(&message.text, first, &matcher);
+                hits.push(SearchHit {
+                    project: file.project.clone(),
+                    session_id: file.session_id.clone(),
+                    uuid: message.uuid.clone(),
+                    timestamp: message.timestamp,
+                    role: message.role.clone(),
+                    model: message.model.clone(),
+                    snippet,
+                    matches,
+                });
+            }
+        }
+
+        hits.sort_by_key(|hit| std::cmp::Reverse(hit.timestamp));
+        Ok(hits)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    pub pattern: String,
+    /// Treat `pattern` as a regular expression instead of words
+    pub regex: bool,
+    /// Only projects whose directory name contains this
+    pub project: Option<String>,
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    /// Only messages written by a matching model
+    pub model_filter: Option<String>,
+}
+
+/// A matching message with a one-line snippet around the first match
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub project: String,
+    pub session_id: String,
+    pub uuid: String,
+    pub timestamp: DateTime<Utc>,
+    pub role: String,
+    pub model: Option<String>,
+    pub snippet: String,
+    /// Byte ranges of the matches within `snippet`
+    pub matches: Vec<(usize, usize)>,
+}
+
+enum Matcher {
+    /// Every word must occur as a word prefix; `any` finds each whole matching word
+    Words {
+        terms: Vec<String>,
+        each: Vec<Regex>,
+        any: Regex,
+    },
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(pattern: &str, regex: bool) -> Result<Self> {
+        if regex {
+            let regex = RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .with_context(|| format!("Invalid regex: {}", pattern))?;
+            return Ok(Self::Regex(regex));
+        }
+
+        let terms: Vec<String> = tokenize(pattern)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if terms.is_empty() {
+            // Only punctuation or single characters: plain substring search
+            let regex = RegexBuilder::new(&regex::escape(pattern.trim()))
+                .case_insensitive(true)
+                .build()?;
+            return Ok(Self::Regex(regex));
+        }
+        let word = |term: &str| {
+            RegexBuilder::new(&format!(r"\b{}", regex::escape(term)))
+                .case_insensitive(true)
+                .build()
+        };
+        let each = terms
+            .iter()
+            .map(|term| word(term))
+            .collect::<Result<Vec<_>, _>>()?;
+        let any = RegexBuilder::new(&format!(
+            r"\b(?:{})\w*",
+            terms
+                .iter()
+                .map(|term| regex::escape(term))
+                .collect::<Vec<_>>()
+                .join("|")
+        ))
+        .case_insensitive(true)
+        .build()?;
+        Ok(Self::Words { terms, each, any })
+    }
+
+    /// Words usable with the inverted index; `None` means scan every message
+    fn terms(&self) -> Option<&[String]> {
+        match self {
+            Self::Words { terms, .. } => Some(terms),
+            Self::Regex(_) => None,
+        }
+    }
+
+    fn first_match(&self, text: &str) -> Option<(usize, usize)> {
+        match self {
+            Self::Words { each, any, .. } => {
+                if !each.iter().all(|regex| regex.is_match(text)) {
+                    return None;
+                }
+                any.find(text).map(|m| (m.start(), m.end()))
+            }
+            Self::Regex(regex) => regex
+                .find_iter(text)
+                .find(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end())),
+        }
+    }
+
+    fn find_all(&self, text: &str) -> Vec<(usize, usize)> {
+        let regex = match self {
+            Self::Words { any, .. } => any,
+            Self::Regex(regex) => regex,
+        };
+        regex
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| (m.start(), m.end()))
+            .collect()
+    }
+}
+
+/// One-line window of `text` around the `first` match, with match ranges in it
+fn build_snippet(
+    text: &str,
+    first: (usize, usize),
+    matcher: &Matcher,
+) -> (String, Vec<(usize, usize)>) {
+    let start = text[..first.0]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT_CHARS - 1)
+        .map_or(0, |(i, _)| i);
+    let end = text[first.1..]
+        .char_indices()
+        .nth(SNIPPET_CONTEXT_CHARS)
+        .map_or(text.len(), |(i, _)| first.1 + i);
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.push_str(&text[start..end].replace(['\n', '\r', '\t'], " "));
+    if end < text.len() {
+        snippet.push('…');
+    }
+    let matches = matcher.find_all(&snippet);
+    (snippet, matches)
+}
+
+pub fn display_search_results(hits: &[SearchHit], limit: usize, json: bool) {
+    if json {
+        let shown = &hits[..hits.len().min(limit)];
+        match serde_json::to_string_pretty(shown) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    if hits.is_empty() {
+        println!("{}", "No matches found".yellow());
+        return;
+    }
+
+    for hit in hits.iter().take(limit) {
+        let role = match hit.role.as_str() {
+            "user" => hit.role.green(),
+            "assistant" => hit.role.cyan(),
+            _ => hit.role.normal(),
+        };
+        println!(
+            "{}  {}  {}/{}{}",
+            hit.timestamp
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .dimmed(),
+            role,
+            hit.project.bright_blue(),
+            hit.session_id,
+            hit.model
+                .as_ref()
+                .map(|m| format!("  {}", m).dimmed().to_string())
+                .unwrap_or_default()
+        );
+        println!("  {}", highlight(&hit.snippet, &hit.matches));
+    }
+
+    println!(
+        "\n{}",
+        if hits.len() > limit {
+            format!(
+                "Showing {} of {} matches (use --limit to see more)",
+                limit,
+                hits.len()
+            )
+        } else {
+            format!("{} matches", hits.len())
+        }
+        .dimmed()
+    );
+}
+
+fn highlight(snippet: &str, matches: &[(usize, usize)]) -> String {
+    let mut out = String::new();
+    let mut pos = 0;
+    for &(start, end) in matches {
+        out.push_str(&snippet[pos..start]);
+        out.push_str(&snippet[start..end].black().on_yellow().bold().to_string());
+        pos = end;
+    }
+    out.push_str(&snippet[pos..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn index_with_session() -> (TempDir, SearchIndex) {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("projects").join("-home-me-app");
+        fs::create_dir_all(&project).unwrap();
+        let lines = [
+            r#"{"uuid":"u1","parentUuid":null,"type":"user","timestamp":"2024-01-01T10:00:00Z","sessionId":"s1","message":{"role":"user","content":"Why does the parser panic on empty lines?"}}"#,
+            r#"{"uuid":"a1","parentUuid":"u1","type":"assistant","timestamp":"2024-01-02T10:00:05Z","sessionId":"s1","message":{"role":"assistant","model":"claude-opus-4-20250514","content":[{"type":"text","text":"The Parser calls unwrap() on an empty line."},{"type":"tool_use","id":"t1","name":"Edit","input":{"file_path":"src/parser.rs"}}]}}"#,
+        ];
+        fs::write(project.join("s1.jsonl"), lines.join("\n")).unwrap();
+
+        let mut index = SearchIndex::default();
+        let update = index.update(dir.path()).unwrap();
+        assert_eq!((update.indexed, update.unchanged), (1, 0));
+        (dir, index)
+    }
+
+    #[test]
+    fn test_word_search_uses_prefixes_and_filters() {
+        let (dir, mut index) = index_with_session();
+        let query = |pattern: &str| SearchQuery {
+            pattern: pattern.to_string(),
+            ..Default::default()
+        };
+
+        let hits = index.search(&query("pars empty")).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].uuid, "a1");
+        let (start, end) = hits[0].matches[0];
+        assert_eq!(&hits[0].snippet[start..end], "Parser");
+
+        assert!(index.search(&query("parser missing")).unwrap().is_empty());
+
+        let opus = SearchQuery {
+            model_filter: Some("opus".to_string()),
+            ..query("parser")
+        };
+        assert_eq!(index.search(&opus).unwrap().len(), 1);
+        let later = SearchQuery {
+            since: NaiveDate::from_ymd_opt(2024, 1, 2),
+            ..query("parser")
+        };
+        assert_eq!(index.search(&later).unwrap().len(), 1);
+        let other = SearchQuery {
+            project: Some("other".to_string()),
+            ..query("parser")
+        };
+        assert!(index.search(&other).unwrap().is_empty());
+
+        // Unchanged files are not re-read
+        let update = index.update(dir.path()).unwrap();
+        assert_eq!((update.indexed, update.unchanged), (0, 1));
+    }
+
+    #[test]
+    fn test_regex_search() {
+        let (_dir, index) = index_with_session();
+        let hits = index
+            .search(&SearchQuery {
+                pattern: r"unwrap\(\)|src/\w+\.rs".to_string(),
+                regex: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].matches.len(), 2);
+        assert!(
+            SearchIndex::default()
+                .search(&SearchQuery {
+                    pattern: "(".to_string(),
+                    regex: true,
+                    ..Default::default()
+                })
+                .is_err()
+        );
+    }
+}
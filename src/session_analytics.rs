@@ -1,7 +1,23 @@
-use crate::models::{SessionUsageMap, TokenUsage};
-use chrono::{DateTime, Datelike, Duration, Local, Timelike, Utc, Weekday};
+use crate::currency::Money;
+use crate::display::format_number;
+use crate::models::{DailyUsageMap, SessionUsageMap, TokenUsage};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike, Utc, Weekday};
+use colored::Colorize;
+use serde::Serialize;
 use std::collections::HashMap;
 
+/// Output tokens per dollar scoring zero and full marks (log scale in between)
+const OUTPUT_PER_DOLLAR_FLOOR: f64 = 100.0;
+const OUTPUT_PER_DOLLAR_TARGET: f64 = 20_000.0;
+/// Weights of output per dollar, cache hit ratio, and non-thinking output in the score
+const OUTPUT_WEIGHT: f64 = 0.4;
+const CACHE_WEIGHT: f64 = 0.4;
+const THINKING_WEIGHT: f64 = 0.2;
+/// Sessions with fewer messages are left off the leaderboard
+const LEADERBOARD_MIN_MESSAGES: u64 = 3;
+/// Most recent days listed in the thinking breakdown
+const THINKING_RECENT_DAYS: usize = 14;
+
 /// Advanced session analytics for Claude Code usage patterns
 pub struct SessionAnalytics<'a> {
     sessions: &'a SessionUsageMap,
@@ -92,6 +108,100 @@ pub struct ModelSwitchingAnalysis {
     pub model_loyalty_score: f64, // 0-1, higher means more loyal to single model
 }
 
+/// Composite efficiency of a session, 0 (worst) to 100 (best)
+///
+/// Combines output tokens per dollar (log scale from 100 to 20,000), the cache
+/// hit ratio (cache reads over all input), and the share of output that was
+/// not extended thinking.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct EfficiencyScore {
+    pub score: f64,
+    pub output_tokens_per_dollar: f64,
+    /// Cache reads over input + cache creation + cache reads (0-1)
+    pub cache_hit_ratio: f64,
+    /// Estimated share of output tokens spent on thinking (0-1)
+    pub thinking_share: f64,
+}
+
+impl EfficiencyScore {
+    /// `None` for usage without cost or output to score
+    pub fn of(usage: &TokenUsage) -> Option<Self> {
+        if usage.total_cost <= 0.0 || usage.output_tokens == 0 {
+            return None;
+        }
+        let output_tokens_per_dollar = usage.output_tokens as f64 / usage.total_cost;
+        let all_input = usage.input_tokens + usage.cache_creation_tokens + usage.cache_read_tokens;
+        let cache_hit_ratio = if all_input > 0 {
+            usage.cache_read_tokens as f64 / all_input as f64
+        } else {
+            0.0
+        };
+        let thinking_share = (usage.thinking_tokens as f64 / usage.output_tokens as f64).min(1.0);
+
+        let output_component = ((output_tokens_per_dollar.ln() - OUTPUT_PER_DOLLAR_FLOOR.ln())
+            / (OUTPUT_PER_DOLLAR_TARGET.ln() - OUTPUT_PER_DOLLAR_FLOOR.ln()))
+        .clamp(0.0, 1.0);
+        let score = 100.0
+            * (OUTPUT_WEIGHT * output_component
+                + CACHE_WEIGHT * cache_hit_ratio
+                + THINKING_WEIGHT * (1.0 - thinking_share));
+
+        Some(Self {
+            score,
+            output_tokens_per_dollar,
+            cache_hit_ratio,
+            thinking_share,
+        })
+    }
+
+    /// Suggestions for the weakest parts of the score
+    pub fn tips(&self) -> Vec<String> {
+        let mut tips = Vec::new();
+        if self.cache_hit_ratio < 0.5 {
+            tips.push(format!(
+                "Only {:.0}% of input came from cache: keep working in one session instead of restarting, and avoid editing CLAUDE.md or switching models mid-session",
+                self.cache_hit_ratio * 100.0
+            ));
+        }
+        if self.output_tokens_per_dollar < 1_000.0 {
+            tips.push(format!(
+                "{:.0} output tokens per dollar: most spend went to re-reading context; /compact long conversations and /clear between unrelated tasks",
+                self.output_tokens_per_dollar
+            ));
+        }
+        if self.thinking_share > 0.4 {
+            tips.push(format!(
+                "About {:.0}% of output was extended thinking: reserve thinking for hard problems",
+                self.thinking_share * 100.0
+            ));
+        }
+        tips
+    }
+}
+
+/// A session ranked on the efficiency leaderboard
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoredSession {
+    pub session: String,
+    /// Summary record or first prompt, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub messages: u64,
+    pub total_tokens: u64,
+    pub cost: f64,
+    pub efficiency: EfficiencyScore,
+    pub tips: Vec<String>,
+}
+
+/// Best and worst scoring sessions
+#[derive(Debug, Clone, Serialize)]
+pub struct EfficiencyLeaderboard {
+    pub sessions_scored: usize,
+    pub average_score: f64,
+    pub best: Vec<ScoredSession>,
+    pub worst: Vec<ScoredSession>,
+}
+
 impl<'a> SessionAnalytics<'a> {
     pub fn new(sessions: &'a SessionUsageMap) -> Self {
         Self { sessions }
@@ -287,8 +397,8 @@ impl<'a> SessionAnalytics<'a> {
 
     /// Analyze session frequency patterns
     pub fn analyze_session_frequency(&self) -> SessionFrequencyAnalysis {
-        let mut daily_sessions: HashMap<chrono::NaiveDate, usize> = HashMap::new();
-        let mut all_dates: Vec<chrono::NaiveDate> = Vec::new();
+        let mut daily_sessions: HashMap<NaiveDate, usize> = HashMap::new();
+        let mut all_dates: Vec<NaiveDate> = Vec::new();
 
         // Count sessions per day
         for (_usage, timestamp) in self.sessions.values() {
@@ -304,29 +414,7 @@ impl<'a> SessionAnalytics<'a> {
         let total_sessions = self.sessions.len();
 
         // Calculate streaks
-        let (longest_streak, current_streak) = if !all_dates.is_empty() {
-            let mut longest = 1;
-            let mut current = 1;
-            let today = Local::now().date_naive();
-
-            for i in 1..all_dates.len() {
-                if all_dates[i] - all_dates[i - 1] == Duration::days(1) {
-                    current += 1;
-                    longest = longest.max(current);
-                } else {
-                    current = 1;
-                }
-            }
-
-            // Check if current streak is still active
-            let last_date = all_dates.last().unwrap();
-            let days_since_last = (today - *last_date).num_days();
-            let current_streak = if days_since_last <= 1 { current } else { 0 };
-
-            (longest, current_streak)
-        } else {
-            (0, 0)
-        };
+        let (longest_streak, current_streak) = usage_streaks(&all_dates, Local::now().date_naive());
 
         // Calculate averages
         let first_date = all_dates
@@ -457,6 +545,280 @@ impl<'a> SessionAnalytics<'a> {
     }
 }
 
+impl SessionAnalytics<'_> {
+    /// Rank sessions with at least a few messages by efficiency score; `best`
+    /// and `worst` hold up to `limit` sessions each and never overlap
+    pub fn efficiency_leaderboard(
+        &self,
+        limit: usize,
+        summaries: &HashMap<String, String>,
+    ) -> EfficiencyLeaderboard {
+        let mut scored: Vec<ScoredSession> = self
+            .sessions
+            .iter()
+            .filter(|(_, (usage, _))| usage.message_count >= LEADERBOARD_MIN_MESSAGES)
+            .filter_map(|(path, (usage, _))| {
+                let efficiency = EfficiencyScore::of(usage)?;
+                Some(ScoredSession {
+                    session: path.clone(),
+                    summary: summaries.get(path).cloned(),
+                    messages: usage.message_count,
+                    total_tokens: usage.total_tokens(),
+                    cost: usage.total_cost,
+                    efficiency,
+                    tips: efficiency.tips(),
+                })
+            })
+            .collect();
+        scored.sort_by(|a, b| {
+            b.efficiency
+                .score
+                .total_cmp(&a.efficiency.score)
+                .then_with(|| a.session.cmp(&b.session))
+        });
+
+        let sessions_scored = scored.len();
+        let average_score = if scored.is_empty() {
+            0.0
+        } else {
+            scored.iter().map(|s| s.efficiency.score).sum::<f64>() / sessions_scored as f64
+        };
+        let best_count = limit.min(sessions_scored);
+        let worst_count = limit.min(sessions_scored - best_count);
+        let mut worst = scored.split_off(sessions_scored - worst_count);
+        worst.reverse();
+        scored.truncate(best_count);
+
+        EfficiencyLeaderboard {
+            sessions_scored,
+            average_score,
+            best: scored,
+            worst,
+        }
+    }
+}
+
+/// Estimated thinking tokens against all output of one model, day, or session
+#[derive(Debug, Clone, Serialize)]
+pub struct ThinkingShare {
+    pub name: String,
+    pub thinking_tokens: u64,
+    pub output_tokens: u64,
+    /// Thinking over output tokens (0-1)
+    pub share: f64,
+}
+
+impl ThinkingShare {
+    fn of(name: impl Into<String>, usage: &TokenUsage) -> Self {
+        let share = if usage.output_tokens > 0 {
+            (usage.thinking_tokens as f64 / usage.output_tokens as f64).min(1.0)
+        } else {
+            0.0
+        };
+        Self {
+            name: name.into(),
+            thinking_tokens: usage.thinking_tokens,
+            output_tokens: usage.output_tokens,
+            share,
+        }
+    }
+}
+
+/// How much of the output was extended thinking, overall and per model, day, and session
+#[derive(Debug, Clone, Serialize)]
+pub struct ThinkingBreakdown {
+    pub total: ThinkingShare,
+    /// Models with any thinking, most thinking first
+    pub by_model: Vec<ThinkingShare>,
+    /// The most recent days with output, newest first
+    pub recent_days: Vec<ThinkingShare>,
+    /// Sessions with the most thinking tokens
+    pub top_sessions: Vec<ThinkingShare>,
+}
+
+impl SessionAnalytics<'_> {
+    /// Thinking token share of the output, with the `limit` sessions that thought the most
+    pub fn thinking_breakdown(&self, daily: &DailyUsageMap, limit: usize) -> ThinkingBreakdown {
+        let mut total = TokenUsage::default();
+        let mut by_model: HashMap<&str, TokenUsage> = HashMap::new();
+        for (usage, _) in self.sessions.values() {
+            total.add(usage);
+            for (model, model_usage) in &usage.by_model {
+                by_model.entry(model).or_default().add(model_usage);
+            }
+        }
+
+        let mut by_model: Vec<ThinkingShare> = by_model
+            .into_iter()
+            .filter(|(_, usage)| usage.thinking_tokens > 0)
+            .map(|(model, usage)| ThinkingShare::of(model, &usage))
+            .collect();
+        by_model.sort_by(|a, b| {
+            b.thinking_tokens
+                .cmp(&a.thinking_tokens)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let mut days: Vec<_> = daily
+            .iter()
+            .filter(|(_, usage)| usage.output_tokens > 0)
+            .collect();
+        days.sort_by(|a, b| b.0.cmp(a.0));
+        let recent_days = days
+            .into_iter()
+            .take(THINKING_RECENT_DAYS)
+            .map(|(date, usage)| ThinkingShare::of(date.to_string(), usage))
+            .collect();
+
+        let mut top_sessions: Vec<ThinkingShare> = self
+            .sessions
+            .iter()
+            .filter(|(_, (usage, _))| usage.thinking_tokens > 0)
+            .map(|(path, (usage, _))| ThinkingShare::of(path.as_str(), usage))
+            .collect();
+        top_sessions.sort_by(|a, b| {
+            b.thinking_tokens
+                .cmp(&a.thinking_tokens)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        top_sessions.truncate(limit);
+
+        ThinkingBreakdown {
+            total: ThinkingShare::of("total", &total),
+            by_model,
+            recent_days,
+            top_sessions,
+        }
+    }
+}
+
+pub fn display_thinking_breakdown(breakdown: &ThinkingBreakdown, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(breakdown) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "💭 Extended Thinking".bold().cyan());
+    println!("{}", "═".repeat(50).blue());
+    if breakdown.total.thinking_tokens == 0 {
+        println!("No thinking blocks found in the usage data");
+        return;
+    }
+    println!(
+        "{} of {} output tokens were thinking ({:.1}%)",
+        format_number(breakdown.total.thinking_tokens).bold(),
+        format_number(breakdown.total.output_tokens),
+        breakdown.total.share * 100.0
+    );
+    println!(
+        "{}",
+        "Estimated from thinking block length (~4 characters per token); billed as output".dimmed()
+    );
+
+    print_thinking_section("🤖 By model", &breakdown.by_model);
+    print_thinking_section("📅 Recent days", &breakdown.recent_days);
+    print_thinking_section(
+        "🔝 Sessions with the most thinking",
+        &breakdown.top_sessions,
+    );
+    println!("\n{}", "═".repeat(50).blue());
+}
+
+fn print_thinking_section(title: &str, rows: &[ThinkingShare]) {
+    if rows.is_empty() {
+        return;
+    }
+    println!("\n{}", title.bold());
+    println!("{}", "─".repeat(40));
+    for row in rows {
+        let share = format!("{:>5.1}%", row.share * 100.0);
+        let share = if row.share > 0.4 {
+            share.red()
+        } else if row.share > 0.2 {
+            share.yellow()
+        } else {
+            share.green()
+        };
+        println!(
+            "  {} {:>10} / {:<10} {}",
+            share,
+            format_number(row.thinking_tokens),
+            format_number(row.output_tokens),
+            row.name
+        );
+    }
+}
+
+pub fn display_efficiency_leaderboard(leaderboard: &EfficiencyLeaderboard, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(leaderboard) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "🏅 Session Efficiency Leaderboard".bold().cyan());
+    println!("{}", "═".repeat(50).blue());
+    if leaderboard.sessions_scored == 0 {
+        println!(
+            "No sessions with at least {} messages to score",
+            LEADERBOARD_MIN_MESSAGES
+        );
+        return;
+    }
+    println!(
+        "{} sessions scored, average score {:.0}/100",
+        leaderboard.sessions_scored, leaderboard.average_score
+    );
+    println!(
+        "{}",
+        "Score: 40% output tokens/$, 40% cache hit ratio, 20% non-thinking output".dimmed()
+    );
+
+    print_leaderboard_section("🥇 Most efficient", &leaderboard.best, false);
+    if !leaderboard.worst.is_empty() {
+        print_leaderboard_section("🐢 Least efficient", &leaderboard.worst, true);
+    }
+    println!("\n{}", "═".repeat(50).blue());
+}
+
+fn print_leaderboard_section(title: &str, sessions: &[ScoredSession], with_tips: bool) {
+    println!("\n{}", title.bold());
+    println!("{}", "─".repeat(40));
+    for (rank, session) in sessions.iter().enumerate() {
+        let score = format!("{:>3.0}", session.efficiency.score);
+        let score = if session.efficiency.score >= 70.0 {
+            score.green()
+        } else if session.efficiency.score >= 40.0 {
+            score.yellow()
+        } else {
+            score.red()
+        };
+        println!(
+            "{:>2}. {} {}  {:.2}, {:.0} out/$, {:.0}% cache, {:.0}% thinking",
+            rank + 1,
+            score.bold(),
+            session.session,
+            Money(session.cost),
+            session.efficiency.output_tokens_per_dollar,
+            session.efficiency.cache_hit_ratio * 100.0,
+            session.efficiency.thinking_share * 100.0
+        );
+        if let Some(summary) = &session.summary {
+            println!("      {}", summary.dimmed());
+        }
+        if with_tips {
+            for tip in &session.tips {
+                println!("      💡 {}", tip);
+            }
+        }
+    }
+}
+
 /// Extract session key from full path
 fn extract_session_key(path: &str) -> String {
     // Extract the session identifier from the path
@@ -468,6 +830,33 @@ fn extract_session_key(path: &str) -> String {
     }
 }
 
+/// Longest and current run of consecutive active days in sorted, deduplicated
+/// `dates`. The current streak counts as active if the last date is today or
+/// yesterday.
+pub fn usage_streaks(dates: &[NaiveDate], today: NaiveDate) -> (usize, usize) {
+    let Some(last_date) = dates.last() else {
+        return (0, 0);
+    };
+
+    let mut longest = 1;
+    let mut current = 1;
+    for pair in dates.windows(2) {
+        if pair[1] - pair[0] == Duration::days(1) {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 1;
+        }
+    }
+
+    let current_streak = if (today - *last_date).num_days() <= 1 {
+        current
+    } else {
+        0
+    };
+    (longest, current_streak)
+}
+
 /// Format duration for display
 pub fn format_duration(duration: &Duration) -> String {
     let total_seconds = duration.num_seconds();
@@ -483,3 +872,102 @@ pub fn format_duration(duration: &Duration) -> String {
         format!("{}s", seconds)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UsageRecord;
+
+    fn usage(output: u64, cache_read: u64, thinking: u64, cost: f64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: 1_000,
+            output_tokens: output,
+            cache_read_tokens: cache_read,
+            thinking_tokens: thinking,
+            total_cost: cost,
+            message_count: 5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_efficiency_score() {
+        let record: UsageRecord = serde_json::from_str(
+            r#"{"timestamp":"2024-01-15T12:00:00Z","message":{"model":"claude-opus-4","content":[{"type":"thinking","thinking":"abcdefgh"},{"type":"text","text":"done"}],"usage":{"input_tokens":10,"output_tokens":4}}}"#,
+        )
+        .unwrap();
+        assert_eq!(TokenUsage::from(&record).thinking_tokens, 2);
+
+        let great = EfficiencyScore::of(&usage(20_000, 9_000, 0, 1.0)).unwrap();
+        assert!((great.cache_hit_ratio - 0.9).abs() < 1e-9);
+        assert!((great.score - 96.0).abs() < 1e-9);
+        assert!(great.tips().is_empty());
+
+        let poor = EfficiencyScore::of(&usage(100, 0, 100, 1.0)).unwrap();
+        assert_eq!(poor.score, 0.0);
+        assert_eq!(poor.tips().len(), 3);
+        assert!(EfficiencyScore::of(&usage(100, 0, 0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_efficiency_leaderboard() {
+        let now = Utc::now();
+        let mut sessions = SessionUsageMap::new();
+        for (name, output) in [("a/1", 20_000), ("a/2", 2_000), ("b/3", 200)] {
+            sessions.insert(name.to_string(), (usage(output, 0, 0, 1.0), now));
+        }
+        let mut short = usage(50_000, 0, 0, 1.0);
+        short.message_count = 1;
+        sessions.insert("b/4".to_string(), (short, now));
+        let summaries = HashMap::from([("a/1".to_string(), "Fix tests".to_string())]);
+
+        let leaderboard = SessionAnalytics::new(&sessions).efficiency_leaderboard(2, &summaries);
+        assert_eq!(leaderboard.sessions_scored, 3);
+        let best: Vec<&str> = leaderboard
+            .best
+            .iter()
+            .map(|s| s.session.as_str())
+            .collect();
+        assert_eq!(best, ["a/1", "a/2"]);
+        assert_eq!(leaderboard.best[0].summary.as_deref(), Some("Fix tests"));
+        let worst: Vec<&str> = leaderboard
+            .worst
+            .iter()
+            .map(|s| s.session.as_str())
+            .collect();
+        assert_eq!(worst, ["b/3"]);
+    }
+
+    #[test]
+    fn test_thinking_breakdown() {
+        let now = Utc::now();
+        let mut sessions = SessionUsageMap::new();
+        sessions.insert(
+            "a/1".to_string(),
+            (
+                usage(1_000, 0, 600, 1.0).tagged("claude-opus-4-20250514", "a/1"),
+                now,
+            ),
+        );
+        sessions.insert(
+            "b/2".to_string(),
+            (
+                usage(1_000, 0, 0, 1.0).tagged("claude-sonnet-4-20250514", "b/2"),
+                now,
+            ),
+        );
+        let day = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let daily = DailyUsageMap::from([(day, usage(2_000, 0, 600, 2.0))]);
+
+        let breakdown = SessionAnalytics::new(&sessions).thinking_breakdown(&daily, 5);
+        assert_eq!(breakdown.total.thinking_tokens, 600);
+        assert_eq!(breakdown.total.output_tokens, 2_000);
+        assert!((breakdown.total.share - 0.3).abs() < 1e-9);
+        assert_eq!(breakdown.by_model.len(), 1);
+        assert_eq!(breakdown.by_model[0].name, "claude-opus-4-20250514");
+        assert_eq!(breakdown.recent_days[0].name, "2024-01-15");
+        assert_eq!(breakdown.top_sessions.len(), 1);
+        assert_eq!(breakdown.top_sessions[0].name, "a/1");
+        assert!((breakdown.top_sessions[0].share - 0.6).abs() < 1e-9);
+    }
+}
@@ -1,7 +1,10 @@
+//! Grouping usage into activity blocks separated by idle gaps (`blocks` command)
+
 use chrono::{DateTime, Duration, Timelike, Utc};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use crate::burn_rate::UsageEvent;
 use crate::models::TokenUsage;
 
 /// Configurable session block for analyzing usage patterns
@@ -11,8 +14,13 @@ pub struct SessionBlock {
     pub end_time: DateTime<Utc>,
     pub usage: TokenUsage,
     pub session_count: usize,
+    /// First and last record within the block
+    pub first_activity: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
     pub is_active: bool,
     pub burn_rate: Option<BurnRate>,
+    #[serde(skip)]
+    sessions: HashSet<String>,
 }
 
 /// Burn rate calculations for active sessions
@@ -58,8 +66,15 @@ impl SessionBlockManager {
         }
     }
 
+    /// Add every usage event to the block of its timestamp
+    pub fn add_events(&mut self, events: &[UsageEvent]) {
+        for event in events {
+            self.add_usage(event.timestamp, &event.usage, &event.session);
+        }
+    }
+
     /// Add usage record to appropriate session block
-    pub fn add_usage(&mut self, timestamp: DateTime<Utc>, usage: &TokenUsage, _session_id: &str) {
+    pub fn add_usage(&mut self, timestamp: DateTime<Utc>, usage: &TokenUsage, session_id: &str) {
         let block_start = self.normalize_to_block(timestamp);
         let block_end = block_start + Duration::hours(self.config.block_hours);
         let block_key = format!(
@@ -73,15 +88,21 @@ impl SessionBlockManager {
 
         if let Some(block) = blocks.iter_mut().find(|b| b.start_time == block_start) {
             block.usage.add(usage);
-            block.session_count += 1;
+            block.sessions.insert(session_id.to_string());
+            block.session_count = block.sessions.len();
+            block.first_activity = block.first_activity.min(timestamp);
+            block.last_activity = block.last_activity.max(timestamp);
         } else {
             blocks.push(SessionBlock {
                 start_time: block_start,
                 end_time: block_end,
                 usage: usage.clone(),
                 session_count: 1,
+                first_activity: timestamp,
+                last_activity: timestamp,
                 is_active,
                 burn_rate: None,
+                sessions: HashSet::from([session_id.to_string()]),
             });
         }
     }
@@ -114,7 +135,8 @@ impl SessionBlockManager {
         for blocks in self.blocks.values_mut() {
             for block in blocks.iter_mut() {
                 if block.is_active {
-                    let elapsed = now - block.start_time;
+                    // Measured from the first record, not the block boundary
+                    let elapsed = now - block.first_activity;
                     let hours_elapsed = elapsed.num_seconds() as f64 / 3600.0;
 
                     if hours_elapsed > 0.1 {
@@ -325,6 +347,7 @@ mod tests {
             cache_read_tokens: 0,
             total_cost: 0.15,
             fast_mode_cost: 0.0,
+            ..Default::default()
         };
 
         let now = Utc::now();
@@ -335,4 +358,43 @@ mod tests {
         assert!(!all_blocks.is_empty(), "Should have at least one block");
         assert_eq!(all_blocks[0].usage.total_cost, 0.15);
     }
+
+    #[test]
+    fn test_events_split_across_blocks() {
+        let mut manager = SessionBlockManager::new(SessionBlockConfig::default());
+        let event = |time: &str, session: &str, output_tokens: u64| UsageEvent {
+            timestamp: DateTime::parse_from_rfc3339(time)
+                .unwrap()
+                .with_timezone(&Utc),
+            session: session.to_string(),
+            model: "claude-sonnet-4-20250514".to_string(),
+            usage: TokenUsage {
+                output_tokens,
+                ..Default::default()
+            },
+        };
+        // One long session spanning two blocks, and a second session in the first
+        manager.add_events(&[
+            event("2024-01-01T06:00:00Z", "p/long", 100),
+            event("2024-01-01T07:30:00Z", "p/short", 10),
+            event("2024-01-01T07:45:00Z", "p/long", 200),
+            event("2024-01-01T09:00:00Z", "p/long", 1000),
+        ]);
+
+        let mut blocks = manager.get_all_blocks();
+        blocks.sort_by_key(|block| block.start_time);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].usage.output_tokens, 310);
+        assert_eq!(blocks[0].session_count, 2);
+        assert_eq!(
+            blocks[0].first_activity,
+            DateTime::parse_from_rfc3339("2024-01-01T06:00:00Z").unwrap()
+        );
+        assert_eq!(
+            blocks[0].last_activity,
+            DateTime::parse_from_rfc3339("2024-01-01T07:45:00Z").unwrap()
+        );
+        assert_eq!(blocks[1].usage.output_tokens, 1000);
+        assert_eq!(blocks[1].session_count, 1);
+    }
 }
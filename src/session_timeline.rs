@@ -0,0 +1,244 @@
+//! Activity timeline of a session (`inspect --timeline`)
+//!
+//! Messages of all conversation files of a session are grouped into fixed
+//! 15-minute buckets from the first to the last message. Buckets without
+//! messages are kept, so the sparkline shows breaks; the table collapses them
+//! into a single idle line.
+
+use crate::conversation_parser::ConversationMessage;
+use crate::currency::{self, Money};
+use crate::display::format_number;
+use crate::pricing::{ModelPricing, PricingFetcher};
+use crate::session_analytics::format_duration;
+use chrono::{DateTime, Duration, Local, Utc};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Length of one timeline bucket
+pub const BUCKET_MINUTES: i64 = 15;
+
+/// Widest sparkline; longer sessions show the busiest bucket of each group
+const MAX_SPARKLINE_WIDTH: usize = 60;
+
+/// Width of the per-bucket bars in characters
+const BAR_WIDTH: usize = 20;
+
+pub(crate) const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Activity within one 15-minute interval
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TimelineBucket {
+    pub start: DateTime<Utc>,
+    pub messages: usize,
+    pub tokens: u64,
+    /// In the display currency
+    pub cost: f64,
+}
+
+/// Group messages into consecutive 15-minute buckets, from the bucket of the
+/// earliest message to that of the latest one
+pub fn bucket_messages(
+    messages: &[&ConversationMessage],
+    pricing_data: &HashMap<String, ModelPricing>,
+) -> Vec<TimelineBucket> {
+    let bucket_seconds = BUCKET_MINUTES * 60;
+    let bucket_of = |timestamp: DateTime<Utc>| timestamp.timestamp().div_euclid(bucket_seconds);
+    let (Some(first), Some(last)) = (
+        messages.iter().map(|m| bucket_of(m.timestamp)).min(),
+        messages.iter().map(|m| bucket_of(m.timestamp)).max(),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut buckets: Vec<TimelineBucket> = (first..=last)
+        .map(|index| TimelineBucket {
+            start: DateTime::from_timestamp(index * bucket_seconds, 0).unwrap_or_default(),
+            ..Default::default()
+        })
+        .collect();
+
+    let fetcher = PricingFetcher::new();
+    let rate = currency::current().rate;
+    for message in messages {
+        let bucket = &mut buckets[(bucket_of(message.timestamp) - first) as usize];
+        bucket.messages += 1;
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        bucket.tokens += usage.total_tokens();
+        let model = message.model.as_deref().unwrap_or("unknown");
+        if let Some(pricing) = fetcher.get_model_pricing(pricing_data, model) {
+            bucket.cost += rate
+                * fetcher.calculate_cost(
+                    &pricing,
+                    usage.input_tokens,
+                    usage.output_tokens,
+                    usage.cache_creation_tokens,
+                    usage.cache_read_tokens,
+                );
+        }
+    }
+    buckets
+}
+
+/// One character per bucket scaled to the busiest one (by tokens); idle buckets
+/// are blank. Wider timelines keep the busiest bucket of each group.
+pub fn sparkline(buckets: &[TimelineBucket], max_width: usize) -> String {
+    if buckets.is_empty() || max_width == 0 {
+        return String::new();
+    }
+    let group = buckets.len().div_ceil(max_width);
+    let peaks: Vec<Option<u64>> = buckets
+        .chunks(group)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .filter(|bucket| bucket.messages > 0)
+                .map(|bucket| bucket.tokens)
+                .max()
+        })
+        .collect();
+    let max = peaks.iter().flatten().copied().max().unwrap_or(0).max(1);
+    peaks
+        .iter()
+        .map(|peak| match peak {
+            None => ' ',
+            Some(tokens) => {
+                let level = (*tokens as f64 / max as f64 * (SPARK_LEVELS.len() - 1) as f64).round()
+                    as usize;
+                SPARK_LEVELS[level]
+            }
+        })
+        .collect()
+}
+
+/// Print the timeline of one session
+pub fn display_session_timeline(buckets: &[TimelineBucket]) {
+    println!(
+        "\n   {} Activity Timeline ({}-minute buckets):",
+        "📅".cyan(),
+        BUCKET_MINUTES
+    );
+    if buckets.is_empty() {
+        println!("   └─ No messages found for this session");
+        return;
+    }
+
+    println!("   {}", sparkline(buckets, MAX_SPARKLINE_WIDTH).cyan());
+
+    let max_tokens = buckets.iter().map(|b| b.tokens).max().unwrap_or(0).max(1);
+    let mut idle = 0;
+    for bucket in buckets {
+        if bucket.messages == 0 {
+            idle += 1;
+            continue;
+        }
+        if idle > 0 {
+            print_idle(idle);
+            idle = 0;
+        }
+        let filled = (bucket.tokens as f64 / max_tokens as f64 * BAR_WIDTH as f64).round() as usize;
+        println!(
+            "   {} {}{} {:>4} msgs {:>11} tokens {:>10.4}",
+            bucket
+                .start
+                .with_timezone(&Local)
+                .format("%m-%d %H:%M")
+                .to_string()
+                .bright_black(),
+            "█".repeat(filled).green(),
+            "░".repeat(BAR_WIDTH - filled).bright_black(),
+            bucket.messages,
+            format_number(bucket.tokens),
+            Money(bucket.cost)
+        );
+    }
+
+    let active = buckets.iter().filter(|b| b.messages > 0).count();
+    let span = Duration::minutes(BUCKET_MINUTES * buckets.len() as i64);
+    println!(
+        "   └─ Active in {} of {} buckets (~{} of {})",
+        active,
+        buckets.len(),
+        format_duration(&Duration::minutes(BUCKET_MINUTES * active as i64)),
+        format_duration(&span)
+    );
+}
+
+fn print_idle(buckets: usize) {
+    println!(
+        "   {}",
+        format!(
+            "            ┆ idle {}",
+            format_duration(&Duration::minutes(BUCKET_MINUTES * buckets as i64))
+        )
+        .bright_black()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+    use crate::pricing::get_fallback_pricing;
+    use chrono::TimeZone;
+
+    fn message(hour: u32, minute: u32, tokens: Option<u64>) -> ConversationMessage {
+        ConversationMessage {
+            uuid: format!("{}:{}", hour, minute),
+            parent_uuid: None,
+            message_type: "assistant".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 15, hour, minute, 0).unwrap(),
+            role: "assistant".to_string(),
+            content: Vec::new(),
+            usage: tokens.map(|output| TokenUsage {
+                output_tokens: output,
+                ..Default::default()
+            }),
+            model: Some("claude-3-opus-20240229".to_string()),
+            session_id: "s".to_string(),
+            cwd: None,
+            is_sidechain: false,
+            git_branch: None,
+        }
+    }
+
+    #[test]
+    fn test_bucket_messages() {
+        let messages = [
+            message(10, 31, Some(400)),
+            message(10, 1, None),
+            message(10, 14, Some(1_000_000)),
+        ];
+        let refs: Vec<&ConversationMessage> = messages.iter().collect();
+        let buckets = bucket_messages(&refs, &get_fallback_pricing());
+
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(
+            buckets[0].start,
+            Utc.with_ymd_and_hms(2024, 1, 15, 10, 0, 0).unwrap()
+        );
+        assert_eq!(buckets[0].messages, 2);
+        assert_eq!(buckets[0].tokens, 1_000_000);
+        // Claude 3 Opus output is $75 per million tokens
+        assert!((buckets[0].cost - 75.0).abs() < 1e-6);
+        assert_eq!(buckets[1].messages, 0);
+        assert_eq!(buckets[2].tokens, 400);
+
+        assert!(bucket_messages(&[], &get_fallback_pricing()).is_empty());
+    }
+
+    #[test]
+    fn test_sparkline() {
+        let bucket = |messages, tokens| TimelineBucket {
+            messages,
+            tokens,
+            ..Default::default()
+        };
+        let buckets = [bucket(1, 100), bucket(0, 0), bucket(2, 50), bucket(1, 0)];
+        assert_eq!(sparkline(&buckets, 10), "█ ▅▁");
+        assert_eq!(sparkline(&buckets, 2), "█▅");
+        assert_eq!(sparkline(&[], 10), "");
+    }
+}
@@ -0,0 +1,353 @@
+//! Cost savings simulator (`simulate` command)
+//!
+//! Reprices historical usage as if the tokens of some models had gone to
+//! another model. Token counts stay as recorded and only the per-token rates
+//! change, so the result ignores any difference in how many tokens the other
+//! model would have needed. Usage of models that are not remapped keeps its
+//! recorded cost.
+
+use crate::currency::{Currency, Money};
+use crate::models::DailyUsageMap;
+use crate::models_registry::ModelsRegistry;
+use crate::pricing::{ModelPricing, PricingFetcher};
+use crate::pricing_cache::PricingCache;
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// One `--map FROM=TO` rule with the target resolved to a priced model
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelMapping {
+    /// Model name, alias, or family whose usage is moved
+    pub from: String,
+    /// Registered model the usage is repriced at
+    pub to: String,
+    #[serde(skip)]
+    pricing: ModelPricing,
+}
+
+impl ModelMapping {
+    /// Parse `FROM=TO`, resolving `TO` through the models registry
+    pub fn parse(
+        spec: &str,
+        registry: &ModelsRegistry,
+        pricing_data: &HashMap<String, ModelPricing>,
+    ) -> Result<Self> {
+        let Some((from, to)) = spec.split_once('=') else {
+            bail!("Invalid mapping '{}'. Use FROM=TO, e.g. opus=sonnet", spec);
+        };
+        let (from, to) = (from.trim(), to.trim());
+        if from.is_empty() || to.is_empty() {
+            bail!("Invalid mapping '{}'. Use FROM=TO, e.g. opus=sonnet", spec);
+        }
+
+        let target = registry
+            .resolve(to)
+            .map_or_else(|| to.to_string(), |info| info.name.clone());
+        let pricing = PricingFetcher::new()
+            .get_model_pricing(pricing_data, &target)
+            .with_context(|| format!("No pricing found for target model '{}'", to))?;
+
+        Ok(Self {
+            from: from.to_string(),
+            to: target,
+            pricing,
+        })
+    }
+}
+
+/// Actual and simulated cost of one month
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MonthlySavings {
+    /// `YYYY-MM`
+    pub month: String,
+    pub actual_cost: f64,
+    pub simulated_cost: f64,
+    pub savings: f64,
+    /// Tokens of remapped models
+    pub remapped_tokens: u64,
+}
+
+/// Actual and simulated cost of one remapped model over the whole range
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelSavings {
+    pub model: String,
+    pub target: String,
+    pub tokens: u64,
+    pub actual_cost: f64,
+    pub simulated_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationReport {
+    pub mappings: Vec<ModelMapping>,
+    /// Oldest month first
+    pub months: Vec<MonthlySavings>,
+    /// Largest savings first
+    pub models: Vec<ModelSavings>,
+    pub actual_cost: f64,
+    pub simulated_cost: f64,
+    pub savings: f64,
+    pub savings_pct: f64,
+}
+
+impl SimulationReport {
+    /// Reprice the per-model usage of every day in `daily_map`, whose costs are
+    /// in `currency`
+    pub fn new(
+        daily_map: &DailyUsageMap,
+        mappings: Vec<ModelMapping>,
+        currency: &Currency,
+    ) -> Self {
+        let registry = ModelsRegistry::new();
+        let fetcher = PricingFetcher::new();
+        let mut months: BTreeMap<String, MonthlySavings> = BTreeMap::new();
+        let mut models: BTreeMap<String, ModelSavings> = BTreeMap::new();
+
+        for (date, usage) in daily_map {
+            let month_key = date.format("%Y-%m").to_string();
+            let month = months.entry(month_key.clone()).or_insert(MonthlySavings {
+                month: month_key,
+                ..Default::default()
+            });
+            month.actual_cost += usage.total_cost;
+            month.simulated_cost += usage.total_cost;
+
+            for (model, model_usage) in &usage.by_model {
+                let Some(mapping) = mappings
+                    .iter()
+                    .find(|m| registry.matches_filter(model, &m.from))
+                else {
+                    continue;
+                };
+                if *model == mapping.to {
+                    continue;
+                }
+
+                // Per-day totals, so the per-request 200k tier cannot apply
+                let simulated = fetcher.calculate_flat_cost(
+                    &mapping.pricing,
+                    model_usage.input_tokens,
+                    model_usage.output_tokens,
+                    model_usage.cache_creation_tokens,
+                    model_usage.cache_read_tokens,
+                ) * currency.rate;
+                month.simulated_cost += simulated - model_usage.total_cost;
+                month.remapped_tokens += model_usage.total_tokens();
+
+                let entry = models.entry(model.clone()).or_insert(ModelSavings {
+                    model: model.clone(),
+                    target: mapping.to.clone(),
+                    tokens: 0,
+                    actual_cost: 0.0,
+                    simulated_cost: 0.0,
+                });
+                entry.tokens += model_usage.total_tokens();
+                entry.actual_cost += model_usage.total_cost;
+                entry.simulated_cost += simulated;
+            }
+        }
+
+        let months: Vec<MonthlySavings> = months
+            .into_values()
+            .map(|mut month| {
+                month.savings = month.actual_cost - month.simulated_cost;
+                month
+            })
+            .collect();
+        let mut models: Vec<ModelSavings> = models.into_values().collect();
+        models.sort_by(|a, b| {
+            (b.actual_cost - b.simulated_cost).total_cmp(&(a.actual_cost - a.simulated_cost))
+        });
+
+        let actual_cost: f64 = months.iter().map(|m| m.actual_cost).sum();
+        let simulated_cost: f64 = months.iter().map(|m| m.simulated_cost).sum();
+        let savings = actual_cost - simulated_cost;
+
+        Self {
+            mappings,
+            months,
+            models,
+            actual_cost,
+            simulated_cost,
+            savings,
+            savings_pct: if actual_cost > 0.0 {
+                savings / actual_cost * 100.0
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+/// Build the simulation from `--map` rules using the effective pricing table
+pub fn simulate(
+    daily_map: &DailyUsageMap,
+    specs: &[String],
+    currency: &Currency,
+) -> Result<SimulationReport> {
+    let registry = ModelsRegistry::new();
+    let pricing_data = PricingCache::effective_pricing();
+    let mappings = specs
+        .iter()
+        .map(|spec| ModelMapping::parse(spec, &registry, &pricing_data))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(SimulationReport::new(daily_map, mappings, currency))
+}
+
+pub fn display_simulation(report: &SimulationReport, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "MODEL SWITCH SIMULATION".bold());
+    println!("{}", "═".repeat(72));
+    for mapping in &report.mappings {
+        println!("  {} {} {}", mapping.from.cyan(), "→".dimmed(), mapping.to);
+    }
+
+    if report.models.is_empty() {
+        println!("\n  No usage of the remapped models in the selected range");
+        return;
+    }
+
+    let mut models = Table::new();
+    models
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec![
+            Cell::new("Model").fg(Color::Cyan),
+            Cell::new("Repriced As").fg(Color::Cyan),
+            Cell::new("Tokens").fg(Color::White),
+            Cell::new("Actual").fg(Color::Red),
+            Cell::new("Simulated").fg(Color::Green),
+        ]);
+    for model in &report.models {
+        models.add_row(vec![
+            Cell::new(&model.model),
+            Cell::new(&model.target),
+            Cell::new(format_tokens(model.tokens)),
+            Cell::new(format!("{:.2}", Money(model.actual_cost))).fg(Color::Red),
+            Cell::new(format!("{:.2}", Money(model.simulated_cost))).fg(Color::Green),
+        ]);
+    }
+    println!("\n{}", "By Model".bold());
+    println!("{models}");
+
+    let mut months = Table::new();
+    months
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec![
+            Cell::new("Month").fg(Color::Cyan),
+            Cell::new("Actual").fg(Color::Red),
+            Cell::new("Simulated").fg(Color::Green),
+            Cell::new("Savings").fg(Color::Yellow),
+        ]);
+    for month in &report.months {
+        months.add_row(vec![
+            Cell::new(&month.month),
+            Cell::new(format!("{:.2}", Money(month.actual_cost))),
+            Cell::new(format!("{:.2}", Money(month.simulated_cost))),
+            Cell::new(format!("{:.2}", Money(month.savings))).fg(savings_color(month.savings)),
+        ]);
+    }
+    months.add_row(vec![
+        Cell::new("Total").fg(Color::Yellow),
+        Cell::new(format!("{:.2}", Money(report.actual_cost))).fg(Color::Yellow),
+        Cell::new(format!("{:.2}", Money(report.simulated_cost))).fg(Color::Yellow),
+        Cell::new(format!("{:.2}", Money(report.savings))).fg(Color::Yellow),
+    ]);
+    println!("\n{}", "By Month".bold());
+    println!("{months}");
+
+    println!(
+        "\n  {:<20} {} ({:.1}% of actual spend)",
+        "Potential savings:".bold(),
+        format!("{:.2}", Money(report.savings)).green().bold(),
+        report.savings_pct
+    );
+    println!(
+        "  {}",
+        "Same token counts at the target model's rates; the 200k context tier is not applied"
+            .dimmed()
+    );
+}
+
+fn savings_color(savings: f64) -> Color {
+    if savings < 0.0 {
+        Color::Red
+    } else {
+        Color::Green
+    }
+}
+
+fn format_tokens(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.0}K", n as f64 / 1_000.0)
+    } else {
+        format!("{}", n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_simulation_reprices_mapped_models() {
+        let pricing_data = crate::pricing::get_fallback_pricing();
+        let registry = ModelsRegistry::new();
+        let mapping = ModelMapping::parse("opus=sonnet-4", &registry, &pricing_data).unwrap();
+        assert_eq!(mapping.to, "claude-sonnet-4-20250514");
+        assert!(ModelMapping::parse("opus", &registry, &pricing_data).is_err());
+
+        let usage = |model: &str, cost: f64| {
+            TokenUsage {
+                input_tokens: 1_000_000,
+                output_tokens: 100_000,
+                total_cost: cost,
+                ..Default::default()
+            }
+            .tagged(model, "p/s")
+        };
+        let mut daily_map = DailyUsageMap::new();
+        let mut day = usage("claude-opus-4-20250514", 22.5);
+        day.add(&usage("claude-3-5-haiku-20241022", 1.2));
+        daily_map.insert(NaiveDate::from_ymd_opt(2025, 6, 3).unwrap(), day);
+        daily_map.insert(
+            NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+            usage("claude-opus-4-20250514", 22.5),
+        );
+
+        let report = SimulationReport::new(&daily_map, vec![mapping.clone()], &Currency::usd());
+        assert_eq!(report.months.len(), 2);
+        assert_eq!(report.models.len(), 1);
+        // 1M input at $3/M plus 100K output at $15/M
+        assert!((report.months[0].simulated_cost - (4.5 + 1.2)).abs() < 1e-9);
+        assert!((report.months[1].savings - 18.0).abs() < 1e-9);
+        assert!((report.savings - 36.0).abs() < 1e-9);
+
+        // Recorded costs already converted to EUR are compared with EUR prices
+        let eur = Currency {
+            code: "EUR".to_string(),
+            rate: 0.5,
+        };
+        for usage in daily_map.values_mut() {
+            usage.scale_costs(eur.rate);
+        }
+        let report = SimulationReport::new(&daily_map, vec![mapping], &eur);
+        assert!((report.months[0].simulated_cost - (2.25 + 0.6)).abs() < 1e-9);
+        assert!((report.savings - 18.0).abs() < 1e-9);
+    }
+}
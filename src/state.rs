@@ -2,6 +2,15 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Profile selected with `--profile`; each profile keeps its own state file
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Keep the TUI state of `profile` separate from the default state
+pub fn use_profile(profile: &str) {
+    let _ = ACTIVE_PROFILE.set(profile.to_string());
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TuiSessionState {
@@ -50,8 +59,12 @@ impl TuiSessionState {
     }
 
     pub fn load() -> Result<Self> {
-        let state_path = Self::get_state_path()?;
+        let mut state_path = Self::get_state_path()?;
 
+        if !state_path.exists() && ACTIVE_PROFILE.get().is_none() {
+            // State written by versions that kept it under ~/.claude
+            state_path = Self::legacy_state_path();
+        }
         if !state_path.exists() {
             return Ok(Self::default());
         }
@@ -70,10 +83,27 @@ impl TuiSessionState {
         Ok(())
     }
 
-    fn get_state_path() -> Result<PathBuf> {
+    /// `tui_session.json` (or `tui_session.<profile>.json`) in the XDG state
+    /// directory, or the data directory on platforms without one
+    pub fn get_state_path() -> Result<PathBuf> {
+        let state_dir = dirs::state_dir()
+            .or_else(dirs::data_dir)
+            .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("state")))
+            .ok_or_else(|| anyhow::anyhow!("Cannot determine state directory"))?
+            .join("claudelytics");
+        let file_name = match ACTIVE_PROFILE.get() {
+            Some(profile) => format!("tui_session.{}.json", profile),
+            None => "tui_session.json".to_string(),
+        };
+        Ok(state_dir.join(file_name))
+    }
+
+    fn legacy_state_path() -> PathBuf {
         let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let state_dir = PathBuf::from(home).join(".claude").join("claudelytics");
-        Ok(state_dir.join("tui_session.json"))
+        PathBuf::from(home)
+            .join(".claude")
+            .join("claudelytics")
+            .join("tui_session.json")
     }
 
     pub fn should_resume(&self) -> bool {
@@ -0,0 +1,194 @@
+//! One-line usage summaries for status bars (`status` command)
+//!
+//! Waybar, tmux and starship re-run their modules every few seconds, so the
+//! `status` command only parses the last day of logs (through the parse cache)
+//! and prints a single line: today's cost, how much of the current 5-hour
+//! block's quota is left, and the block's burn rate.
+
+use crate::billing_blocks::BillingBlockManager;
+use crate::currency::Money;
+use crate::models::DailyUsageMap;
+use crate::quota::{self, Plan, QuotaLimits};
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+
+/// Remaining quota below which the status turns yellow
+const WARNING_REMAINING_PCT: f64 = 25.0;
+/// Remaining quota below which the status turns red
+const CRITICAL_REMAINING_PCT: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFormat {
+    /// JSON object for a waybar `custom` module
+    Waybar,
+    /// Text with tmux `#[fg=...]` color markup, for `status-right`
+    Tmux,
+    /// Plain text for a starship `custom` module or any shell prompt
+    Starship,
+}
+
+/// What a status bar shows
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusLine {
+    pub today_cost: f64,
+    pub today_tokens: u64,
+    pub plan: String,
+    /// Share of the current block's allowance left; `None` outside a block
+    pub quota_remaining_pct: Option<f64>,
+    pub minutes_until_reset: Option<i64>,
+    /// Cost per hour in the current block
+    pub cost_per_hour: Option<f64>,
+}
+
+impl StatusLine {
+    /// "normal", "warning" or "critical", from the quota left
+    pub fn level(&self) -> &'static str {
+        match self.quota_remaining_pct {
+            Some(pct) if pct < CRITICAL_REMAINING_PCT => "critical",
+            Some(pct) if pct < WARNING_REMAINING_PCT => "warning",
+            _ => "normal",
+        }
+    }
+}
+
+pub fn build_status_line(
+    daily_map: &DailyUsageMap,
+    billing_manager: &BillingBlockManager,
+    plan: Plan,
+    limits: QuotaLimits,
+    now: DateTime<Utc>,
+) -> StatusLine {
+    let today = now.with_timezone(&Local).date_naive();
+    let (today_cost, today_tokens) = daily_map
+        .get(&today)
+        .map_or((0.0, 0), |usage| (usage.total_cost, usage.total_tokens()));
+    let burn_down = quota::build_burn_down(billing_manager, plan, limits, now);
+
+    StatusLine {
+        today_cost,
+        today_tokens,
+        plan: plan.name().to_string(),
+        quota_remaining_pct: burn_down
+            .as_ref()
+            .map(|b| (100.0 - b.block.quota_pct()).max(0.0)),
+        minutes_until_reset: burn_down.as_ref().map(|b| b.minutes_until_reset),
+        cost_per_hour: burn_down.as_ref().map(|b| b.cost_per_hour),
+    }
+}
+
+/// The line to print, without a trailing newline
+pub fn render(status: &StatusLine, format: StatusFormat) -> String {
+    let text = compact_text(status);
+    match format {
+        StatusFormat::Waybar => {
+            let mut tooltip = format!(
+                "Today: {:.2} ({} tokens)",
+                Money(status.today_cost),
+                crate::display::format_number(status.today_tokens)
+            );
+            match (status.quota_remaining_pct, status.minutes_until_reset) {
+                (Some(pct), Some(minutes)) => tooltip.push_str(&format!(
+                    "\n{} block: {:.0}% left, resets in {}h {:02}m",
+                    status.plan,
+                    pct,
+                    minutes / 60,
+                    minutes % 60
+                )),
+                _ => tooltip.push_str(&format!("\n{} block: no activity", status.plan)),
+            }
+            if let Some(rate) = status.cost_per_hour {
+                tooltip.push_str(&format!("\nBurn rate: {:.2}/h", Money(rate)));
+            }
+            serde_json::json!({
+                "text": text,
+                "tooltip": tooltip,
+                "class": status.level(),
+                // Waybar's format-icons pick an icon from the percentage
+                "percentage": status.quota_remaining_pct.map_or(100, |pct| pct.round() as u8),
+            })
+            .to_string()
+        }
+        StatusFormat::Tmux => match status.level() {
+            "critical" => format!("#[fg=red]{}#[default]", text),
+            "warning" => format!("#[fg=yellow]{}#[default]", text),
+            _ => text,
+        },
+        StatusFormat::Starship => text,
+    }
+}
+
+/// e.g. `$12.34 · 62% · $4.10/h`; the quota and rate are left out between blocks
+fn compact_text(status: &StatusLine) -> String {
+    let mut parts = vec![format!("{:.2}", Money(status.today_cost))];
+    if let Some(pct) = status.quota_remaining_pct {
+        parts.push(format!("{:.0}%", pct));
+    }
+    if let Some(rate) = status.cost_per_hour {
+        parts.push(format!("{:.2}/h", Money(rate)));
+    }
+    parts.join(" · ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::billing_blocks::{BillingBlockConfig, BlockAnchor};
+    use crate::models::TokenUsage;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_status_line_formats() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let usage = TokenUsage {
+            input_tokens: 1000,
+            output_tokens: 500,
+            total_cost: 6.0,
+            ..Default::default()
+        };
+        let mut daily_map = DailyUsageMap::new();
+        daily_map.insert(now.with_timezone(&Local).date_naive(), usage.clone());
+        let mut manager = BillingBlockManager::with_config(BillingBlockConfig {
+            anchor: BlockAnchor::Rolling,
+            duration_hours: 5,
+        });
+        manager.add_usage(now - Duration::hours(2), &usage, Some("s1"));
+        manager.finalize();
+        let limits = QuotaLimits {
+            tokens: 2000,
+            messages: 100,
+            cost: 100.0,
+        };
+
+        let status = build_status_line(&daily_map, &manager, Plan::Pro, limits, now);
+        assert_eq!(status.today_cost, 6.0);
+        assert_eq!(status.quota_remaining_pct, Some(25.0));
+        assert_eq!(status.level(), "normal");
+        assert_eq!(
+            render(&status, StatusFormat::Starship),
+            "$6.00 · 25% · $3.00/h"
+        );
+
+        let waybar: serde_json::Value =
+            serde_json::from_str(&render(&status, StatusFormat::Waybar)).unwrap();
+        assert_eq!(waybar["text"], "$6.00 · 25% · $3.00/h");
+        assert_eq!(waybar["class"], "normal");
+        assert_eq!(waybar["percentage"], 25);
+
+        let idle = build_status_line(
+            &daily_map,
+            &manager,
+            Plan::Pro,
+            limits,
+            now + Duration::days(1),
+        );
+        assert_eq!(idle.today_cost, 0.0);
+        assert_eq!(idle.quota_remaining_pct, None);
+        assert_eq!(render(&idle, StatusFormat::Tmux), "$0.00");
+
+        let low = StatusLine {
+            quota_remaining_pct: Some(5.0),
+            ..status
+        };
+        assert!(render(&low, StatusFormat::Tmux).starts_with("#[fg=red]$6.00"));
+    }
+}
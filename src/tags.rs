@@ -0,0 +1,141 @@
+//! Session tags for `--group-by tag` and the `tags` command
+//!
+//! Tags come from the `tags` section of the configuration file: project-path
+//! globs (`tags.projects`) and manual tags per session (`tags.sessions`). A
+//! session gets the tags of every matching rule plus its manual tags; sessions
+//! without any tag are grouped as `untagged`.
+
+use crate::config_v2::TagsConfig;
+use std::collections::BTreeMap;
+
+/// Group name for sessions without tags
+pub const UNTAGGED: &str = "untagged";
+
+pub struct Tagger {
+    /// Rules with patterns in Claude's project directory form
+    rules: Vec<(String, Vec<String>)>,
+    sessions: BTreeMap<String, Vec<String>>,
+}
+
+impl Tagger {
+    pub fn new(config: &TagsConfig) -> Self {
+        Self {
+            rules: config
+                .projects
+                .iter()
+                .map(|rule| (project_dir_pattern(&rule.pattern), rule.tags.clone()))
+                .collect(),
+            sessions: config.sessions.clone(),
+        }
+    }
+
+    /// Tags of a `project-dir/session-id` session, sorted, or `[untagged]`
+    pub fn tags_for(&self, session: &str) -> Vec<String> {
+        let project = session.rsplit_once('/').map_or("", |(project, _)| project);
+
+        let mut tags: Vec<String> = self
+            .rules
+            .iter()
+            .filter(|(pattern, _)| glob_match(pattern, project))
+            .flat_map(|(_, tags)| tags.iter().cloned())
+            .chain(self.sessions.get(session).into_iter().flatten().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+
+        if tags.is_empty() {
+            tags.push(UNTAGGED.to_string());
+        }
+        tags
+    }
+}
+
+/// Claude Code stores each project under its path with `/` and `.` replaced by
+/// `-`, so `~/work/*` becomes `-home-me-work-*`. Patterns already in that form
+/// are left unchanged.
+pub fn project_dir_pattern(pattern: &str) -> String {
+    let expanded = match pattern.strip_prefix('~') {
+        Some(rest) => match dirs::home_dir() {
+            Some(home) => format!("{}{}", home.display(), rest),
+            None => pattern.to_string(),
+        },
+        None => pattern.to_string(),
+    };
+    expanded.replace(['/', '\\', '.'], "-")
+}
+
+/// Match `text` against a glob where `*` matches any run of characters and `?`
+/// a single character
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p + 1, t));
+            p += 1;
+        } else if let Some((after_star, tried)) = backtrack {
+            p = after_star;
+            t = tried + 1;
+            backtrack = Some((after_star, tried + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_v2::ProjectTagRule;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("-home-me-work-*", "-home-me-work-api"));
+        assert!(glob_match("*client-x*", "-home-me-client-x-site"));
+        assert!(glob_match("-home-?e-*", "-home-me-oss"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("-home-me-work-*", "-home-me-oss-lib"));
+        assert!(!glob_match("-home-me", "-home-me-work"));
+    }
+
+    #[test]
+    fn test_tags_for_session() {
+        let config = TagsConfig {
+            projects: vec![
+                ProjectTagRule {
+                    pattern: "/home/me/work/*".to_string(),
+                    tags: vec!["work".to_string()],
+                },
+                ProjectTagRule {
+                    pattern: "*client-x*".to_string(),
+                    tags: vec!["client-x".to_string(), "work".to_string()],
+                },
+            ],
+            sessions: BTreeMap::from([(
+                "-home-me-oss-lib/abc".to_string(),
+                vec!["oss".to_string()],
+            )]),
+        };
+        let tagger = Tagger::new(&config);
+
+        assert_eq!(tagger.tags_for("-home-me-work-api/s1"), vec!["work"]);
+        assert_eq!(
+            tagger.tags_for("-home-me-work-client-x/s2"),
+            vec!["client-x", "work"]
+        );
+        assert_eq!(tagger.tags_for("-home-me-oss-lib/abc"), vec!["oss"]);
+        assert_eq!(tagger.tags_for("-home-me-oss-lib/def"), vec![UNTAGGED]);
+        assert_eq!(
+            project_dir_pattern("/home/me/my.site/*"),
+            "-home-me-my-site-*"
+        );
+    }
+}
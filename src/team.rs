@@ -0,0 +1,479 @@
+//! Team reports from several users' bundles (`team` and `export --team-bundle`)
+//!
+//! A team bundle is a JSON file with one user's usage per day, session, and
+//! model (the rows of the snapshot archive). It holds token counts and costs,
+//! but no prompts or conversation content. `team` merges bundles into combined
+//! reports with a per-user breakdown; bundles with the same user name (e.g.
+//! from two machines) are added up as one user.
+
+use crate::archive::{ArchivedUsage, snapshot_rows};
+use crate::currency::Money;
+use crate::models::{DailyUsageMap, SessionUsageMap};
+use crate::reports::parse_session_path;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+use colored::Colorize;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::path::Path;
+
+const BUNDLE_FORMAT: &str = "claudelytics-team-bundle";
+const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamBundle {
+    pub format: String,
+    pub version: u32,
+    pub user: String,
+    pub generated_at: DateTime<Utc>,
+    pub rows: Vec<ArchivedUsage>,
+}
+
+impl TeamBundle {
+    pub fn new(
+        user: &str,
+        daily_map: &DailyUsageMap,
+        session_map: &SessionUsageMap,
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            user: user.to_string(),
+            generated_at,
+            rows: snapshot_rows(daily_map, session_map, generated_at),
+        }
+    }
+
+    /// User name for bundles exported without `--user`
+    pub fn default_user() -> String {
+        std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "unknown".to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let bundle: TeamBundle = serde_json::from_str(&content).with_context(|| {
+            format!(
+                "{} is not a team bundle (create one with `claudelytics export --team-bundle`)",
+                path.display()
+            )
+        })?;
+        if bundle.format != BUNDLE_FORMAT {
+            bail!("{} is not a team bundle", path.display());
+        }
+        if bundle.version > BUNDLE_VERSION {
+            bail!(
+                "{} uses bundle version {}; upgrade claudelytics to read it",
+                path.display(),
+                bundle.version
+            );
+        }
+        Ok(bundle)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UserSummary {
+    pub user: String,
+    pub active_days: usize,
+    pub sessions: usize,
+    pub messages: u64,
+    pub tokens: u64,
+    pub cost: f64,
+    /// Share of the team's cost
+    pub share_pct: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TeamDay {
+    pub date: NaiveDate,
+    /// Cost per user with usage that day
+    pub users: BTreeMap<String, f64>,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TeamModel {
+    pub model: String,
+    /// Number of users who used the model
+    pub users: usize,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TeamProject {
+    pub project: String,
+    pub user: String,
+    pub sessions: usize,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TeamTotals {
+    pub users: usize,
+    pub sessions: usize,
+    pub messages: u64,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamReport {
+    pub anonymized: bool,
+    /// Most expensive first
+    pub users: Vec<UserSummary>,
+    /// Oldest first
+    pub daily: Vec<TeamDay>,
+    /// Most expensive first
+    pub models: Vec<TeamModel>,
+    /// Most expensive first
+    pub projects: Vec<TeamProject>,
+    pub totals: TeamTotals,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    days: BTreeSet<NaiveDate>,
+    sessions: BTreeSet<String>,
+    messages: u64,
+    tokens: u64,
+    cost: f64,
+}
+
+impl Accumulator {
+    fn add(&mut self, row: &ArchivedUsage) {
+        self.days.insert(row.date);
+        self.sessions.insert(row.session.clone());
+        self.messages += row.message_count;
+        self.tokens += row_tokens(row);
+        self.cost += row.cost;
+    }
+}
+
+impl TeamReport {
+    /// Merge bundles, keeping rows from `since` to `until`. With `anonymize`,
+    /// users become `user-N` and projects `project-N` in order of appearance.
+    pub fn new(
+        bundles: &[TeamBundle],
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+        anonymize: bool,
+    ) -> Self {
+        let mut user_names: HashMap<String, String> = HashMap::new();
+        let mut project_names: HashMap<String, String> = HashMap::new();
+
+        let mut users: BTreeMap<String, Accumulator> = BTreeMap::new();
+        let mut daily: BTreeMap<NaiveDate, TeamDay> = BTreeMap::new();
+        let mut models: BTreeMap<String, (BTreeSet<String>, Accumulator)> = BTreeMap::new();
+        let mut projects: BTreeMap<(String, String), Accumulator> = BTreeMap::new();
+
+        for bundle in bundles {
+            let next_user = user_names.len() + 1;
+            let user = user_names
+                .entry(bundle.user.clone())
+                .or_insert_with(|| {
+                    if anonymize {
+                        format!("user-{}", next_user)
+                    } else {
+                        bundle.user.clone()
+                    }
+                })
+                .clone();
+
+            let rows = bundle.rows.iter().filter(|row| {
+                since.is_none_or(|since| row.date >= since)
+                    && until.is_none_or(|until| row.date <= until)
+            });
+            for row in rows {
+                let (project, _) = parse_session_path(&row.session);
+                let next_project = project_names.len() + 1;
+                let project = project_names
+                    .entry(project.clone())
+                    .or_insert_with(|| {
+                        if anonymize {
+                            format!("project-{}", next_project)
+                        } else {
+                            project
+                        }
+                    })
+                    .clone();
+
+                // Session keys are only unique per user
+                let mut row = row.clone();
+                row.session = format!("{}/{}", user, row.session);
+
+                users.entry(user.clone()).or_default().add(&row);
+                let day = daily.entry(row.date).or_insert_with(|| TeamDay {
+                    date: row.date,
+                    ..Default::default()
+                });
+                *day.users.entry(user.clone()).or_default() += row.cost;
+                day.tokens += row_tokens(&row);
+                day.cost += row.cost;
+                let model = models.entry(row.model.clone()).or_default();
+                model.0.insert(user.clone());
+                model.1.add(&row);
+                projects
+                    .entry((project, user.clone()))
+                    .or_default()
+                    .add(&row);
+            }
+        }
+
+        let totals = TeamTotals {
+            users: users.len(),
+            sessions: users.values().map(|u| u.sessions.len()).sum(),
+            messages: users.values().map(|u| u.messages).sum(),
+            tokens: users.values().map(|u| u.tokens).sum(),
+            cost: users.values().map(|u| u.cost).sum(),
+        };
+
+        let mut users: Vec<UserSummary> = users
+            .into_iter()
+            .map(|(user, acc)| UserSummary {
+                user,
+                active_days: acc.days.len(),
+                sessions: acc.sessions.len(),
+                messages: acc.messages,
+                tokens: acc.tokens,
+                cost: acc.cost,
+                share_pct: if totals.cost > 0.0 {
+                    acc.cost / totals.cost * 100.0
+                } else {
+                    0.0
+                },
+            })
+            .collect();
+        users.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+
+        let mut models: Vec<TeamModel> = models
+            .into_iter()
+            .map(|(model, (model_users, acc))| TeamModel {
+                model,
+                users: model_users.len(),
+                tokens: acc.tokens,
+                cost: acc.cost,
+            })
+            .collect();
+        models.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+
+        let mut projects: Vec<TeamProject> = projects
+            .into_iter()
+            .map(|((project, user), acc)| TeamProject {
+                project,
+                user,
+                sessions: acc.sessions.len(),
+                tokens: acc.tokens,
+                cost: acc.cost,
+            })
+            .collect();
+        projects.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+
+        Self {
+            anonymized: anonymize,
+            users,
+            daily: daily.into_values().collect(),
+            models,
+            projects,
+            totals,
+        }
+    }
+}
+
+fn row_tokens(row: &ArchivedUsage) -> u64 {
+    row.input_tokens + row.output_tokens + row.cache_creation_tokens + row.cache_read_tokens
+}
+
+pub fn display_team_report(report: &TeamReport, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("{}", "👥 Claude Code Team Usage".bright_blue().bold());
+    println!(
+        "{} users · {} sessions · {} tokens · {}",
+        report.totals.users,
+        report.totals.sessions,
+        format_tokens(report.totals.tokens),
+        format!("${:.2}", report.totals.cost).green().bold()
+    );
+
+    let mut users = new_table(vec![
+        "User",
+        "Active Days",
+        "Sessions",
+        "Tokens",
+        "Cost",
+        "Share",
+    ]);
+    for user in &report.users {
+        users.add_row(vec![
+            Cell::new(&user.user).fg(Color::Cyan),
+            Cell::new(user.active_days),
+            Cell::new(user.sessions),
+            Cell::new(format_tokens(user.tokens)),
+            Cell::new(format!("{:.2}", Money(user.cost))).fg(Color::Red),
+            Cell::new(format!("{:.1}%", user.share_pct)),
+        ]);
+    }
+    println!("\n{}", "By User".bold());
+    println!("{users}");
+
+    let names: Vec<&str> = report.users.iter().map(|u| u.user.as_str()).collect();
+    let mut header = vec!["Date"];
+    header.extend(names.iter().copied());
+    header.push("Total");
+    let mut daily = new_table(header);
+    for day in &report.daily {
+        let mut row = vec![Cell::new(day.date)];
+        row.extend(names.iter().map(|name| match day.users.get(*name) {
+            Some(cost) => Cell::new(format!("{:.2}", Money(*cost))),
+            None => Cell::new("-").fg(Color::DarkGrey),
+        }));
+        row.push(Cell::new(format!("{:.2}", Money(day.cost))).fg(Color::Red));
+        daily.add_row(row);
+    }
+    let mut total_row = vec![Cell::new("Total").fg(Color::Yellow)];
+    total_row.extend(
+        report
+            .users
+            .iter()
+            .map(|user| Cell::new(format!("{:.2}", Money(user.cost))).fg(Color::Yellow)),
+    );
+    total_row.push(Cell::new(format!("{:.2}", Money(report.totals.cost))).fg(Color::Yellow));
+    daily.add_row(total_row);
+    println!("\n{}", "Daily Cost by User".bold());
+    println!("{daily}");
+
+    let mut models = new_table(vec!["Model", "Users", "Tokens", "Cost"]);
+    for model in &report.models {
+        models.add_row(vec![
+            Cell::new(&model.model),
+            Cell::new(model.users),
+            Cell::new(format_tokens(model.tokens)),
+            Cell::new(format!("{:.2}", Money(model.cost))).fg(Color::Red),
+        ]);
+    }
+    println!("\n{}", "By Model".bold());
+    println!("{models}");
+
+    let mut projects = new_table(vec!["Project", "User", "Sessions", "Tokens", "Cost"]);
+    for project in &report.projects {
+        projects.add_row(vec![
+            Cell::new(&project.project),
+            Cell::new(&project.user).fg(Color::Cyan),
+            Cell::new(project.sessions),
+            Cell::new(format_tokens(project.tokens)),
+            Cell::new(format!("{:.2}", Money(project.cost))).fg(Color::Red),
+        ]);
+    }
+    println!("\n{}", "By Project".bold());
+    println!("{projects}");
+}
+
+fn new_table(header: Vec<&str>) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(
+            header
+                .into_iter()
+                .map(|title| Cell::new(title).fg(Color::Cyan)),
+        );
+    table
+}
+
+fn format_tokens(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.0}K", n as f64 / 1_000.0)
+    } else {
+        format!("{}", n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+
+    fn bundle(user: &str, sessions: &[(u32, &str, f64)]) -> TeamBundle {
+        let now = Utc::now();
+        let mut daily_map = DailyUsageMap::new();
+        let mut session_map = SessionUsageMap::new();
+        for (day, session, cost) in sessions {
+            let usage = TokenUsage {
+                input_tokens: 1000,
+                total_cost: *cost,
+                message_count: 1,
+                ..Default::default()
+            }
+            .tagged("claude-sonnet-4-20250514", session);
+            daily_map
+                .entry(NaiveDate::from_ymd_opt(2025, 6, *day).unwrap())
+                .or_default()
+                .add(&usage);
+            session_map.insert(session.to_string(), (usage, now));
+        }
+        TeamBundle::new(user, &daily_map, &session_map, now)
+    }
+
+    #[test]
+    fn test_team_report_merges_users() {
+        let bundles = vec![
+            bundle(
+                "alice",
+                &[
+                    (1, "-home-alice-api/s1", 3.0),
+                    (2, "-home-alice-api/s2", 1.0),
+                ],
+            ),
+            bundle("bob", &[(2, "-home-bob-web/s1", 4.0)]),
+            // Same user from another machine
+            bundle("alice", &[(3, "-home-alice-cli/s9", 2.0)]),
+        ];
+
+        let report = TeamReport::new(&bundles, None, None, false);
+        assert_eq!(report.totals.users, 2);
+        assert_eq!(report.totals.sessions, 4);
+        assert_eq!(report.users[0].user, "alice");
+        assert_eq!(report.users[0].cost, 6.0);
+        assert_eq!(report.users[0].active_days, 3);
+        assert_eq!(report.daily.len(), 3);
+        assert_eq!(report.daily[1].users.len(), 2);
+        assert_eq!(report.models[0].users, 2);
+
+        let since = NaiveDate::from_ymd_opt(2025, 6, 2);
+        let report = TeamReport::new(&bundles, since, None, true);
+        assert_eq!(report.totals.cost, 7.0);
+        let users: Vec<_> = report.users.iter().map(|u| u.user.as_str()).collect();
+        assert_eq!(users, vec!["user-2", "user-1"]);
+        assert!(
+            report
+                .projects
+                .iter()
+                .all(|p| p.project.starts_with("project-"))
+        );
+    }
+}
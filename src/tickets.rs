@@ -0,0 +1,503 @@
+//! Cost attribution per ticket (`tickets` command)
+//!
+//! Ticket IDs such as `PROJ-1234` are extracted from each conversation's
+//! summary, user prompts, and git branch with the regular expressions of the
+//! `tickets` config section. A priced response is attributed to the tickets
+//! in the branch it was made on; otherwise its cost is split evenly over the
+//! tickets mentioned anywhere in the conversation. Responses of conversations
+//! without tickets are reported as unattributed.
+
+use crate::config_v2::TicketsConfig;
+use crate::conversation_parser::{Conversation, ConversationParser, MessageContentBlock};
+use crate::currency::{self, Money};
+use crate::display::format_number;
+use crate::pricing::{ModelPricing, PricingFetcher};
+use crate::pricing_cache::PricingCache;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use colored::Colorize;
+use csv::Writer;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+/// JIRA-style keys: an uppercase project key, a dash, and a number
+pub const DEFAULT_PATTERN: &str = r"\b[A-Z][A-Z0-9]{1,9}-[1-9][0-9]{0,6}\b";
+
+/// Prefixes of identifiers that look like tickets but are not (UTF-8, SHA-256, ...)
+const BUILTIN_IGNORED: &[&str] = &[
+    "AES", "CVE", "GPT", "HTTP", "ISO", "PEP", "RFC", "RSA", "SHA", "TLS", "UTF",
+];
+
+/// Finds ticket IDs in text
+pub struct TicketMatcher {
+    patterns: Vec<Regex>,
+    ignore: HashSet<String>,
+}
+
+/// Usage attributed to one ticket
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TicketStats {
+    pub ticket: String,
+    pub sessions: usize,
+    /// Responses attributed to the ticket, in full or in part
+    pub requests: u64,
+    pub tokens: u64,
+    /// In the display currency
+    pub cost: f64,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+    pub projects: Vec<String>,
+}
+
+/// Usage of conversations that mention no ticket
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UnattributedUsage {
+    pub sessions: usize,
+    pub requests: u64,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TicketReport {
+    pub total_cost: f64,
+    pub attributed_cost: f64,
+    /// Tickets by cost, most expensive first
+    pub tickets: Vec<TicketStats>,
+    pub unattributed: UnattributedUsage,
+}
+
+/// Running totals of one ticket; tokens are split with the cost, so they are fractional
+#[derive(Debug, Default)]
+struct Totals {
+    sessions: BTreeSet<String>,
+    requests: u64,
+    tokens: f64,
+    cost: f64,
+    first_seen: Option<DateTime<Utc>>,
+    last_seen: Option<DateTime<Utc>>,
+    projects: BTreeSet<String>,
+}
+
+impl Totals {
+    fn add(&mut self, other: Totals) {
+        self.sessions.extend(other.sessions);
+        self.requests += other.requests;
+        self.tokens += other.tokens;
+        self.cost += other.cost;
+        self.first_seen = self.first_seen.into_iter().chain(other.first_seen).min();
+        self.last_seen = self.last_seen.into_iter().chain(other.last_seen).max();
+        self.projects.extend(other.projects);
+    }
+}
+
+impl TicketMatcher {
+    /// Match `patterns` if given, else the configured patterns, else [`DEFAULT_PATTERN`]
+    pub fn new(config: &TicketsConfig, patterns: &[String]) -> Result<Self> {
+        let patterns = if !patterns.is_empty() {
+            patterns
+        } else {
+            &config.patterns
+        };
+        let patterns = if patterns.is_empty() {
+            vec![Regex::new(DEFAULT_PATTERN).expect("default ticket pattern is valid")]
+        } else {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Regex::new(pattern)
+                        .with_context(|| format!("Invalid ticket pattern '{}'", pattern))
+                })
+                .collect::<Result<_>>()?
+        };
+        let ignore = BUILTIN_IGNORED
+            .iter()
+            .map(|prefix| prefix.to_string())
+            .chain(config.ignore.iter().map(|prefix| prefix.to_uppercase()))
+            .collect();
+        Ok(Self { patterns, ignore })
+    }
+
+    /// Ticket IDs in `text`, in order of first appearance
+    pub fn find(&self, text: &str) -> Vec<String> {
+        let mut tickets = Vec::new();
+        for regex in &self.patterns {
+            for captures in regex.captures_iter(text) {
+                // The first capture group is the ID when the pattern has one
+                let Some(ticket) = captures.get(1).or_else(|| captures.get(0)) else {
+                    continue;
+                };
+                let ticket = ticket.as_str();
+                let prefix = ticket.split('-').next().unwrap_or(ticket);
+                if !self.ignore.contains(&prefix.to_uppercase())
+                    && !tickets.iter().any(|t| t == ticket)
+                {
+                    tickets.push(ticket.to_string());
+                }
+            }
+        }
+        tickets
+    }
+}
+
+/// Scan all conversations under `claude_dir` and attribute their cost to tickets
+pub fn analyze_tickets(
+    claude_dir: &Path,
+    matcher: &TicketMatcher,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    project_filter: Option<&str>,
+) -> Result<TicketReport> {
+    let parser = ConversationParser::new(claude_dir.to_path_buf());
+    let files = parser.find_conversation_files()?;
+    let pricing_data = PricingCache::effective_pricing();
+
+    let per_file: Vec<BTreeMap<Option<String>, Totals>> = files
+        .par_iter()
+        .filter_map(|path| parser.parse_conversation(path).ok())
+        .filter_map(|conversation| {
+            let project = conversation
+                .file_path
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if project_filter.is_some_and(|filter| !project.contains(filter)) {
+                return None;
+            }
+            Some(attribute_conversation(
+                &conversation,
+                &project,
+                matcher,
+                &pricing_data,
+                since,
+                until,
+            ))
+        })
+        .collect();
+
+    let mut by_ticket: BTreeMap<Option<String>, Totals> = BTreeMap::new();
+    for totals in per_file {
+        for (ticket, ticket_totals) in totals {
+            by_ticket.entry(ticket).or_default().add(ticket_totals);
+        }
+    }
+    Ok(build_report(by_ticket))
+}
+
+/// Tickets mentioned in the summary, the user prompts, and the git branches of a conversation
+fn conversation_tickets(conversation: &Conversation, matcher: &TicketMatcher) -> Vec<String> {
+    let mut tickets = Vec::new();
+    let mut add = |found: Vec<String>| {
+        for ticket in found {
+            if !tickets.contains(&ticket) {
+                tickets.push(ticket);
+            }
+        }
+    };
+    if let Some(summary) = &conversation.summary {
+        add(matcher.find(&summary.summary));
+    }
+    for message in &conversation.messages {
+        if let Some(branch) = &message.git_branch {
+            add(matcher.find(branch));
+        }
+        if message.role != "user" {
+            continue;
+        }
+        for block in &message.content {
+            if let MessageContentBlock::Text { text, .. } = block {
+                add(matcher.find(text));
+            }
+        }
+    }
+    tickets
+}
+
+/// Totals of one conversation by ticket; `None` collects unattributed usage
+fn attribute_conversation(
+    conversation: &Conversation,
+    project: &str,
+    matcher: &TicketMatcher,
+    pricing_data: &HashMap<String, ModelPricing>,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+) -> BTreeMap<Option<String>, Totals> {
+    let fetcher = PricingFetcher::new();
+    let rate = currency::current().rate;
+    let session = conversation.file_path.to_string_lossy().into_owned();
+    let mentioned = conversation_tickets(conversation, matcher);
+    let mut totals: BTreeMap<Option<String>, Totals> = BTreeMap::new();
+
+    for message in &conversation.messages {
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let date = message.timestamp.with_timezone(&Local).date_naive();
+        if since.is_some_and(|since| date < since) || until.is_some_and(|until| date > until) {
+            continue;
+        }
+        let model = message.model.as_deref().unwrap_or("unknown");
+        let cost = fetcher
+            .get_model_pricing(pricing_data, model)
+            .map(|pricing| {
+                rate * fetcher.calculate_cost(
+                    &pricing,
+                    usage.input_tokens,
+                    usage.output_tokens,
+                    usage.cache_creation_tokens,
+                    usage.cache_read_tokens,
+                )
+            })
+            .unwrap_or(0.0);
+
+        let on_branch = message
+            .git_branch
+            .as_deref()
+            .map(|branch| matcher.find(branch))
+            .unwrap_or_default();
+        let tickets: Vec<Option<String>> = if !on_branch.is_empty() {
+            on_branch.into_iter().map(Some).collect()
+        } else if !mentioned.is_empty() {
+            mentioned.iter().cloned().map(Some).collect()
+        } else {
+            vec![None]
+        };
+
+        let share = tickets.len() as f64;
+        for ticket in tickets {
+            let entry = totals.entry(ticket).or_default();
+            entry.sessions.insert(session.clone());
+            entry.add(Totals {
+                sessions: BTreeSet::from([session.clone()]),
+                requests: 1,
+                tokens: usage.total_tokens() as f64 / share,
+                cost: cost / share,
+                first_seen: Some(message.timestamp),
+                last_seen: Some(message.timestamp),
+                projects: BTreeSet::from([project.to_string()]),
+            });
+        }
+    }
+    totals
+}
+
+fn build_report(by_ticket: BTreeMap<Option<String>, Totals>) -> TicketReport {
+    let mut report = TicketReport::default();
+    for (ticket, totals) in by_ticket {
+        report.total_cost += totals.cost;
+        let Some(ticket) = ticket else {
+            report.unattributed = UnattributedUsage {
+                sessions: totals.sessions.len(),
+                requests: totals.requests,
+                tokens: totals.tokens.round() as u64,
+                cost: totals.cost,
+            };
+            continue;
+        };
+        report.attributed_cost += totals.cost;
+        report.tickets.push(TicketStats {
+            ticket,
+            sessions: totals.sessions.len(),
+            requests: totals.requests,
+            tokens: totals.tokens.round() as u64,
+            cost: totals.cost,
+            first_seen: totals.first_seen,
+            last_seen: totals.last_seen,
+            projects: totals.projects.into_iter().collect(),
+        });
+    }
+    report.tickets.sort_by(|a, b| {
+        b.cost
+            .total_cmp(&a.cost)
+            .then_with(|| a.ticket.cmp(&b.ticket))
+    });
+    report
+}
+
+/// The report as CSV, one row per ticket followed by the unattributed usage
+pub fn report_to_csv(report: &TicketReport) -> Result<String> {
+    let code = &currency::current().code;
+    let date = |timestamp: Option<DateTime<Utc>>| {
+        timestamp
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    };
+    let mut wtr = Writer::from_writer(Vec::new());
+    wtr.write_record([
+        "Ticket",
+        "Sessions",
+        "Requests",
+        "Total Tokens",
+        &format!("Cost {}", code),
+        "First Seen",
+        "Last Seen",
+        "Projects",
+    ])?;
+    for ticket in &report.tickets {
+        wtr.write_record(&[
+            ticket.ticket.clone(),
+            ticket.sessions.to_string(),
+            ticket.requests.to_string(),
+            ticket.tokens.to_string(),
+            format!("{:.4}", ticket.cost),
+            date(ticket.first_seen),
+            date(ticket.last_seen),
+            ticket.projects.join(";"),
+        ])?;
+    }
+    if report.unattributed.requests > 0 {
+        let unattributed = &report.unattributed;
+        wtr.write_record(&[
+            "(unattributed)".to_string(),
+            unattributed.sessions.to_string(),
+            unattributed.requests.to_string(),
+            unattributed.tokens.to_string(),
+            format!("{:.4}", unattributed.cost),
+            String::new(),
+            String::new(),
+            String::new(),
+        ])?;
+    }
+    Ok(String::from_utf8(wtr.into_inner()?)?)
+}
+
+pub fn display_ticket_report(report: &TicketReport, limit: usize) {
+    println!("\n{}", "COST BY TICKET".bold());
+    println!("{}", "═".repeat(80));
+
+    if report.tickets.is_empty() {
+        println!("  No ticket IDs found in conversations");
+    } else {
+        println!(
+            "  {:<20} {:>12}",
+            "Attributed cost:".cyan(),
+            format!("{:.2}", Money(report.attributed_cost))
+        );
+        println!(
+            "  {:<20} {:>12}",
+            "Unattributed cost:".cyan(),
+            format!("{:.2}", Money(report.unattributed.cost))
+        );
+
+        println!(
+            "\n  {:<16} {:>8} {:>8} {:>10} {:>11}  {:<10} {:<10}",
+            "Ticket", "Sessions", "Requests", "Tokens", "Cost", "First", "Last"
+        );
+        println!("{}", "─".repeat(80));
+        let date = |timestamp: Option<DateTime<Utc>>| {
+            timestamp
+                .map(|t| t.with_timezone(&Local).format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        for ticket in report.tickets.iter().take(limit) {
+            println!(
+                "  {:<16} {:>8} {:>8} {:>10} {:>11}  {:<10} {:<10}",
+                ticket.ticket.bold(),
+                ticket.sessions,
+                ticket.requests,
+                format_number(ticket.tokens),
+                format!("{:.2}", Money(ticket.cost)).green(),
+                date(ticket.first_seen),
+                date(ticket.last_seen)
+            );
+        }
+        if report.tickets.len() > limit {
+            println!(
+                "  {}",
+                format!("... {} more", report.tickets.len() - limit).dimmed()
+            );
+        }
+    }
+
+    if report.unattributed.requests > 0 {
+        println!(
+            "\n  {}",
+            format!(
+                "{} requests in {} sessions mention no ticket",
+                report.unattributed.requests, report.unattributed.sessions
+            )
+            .dimmed()
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_matcher_finds_tickets() {
+        let matcher = TicketMatcher::new(&TicketsConfig::default(), &[]).unwrap();
+        assert_eq!(
+            matcher.find("Fix PROJ-12 and OPS-7; see PROJ-12, UTF-8 and SHA-256"),
+            vec!["PROJ-12", "OPS-7"]
+        );
+
+        let config = TicketsConfig {
+            patterns: vec![r"#(\d+)".to_string()],
+            ignore: Vec::new(),
+        };
+        let matcher = TicketMatcher::new(&config, &[]).unwrap();
+        assert_eq!(matcher.find("closes #42"), vec!["42"]);
+        // Patterns from the command line replace the configured ones
+        let matcher = TicketMatcher::new(&config, &[r"\bgh-\d+".to_string()]).unwrap();
+        assert_eq!(matcher.find("gh-9 #42"), vec!["gh-9"]);
+        assert!(TicketMatcher::new(&config, &["(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_cost_is_attributed_by_branch_then_mentions() {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("projects").join("-home-me-app");
+        fs::create_dir_all(&project).unwrap();
+        let assistant = |uuid: &str, branch: &str| {
+            format!(
+                r#"{{"uuid":"{uuid}","type":"assistant","timestamp":"2024-01-01T10:00:01Z","sessionId":"s1","gitBranch":"{branch}","message":{{"id":"{uuid}","role":"assistant","model":"claude-sonnet-4-20250514","content":[{{"type":"text","text":"Done"}}],"usage":{{"input_tokens":1000,"output_tokens":1000}}}}}}"#
+            )
+        };
+        let mentions = [
+            r#"{"type":"summary","summary":"Login fixes for APP-1","leafUuid":"a2"}"#.to_string(),
+            r#"{"uuid":"u1","type":"user","timestamp":"2024-01-01T10:00:00Z","sessionId":"s1","message":{"role":"user","content":"Also look at APP-2"}}"#.to_string(),
+            assistant("a1", "main"),
+            assistant("a2", "feature/APP-3-tokens"),
+        ];
+        fs::write(project.join("s1.jsonl"), mentions.join("\n")).unwrap();
+        let untracked = [
+            r#"{"uuid":"u1","type":"user","timestamp":"2024-01-01T11:00:00Z","sessionId":"s2","message":{"role":"user","content":"Refactor"}}"#.to_string(),
+            assistant("a3", "main"),
+        ];
+        fs::write(project.join("s2.jsonl"), untracked.join("\n")).unwrap();
+
+        let matcher = TicketMatcher::new(&TicketsConfig::default(), &[]).unwrap();
+        let report = analyze_tickets(dir.path(), &matcher, None, None, None).unwrap();
+
+        // The branch's ticket gets the whole second response; the first is split
+        // over all tickets mentioned in the conversation
+        let ticket = |id: &str| report.tickets.iter().find(|t| t.ticket == id).unwrap();
+        assert_eq!(report.tickets[0].ticket, "APP-3");
+        assert_eq!(ticket("APP-3").tokens, 2000 + 667);
+        assert_eq!(ticket("APP-1").tokens, 667);
+        assert_eq!(ticket("APP-3").requests, 2);
+        assert_eq!(ticket("APP-2").requests, 1);
+        assert_eq!(ticket("APP-1").projects, vec!["-home-me-app"]);
+        assert_eq!(report.unattributed.sessions, 1);
+        assert_eq!(report.unattributed.tokens, 2000);
+        assert!((report.attributed_cost - 2.0 * report.unattributed.cost).abs() < 1e-9);
+
+        let csv = report_to_csv(&report).unwrap();
+        assert!(csv.starts_with("Ticket,Sessions,Requests,Total Tokens,Cost "));
+        assert!(
+            csv.lines()
+                .last()
+                .unwrap()
+                .starts_with("(unattributed),1,1,2000,")
+        );
+    }
+}
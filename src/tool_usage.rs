@@ -0,0 +1,389 @@
+//! Tool usage analytics across sessions (`tools` command)
+//!
+//! Aggregates the tool calls (Bash, Edit, Read, ...) in every conversation by
+//! tool name. A call's cost is its share of the assistant response that issued
+//! it: the response's cost split evenly over the tool calls it made. A call
+//! fails when its `tool_result` is flagged `is_error`.
+
+use crate::conversation_parser::{Conversation, ConversationParser, MessageContentBlock};
+use crate::pricing::{ModelPricing, PricingFetcher};
+use crate::pricing_cache::PricingCache;
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use colored::Colorize;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Per-tool totals over all matching conversations
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub error_rate_pct: f64,
+    /// Tokens of the responses that issued the calls, split per call
+    pub tokens: u64,
+    pub cost: f64,
+    /// Per-project breakdown, most calls first
+    pub projects: Vec<ToolProjectStats>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ToolProjectStats {
+    pub project: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ToolUsageReport {
+    pub total_calls: u64,
+    pub total_errors: u64,
+    pub total_cost: f64,
+    /// Tools by number of calls
+    pub tools: Vec<ToolStats>,
+}
+
+/// Counters for one tool in one project
+#[derive(Debug, Default, Clone, Copy)]
+struct Counts {
+    calls: u64,
+    errors: u64,
+    tokens: f64,
+    cost: f64,
+}
+
+impl Counts {
+    fn add(&mut self, other: &Counts) {
+        self.calls += other.calls;
+        self.errors += other.errors;
+        self.tokens += other.tokens;
+        self.cost += other.cost;
+    }
+}
+
+/// Scan all conversations under `claude_dir` and aggregate their tool calls
+pub fn analyze_tool_usage(
+    claude_dir: &Path,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    project_filter: Option<&str>,
+) -> Result<ToolUsageReport> {
+    let parser = ConversationParser::new(claude_dir.to_path_buf());
+    let files = parser.find_conversation_files()?;
+    let pricing_data = PricingCache::effective_pricing();
+
+    let per_file: Vec<(String, HashMap<String, Counts>)> = files
+        .par_iter()
+        .filter_map(|path| parser.parse_conversation(path).ok())
+        .filter_map(|conversation| {
+            let project = conversation
+                .file_path
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if project_filter.is_some_and(|filter| !project.contains(filter)) {
+                return None;
+            }
+            let counts = count_tool_calls(&conversation, &pricing_data, since, until);
+            (!counts.is_empty()).then_some((project, counts))
+        })
+        .collect();
+
+    let mut by_tool: BTreeMap<String, BTreeMap<String, Counts>> = BTreeMap::new();
+    for (project, counts) in per_file {
+        for (tool, tool_counts) in counts {
+            by_tool
+                .entry(tool)
+                .or_default()
+                .entry(project.clone())
+                .or_default()
+                .add(&tool_counts);
+        }
+    }
+
+    Ok(build_report(by_tool))
+}
+
+fn build_report(by_tool: BTreeMap<String, BTreeMap<String, Counts>>) -> ToolUsageReport {
+    let mut tools: Vec<ToolStats> = by_tool
+        .into_iter()
+        .map(|(tool, projects)| {
+            let mut total = Counts::default();
+            let mut projects: Vec<ToolProjectStats> = projects
+                .into_iter()
+                .map(|(project, counts)| {
+                    total.add(&counts);
+                    ToolProjectStats {
+                        project,
+                        calls: counts.calls,
+                        errors: counts.errors,
+                        cost: counts.cost,
+                    }
+                })
+                .collect();
+            projects.sort_by(|a, b| b.calls.cmp(&a.calls).then(a.project.cmp(&b.project)));
+
+            ToolStats {
+                tool,
+                calls: total.calls,
+                errors: total.errors,
+                error_rate_pct: percentage(total.errors, total.calls),
+                tokens: total.tokens.round() as u64,
+                cost: total.cost,
+                projects,
+            }
+        })
+        .collect();
+    tools.sort_by(|a, b| b.calls.cmp(&a.calls).then(a.tool.cmp(&b.tool)));
+
+    ToolUsageReport {
+        total_calls: tools.iter().map(|t| t.calls).sum(),
+        total_errors: tools.iter().map(|t| t.errors).sum(),
+        total_cost: tools.iter().map(|t| t.cost).sum(),
+        tools,
+    }
+}
+
+/// Tool calls of one conversation by tool name
+///
+/// Streamed responses put each content block on its own line and only the
+/// first line carries the usage, so a response runs from an assistant message
+/// with usage until the next one (or the next user turn).
+fn count_tool_calls(
+    conversation: &Conversation,
+    pricing_data: &HashMap<String, ModelPricing>,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+) -> HashMap<String, Counts> {
+    let fetcher = PricingFetcher::new();
+    let mut counts: HashMap<String, Counts> = HashMap::new();
+    let mut tool_names: HashMap<&str, &str> = HashMap::new();
+    // (tokens, cost) of the current response and the tools it called
+    let mut response: Option<(f64, f64)> = None;
+    let mut response_tools: Vec<&str> = Vec::new();
+
+    for message in &conversation.messages {
+        let date = message.timestamp.with_timezone(&Local).date_naive();
+        let in_range =
+            since.is_none_or(|since| date >= since) && until.is_none_or(|until| date <= until);
+
+        if message.role != "assistant" || message.usage.is_some() {
+            attribute_response(&mut counts, response.take(), &mut response_tools);
+        }
+        if let Some(usage) = &message.usage {
+            let model = message.model.as_deref().unwrap_or("unknown");
+            let cost = fetcher
+                .get_model_pricing(pricing_data, model)
+                .map(|pricing| {
+                    fetcher.calculate_cost(
+                        &pricing,
+                        usage.input_tokens,
+                        usage.output_tokens,
+                        usage.cache_creation_tokens,
+                        usage.cache_read_tokens,
+                    )
+                })
+                .unwrap_or(0.0);
+            response = Some((usage.total_tokens() as f64, cost));
+        }
+
+        for block in &message.content {
+            match block {
+                MessageContentBlock::ToolUse { id, name, .. } => {
+                    tool_names.insert(id, name);
+                    if in_range {
+                        counts.entry(name.clone()).or_default().calls += 1;
+                        response_tools.push(name);
+                    }
+                }
+                MessageContentBlock::ToolResult {
+                    tool_use_id,
+                    is_error: true,
+                    ..
+                } if in_range => {
+                    if let Some(name) = tool_names.get(tool_use_id.as_str()) {
+                        counts.entry(name.to_string()).or_default().errors += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    attribute_response(&mut counts, response, &mut response_tools);
+
+    counts
+}
+
+/// Split a finished response's tokens and cost evenly over the tools it called
+fn attribute_response(
+    counts: &mut HashMap<String, Counts>,
+    response: Option<(f64, f64)>,
+    tools: &mut Vec<&str>,
+) {
+    if let Some((tokens, cost)) = response
+        && !tools.is_empty()
+    {
+        let share = tools.len() as f64;
+        for tool in tools.iter() {
+            let entry = counts.entry(tool.to_string()).or_default();
+            entry.tokens += tokens / share;
+            entry.cost += cost / share;
+        }
+    }
+    tools.clear();
+}
+
+fn percentage(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100.0
+    }
+}
+
+pub fn display_tool_usage(report: &ToolUsageReport, json: bool, by_project: bool, top: usize) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!("\n{}", "TOOL USAGE".bold());
+    println!("{}", "═".repeat(72));
+
+    if report.tools.is_empty() {
+        println!("  No tool calls found");
+        return;
+    }
+
+    println!("  {:<20} {:>10}", "Tool calls:".cyan(), report.total_calls);
+    println!(
+        "  {:<20} {:>9.1}%",
+        "Failure rate:".cyan(),
+        percentage(report.total_errors, report.total_calls)
+    );
+    println!(
+        "  {:<20} {:>10}",
+        "Associated cost:".bold(),
+        format!("${:.2}", report.total_cost)
+    );
+
+    println!("\n{}", "By Tool".bold());
+    println!("{}", "─".repeat(72));
+    println!(
+        "  {:<24} {:>8} {:>8} {:>7} {:>9} {:>9}",
+        "", "Calls", "Errors", "Fail%", "Tokens", "Cost"
+    );
+    for tool in report.tools.iter().take(top) {
+        let fail = format!("{:>6.1}%", tool.error_rate_pct);
+        println!(
+            "  {:<24} {:>8} {:>8} {} {:>9} {:>9}",
+            truncate_label(&tool.tool, 24),
+            tool.calls,
+            tool.errors,
+            if tool.error_rate_pct >= 10.0 {
+                fail.red().to_string()
+            } else {
+                fail
+            },
+            format_tokens(tool.tokens),
+            format!("${:.2}", tool.cost)
+        );
+    }
+    if report.tools.len() > top {
+        println!(
+            "  {}",
+            format!("... {} more", report.tools.len() - top).dimmed()
+        );
+    }
+
+    if by_project {
+        for tool in report.tools.iter().take(top) {
+            println!("\n{}", format!("{} by Project", tool.tool).bold());
+            println!("{}", "─".repeat(72));
+            for project in &tool.projects {
+                println!(
+                    "  {:<40} {:>8} {:>8} {:>9}",
+                    truncate_label(&project.project, 40),
+                    project.calls,
+                    project.errors,
+                    format!("${:.2}", project.cost)
+                );
+            }
+        }
+    }
+    println!();
+}
+
+fn truncate_label(label: &str, max: usize) -> String {
+    let count = label.chars().count();
+    if count <= max {
+        label.to_string()
+    } else {
+        let tail: String = label.chars().skip(count - (max - 3)).collect();
+        format!("...{}", tail)
+    }
+}
+
+fn format_tokens(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.0}K", n as f64 / 1_000.0)
+    } else {
+        format!("{}", n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_tool_usage_counts_costs_and_failures() {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("projects").join("-home-me-app");
+        fs::create_dir_all(&project).unwrap();
+        let lines = [
+            r#"{"uuid":"u1","parentUuid":null,"type":"user","timestamp":"2024-01-01T10:00:00Z","sessionId":"s1","message":{"role":"user","content":"Run the tests"}}"#,
+            // Streamed response: usage on the first line, tool calls on the following ones
+            r#"{"uuid":"a1","parentUuid":"u1","type":"assistant","timestamp":"2024-01-01T10:00:01Z","sessionId":"s1","message":{"id":"m1","role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"text","text":"Running"}],"usage":{"input_tokens":1000,"output_tokens":1000}}}"#,
+            r#"{"uuid":"a2","parentUuid":"a1","type":"assistant","timestamp":"2024-01-01T10:00:01Z","sessionId":"s1","message":{"id":"m1","role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo test"}}],"usage":{"input_tokens":1000,"output_tokens":1000}}}"#,
+            r#"{"uuid":"a3","parentUuid":"a2","type":"assistant","timestamp":"2024-01-01T10:00:01Z","sessionId":"s1","message":{"id":"m1","role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"tool_use","id":"t2","name":"Read","input":{"file_path":"Cargo.toml"}}],"usage":{"input_tokens":1000,"output_tokens":1000}}}"#,
+            r#"{"uuid":"u2","parentUuid":"a3","type":"user","timestamp":"2024-01-01T10:00:02Z","sessionId":"s1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","is_error":true,"content":[{"type":"text","text":"exit code 101"}]},{"type":"tool_result","tool_use_id":"t2","content":"[package]"}]}}"#,
+            r#"{"uuid":"a4","parentUuid":"u2","type":"assistant","timestamp":"2024-01-01T10:00:03Z","sessionId":"s1","message":{"id":"m2","role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"tool_use","id":"t3","name":"Bash","input":{"command":"cargo build"}}],"usage":{"input_tokens":500,"output_tokens":500}}}"#,
+        ];
+        fs::write(project.join("s1.jsonl"), lines.join("\n")).unwrap();
+
+        let report = analyze_tool_usage(dir.path(), None, None, None).unwrap();
+        assert_eq!(report.total_calls, 3);
+        assert_eq!(report.total_errors, 1);
+
+        let bash = &report.tools[0];
+        assert_eq!(bash.tool, "Bash");
+        assert_eq!((bash.calls, bash.errors), (2, 1));
+        assert!((bash.error_rate_pct - 50.0).abs() < 1e-9);
+        // Half of the first response plus all of the second
+        assert_eq!(bash.tokens, 1000 + 1000);
+        assert_eq!(bash.projects[0].project, "-home-me-app");
+
+        let read = &report.tools[1];
+        assert_eq!(
+            (read.tool.as_str(), read.calls, read.tokens),
+            ("Read", 1, 1000)
+        );
+        assert!((bash.cost - 2.0 * read.cost).abs() < 1e-9);
+
+        let filtered = analyze_tool_usage(dir.path(), None, None, Some("other")).unwrap();
+        assert!(filtered.tools.is_empty());
+    }
+}
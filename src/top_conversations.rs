@@ -0,0 +1,298 @@
+//! Top-N most expensive conversations (`top` command)
+//!
+//! Session files are split into conversations by their message threads (see
+//! [`Conversation::root_groups`]), so a long-lived session that handled several
+//! tasks shows each task separately, labelled with its first user prompt.
+
+use crate::conversation_parser::{
+    Conversation, ConversationMessage, ConversationParser, MessageContentBlock,
+};
+use crate::currency::{Currency, Money};
+use crate::models::TokenUsage;
+use crate::pricing::{ModelPricing, PricingFetcher};
+use crate::pricing_cache::PricingCache;
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use colored::Colorize;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Maximum characters kept from the first user prompt
+const PROMPT_SNIPPET_CHARS: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RankBy {
+    Cost,
+    Tokens,
+}
+
+/// One conversation with its aggregated usage
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversationCost {
+    pub project: String,
+    pub session_id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// First user prompt, whitespace-collapsed and truncated
+    pub first_prompt: String,
+    /// Model that accounted for most of the cost
+    pub model: String,
+    pub message_count: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost: f64,
+    pub file_path: PathBuf,
+}
+
+/// Scan all conversations under `claude_dir` and return the top `limit` by `rank_by`,
+/// with costs in `currency`
+pub fn find_top_conversations(
+    claude_dir: &Path,
+    rank_by: RankBy,
+    limit: usize,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    currency: &Currency,
+) -> Result<Vec<ConversationCost>> {
+    let parser = ConversationParser::new(claude_dir.to_path_buf());
+    let files = parser.find_conversation_files()?;
+    let pricing_data = PricingCache::effective_pricing();
+
+    let mut conversations: Vec<ConversationCost> = files
+        .par_iter()
+        .filter_map(|path| parser.parse_conversation(path).ok())
+        .flat_map_iter(|conversation| summarize_conversation(&conversation, &pricing_data))
+        .filter(|entry| {
+            let date = entry.started_at.with_timezone(&Local).date_naive();
+            since.is_none_or(|since| date >= since) && until.is_none_or(|until| date <= until)
+        })
+        .map(|mut entry| {
+            entry.cost *= currency.rate;
+            entry
+        })
+        .collect();
+
+    match rank_by {
+        RankBy::Cost => conversations.sort_by(|a, b| b.cost.total_cmp(&a.cost)),
+        RankBy::Tokens => conversations.sort_by_key(|c| std::cmp::Reverse(c.total_tokens)),
+    }
+    conversations.truncate(limit);
+    Ok(conversations)
+}
+
+/// Split a session file into conversations and price each of them
+fn summarize_conversation(
+    conversation: &Conversation,
+    pricing_data: &HashMap<String, ModelPricing>,
+) -> Vec<ConversationCost> {
+    let fetcher = PricingFetcher::new();
+    let session_id = conversation
+        .file_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let project = conversation
+        .file_path
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    conversation
+        .root_groups()
+        .into_iter()
+        .filter_map(|messages| {
+            let first = messages.first()?;
+            let last = messages.last()?;
+
+            let mut usage = TokenUsage::default();
+            let mut cost_by_model: HashMap<&str, f64> = HashMap::new();
+            for message in &messages {
+                let Some(msg_usage) = &message.usage else {
+                    continue;
+                };
+                usage.add(msg_usage);
+
+                let model = message.model.as_deref().unwrap_or("unknown");
+                let cost = fetcher
+                    .get_model_pricing(pricing_data, model)
+                    .map(|pricing| {
+                        fetcher.calculate_cost(
+                            &pricing,
+                            msg_usage.input_tokens,
+                            msg_usage.output_tokens,
+                            msg_usage.cache_creation_tokens,
+                            msg_usage.cache_read_tokens,
+                        )
+                    })
+                    .unwrap_or(0.0);
+                *cost_by_model.entry(model).or_default() += cost;
+            }
+
+            // Threads without any usage (e.g. interrupted prompts) are not conversations
+            if usage.total_tokens() == 0 {
+                return None;
+            }
+
+            let model = cost_by_model
+                .iter()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(model, _)| model.to_string())
+                .unwrap_or_default();
+
+            Some(ConversationCost {
+                project: project.clone(),
+                session_id: session_id.clone(),
+                started_at: first.timestamp,
+                ended_at: last.timestamp,
+                first_prompt: first_user_prompt(&messages).unwrap_or_default(),
+                model,
+                message_count: messages.len(),
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cache_creation_tokens: usage.cache_creation_tokens,
+                cache_read_tokens: usage.cache_read_tokens,
+                total_tokens: usage.total_tokens(),
+                cost: cost_by_model.values().sum(),
+                file_path: conversation.file_path.clone(),
+            })
+        })
+        .collect()
+}
+
+/// First text typed by the user (tool results are skipped)
+fn first_user_prompt(messages: &[&ConversationMessage]) -> Option<String> {
+    messages
+        .iter()
+        .filter(|m| m.role == "user")
+        .flat_map(|m| &m.content)
+        .find_map(|block| match block {
+            MessageContentBlock::Text { content_type, text }
+                if content_type == "text" && !text.trim().is_empty() =>
+            {
+                Some(snippet(text))
+            }
+            _ => None,
+        })
+}
+
+fn snippet(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() > PROMPT_SNIPPET_CHARS {
+        let truncated: String = collapsed.chars().take(PROMPT_SNIPPET_CHARS - 3).collect();
+        format!("{}...", truncated)
+    } else {
+        collapsed
+    }
+}
+
+pub fn display_top_conversations(conversations: &[ConversationCost], rank_by: RankBy, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(conversations) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    let title = match rank_by {
+        RankBy::Cost => "TOP CONVERSATIONS BY COST",
+        RankBy::Tokens => "TOP CONVERSATIONS BY TOKENS",
+    };
+    println!("\n{}", title.bold());
+    println!("{}", "═".repeat(72));
+
+    if conversations.is_empty() {
+        println!("  No conversations found");
+        return;
+    }
+
+    for (rank, entry) in conversations.iter().enumerate() {
+        let prompt = if entry.first_prompt.is_empty() {
+            "(no prompt)".dimmed().to_string()
+        } else {
+            entry.first_prompt.clone()
+        };
+        println!(
+            "\n{:>3}. {}  {}  {}",
+            rank + 1,
+            format!("{:.2}", Money(entry.cost)).green().bold(),
+            format!("{} tokens", format_tokens(entry.total_tokens)).cyan(),
+            entry.model.dimmed()
+        );
+        println!("     {}", prompt);
+        println!(
+            "     {}",
+            format!(
+                "{} · {} · {} · {} messages",
+                entry.project,
+                entry.session_id,
+                entry
+                    .started_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M"),
+                entry.message_count
+            )
+            .dimmed()
+        );
+    }
+    println!();
+}
+
+fn format_tokens(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.0}K", n as f64 / 1_000.0)
+    } else {
+        format!("{}", n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_top_conversations_split_and_rank() {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("projects").join("-home-me-app");
+        fs::create_dir_all(&project).unwrap();
+        let lines = [
+            r#"{"uuid":"u1","parentUuid":null,"type":"user","timestamp":"2024-01-01T10:00:00Z","sessionId":"s1","message":{"role":"user","content":"Refactor   the\nparser"}}"#,
+            r#"{"uuid":"a1","parentUuid":"u1","type":"assistant","timestamp":"2024-01-01T10:00:05Z","sessionId":"s1","message":{"id":"m1","role":"assistant","model":"claude-sonnet-4-20250514","content":[],"usage":{"input_tokens":1000,"output_tokens":1000}}}"#,
+            r#"{"uuid":"u2","parentUuid":null,"type":"user","timestamp":"2024-01-01T11:00:00Z","sessionId":"s1","message":{"role":"user","content":[{"type":"text","text":"Write docs"}]}}"#,
+            r#"{"uuid":"a2","parentUuid":"u2","type":"assistant","timestamp":"2024-01-01T11:00:05Z","sessionId":"s1","message":{"id":"m2","role":"assistant","model":"claude-opus-4-20250514","content":[],"usage":{"input_tokens":1000,"output_tokens":1000}}}"#,
+        ];
+        fs::write(project.join("s1.jsonl"), lines.join("\n")).unwrap();
+
+        let usd = Currency::usd();
+        let top = find_top_conversations(dir.path(), RankBy::Cost, 10, None, None, &usd).unwrap();
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].first_prompt, "Write docs");
+        assert_eq!(top[0].model, "claude-opus-4-20250514");
+        assert_eq!(top[1].first_prompt, "Refactor the parser");
+        assert_eq!(top[1].project, "-home-me-app");
+        assert_eq!(top[1].session_id, "s1");
+        assert!(top[0].cost > top[1].cost);
+
+        let top = find_top_conversations(dir.path(), RankBy::Tokens, 1, None, None, &usd).unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].total_tokens, 2000);
+
+        let eur = Currency {
+            code: "EUR".to_string(),
+            rate: 0.5,
+        };
+        let converted =
+            find_top_conversations(dir.path(), RankBy::Tokens, 1, None, None, &eur).unwrap();
+        assert!((converted[0].cost - top[0].cost * 0.5).abs() < 1e-12);
+    }
+}
@@ -16,8 +16,13 @@ use std::io;
 
 use super::{AppMode, ExportDialogState, ExportFormat, Tab, TuiApp};
 use crate::billing_blocks::BillingBlockManager;
+use crate::bookmarks::BookmarkStore;
+use crate::clipboard::Clipboard;
+use crate::config_v2::ClipboardConfig;
 use crate::models::{Command, CommandAction, DailyReport, SessionReport};
+use crate::parser::UsageParser;
 use crate::pricing_cache::PricingCache;
+use crate::quota::{Plan, QuotaLimits};
 use crate::tui::PricingCacheStatus;
 use crate::tui_visuals::VisualEffectsManager;
 
@@ -77,15 +82,27 @@ impl TuiApp {
             command_palette_query: String::new(),
             available_commands: available_commands.clone(),
             filtered_commands: available_commands,
-            bookmarked_sessions: Vec::new(),
+            prompt: None,
+            usage_parser: None,
+            model_filter: None,
+            date_range: None,
+            bookmarks: BookmarkStore::load().unwrap_or_default(),
+            bookmarks_table_state: TableState::default(),
+            note_input: None,
             comparison_sessions: Vec::new(),
+            comparison_view: None,
             billing_manager,
             billing_blocks_table_state: TableState::default(),
             billing_blocks_scroll_state,
+            block_detail: None,
             show_billing_summary: true,
+            quota_plan: Plan::default(),
+            quota_limits: Plan::default().limits(),
+            clipboard: Clipboard::default(),
             pricing_cache_status,
             visual_mode_start: None,
             visual_mode_selections: Vec::new(),
+            selection_export: None,
             search_cursor_position: 0,
             g_pressed: false,
             export_dialog_state: ExportDialogState {
@@ -100,6 +117,13 @@ impl TuiApp {
             weekly_table_state: TableState::default(),
             cache_analysis: None,
             cache_table_state: TableState::default(),
+            claude_dirs: Vec::new(),
+            conversation_view: None,
+            daily_calendar: None,
+            session_day_filter: None,
+            tab_bar_area: Rect::default(),
+            table_body_area: None,
+            last_click: None,
         };
 
         // Apply initial filters and sorting
@@ -107,6 +131,31 @@ impl TuiApp {
         app
     }
 
+    /// Claude directories used to open session conversations
+    pub fn with_claude_dirs(mut self, claude_dirs: Vec<std::path::PathBuf>) -> Self {
+        self.claude_dirs = claude_dirs;
+        self
+    }
+
+    /// Parser used to re-read usage when the palette sets a model filter
+    pub fn with_usage_parser(mut self, parser: UsageParser) -> Self {
+        self.usage_parser = Some(parser);
+        self
+    }
+
+    /// How exports and copies reach the clipboard (`clipboard` section of the config)
+    pub fn with_clipboard(mut self, config: &ClipboardConfig) -> Self {
+        self.clipboard = Clipboard::new(config);
+        self
+    }
+
+    /// Subscription quota the current block is measured against on the Billing tab
+    pub fn with_quota(mut self, plan: Plan, limits: QuotaLimits) -> Self {
+        self.quota_plan = plan;
+        self.quota_limits = limits;
+        self
+    }
+
     // State extraction methods for resume functionality
     pub fn get_current_tab_index(&self) -> usize {
         self.current_tab as usize
@@ -116,10 +165,6 @@ impl TuiApp {
         self.search_query.clone()
     }
 
-    pub fn get_bookmarked_sessions(&self) -> Vec<String> {
-        self.bookmarked_sessions.clone()
-    }
-
     pub fn get_comparison_sessions(&self) -> Vec<String> {
         self.comparison_sessions.clone()
     }
@@ -150,7 +195,8 @@ impl TuiApp {
             3 => Tab::Sessions,
             4 => Tab::Cache,
             5 => Tab::BillingBlocks,
-            6 => Tab::Help,
+            6 => Tab::Bookmarks,
+            7 => Tab::Help,
             _ => Tab::Overview,
         };
     }
@@ -162,8 +208,19 @@ impl TuiApp {
         }
     }
 
-    pub fn set_bookmarked_sessions(&mut self, bookmarks: Vec<String>) {
-        self.bookmarked_sessions = bookmarks;
+    /// Move bookmarks kept in older TUI session state into the bookmark store
+    pub fn import_legacy_bookmarks(&mut self, sessions: &[String]) -> Result<()> {
+        let missing: Vec<&String> = sessions
+            .iter()
+            .filter(|s| !self.bookmarks.contains(s))
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        for session in missing {
+            self.bookmarks.entry(session);
+        }
+        self.bookmarks.save()
     }
 
     pub fn set_comparison_sessions(&mut self, comparisons: Vec<String>) {
@@ -187,64 +244,218 @@ impl TuiApp {
         self.status_message = Some("\u{2728} Previous session state restored".to_string());
     }
 
-    fn create_available_commands() -> Vec<Command> {
+    pub(crate) fn create_available_commands() -> Vec<Command> {
+        let command = |name: &str,
+                       description: &str,
+                       shortcut: Option<&str>,
+                       action: CommandAction,
+                       category: &str| Command {
+            name: name.to_string(),
+            description: description.to_string(),
+            shortcut: shortcut.map(str::to_string),
+            action,
+            category: category.to_string(),
+        };
+        let sort = |key: &str| CommandAction::SetSort(key.to_string());
+        let filter = |key: &str| CommandAction::SetFilter(key.to_string());
+
         vec![
-            Command {
-                name: "Switch to Overview".to_string(),
-                description: "Go to overview tab".to_string(),
-                shortcut: Some("1".to_string()),
-                action: CommandAction::SwitchTab(0),
-                category: "Navigation".to_string(),
-            },
-            Command {
-                name: "Switch to Daily".to_string(),
-                description: "Go to daily usage tab".to_string(),
-                shortcut: Some("2".to_string()),
-                action: CommandAction::SwitchTab(1),
-                category: "Navigation".to_string(),
-            },
-            Command {
-                name: "Switch to Sessions".to_string(),
-                description: "Go to sessions tab".to_string(),
-                shortcut: Some("3".to_string()),
-                action: CommandAction::SwitchTab(2),
-                category: "Navigation".to_string(),
-            },
-            Command {
-                name: "Switch to Cache".to_string(),
-                description: "Go to cache analysis tab".to_string(),
-                shortcut: Some("5".to_string()),
-                action: CommandAction::SwitchTab(4),
-                category: "Navigation".to_string(),
-            },
-            Command {
-                name: "Switch to Billing".to_string(),
-                description: "Go to billing blocks tab".to_string(),
-                shortcut: Some("6".to_string()),
-                action: CommandAction::SwitchTab(5),
-                category: "Navigation".to_string(),
-            },
-            Command {
-                name: "Switch to Help".to_string(),
-                description: "Go to help tab".to_string(),
-                shortcut: Some("h".to_string()),
-                action: CommandAction::SwitchTab(6),
-                category: "Navigation".to_string(),
-            },
-            Command {
-                name: "Export Data".to_string(),
-                description: "Open export dialog (CSV/JSON to clipboard)".to_string(),
-                shortcut: Some("e/Ctrl+E".to_string()),
-                action: CommandAction::ExportData("current".to_string()),
-                category: "Data".to_string(),
-            },
-            Command {
-                name: "Bookmark Session".to_string(),
-                description: "Bookmark selected session".to_string(),
-                shortcut: Some("b".to_string()),
-                action: CommandAction::BookmarkSession("current".to_string()),
-                category: "Organization".to_string(),
-            },
+            command(
+                "Switch to Overview",
+                "Go to overview tab",
+                Some("1"),
+                CommandAction::SwitchTab(0),
+                "Navigation",
+            ),
+            command(
+                "Switch to Daily",
+                "Go to daily usage tab",
+                Some("2"),
+                CommandAction::SwitchTab(1),
+                "Navigation",
+            ),
+            command(
+                "Switch to Weekly",
+                "Go to weekly usage tab",
+                Some("3"),
+                CommandAction::SwitchTab(2),
+                "Navigation",
+            ),
+            command(
+                "Switch to Sessions",
+                "Go to sessions tab",
+                Some("4"),
+                CommandAction::SwitchTab(3),
+                "Navigation",
+            ),
+            command(
+                "Switch to Cache",
+                "Go to cache analysis tab",
+                Some("5"),
+                CommandAction::SwitchTab(4),
+                "Navigation",
+            ),
+            command(
+                "Switch to Billing",
+                "Go to billing blocks tab",
+                Some("6"),
+                CommandAction::SwitchTab(5),
+                "Navigation",
+            ),
+            command(
+                "Switch to Bookmarks",
+                "Go to bookmarked sessions tab",
+                Some("7"),
+                CommandAction::SwitchTab(6),
+                "Navigation",
+            ),
+            command(
+                "Switch to Help",
+                "Go to help tab",
+                Some("h"),
+                CommandAction::SwitchTab(7),
+                "Navigation",
+            ),
+            command(
+                "Sort by Date",
+                "Newest days and sessions first",
+                None,
+                sort("date"),
+                "Sort",
+            ),
+            command(
+                "Sort by Cost",
+                "Most expensive days and sessions first",
+                None,
+                sort("cost"),
+                "Sort",
+            ),
+            command(
+                "Sort by Tokens",
+                "Days and sessions with the most tokens first",
+                None,
+                sort("tokens"),
+                "Sort",
+            ),
+            command(
+                "Sort by Efficiency",
+                "Best cache hit rate and efficiency score first",
+                None,
+                sort("efficiency"),
+                "Sort",
+            ),
+            command(
+                "Sort by Project",
+                "Sessions by project path",
+                None,
+                sort("project"),
+                "Sort",
+            ),
+            command(
+                "Show All Time",
+                "Remove the time filter",
+                None,
+                filter("all"),
+                "Filter",
+            ),
+            command(
+                "Show Today",
+                "Limit daily and session rows to today",
+                None,
+                filter("today"),
+                "Filter",
+            ),
+            command(
+                "Show Last 7 Days",
+                "Limit daily and session rows to the last week",
+                None,
+                filter("week"),
+                "Filter",
+            ),
+            command(
+                "Show Last 30 Days",
+                "Limit daily and session rows to the last month",
+                None,
+                filter("month"),
+                "Filter",
+            ),
+            command(
+                "Filter by Model...",
+                "Re-read usage of models matching a name, e.g. opus",
+                None,
+                CommandAction::FilterModel,
+                "Filter",
+            ),
+            command(
+                "Cycle Model Family",
+                "Show All, Opus, Sonnet or Haiku usage in turn",
+                Some("M"),
+                CommandAction::CycleModelFamily,
+                "Filter",
+            ),
+            command(
+                "Pick Date Range...",
+                "Limit rows to a range, e.g. last monday..today or 7d",
+                None,
+                CommandAction::PickDateRange,
+                "Filter",
+            ),
+            command(
+                "Clear Filters",
+                "Remove search, time, date range, day and model filters",
+                None,
+                CommandAction::ClearFilters,
+                "Filter",
+            ),
+            command(
+                "Refresh Data",
+                "Reset sorting and filters to the loaded data",
+                Some("r"),
+                CommandAction::RefreshData,
+                "Data",
+            ),
+            command(
+                "Export Data",
+                "Open export dialog (CSV/JSON to clipboard)",
+                Some("e/Ctrl+E"),
+                CommandAction::ExportData,
+                "Data",
+            ),
+            command(
+                "Bookmark Session",
+                "Bookmark selected session",
+                Some("b"),
+                CommandAction::BookmarkSession,
+                "Organization",
+            ),
+            command(
+                "Compare Session",
+                "Add or remove the selected session from the comparison",
+                Some("x"),
+                CommandAction::CompareSelected,
+                "Organization",
+            ),
+            command(
+                "View Comparison",
+                "Show the marked sessions side by side",
+                Some("C"),
+                CommandAction::ShowComparison,
+                "Organization",
+            ),
+            command(
+                "Open Conversation",
+                "Read the messages of the selected session",
+                Some("c"),
+                CommandAction::OpenConversation,
+                "Sessions",
+            ),
+            command(
+                "Show Quick Help",
+                "Toggle the key binding popup",
+                Some("?"),
+                CommandAction::ShowHelp,
+                "Help",
+            ),
         ]
     }
 
@@ -283,43 +494,59 @@ impl TuiApp {
                 && let Ok(evt) = event::read()
             {
                 match evt {
-                    Event::Key(key) => {
-                        if key.kind == KeyEventKind::Press {
-                            // Add key press visual effect
-                            if let KeyCode::Char(c) = key.code {
-                                let key_str = if key.modifiers.contains(KeyModifiers::CONTROL) {
-                                    format!("Ctrl+{}", c)
-                                } else {
-                                    c.to_string()
-                                };
-                                let effect_pos = Rect {
-                                    x: (terminal.size()?.width / 2).saturating_sub(2),
-                                    y: terminal.size()?.height.saturating_sub(5),
-                                    width: 5,
-                                    height: 1,
-                                };
-                                self.visual_effects.add_key_effect(key_str, effect_pos);
-                            }
+                    Event::Key(key) if key.kind == KeyEventKind::Press => {
+                        // Add key press visual effect
+                        if let KeyCode::Char(c) = key.code {
+                            let key_str = if key.modifiers.contains(KeyModifiers::CONTROL) {
+                                format!("Ctrl+{}", c)
+                            } else {
+                                c.to_string()
+                            };
+                            let effect_pos = Rect {
+                                x: (terminal.size()?.width / 2).saturating_sub(2),
+                                y: terminal.size()?.height.saturating_sub(5),
+                                width: 5,
+                                height: 1,
+                            };
+                            self.visual_effects.add_key_effect(key_str, effect_pos);
+                        }
 
-                            match self.current_mode {
-                                AppMode::CommandPalette => {
-                                    self.handle_command_palette_input(key.code, key.modifiers)?;
-                                }
-                                AppMode::Search => {
+                        match self.current_mode {
+                            AppMode::CommandPalette => {
+                                self.handle_command_palette_input(key.code, key.modifiers)?;
+                            }
+                            AppMode::Search => {
+                                self.handle_search_input(key.code)?;
+                            }
+                            AppMode::Visual => {
+                                self.handle_visual_mode_input(key.code)?;
+                            }
+                            AppMode::ExportDialog => {
+                                self.handle_export_dialog_input(key.code)?;
+                            }
+                            AppMode::SelectionExport => {
+                                self.handle_selection_export_input(key.code)?;
+                            }
+                            AppMode::Conversation => {
+                                self.handle_conversation_input(key.code)?;
+                            }
+                            AppMode::NoteInput => {
+                                self.handle_note_input(key.code)?;
+                            }
+                            AppMode::Prompt => {
+                                self.handle_prompt_input(key.code)?;
+                            }
+                            AppMode::Compare => {
+                                self.handle_compare_input(key.code)?;
+                            }
+                            AppMode::BlockDetail => {
+                                self.handle_block_detail_input(key.code)?;
+                            }
+                            AppMode::Normal => {
+                                if self.search_mode {
                                     self.handle_search_input(key.code)?;
-                                }
-                                AppMode::Visual => {
-                                    self.handle_visual_mode_input(key.code)?;
-                                }
-                                AppMode::ExportDialog => {
-                                    self.handle_export_dialog_input(key.code)?;
-                                }
-                                AppMode::Normal => {
-                                    if self.search_mode {
-                                        self.handle_search_input(key.code)?;
-                                    } else {
-                                        self.handle_normal_input(key.code, key.modifiers)?;
-                                    }
+                                } else {
+                                    self.handle_normal_input(key.code, key.modifiers)?;
                                 }
                             }
                         }
@@ -0,0 +1,301 @@
+//! Per-session breakdown of a billing block, opened with Enter on the Billing tab
+//!
+//! The parser tags every usage record with its session, so each block already
+//! carries the usage of the sessions that were active in its window. Enter or
+//! `c` on a session opens its conversation; closing the conversation comes
+//! back to the breakdown.
+
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState},
+};
+
+use super::{AppMode, TuiApp};
+use crate::currency::Money;
+use crate::models::TokenUsage;
+
+#[derive(Debug)]
+pub(crate) struct BlockDetailState {
+    title: String,
+    total_cost: f64,
+    total_tokens: u64,
+    sessions: Vec<BlockSession>,
+    table_state: TableState,
+}
+
+/// A session's usage within one block
+#[derive(Debug)]
+struct BlockSession {
+    /// `project/session-id`
+    key: String,
+    project: String,
+    session_id: String,
+    summary: Option<String>,
+    /// Models by cost within the block
+    models: Vec<String>,
+    cost: f64,
+    tokens: u64,
+}
+
+impl BlockDetailState {
+    fn selected_session(&self) -> Option<&BlockSession> {
+        self.table_state
+            .selected()
+            .and_then(|i| self.sessions.get(i))
+    }
+
+    fn select_next(&mut self) {
+        let next = self
+            .table_state
+            .selected()
+            .map_or(0, |i| (i + 1).min(self.sessions.len().saturating_sub(1)));
+        self.table_state.select(Some(next));
+    }
+
+    fn select_previous(&mut self) {
+        let previous = self
+            .table_state
+            .selected()
+            .map_or(0, |i| i.saturating_sub(1));
+        self.table_state.select(Some(previous));
+    }
+}
+
+impl TuiApp {
+    /// Break the selected billing block down by session
+    pub(crate) fn open_block_detail(&mut self) {
+        let report = self.billing_manager.generate_report();
+        let Some(block) = self
+            .billing_blocks_table_state
+            .selected()
+            .and_then(|i| report.blocks.get(i))
+        else {
+            return;
+        };
+
+        let mut sessions = block_sessions(&block.usage);
+        if sessions.is_empty() {
+            self.status_message = Some("No sessions recorded in this block".to_string());
+            return;
+        }
+        for session in &mut sessions {
+            session.summary = self
+                .original_session_report
+                .sessions
+                .iter()
+                .find(|s| s.project_path == session.project && s.session_id == session.session_id)
+                .and_then(|s| s.summary.clone());
+        }
+
+        let mut table_state = TableState::default();
+        table_state.select(Some(0));
+        self.status_message = Some(format!(
+            "{} sessions in this block: Enter/c opens a conversation, Esc goes back",
+            sessions.len()
+        ));
+        self.block_detail = Some(BlockDetailState {
+            title: format!("{} {}", block.date, block.time_range),
+            total_cost: block.usage.total_cost,
+            total_tokens: block.usage.total_tokens(),
+            sessions,
+            table_state,
+        });
+        self.current_mode = AppMode::BlockDetail;
+    }
+
+    pub(crate) fn handle_block_detail_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(detail) = self.block_detail.as_mut() else {
+            self.current_mode = AppMode::Normal;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.block_detail = None;
+                self.current_mode = AppMode::Normal;
+                self.status_message = None;
+            }
+            KeyCode::Down | KeyCode::Char('j') => detail.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => detail.select_previous(),
+            KeyCode::Enter | KeyCode::Char('c') => {
+                if let Some(key) = detail.selected_session().map(|s| s.key.clone()) {
+                    self.open_conversation(&key);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub(crate) fn render_block_detail(&mut self, f: &mut Frame) {
+        let Some(detail) = self.block_detail.as_mut() else {
+            return;
+        };
+        let area = f.area();
+        let popup_area = Rect {
+            x: 2,
+            y: 2,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(4),
+        };
+        f.render_widget(Clear, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(popup_area);
+
+        let summary = Paragraph::new(Line::from(vec![
+            Span::styled("Cost: ", Style::default().fg(Color::White)),
+            Span::styled(
+                format!("{:.2}", Money(detail.total_cost)),
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  Tokens: ", Style::default().fg(Color::White)),
+            Span::styled(
+                Self::format_number(detail.total_tokens),
+                Style::default().fg(Color::Magenta),
+            ),
+            Span::styled("  Sessions: ", Style::default().fg(Color::White)),
+            Span::styled(
+                detail.sessions.len().to_string(),
+                Style::default().fg(Color::Blue),
+            ),
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("\u{23f0} Block {}", detail.title))
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+        f.render_widget(summary, chunks[0]);
+
+        let header = Row::new(
+            [
+                "Project", "Session", "Summary", "Models", "Cost", "Tokens", "Share",
+            ]
+            .map(|h| {
+                Cell::from(h).style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }),
+        )
+        .bottom_margin(1);
+        let total_cost = detail.total_cost;
+        let rows = detail.sessions.iter().map(|session| {
+            let share = if total_cost > 0.0 {
+                session.cost / total_cost * 100.0
+            } else {
+                0.0
+            };
+            Row::new(vec![
+                Cell::from(Self::truncate_text(&session.project, 24))
+                    .style(Style::default().fg(Color::White)),
+                Cell::from(session.session_id.chars().take(8).collect::<String>())
+                    .style(Style::default().fg(Color::Gray)),
+                Cell::from(
+                    session
+                        .summary
+                        .as_deref()
+                        .map(|s| Self::truncate_text(s, 40))
+                        .unwrap_or_default(),
+                )
+                .style(Style::default().fg(Color::White)),
+                Cell::from(session.models.join(", ")).style(Style::default().fg(Color::Yellow)),
+                Cell::from(format!("{:.2}", Money(session.cost)))
+                    .style(Style::default().fg(Color::Green)),
+                Cell::from(Self::format_number(session.tokens))
+                    .style(Style::default().fg(Color::Magenta)),
+                Cell::from(format!("{:.1}%", share)).style(Style::default().fg(Color::Cyan)),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(24),
+                Constraint::Length(9),
+                Constraint::Fill(1),
+                Constraint::Length(24),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(7),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Sessions (Enter/c: open conversation, Esc: back)")
+                .border_style(Style::default().fg(Color::Blue)),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("\u{25ba} ");
+        f.render_stateful_widget(table, chunks[1], &mut detail.table_state);
+    }
+}
+
+/// Sessions of a block's usage, most expensive first
+fn block_sessions(usage: &TokenUsage) -> Vec<BlockSession> {
+    let mut sessions: Vec<BlockSession> = usage
+        .by_session
+        .iter()
+        .map(|(key, usage)| {
+            let (project, session_id) = key.rsplit_once('/').unwrap_or(("", key));
+            let mut models: Vec<(&String, f64)> = usage
+                .by_model
+                .iter()
+                .map(|(model, usage)| (model, usage.total_cost))
+                .collect();
+            models.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+            BlockSession {
+                key: key.clone(),
+                project: project.to_string(),
+                session_id: session_id.to_string(),
+                summary: None,
+                models: models.into_iter().map(|(m, _)| m.clone()).collect(),
+                cost: usage.total_cost,
+                tokens: usage.total_tokens(),
+            }
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.cost.total_cmp(&a.cost).then(a.key.cmp(&b.key)));
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(cost: f64, tokens: u64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: tokens,
+            total_cost: cost,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_block_sessions() {
+        let mut block = TokenUsage::default();
+        block.add(&usage(0.5, 100).tagged("claude-sonnet-4", "-home-a/session-1"));
+        block.add(&usage(2.0, 300).tagged("claude-opus-4", "-home-b/session-2"));
+        block.add(&usage(1.0, 50).tagged("claude-haiku", "-home-a/session-1"));
+
+        let sessions = block_sessions(&block);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].key, "-home-b/session-2");
+        assert_eq!(sessions[0].project, "-home-b");
+        assert_eq!(sessions[0].session_id, "session-2");
+        assert_eq!(sessions[1].cost, 1.5);
+        assert_eq!(sessions[1].tokens, 150);
+        assert_eq!(sessions[1].models, ["claude-haiku", "claude-sonnet-4"]);
+    }
+}
@@ -2,8 +2,12 @@ use anyhow::Result;
 use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::widgets::ScrollbarState;
 
-use super::{AppMode, TuiApp};
-use crate::models::CommandAction;
+use chrono::Local;
+
+use super::{AppMode, PromptKind, PromptState, SortMode, Tab, TimeFilter, TuiApp};
+use crate::date_range;
+use crate::models::{Command, CommandAction};
+use crate::tui_visuals::ToastNotification;
 
 impl TuiApp {
     pub(crate) fn handle_command_palette_input(
@@ -18,14 +22,17 @@ impl TuiApp {
                 self.status_message = None;
             }
             KeyCode::Enter => {
-                if let Some(selected) = self.command_table_state.selected()
-                    && let Some(command) = self.filtered_commands.get(selected)
-                {
-                    let action = command.action.clone();
-                    self.execute_command(&action)?;
-                }
+                let action = self
+                    .command_table_state
+                    .selected()
+                    .and_then(|selected| self.filtered_commands.get(selected))
+                    .map(|command| command.action.clone());
+                // Commands may open a dialog or prompt of their own
                 self.current_mode = AppMode::Normal;
                 self.command_palette_query.clear();
+                if let Some(action) = action {
+                    self.execute_command(&action)?;
+                }
             }
             KeyCode::Down | KeyCode::Char('j') => {
                 let i = match self.command_table_state.selected() {
@@ -72,38 +79,216 @@ impl TuiApp {
                 self.set_current_tab(*index);
                 self.status_message = Some(format!("Switched to tab {}", index + 1));
             }
-            CommandAction::ExportData(_) => {
-                self.status_message =
-                    Some("Use 'claudelytics export' command for CSV export".to_string());
+            CommandAction::SetSort(key) => {
+                let sort_mode = match key.as_str() {
+                    "cost" => SortMode::Cost,
+                    "tokens" => SortMode::Tokens,
+                    "efficiency" => SortMode::Efficiency,
+                    "project" => SortMode::Project,
+                    _ => SortMode::Date,
+                };
+                self.set_sort_mode(sort_mode);
+            }
+            CommandAction::SetFilter(key) => {
+                let time_filter = match key.as_str() {
+                    "today" => TimeFilter::Today,
+                    "week" => TimeFilter::LastWeek,
+                    "month" => TimeFilter::LastMonth,
+                    _ => TimeFilter::All,
+                };
+                self.set_time_filter(time_filter);
+            }
+            CommandAction::FilterModel => self.open_prompt(PromptKind::ModelFilter),
+            CommandAction::CycleModelFamily => self.cycle_model_family()?,
+            CommandAction::PickDateRange => self.open_prompt(PromptKind::DateRange),
+            CommandAction::ClearFilters => self.clear_filters()?,
+            CommandAction::RefreshData => {
+                self.refresh_data()?;
+                self.visual_effects
+                    .add_toast(ToastNotification::success("Data refreshed!".to_string()));
             }
-            CommandAction::BookmarkSession(_) => {
+            CommandAction::ExportData => {
+                self.open_export_dialog();
+            }
+            CommandAction::BookmarkSession => {
                 self.bookmark_selected_session();
             }
-            _ => {
-                self.status_message = Some("Command executed".to_string());
+            CommandAction::CompareSelected => {
+                self.toggle_comparison_selection();
+            }
+            CommandAction::ShowComparison => {
+                self.open_comparison_view();
+            }
+            CommandAction::OpenConversation => {
+                // The selected row of the Sessions tab unless a bookmark is selected
+                if self.current_tab != Tab::Bookmarks {
+                    self.current_tab = Tab::Sessions;
+                }
+                self.open_conversation_view();
+            }
+            CommandAction::ShowHelp => {
+                self.show_help_popup = !self.show_help_popup;
             }
         }
         Ok(())
     }
 
-    fn filter_commands(&mut self) {
-        if self.command_palette_query.is_empty() {
-            self.filtered_commands = self.available_commands.clone();
-        } else {
-            let query = self.command_palette_query.to_lowercase();
-            self.filtered_commands = self
-                .available_commands
-                .iter()
-                .filter(|cmd| {
-                    cmd.name.to_lowercase().contains(&query)
-                        || cmd.description.to_lowercase().contains(&query)
-                        || cmd.category.to_lowercase().contains(&query)
-                })
-                .cloned()
-                .collect();
+    pub(crate) fn open_prompt(&mut self, kind: PromptKind) {
+        let text = match kind {
+            PromptKind::ModelFilter => self.model_filter.clone().unwrap_or_default(),
+            PromptKind::DateRange => String::new(),
+        };
+        self.prompt = Some(PromptState {
+            kind,
+            text,
+            error_message: None,
+        });
+        self.current_mode = AppMode::Prompt;
+        self.status_message = Some("Enter to apply (empty clears), Esc to cancel".to_string());
+    }
+
+    pub(crate) fn handle_prompt_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(prompt) = self.prompt.as_mut() else {
+            self.current_mode = AppMode::Normal;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Esc => {
+                self.prompt = None;
+                self.current_mode = AppMode::Normal;
+                self.status_message = None;
+            }
+            KeyCode::Enter => {
+                let text = prompt.text.trim().to_string();
+                match prompt.kind {
+                    PromptKind::ModelFilter => {
+                        self.prompt = None;
+                        self.current_mode = AppMode::Normal;
+                        self.set_model_filter((!text.is_empty()).then_some(text))?;
+                    }
+                    PromptKind::DateRange if text.is_empty() => {
+                        self.prompt = None;
+                        self.current_mode = AppMode::Normal;
+                        self.set_date_range(None);
+                    }
+                    PromptKind::DateRange => {
+                        match date_range::parse_range(&text, Local::now().date_naive()) {
+                            Ok(range) => {
+                                self.prompt = None;
+                                self.current_mode = AppMode::Normal;
+                                self.set_date_range(Some(range));
+                            }
+                            // Keep the prompt open so the range can be corrected
+                            Err(e) => prompt.error_message = Some(e.to_string()),
+                        }
+                    }
+                }
+            }
+            KeyCode::Backspace => {
+                prompt.text.pop();
+                prompt.error_message = None;
+            }
+            KeyCode::Char(c) => {
+                prompt.text.push(c);
+                prompt.error_message = None;
+            }
+            _ => {}
         }
+        Ok(())
+    }
 
+    fn filter_commands(&mut self) {
+        self.filtered_commands =
+            rank_commands(&self.command_palette_query, &self.available_commands);
         self.command_scroll_state = ScrollbarState::new(self.filtered_commands.len());
         self.command_table_state.select(Some(0));
     }
 }
+
+/// Commands matching `query`, best match first; all of them for an empty query
+pub(crate) fn rank_commands(query: &str, commands: &[Command]) -> Vec<Command> {
+    if query.trim().is_empty() {
+        return commands.to_vec();
+    }
+    let mut scored: Vec<(i64, &Command)> = commands
+        .iter()
+        .filter_map(|command| {
+            // A match in the name counts more than one in the category or description
+            [
+                fuzzy_score(query, &command.name).map(|score| score * 2),
+                fuzzy_score(query, &command.category),
+                fuzzy_score(query, &command.description),
+            ]
+            .into_iter()
+            .flatten()
+            .max()
+            .map(|score| (score, command))
+        })
+        .collect();
+    // Stable, so equal scores keep the palette order
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .map(|(_, command)| command.clone())
+        .collect()
+}
+
+/// Score `query` as a case-insensitive subsequence of `text`, `None` if it isn't one
+///
+/// Every matched character scores a point, with bonuses for runs of consecutive
+/// characters and for characters starting a word, and a penalty for how far
+/// into `text` the match starts. Spaces in the query are ignored.
+pub(crate) fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = (next..text.len()).find(|&i| text[i] == wanted)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 8;
+        }
+        if previous.is_none() {
+            score -= found.min(10) as i64;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("xyz", "Sort by Cost").is_none());
+        assert!(fuzzy_score("sbc", "Sort by Cost").is_some());
+        // Word starts beat the same letters inside words
+        assert!(fuzzy_score("sc", "Sort by Cost") > fuzzy_score("sc", "Describe"));
+        // Consecutive letters beat scattered ones
+        assert!(fuzzy_score("ost", "Cost") > fuzzy_score("ost", "Collision test"));
+    }
+
+    #[test]
+    fn test_rank_commands() {
+        let commands = TuiApp::create_available_commands();
+        assert_eq!(rank_commands("", &commands).len(), commands.len());
+
+        let names = |query: &str| -> Vec<String> {
+            rank_commands(query, &commands)
+                .into_iter()
+                .map(|command| command.name)
+                .collect()
+        };
+        assert_eq!(names("cost")[0], "Sort by Cost");
+        assert_eq!(names("date range")[0], "Pick Date Range...");
+        assert_eq!(names("conv")[0], "Open Conversation");
+        assert!(names("qqq").is_empty());
+    }
+}
@@ -0,0 +1,458 @@
+//! Side-by-side comparison of the sessions marked with `x`, opened with `C`
+//!
+//! Cost, tokens and cache ratio come from the session report; duration,
+//! message counts and models are read from each session's log when it can be
+//! found. The best value of each metric that has a direction is highlighted,
+//! and the session with the most best values is marked as the winner.
+
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+};
+use std::collections::HashMap;
+
+use super::{AppMode, TuiApp};
+use crate::conversation_parser::ConversationParser;
+use crate::currency::Money;
+use crate::models::SessionUsage;
+
+/// Width of the metric name column and of the bar labels
+const LABEL_WIDTH: u16 = 16;
+
+#[derive(Debug)]
+pub(crate) struct ComparedSession {
+    label: String,
+    cost: f64,
+    tokens: u64,
+    /// Share of input-side tokens read from the cache
+    cache_ratio: f64,
+    log: Option<SessionLogStats>,
+}
+
+/// Figures only the session log has
+#[derive(Debug)]
+struct SessionLogStats {
+    duration: chrono::Duration,
+    user_messages: usize,
+    assistant_messages: usize,
+    /// Models by number of messages
+    models: Vec<String>,
+}
+
+impl ComparedSession {
+    fn new(session: &SessionUsage, log: Option<SessionLogStats>) -> Self {
+        let project = session
+            .project_path
+            .rsplit(['/', '-'])
+            .find(|part| !part.is_empty())
+            .unwrap_or(&session.project_path);
+        let short_id: String = session.session_id.chars().take(8).collect();
+        let input_side =
+            session.input_tokens + session.cache_creation_tokens + session.cache_read_tokens;
+        Self {
+            label: format!("{} {}", TuiApp::truncate_text(project, 14), short_id),
+            cost: session.total_cost,
+            tokens: session.total_tokens,
+            cache_ratio: if input_side == 0 {
+                0.0
+            } else {
+                session.cache_read_tokens as f64 / input_side as f64
+            },
+            log,
+        }
+    }
+
+    fn cost_per_message(&self) -> Option<f64> {
+        self.log
+            .as_ref()
+            .filter(|log| log.assistant_messages > 0)
+            .map(|log| self.cost / log.assistant_messages as f64)
+    }
+}
+
+/// Comparison rows: label, one value per session, and the best session if the metric has one
+struct Metric {
+    name: &'static str,
+    values: Vec<String>,
+    best: Option<usize>,
+}
+
+impl TuiApp {
+    /// Compare the sessions marked with `x`
+    pub(crate) fn open_comparison_view(&mut self) {
+        if self.comparison_sessions.is_empty() {
+            self.status_message =
+                Some("Mark sessions with 'x' in the Sessions tab, then press C".to_string());
+            return;
+        }
+
+        let sessions: Vec<ComparedSession> = self
+            .comparison_sessions
+            .iter()
+            .filter_map(|key| {
+                let session = self
+                    .original_session_report
+                    .sessions
+                    .iter()
+                    .find(|s| format!("{}/{}", s.project_path, s.session_id) == *key)?;
+                Some(ComparedSession::new(session, self.session_log_stats(key)))
+            })
+            .collect();
+        if sessions.is_empty() {
+            self.status_message =
+                Some("None of the marked sessions are in the loaded data".to_string());
+            return;
+        }
+
+        self.status_message = Some(format!(
+            "Comparing {} sessions: Esc closes, X clears the selection",
+            sessions.len()
+        ));
+        self.comparison_view = Some(sessions);
+        self.current_mode = AppMode::Compare;
+    }
+
+    /// Duration, message counts and models from a session's log, if it can be read
+    fn session_log_stats(&self, session_key: &str) -> Option<SessionLogStats> {
+        let (claude_dir, path) = self.conversation_file(session_key)?;
+        let conversation = ConversationParser::new(claude_dir)
+            .parse_conversation(&path)
+            .ok()?;
+
+        let mut models: HashMap<&str, usize> = HashMap::new();
+        for model in conversation
+            .messages
+            .iter()
+            .filter_map(|m| m.model.as_deref())
+            .filter(|model| *model != "<synthetic>")
+        {
+            *models.entry(model).or_default() += 1;
+        }
+        let mut models: Vec<(&str, usize)> = models.into_iter().collect();
+        models.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        let count = |role: &str| {
+            conversation
+                .messages
+                .iter()
+                .filter(|m| m.role == role)
+                .count()
+        };
+        Some(SessionLogStats {
+            duration: match (conversation.started_at, conversation.ended_at) {
+                (Some(start), Some(end)) => end - start,
+                _ => chrono::Duration::zero(),
+            },
+            user_messages: count("user"),
+            assistant_messages: count("assistant"),
+            models: models.into_iter().map(|(m, _)| m.to_string()).collect(),
+        })
+    }
+
+    pub(crate) fn handle_compare_input(&mut self, key: KeyCode) -> Result<()> {
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('C') => {
+                self.comparison_view = None;
+                self.current_mode = AppMode::Normal;
+                self.status_message = None;
+            }
+            KeyCode::Char('X') => {
+                self.comparison_sessions.clear();
+                self.comparison_view = None;
+                self.current_mode = AppMode::Normal;
+                self.status_message = Some("Comparison selection cleared".to_string());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub(crate) fn render_comparison_view(&mut self, f: &mut Frame) {
+        let Some(sessions) = &self.comparison_view else {
+            return;
+        };
+        let area = f.area();
+        let popup_area = Rect {
+            x: 1,
+            y: 1,
+            width: area.width.saturating_sub(2),
+            height: area.height.saturating_sub(2),
+        };
+        f.render_widget(Clear, popup_area);
+
+        let metrics = comparison_metrics(sessions);
+        let winner = overall_winner(&metrics, sessions.len());
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(metrics.len() as u16 + 4),
+                Constraint::Min(0),
+            ])
+            .split(popup_area);
+
+        let header = Row::new(std::iter::once(Cell::from("")).chain(
+            sessions.iter().enumerate().map(|(i, s)| {
+                let label = if winner == Some(i) {
+                    format!("\u{1f3c6} {}", s.label)
+                } else {
+                    s.label.clone()
+                };
+                Cell::from(label).style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }),
+        ))
+        .bottom_margin(1);
+        let rows = metrics.iter().map(|metric| {
+            Row::new(
+                std::iter::once(Cell::from(metric.name).style(Style::default().fg(Color::Yellow)))
+                    .chain(metric.values.iter().enumerate().map(|(i, value)| {
+                        let style = if metric.best == Some(i) {
+                            Style::default()
+                                .fg(Color::Green)
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(Color::White)
+                        };
+                        Cell::from(value.clone()).style(style)
+                    })),
+            )
+        });
+        let widths = std::iter::once(Constraint::Length(LABEL_WIDTH))
+            .chain(sessions.iter().map(|_| Constraint::Fill(1)));
+        let table = Table::new(rows, widths).header(header).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("\u{2696}\u{fe0f} Session Comparison (Esc: close, X: clear selection)")
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+        f.render_widget(table, chunks[0]);
+
+        let bar_width = usize::from(chunks[1].width.saturating_sub(LABEL_WIDTH * 2 + 4));
+        let mut lines = Vec::new();
+        let mut bars = |title: &str, values: Vec<f64>, text: &dyn Fn(f64) -> String| {
+            lines.push(Line::from(Span::styled(
+                title.to_string(),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            let max = values.iter().cloned().fold(0.0, f64::max);
+            for (session, value) in sessions.iter().zip(&values) {
+                lines.push(Line::from(vec![
+                    Span::raw(format!(
+                        "  {:<width$}",
+                        session.label,
+                        width = usize::from(LABEL_WIDTH)
+                    )),
+                    Span::styled(
+                        bar(*value, max, bar_width),
+                        Style::default().fg(Color::Blue),
+                    ),
+                    Span::raw(format!(" {}", text(*value))),
+                ]));
+            }
+            lines.push(Line::from(""));
+        };
+        bars("Cost", sessions.iter().map(|s| s.cost).collect(), &|v| {
+            format!("{:.2}", Money(v))
+        });
+        bars(
+            "Tokens",
+            sessions.iter().map(|s| s.tokens as f64).collect(),
+            &|v| Self::format_number(v as u64),
+        );
+        bars(
+            "Cache ratio",
+            sessions.iter().map(|s| s.cache_ratio * 100.0).collect(),
+            &|v| format!("{:.1}%", v),
+        );
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Blue)),
+        );
+        f.render_widget(paragraph, chunks[1]);
+    }
+}
+
+fn comparison_metrics(sessions: &[ComparedSession]) -> Vec<Metric> {
+    let from_log = |value: &dyn Fn(&SessionLogStats) -> String| -> Vec<String> {
+        sessions
+            .iter()
+            .map(|s| s.log.as_ref().map_or_else(|| "-".to_string(), value))
+            .collect()
+    };
+    let cost_per_message: Vec<Option<f64>> =
+        sessions.iter().map(|s| s.cost_per_message()).collect();
+
+    vec![
+        Metric {
+            name: "Cost",
+            values: sessions
+                .iter()
+                .map(|s| format!("{:.2}", Money(s.cost)))
+                .collect(),
+            best: best_index(
+                &sessions.iter().map(|s| Some(s.cost)).collect::<Vec<_>>(),
+                true,
+            ),
+        },
+        Metric {
+            name: "Tokens",
+            values: sessions
+                .iter()
+                .map(|s| TuiApp::format_number(s.tokens))
+                .collect(),
+            best: best_index(
+                &sessions
+                    .iter()
+                    .map(|s| Some(s.tokens as f64))
+                    .collect::<Vec<_>>(),
+                true,
+            ),
+        },
+        Metric {
+            name: "Cache ratio",
+            values: sessions
+                .iter()
+                .map(|s| format!("{:.1}%", s.cache_ratio * 100.0))
+                .collect(),
+            best: best_index(
+                &sessions
+                    .iter()
+                    .map(|s| Some(s.cache_ratio))
+                    .collect::<Vec<_>>(),
+                false,
+            ),
+        },
+        Metric {
+            name: "Cost/response",
+            values: cost_per_message
+                .iter()
+                .map(|c| c.map_or_else(|| "-".to_string(), |c| format!("{:.4}", Money(c))))
+                .collect(),
+            best: best_index(&cost_per_message, true),
+        },
+        Metric {
+            name: "Duration",
+            values: from_log(&|log| format_duration(log.duration)),
+            best: None,
+        },
+        Metric {
+            name: "Messages",
+            values: from_log(&|log| {
+                format!(
+                    "{} user / {} assistant",
+                    log.user_messages, log.assistant_messages
+                )
+            }),
+            best: None,
+        },
+        Metric {
+            name: "Models",
+            values: from_log(&|log| {
+                if log.models.is_empty() {
+                    "-".to_string()
+                } else {
+                    log.models.join(", ")
+                }
+            }),
+            best: None,
+        },
+    ]
+}
+
+/// Index of the single best value, if at least two sessions have one and it isn't tied
+fn best_index(values: &[Option<f64>], lower_is_better: bool) -> Option<usize> {
+    let present: Vec<(usize, f64)> = values
+        .iter()
+        .enumerate()
+        .filter_map(|(i, v)| v.map(|v| (i, v)))
+        .collect();
+    if present.len() < 2 {
+        return None;
+    }
+    let key = |v: f64| if lower_is_better { -v } else { v };
+    let best = present
+        .iter()
+        .map(|(_, v)| key(*v))
+        .fold(f64::NEG_INFINITY, f64::max);
+    let mut winners = present.iter().filter(|(_, v)| key(*v) == best);
+    match (winners.next(), winners.next()) {
+        (Some((i, _)), None) => Some(*i),
+        _ => None,
+    }
+}
+
+/// Session with the most best values, unless tied
+fn overall_winner(metrics: &[Metric], sessions: usize) -> Option<usize> {
+    let mut wins = vec![0; sessions];
+    for best in metrics.iter().filter_map(|m| m.best) {
+        wins[best] += 1;
+    }
+    best_index(
+        &wins.iter().map(|w| Some(*w as f64)).collect::<Vec<_>>(),
+        false,
+    )
+    .filter(|i| wins[*i] > 0)
+}
+
+/// Bar of `width` cells filled in proportion to `value / max`
+fn bar(value: f64, max: f64, width: usize) -> String {
+    let filled = if max > 0.0 {
+        ((value / max) * width as f64).round() as usize
+    } else {
+        0
+    };
+    format!(
+        "{}{}",
+        "\u{2588}".repeat(filled.min(width)),
+        "\u{2591}".repeat(width.saturating_sub(filled))
+    )
+}
+
+fn format_duration(duration: chrono::Duration) -> String {
+    let minutes = duration.num_minutes();
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_index() {
+        assert_eq!(
+            best_index(&[Some(3.0), Some(1.0), Some(2.0)], true),
+            Some(1)
+        );
+        assert_eq!(
+            best_index(&[Some(3.0), Some(1.0), Some(2.0)], false),
+            Some(0)
+        );
+        // Missing values are skipped, ties and a single value have no winner
+        assert_eq!(best_index(&[None, Some(1.0), Some(2.0)], false), Some(2));
+        assert_eq!(best_index(&[Some(1.0), Some(1.0)], true), None);
+        assert_eq!(best_index(&[Some(1.0), None], true), None);
+    }
+
+    #[test]
+    fn test_bar_and_duration() {
+        assert_eq!(bar(5.0, 10.0, 4), "\u{2588}\u{2588}\u{2591}\u{2591}");
+        assert_eq!(bar(0.0, 0.0, 2), "\u{2591}\u{2591}");
+        assert_eq!(format_duration(chrono::Duration::minutes(135)), "2h 15m");
+        assert_eq!(format_duration(chrono::Duration::minutes(9)), "9m");
+    }
+}
@@ -0,0 +1,705 @@
+//! Full-text conversation viewer opened from the Sessions tab
+//!
+//! Messages are laid out once per width into styled lines: markdown text is
+//! wrapped, fenced code blocks are highlighted with syntect, and thinking
+//! blocks can be collapsed or expanded per message. Only a window of
+//! `WINDOW_SIZE` messages is read from the file at a time; moving past either
+//! end of the window loads the neighbouring messages.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+
+use super::{AppMode, TuiApp};
+use crate::conversation_parser::{
+    ConversationIndex, ConversationMessage, ConversationParser, MessageContentBlock,
+};
+use crate::tui_visuals::ToastNotification;
+
+const CODE_THEME: &str = "base16-ocean.dark";
+/// Messages kept in memory (and laid out) at once
+const WINDOW_SIZE: usize = 200;
+/// Lines scrolled per mouse wheel notch
+const WHEEL_LINES: isize = 3;
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn code_theme() -> &'static Theme {
+    static THEME: OnceLock<Theme> = OnceLock::new();
+    THEME.get_or_init(|| {
+        let mut themes = ThemeSet::load_defaults().themes;
+        themes.remove(CODE_THEME).unwrap_or_default()
+    })
+}
+
+#[derive(Debug)]
+pub(crate) struct ConversationViewState {
+    title: String,
+    index: ConversationIndex,
+    /// Position of `messages[0]` in the conversation
+    window_start: usize,
+    messages: Vec<ConversationMessage>,
+    /// Selected message within `messages`
+    selected: usize,
+    scroll: usize,
+    viewport_height: usize,
+    /// Conversation positions of messages with expanded thinking
+    expanded_thinking: HashSet<usize>,
+    layout: Option<ViewLayout>,
+    /// Scroll the selected message to the top after the next layout
+    focus_selected: bool,
+}
+
+/// Rendered lines for one width, with the first line of each message
+#[derive(Debug)]
+struct ViewLayout {
+    width: u16,
+    lines: Vec<Line<'static>>,
+    message_starts: Vec<usize>,
+}
+
+impl ConversationViewState {
+    fn new(title: String, index: ConversationIndex) -> Result<Self> {
+        let messages = index.load_messages(0..WINDOW_SIZE)?;
+        Ok(Self {
+            title,
+            index,
+            window_start: 0,
+            messages,
+            selected: 0,
+            scroll: 0,
+            viewport_height: 0,
+            expanded_thinking: HashSet::new(),
+            layout: None,
+            focus_selected: false,
+        })
+    }
+
+    /// Load the window holding `position` (roughly centered on it) and select it
+    fn load_window_around(&mut self, position: usize) -> Result<()> {
+        let start = position
+            .saturating_sub(WINDOW_SIZE / 2)
+            .min(self.index.len().saturating_sub(WINDOW_SIZE));
+        self.messages = self.index.load_messages(start..start + WINDOW_SIZE)?;
+        self.window_start = start;
+        self.selected = position - start;
+        self.layout = None;
+        self.focus_selected = true;
+        Ok(())
+    }
+
+    fn ensure_layout(&mut self, width: u16) {
+        if self.layout.as_ref().is_some_and(|l| l.width == width) {
+            return;
+        }
+        let mut lines = Vec::new();
+        let mut message_starts = Vec::with_capacity(self.messages.len());
+        for (index, message) in self.messages.iter().enumerate() {
+            message_starts.push(lines.len());
+            lines.extend(message_lines(
+                message,
+                self.expanded_thinking
+                    .contains(&(self.window_start + index)),
+                width as usize,
+            ));
+            lines.push(Line::from(""));
+        }
+        self.layout = Some(ViewLayout {
+            width,
+            lines,
+            message_starts,
+        });
+    }
+
+    fn line_count(&self) -> usize {
+        self.layout.as_ref().map_or(0, |l| l.lines.len())
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.line_count()
+            .saturating_sub(self.viewport_height.max(1))
+    }
+
+    fn scroll_by(&mut self, delta: isize) -> Result<()> {
+        let target = self.scroll.saturating_add_signed(delta);
+        // Scrolling past either end of the window moves on to the next message outside it
+        let window_end = self.window_start + self.messages.len();
+        if self.layout.is_some() {
+            if delta > 0 && target > self.max_scroll() && window_end < self.index.len() {
+                return self.load_window_around(window_end);
+            }
+            if delta < 0 && self.scroll == 0 && self.window_start > 0 {
+                return self.load_window_around(self.window_start - 1);
+            }
+        }
+        self.scroll = target.min(self.max_scroll());
+        // Keep the selection on the message at the top of the viewport
+        if let Some(layout) = &self.layout {
+            self.selected = layout
+                .message_starts
+                .iter()
+                .rposition(|&start| start <= self.scroll)
+                .unwrap_or(0);
+        }
+        Ok(())
+    }
+
+    /// Select the message at `position` in the conversation, loading its window if needed
+    fn select_message(&mut self, position: usize) -> Result<()> {
+        if self.index.is_empty() {
+            return Ok(());
+        }
+        let position = position.min(self.index.len() - 1);
+        if !(self.window_start..self.window_start + self.messages.len()).contains(&position) {
+            return self.load_window_around(position);
+        }
+        self.selected = position - self.window_start;
+        if let Some(layout) = &self.layout {
+            self.scroll = layout.message_starts[self.selected].min(self.max_scroll());
+        }
+        Ok(())
+    }
+
+    /// Position of the selected message in the conversation
+    fn selected_position(&self) -> usize {
+        self.window_start + self.selected
+    }
+
+    fn toggle_thinking(&mut self, all: bool) {
+        if all {
+            if self.expanded_thinking.is_empty() {
+                self.expanded_thinking = (0..self.index.len()).collect();
+            } else {
+                self.expanded_thinking.clear();
+            }
+        } else if !self.expanded_thinking.remove(&self.selected_position()) {
+            self.expanded_thinking.insert(self.selected_position());
+        }
+        // Re-layout on the next draw and keep the selected message in view
+        self.layout = None;
+        self.focus_selected = true;
+    }
+}
+
+impl TuiApp {
+    /// Claude directory and log file of a `project/session-id` session
+    pub(crate) fn conversation_file(&self, session_key: &str) -> Option<(PathBuf, PathBuf)> {
+        // Plain logs first, then compressed copies of them
+        ["", ".gz", ".zst"].iter().find_map(|suffix| {
+            let relative = format!("{}.jsonl{}", session_key, suffix);
+            self.claude_dirs.iter().find_map(|dir| {
+                let path = dir.join("projects").join(&relative);
+                path.exists().then(|| (dir.clone(), path))
+            })
+        })
+    }
+
+    /// Open the selected session (Sessions or Bookmarks tab) in the conversation viewer
+    pub(crate) fn open_conversation_view(&mut self) {
+        if let Some(session_key) = self.selected_session_key() {
+            self.open_conversation(&session_key);
+        }
+    }
+
+    /// Open a `project/session-id` session in the conversation viewer
+    pub(crate) fn open_conversation(&mut self, session_key: &str) {
+        let session_id = session_key.rsplit('/').next().unwrap_or(session_key);
+
+        let Some((claude_dir, path)) = self.conversation_file(session_key) else {
+            self.status_message = Some(format!(
+                "\u{274c} Conversation file not found for {}",
+                session_id
+            ));
+            return;
+        };
+
+        let view = ConversationParser::new(claude_dir)
+            .index_conversation(&path)
+            .and_then(|index| {
+                let title = index
+                    .summary
+                    .as_ref()
+                    .map(|s| s.summary.clone())
+                    .unwrap_or_else(|| session_id.to_string());
+                ConversationViewState::new(title, index)
+            });
+        match view {
+            Ok(view) => {
+                self.conversation_view = Some(view);
+                self.current_mode = AppMode::Conversation;
+                self.status_message = Some(
+                    "Conversation: j/k scroll, n/p message, t thinking, y yank, Esc close"
+                        .to_string(),
+                );
+            }
+            Err(e) => {
+                self.status_message = Some(format!("\u{274c} Failed to load conversation: {}", e));
+            }
+        }
+    }
+
+    pub(crate) fn handle_conversation_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(view) = self.conversation_view.as_mut() else {
+            self.current_mode = AppMode::Normal;
+            return Ok(());
+        };
+        let page = view.viewport_height.max(1) as isize;
+
+        let result = match key {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.conversation_view = None;
+                // Back to the billing block breakdown it was opened from, if any
+                self.current_mode = if self.block_detail.is_some() {
+                    AppMode::BlockDetail
+                } else {
+                    AppMode::Normal
+                };
+                self.status_message = None;
+                Ok(())
+            }
+            KeyCode::Down | KeyCode::Char('j') => view.scroll_by(1),
+            KeyCode::Up | KeyCode::Char('k') => view.scroll_by(-1),
+            KeyCode::PageDown | KeyCode::Char(' ') => view.scroll_by(page),
+            KeyCode::PageUp => view.scroll_by(-page),
+            KeyCode::Home | KeyCode::Char('g') => view.select_message(0),
+            KeyCode::End | KeyCode::Char('G') => {
+                if view.window_start + view.messages.len() < view.index.len() {
+                    view.select_message(view.index.len() - 1)
+                } else {
+                    view.scroll = view.max_scroll();
+                    view.scroll_by(0)
+                }
+            }
+            KeyCode::Char('n') => view.select_message(view.selected_position() + 1),
+            KeyCode::Char('p') => view.select_message(view.selected_position().saturating_sub(1)),
+            KeyCode::Char('t') => {
+                view.toggle_thinking(false);
+                Ok(())
+            }
+            KeyCode::Char('T') => {
+                view.toggle_thinking(true);
+                Ok(())
+            }
+            KeyCode::Char('y') => {
+                if let Some(message) = view.messages.get(view.selected) {
+                    let text = message_plain_text(message);
+                    match self.clipboard.copy(&text, "message.txt") {
+                        Ok(copied) => {
+                            self.visual_effects
+                                .add_toast(ToastNotification::success(format!(
+                                    "Copied message {} to {}",
+                                    view.selected_position() + 1,
+                                    copied.describe()
+                                )))
+                        }
+                        Err(e) => self
+                            .visual_effects
+                            .add_toast(ToastNotification::error(format!("Copy failed: {}", e))),
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            self.status_message = Some(format!("\u{274c} Failed to load messages: {}", e));
+        }
+        Ok(())
+    }
+
+    /// Scroll wheel in the conversation viewer
+    pub(crate) fn handle_conversation_mouse(&mut self, mouse: MouseEvent) {
+        let Some(view) = self.conversation_view.as_mut() else {
+            return;
+        };
+        let result = match mouse.kind {
+            MouseEventKind::ScrollDown => view.scroll_by(WHEEL_LINES),
+            MouseEventKind::ScrollUp => view.scroll_by(-WHEEL_LINES),
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            self.status_message = Some(format!("\u{274c} Failed to load messages: {}", e));
+        }
+    }
+
+    pub(crate) fn render_conversation_view(&mut self, f: &mut Frame) {
+        let Some(view) = self.conversation_view.as_mut() else {
+            return;
+        };
+        let area = f.area();
+        let popup_area = Rect {
+            x: area.x + 1,
+            y: area.y + 1,
+            width: area.width.saturating_sub(2),
+            height: area.height.saturating_sub(2),
+        };
+        f.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "\u{1f4ac} {} [{}/{}]",
+                view.title,
+                (view.selected_position() + 1).min(view.index.len()),
+                view.index.len()
+            ))
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(popup_area);
+        f.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(inner);
+
+        view.viewport_height = chunks[0].height as usize;
+        view.ensure_layout(chunks[0].width);
+        if std::mem::take(&mut view.focus_selected)
+            && let Some(layout) = &view.layout
+            && let Some(&start) = layout.message_starts.get(view.selected)
+        {
+            view.scroll = start;
+        }
+        view.scroll = view.scroll.min(view.max_scroll());
+
+        if let Some(layout) = &view.layout {
+            let selected_start = layout.message_starts.get(view.selected).copied();
+            let visible: Vec<Line> = layout
+                .lines
+                .iter()
+                .enumerate()
+                .skip(view.scroll)
+                .take(view.viewport_height)
+                .map(|(i, line)| {
+                    if Some(i) == selected_start {
+                        line.clone()
+                            .patch_style(Style::default().add_modifier(Modifier::REVERSED))
+                    } else {
+                        line.clone()
+                    }
+                })
+                .collect();
+            f.render_widget(Paragraph::new(visible), chunks[0]);
+        }
+
+        let hints = Line::from(vec![
+            Span::styled("j/k", Style::default().fg(Color::Yellow)),
+            Span::styled(" scroll  ", Style::default().fg(Color::Gray)),
+            Span::styled("n/p", Style::default().fg(Color::Yellow)),
+            Span::styled(" message  ", Style::default().fg(Color::Gray)),
+            Span::styled("t/T", Style::default().fg(Color::Yellow)),
+            Span::styled(" thinking  ", Style::default().fg(Color::Gray)),
+            Span::styled("y", Style::default().fg(Color::Yellow)),
+            Span::styled(" yank  ", Style::default().fg(Color::Gray)),
+            Span::styled("Esc", Style::default().fg(Color::Red)),
+            Span::styled(" close", Style::default().fg(Color::Gray)),
+        ]);
+        f.render_widget(Paragraph::new(hints), chunks[1]);
+    }
+}
+
+/// Header, content and tool lines of one message
+fn message_lines(
+    message: &ConversationMessage,
+    thinking_expanded: bool,
+    width: usize,
+) -> Vec<Line<'static>> {
+    let role_color = match message.role.as_str() {
+        "user" => Color::Green,
+        "assistant" => Color::Cyan,
+        _ => Color::Gray,
+    };
+    let mut header = vec![
+        Span::styled(
+            format!("\u{258c} {}", message.role),
+            Style::default().fg(role_color).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(" \u{b7} {}", message.timestamp.format("%Y-%m-%d %H:%M:%S")),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+    if let Some(model) = &message.model {
+        header.push(Span::styled(
+            format!(" \u{b7} {}", model),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    let mut lines = vec![Line::from(header)];
+
+    for block in &message.content {
+        match block {
+            MessageContentBlock::Text { content_type, text } if content_type == "thinking" => {
+                let count = text.lines().count();
+                if thinking_expanded {
+                    lines.push(Line::from(Span::styled(
+                        "\u{25be} thinking",
+                        Style::default().fg(Color::Magenta),
+                    )));
+                    let style = Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::ITALIC);
+                    for raw in text.lines() {
+                        for wrapped in wrap(raw, width.saturating_sub(2)) {
+                            lines.push(Line::from(Span::styled(format!("  {}", wrapped), style)));
+                        }
+                    }
+                } else {
+                    lines.push(Line::from(Span::styled(
+                        format!("\u{25b8} thinking ({} lines, t to expand)", count),
+                        Style::default().fg(Color::Magenta),
+                    )));
+                }
+            }
+            MessageContentBlock::Text { text, .. } => {
+                lines.extend(markdown_lines(text, width));
+            }
+            MessageContentBlock::ToolUse { name, input, .. } => {
+                let summary = truncate(&input.to_string(), width.saturating_sub(name.len() + 6));
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("\u{1f527} {}", name),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!(" {}", summary),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]));
+            }
+            MessageContentBlock::ToolResult { content, .. } => {
+                let first = content.lines().next().unwrap_or("");
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "\u{21b3} {} ({} lines)",
+                        truncate(first, width.saturating_sub(16)),
+                        content.lines().count()
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+    }
+    lines
+}
+
+/// Wrapped markdown text with highlighted fenced code blocks
+fn markdown_lines(text: &str, width: usize) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut code: Option<(String, Vec<&str>)> = None;
+
+    for raw in text.lines() {
+        let trimmed = raw.trim_start();
+        if let Some(fence) = trimmed.strip_prefix("```") {
+            match code.take() {
+                Some((lang, body)) => lines.extend(code_lines(&lang, &body)),
+                None => code = Some((fence.trim().to_string(), Vec::new())),
+            }
+            continue;
+        }
+        if let Some((_, body)) = code.as_mut() {
+            body.push(raw);
+            continue;
+        }
+
+        let heading = trimmed.trim_start_matches('#');
+        if heading.len() < trimmed.len() && heading.starts_with(' ') {
+            lines.push(Line::from(Span::styled(
+                heading.trim().to_string(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            continue;
+        }
+
+        let (prefix, body) = match trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            Some(item) => ("\u{2022} ".to_string(), item),
+            None => (String::new(), raw),
+        };
+        let indent = " ".repeat(prefix.chars().count());
+        let mut in_code = false;
+        for (i, wrapped) in wrap(body, width.saturating_sub(prefix.len()))
+            .into_iter()
+            .enumerate()
+        {
+            let lead = if i == 0 {
+                prefix.clone()
+            } else {
+                indent.clone()
+            };
+            let mut spans = vec![Span::raw(lead)];
+            spans.extend(inline_code_spans(&wrapped, &mut in_code));
+            lines.push(Line::from(spans));
+        }
+    }
+    // Unterminated fence: still show what we have
+    if let Some((lang, body)) = code {
+        lines.extend(code_lines(&lang, &body));
+    }
+    lines
+}
+
+fn code_lines(lang: &str, body: &[&str]) -> Vec<Line<'static>> {
+    let syntaxes = syntax_set();
+    let syntax = syntaxes
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, code_theme());
+    let gutter = Span::styled("\u{2502} ", Style::default().fg(Color::DarkGray));
+
+    body.iter()
+        .map(|raw| {
+            let line = format!("{}\n", raw);
+            let mut spans = vec![gutter.clone()];
+            match highlighter.highlight_line(&line, syntaxes) {
+                Ok(ranges) => spans.extend(ranges.into_iter().map(|(style, text)| {
+                    let fg = style.foreground;
+                    Span::styled(
+                        text.trim_end_matches('\n').to_string(),
+                        Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b)),
+                    )
+                })),
+                Err(_) => spans.push(Span::raw(raw.to_string())),
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Split on backticks, styling `inline code`. `in_code` carries across wrapped lines.
+fn inline_code_spans(text: &str, in_code: &mut bool) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for (i, part) in text.split('`').enumerate() {
+        if i > 0 {
+            *in_code = !*in_code;
+        }
+        if part.is_empty() {
+            continue;
+        }
+        let style = if *in_code {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        spans.push(Span::styled(part.to_string(), style));
+    }
+    spans
+}
+
+/// Greedy word wrap by character count; words longer than `width` are split
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for word in text.split(' ') {
+        let mut word: Vec<char> = word.chars().collect();
+        let needed = if current_len == 0 {
+            word.len()
+        } else {
+            current_len + 1 + word.len()
+        };
+        if needed > width && current_len > 0 {
+            lines.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        while word.len() > width {
+            let rest = word.split_off(width);
+            lines.push(word.into_iter().collect());
+            word = rest;
+        }
+        if current_len > 0 {
+            current.push(' ');
+            current_len += 1;
+        }
+        current_len += word.len();
+        current.extend(word);
+    }
+    lines.push(current);
+    lines
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(max.saturating_sub(1)).collect();
+        truncated.push('\u{2026}');
+        truncated
+    }
+}
+
+/// Message content as plain text for the clipboard
+fn message_plain_text(message: &ConversationMessage) -> String {
+    message
+        .content
+        .iter()
+        .map(|block| match block {
+            MessageContentBlock::Text { content_type, text } if content_type == "thinking" => {
+                format!("[thinking]\n{}", text)
+            }
+            MessageContentBlock::Text { text, .. } => text.clone(),
+            MessageContentBlock::ToolUse { name, input, .. } => {
+                format!("[tool: {}] {}", name, input)
+            }
+            MessageContentBlock::ToolResult { content, .. } => content.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+
+    #[test]
+    fn test_markdown_lines_highlights_code_blocks() {
+        let text = "Intro with `code`\n```rust\nfn main() {}\n```\n- item";
+        let lines = markdown_lines(text, 40);
+        let texts: Vec<String> = lines.iter().map(line_text).collect();
+        assert_eq!(
+            texts,
+            vec!["Intro with code", "\u{2502} fn main() {}", "\u{2022} item"]
+        );
+        // Inline code and the highlighted line carry colors
+        assert_eq!(lines[0].spans[2].style.fg, Some(Color::Yellow));
+        assert!(
+            lines[1].spans[1..]
+                .iter()
+                .any(|s| matches!(s.style.fg, Some(Color::Rgb(..))))
+        );
+    }
+}
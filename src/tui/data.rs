@@ -1,7 +1,14 @@
 use anyhow::Result;
+use chrono::{Local, NaiveDate};
 use ratatui::widgets::ScrollbarState;
 
+use super::export::daily_totals;
 use super::{SortMode, Tab, TimeFilter, TuiApp};
+use crate::date_range;
+use crate::reports::{
+    attach_session_summaries, generate_daily_report_sorted, generate_session_report_sorted,
+};
+use crate::tui_visuals::ToastNotification;
 
 impl TuiApp {
     pub(crate) fn refresh_data(&mut self) -> Result<()> {
@@ -15,13 +22,17 @@ impl TuiApp {
     }
 
     pub(crate) fn cycle_sort_mode(&mut self) {
-        self.sort_mode = match self.sort_mode {
+        self.set_sort_mode(match self.sort_mode {
             SortMode::Date => SortMode::Cost,
             SortMode::Cost => SortMode::Tokens,
             SortMode::Tokens => SortMode::Efficiency,
             SortMode::Efficiency => SortMode::Project,
             SortMode::Project => SortMode::Date,
-        };
+        });
+    }
+
+    pub(crate) fn set_sort_mode(&mut self, sort_mode: SortMode) {
+        self.sort_mode = sort_mode;
         self.apply_filters();
         let mode_str = match self.sort_mode {
             SortMode::Date => "Date",
@@ -34,12 +45,18 @@ impl TuiApp {
     }
 
     pub(crate) fn cycle_time_filter(&mut self) {
-        self.time_filter = match self.time_filter {
+        self.set_time_filter(match self.time_filter {
             TimeFilter::All => TimeFilter::Today,
             TimeFilter::Today => TimeFilter::LastWeek,
             TimeFilter::LastWeek => TimeFilter::LastMonth,
             TimeFilter::LastMonth => TimeFilter::All,
-        };
+        });
+    }
+
+    /// Set the time filter, replacing a date range picked in the palette
+    pub(crate) fn set_time_filter(&mut self, time_filter: TimeFilter) {
+        self.time_filter = time_filter;
+        self.date_range = None;
         self.apply_filters();
         let filter_str = match self.time_filter {
             TimeFilter::All => "All Time",
@@ -50,12 +67,104 @@ impl TuiApp {
         self.status_message = Some(format!("\u{1f4c5} Filter: {}", filter_str));
     }
 
+    /// Limit the Daily and Sessions tabs to a date range, replacing the time filter
+    pub(crate) fn set_date_range(&mut self, range: Option<(NaiveDate, NaiveDate)>) {
+        self.date_range = range;
+        self.time_filter = TimeFilter::All;
+        self.apply_filters();
+        self.status_message = Some(match range {
+            Some((since, until)) => format!(
+                "\u{1f4c5} Range: {} to {} ({} days, {} sessions)",
+                since,
+                until,
+                self.daily_report.daily.len(),
+                self.session_report.sessions.len()
+            ),
+            None => "\u{1f4c5} Date range cleared".to_string(),
+        });
+    }
+
+    /// Dates the Daily and Sessions tabs are limited to, if any
+    fn date_bounds(&self) -> Option<(NaiveDate, NaiveDate)> {
+        if self.date_range.is_some() {
+            return self.date_range;
+        }
+        let today = Local::now().date_naive();
+        match self.time_filter {
+            TimeFilter::All => None,
+            TimeFilter::Today => Some((today, today)),
+            TimeFilter::LastWeek => date_range::last(7, today).ok(),
+            TimeFilter::LastMonth => date_range::last(30, today).ok(),
+        }
+    }
+
+    /// Re-read usage keeping only models matching `filter`, or every model for `None`
+    pub(crate) fn set_model_filter(&mut self, filter: Option<String>) -> Result<()> {
+        let Some(parser) = self.usage_parser.as_mut() else {
+            self.status_message =
+                Some("\u{274c} Model filter needs the usage logs, which aren't loaded".to_string());
+            return Ok(());
+        };
+        parser.set_model_filter(filter.clone());
+        let (mut daily_map, mut session_map, mut billing_manager) = parser.parse_all()?;
+        // Same display currency as the data loaded at startup
+        crate::currency::current().convert(
+            &mut daily_map,
+            &mut session_map,
+            &mut billing_manager,
+            &mut [],
+        );
+        let mut session_report = generate_session_report_sorted(session_map, None, None);
+        attach_session_summaries(&mut session_report, &parser.session_summaries());
+        self.original_daily_report = generate_daily_report_sorted(daily_map, None, None);
+        self.original_session_report = session_report;
+        // Computed from the daily report on demand
+        self.weekly_report = None;
+        let blocks = billing_manager.generate_report().blocks.len();
+        self.billing_manager = billing_manager;
+        self.billing_blocks_table_state.select(Some(0));
+        self.billing_blocks_scroll_state = ScrollbarState::new(blocks);
+        self.block_detail = None;
+        self.model_filter = filter;
+        self.apply_filters();
+        self.status_message = Some(match &self.model_filter {
+            Some(model) => format!(
+                "\u{1f50e} Model: {} ({} sessions)",
+                model,
+                self.session_report.sessions.len()
+            ),
+            None => "\u{1f50e} Model filter cleared".to_string(),
+        });
+        Ok(())
+    }
+
+    /// Cycle the model filter through All, Opus, Sonnet and Haiku
+    pub(crate) fn cycle_model_family(&mut self) -> Result<()> {
+        let next = next_model_family(self.model_filter.as_deref());
+        self.set_model_filter(next.map(str::to_string))
+    }
+
+    /// Drop the search, time, date range, day and model filters
+    pub(crate) fn clear_filters(&mut self) -> Result<()> {
+        self.search_query.clear();
+        self.session_day_filter = None;
+        self.date_range = None;
+        self.time_filter = TimeFilter::All;
+        if self.model_filter.is_some() {
+            self.set_model_filter(None)?;
+        } else {
+            self.apply_filters();
+        }
+        self.status_message = Some("Filters cleared".to_string());
+        Ok(())
+    }
+
     pub(crate) fn apply_filters(&mut self) {
         // Reset to original data
         self.daily_report = self.original_daily_report.clone();
         self.session_report = self.original_session_report.clone();
 
-        // Apply search filter only (skip time filter for now)
+        // Search filter
         if !self.search_query.is_empty() {
             self.session_report.sessions.retain(|session| {
                 session
@@ -66,9 +175,33 @@ impl TuiApp {
                         .session_id
                         .to_lowercase()
                         .contains(&self.search_query.to_lowercase())
+                    || session.summary.as_ref().is_some_and(|summary| {
+                        summary
+                            .to_lowercase()
+                            .contains(&self.search_query.to_lowercase())
+                    })
             });
         }
 
+        if let Some((since, until)) = self.date_bounds() {
+            let in_range = |date: &str| {
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .is_ok_and(|date| date >= since && date <= until)
+            };
+            self.daily_report.daily.retain(|day| in_range(&day.date));
+            self.daily_report.totals = daily_totals(&self.daily_report.daily);
+            self.session_report
+                .sessions
+                .retain(|session| in_range(&session.last_activity));
+        }
+
+        if let Some(day) = self.session_day_filter {
+            let day = day.format("%Y-%m-%d").to_string();
+            self.session_report
+                .sessions
+                .retain(|session| session.last_activity == day);
+        }
+
         // Sort both daily and sessions
         match self.sort_mode {
             SortMode::Date => {
@@ -92,10 +225,10 @@ impl TuiApp {
             SortMode::Tokens => {
                 self.daily_report
                     .daily
-                    .sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens));
+                    .sort_by_key(|d| std::cmp::Reverse(d.total_tokens));
                 self.session_report
                     .sessions
-                    .sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens));
+                    .sort_by_key(|s| std::cmp::Reverse(s.total_tokens));
             }
             SortMode::Efficiency => {
                 let cache_rate = |cr: u64, cc: u64, inp: u64| -> f64 {
@@ -113,12 +246,11 @@ impl TuiApp {
                         cache_rate(b.cache_read_tokens, b.cache_creation_tokens, b.input_tokens);
                     rb.partial_cmp(&ra).unwrap_or(std::cmp::Ordering::Equal)
                 });
+                // Sessions by efficiency score, unscored sessions last
                 self.session_report.sessions.sort_by(|a, b| {
-                    let ra =
-                        cache_rate(a.cache_read_tokens, a.cache_creation_tokens, a.input_tokens);
-                    let rb =
-                        cache_rate(b.cache_read_tokens, b.cache_creation_tokens, b.input_tokens);
-                    rb.partial_cmp(&ra).unwrap_or(std::cmp::Ordering::Equal)
+                    let score =
+                        |s: &crate::models::SessionUsage| s.efficiency_score.unwrap_or(-1.0);
+                    score(b).total_cmp(&score(a))
                 });
             }
             SortMode::Project => {
@@ -147,13 +279,42 @@ impl TuiApp {
             && let Some(session) = self.session_report.sessions.get(selected)
         {
             let session_id = format!("{}/{}", session.project_path, session.session_id);
-            if !self.bookmarked_sessions.contains(&session_id) {
-                self.bookmarked_sessions.push(session_id.clone());
+            if !self.bookmarks.contains(&session_id) {
+                self.bookmarks.entry(&session_id);
                 self.status_message = Some(format!("\u{1f516} Bookmarked session: {}", session_id));
             } else {
-                self.bookmarked_sessions.retain(|s| s != &session_id);
+                self.bookmarks.remove(&session_id);
                 self.status_message = Some(format!("\u{1f4cc} Removed bookmark: {}", session_id));
             }
+            self.save_bookmarks();
+        }
+    }
+
+    /// Persist bookmarks, reporting failures in a toast
+    pub(crate) fn save_bookmarks(&mut self) {
+        if let Err(e) = self.bookmarks.save() {
+            self.visual_effects
+                .add_toast(ToastNotification::error(format!(
+                    "Failed to save bookmarks: {}",
+                    e
+                )));
+        }
+    }
+
+    /// `project/session-id` of the selected row in the Sessions or Bookmarks tab
+    pub(crate) fn selected_session_key(&self) -> Option<String> {
+        match self.current_tab {
+            Tab::Sessions => self
+                .session_table_state
+                .selected()
+                .and_then(|i| self.session_report.sessions.get(i))
+                .map(|s| format!("{}/{}", s.project_path, s.session_id)),
+            Tab::Bookmarks => self
+                .bookmarks_table_state
+                .selected()
+                .and_then(|i| self.bookmarks.bookmarks.get(i))
+                .map(|b| b.session.clone()),
+            _ => None,
         }
     }
 
@@ -189,3 +350,29 @@ impl TuiApp {
         }
     }
 }
+
+/// Model families `M` cycles through, after All
+const MODEL_FAMILIES: [&str; 3] = ["opus", "sonnet", "haiku"];
+
+/// Family after `current`; a model name typed in the palette goes back to All
+fn next_model_family(current: Option<&str>) -> Option<&'static str> {
+    match current.and_then(|filter| MODEL_FAMILIES.iter().position(|f| *f == filter)) {
+        Some(index) => MODEL_FAMILIES.get(index + 1).copied(),
+        None if current.is_none() => Some(MODEL_FAMILIES[0]),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_model_family() {
+        assert_eq!(next_model_family(None), Some("opus"));
+        assert_eq!(next_model_family(Some("opus")), Some("sonnet"));
+        assert_eq!(next_model_family(Some("sonnet")), Some("haiku"));
+        assert_eq!(next_model_family(Some("haiku")), None);
+        assert_eq!(next_model_family(Some("claude-opus-4")), None);
+    }
+}
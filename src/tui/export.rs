@@ -1,7 +1,12 @@
 use anyhow::Result;
-use copypasta::{ClipboardContext, ClipboardProvider};
+use std::path::PathBuf;
 
-use super::{AppMode, ExportFormat, Tab, TuiApp};
+use super::{AppMode, ExportFormat, SelectionExportState, Tab, TuiApp};
+use crate::clipboard::Copied;
+use crate::currency;
+use crate::export::ExportFormat as FileFormat;
+use crate::models::{DailyReport, DailyUsage, SessionReport, TokenUsage, TokenUsageTotals};
+use crate::tui_visuals::ToastNotification;
 
 impl TuiApp {
     pub(crate) fn open_export_dialog(&mut self) {
@@ -84,25 +89,23 @@ impl TuiApp {
     fn export_to_csv(&mut self, data_type: &str) -> Result<()> {
         use tempfile::NamedTempFile;
 
+        let file_name = format!("{}.csv", data_type);
         let result = match data_type {
             "daily" => {
                 let temp_file = NamedTempFile::new()?;
                 let path = temp_file.path().to_path_buf();
                 crate::export::export_daily_to_csv(&self.daily_report, &path)?;
-                self.copy_to_clipboard_from_file(&path)?;
-                Ok(())
+                self.copy_to_clipboard_from_file(&path, &file_name)
             }
             "sessions" => {
                 let temp_file = NamedTempFile::new()?;
                 let path = temp_file.path().to_path_buf();
                 crate::export::export_sessions_to_csv(&self.session_report, &path)?;
-                self.copy_to_clipboard_from_file(&path)?;
-                Ok(())
+                self.copy_to_clipboard_from_file(&path, &file_name)
             }
             "billing" => {
                 let content = self.generate_billing_csv()?;
-                self.copy_to_clipboard(&content)?;
-                Ok(())
+                self.clipboard.copy(&content, &file_name)
             }
             "summary" => {
                 let temp_file = NamedTempFile::new()?;
@@ -112,17 +115,19 @@ impl TuiApp {
                     &self.session_report,
                     &path,
                 )?;
-                self.copy_to_clipboard_from_file(&path)?;
-                Ok(())
+                self.copy_to_clipboard_from_file(&path, &file_name)
             }
             _ => Err(anyhow::anyhow!("Unknown data type")),
         };
 
         match result {
-            Ok(_) => {
+            Ok(copied) => {
                 self.export_dialog_state.show_success_message = true;
-                self.export_dialog_state.success_message =
-                    format!("\u{2705} {} data exported to clipboard as CSV!", data_type);
+                self.export_dialog_state.success_message = format!(
+                    "\u{2705} {} data exported as CSV to {}",
+                    data_type,
+                    copied.describe()
+                );
                 self.status_message = Some(self.export_dialog_state.success_message.clone());
                 self.current_mode = AppMode::Normal;
             }
@@ -157,11 +162,17 @@ impl TuiApp {
             _ => return Err(anyhow::anyhow!("Unknown data type")),
         };
 
-        match self.copy_to_clipboard(&json_content) {
-            Ok(_) => {
+        match self
+            .clipboard
+            .copy(&json_content, &format!("{}.json", data_type))
+        {
+            Ok(copied) => {
                 self.export_dialog_state.show_success_message = true;
-                self.export_dialog_state.success_message =
-                    format!("\u{2705} {} data exported to clipboard as JSON!", data_type);
+                self.export_dialog_state.success_message = format!(
+                    "\u{2705} {} data exported as JSON to {}",
+                    data_type,
+                    copied.describe()
+                );
                 self.status_message = Some(self.export_dialog_state.success_message.clone());
                 self.current_mode = AppMode::Normal;
             }
@@ -173,13 +184,124 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Open the filename prompt for rows picked in visual mode
+    pub(crate) fn open_selection_export(&mut self, rows: Vec<usize>) {
+        let kind = if self.current_tab == Tab::Sessions {
+            "sessions"
+        } else {
+            "daily"
+        };
+        let format = FileFormat::Csv;
+        self.selection_export = Some(SelectionExportState {
+            tab: self.current_tab,
+            rows,
+            format,
+            filename: format!(
+                "claudelytics-{}-{}.{}",
+                kind,
+                chrono::Local::now().format("%Y%m%d-%H%M%S"),
+                format.extension()
+            ),
+            error_message: None,
+        });
+        self.current_mode = AppMode::SelectionExport;
+        self.status_message = Some(
+            "Export selection: edit filename, Tab to switch format, Enter to save".to_string(),
+        );
+    }
+
+    pub(crate) fn handle_selection_export_input(
+        &mut self,
+        key: crossterm::event::KeyCode,
+    ) -> Result<()> {
+        use crossterm::event::KeyCode;
+        let Some(state) = self.selection_export.as_mut() else {
+            self.current_mode = AppMode::Normal;
+            return Ok(());
+        };
+
+        match key {
+            KeyCode::Esc => {
+                self.selection_export = None;
+                self.current_mode = AppMode::Normal;
+                self.status_message = Some("Export cancelled".to_string());
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                let next = match (state.format, key == KeyCode::Tab) {
+                    (FileFormat::Csv, true) | (FileFormat::Markdown, false) => FileFormat::Json,
+                    (FileFormat::Json, true) | (FileFormat::Csv, false) => FileFormat::Markdown,
+                    _ => FileFormat::Csv,
+                };
+                // Keep the extension in step unless the user typed their own
+                let old_suffix = format!(".{}", state.format.extension());
+                if let Some(stem) = state.filename.strip_suffix(&old_suffix) {
+                    state.filename = format!("{}.{}", stem, next.extension());
+                }
+                state.format = next;
+            }
+            KeyCode::Backspace => {
+                state.filename.pop();
+            }
+            KeyCode::Char(c) => {
+                state.filename.push(c);
+            }
+            KeyCode::Enter => {
+                self.execute_selection_export();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn execute_selection_export(&mut self) {
+        let Some(state) = self.selection_export.as_mut() else {
+            return;
+        };
+        let filename = state.filename.trim();
+        if filename.is_empty() {
+            state.error_message = Some("Filename cannot be empty".to_string());
+            return;
+        }
+        let path = PathBuf::from(filename);
+
+        let result = if state.tab == Tab::Sessions {
+            let report = selected_sessions(&self.session_report, &state.rows);
+            crate::export::export_sessions(&report, &path, state.format)
+        } else {
+            let report = selected_daily(&self.daily_report, &state.rows);
+            crate::export::export_daily(&report, &path, state.format)
+        };
+
+        match result {
+            Ok(()) => {
+                let shown = std::fs::canonicalize(&path).unwrap_or(path);
+                let message = format!(
+                    "\u{2705} Exported {} rows to {}",
+                    state.rows.len(),
+                    shown.display()
+                );
+                self.visual_effects
+                    .add_toast(ToastNotification::success(message.clone()));
+                self.status_message = Some(message);
+                self.selection_export = None;
+                self.current_mode = AppMode::Normal;
+            }
+            Err(e) => {
+                state.error_message = Some(format!("Export failed: {}", e));
+                self.visual_effects
+                    .add_toast(ToastNotification::error(format!("Export failed: {}", e)));
+            }
+        }
+    }
+
     fn generate_billing_csv(&self) -> Result<String> {
         use std::fmt::Write;
         let mut output = String::new();
 
         writeln!(
             &mut output,
-            "Date,Block,Start Time,End Time,Sessions,Input Tokens,Output Tokens,Total Tokens,Cost USD"
+            "Date,Block,Start Time,End Time,Sessions,Input Tokens,Output Tokens,Total Tokens,Cost {}",
+            currency::current().code
         )?;
 
         let report = self.billing_manager.generate_report();
@@ -202,16 +324,65 @@ impl TuiApp {
         Ok(output)
     }
 
-    fn copy_to_clipboard(&self, content: &str) -> Result<()> {
-        let mut ctx = ClipboardContext::new()
-            .map_err(|e| anyhow::anyhow!("Failed to access clipboard: {}", e))?;
-        ctx.set_contents(content.to_string())
-            .map_err(|e| anyhow::anyhow!("Failed to copy to clipboard: {}", e))?;
-        Ok(())
+    fn copy_to_clipboard_from_file(
+        &self,
+        path: &std::path::Path,
+        file_name: &str,
+    ) -> Result<Copied> {
+        let content = std::fs::read_to_string(path)?;
+        self.clipboard.copy(&content, file_name)
     }
+}
 
-    fn copy_to_clipboard_from_file(&self, path: &std::path::Path) -> Result<()> {
-        let content = std::fs::read_to_string(path)?;
-        self.copy_to_clipboard(&content)
+/// Daily report limited to the given rows, with totals recomputed
+fn selected_daily(report: &DailyReport, rows: &[usize]) -> DailyReport {
+    let daily: Vec<_> = rows
+        .iter()
+        .filter_map(|&i| report.daily.get(i).cloned())
+        .collect();
+    let totals = daily_totals(&daily);
+    DailyReport { daily, totals }
+}
+
+/// Totals of the given daily rows
+pub(super) fn daily_totals(daily: &[DailyUsage]) -> TokenUsageTotals {
+    sum_totals(daily.iter().map(|d| TokenUsage {
+        input_tokens: d.input_tokens,
+        output_tokens: d.output_tokens,
+        cache_creation_tokens: d.cache_creation_tokens,
+        cache_read_tokens: d.cache_read_tokens,
+        total_cost: d.total_cost,
+        thinking_tokens: d.thinking_tokens,
+        ..Default::default()
+    }))
+}
+
+/// Session report limited to the given rows, with totals recomputed
+fn selected_sessions(report: &SessionReport, rows: &[usize]) -> SessionReport {
+    let sessions: Vec<_> = rows
+        .iter()
+        .filter_map(|&i| report.sessions.get(i).cloned())
+        .collect();
+    let totals = sum_totals(sessions.iter().map(|s| TokenUsage {
+        input_tokens: s.input_tokens,
+        output_tokens: s.output_tokens,
+        cache_creation_tokens: s.cache_creation_tokens,
+        cache_read_tokens: s.cache_read_tokens,
+        total_cost: s.total_cost,
+        thinking_tokens: s.thinking_tokens,
+        ..Default::default()
+    }));
+    SessionReport {
+        sessions,
+        totals,
+        pagination: None,
+    }
+}
+
+fn sum_totals(rows: impl Iterator<Item = TokenUsage>) -> TokenUsageTotals {
+    let mut totals = TokenUsage::default();
+    for row in rows {
+        totals.add(&row);
     }
+    TokenUsageTotals::from(&totals)
 }
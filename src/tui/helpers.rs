@@ -1,3 +1,4 @@
+use ratatui::layout::{Margin, Rect};
 use ratatui::style::Color;
 
 use super::TuiApp;
@@ -22,6 +23,16 @@ impl TuiApp {
         }
     }
 
+    /// Rows of a bordered table drawn in `area`, below `header_lines` of header
+    pub(crate) fn table_body(area: Rect, header_lines: u16) -> Rect {
+        let inner = area.inner(Margin::new(1, 1));
+        Rect {
+            y: inner.y + header_lines.min(inner.height),
+            height: inner.height.saturating_sub(header_lines),
+            ..inner
+        }
+    }
+
     pub(crate) fn truncate_text(text: &str, max_length: usize) -> String {
         if text.chars().count() <= max_length {
             text.to_string()
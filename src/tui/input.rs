@@ -1,10 +1,18 @@
 use anyhow::Result;
-use copypasta::{ClipboardContext, ClipboardProvider};
+use chrono::Days;
 use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+use ratatui::text::Line;
+use std::time::{Duration, Instant};
 
-use super::{AppMode, Tab, TuiApp};
+use super::render::TAB_TITLES;
+use super::{AppMode, LastClick, NoteInputState, Tab, TuiApp};
+use crate::currency::Money;
 use crate::tui_visuals::{AnimationStyle, ToastNotification};
 
+/// Longest gap between the clicks of a double click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
 impl TuiApp {
     pub(crate) fn handle_normal_input(
         &mut self,
@@ -32,7 +40,18 @@ impl TuiApp {
             return Ok(());
         }
 
+        if self.current_tab == Tab::Daily && self.handle_calendar_input(key) {
+            return Ok(());
+        }
+
         match key {
+            KeyCode::Esc
+                if self.current_tab == Tab::Sessions && self.session_day_filter.is_some() =>
+            {
+                self.session_day_filter = None;
+                self.apply_filters();
+                self.status_message = Some("Day filter cleared".to_string());
+            }
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.should_quit = true;
                 self.visual_effects.add_toast(ToastNotification::info(
@@ -73,6 +92,11 @@ impl TuiApp {
                     "Switched to Billing Blocks".to_string(),
                 ));
             }
+            KeyCode::Char('7') => {
+                self.current_tab = Tab::Bookmarks;
+                self.visual_effects
+                    .add_toast(ToastNotification::info("Switched to Bookmarks".to_string()));
+            }
             KeyCode::Char('h') => {
                 self.current_tab = Tab::Help;
                 self.visual_effects
@@ -93,30 +117,24 @@ impl TuiApp {
                 self.half_page_up();
             }
             // Enhanced vim navigation
-            KeyCode::Char('g') => {
-                if modifiers.contains(KeyModifiers::NONE) {
-                    if self.g_pressed {
-                        self.jump_to_top();
-                        self.g_pressed = false;
-                        self.status_message = Some("Jumped to top".to_string());
-                    } else {
-                        self.g_pressed = true;
-                        self.status_message = Some("Press 'g' again to jump to top".to_string());
-                    }
+            KeyCode::Char('g') if modifiers.contains(KeyModifiers::NONE) => {
+                if self.g_pressed {
+                    self.jump_to_top();
+                    self.g_pressed = false;
+                    self.status_message = Some("Jumped to top".to_string());
+                } else {
+                    self.g_pressed = true;
+                    self.status_message = Some("Press 'g' again to jump to top".to_string());
                 }
             }
             KeyCode::Char('G') => {
                 self.jump_to_bottom();
             }
-            KeyCode::Char('d') => {
-                if modifiers.contains(KeyModifiers::CONTROL) {
-                    self.half_page_down();
-                }
+            KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.half_page_down();
             }
-            KeyCode::Char('u') => {
-                if modifiers.contains(KeyModifiers::CONTROL) {
-                    self.half_page_up();
-                }
+            KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.half_page_up();
             }
             KeyCode::Char('0') => {
                 self.jump_to_line_start();
@@ -158,6 +176,18 @@ impl TuiApp {
             KeyCode::Char('f') => {
                 self.cycle_time_filter();
             }
+            KeyCode::Char('M') => {
+                self.cycle_model_family()?;
+            }
+            KeyCode::Char('c') if matches!(self.current_tab, Tab::Sessions | Tab::Bookmarks) => {
+                self.open_conversation_view();
+            }
+            KeyCode::Char('n') if matches!(self.current_tab, Tab::Sessions | Tab::Bookmarks) => {
+                self.open_note_input();
+            }
+            KeyCode::Char('d') if self.current_tab == Tab::Bookmarks => {
+                self.remove_selected_bookmark();
+            }
             KeyCode::Char('c') => {
                 self.status_message = None;
             }
@@ -176,6 +206,9 @@ impl TuiApp {
             KeyCode::Char('x') => {
                 self.toggle_comparison_selection();
             }
+            KeyCode::Char('C') => {
+                self.open_comparison_view();
+            }
             KeyCode::Char('?') => {
                 self.show_help_popup = !self.show_help_popup;
             }
@@ -187,6 +220,39 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Keys of the Daily tab calendar; returns whether the key was used
+    pub(crate) fn handle_calendar_input(&mut self, key: KeyCode) -> bool {
+        if key == KeyCode::Char('m') {
+            self.toggle_daily_calendar();
+            return true;
+        }
+        let Some(day) = self.daily_calendar else {
+            return false;
+        };
+        let moved = match key {
+            KeyCode::Left => day.pred_opt(),
+            KeyCode::Right => day.succ_opt(),
+            KeyCode::Up => day.checked_sub_days(Days::new(7)),
+            KeyCode::Down => day.checked_add_days(Days::new(7)),
+            KeyCode::Enter => {
+                self.session_day_filter = Some(day);
+                self.current_tab = Tab::Sessions;
+                self.apply_filters();
+                self.status_message = Some(format!(
+                    "Sessions on {} ({} found), Esc clears the filter",
+                    day,
+                    self.session_report.sessions.len()
+                ));
+                return true;
+            }
+            _ => return false,
+        };
+        if let Some(moved) = moved {
+            self.daily_calendar = Some(moved);
+        }
+        true
+    }
+
     pub(crate) fn handle_search_input(&mut self, key: KeyCode) -> Result<()> {
         match key {
             KeyCode::Esc => {
@@ -330,68 +396,124 @@ impl TuiApp {
         for &index in &self.visual_mode_selections {
             if let Some(session) = self.session_report.sessions.get(index) {
                 let session_id = format!("{}/{}", session.project_path, session.session_id);
-                if !self.bookmarked_sessions.contains(&session_id) {
-                    self.bookmarked_sessions.push(session_id);
+                if !self.bookmarks.contains(&session_id) {
+                    self.bookmarks.entry(&session_id);
                     count += 1;
                 }
             }
         }
+        if count > 0 {
+            self.save_bookmarks();
+        }
 
         self.status_message = Some(format!("Bookmarked {} sessions", count));
         self.toggle_visual_mode();
     }
 
+    /// Start editing the note of the selected session, bookmarking it on save
+    pub(crate) fn open_note_input(&mut self) {
+        let Some(session) = self.selected_session_key() else {
+            return;
+        };
+        let text = self
+            .bookmarks
+            .get(&session)
+            .and_then(|b| b.note.clone())
+            .unwrap_or_default();
+        self.note_input = Some(NoteInputState { session, text });
+        self.current_mode = AppMode::NoteInput;
+        self.status_message = Some("Note: Enter to save, Esc to cancel".to_string());
+    }
+
+    pub(crate) fn handle_note_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(input) = self.note_input.as_mut() else {
+            self.current_mode = AppMode::Normal;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Esc => {
+                self.note_input = None;
+                self.current_mode = AppMode::Normal;
+                self.status_message = None;
+            }
+            KeyCode::Enter => {
+                if let Some(input) = self.note_input.take() {
+                    let note = input.text.trim();
+                    self.bookmarks.entry(&input.session).note =
+                        (!note.is_empty()).then(|| note.to_string());
+                    self.save_bookmarks();
+                    self.visual_effects
+                        .add_toast(ToastNotification::success("Note saved".to_string()));
+                }
+                self.current_mode = AppMode::Normal;
+                self.status_message = None;
+            }
+            KeyCode::Backspace => {
+                input.text.pop();
+            }
+            KeyCode::Char(c) => {
+                input.text.push(c);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove_selected_bookmark(&mut self) {
+        let Some(session) = self.selected_session_key() else {
+            return;
+        };
+        self.bookmarks.remove(&session);
+        self.save_bookmarks();
+        let len = self.bookmarks.bookmarks.len();
+        if let Some(selected) = self.bookmarks_table_state.selected()
+            && selected >= len
+        {
+            self.bookmarks_table_state.select(len.checked_sub(1));
+        }
+        self.status_message = Some(format!("\u{1f4cc} Removed bookmark: {}", session));
+    }
+
     pub(crate) fn export_visual_selections(&mut self) -> Result<()> {
-        self.status_message = Some(format!(
-            "Would export {} selected items",
-            self.visual_mode_selections.len()
-        ));
+        if !matches!(self.current_tab, Tab::Daily | Tab::Sessions) {
+            self.status_message =
+                Some("Visual export only works in Daily and Sessions tabs".to_string());
+            return Ok(());
+        }
+        if self.visual_mode_selections.is_empty() {
+            self.status_message = Some("Nothing selected to export".to_string());
+            return Ok(());
+        }
+
+        let mut rows = self.visual_mode_selections.clone();
+        rows.sort_unstable();
+        rows.dedup();
         self.toggle_visual_mode();
+        self.open_selection_export(rows);
         Ok(())
     }
 
+    /// Clicks select tabs and table rows, double clicks open a session's
+    /// conversation, and the wheel scrolls tables and the conversation viewer
     pub(crate) fn handle_mouse_event(&mut self, mouse: MouseEvent) {
+        match self.current_mode {
+            AppMode::Conversation => return self.handle_conversation_mouse(mouse),
+            AppMode::Normal if !self.search_mode => {}
+            // Dialogs and prompts are keyboard only
+            _ => return,
+        }
+
+        let position = Position::new(mouse.column, mouse.row);
         match mouse.kind {
             MouseEventKind::Down(MouseButton::Left) => {
-                if mouse.row <= 2 {
-                    let tab_width = 16;
-                    let selected_tab = (mouse.column / tab_width) as usize;
-                    match selected_tab {
-                        0 => self.current_tab = Tab::Overview,
-                        1 => self.current_tab = Tab::Daily,
-                        2 => self.current_tab = Tab::Weekly,
-                        3 => self.current_tab = Tab::Sessions,
-                        4 => self.current_tab = Tab::Cache,
-                        5 => self.current_tab = Tab::BillingBlocks,
-                        6 => self.current_tab = Tab::Help,
-                        _ => {}
-                    }
-                } else {
-                    match self.current_tab {
-                        Tab::Daily => {
-                            if mouse.row >= 6
-                                && mouse.row <= 6 + self.daily_report.daily.len() as u16
-                            {
-                                let selected_row = (mouse.row - 6) as usize;
-                                if selected_row < self.daily_report.daily.len() {
-                                    self.daily_table_state.select(Some(selected_row));
-                                }
-                            }
-                        }
-                        Tab::Sessions => {
-                            if mouse.row >= 6
-                                && mouse.row <= 6 + self.session_report.sessions.len() as u16
-                            {
-                                let selected_row = (mouse.row - 6) as usize;
-                                if selected_row < self.session_report.sessions.len() {
-                                    self.session_table_state.select(Some(selected_row));
-                                    self.session_scroll_state =
-                                        self.session_scroll_state.position(selected_row);
-                                }
-                            }
-                        }
-                        _ => {}
+                if self.tab_bar_area.contains(position) {
+                    if let Some(index) = tab_at(self.tab_bar_area, mouse.column) {
+                        self.set_current_tab(index);
                     }
+                    self.last_click = None;
+                } else if let Some(row) = self.table_row_at(position) {
+                    self.select_row(row);
+                    self.handle_row_click(row);
                 }
             }
             MouseEventKind::ScrollUp => {
@@ -404,26 +526,147 @@ impl TuiApp {
         }
     }
 
+    /// Open the conversation of a session row clicked twice in a row
+    fn handle_row_click(&mut self, row: usize) {
+        let now = Instant::now();
+        let double_click = self.last_click.is_some_and(|click| {
+            click.tab == self.current_tab
+                && click.row == row
+                && now.duration_since(click.at) <= DOUBLE_CLICK_INTERVAL
+        });
+        if double_click {
+            self.last_click = None;
+            match self.current_tab {
+                Tab::Sessions | Tab::Bookmarks => self.open_conversation_view(),
+                Tab::BillingBlocks => self.open_block_detail(),
+                _ => {}
+            }
+        } else {
+            self.last_click = Some(LastClick {
+                at: now,
+                tab: self.current_tab,
+                row,
+            });
+        }
+    }
+
+    /// Row of the current tab's table under `position`, if any
+    fn table_row_at(&self, position: Position) -> Option<usize> {
+        let body = self.table_body_area?;
+        if !body.contains(position) {
+            return None;
+        }
+        let (offset, len) = match self.current_tab {
+            Tab::Daily => (
+                self.daily_table_state.offset(),
+                self.daily_report.daily.len(),
+            ),
+            Tab::Weekly => (
+                self.weekly_table_state.offset(),
+                self.weekly_report.as_ref().map_or(0, |r| r.weekly.len()),
+            ),
+            Tab::Sessions => (
+                self.session_table_state.offset(),
+                self.session_report.sessions.len(),
+            ),
+            Tab::Cache => (
+                self.cache_table_state.offset(),
+                self.cache_analysis.as_ref().map_or(0, |a| a.sessions.len()),
+            ),
+            Tab::BillingBlocks => (
+                self.billing_blocks_table_state.offset(),
+                self.billing_manager.generate_report().blocks.len(),
+            ),
+            Tab::Bookmarks => (
+                self.bookmarks_table_state.offset(),
+                self.bookmarks.bookmarks.len(),
+            ),
+            Tab::Overview | Tab::Help => return None,
+        };
+        let row = offset + usize::from(position.y - body.y);
+        (row < len).then_some(row)
+    }
+
+    fn select_row(&mut self, row: usize) {
+        match self.current_tab {
+            Tab::Daily => self.daily_table_state.select(Some(row)),
+            Tab::Weekly => self.weekly_table_state.select(Some(row)),
+            Tab::Sessions => {
+                self.session_table_state.select(Some(row));
+                self.session_scroll_state = self.session_scroll_state.position(row);
+            }
+            Tab::Cache => self.cache_table_state.select(Some(row)),
+            Tab::BillingBlocks => {
+                self.billing_blocks_table_state.select(Some(row));
+                self.billing_blocks_scroll_state = self.billing_blocks_scroll_state.position(row);
+            }
+            Tab::Bookmarks => self.bookmarks_table_state.select(Some(row)),
+            Tab::Overview | Tab::Help => {}
+        }
+    }
+
     pub(crate) fn handle_enter(&mut self) {
+        if self.current_tab == Tab::BillingBlocks {
+            self.open_block_detail();
+            return;
+        }
         if self.current_tab == Tab::Sessions
             && let Some(selected) = self.session_table_state.selected()
             && let Some(session) = self.session_report.sessions.get(selected)
         {
             let info = format!(
-                "Project: {}, Session: {}, Cost: ${:.2}, Tokens: {}",
-                session.project_path, session.session_id, session.total_cost, session.total_tokens
+                "Project: {}, Session: {}, Cost: {:.2}, Tokens: {}",
+                session.project_path,
+                session.session_id,
+                Money(session.total_cost),
+                session.total_tokens
             );
 
-            if let Ok(mut ctx) = ClipboardContext::new() {
-                if ctx.set_contents(info.clone()).is_ok() {
-                    self.status_message =
-                        Some("\u{1f4cb} Copied session info to clipboard".to_string());
-                } else {
-                    self.status_message = Some("\u{274c} Failed to copy to clipboard".to_string());
-                }
-            } else {
-                self.status_message = Some("\u{274c} Clipboard not available".to_string());
-            }
+            self.status_message = Some(match self.clipboard.copy(&info, "session.txt") {
+                Ok(copied) => format!("\u{1f4cb} Copied session info to {}", copied.describe()),
+                Err(e) => format!("\u{274c} Failed to copy: {}", e),
+            });
+        }
+    }
+}
+
+/// Index of the tab whose title is at `column` of the tab bar drawn in `area`
+///
+/// Mirrors the layout of `ratatui::widgets::Tabs`: inside the border each
+/// title is padded by a space on both sides and followed by a one column divider.
+pub(crate) fn tab_at(area: Rect, column: u16) -> Option<usize> {
+    let mut x = area.x + 1;
+    for (index, title) in TAB_TITLES.iter().enumerate() {
+        let end = x + Line::from(*title).width() as u16 + 2;
+        if (x..end).contains(&column) {
+            return Some(index);
         }
+        x = end + 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab_at() {
+        let area = Rect::new(0, 0, 120, 3);
+        // The border, then " 📊 Overview " (emoji two columns wide) and a divider
+        assert_eq!(tab_at(area, 0), None);
+        assert_eq!(tab_at(area, 1), Some(0));
+        assert_eq!(tab_at(area, 13), Some(0));
+        assert_eq!(tab_at(area, 14), None);
+        assert_eq!(tab_at(area, 15), Some(1));
+        assert_eq!(tab_at(area, 119), None);
+    }
+
+    #[test]
+    fn test_table_body() {
+        let body = TuiApp::table_body(Rect::new(0, 10, 40, 12), 2);
+        assert_eq!(body, Rect::new(1, 13, 38, 8));
+        // Too small for any rows
+        assert_eq!(TuiApp::table_body(Rect::new(0, 0, 40, 3), 2).height, 0);
     }
 }
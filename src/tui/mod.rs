@@ -11,7 +11,10 @@
 //! sorting, and export capabilities for Claude Code usage data.
 
 mod app;
+mod block_detail;
 mod command_palette;
+mod compare;
+mod conversation;
 mod data;
 mod export;
 mod helpers;
@@ -21,11 +24,19 @@ mod render;
 mod tabs;
 
 use crate::billing_blocks::BillingBlockManager;
+use crate::bookmarks::BookmarkStore;
 use crate::cache_analysis::CacheAnalysis;
+use crate::clipboard::Clipboard;
 use crate::models::{Command, DailyReport, SessionReport, WeeklyReport};
+use crate::parser::UsageParser;
+use crate::quota::{Plan, QuotaLimits};
 use crate::tui_visuals::VisualEffectsManager;
 
+use chrono::NaiveDate;
+use ratatui::layout::Rect;
 use ratatui::widgets::{ScrollbarState, TableState};
+use std::path::PathBuf;
+use std::time::Instant;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) enum Tab {
@@ -35,6 +46,7 @@ pub(crate) enum Tab {
     Sessions,
     Cache,
     BillingBlocks,
+    Bookmarks,
     Help,
 }
 
@@ -46,6 +58,12 @@ pub(crate) enum AppMode {
     Search,
     Visual,
     ExportDialog,
+    SelectionExport,
+    Conversation,
+    NoteInput,
+    Prompt,
+    Compare,
+    BlockDetail,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -81,6 +99,46 @@ pub(crate) struct ExportDialogState {
     error_message: Option<String>,
 }
 
+/// Note being edited for a bookmarked session
+#[derive(Debug)]
+pub(crate) struct NoteInputState {
+    session: String,
+    text: String,
+}
+
+/// What a palette prompt asks for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PromptKind {
+    ModelFilter,
+    DateRange,
+}
+
+/// Text being typed into a palette prompt
+#[derive(Debug)]
+pub(crate) struct PromptState {
+    kind: PromptKind,
+    text: String,
+    error_message: Option<String>,
+}
+
+/// Visual-mode selection waiting in the export filename prompt
+#[derive(Debug)]
+pub(crate) struct SelectionExportState {
+    tab: Tab,
+    rows: Vec<usize>,
+    format: crate::export::ExportFormat,
+    filename: String,
+    error_message: Option<String>,
+}
+
+/// Table row clicked last, to detect double clicks
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LastClick {
+    at: Instant,
+    tab: Tab,
+    row: usize,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct PricingCacheStatus {
     exists: bool,
@@ -113,19 +171,32 @@ pub struct TuiApp {
     pub(crate) command_palette_query: String,
     pub(crate) available_commands: Vec<Command>,
     pub(crate) filtered_commands: Vec<Command>,
+    pub(crate) prompt: Option<PromptState>,
+    // Palette filters: model filter (re-reads usage) and picked date range
+    pub(crate) usage_parser: Option<UsageParser>,
+    pub(crate) model_filter: Option<String>,
+    pub(crate) date_range: Option<(NaiveDate, NaiveDate)>,
     // Enhanced features
-    pub(crate) bookmarked_sessions: Vec<String>,
+    pub(crate) bookmarks: BookmarkStore,
+    pub(crate) bookmarks_table_state: TableState,
+    pub(crate) note_input: Option<NoteInputState>,
     pub(crate) comparison_sessions: Vec<String>,
+    pub(crate) comparison_view: Option<Vec<compare::ComparedSession>>,
     // Billing blocks
     pub(crate) billing_manager: BillingBlockManager,
     pub(crate) billing_blocks_table_state: TableState,
     pub(crate) billing_blocks_scroll_state: ScrollbarState,
+    pub(crate) block_detail: Option<block_detail::BlockDetailState>,
     pub(crate) show_billing_summary: bool,
+    pub(crate) quota_plan: Plan,
+    pub(crate) quota_limits: QuotaLimits,
     // Pricing cache status
     pub(crate) pricing_cache_status: Option<PricingCacheStatus>,
     // Visual mode selection
     pub(crate) visual_mode_start: Option<usize>,
     pub(crate) visual_mode_selections: Vec<usize>,
+    pub(crate) selection_export: Option<SelectionExportState>,
+    pub(crate) clipboard: Clipboard,
     // Search mode cursor position for word navigation
     pub(crate) search_cursor_position: usize,
     // Track if 'g' was pressed for 'gg' command
@@ -142,4 +213,15 @@ pub struct TuiApp {
     // Cache analysis (computed lazily)
     pub(crate) cache_analysis: Option<CacheAnalysis>,
     pub(crate) cache_table_state: TableState,
+    // Conversation viewer
+    pub(crate) claude_dirs: Vec<PathBuf>,
+    pub(crate) conversation_view: Option<conversation::ConversationViewState>,
+    // Daily tab calendar (toggled with 'm'), selected day when shown
+    pub(crate) daily_calendar: Option<NaiveDate>,
+    // Day picked in the calendar that the Sessions tab is limited to
+    pub(crate) session_day_filter: Option<NaiveDate>,
+    // Screen areas of the last frame and the previous click, for the mouse
+    pub(crate) tab_bar_area: Rect,
+    pub(crate) table_body_area: Option<Rect>,
+    pub(crate) last_click: Option<LastClick>,
 }
@@ -8,7 +8,8 @@ impl TuiApp {
             Tab::Weekly => Tab::Sessions,
             Tab::Sessions => Tab::Cache,
             Tab::Cache => Tab::BillingBlocks,
-            Tab::BillingBlocks => Tab::Help,
+            Tab::BillingBlocks => Tab::Bookmarks,
+            Tab::Bookmarks => Tab::Help,
             Tab::Help => Tab::Overview,
         };
     }
@@ -21,7 +22,8 @@ impl TuiApp {
             Tab::Sessions => Tab::Weekly,
             Tab::Cache => Tab::Sessions,
             Tab::BillingBlocks => Tab::Cache,
-            Tab::Help => Tab::BillingBlocks,
+            Tab::Bookmarks => Tab::BillingBlocks,
+            Tab::Help => Tab::Bookmarks,
         };
     }
 
@@ -109,6 +111,16 @@ impl TuiApp {
                     self.cache_table_state.select(Some(i));
                 }
             }
+            Tab::Bookmarks => {
+                let len = self.bookmarks.bookmarks.len();
+                if len > 0 {
+                    let i = match self.bookmarks_table_state.selected() {
+                        Some(i) if i + 1 < len => i + 1,
+                        _ => 0,
+                    };
+                    self.bookmarks_table_state.select(Some(i));
+                }
+            }
             _ => {}
         }
     }
@@ -197,6 +209,16 @@ impl TuiApp {
                     self.cache_table_state.select(Some(i));
                 }
             }
+            Tab::Bookmarks => {
+                let len = self.bookmarks.bookmarks.len();
+                if len > 0 {
+                    let i = match self.bookmarks_table_state.selected() {
+                        Some(i) if i > 0 => i - 1,
+                        _ => len - 1,
+                    };
+                    self.bookmarks_table_state.select(Some(i));
+                }
+            }
             _ => {}
         }
     }
@@ -220,6 +242,9 @@ impl TuiApp {
             Tab::Cache => {
                 self.cache_table_state.select(Some(0));
             }
+            Tab::Bookmarks => {
+                self.bookmarks_table_state.select(Some(0));
+            }
             _ => {}
         }
     }
@@ -268,6 +293,12 @@ impl TuiApp {
                     self.cache_table_state.select(Some(len - 1));
                 }
             }
+            Tab::Bookmarks => {
+                let len = self.bookmarks.bookmarks.len();
+                if len > 0 {
+                    self.bookmarks_table_state.select(Some(len - 1));
+                }
+            }
             _ => {}
         }
     }
@@ -6,7 +6,20 @@ use ratatui::{
     widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, Tabs, Wrap},
 };
 
-use super::{AppMode, ExportFormat, SortMode, Tab, TimeFilter, TuiApp};
+use super::{AppMode, ExportFormat, PromptKind, SortMode, Tab, TimeFilter, TuiApp};
+use crate::export::ExportFormat as FileFormat;
+
+/// Tab bar titles, in `Tab` order
+pub(crate) const TAB_TITLES: [&str; 8] = [
+    "\u{1f4ca} Overview",
+    "\u{1f4c5} Daily",
+    "\u{1f4c6} Weekly",
+    "\u{1f4cb} Sessions",
+    "\u{1f504} Cache",
+    "\u{23f0} Billing",
+    "\u{1f516} Bookmarks",
+    "\u{2753} Help",
+];
 
 impl TuiApp {
     pub(crate) fn ui(&mut self, f: &mut Frame) {
@@ -19,6 +32,30 @@ impl TuiApp {
                 self.render_main_ui(f);
                 self.render_export_dialog(f);
             }
+            AppMode::SelectionExport => {
+                self.render_main_ui(f);
+                self.render_selection_export(f);
+            }
+            AppMode::Conversation => {
+                self.render_main_ui(f);
+                self.render_conversation_view(f);
+            }
+            AppMode::NoteInput => {
+                self.render_main_ui(f);
+                self.render_note_input(f);
+            }
+            AppMode::Prompt => {
+                self.render_main_ui(f);
+                self.render_prompt(f);
+            }
+            AppMode::Compare => {
+                self.render_main_ui(f);
+                self.render_comparison_view(f);
+            }
+            AppMode::BlockDetail => {
+                self.render_main_ui(f);
+                self.render_block_detail(f);
+            }
             _ => {
                 self.render_main_ui(f);
             }
@@ -58,17 +95,25 @@ impl TuiApp {
             AppMode::CommandPalette => "Command",
             AppMode::Search => "Search",
             AppMode::Visual => "Visual",
-            AppMode::ExportDialog => "Export",
+            AppMode::ExportDialog | AppMode::SelectionExport => "Export",
+            AppMode::Conversation => "Conversation",
+            AppMode::NoteInput => "Note",
+            AppMode::Prompt => "Filter",
+            AppMode::Compare => "Compare",
+            AppMode::BlockDetail => "Block",
         }
         .to_string();
 
-        self.visual_effects.status_bar.filter = match self.time_filter {
+        let time_filter = match self.time_filter {
             TimeFilter::All => "All",
             TimeFilter::Today => "Today",
             TimeFilter::LastWeek => "Week",
             TimeFilter::LastMonth => "Month",
-        }
-        .to_string();
+        };
+        self.visual_effects.status_bar.filter = match &self.model_filter {
+            Some(model) => format!("{} \u{b7} {}", time_filter, model),
+            None => time_filter.to_string(),
+        };
 
         self.visual_effects.status_bar.sort = match self.sort_mode {
             SortMode::Date => "Date",
@@ -92,6 +137,7 @@ impl TuiApp {
                 .as_ref()
                 .map(|a| a.sessions.len())
                 .unwrap_or(0),
+            Tab::Bookmarks => self.bookmarks.bookmarks.len(),
             _ => 0,
         };
 
@@ -100,6 +146,7 @@ impl TuiApp {
             Tab::Weekly => self.weekly_table_state.selected(),
             Tab::Sessions => self.session_table_state.selected(),
             Tab::Cache => self.cache_table_state.selected(),
+            Tab::Bookmarks => self.bookmarks_table_state.selected(),
             _ => None,
         };
 
@@ -110,12 +157,23 @@ impl TuiApp {
                 ("/".to_string(), "Search".to_string()),
                 ("r".to_string(), "Refresh".to_string()),
             ],
+            Tab::Daily => vec![
+                ("m".to_string(), "Calendar".to_string()),
+                ("v".to_string(), "Select".to_string()),
+            ],
             Tab::Sessions => vec![
                 ("b".to_string(), "Bookmark".to_string()),
+                ("n".to_string(), "Note".to_string()),
                 ("x".to_string(), "Compare".to_string()),
+                ("C".to_string(), "View".to_string()),
                 ("s".to_string(), "Sort".to_string()),
                 ("f".to_string(), "Filter".to_string()),
             ],
+            Tab::Bookmarks => vec![
+                ("n".to_string(), "Note".to_string()),
+                ("c".to_string(), "Conversation".to_string()),
+                ("d".to_string(), "Remove".to_string()),
+            ],
             _ => vec![],
         };
         self.visual_effects.status_bar.set_key_hints(hints);
@@ -144,16 +202,7 @@ impl TuiApp {
         let main_area = main_chunks[1];
 
         // Tab bar with enhanced titles
-        let tab_titles = vec![
-            "\u{1f4ca} Overview",
-            "\u{1f4c5} Daily",
-            "\u{1f4c6} Weekly",
-            "\u{1f4cb} Sessions",
-            "\u{1f504} Cache",
-            "\u{23f0} Billing",
-            "\u{2753} Help",
-        ];
-        let tabs = Tabs::new(tab_titles)
+        let tabs = Tabs::new(TAB_TITLES)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
@@ -167,8 +216,10 @@ impl TuiApp {
             )
             .select(self.current_tab as usize);
         f.render_widget(tabs, main_chunks[0]);
+        self.tab_bar_area = main_chunks[0];
 
-        // Main content
+        // Main content; tabs with a table record where its rows are
+        self.table_body_area = None;
         match self.current_tab {
             Tab::Overview => self.render_overview(f, main_area),
             Tab::Daily => self.render_daily(f, main_area),
@@ -176,6 +227,7 @@ impl TuiApp {
             Tab::Sessions => self.render_sessions(f, main_area),
             Tab::Cache => self.render_cache(f, main_area),
             Tab::BillingBlocks => self.render_billing_blocks(f, main_area),
+            Tab::Bookmarks => self.render_bookmarks(f, main_area),
             Tab::Help => self.render_help(f, main_area),
         }
 
@@ -319,6 +371,53 @@ impl TuiApp {
         f.render_stateful_widget(commands_table, chunks[1], &mut self.command_table_state);
     }
 
+    pub(crate) fn render_prompt(&mut self, f: &mut Frame) {
+        let Some(prompt) = &self.prompt else {
+            return;
+        };
+        let area = f.area();
+        let popup_area = Rect {
+            x: area.width / 6,
+            y: area.height / 3,
+            width: area.width * 2 / 3,
+            height: 7,
+        };
+        f.render_widget(Clear, popup_area);
+
+        let (title, hint) = match prompt.kind {
+            PromptKind::ModelFilter => (
+                "\u{1f50e} Filter by model",
+                "Model name or family, e.g. opus or sonnet-4",
+            ),
+            PromptKind::DateRange => (
+                "\u{1f4c5} Date range",
+                "e.g. last monday..today, 2024-03-01..2024-03-15, 7d or yesterday",
+            ),
+        };
+        let last_line = match &prompt.error_message {
+            Some(error) => Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))),
+            None => Line::from(""),
+        };
+        let lines = vec![
+            Line::from(Span::styled(hint, Style::default().fg(Color::DarkGray))),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(prompt.text.clone(), Style::default().fg(Color::White)),
+                Span::styled("\u{2588}", Style::default().fg(Color::Yellow)),
+            ]),
+            last_line,
+        ];
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{} (Enter: apply, Esc: cancel)", title))
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
+            .wrap(Wrap { trim: false });
+        f.render_widget(paragraph, popup_area);
+    }
+
     pub(crate) fn render_export_dialog(&mut self, f: &mut Frame) {
         let area = f.area();
         let popup_area = Rect {
@@ -438,4 +537,111 @@ impl TuiApp {
             f.render_widget(error_msg, chunks[3]);
         }
     }
+
+    pub(crate) fn render_selection_export(&mut self, f: &mut Frame) {
+        let Some(state) = &self.selection_export else {
+            return;
+        };
+        let area = f.area();
+        let popup_area = Rect {
+            x: area.width / 6,
+            y: area.height / 3,
+            width: area.width * 2 / 3,
+            height: 11,
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("\u{1f4be} Export Selection")
+            .border_style(Style::default().fg(Color::Cyan));
+
+        let inner_area = block.inner(popup_area);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(2),
+                Constraint::Length(2),
+                Constraint::Length(2),
+                Constraint::Min(0),
+            ])
+            .split(inner_area);
+
+        f.render_widget(block, popup_area);
+
+        let kind = if state.tab == Tab::Sessions {
+            "sessions"
+        } else {
+            "days"
+        };
+        let data_info = Paragraph::new(Line::from(vec![
+            Span::styled("Exporting: ", Style::default().fg(Color::White)),
+            Span::styled(
+                format!("{} selected {}", state.rows.len(), kind),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        f.render_widget(data_info, chunks[0]);
+
+        let mut format_spans = vec![Span::styled("Format: ", Style::default().fg(Color::Cyan))];
+        for (format, label) in [
+            (FileFormat::Csv, "  CSV  "),
+            (FileFormat::Json, "  JSON  "),
+            (FileFormat::Markdown, "  Markdown  "),
+        ] {
+            let style = if state.format == format {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            format_spans.push(Span::styled(label, style));
+            format_spans.push(Span::raw("  "));
+        }
+        f.render_widget(Paragraph::new(Line::from(format_spans)), chunks[1]);
+
+        let filename = Paragraph::new(Line::from(vec![
+            Span::styled("File: ", Style::default().fg(Color::Cyan)),
+            Span::styled(&state.filename, Style::default().fg(Color::White)),
+            Span::styled("\u{2588}", Style::default().fg(Color::Yellow)),
+        ]));
+        f.render_widget(filename, chunks[2]);
+
+        let instructions = Paragraph::new(Line::from(vec![
+            Span::styled(
+                "Tab",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Switch format  ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                "Enter",
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Save  ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                "Esc",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Cancel", Style::default().fg(Color::Gray)),
+        ]));
+        f.render_widget(instructions, chunks[3]);
+
+        if let Some(error) = &state.error_message {
+            let error_msg = Paragraph::new(Line::from(vec![
+                Span::styled("\u{274c} ", Style::default().fg(Color::Red)),
+                Span::styled(error, Style::default().fg(Color::Red)),
+            ]))
+            .wrap(Wrap { trim: true });
+            f.render_widget(error_msg, chunks[4]);
+        }
+    }
 }
@@ -3,20 +3,35 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation, Table, Wrap},
+    widgets::{
+        Block, Borders, Cell, Gauge, Paragraph, Row, Scrollbar, ScrollbarOrientation, Table, Wrap,
+    },
 };
 
+use crate::currency::Money;
+use crate::quota::{self, BurnDown};
 use crate::tui::TuiApp;
 
 impl TuiApp {
     pub(crate) fn render_billing_blocks(&mut self, f: &mut Frame, area: Rect) {
+        let burn_down = quota::build_burn_down(
+            &self.billing_manager,
+            self.quota_plan,
+            self.quota_limits,
+            chrono::Utc::now(),
+        );
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(if self.show_billing_summary { 12 } else { 3 }),
+                Constraint::Length(if burn_down.is_some() { 4 } else { 0 }),
                 Constraint::Min(0),
             ])
             .split(area);
+        if let Some(burn_down) = &burn_down {
+            Self::render_burn_down(f, chunks[1], burn_down);
+        }
+        let chunks = [chunks[0], chunks[2]];
 
         let report = self.billing_manager.generate_report();
 
@@ -41,7 +56,7 @@ impl TuiApp {
                         Style::default().fg(Color::White),
                     ),
                     Span::styled(
-                        format!("${:.2}", current_block_cost),
+                        format!("{:.2}", Money(current_block_cost)),
                         Style::default()
                             .fg(if current_block_cost > 5.0 {
                                 Color::Red
@@ -77,8 +92,10 @@ impl TuiApp {
                     Span::styled(
                         if let Some(ref peak) = report.peak_block {
                             format!(
-                                "${:.2} ({} {})",
-                                peak.usage.total_cost, peak.date, peak.time_range
+                                "{:.2} ({} {})",
+                                Money(peak.usage.total_cost),
+                                peak.date,
+                                peak.time_range
                             )
                         } else {
                             "No peak block yet".to_string()
@@ -93,13 +110,13 @@ impl TuiApp {
                         Style::default().fg(Color::White),
                     ),
                     Span::styled(
-                        format!("${:.2}", report.average_per_block.total_cost),
+                        format!("{:.2}", Money(report.average_per_block.total_cost)),
                         Style::default().fg(Color::Yellow),
                     ),
                     Span::styled("  |  ", Style::default().fg(Color::DarkGray)),
                     Span::styled("\u{1f3af} Total Cost: ", Style::default().fg(Color::White)),
                     Span::styled(
-                        format!("${:.2}", report.total_usage.total_cost),
+                        format!("{:.2}", Money(report.total_usage.total_cost)),
                         Style::default()
                             .fg(Color::Green)
                             .add_modifier(Modifier::BOLD),
@@ -233,13 +250,13 @@ impl TuiApp {
 
             Row::new(vec![
                 Cell::from(format!("{} - {}", &block.date, &block.time_range)).style(style),
-                Cell::from(format!("${:.2}", block.usage.total_cost))
+                Cell::from(format!("{:.2}", Money(block.usage.total_cost)))
                     .style(Style::default().fg(cost_color)),
                 Cell::from(Self::format_number(block.usage.total_tokens()))
                     .style(Style::default().fg(Color::Magenta)),
                 Cell::from(format!("{}", block.session_count))
                     .style(Style::default().fg(Color::Blue)),
-                Cell::from(format!("${:.2}", avg_per_session))
+                Cell::from(format!("{:.2}", Money(avg_per_session)))
                     .style(Style::default().fg(Color::Yellow)),
                 Cell::from(format!("{:.1}%", percentage)).style(Style::default().fg(Color::Cyan)),
             ])
@@ -261,13 +278,14 @@ impl TuiApp {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("\u{1f4cb} 5-Hour Billing Blocks")
+                .title("\u{1f4cb} 5-Hour Billing Blocks (Enter: sessions)")
                 .border_style(Style::default().fg(Color::Blue)),
         )
         .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
         .highlight_symbol("\u{25ba} ");
 
         f.render_stateful_widget(table, chunks[1], &mut self.billing_blocks_table_state);
+        self.table_body_area = Some(Self::table_body(chunks[1], 2));
 
         // Scrollbar
         if report.blocks.len() > 10 {
@@ -284,4 +302,49 @@ impl TuiApp {
             );
         }
     }
+
+    /// Quota used by the in-progress block, with the burn rate and projected exhaustion
+    fn render_burn_down(f: &mut Frame, area: Rect, burn_down: &BurnDown) {
+        let pct = burn_down.block.quota_pct();
+        let color = if pct >= 90.0 {
+            Color::Red
+        } else if pct >= 70.0 {
+            Color::Yellow
+        } else {
+            Color::Green
+        };
+        let exhaustion = match burn_down.projected_exhaustion {
+            _ if burn_down.is_exhausted() => "quota used up".to_string(),
+            Some(time) => format!(
+                "runs out at {}",
+                time.with_timezone(&chrono::Local).format("%H:%M")
+            ),
+            None => "lasts until the reset".to_string(),
+        };
+        let gauge = Gauge::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        "\u{23f3} Current Block Burn-down ({} plan) \u{2014} resets in {}h {:02}m",
+                        burn_down.plan,
+                        burn_down.minutes_until_reset / 60,
+                        burn_down.minutes_until_reset % 60
+                    ))
+                    .border_style(Style::default().fg(Color::Blue)),
+            )
+            .gauge_style(Style::default().fg(color))
+            .ratio((pct / 100.0).clamp(0.0, 1.0))
+            .label(format!(
+                "{:.0}% of quota \u{00b7} {} / {} tokens \u{00b7} {} / {} msgs \u{00b7} {:.2}/h \u{00b7} {}",
+                pct,
+                Self::format_number(burn_down.block.tokens),
+                Self::format_number(burn_down.limits.tokens),
+                burn_down.block.messages,
+                burn_down.limits.messages,
+                Money(burn_down.cost_per_hour),
+                exhaustion
+            ));
+        f.render_widget(gauge, area);
+    }
 }
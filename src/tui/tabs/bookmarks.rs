@@ -0,0 +1,150 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, Wrap},
+};
+
+use crate::currency::Money;
+use crate::tui::TuiApp;
+
+impl TuiApp {
+    pub(crate) fn render_bookmarks(&mut self, f: &mut Frame, area: Rect) {
+        if self.bookmarks.bookmarks.is_empty() {
+            let empty_text = vec![
+                Line::from(""),
+                Line::from("No bookmarked sessions"),
+                Line::from(""),
+                Line::from("- Press 'b' in the Sessions tab to bookmark a session"),
+                Line::from("- Press 'n' in the Sessions tab to bookmark it with a note"),
+                Line::from("- Or run: claudelytics bookmarks add <session> --note \"...\""),
+            ];
+            let empty_paragraph = Paragraph::new(empty_text)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("\u{1f516} Bookmarks")
+                        .border_style(Style::default().fg(Color::DarkGray)),
+                )
+                .style(Style::default().fg(Color::Gray));
+            f.render_widget(empty_paragraph, area);
+            return;
+        }
+
+        if self.bookmarks_table_state.selected().is_none() {
+            self.bookmarks_table_state.select(Some(0));
+        }
+
+        let header_cells = ["Project", "Session", "Cost", "Note", "Tags", "Added"]
+            .iter()
+            .map(|h| {
+                Cell::from(*h).style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )
+            });
+        let header = Row::new(header_cells).height(1).bottom_margin(1);
+
+        let rows = self.bookmarks.bookmarks.iter().map(|bookmark| {
+            // Cost over the full loaded range, independent of the current filter
+            let cost = self
+                .original_session_report
+                .sessions
+                .iter()
+                .find(|s| {
+                    s.session_id == bookmark.session_id() && s.project_path == bookmark.project()
+                })
+                .map(|s| s.total_cost);
+            let session_short: String = bookmark.session_id().chars().take(8).collect();
+
+            Row::new(vec![
+                Cell::from(Self::truncate_text(
+                    &Self::extract_project_name(bookmark.project()),
+                    30,
+                )),
+                Cell::from(session_short).style(Style::default().fg(Color::DarkGray)),
+                match cost {
+                    Some(cost) => Cell::from(format!("{:.2}", Money(cost)))
+                        .style(Style::default().fg(Self::cost_color(cost))),
+                    None => Cell::from("-").style(Style::default().fg(Color::DarkGray)),
+                },
+                Cell::from(bookmark.note.clone().unwrap_or_default())
+                    .style(Style::default().fg(Color::White)),
+                Cell::from(bookmark.tags.join(", ")).style(Style::default().fg(Color::Magenta)),
+                Cell::from(
+                    bookmark
+                        .created_at
+                        .with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d")
+                        .to_string(),
+                )
+                .style(Style::default().fg(Color::Yellow)),
+            ])
+            .height(1)
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(22),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Percentage(40),
+                Constraint::Length(16),
+                Constraint::Length(12),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "\u{1f516} Bookmarks ({}) - n: note, c: conversation, d: remove",
+                    self.bookmarks.bookmarks.len()
+                ))
+                .border_style(Style::default().fg(Color::Blue)),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("\u{25ba} ");
+
+        f.render_stateful_widget(table, area, &mut self.bookmarks_table_state);
+        self.table_body_area = Some(Self::table_body(area, 2));
+    }
+
+    pub(crate) fn render_note_input(&mut self, f: &mut Frame) {
+        let Some(input) = &self.note_input else {
+            return;
+        };
+        let area = f.area();
+        let popup_area = Rect {
+            x: area.width / 6,
+            y: area.height / 3,
+            width: area.width * 2 / 3,
+            height: 7,
+        };
+        f.render_widget(Clear, popup_area);
+
+        let lines = vec![
+            Line::from(Span::styled(
+                input.session.clone(),
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(input.text.clone(), Style::default().fg(Color::White)),
+                Span::styled("\u{2588}", Style::default().fg(Color::Yellow)),
+            ]),
+        ];
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("\u{1f4dd} Bookmark note (Enter: save, Esc: cancel)")
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
+            .wrap(Wrap { trim: false });
+        f.render_widget(paragraph, popup_area);
+    }
+}
@@ -274,5 +274,6 @@ impl TuiApp {
         );
 
         f.render_stateful_widget(table, chunks[1], &mut self.cache_table_state);
+        self.table_body_area = Some(Self::table_body(chunks[1], 1));
     }
 }
@@ -6,9 +6,31 @@ use ratatui::{
     widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
 };
 
-use crate::tui::{SortMode, TuiApp};
+use chrono::{Datelike, Days, Local, NaiveDate};
+use std::collections::HashMap;
+
+use crate::currency::Money;
+use crate::tui::{AppMode, SortMode, TuiApp};
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
 
 impl TuiApp {
+    /// Show or hide the calendar, starting on the selected row's day
+    pub(crate) fn toggle_daily_calendar(&mut self) {
+        if self.daily_calendar.take().is_some() {
+            self.status_message = None;
+            return;
+        }
+        let selected = self
+            .daily_table_state
+            .selected()
+            .and_then(|i| self.daily_report.daily.get(i))
+            .and_then(|day| NaiveDate::parse_from_str(&day.date, "%Y-%m-%d").ok());
+        self.daily_calendar = Some(selected.unwrap_or_else(|| Local::now().date_naive()));
+        self.status_message =
+            Some("Calendar: arrows move, Enter shows the day's sessions, m closes".to_string());
+    }
+
     pub(crate) fn render_daily(&mut self, f: &mut Frame, area: Rect) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -45,7 +67,14 @@ impl TuiApp {
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" Refresh", Style::default().fg(Color::White)),
+            Span::styled(" Refresh | ", Style::default().fg(Color::White)),
+            Span::styled(
+                "m",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Calendar", Style::default().fg(Color::White)),
         ]);
 
         let sort_label = match self.sort_mode {
@@ -65,6 +94,11 @@ impl TuiApp {
             .wrap(Wrap { trim: true });
         f.render_widget(controls, chunks[0]);
 
+        if let Some(day) = self.daily_calendar {
+            self.render_daily_calendar(f, chunks[1], day);
+            return;
+        }
+
         // Enhanced table with color coding
         let header_cells = ["Date", "Cost", "Tokens", "Input", "Output", "Cache", "Hit%"]
             .iter()
@@ -95,10 +129,17 @@ impl TuiApp {
 
             let cost_color = Self::cost_color(day.total_cost);
             let hit_color = Self::cache_hit_color(cache_hit_pct);
+            let row_style = if self.current_mode == AppMode::Visual
+                && self.visual_mode_selections.contains(&i)
+            {
+                Style::default().bg(Color::Yellow)
+            } else {
+                Style::default()
+            };
 
             Row::new(vec![
                 Cell::from(day.date.clone()).style(style),
-                Cell::from(format!("${:.2}", day.total_cost))
+                Cell::from(format!("{:.2}", Money(day.total_cost)))
                     .style(Style::default().fg(cost_color)),
                 Cell::from(Self::format_number(day.total_tokens))
                     .style(Style::default().fg(Color::Magenta)),
@@ -112,6 +153,7 @@ impl TuiApp {
                 .style(Style::default().fg(Color::Yellow)),
                 Cell::from(format!("{:.1}%", cache_hit_pct)).style(Style::default().fg(hit_color)),
             ])
+            .style(row_style)
             .height(1)
         });
 
@@ -138,5 +180,151 @@ impl TuiApp {
         .highlight_symbol("\u{25ba} ");
 
         f.render_stateful_widget(table, chunks[1], &mut self.daily_table_state);
+        self.table_body_area = Some(Self::table_body(chunks[1], 2));
+    }
+
+    /// Month grid around `selected`, each day colored by its cost tier
+    fn render_daily_calendar(&self, f: &mut Frame, area: Rect, selected: NaiveDate) {
+        let costs: HashMap<&str, f64> = self
+            .original_daily_report
+            .daily
+            .iter()
+            .map(|day| (day.date.as_str(), day.total_cost))
+            .collect();
+        let weeks = month_weeks(selected);
+        let month_costs: Vec<f64> = weeks
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|date| cost_on(&costs, *date))
+            .collect();
+        let max_cost = month_costs.iter().copied().fold(0.0, f64::max);
+        let month_total: f64 = month_costs.iter().sum();
+
+        let header = Row::new(WEEKDAYS.iter().map(|name| {
+            Cell::from(*name).style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+        }))
+        .height(1)
+        .bottom_margin(1);
+
+        let rows = weeks.iter().map(|week| {
+            Row::new(week.iter().map(|date| {
+                let Some(date) = date else {
+                    return Cell::from("");
+                };
+                let cost = cost_on(&costs, *date);
+                let mut style = Style::default()
+                    .fg(Color::Black)
+                    .bg(tier_color(cost, max_cost));
+                if cost == 0.0 {
+                    style = Style::default().fg(Color::DarkGray);
+                }
+                if *date == selected {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+                }
+                let cost_text = if cost > 0.0 {
+                    format!("{:.2}", Money(cost))
+                } else {
+                    String::new()
+                };
+                Cell::from(vec![
+                    Line::from(format!(" {}", date.day())),
+                    Line::from(format!(" {}", cost_text)),
+                ])
+                .style(style)
+            }))
+            .height(2)
+            .bottom_margin(1)
+        });
+
+        let table = Table::new(rows, [Constraint::Ratio(1, 7); 7])
+            .header(header)
+            .column_spacing(1)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        "\u{1f4c6} {} \u{2014} ${:.2} \u{2014} {}: ${:.2}",
+                        selected.format("%B %Y"),
+                        month_total,
+                        selected.format("%a %b %-d"),
+                        cost_on(&costs, selected)
+                    ))
+                    .border_style(Style::default().fg(Color::Blue)),
+            );
+        f.render_widget(table, area);
+    }
+}
+
+fn cost_on(costs: &HashMap<&str, f64>, date: NaiveDate) -> f64 {
+    costs
+        .get(date.format("%Y-%m-%d").to_string().as_str())
+        .copied()
+        .unwrap_or(0.0)
+}
+
+/// Weeks (Monday first) of the month containing `date`, `None` outside the month
+fn month_weeks(date: NaiveDate) -> Vec<[Option<NaiveDate>; 7]> {
+    let first = date.with_day(1).unwrap_or(date);
+    let mut day = first - Days::new(first.weekday().num_days_from_monday() as u64);
+    let mut weeks = Vec::new();
+    while weeks.is_empty() || day.month() == first.month() {
+        let mut week = [None; 7];
+        for slot in &mut week {
+            *slot = (day.month() == first.month()).then_some(day);
+            day = day + Days::new(1);
+        }
+        weeks.push(week);
+    }
+    weeks
+}
+
+/// Background for a day's cost, in quarters of the month's most expensive day
+fn tier_color(cost: f64, max_cost: f64) -> Color {
+    let share = if max_cost > 0.0 { cost / max_cost } else { 0.0 };
+    if share > 0.75 {
+        Color::Red
+    } else if share > 0.5 {
+        Color::LightRed
+    } else if share > 0.25 {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_weeks() {
+        // September 2024 starts on a Sunday and ends on a Monday
+        let weeks = month_weeks(NaiveDate::from_ymd_opt(2024, 9, 15).unwrap());
+        assert_eq!(weeks.len(), 6);
+        assert_eq!(weeks[0][5], None);
+        assert_eq!(weeks[0][6], NaiveDate::from_ymd_opt(2024, 9, 1));
+        assert_eq!(weeks[5][0], NaiveDate::from_ymd_opt(2024, 9, 30));
+        assert_eq!(weeks[5][1], None);
+        assert_eq!(weeks.iter().flatten().flatten().count(), 30);
+
+        // February 2021 fills exactly four weeks
+        assert_eq!(
+            month_weeks(NaiveDate::from_ymd_opt(2021, 2, 1).unwrap()).len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_tier_color() {
+        assert_eq!(tier_color(10.0, 10.0), Color::Red);
+        assert_eq!(tier_color(6.0, 10.0), Color::LightRed);
+        assert_eq!(tier_color(3.0, 10.0), Color::Yellow);
+        assert_eq!(tier_color(1.0, 10.0), Color::Green);
+        assert_eq!(tier_color(0.0, 0.0), Color::Green);
     }
 }
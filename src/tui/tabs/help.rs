@@ -32,7 +32,7 @@ impl TuiApp {
                     .add_modifier(Modifier::BOLD),
             )]),
             Line::from(vec![
-                Span::styled("  1-6/h, Tab/Shift+Tab", Style::default().fg(Color::Green)),
+                Span::styled("  1-7/h, Tab/Shift+Tab", Style::default().fg(Color::Green)),
                 Span::styled("  Switch between tabs", Style::default().fg(Color::White)),
             ]),
             Line::from(vec![
@@ -157,7 +157,7 @@ impl TuiApp {
             Line::from(vec![
                 Span::styled("    e", Style::default().fg(Color::Blue)),
                 Span::styled(
-                    "               Export selected items",
+                    "               Export selected items to a file",
                     Style::default().fg(Color::Gray),
                 ),
             ]),
@@ -182,10 +182,45 @@ impl TuiApp {
                     Style::default().fg(Color::White),
                 ),
             ]),
+            Line::from(vec![
+                Span::styled("  n", Style::default().fg(Color::Green)),
+                Span::styled(
+                    "                 Bookmark session with a note (Sessions/Bookmarks)",
+                    Style::default().fg(Color::White),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("  x, C", Style::default().fg(Color::Green)),
+                Span::styled(
+                    "              Mark sessions to compare, view them side by side",
+                    Style::default().fg(Color::White),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("  M", Style::default().fg(Color::Green)),
+                Span::styled(
+                    "                 Cycle model filter: All, Opus, Sonnet, Haiku",
+                    Style::default().fg(Color::White),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("  Enter", Style::default().fg(Color::Green)),
+                Span::styled(
+                    "             Billing: sessions of the block (Enter/c opens one)",
+                    Style::default().fg(Color::White),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("  m", Style::default().fg(Color::Green)),
+                Span::styled(
+                    "                 Daily: calendar (arrows move, Enter filters Sessions)",
+                    Style::default().fg(Color::White),
+                ),
+            ]),
             Line::from(vec![
                 Span::styled("  c", Style::default().fg(Color::Green)),
                 Span::styled(
-                    "                 Clear status message",
+                    "                 Clear status message (Sessions: open conversation)",
                     Style::default().fg(Color::White),
                 ),
             ]),
@@ -1,4 +1,5 @@
 mod billing;
+mod bookmarks;
 mod cache;
 mod daily;
 mod help;
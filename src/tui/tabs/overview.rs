@@ -6,6 +6,7 @@ use ratatui::{
     widgets::{Block, Borders, Gauge, Paragraph, Wrap},
 };
 
+use crate::currency::Money;
 use crate::tui::TuiApp;
 use crate::tui_visuals::{ProgressColorScheme, SmoothProgressBar};
 
@@ -27,7 +28,7 @@ impl TuiApp {
             Line::from(vec![
                 Span::styled("\u{1f4b0} Total Cost: ", Style::default().fg(Color::White)),
                 Span::styled(
-                    format!("${:.2}", self.daily_report.totals.total_cost),
+                    format!("{:.2}", Money(self.daily_report.totals.total_cost)),
                     Style::default()
                         .fg(Color::Green)
                         .add_modifier(Modifier::BOLD),
@@ -155,13 +156,13 @@ impl TuiApp {
                 ),
                 Span::styled(
                     format!(
-                        "${:.2}",
-                        if self.daily_report.daily.is_empty() {
+                        "{:.2}",
+                        Money(if self.daily_report.daily.is_empty() {
                             0.0
                         } else {
                             self.daily_report.totals.total_cost
                                 / self.daily_report.daily.len() as f64
-                        }
+                        })
                     ),
                     Style::default().fg(Color::Green),
                 ),
@@ -254,8 +255,9 @@ impl TuiApp {
                 .gauge_style(Style::default().fg(Color::Green))
                 .ratio(cost_ratio)
                 .label(format!(
-                    "${:.2} / ${:.0}",
-                    self.daily_report.totals.total_cost, cost_ceiling
+                    "{:.2} / {:.0}",
+                    Money(self.daily_report.totals.total_cost),
+                    Money(cost_ceiling)
                 ));
             f.render_widget(gauge, chunks[3]);
         }
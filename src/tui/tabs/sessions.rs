@@ -6,6 +6,7 @@ use ratatui::{
     widgets::{Block, Borders, Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation, Table, Wrap},
 };
 
+use crate::currency::Money;
 use crate::tui::{AppMode, SortMode, TuiApp};
 
 impl TuiApp {
@@ -68,6 +69,13 @@ impl TuiApp {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(" Compare | ", Style::default().fg(Color::White)),
+            Span::styled(
+                "C",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" View comparison | ", Style::default().fg(Color::White)),
             Span::styled(
                 "b",
                 Style::default()
@@ -75,6 +83,13 @@ impl TuiApp {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(" Bookmark | ", Style::default().fg(Color::White)),
+            Span::styled(
+                "c",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Conversation | ", Style::default().fg(Color::White)),
             Span::styled(
                 "/",
                 Style::default()
@@ -135,9 +150,11 @@ impl TuiApp {
         let header_cells = [
             "Project",
             "Session",
+            "Summary",
             "Cost",
             "Tokens",
             "Cache Hit%",
+            "Score",
             "Last Activity",
         ]
         .iter()
@@ -183,7 +200,10 @@ impl TuiApp {
                     dir_parts.join("/")
                 };
 
-                let project_name = Self::extract_project_name(&dir_part);
+                let mut project_name = Self::extract_project_name(&dir_part);
+                if self.bookmarks.contains(&full_path) {
+                    project_name = format!("\u{2605} {}", project_name);
+                }
                 let session_short = if uuid_part.len() >= 8 {
                     uuid_part[..8].to_string()
                 } else {
@@ -216,12 +236,23 @@ impl TuiApp {
                 Row::new(vec![
                     Cell::from(Self::truncate_text(&project_name, 30)).style(style),
                     Cell::from(session_short).style(Style::default().fg(Color::DarkGray)),
-                    Cell::from(format!("${:.2}", session.total_cost))
+                    Cell::from(Self::truncate_text(
+                        session.summary.as_deref().unwrap_or(""),
+                        60,
+                    ))
+                    .style(style),
+                    Cell::from(format!("{:.2}", Money(session.total_cost)))
                         .style(Style::default().fg(cost_color)),
                     Cell::from(Self::format_number(session.total_tokens))
                         .style(Style::default().fg(Color::Magenta)),
                     Cell::from(format!("{:.1}%", cache_hit_pct))
                         .style(Style::default().fg(hit_color)),
+                    Cell::from(
+                        session
+                            .efficiency_score
+                            .map_or_else(|| "-".to_string(), |score| format!("{:.0}", score)),
+                    )
+                    .style(Style::default().fg(Color::Blue)),
                     Cell::from(session.last_activity.clone())
                         .style(Style::default().fg(Color::Yellow)),
                 ])
@@ -231,12 +262,14 @@ impl TuiApp {
         let table = Table::new(
             rows,
             [
-                Constraint::Percentage(30),
+                Constraint::Percentage(20),
                 Constraint::Length(10),
+                Constraint::Min(20),
                 Constraint::Length(10),
                 Constraint::Length(12),
                 Constraint::Length(10),
-                Constraint::Length(20),
+                Constraint::Length(6),
+                Constraint::Length(12),
             ],
         )
         .header(header)
@@ -252,11 +285,9 @@ impl TuiApp {
         .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
         .highlight_symbol("\u{25ba} ");
 
-        f.render_stateful_widget(
-            table,
-            chunks[1].inner(Margin::new(0, 1)),
-            &mut self.session_table_state,
-        );
+        let table_area = chunks[1].inner(Margin::new(0, 1));
+        f.render_stateful_widget(table, table_area, &mut self.session_table_state);
+        self.table_body_area = Some(Self::table_body(table_area, 2));
 
         // Enhanced scrollbar
         let scrollbar = Scrollbar::default()
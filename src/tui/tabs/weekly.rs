@@ -7,6 +7,7 @@ use ratatui::{
     widgets::{Block, Borders, Cell, Paragraph, Row, Table},
 };
 
+use crate::currency::Money;
 use crate::models::TokenUsage;
 use crate::reports::generate_weekly_report_sorted;
 use crate::tui::TuiApp;
@@ -27,6 +28,7 @@ impl TuiApp {
                     cache_read_tokens: day.cache_read_tokens,
                     total_cost: day.total_cost,
                     fast_mode_cost: 0.0,
+                    ..Default::default()
                 };
                 daily_map.insert(date, usage);
             }
@@ -109,9 +111,9 @@ impl TuiApp {
                         .style(Style::default().fg(Color::Cyan)),
                     Cell::from(Self::format_number(w.total_tokens))
                         .style(Style::default().fg(Color::Magenta)),
-                    Cell::from(format!("${:.2}", w.total_cost))
+                    Cell::from(format!("{:.2}", Money(w.total_cost)))
                         .style(Style::default().fg(cost_color)),
-                    Cell::from(format!("${:.2}", w.avg_daily_cost))
+                    Cell::from(format!("{:.2}", Money(w.avg_daily_cost)))
                         .style(Style::default().fg(Color::White)),
                 ])
             })
@@ -142,13 +144,14 @@ impl TuiApp {
         );
 
         f.render_stateful_widget(table, chunks[0], &mut self.weekly_table_state);
+        self.table_body_area = Some(Self::table_body(chunks[0], 1));
 
         // Totals bar
         let totals = &report.totals;
         let total_info = Paragraph::new(Line::from(vec![
             Span::styled("Totals: ", Style::default().fg(Color::Yellow)),
             Span::styled(
-                format!("${:.2}", totals.total_cost),
+                format!("{:.2}", Money(totals.total_cost)),
                 Style::default()
                     .fg(Color::Green)
                     .add_modifier(Modifier::BOLD),
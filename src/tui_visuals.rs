@@ -287,7 +287,6 @@ pub enum ToastType {
     Info,
     #[allow(dead_code)]
     Warning,
-    #[allow(dead_code)]
     Error,
 }
 
@@ -328,7 +327,6 @@ impl ToastNotification {
         Self::new_with_type(message, ToastType::Warning, 4)
     }
 
-    #[allow(dead_code)]
     pub fn error(message: String) -> Self {
         Self::new_with_type(message, ToastType::Error, 5)
     }
@@ -435,6 +433,12 @@ pub struct EnhancedStatusBar {
     pub key_hints: Vec<(String, String)>,
 }
 
+impl Default for EnhancedStatusBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EnhancedStatusBar {
     pub fn new() -> Self {
         Self {
@@ -540,6 +544,12 @@ pub struct VisualEffectsManager {
     pub status_bar: EnhancedStatusBar,
 }
 
+impl Default for VisualEffectsManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl VisualEffectsManager {
     pub fn new() -> Self {
         Self {
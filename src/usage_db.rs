@@ -0,0 +1,545 @@
+//! SQLite usage database (`sync-db`, `sql`, `--backend sqlite`)
+//!
+//! `sync-db` loads every usage record into `~/.local/share/claudelytics/usage.db`,
+//! one row per assistant message with indexes on date, project, and model,
+//! together with the session summaries. Each sync replaces the rows of the
+//! sessions it finds; sessions whose JSONL files were pruned keep their rows.
+//! Reports run with `--backend sqlite` read the database instead of re-parsing
+//! the JSONL files, and `claudelytics sql` runs read-only ad-hoc queries.
+//! Available when built with `--features sqlite`.
+
+use crate::billing_blocks::BillingBlockManager;
+use crate::burn_rate::UsageEvent;
+use crate::models::{DailyUsageMap, SessionUsageMap};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Result of `sync-db`
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncSummary {
+    pub db_path: PathBuf,
+    pub synced_at: DateTime<Utc>,
+    /// Records written for the sessions found in the JSONL files
+    pub records: usize,
+    pub sessions: usize,
+    /// Sessions only in the database (their JSONL files are gone)
+    pub retained_sessions: usize,
+    pub total_records: usize,
+}
+
+/// Usage read back from the database, shaped like `UsageParser::parse_all`
+pub struct StoredUsage {
+    pub daily_map: DailyUsageMap,
+    pub session_map: SessionUsageMap,
+    pub billing_manager: BillingBlockManager,
+    pub session_summaries: HashMap<String, String>,
+    /// Every stored record, like `UsageParser::with_all_usage_events`
+    pub events: Vec<UsageEvent>,
+}
+
+/// Rows returned by `claudelytics sql`
+#[derive(Debug, Clone, Default)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+pub use sqlite_store::{load, query, sync};
+
+#[cfg(feature = "sqlite")]
+mod sqlite_store {
+    use super::{QueryResult, StoredUsage, SyncSummary};
+    use crate::billing_blocks::{BillingBlockConfig, BillingBlockManager};
+    use crate::burn_rate::UsageEvent;
+    use crate::models::{DailyUsageMap, SessionUsageMap, TokenUsage};
+    use crate::models_registry::ModelsRegistry;
+    use crate::parser::UsageParser;
+    use crate::reports::parse_session_path;
+    use anyhow::{Context, Result};
+    use chrono::{DateTime, Local, NaiveDate, Utc};
+    use rusqlite::types::ValueRef;
+    use rusqlite::{Connection, OpenFlags, params};
+    use std::collections::{BTreeMap, HashMap};
+    use std::path::{Path, PathBuf};
+
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS usage (
+            timestamp TEXT NOT NULL,
+            date TEXT NOT NULL,
+            project TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            model TEXT NOT NULL,
+            input_tokens INTEGER NOT NULL,
+            output_tokens INTEGER NOT NULL,
+            cache_creation_tokens INTEGER NOT NULL,
+            cache_read_tokens INTEGER NOT NULL,
+            cost REAL NOT NULL,
+            fast_mode_cost REAL NOT NULL DEFAULT 0,
+            thinking_tokens INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_usage_date ON usage(date);
+        CREATE INDEX IF NOT EXISTS idx_usage_project ON usage(project, session_id);
+        CREATE INDEX IF NOT EXISTS idx_usage_model ON usage(model);
+        CREATE TABLE IF NOT EXISTS sessions (
+            project TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            summary TEXT,
+            last_activity TEXT NOT NULL,
+            PRIMARY KEY (project, session_id)
+        );
+        CREATE TABLE IF NOT EXISTS meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );";
+
+    /// Get the database file path
+    pub fn get_db_path() -> Result<PathBuf> {
+        let data_dir = dirs::data_dir()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("share")))
+            .ok_or_else(|| anyhow::anyhow!("Could not determine data directory"))?;
+
+        Ok(data_dir.join("claudelytics").join("usage.db"))
+    }
+
+    /// Parse all JSONL files with `parser` and replace the rows of every session found
+    pub fn sync(parser: UsageParser) -> Result<SyncSummary> {
+        sync_to(parser, &get_db_path()?)
+    }
+
+    fn sync_to(parser: UsageParser, path: &Path) -> Result<SyncSummary> {
+        let parser = parser.with_imports(false).with_all_usage_events();
+        parser.parse_all()?;
+        let summaries = parser.session_summaries();
+
+        let mut by_session: BTreeMap<String, Vec<UsageEvent>> = BTreeMap::new();
+        for event in parser.recent_usage() {
+            by_session
+                .entry(event.session.clone())
+                .or_default()
+                .push(event);
+        }
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut conn = Connection::open(path)
+            .with_context(|| format!("Failed to open database: {}", path.display()))?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create database schema")?;
+
+        let synced_at = Utc::now();
+        let tx = conn.transaction()?;
+        let mut records = 0;
+        {
+            let mut delete =
+                tx.prepare("DELETE FROM usage WHERE project = ?1 AND session_id = ?2")?;
+            let mut insert = tx.prepare(
+                "INSERT INTO usage (timestamp, date, project, session_id, model, input_tokens,
+                    output_tokens, cache_creation_tokens, cache_read_tokens, cost,
+                    fast_mode_cost, thinking_tokens)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            )?;
+            let mut upsert_session = tx.prepare(
+                "INSERT OR REPLACE INTO sessions (project, session_id, summary, last_activity)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+
+            for (session, events) in &by_session {
+                let (project, session_id) = parse_session_path(session);
+                delete.execute(params![project, session_id])?;
+                for event in events {
+                    let usage = &event.usage;
+                    insert.execute(params![
+                        event.timestamp.to_rfc3339(),
+                        event
+                            .timestamp
+                            .with_timezone(&Local)
+                            .date_naive()
+                            .to_string(),
+                        project,
+                        session_id,
+                        event.model,
+                        usage.input_tokens as i64,
+                        usage.output_tokens as i64,
+                        usage.cache_creation_tokens as i64,
+                        usage.cache_read_tokens as i64,
+                        usage.total_cost,
+                        usage.fast_mode_cost,
+                        usage.thinking_tokens as i64,
+                    ])?;
+                    records += 1;
+                }
+                let last_activity = events.iter().map(|e| e.timestamp).max();
+                upsert_session.execute(params![
+                    project,
+                    session_id,
+                    summaries.get(session),
+                    last_activity.map(|t| t.to_rfc3339()),
+                ])?;
+            }
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('last_sync', ?1)",
+            params![synced_at.to_rfc3339()],
+        )?;
+        tx.commit().context("Failed to write database")?;
+
+        let total_sessions: i64 =
+            conn.query_row("SELECT COUNT(*) FROM sessions", [], |row| row.get(0))?;
+        let total_records: i64 =
+            conn.query_row("SELECT COUNT(*) FROM usage", [], |row| row.get(0))?;
+
+        Ok(SyncSummary {
+            db_path: path.to_path_buf(),
+            synced_at,
+            records,
+            sessions: by_session.len(),
+            retained_sessions: (total_sessions as usize).saturating_sub(by_session.len()),
+            total_records: total_records as usize,
+        })
+    }
+
+    fn open_read_only(path: &Path) -> Result<Connection> {
+        if !path.exists() {
+            anyhow::bail!(
+                "No usage database at {}\nRun `claudelytics sync-db` first",
+                path.display()
+            );
+        }
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .with_context(|| format!("Failed to open database: {}", path.display()))
+    }
+
+    /// Usage in the date range (local dates, inclusive), built like a JSONL parse
+    pub fn load(
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+        model_filter: Option<&str>,
+        billing_config: BillingBlockConfig,
+    ) -> Result<StoredUsage> {
+        load_from(&get_db_path()?, since, until, model_filter, billing_config)
+    }
+
+    fn load_from(
+        path: &Path,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+        model_filter: Option<&str>,
+        billing_config: BillingBlockConfig,
+    ) -> Result<StoredUsage> {
+        let conn = open_read_only(path)?;
+        let registry = ModelsRegistry::new();
+        let mut model_matches: HashMap<String, bool> = HashMap::new();
+
+        let mut daily_map = DailyUsageMap::new();
+        let mut session_map = SessionUsageMap::new();
+        let mut billing_manager = BillingBlockManager::with_config(billing_config);
+        let mut events = Vec::new();
+
+        let mut statement = conn.prepare(
+            "SELECT timestamp, date, project, session_id, model, input_tokens, output_tokens,
+                cache_creation_tokens, cache_read_tokens, cost, fast_mode_cost, thinking_tokens
+             FROM usage
+             WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2)
+             ORDER BY timestamp",
+        )?;
+        let mut rows = statement.query(params![
+            since.map(|d| d.to_string()),
+            until.map(|d| d.to_string())
+        ])?;
+        while let Some(row) = rows.next()? {
+            let model: String = row.get(4)?;
+            if let Some(filter) = model_filter
+                && !*model_matches
+                    .entry(model.clone())
+                    .or_insert_with(|| registry.matches_filter(&model, filter))
+            {
+                continue;
+            }
+
+            let timestamp: String = row.get(0)?;
+            let timestamp = DateTime::parse_from_rfc3339(&timestamp)
+                .context("Invalid timestamp in database")?
+                .with_timezone(&Utc);
+            let date: String = row.get(1)?;
+            let date = date
+                .parse::<NaiveDate>()
+                .context("Invalid date in database")?;
+            let project: String = row.get(2)?;
+            let session_id: String = row.get(3)?;
+            let session = session_key(&project, &session_id);
+
+            let usage = TokenUsage {
+                input_tokens: row.get::<_, i64>(5)? as u64,
+                output_tokens: row.get::<_, i64>(6)? as u64,
+                cache_creation_tokens: row.get::<_, i64>(7)? as u64,
+                cache_read_tokens: row.get::<_, i64>(8)? as u64,
+                total_cost: row.get(9)?,
+                fast_mode_cost: row.get(10)?,
+                thinking_tokens: row.get::<_, i64>(11)? as u64,
+                message_count: 1,
+                ..Default::default()
+            };
+            events.push(UsageEvent {
+                timestamp,
+                session: session.clone(),
+                model: model.clone(),
+                usage: usage.clone(),
+            });
+            let usage = usage.tagged(&model, &session);
+
+            daily_map.entry(date).or_default().add(&usage);
+            let entry = session_map
+                .entry(session.clone())
+                .or_insert((TokenUsage::default(), timestamp));
+            entry.0.add(&usage);
+            if timestamp > entry.1 {
+                entry.1 = timestamp;
+            }
+            billing_manager.add_usage(timestamp, &usage, Some(&session));
+        }
+        billing_manager.finalize();
+
+        let mut statement = conn.prepare(
+            "SELECT project, session_id, summary FROM sessions WHERE summary IS NOT NULL",
+        )?;
+        let session_summaries = statement
+            .query_map([], |row| {
+                let project: String = row.get(0)?;
+                let session_id: String = row.get(1)?;
+                Ok((session_key(&project, &session_id), row.get(2)?))
+            })?
+            .collect::<rusqlite::Result<HashMap<String, String>>>()?;
+
+        Ok(StoredUsage {
+            daily_map,
+            session_map,
+            billing_manager,
+            session_summaries,
+            events,
+        })
+    }
+
+    fn session_key(project: &str, session_id: &str) -> String {
+        if project.is_empty() {
+            session_id.to_string()
+        } else {
+            format!("{}/{}", project, session_id)
+        }
+    }
+
+    /// Run a read-only query against the database
+    pub fn query(sql: &str) -> Result<QueryResult> {
+        query_in(&get_db_path()?, sql)
+    }
+
+    fn query_in(path: &Path, sql: &str) -> Result<QueryResult> {
+        let conn = open_read_only(path)?;
+        let mut statement = conn.prepare(sql).context("Invalid SQL")?;
+        let columns: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        let mut result = QueryResult {
+            columns,
+            rows: Vec::new(),
+        };
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let values = (0..result.columns.len())
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        ValueRef::Null => serde_json::Value::Null,
+                        ValueRef::Integer(n) => n.into(),
+                        ValueRef::Real(x) => x.into(),
+                        ValueRef::Text(text) => String::from_utf8_lossy(text).into(),
+                        ValueRef::Blob(blob) => format!("<{} bytes>", blob.len()).into(),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            result.rows.push(values);
+        }
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_sync_load_and_query() {
+            let dir = tempfile::TempDir::new().unwrap();
+            let project = dir.path().join("projects").join("-home-me-app");
+            std::fs::create_dir_all(&project).unwrap();
+            std::fs::write(
+                project.join("s1.jsonl"),
+                r#"{"timestamp":"2024-01-15T12:00:00Z","costUSD":0.5,"message":{"model":"claude-3-opus-20240229","usage":{"input_tokens":100,"output_tokens":200}}}
+{"timestamp":"2024-01-16T12:00:00Z","costUSD":1.5,"message":{"model":"claude-3-5-sonnet-20241022","usage":{"input_tokens":300,"output_tokens":400}}}
+"#,
+            )
+            .unwrap();
+            let parser = || UsageParser::new(dir.path().to_path_buf(), None, None, None).unwrap();
+            let (live_daily, live_sessions, live_blocks) = parser().parse_all().unwrap();
+
+            let db = dir.path().join("usage.db");
+            let summary = sync_to(parser(), &db).unwrap();
+            assert_eq!((summary.records, summary.sessions), (2, 1));
+            // Re-syncing replaces the session's rows instead of duplicating them
+            let summary = sync_to(parser(), &db).unwrap();
+            assert_eq!(summary.total_records, 2);
+
+            let stored = load_from(&db, None, None, None, BillingBlockConfig::default()).unwrap();
+            assert_eq!(stored.daily_map.len(), live_daily.len());
+            for (date, usage) in &live_daily {
+                let loaded = &stored.daily_map[date];
+                assert_eq!(loaded.total_tokens(), usage.total_tokens());
+                assert!((loaded.total_cost - usage.total_cost).abs() < 1e-9);
+                assert_eq!(
+                    loaded.by_model.keys().collect::<Vec<_>>(),
+                    usage.by_model.keys().collect::<Vec<_>>()
+                );
+            }
+            assert_eq!(stored.session_map.len(), live_sessions.len());
+            assert_eq!(
+                stored.billing_manager.get_blocks_with_usage().len(),
+                live_blocks.get_blocks_with_usage().len()
+            );
+
+            let opus =
+                load_from(&db, None, None, Some("opus"), BillingBlockConfig::default()).unwrap();
+            assert_eq!(opus.daily_map.len(), 1);
+
+            let result = query_in(
+                &db,
+                "SELECT model, SUM(output_tokens) AS output FROM usage GROUP BY model ORDER BY model",
+            )
+            .unwrap();
+            assert_eq!(result.columns, vec!["model", "output"]);
+            assert_eq!(
+                result.rows[1],
+                vec![
+                    serde_json::json!("claude-3-opus-20240229"),
+                    serde_json::json!(200)
+                ]
+            );
+            // The database is opened read-only for ad-hoc queries
+            assert!(query_in(&db, "DELETE FROM usage").is_err());
+        }
+    }
+}
+
+/// Fallback used when the binary was built without SQLite support
+#[cfg(not(feature = "sqlite"))]
+mod sqlite_store {
+    use super::{QueryResult, StoredUsage, SyncSummary};
+    use crate::billing_blocks::BillingBlockConfig;
+    use crate::parser::UsageParser;
+    use anyhow::Result;
+    use chrono::NaiveDate;
+
+    fn unsupported<T>() -> Result<T> {
+        anyhow::bail!(
+            "The SQLite backend is not available in this build. Rebuild with `cargo install claudelytics --features sqlite`"
+        )
+    }
+
+    pub fn sync(_parser: UsageParser) -> Result<SyncSummary> {
+        unsupported()
+    }
+
+    pub fn load(
+        _since: Option<NaiveDate>,
+        _until: Option<NaiveDate>,
+        _model_filter: Option<&str>,
+        _billing_config: BillingBlockConfig,
+    ) -> Result<StoredUsage> {
+        unsupported()
+    }
+
+    pub fn query(_sql: &str) -> Result<QueryResult> {
+        unsupported()
+    }
+}
+
+pub fn display_sync_summary(summary: &SyncSummary, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(summary) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    println!(
+        "🗄️  Synced {} records from {} sessions",
+        summary.records.to_string().green(),
+        summary.sessions
+    );
+    if summary.retained_sessions > 0 {
+        println!(
+            "   Kept {} sessions whose JSONL files are gone",
+            summary.retained_sessions
+        );
+    }
+    println!(
+        "   Database: {} ({} records)",
+        summary.db_path.display(),
+        summary.total_records
+    );
+}
+
+pub fn display_query_result(result: &QueryResult, json: bool) {
+    if json {
+        let rows: Vec<serde_json::Map<String, serde_json::Value>> = result
+            .rows
+            .iter()
+            .map(|row| {
+                result
+                    .columns
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().cloned())
+                    .collect()
+            })
+            .collect();
+        match serde_json::to_string_pretty(&rows) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("Failed to serialize: {}", e),
+        }
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(
+            result
+                .columns
+                .iter()
+                .map(|column| Cell::new(column).fg(Color::Cyan)),
+        );
+    for row in &result.rows {
+        table.add_row(row.iter().map(|value| match value {
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Null => "NULL".to_string(),
+            other => other.to_string(),
+        }));
+    }
+    println!("{table}");
+    println!(
+        "{}",
+        format!(
+            "{} row{}",
+            result.rows.len(),
+            if result.rows.len() == 1 { "" } else { "s" }
+        )
+        .dimmed()
+    );
+}
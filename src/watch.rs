@@ -0,0 +1,362 @@
+//! Watch mode: poll the Claude directories and report new usage as it arrives
+//!
+//! Each poll re-parses the data (the parse cache keeps this cheap) and compares
+//! the per-session, per-model totals with the previous poll. Any growth is a
+//! usage delta, printed either as a refreshed daily table or as one JSON
+//! object per line for piping into other tools. With `--notify`, desktop
+//! notifications are shown for the rules in `notifications`.
+
+use crate::currency::{Currency, Money};
+use crate::display::display_daily_report_table;
+use crate::display::print_warning;
+use crate::models::{DailyUsageMap, SessionUsageMap, TokenUsage};
+use crate::notifications::{self, Notifier, NotifyRules};
+use crate::parser::UsageParser;
+use crate::reports::generate_daily_report_sorted;
+use crate::tags::project_dir_pattern;
+use crate::terminal::Terminal;
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use colored::Colorize;
+use crossterm::{
+    cursor::MoveTo,
+    execute,
+    terminal::{Clear, ClearType},
+};
+use serde::Serialize;
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+/// Deltas listed under the table in table mode
+const RECENT_DELTAS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchFormat {
+    /// Redraw today's daily table whenever usage changes
+    Table,
+    /// One JSON object per usage delta on stdout
+    JsonLines,
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Seconds between polls
+    pub interval: u64,
+    pub format: WatchFormat,
+    /// Only report sessions whose project directory contains this (a path or part of one)
+    pub project: Option<String>,
+    /// Desktop notification rules; `None` without `--notify`
+    pub notify: Option<NotifyRules>,
+    /// Currency the baseline's costs are in; each poll is converted to it
+    pub currency: Currency,
+}
+
+/// New usage in one session and model since the previous poll
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageDelta {
+    /// When the delta was detected
+    pub detected_at: DateTime<Utc>,
+    /// Latest activity in the session
+    pub last_activity: DateTime<Utc>,
+    pub project: String,
+    pub session_id: String,
+    pub model: String,
+    pub messages: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost: f64,
+}
+
+/// Matches `project-dir/session-id` keys against a `--project` filter
+struct ProjectFilter(Option<String>);
+
+impl ProjectFilter {
+    fn new(project: Option<&str>) -> Self {
+        // Accept real paths (`~/work/api`) as well as Claude's directory form
+        Self(project.map(project_dir_pattern))
+    }
+
+    fn matches(&self, session: &str) -> bool {
+        let Some(filter) = &self.0 else {
+            return true;
+        };
+        let project = session
+            .rsplit_once('/')
+            .map_or(session, |(project, _)| project);
+        project.contains(filter.as_str())
+    }
+}
+
+/// Usage added between two snapshots of the session map, one entry per
+/// session and model, sorted by last activity
+pub fn usage_deltas(
+    previous: &SessionUsageMap,
+    current: &SessionUsageMap,
+    project: Option<&str>,
+    detected_at: DateTime<Utc>,
+) -> Vec<UsageDelta> {
+    let filter = ProjectFilter::new(project);
+    let empty = TokenUsage::default();
+
+    let mut deltas = Vec::new();
+    for (session, (usage, last_activity)) in current {
+        if !filter.matches(session) {
+            continue;
+        }
+        let before = previous.get(session).map(|(usage, _)| usage);
+        let (project, session_id) = session.rsplit_once('/').unwrap_or(("", session));
+
+        for (model, model_usage) in &usage.by_model {
+            let old = before
+                .and_then(|before| before.by_model.get(model))
+                .unwrap_or(&empty);
+            if model_usage.message_count <= old.message_count
+                && model_usage.total_tokens() <= old.total_tokens()
+            {
+                continue;
+            }
+            let input_tokens = model_usage.input_tokens.saturating_sub(old.input_tokens);
+            let output_tokens = model_usage.output_tokens.saturating_sub(old.output_tokens);
+            let cache_creation_tokens = model_usage
+                .cache_creation_tokens
+                .saturating_sub(old.cache_creation_tokens);
+            let cache_read_tokens = model_usage
+                .cache_read_tokens
+                .saturating_sub(old.cache_read_tokens);
+            deltas.push(UsageDelta {
+                detected_at,
+                last_activity: *last_activity,
+                project: project.to_string(),
+                session_id: session_id.to_string(),
+                model: model.clone(),
+                messages: model_usage.message_count.saturating_sub(old.message_count),
+                input_tokens,
+                output_tokens,
+                cache_creation_tokens,
+                cache_read_tokens,
+                total_tokens: input_tokens
+                    + output_tokens
+                    + cache_creation_tokens
+                    + cache_read_tokens,
+                cost: (model_usage.total_cost - old.total_cost).max(0.0),
+            });
+        }
+    }
+    deltas.sort_by(|a, b| {
+        a.last_activity
+            .cmp(&b.last_activity)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+            .then_with(|| a.model.cmp(&b.model))
+    });
+    deltas
+}
+
+/// Daily usage restricted to sessions matching the project filter
+fn filter_daily_by_project(daily_map: &DailyUsageMap, project: Option<&str>) -> DailyUsageMap {
+    if project.is_none() {
+        return daily_map.clone();
+    }
+    let filter = ProjectFilter::new(project);
+    daily_map
+        .iter()
+        .filter_map(|(date, usage)| {
+            let mut filtered = TokenUsage::default();
+            for session_usage in usage
+                .by_session
+                .iter()
+                .filter(|(session, _)| filter.matches(session))
+                .map(|(_, usage)| usage)
+            {
+                filtered.add(session_usage);
+            }
+            (filtered.message_count > 0).then_some((*date, filtered))
+        })
+        .collect()
+}
+
+/// Poll until interrupted, starting from the already parsed `baseline`
+pub fn run_watch(
+    parser: &UsageParser,
+    baseline: (DailyUsageMap, SessionUsageMap),
+    options: &WatchOptions,
+) -> Result<()> {
+    let project = options.project.as_deref();
+    let (mut daily_map, mut session_map) = baseline;
+    let mut recent: Vec<UsageDelta> = Vec::new();
+    let mut notifier = options
+        .notify
+        .clone()
+        .map(|rules| Notifier::new(rules, &filter_daily_by_project(&daily_map, project)));
+
+    if options.format == WatchFormat::Table {
+        render_table(&daily_map, &recent, options)?;
+    }
+
+    loop {
+        thread::sleep(Duration::from_secs(options.interval.max(1)));
+
+        let (mut new_daily, mut new_sessions, mut billing_manager) = parser.parse_all()?;
+        options.currency.convert(
+            &mut new_daily,
+            &mut new_sessions,
+            &mut billing_manager,
+            &mut [],
+        );
+        let deltas = usage_deltas(&session_map, &new_sessions, project, Utc::now());
+        daily_map = new_daily;
+        session_map = new_sessions;
+
+        if let Some(active) = &mut notifier {
+            let changed: Vec<String> = deltas
+                .iter()
+                .map(|delta| format!("{}/{}", delta.project, delta.session_id))
+                .collect();
+            let due = active.check(
+                &filter_daily_by_project(&daily_map, project),
+                &session_map,
+                &changed,
+                &billing_manager,
+                Utc::now(),
+            );
+            for notification in &due {
+                if let Err(e) = notifications::send(notification) {
+                    print_warning(&format!("Desktop notifications disabled: {:#}", e));
+                    notifier = None;
+                    break;
+                }
+            }
+        }
+
+        if deltas.is_empty() {
+            continue;
+        }
+
+        match options.format {
+            WatchFormat::JsonLines => {
+                for delta in &deltas {
+                    println!("{}", serde_json::to_string(delta)?);
+                }
+            }
+            WatchFormat::Table => {
+                recent.extend(deltas);
+                let overflow = recent.len().saturating_sub(RECENT_DELTAS);
+                recent.drain(..overflow);
+                render_table(&daily_map, &recent, options)?;
+            }
+        }
+    }
+}
+
+fn render_table(
+    daily_map: &DailyUsageMap,
+    recent: &[UsageDelta],
+    options: &WatchOptions,
+) -> Result<()> {
+    execute!(io::stdout(), Clear(ClearType::All), MoveTo(0, 0))?;
+
+    println!("{}", "🔍 Watching for new usage...".bright_blue().bold());
+    if let Some(project) = &options.project {
+        println!("📁 Project: {}", project.bright_yellow());
+    }
+    println!(
+        "🕐 Last updated: {}  (every {}s, Ctrl+C to stop)",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        options.interval
+    );
+    println!("{}", Terminal::separator('─').bright_black());
+
+    let today = Local::now().date_naive();
+    let today_map: DailyUsageMap = filter_daily_by_project(daily_map, options.project.as_deref())
+        .into_iter()
+        .filter(|(date, _)| *date == today)
+        .collect();
+    if today_map.is_empty() {
+        println!("{}", "No usage today yet.".dimmed());
+    } else {
+        display_daily_report_table(&generate_daily_report_sorted(today_map, None, None));
+    }
+
+    if !recent.is_empty() {
+        println!("\n{}", "Recent activity".bold());
+        for delta in recent.iter().rev() {
+            println!(
+                "{}  {}/{}  {}  +{} msgs  +{} tokens  {}",
+                delta
+                    .last_activity
+                    .with_timezone(&Local)
+                    .format("%H:%M:%S")
+                    .to_string()
+                    .bright_black(),
+                delta.project.bright_cyan(),
+                delta.session_id,
+                delta.model.magenta(),
+                delta.messages,
+                delta.total_tokens,
+                format!("+{:.4}", Money(delta.cost)).bright_red()
+            );
+        }
+    }
+    println!("{}", Terminal::separator('─').bright_black());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn usage(model: &str, messages: u64, input: u64, cost: f64) -> TokenUsage {
+        let mut usage = TokenUsage {
+            input_tokens: input,
+            total_cost: cost,
+            message_count: messages,
+            ..Default::default()
+        };
+        usage.by_model.insert(model.to_string(), usage.clone());
+        usage
+    }
+
+    #[test]
+    fn test_usage_deltas() {
+        let now = Utc::now();
+        let earlier = now - ChronoDuration::minutes(5);
+        let mut previous = SessionUsageMap::new();
+        previous.insert(
+            "-home-me-api/s1".to_string(),
+            (usage("claude-sonnet-4", 2, 100, 0.5), earlier),
+        );
+        previous.insert(
+            "-home-me-site/s2".to_string(),
+            (usage("claude-opus-4", 1, 50, 1.0), earlier),
+        );
+
+        let mut current = previous.clone();
+        current.insert(
+            "-home-me-api/s1".to_string(),
+            (usage("claude-sonnet-4", 3, 160, 0.75), now),
+        );
+        current.insert(
+            "-home-me-api/s3".to_string(),
+            (usage("claude-opus-4", 1, 40, 2.0), now),
+        );
+
+        let deltas = usage_deltas(&previous, &current, None, now);
+        assert_eq!(deltas.len(), 2);
+        let s1 = deltas.iter().find(|d| d.session_id == "s1").unwrap();
+        assert_eq!(s1.project, "-home-me-api");
+        assert_eq!(s1.model, "claude-sonnet-4");
+        assert_eq!((s1.messages, s1.input_tokens), (1, 60));
+        assert!((s1.cost - 0.25).abs() < 1e-9);
+
+        assert!(usage_deltas(&previous, &current, Some("site"), now).is_empty());
+        assert_eq!(
+            usage_deltas(&previous, &current, Some("/home/me/api"), now).len(),
+            2
+        );
+        assert!(usage_deltas(&current, &current, None, now).is_empty());
+    }
+}